@@ -0,0 +1,297 @@
+//! Inspect and convert CBOR on the command line.
+//!
+//! Input is read from a file or, if none is given, from stdin. Commands
+//! which read CBOR accept CBOR sequences, i.e. zero or more items.
+
+use minicbor::decode::{self, Decoder};
+use minicbor::value::Value;
+use std::fmt::Write as _;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: minicbor-cli <command> [options] [file]
+
+Commands:
+    diag        Show CBOR in diagnostic notation.
+    convert     Convert between formats.
+    validate    Check that CBOR is well-formed.
+    split       Print every item of a CBOR sequence in hex, one per line.
+    help        Show this message.
+
+Options:
+    --hex                 Read CBOR input in hex (diag, validate, split).
+    --pretty              Indent arrays and maps (diag).
+    --from <format>       The input format (convert, default: cbor).
+    --to <format>         The output format (convert, default: edn).
+    --canonical <profile> Also check for the canonical form of a profile,
+                          either `ctap2` or `dag-cbor` (validate).
+
+Formats:
+    cbor    Binary CBOR.
+    hex     Hex-encoded CBOR, whitespace is ignored.
+    json    JSON, one value per line for CBOR sequences.
+    edn     Diagnostic notation.
+";
+
+type Error = Box<dyn std::error::Error>;
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match run(&args) {
+        Ok(())  => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("minicbor-cli: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The data formats of the `convert` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Cbor,
+    Hex,
+    Json,
+    Edn
+}
+
+impl std::str::FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cbor" => Ok(Format::Cbor),
+            "hex"  => Ok(Format::Hex),
+            "json" => Ok(Format::Json),
+            "edn"  => Ok(Format::Edn),
+            _      => Err(format!("unknown format: {}", s).into())
+        }
+    }
+}
+
+/// Command line options.
+#[derive(Debug)]
+struct Options {
+    pretty: bool,
+    from: Format,
+    to: Format,
+    canonical: Option<String>,
+    file: Option<String>
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, Error> {
+        let mut opts = Options {
+            pretty: false,
+            from: Format::Cbor,
+            to: Format::Edn,
+            canonical: None,
+            file: None
+        };
+        let mut args = args.iter();
+        while let Some(a) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("missing value of {}", a));
+            match a.as_str() {
+                "--hex"       => opts.from = Format::Hex,
+                "--pretty"    => opts.pretty = true,
+                "--from"      => opts.from = value()?.parse()?,
+                "--to"        => opts.to = value()?.parse()?,
+                "--canonical" => opts.canonical = Some(value()?.clone()),
+                _ if a.starts_with("--") => return Err(format!("unknown option: {}", a).into()),
+                _ if opts.file.is_none() => opts.file = Some(a.clone()),
+                _ => return Err(format!("unexpected argument: {}", a).into())
+            }
+        }
+        Ok(opts)
+    }
+
+    /// Read all input bytes.
+    fn input(&self) -> Result<Vec<u8>, Error> {
+        match &self.file {
+            Some(f) => Ok(std::fs::read(f).map_err(|e| format!("{}: {}", f, e))?),
+            None    => {
+                let mut v = Vec::new();
+                io::stdin().read_to_end(&mut v)?;
+                Ok(v)
+            }
+        }
+    }
+
+    /// Read the input as CBOR, either binary or hex-encoded.
+    fn cbor(&self) -> Result<Vec<u8>, Error> {
+        let b = self.input()?;
+        match self.from {
+            Format::Hex => from_hex(&b),
+            _           => Ok(b)
+        }
+    }
+
+    /// Read the input as text.
+    fn text(&self) -> Result<String, Error> {
+        Ok(String::from_utf8(self.input()?).map_err(|_| "input is not valid utf-8")?)
+    }
+}
+
+fn run(args: &[String]) -> Result<(), Error> {
+    let (cmd, args) = match args.split_first() {
+        Some(x) => x,
+        None    => return Err(format!("missing command\n\n{}", USAGE).into())
+    };
+    let opts = Options::parse(args)?;
+    let mut out = io::stdout();
+    match cmd.as_str() {
+        "diag" => {
+            let cbor = opts.cbor()?;
+            let diag = minicbor::display(&cbor).to_string();
+            if opts.pretty {
+                writeln!(out, "{}", pretty(&diag))?
+            } else {
+                writeln!(out, "{}", diag)?
+            }
+        }
+        "convert" => {
+            let cbor = match opts.from {
+                Format::Cbor => opts.input()?,
+                Format::Hex  => from_hex(&opts.input()?)?,
+                Format::Json => minicbor::json::from_json(opts.text()?.trim())?,
+                Format::Edn  => minicbor::edn::from_edn(&opts.text()?)?
+            };
+            match opts.to {
+                Format::Cbor => out.write_all(&cbor)?,
+                Format::Hex  => writeln!(out, "{}", to_hex(&cbor))?,
+                Format::Edn  => writeln!(out, "{}", minicbor::display(&cbor))?,
+                Format::Json => for item in items(&cbor)? {
+                    writeln!(out, "{}", minicbor::json::to_json(item)?)?
+                }
+            }
+        }
+        "validate" => {
+            let cbor = opts.cbor()?;
+            let mut d = Decoder::new(&cbor);
+            let mut n = 0;
+            while d.position() < cbor.len() {
+                let p = d.position();
+                d.decode::<Value>().map_err(|e| format!("item {} at offset {}: {}", n, p, e))?;
+                if let Some(profile) = &opts.canonical {
+                    let item = &cbor[p .. d.position()];
+                    match profile.as_str() {
+                        "ctap2"    => minicbor::ctap2::validate(item),
+                        "dag-cbor" => minicbor::dag_cbor::validate(item),
+                        _          => return Err(format!("unknown profile: {}", profile).into())
+                    }
+                    .map_err(|e| format!("item {} at offset {} is not canonical: {}", n, p, e))?
+                }
+                n += 1
+            }
+            writeln!(out, "ok: {} item(s)", n)?
+        }
+        "split" => {
+            for item in items(&opts.cbor()?)? {
+                writeln!(out, "{}", to_hex(item))?
+            }
+        }
+        "help" | "--help" | "-h" => write!(out, "{}", USAGE)?,
+        _ => return Err(format!("unknown command: {}\n\n{}", cmd, USAGE).into())
+    }
+    Ok(())
+}
+
+/// Get the items of a CBOR sequence.
+fn items(cbor: &[u8]) -> Result<Vec<&[u8]>, decode::Error> {
+    let mut d = Decoder::new(cbor);
+    let mut v = Vec::new();
+    while d.position() < cbor.len() {
+        let p = d.position();
+        d.skip()?;
+        v.push(&cbor[p .. d.position()])
+    }
+    Ok(v)
+}
+
+fn to_hex(b: &[u8]) -> String {
+    let mut s = String::with_capacity(2 * b.len());
+    for x in b {
+        let _ = write!(s, "{:02x}", x);
+    }
+    s
+}
+
+fn from_hex(b: &[u8]) -> Result<Vec<u8>, Error> {
+    let digits = b.iter()
+        .filter(|c| !c.is_ascii_whitespace())
+        .map(|c| char::from(*c).to_digit(16).map(|d| d as u8).ok_or("invalid hex digit"))
+        .collect::<Result<Vec<u8>, _>>()?;
+    if digits.len() % 2 != 0 {
+        return Err("odd number of hex digits".into())
+    }
+    Ok(digits.chunks(2).map(|d| d[0] << 4 | d[1]).collect())
+}
+
+/// Indent the arrays and maps of diagnostic notation.
+fn pretty(diag: &str) -> String {
+    let mut s = String::with_capacity(diag.len());
+    let mut open = Vec::new();
+    let mut chars = diag.chars().peekable();
+    let newline = |s: &mut String, open: &[char]| {
+        s.push('\n');
+        for _ in open.iter().filter(|c| **c != '(') {
+            s.push_str("  ")
+        }
+    };
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                s.push(c);
+                while let Some(x) = chars.next() {
+                    s.push(x);
+                    if x == '\\' {
+                        s.extend(chars.next())
+                    } else if x == c {
+                        break
+                    }
+                }
+            }
+            '[' | '{' => {
+                s.push(c);
+                if chars.peek() == Some(&'_') {
+                    s.push('_');
+                    chars.next();
+                    if chars.peek() == Some(&' ') {
+                        s.push(' ');
+                        chars.next();
+                    }
+                }
+                if let Some(']' | '}') = chars.peek() {
+                    s.extend(chars.next());
+                    continue
+                }
+                s.truncate(s.trim_end().len());
+                open.push(c);
+                newline(&mut s, &open)
+            }
+            ']' | '}' => {
+                open.pop();
+                newline(&mut s, &open);
+                s.push(c)
+            }
+            '(' => {
+                open.push(c);
+                s.push(c)
+            }
+            ')' => {
+                open.pop();
+                s.push(c)
+            }
+            ',' if matches!(open.last(), None | Some('[' | '{')) => {
+                s.push(',');
+                if chars.peek() == Some(&' ') {
+                    chars.next();
+                }
+                newline(&mut s, &open)
+            }
+            _ => s.push(c)
+        }
+    }
+    s
+}
@@ -0,0 +1,89 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Run the CLI with the given arguments and input.
+///
+/// Returns stdout on success and stderr otherwise.
+fn run(args: &[&str], input: &[u8]) -> Result<Vec<u8>, String> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_minicbor-cli"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    let out = child.wait_with_output().unwrap();
+    if out.status.success() {
+        Ok(out.stdout)
+    } else {
+        Err(String::from_utf8(out.stderr).unwrap())
+    }
+}
+
+fn run_str(args: &[&str], input: &str) -> Result<String, String> {
+    run(args, input.as_bytes()).map(|b| String::from_utf8(b).unwrap())
+}
+
+#[test]
+fn diag() {
+    let cbor = b"\x82\x01\xa1\x61a\x41\xff\x02";
+    assert_eq!("[1, {\"a\": h'ff'}], 2\n", String::from_utf8(run(&["diag"], cbor).unwrap()).unwrap());
+    assert_eq!("[1, {\"a\": h'ff'}], 2\n", run_str(&["diag", "--hex"], "8201a1 6161 41ff\n02").unwrap());
+
+    let expected = "\
+[
+  1,
+  {_
+    \"a\": [_ ],
+    \"b\": (_ \"x,\", \"]\")
+  },
+  1([])
+],
+2
+";
+    let hex = "8301bf61619fff61627f6278 2c615dffffc18002";
+    assert_eq!(expected, run_str(&["diag", "--hex", "--pretty"], hex).unwrap())
+}
+
+#[test]
+fn convert() {
+    let hex = |args: &[&str], input: &str| run_str(args, input).unwrap().trim_end().to_string();
+    assert_eq!("a161788201fb4004000000000000", hex(&["convert", "--from", "json", "--to", "hex"], r#"{"x": [1, 2.5]}"#));
+    assert_eq!("9f01ff420102", hex(&["convert", "--from", "edn", "--to", "hex"], "[_ 1], <<1, 2>>"));
+    assert_eq!("[1]\n{\"a\":true}", hex(&["convert", "--from", "edn", "--to", "json"], r#"[1], {"a": true}"#));
+    assert_eq!("[1, 2]", hex(&["convert", "--from", "json"], "[1, 2]"));
+    assert_eq!(b"\x82\x01\x02".to_vec(), run(&["convert", "--from", "hex", "--to", "cbor"], b"820102").unwrap());
+    assert!(run_str(&["convert", "--from", "edn"], "[1 2]").unwrap_err().contains("invalid edn"));
+    assert!(run_str(&["convert", "--from", "yaml"], "").unwrap_err().contains("unknown format"))
+}
+
+#[test]
+fn validate() {
+    assert_eq!("ok: 2 item(s)\n", run_str(&["validate", "--hex"], "a20102030480").unwrap());
+    assert_eq!("ok: 1 item(s)\n", run_str(&["validate", "--hex", "--canonical", "ctap2"], "a201020304").unwrap());
+    assert_eq!("ok: 1 item(s)\n", run_str(&["validate", "--hex", "--canonical", "dag-cbor"], "a2616101616202").unwrap());
+
+    let e = run_str(&["validate", "--hex", "--canonical", "ctap2"], "01 a203040102").unwrap_err();
+    assert!(e.contains("item 1 at offset 1 is not canonical"), "{}", e);
+    let e = run_str(&["validate", "--hex"], "8201").unwrap_err();
+    assert!(e.contains("item 0 at offset 0"), "{}", e);
+    let e = run_str(&["validate", "--hex"], "62ffff").unwrap_err();
+    assert!(e.contains("item 0 at offset 0"), "{}", e)
+}
+
+#[test]
+fn split() {
+    assert_eq!("a201020304\n80\n00\n9f6161ff\n", run_str(&["split", "--hex"], "a201020304 80 00 9f6161ff").unwrap());
+    assert_eq!("", run_str(&["split", "--hex"], "").unwrap());
+    assert!(run_str(&["split", "--hex"], "8301").is_err());
+    assert!(run_str(&["split", "--hex"], "8").unwrap_err().contains("odd number of hex digits"))
+}
+
+#[test]
+fn usage() {
+    assert!(run_str(&["help"], "").unwrap().starts_with("Usage"));
+    assert!(run_str(&[], "").unwrap_err().contains("missing command"));
+    assert!(run_str(&["frobnicate"], "").unwrap_err().contains("unknown command"));
+    assert!(run_str(&["diag", "--bogus"], "").unwrap_err().contains("unknown option"))
+}
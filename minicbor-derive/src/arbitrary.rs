@@ -0,0 +1,112 @@
+use crate::lifetimes::add_lifetime;
+use quote::quote;
+use syn::spanned::Spanned;
+
+/// Entry point to derive `minicbor::ext::arbitrary::Arbitrary` on structs and enums.
+pub fn derive_from(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    let result = match &input.data {
+        syn::Data::Struct(_) | syn::Data::Enum(_) => on_data(&input),
+        syn::Data::Union(u) => {
+            let msg = "deriving `minicbor::Arbitrary` for a `union` is not supported";
+            Err(syn::Error::new(u.union_token.span(), msg))
+        }
+    };
+    proc_macro::TokenStream::from(result.unwrap_or_else(|e| e.to_compile_error()))
+}
+
+/// Create an `Arbitrary` impl for structs and enums.
+//
+// Values are constructed field by field in declaration order and enum
+// variants are chosen uniformly. Instead of adding bounds to type
+// parameters, every field type is required to implement `Arbitrary`, so
+// types with fields which do not are still accepted, their impl is just
+// not usable. Field types which refer to the type itself are exempt, as
+// their bound could never be proven.
+fn on_data(inp: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &inp.ident;
+
+    let body = match &inp.data {
+        syn::Data::Struct(s) => construct(quote!(#name), &s.fields),
+        syn::Data::Enum(e)   => {
+            let len  = e.variants.len();
+            let pos  = 0 .. len;
+            let cons = e.variants.iter().map(|v| {
+                let con = &v.ident;
+                construct(quote!(#name::#con), &v.fields)
+            });
+            quote! {
+                match __u777.choose_index(#len)? {
+                    #(#pos => #cons,)*
+                    _ => unreachable!("index < {}", #len)
+                }
+            }
+        }
+        syn::Data::Union(_) => unreachable!("`derive_from` matched against structs and enums")
+    };
+
+    let types: Vec<&syn::Type> = match &inp.data {
+        syn::Data::Struct(s) => s.fields.iter().map(|f| &f.ty).collect(),
+        syn::Data::Enum(e)   => e.variants.iter().flat_map(|v| v.fields.iter().map(|f| &f.ty)).collect(),
+        syn::Data::Union(_)  => Vec::new()
+    };
+
+    let mut lifetime: syn::LifetimeDef = syn::parse_str("'arbitrary")?;
+    for l in inp.generics.lifetimes() {
+        lifetime.bounds.push(l.lifetime.clone())
+    }
+
+    let g = add_lifetime(&inp.generics, lifetime);
+    let (impl_generics, ..) = g.split_for_impl();
+    let (_, typ_generics, _) = inp.generics.split_for_impl();
+
+    let mut where_clause = inp.generics.where_clause.clone().unwrap_or_else(|| syn::parse_quote!(where));
+    for ty in types.into_iter().filter(|ty| !mentions(ty, name)) {
+        where_clause.predicates.push(syn::parse_quote!(#ty: minicbor::ext::arbitrary::Arbitrary<'arbitrary>))
+    }
+
+    Ok(quote! {
+        impl #impl_generics minicbor::ext::arbitrary::Arbitrary<'arbitrary> for #name #typ_generics #where_clause {
+            fn arbitrary(__u777: &mut minicbor::ext::arbitrary::Unstructured<'arbitrary>) -> minicbor::ext::arbitrary::Result<Self> {
+                Ok(#body)
+            }
+        }
+    })
+}
+
+/// Construct a struct or enum variant from arbitrary field values.
+fn construct(path: proc_macro2::TokenStream, fields: &syn::Fields) -> proc_macro2::TokenStream {
+    let value = quote!(minicbor::ext::arbitrary::Arbitrary::arbitrary(__u777)?);
+    match fields {
+        syn::Fields::Named(f) => {
+            let idents = f.named.iter().map(|f| &f.ident);
+            quote!(#path { #(#idents: #value),* })
+        }
+        syn::Fields::Unnamed(f) => {
+            let values = f.unnamed.iter().map(|_| &value);
+            quote!(#path(#(#values),*))
+        }
+        syn::Fields::Unit => path
+    }
+}
+
+/// Check if the given type refers to a type of the given name.
+fn mentions(ty: &syn::Type, name: &syn::Ident) -> bool {
+    use syn::visit::Visit;
+
+    struct Finder<'a> {
+        name: &'a syn::Ident,
+        found: bool
+    }
+
+    impl<'a> Visit<'a> for Finder<'_> {
+        fn visit_path_segment(&mut self, s: &'a syn::PathSegment) {
+            self.found |= s.ident == *self.name;
+            syn::visit::visit_path_segment(self, s)
+        }
+    }
+
+    let mut f = Finder { name, found: false };
+    f.visit_type(ty);
+    f.found
+}
@@ -4,6 +4,7 @@ pub mod typeparam;
 pub mod codec;
 pub mod encoding;
 pub mod idx;
+pub mod repr;
 
 use std::collections::HashMap;
 use std::fmt;
@@ -15,6 +16,7 @@ pub use typeparam::TypeParams;
 pub use codec::CustomCodec;
 pub use encoding::Encoding;
 pub use idx::Idx;
+pub use repr::Repr;
 
 /// Recognised attributes.
 #[derive(Debug, Clone)]
@@ -22,19 +24,31 @@ pub struct Attributes(Level, HashMap<Kind, Value>);
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 enum Kind {
+    AcceptArray,
+    Alias,
     Codec,
+    CollectMissing,
+    DenyUnknown,
     Encoding,
+    Heap,
     Index,
     IndexOnly,
+    MapErr,
+    Parser,
+    Repr,
+    RequireCanonical,
     Transparent,
     TypeParam
 }
 
 #[derive(Debug, Clone)]
 enum Value {
+    Aliases(Vec<u32>, proc_macro2::Span),
     Codec(CustomCodec, proc_macro2::Span),
     Encoding(Encoding, proc_macro2::Span),
     Index(Idx, proc_macro2::Span),
+    Path(syn::ExprPath, proc_macro2::Span),
+    Repr(Repr, proc_macro2::Span),
     Span(proc_macro2::Span),
     TypeParam(TypeParams, proc_macro2::Span)
 }
@@ -80,18 +94,14 @@ impl Attributes {
     fn try_from(l: Level, a: &syn::Attribute) -> syn::Result<Attributes> {
         let mut attrs = Attributes::new(l);
 
-        // #[n(...)]
-        if a.path.is_ident("n") {
-            let idx = parse_u32_arg(a).map(Idx::N)?;
-            attrs.try_insert(Kind::Index, Value::Index(idx, a.tokens.span()))?;
-            return Ok(attrs)
-        }
-
-        // #[b(...)]
-        if a.path.is_ident("b") {
-            let idx = parse_u32_arg(a).map(Idx::B)?;
-            attrs.try_insert(Kind::Index, Value::Index(idx, a.tokens.span()))?;
-            return Ok(attrs)
+        // #[n(...)] or #[b(...)]
+        if a.path.is_ident("n") || a.path.is_ident("b") {
+            if let syn::Meta::List(ml) = a.parse_meta()? {
+                attrs.insert_index(&ml, a.tokens.span())?;
+                return Ok(attrs)
+            }
+            let msg = format!("{} expects a u32 argument", a.path.get_ident().expect("n or b"));
+            return Err(syn::Error::new(a.span(), msg))
         }
 
         // #[cbor(...)]
@@ -110,6 +120,18 @@ impl Attributes {
                 syn::NestedMeta::Meta(syn::Meta::Path(arg)) =>
                     if arg.is_ident("index_only") {
                         attrs.try_insert(Kind::IndexOnly, Value::Span(nested.span()))?
+                    } else if arg.is_ident("accept_array") {
+                        attrs.try_insert(Kind::AcceptArray, Value::Span(nested.span()))?
+                    } else if arg.is_ident("collect_missing") {
+                        attrs.try_insert(Kind::CollectMissing, Value::Span(nested.span()))?
+                    } else if arg.is_ident("deny_unknown_fields") {
+                        attrs.try_insert(Kind::DenyUnknown, Value::Span(nested.span()))?
+                    } else if arg.is_ident("heap") {
+                        attrs.try_insert(Kind::Heap, Value::Span(nested.span()))?
+                    } else if arg.is_ident("parser") {
+                        attrs.try_insert(Kind::Parser, Value::Span(nested.span()))?
+                    } else if arg.is_ident("require_canonical") {
+                        attrs.try_insert(Kind::RequireCanonical, Value::Span(nested.span()))?
                     } else if arg.is_ident("transparent") {
                         attrs.try_insert(Kind::Transparent, Value::Span(nested.span()))?
                     } else if arg.is_ident("map") {
@@ -141,6 +163,31 @@ impl Attributes {
                         } else {
                             return Err(syn::Error::new(arg.span(), "string required"))
                         }
+                    } else if arg.path.is_ident("map_err") {
+                        if let syn::Lit::Str(path) = &arg.lit {
+                            let p = syn::parse_str(&path.value())?;
+                            attrs.try_insert(Kind::MapErr, Value::Path(p, nested.span()))?
+                        } else {
+                            return Err(syn::Error::new(arg.span(), "string required"))
+                        }
+                    } else if arg.path.is_ident("repr") {
+                        if let syn::Lit::Str(s) = &arg.lit {
+                            let r = match s.value().as_str() {
+                                "array" => Repr::Array,
+                                "map"   => Repr::Map,
+                                "tag"   => {
+                                    let msg = "a tag representation requires a base tag, e.g. `repr(tag = 32768)`";
+                                    return Err(syn::Error::new(s.span(), msg))
+                                }
+                                _       => {
+                                    let msg = "expected \"array\" or \"map\"";
+                                    return Err(syn::Error::new(s.span(), msg))
+                                }
+                            };
+                            attrs.try_insert(Kind::Repr, Value::Repr(r, nested.span()))?
+                        } else {
+                            return Err(syn::Error::new(arg.span(), "string required"))
+                        }
                     } else if arg.path.is_ident("encode_bound") {
                         if let syn::Lit::Str(path) = &arg.lit {
                             let t: syn::TypeParam = syn::parse_str(&path.value())?;
@@ -170,20 +217,11 @@ impl Attributes {
                         return Err(syn::Error::new(nested.span(), "unknown attribute"))
                     }
                 syn::NestedMeta::Meta(syn::Meta::List(arg)) =>
-                    if arg.path.is_ident("n") {
-                        if let Some(syn::NestedMeta::Lit(syn::Lit::Int(n))) = arg.nested.first() {
-                            let idx = parse_int(n).map(Idx::N)?;
-                            attrs.try_insert(Kind::Index, Value::Index(idx, a.tokens.span()))?;
-                        } else {
-                            return Err(syn::Error::new(arg.span(), "n expects a u32 argument"))
-                        }
-                    } else if arg.path.is_ident("b") {
-                        if let Some(syn::NestedMeta::Lit(syn::Lit::Int(n))) = arg.nested.first() {
-                            let idx = parse_int(n).map(Idx::B)?;
-                            attrs.try_insert(Kind::Index, Value::Index(idx, a.tokens.span()))?;
-                        } else {
-                            return Err(syn::Error::new(arg.span(), "b expects a u32 argument"))
-                        }
+                    if arg.path.is_ident("n") || arg.path.is_ident("b") {
+                        attrs.insert_index(arg, a.tokens.span())?
+                    } else if arg.path.is_ident("repr") {
+                        let base = parse_tag_base(arg)?;
+                        attrs.try_insert(Kind::Repr, Value::Repr(Repr::Tag(base), nested.span()))?
                     } else {
                         return Err(syn::Error::new(nested.span(), "unknown attribute"))
                     }
@@ -195,14 +233,53 @@ impl Attributes {
         Ok(attrs)
     }
 
+    /// Insert the index and aliases of `n(<u32>, alias = <u32>, ...)`
+    /// or `b(<u32>, alias = <u32>, ...)`.
+    fn insert_index(&mut self, ml: &syn::MetaList, span: proc_macro2::Span) -> syn::Result<()> {
+        let is_b = ml.path.is_ident("b");
+        let mut args = ml.nested.iter();
+        if let Some(syn::NestedMeta::Lit(syn::Lit::Int(n))) = args.next() {
+            let idx = parse_int(n)?;
+            self.try_insert(Kind::Index, Value::Index(if is_b { Idx::B(idx) } else { Idx::N(idx) }, span))?
+        } else {
+            let msg = if is_b { "b expects a u32 argument" } else { "n expects a u32 argument" };
+            return Err(syn::Error::new(ml.span(), msg))
+        }
+        let mut aliases = Vec::new();
+        for arg in args {
+            match arg {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("alias") => {
+                    if let syn::Lit::Int(n) = &nv.lit {
+                        aliases.push(parse_int(n)?)
+                    } else {
+                        return Err(syn::Error::new(nv.lit.span(), "alias expects a u32 argument"))
+                    }
+                }
+                _ => return Err(syn::Error::new(arg.span(), "unknown attribute"))
+            }
+        }
+        if !aliases.is_empty() {
+            self.try_insert(Kind::Alias, Value::Aliases(aliases, span))?
+        }
+        Ok(())
+    }
+
     pub fn encoding(&self) -> Option<Encoding> {
         self.get(Kind::Encoding).and_then(|v| v.encoding())
     }
 
+    pub fn repr(&self) -> Option<Repr> {
+        self.get(Kind::Repr).and_then(|v| v.repr())
+    }
+
     pub fn index(&self) -> Option<Idx> {
         self.get(Kind::Index).and_then(|v| v.index())
     }
 
+    pub fn aliases(&self) -> &[u32] {
+        self.get(Kind::Alias).and_then(|v| v.aliases()).unwrap_or_default()
+    }
+
     pub fn codec(&self) -> Option<&CustomCodec> {
         self.get(Kind::Codec).and_then(|v| v.codec())
     }
@@ -211,6 +288,10 @@ impl Attributes {
         self.get(Kind::TypeParam).and_then(|v| v.type_params())
     }
 
+    pub fn map_err(&self) -> Option<&syn::ExprPath> {
+        self.get(Kind::MapErr).and_then(|v| v.path())
+    }
+
     pub fn transparent(&self) -> bool {
         self.contains_key(Kind::Transparent)
     }
@@ -219,6 +300,30 @@ impl Attributes {
         self.contains_key(Kind::IndexOnly)
     }
 
+    pub fn accept_array(&self) -> bool {
+        self.contains_key(Kind::AcceptArray)
+    }
+
+    pub fn collect_missing(&self) -> bool {
+        self.contains_key(Kind::CollectMissing)
+    }
+
+    pub fn deny_unknown_fields(&self) -> bool {
+        self.contains_key(Kind::DenyUnknown)
+    }
+
+    pub fn heap(&self) -> bool {
+        self.contains_key(Kind::Heap)
+    }
+
+    pub fn parser(&self) -> bool {
+        self.contains_key(Kind::Parser)
+    }
+
+    pub fn require_canonical(&self) -> bool {
+        self.contains_key(Kind::RequireCanonical)
+    }
+
     fn contains_key(&self, k: Kind) -> bool {
         self.1.contains_key(&k)
     }
@@ -234,29 +339,37 @@ impl Attributes {
     fn try_insert(&mut self, key: Kind, val: Value) -> syn::Result<()> {
         match self.0 {
             Level::Struct => match key {
-                Kind::Encoding  | Kind::Transparent => {}
-                Kind::TypeParam | Kind::Codec | Kind::Index | Kind::IndexOnly => {
+                Kind::Encoding  | Kind::Transparent | Kind::DenyUnknown | Kind::CollectMissing
+                | Kind::RequireCanonical | Kind::Parser | Kind::Heap => {}
+                Kind::TypeParam | Kind::Codec | Kind::Index | Kind::IndexOnly | Kind::MapErr
+                | Kind::Alias | Kind::Repr | Kind::AcceptArray => {
                     let msg = format!("attribute is not supported on {}-level", self.0);
                     return Err(syn::Error::new(val.span(), msg))
                 }
             }
             Level::Field => match key {
-                Kind::TypeParam | Kind::Codec     | Kind::Index => {}
-                Kind::Encoding  | Kind::IndexOnly | Kind::Transparent => {
+                Kind::TypeParam | Kind::Codec     | Kind::Index | Kind::MapErr | Kind::Alias => {}
+                Kind::Encoding  | Kind::IndexOnly | Kind::Transparent
+                | Kind::DenyUnknown | Kind::CollectMissing | Kind::RequireCanonical | Kind::Parser
+                | Kind::Heap | Kind::Repr | Kind::AcceptArray => {
                     let msg = format!("attribute is not supported on {}-level", self.0);
                     return Err(syn::Error::new(val.span(), msg))
                 }
             }
             Level::Enum => match key {
-                Kind::Encoding  | Kind::IndexOnly => {}
-                Kind::TypeParam | Kind::Codec | Kind::Index | Kind::Transparent => {
+                Kind::Encoding  | Kind::IndexOnly | Kind::DenyUnknown | Kind::CollectMissing
+                | Kind::RequireCanonical | Kind::Repr | Kind::AcceptArray => {}
+                Kind::TypeParam | Kind::Codec | Kind::Index | Kind::Transparent | Kind::MapErr
+                | Kind::Parser  | Kind::Alias | Kind::Heap => {
                     let msg = format!("attribute is not supported on {}-level", self.0);
                     return Err(syn::Error::new(val.span(), msg))
                 }
             }
             Level::Variant => match key {
-                Kind::Encoding  | Kind::Index => {}
-                Kind::TypeParam | Kind::Codec | Kind::IndexOnly | Kind::Transparent => {
+                Kind::Encoding  | Kind::Index | Kind::DenyUnknown | Kind::CollectMissing => {}
+                Kind::TypeParam | Kind::Codec | Kind::IndexOnly | Kind::Transparent | Kind::MapErr
+                | Kind::RequireCanonical | Kind::Parser | Kind::Alias | Kind::Heap | Kind::Repr
+                | Kind::AcceptArray => {
                     let msg = format!("attribute is not supported on {}-level", self.0);
                     return Err(syn::Error::new(val.span(), msg))
                 }
@@ -295,10 +408,13 @@ impl Attributes {
 impl Value {
     fn span(&self) -> proc_macro2::Span {
         match self {
+            Value::Aliases(_, s)   => *s,
             Value::TypeParam(_, s) => *s,
             Value::Codec(_, s)     => *s,
             Value::Encoding(_, s)  => *s,
             Value::Index(_, s)     => *s,
+            Value::Path(_, s)      => *s,
+            Value::Repr(_, s)      => *s,
             Value::Span(s)         => *s
         }
     }
@@ -311,6 +427,14 @@ impl Value {
         }
     }
 
+    fn aliases(&self) -> Option<&[u32]> {
+        if let Value::Aliases(a, _) = self {
+            Some(a)
+        } else {
+            None
+        }
+    }
+
     fn codec(&self) -> Option<&CustomCodec> {
         if let Value::Codec(c, _) = self {
             Some(c)
@@ -319,6 +443,14 @@ impl Value {
         }
     }
 
+    fn path(&self) -> Option<&syn::ExprPath> {
+        if let Value::Path(p, _) = self {
+            Some(p)
+        } else {
+            None
+        }
+    }
+
     fn encoding(&self) -> Option<Encoding> {
         if let Value::Encoding(e, _) = self {
             Some(*e)
@@ -327,6 +459,14 @@ impl Value {
         }
     }
 
+    fn repr(&self) -> Option<Repr> {
+        if let Value::Repr(r, _) = self {
+            Some(*r)
+        } else {
+            None
+        }
+    }
+
     fn type_params(&self) -> Option<&TypeParams> {
         if let Value::TypeParam(t, _) = self {
             Some(t)
@@ -336,13 +476,24 @@ impl Value {
     }
 }
 
-fn parse_u32_arg(a: &syn::Attribute) -> syn::Result<u32> {
-    parse_int(&a.parse_args()?)
-}
-
 fn parse_int(n: &syn::LitInt) -> syn::Result<u32> {
     n.base10_digits()
      .parse()
      .map_err(|_| syn::Error::new(n.span(), "expected `u32` value"))
 }
 
+/// Parse the base tag of `repr(tag = <u64>)`.
+fn parse_tag_base(ml: &syn::MetaList) -> syn::Result<u64> {
+    let mut args = ml.nested.iter();
+    if let (Some(syn::NestedMeta::Meta(syn::Meta::NameValue(nv))), None) = (args.next(), args.next()) {
+        if nv.path.is_ident("tag") {
+            if let syn::Lit::Int(n) = &nv.lit {
+                return n.base10_digits()
+                    .parse()
+                    .map_err(|_| syn::Error::new(n.span(), "expected `u64` value"))
+            }
+        }
+    }
+    Err(syn::Error::new(ml.span(), "expected `repr(tag = <u64>)`"))
+}
+
@@ -1,13 +1,7 @@
 /// The encoding to use for structs and enum variants.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Encoding {
+    #[default]
     Array,
     Map
 }
-
-impl Default for Encoding {
-    fn default() -> Self {
-        Encoding::Array
-    }
-}
-
@@ -0,0 +1,11 @@
+/// The representation of enum variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Repr {
+    /// A 2-element array of variant index and variant value.
+    #[default]
+    Array,
+    /// A 1-element map from variant index to variant value.
+    Map,
+    /// The variant value, tagged with the sum of base tag and variant index.
+    Tag(u64)
+}
@@ -1,6 +1,6 @@
 use crate::Mode;
-use crate::{add_bound_to_type_params, collect_type_params, is_cow, is_option, is_str, is_byte_slice};
-use crate::attrs::{Attributes, CustomCodec, Encoding, Level};
+use crate::{add_bound_to_type_params, collect_type_params, enum_repr, is_cow, is_option, is_str, is_byte_slice, is_path};
+use crate::attrs::{Attributes, CustomCodec, Encoding, Level, Repr};
 use crate::fields::Fields;
 use crate::variants::Variants;
 use crate::lifetimes::{gen_lifetime, lifetimes_to_constrain, add_lifetime};
@@ -11,6 +11,7 @@ use syn::spanned::Spanned;
 /// Entry point to derive `minicbor::Decode` on structs and enums.
 pub fn derive_from(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let mut input = syn::parse_macro_input!(input as syn::DeriveInput);
+    let parser = crate::parser::derive_from(&input);
     let result = match &input.data {
         syn::Data::Struct(_) => on_struct(&mut input),
         syn::Data::Enum(_)   => on_enum(&mut input),
@@ -19,6 +20,7 @@ pub fn derive_from(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             Err(syn::Error::new(u.union_token.span(), msg))
         }
     };
+    let result = result.and_then(|r| parser.map(|p| quote!(#r #p)));
     proc_macro::TokenStream::from(result.unwrap_or_else(|e| e.to_compile_error()))
 }
 
@@ -51,7 +53,7 @@ fn on_struct(inp: &mut syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream
     let blacklist = {
         let iter = data.fields.iter()
             .zip(&decode_fns)
-            .filter_map(|(f, ff)| ff.is_some().then(|| f));
+            .filter_map(|(f, ff)| ff.is_some().then_some(f));
         collect_type_params(&inp.generics, iter)
     };
 
@@ -71,42 +73,53 @@ fn on_struct(inp: &mut syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream
             let msg = "#[cbor(transparent)] requires a struct with one field";
             return Err(syn::Error::new(inp.ident.span(), msg))
         }
+        if attrs.heap() {
+            let msg = "#[cbor(heap)] can not be combined with #[cbor(transparent)]";
+            return Err(syn::Error::new(inp.ident.span(), msg))
+        }
         let f = data.fields.iter().next().expect("struct has 1 field");
         let a = fields.attrs.first().expect("struct has 1 field");
-        return make_transparent_impl(&inp.ident, f, a, impl_generics, typ_generics, where_clause)
+        let c = gen_canonical_check(&attrs);
+        return make_transparent_impl(&inp.ident, f, a, c, impl_generics, typ_generics, where_clause)
     }
 
     let field_str  = fields.idents.iter().map(|n| format!("{}::{}", name, n)).collect::<Vec<_>>();
-    let statements = gen_statements(&fields, &decode_fns, attrs.encoding().unwrap_or_default())?;
-
-    let Fields { indices, idents, .. } = fields;
-
-    let result = if let syn::Fields::Named(_) = data.fields {
-        quote! {
-            Ok(#name {
-                #(#idents : if let Some(x) = #idents {
-                    x
+    let type_str   = name.to_string();
+    let unknown    = attrs.deny_unknown_fields().then_some(type_str.as_str());
+    let encoding   = attrs.encoding().unwrap_or_default();
+
+    let (statements, values) = if attrs.heap() && fields.len() > 0 {
+        let values = fields.idents.iter().zip(fields.types.iter())
+            .map(|(n, ty)| {
+                if is_option(ty, |_| true) {
+                    quote!(Some(#n.and_then(|x| *x)))
                 } else {
-                    return Err(minicbor::decode::Error::MissingValue(#indices, #field_str))
-                }),*
+                    quote!(#n.map(|x| *x))
+                }
             })
-        }
-    } else if let syn::Fields::Unit = &data.fields {
-        quote!(Ok(#name))
+            .collect::<Vec<_>>();
+        (gen_heap_statements(&fields, &decode_fns, encoding, unknown)?, values)
     } else {
-        quote! {
-            Ok(#name(#(if let Some(x) = #idents {
-                x
-            } else {
-                return Err(minicbor::decode::Error::MissingValue(#indices, #field_str))
-            }),*))
-        }
+        let values = fields.idents.iter().map(|n| quote!(#n)).collect::<Vec<_>>();
+        (gen_statements(&fields, &decode_fns, encoding, unknown)?, values)
+    };
+
+    let missing = if attrs.collect_missing() {
+        gen_missing_check(name.span(), &fields, &field_str)?
+    } else {
+        quote!()
     };
 
+    let canonical = gen_canonical_check(&attrs);
+
+    let result = gen_constructor(name, &data.fields, &fields, &field_str, &values);
+
     Ok(quote! {
         impl #impl_generics minicbor::Decode<'bytes> for #name #typ_generics #where_clause {
             fn decode(__d777: &mut minicbor::Decoder<'bytes>) -> core::result::Result<#name #typ_generics, minicbor::decode::Error> {
+                #canonical
                 #statements
+                #missing
                 #result
             }
         }
@@ -126,8 +139,14 @@ fn on_enum(inp: &mut syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream>
     let enum_attrs    = Attributes::try_from_iter(Level::Enum, inp.attrs.iter())?;
     let enum_encoding = enum_attrs.encoding().unwrap_or_default();
     let index_only    = enum_attrs.index_only();
+    let repr          = enum_repr(name, &enum_attrs)?;
     let variants      = Variants::try_from(name.span(), data.variants.iter())?;
 
+    if enum_attrs.accept_array() && repr == Repr::Array {
+        let msg = "#[cbor(accept_array)] requires #[cbor(repr = \"map\")] or #[cbor(repr(tag = ...))]";
+        return Err(syn::Error::new(name.span(), msg))
+    }
+
     let mut blacklist = HashSet::new();
     let mut field_attrs = Vec::new();
     let mut lifetime = gen_lifetime()?;
@@ -162,15 +181,24 @@ fn on_enum(inp: &mut syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream>
             blacklist.extend({
                 let iter = var.fields.iter()
                     .zip(&decode_fns)
-                    .filter_map(|(f, ff)| ff.is_some().then(|| f));
+                    .filter_map(|(f, ff)| ff.is_some().then_some(f));
                 collect_type_params(&inp.generics, iter)
             });
-            let statements = gen_statements(&fields, &decode_fns, encoding)?;
+            let type_str = format!("{}::{}", name, con);
+            let unknown  = (attrs.deny_unknown_fields() || enum_attrs.deny_unknown_fields())
+                .then_some(type_str.as_str());
+            let statements = gen_statements(&fields, &decode_fns, encoding, unknown)?;
+            let missing = if attrs.collect_missing() || enum_attrs.collect_missing() {
+                gen_missing_check(var.ident.span(), &fields, &field_str)?
+            } else {
+                quote!()
+            };
             let Fields { indices, idents, .. } = fields;
             if let syn::Fields::Named(_) = var.fields {
                 quote! {
                     #idx => {
                         #statements
+                        #missing
                         Ok(#name::#con {
                             #(#idents : if let Some(x) = #idents {
                                 x
@@ -184,6 +212,7 @@ fn on_enum(inp: &mut syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream>
                 quote! {
                     #idx => {
                         #statements
+                        #missing
                         Ok(#name::#con(#(if let Some(x) = #idents {
                             x
                         } else {
@@ -207,29 +236,69 @@ fn on_enum(inp: &mut syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream>
     let (impl_generics , ..) = g.split_for_impl();
     let (_, typ_generics, where_clause) = inp.generics.split_for_impl();
 
-    let check = if index_only {
-        quote!()
+    let name_str = name.to_string();
+
+    let canonical = gen_canonical_check(&enum_attrs);
+
+    let index = if index_only {
+        quote!(__d777.u32()?)
     } else {
-        quote! {
-            if Some(2) != __d777.array()? {
-                return Err(minicbor::decode::Error::Message("expected enum (2-element array)"))
+        let target = gen_variant_index(repr);
+        if enum_attrs.accept_array() {
+            let legacy = gen_variant_index(Repr::Array);
+            quote! {
+                if minicbor::data::Type::Array == __d777.datatype()? {
+                    #legacy
+                } else {
+                    #target
+                }
             }
+        } else {
+            target
         }
     };
 
     Ok(quote! {
         impl #impl_generics minicbor::Decode<'bytes> for #name #typ_generics #where_clause {
             fn decode(__d777: &mut minicbor::Decoder<'bytes>) -> core::result::Result<#name #typ_generics, minicbor::decode::Error> {
-                #check
-                match __d777.u32()? {
+                #canonical
+                match #index {
                     #(#rows)*
-                    n => Err(minicbor::decode::Error::UnknownVariant(n))
+                    n => Err(minicbor::decode::Error::UnknownVariant(n, #name_str))
                 }
             }
         }
     })
 }
 
+/// Generate code to decode the variant index of an enum in the given representation.
+fn gen_variant_index(repr: Repr) -> proc_macro2::TokenStream {
+    match repr {
+        Repr::Array => quote! {{
+            if Some(2) != __d777.array()? {
+                return Err(minicbor::decode::Error::Message("expected enum (2-element array)"))
+            }
+            __d777.u32()?
+        }},
+        Repr::Map => quote! {{
+            if Some(1) != __d777.map()? {
+                return Err(minicbor::decode::Error::Message("expected enum (1-element map)"))
+            }
+            __d777.u32()?
+        }},
+        Repr::Tag(base) => {
+            let base = proc_macro2::Literal::u64_suffixed(base);
+            quote! {{
+                let __t777 = u64::from(__d777.tag()?);
+                let __i777 = __t777.checked_sub(#base)
+                    .ok_or(minicbor::decode::Error::Message("expected enum (tag >= base tag)"))?;
+                <u32 as core::convert::TryFrom<u64>>::try_from(__i777)
+                    .map_err(|_| minicbor::decode::Error::Overflow(__t777, "enum variant tag"))?
+            }}
+        }
+    }
+}
+
 /// Generate decoding statements for every item.
 //
 // For every name `n`, type `t` and index `i` we declare a local mutable
@@ -242,97 +311,282 @@ fn on_enum(inp: &mut syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream>
 // successful, we assign the result to `n` as `Some(v)`, otherwise we
 // error, or -- if `t` is an option and the decoding failed because an
 // unknown enum variant was decoded -- we skip the variant value and
-// continue decoding.
+// continue decoding. Items with unknown indices are skipped, unless
+// `unknown` contains a type name, in which case an `UnknownIndex` error
+// is produced (NULLs in arrays denote absent values and are still skipped).
 //
 // --------------------------------------------------------------------
 // [1]: These variables will later be deconstructed in `on_enum` and
 // `on_struct` and their inner value will be used to initialise a field.
 // If not present, an error will be produced.
-fn gen_statements(fields: &Fields, decode_fns: &[Option<CustomCodec>], encoding: Encoding) -> syn::Result<proc_macro2::TokenStream> {
+fn gen_statements
+    ( fields: &Fields
+    , decode_fns: &[Option<CustomCodec>]
+    , encoding: Encoding
+    , unknown: Option<&str>
+    ) -> syn::Result<proc_macro2::TokenStream>
+{
+    let inits   = gen_inits(fields);
+    let places  = fields.idents.iter().map(|n| quote!(#n)).collect::<Vec<_>>();
+    let actions = gen_actions(fields, decode_fns, &places, false)?;
+
+    let Fields { idents, types, .. } = fields;
+
+    let iteration = gen_iteration(fields, &actions, encoding, unknown);
+
+    Ok(quote! {
+        #(let mut #idents : core::option::Option<#types> = #inits;)*
+        #iteration
+    })
+}
+
+/// Generate decoding statements for every item with `#[cbor(heap)]`.
+//
+// Like `gen_statements`, but the local variables are of type
+// `Option<Box<t>>` and every decoded value is moved into its own heap
+// allocation, so that the stack frame holds only a pointer per field.
+// The variables start out as `None`, i.e. nothing is allocated before a
+// value has been decoded and optional fields which are not present are
+// `None` in the end (cf. `on_struct`).
+fn gen_heap_statements
+    ( fields: &Fields
+    , decode_fns: &[Option<CustomCodec>]
+    , encoding: Encoding
+    , unknown: Option<&str>
+    ) -> syn::Result<proc_macro2::TokenStream>
+{
+    let places  = fields.idents.iter().map(|n| quote!(#n)).collect::<Vec<_>>();
+    let actions = gen_actions(fields, decode_fns, &places, true)?;
+
+    let Fields { idents, types, .. } = fields;
+
+    let iteration = gen_iteration(fields, &actions, encoding, unknown);
+
+    Ok(quote! {
+        #(let mut #idents : core::option::Option<minicbor::__private::Box<#types>> = None;)*
+        #iteration
+    })
+}
+
+/// Generate the initial values of the field variables (cf. `gen_statements`).
+pub(crate) fn gen_inits(fields: &Fields) -> Vec<proc_macro2::TokenStream> {
+    fields.types.iter()
+        .map(|ty| {
+            if is_option(ty, |_| true) {
+                quote!(Some(None))
+            } else {
+                quote!(None)
+            }
+        })
+        .collect()
+}
+
+/// Generate the decoding action of every field.
+//
+// An action decodes the next CBOR item from `__d777` and assigns it as
+// `Some(v)` to the corresponding place, which is the field variable
+// itself (cf. `gen_statements`) or something the parser derives from it.
+// If `boxed` is true, `Some(Box::new(v))` is assigned instead (cf.
+// `gen_heap_statements`).
+pub(crate) fn gen_actions
+    ( fields: &Fields
+    , decode_fns: &[Option<CustomCodec>]
+    , places: &[proc_macro2::TokenStream]
+    , boxed: bool
+    ) -> syn::Result<Vec<proc_macro2::TokenStream>>
+{
     assert_eq!(fields.len(), decode_fns.len());
+    assert_eq!(fields.len(), places.len());
 
     let default_decode_fn: syn::ExprPath = syn::parse_str("minicbor::Decode::decode")?;
 
-    let inits = fields.types.iter().map(|ty| {
-        if is_option(ty, |_| true) {
-            quote!(Some(None))
+    let some = |v: proc_macro2::TokenStream| {
+        if boxed {
+            quote!(Some(minicbor::__private::Box::new(#v)))
         } else {
-            quote!(None)
+            quote!(Some(#v))
         }
-    });
+    };
 
-    let actions = fields.indices.iter().zip(fields.idents.iter().zip(fields.types.iter().zip(decode_fns)))
-        .map(|(ix, (name, (ty, ff)))| {
+    let actions = fields.indices.iter().zip(places.iter().zip(fields.types.iter().zip(decode_fns.iter().zip(&fields.attrs))))
+        .map(|(ix, (name, (ty, (ff, attrs))))| {
             let decode_fn = ff.as_ref()
                 .and_then(|ff| ff.to_decode_path())
                 .unwrap_or_else(|| default_decode_fn.clone());
+            let error = map_error(attrs);
             if is_option(ty, |_| true) {
+                let value = some(quote!(__v777));
                 return quote! {
                     match #decode_fn(__d777) {
-                        Ok(__v777) => #name = Some(__v777),
-                        Err(minicbor::decode::Error::UnknownVariant(_)) => { __d777.skip()? }
-                        Err(e) => return Err(e)
+                        Ok(__v777) => #name = #value,
+                        Err(minicbor::decode::Error::UnknownVariant(..)) => { __d777.skip()? }
+                        Err(e) => return Err(#error)
                     }
                 }
             }
-            if ix.is_b() && is_cow(ty, |t| is_str(t) || is_byte_slice(t)) {
+            if ix.is_b() && is_cow(ty, |t| is_str(t) || is_byte_slice(t) || is_path(t)) {
+                let value = some(quote!(std::borrow::Cow::Borrowed(__v777)));
                 return quote! {
                     match #decode_fn(__d777) {
-                        Ok(__v777) => #name = Some(std::borrow::Cow::Borrowed(__v777)),
-                        Err(minicbor::decode::Error::UnknownVariant(_)) => { __d777.skip()? }
-                        Err(e) => return Err(e)
+                        Ok(__v777) => #name = #value,
+                        Err(minicbor::decode::Error::UnknownVariant(..)) => { __d777.skip()? }
+                        Err(e) => return Err(#error)
                     }
                 }
             }
-            quote!({ #name = Some(#decode_fn(__d777)?) })
+            let value = if let Some(f) = attrs.map_err() {
+                some(quote!(#decode_fn(__d777).map_err(#f)?))
+            } else {
+                some(quote!(#decode_fn(__d777)?))
+            };
+            quote!({ #name = #value })
     })
     .collect::<Vec<_>>();
 
-    let Fields { idents, types, indices, .. } = fields;
+    Ok(actions)
+}
 
-    Ok(match encoding {
-        Encoding::Array => quote! {
-            #(let mut #idents : core::option::Option<#types> = #inits;)*
+/// Generate the construction of a struct from its field values.
+//
+// The values are expressions of type `Option<t>`, usually the field
+// variables themselves (cf. `gen_statements`). Every value which is
+// `None` results in a `MissingValue` error.
+pub(crate) fn gen_constructor
+    ( name: &syn::Ident
+    , data: &syn::Fields
+    , fields: &Fields
+    , field_str: &[String]
+    , values: &[proc_macro2::TokenStream]
+    ) -> proc_macro2::TokenStream
+{
+    let Fields { indices, idents, .. } = fields;
+
+    if let syn::Fields::Named(_) = data {
+        quote! {
+            Ok(#name {
+                #(#idents : if let Some(x) = #values {
+                    x
+                } else {
+                    return Err(minicbor::decode::Error::MissingValue(#indices, #field_str))
+                }),*
+            })
+        }
+    } else if let syn::Fields::Unit = data {
+        quote!(Ok(#name))
+    } else {
+        quote! {
+            Ok(#name(#(if let Some(x) = #values {
+                x
+            } else {
+                return Err(minicbor::decode::Error::MissingValue(#indices, #field_str))
+            }),*))
+        }
+    }
+}
+
+/// Generate the iteration over all CBOR array or map elements.
+//
+// Elements whose index is found in `fields` run the corresponding action,
+// all others are skipped or produce an `UnknownIndex` error (cf.
+// `gen_statements`).
+fn gen_iteration
+    ( fields: &Fields
+    , actions: &[proc_macro2::TokenStream]
+    , encoding: Encoding
+    , unknown: Option<&str>
+    ) -> proc_macro2::TokenStream
+{
+    let patterns = fields.patterns();
+
+    let skip_array = if let Some(t) = unknown {
+        quote! {
+            if minicbor::data::Type::Null == __d777.datatype()? {
+                __d777.skip()?
+            } else {
+                return Err(minicbor::decode::Error::UnknownIndex(__i777, #t))
+            }
+        }
+    } else {
+        quote!(__d777.skip()?)
+    };
+
+    let skip_map = if let Some(t) = unknown {
+        quote!(return Err(minicbor::decode::Error::UnknownIndex(__k777.into(), #t)))
+    } else {
+        quote!(__d777.skip()?)
+    };
 
+    match encoding {
+        Encoding::Array => quote! {
             if let Some(__len777) = __d777.array()? {
                 for __i777 in 0 .. __len777 {
                     match __i777 {
-                        #(#indices => #actions)*
-                        _          => __d777.skip()?
+                        #(#patterns => #actions)*
+                        _          => #skip_array
                     }
                 }
             } else {
-                let mut __i777 = 0;
+                let mut __i777: u64 = 0;
                 while minicbor::data::Type::Break != __d777.datatype()? {
                     match __i777 {
-                        #(#indices => #actions)*
-                        _          => __d777.skip()?
+                        #(#patterns => #actions)*
+                        _          => #skip_array
                     }
-                    __i777 += 1
+                    __i777 = __i777.saturating_add(1)
                 }
                 __d777.skip()?
             }
         },
         Encoding::Map => quote! {
-            #(let mut #idents : core::option::Option<#types> = #inits;)*
-
             if let Some(__len777) = __d777.map()? {
                 for _ in 0 .. __len777 {
                     match __d777.u32()? {
-                        #(#indices => #actions)*
-                        _          => __d777.skip()?
+                        #(#patterns => #actions)*
+                        __k777     => #skip_map
                     }
                 }
             } else {
                 while minicbor::data::Type::Break != __d777.datatype()? {
                     match __d777.u32()? {
-                        #(#indices => #actions)*
-                        _          => __d777.skip()?
+                        #(#patterns => #actions)*
+                        __k777     => #skip_map
                     }
                 }
                 __d777.skip()?
             }
         }
+    }
+}
+
+/// Generate a check which reports all missing, non-optional fields at once.
+//
+// Every field variable (cf. `gen_statements`) of a non-`Option` type which
+// is still `None` after decoding is recorded in a `MissingFields` set over a
+// static table of all such fields. If the set is not empty, a single
+// `MissingValues` error is returned.
+pub(crate) fn gen_missing_check(span: proc_macro2::Span, fields: &Fields, field_str: &[String]) -> syn::Result<proc_macro2::TokenStream> {
+    let required = fields.idents.iter()
+        .zip(fields.indices.iter().zip(fields.types.iter().zip(field_str)))
+        .filter(|(_, (_, (ty, _)))| !is_option(ty, |_| true))
+        .map(|(n, (i, (_, s)))| (n, i.val(), s))
+        .collect::<Vec<_>>();
+
+    if required.len() > 64 {
+        let msg = "#[cbor(collect_missing)] supports at most 64 non-optional fields";
+        return Err(syn::Error::new(span, msg))
+    }
+
+    let idents  = required.iter().map(|r| r.0);
+    let indices = required.iter().map(|r| r.1);
+    let strs    = required.iter().map(|r| r.2);
+    let pos     = 0 .. required.len();
+
+    Ok(quote! {
+        let mut __m777 = minicbor::decode::MissingFields::new(&[#((#indices, #strs)),*]);
+        #(if #idents.is_none() { __m777.insert(#pos) })*
+        if !__m777.is_empty() {
+            return Err(minicbor::decode::Error::MissingValues(__m777))
+        }
     })
 }
 
@@ -341,6 +595,7 @@ fn make_transparent_impl
     ( name: &syn::Ident
     , field: &syn::Field
     , attrs: &Attributes
+    , canonical: proc_macro2::TokenStream
     , impl_generics: syn::ImplGenerics
     , typ_generics: syn::TypeGenerics
     , where_clause: Option<&syn::WhereClause>
@@ -352,26 +607,262 @@ fn make_transparent_impl
         return Err(syn::Error::new(span, msg))
     }
 
+    let value =
+        if let Some(f) = attrs.map_err() {
+            quote!(minicbor::Decode::decode(__d777).map_err(#f)?)
+        } else {
+            quote!(minicbor::Decode::decode(__d777)?)
+        };
+
     let call =
         if let Some(id) = &field.ident {
             quote! {
-                Ok(#name { #id: minicbor::Decode::decode(__d777)? })
+                Ok(#name { #id: #value })
             }
         } else {
             quote! {
-                Ok(#name(minicbor::Decode::decode(__d777)?))
+                Ok(#name(#value))
             }
         };
 
     Ok(quote! {
         impl #impl_generics minicbor::Decode<'bytes> for #name #typ_generics #where_clause {
             fn decode(__d777: &mut minicbor::Decoder<'bytes>) -> core::result::Result<#name #typ_generics, minicbor::decode::Error> {
+                #canonical
                 #call
             }
         }
     })
 }
 
+/// Entry point to derive `minicbor::decode::DecodeInPlace` on structs and enums.
+pub fn derive_in_place_from(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    let result = match &input.data {
+        syn::Data::Struct(_) => on_struct_in_place(&input),
+        syn::Data::Enum(_)   => on_enum_in_place(&input),
+        syn::Data::Union(u)  => {
+            let msg = "deriving `minicbor::decode::DecodeInPlace` for a `union` is not supported";
+            Err(syn::Error::new(u.union_token.span(), msg))
+        }
+    };
+    proc_macro::TokenStream::from(result.unwrap_or_else(|e| e.to_compile_error()))
+}
+
+/// Create a `DecodeInPlace` impl for (tuple) structs.
+//
+// Fields are decoded into the existing value, unless they have a custom
+// decode function, in which case they are replaced. The impl requires the
+// struct to implement `Decode` and every field type without a custom decode
+// function to implement `DecodeInPlace`. Absent optional fields are set to
+// `None`, absent non-optional fields cause an error as with `Decode`.
+fn on_struct_in_place(inp: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let data =
+        if let syn::Data::Struct(data) = &inp.data {
+            data
+        } else {
+            unreachable!("`derive_in_place_from` matched against `syn::Data::Struct`")
+        };
+
+    let name   = &inp.ident;
+    let attrs  = Attributes::try_from_iter(Level::Struct, inp.attrs.iter())?;
+    let fields = Fields::try_from(name.span(), data.fields.iter())?;
+
+    let decode_fns: Vec<Option<CustomCodec>> = fields.attrs.iter()
+        .map(|a| a.codec().cloned().filter(CustomCodec::is_decode))
+        .collect();
+
+    let mut lifetime = gen_lifetime()?;
+    for l in lifetimes_to_constrain(fields.indices.iter().zip(fields.types.iter())) {
+        if !lifetime.bounds.iter().any(|b| *b == l) {
+            lifetime.bounds.push(l.clone())
+        }
+    }
+
+    let g = add_lifetime(&inp.generics, lifetime);
+    let (impl_generics, ..) = g.split_for_impl();
+    let (_, typ_generics, _) = inp.generics.split_for_impl();
+
+    let places = fields.pos.iter().zip(fields.idents.iter().zip(&fields.is_name))
+        .map(|(p, (n, is_name))| {
+            if *is_name {
+                quote!(self.#n)
+            } else {
+                let i = syn::Index::from(*p);
+                quote!(self.#i)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut where_clause = inp.generics.where_clause.clone().unwrap_or_else(|| syn::parse_quote!(where));
+    where_clause.predicates.push(syn::parse_quote!(#name #typ_generics: minicbor::Decode<'bytes>));
+    for (ty, ff) in fields.types.iter().zip(&decode_fns) {
+        if ff.is_none() {
+            where_clause.predicates.push(syn::parse_quote!(#ty: minicbor::decode::DecodeInPlace<'bytes>))
+        }
+    }
+
+    let canonical = gen_canonical_check(&attrs);
+
+    if attrs.transparent() {
+        if fields.len() != 1 {
+            let msg = "#[cbor(transparent)] requires a struct with one field";
+            return Err(syn::Error::new(inp.ident.span(), msg))
+        }
+        let place = &places[0];
+        let call  = if let Some(f) = fields.attrs[0].map_err() {
+            quote!(minicbor::decode::DecodeInPlace::decode_into(&mut #place, __d777).map_err(#f))
+        } else {
+            quote!(minicbor::decode::DecodeInPlace::decode_into(&mut #place, __d777))
+        };
+        return Ok(quote! {
+            impl #impl_generics minicbor::decode::DecodeInPlace<'bytes> for #name #typ_generics #where_clause {
+                fn decode_into(&mut self, __d777: &mut minicbor::Decoder<'bytes>) -> core::result::Result<(), minicbor::decode::Error> {
+                    #canonical
+                    #call
+                }
+            }
+        })
+    }
+
+    if let syn::Fields::Unit = &data.fields {
+        return Ok(quote! {
+            impl #impl_generics minicbor::decode::DecodeInPlace<'bytes> for #name #typ_generics #where_clause {}
+        })
+    }
+
+    let actions = fields.indices.iter().zip(fields.types.iter().zip(decode_fns.iter().zip(&places)))
+        .enumerate()
+        .map(|(k, (ix, (ty, (ff, place))))| {
+            let decode_fn = ff.as_ref().and_then(|ff| ff.to_decode_path());
+            let error     = map_error(&fields.attrs[k]);
+            let map_err   = fields.attrs[k].map_err().map(|f| quote!(.map_err(#f)));
+            if is_option(ty, |_| true) {
+                let call = if let Some(f) = decode_fn {
+                    quote!(#f(__d777).map(|__v777| #place = __v777))
+                } else {
+                    quote!(minicbor::decode::DecodeInPlace::decode_into(&mut #place, __d777))
+                };
+                return quote! {
+                    match #call {
+                        Ok(()) => __s777[#k] = true,
+                        Err(minicbor::decode::Error::UnknownVariant(..)) => { __d777.skip()? }
+                        Err(e) => return Err(#error)
+                    }
+                }
+            }
+            if ix.is_b() && is_cow(ty, |t| is_str(t) || is_byte_slice(t) || is_path(t)) {
+                let decode_fn = decode_fn.unwrap_or_else(|| syn::parse_quote!(minicbor::Decode::decode));
+                return quote! {
+                    match #decode_fn(__d777) {
+                        Ok(__v777) => {
+                            #place = std::borrow::Cow::Borrowed(__v777);
+                            __s777[#k] = true
+                        }
+                        Err(minicbor::decode::Error::UnknownVariant(..)) => { __d777.skip()? }
+                        Err(e) => return Err(#error)
+                    }
+                }
+            }
+            if let Some(f) = decode_fn {
+                quote!({ #place = #f(__d777)#map_err?; __s777[#k] = true })
+            } else {
+                quote!({
+                    minicbor::decode::DecodeInPlace::decode_into(&mut #place, __d777)#map_err?;
+                    __s777[#k] = true
+                })
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let type_str  = name.to_string();
+    let unknown   = attrs.deny_unknown_fields().then_some(type_str.as_str());
+    let iteration = gen_iteration(&fields, &actions, attrs.encoding().unwrap_or_default(), unknown);
+
+    let field_str = fields.idents.iter().map(|n| format!("{}::{}", name, n)).collect::<Vec<_>>();
+    let len       = fields.len();
+    let optional  = (0 .. len).zip(&places)
+        .filter(|(k, _)| is_option(&fields.types[*k], |_| true))
+        .map(|(k, p)| quote!(if !__s777[#k] { #p = None }));
+
+    let required = (0 .. len)
+        .filter(|k| !is_option(&fields.types[*k], |_| true))
+        .collect::<Vec<_>>();
+
+    let missing = if attrs.collect_missing() {
+        if required.len() > 64 {
+            let msg = "#[cbor(collect_missing)] supports at most 64 non-optional fields";
+            return Err(syn::Error::new(name.span(), msg))
+        }
+        let seen    = required.iter();
+        let indices = required.iter().map(|k| fields.indices[*k].val());
+        let strs    = required.iter().map(|k| &field_str[*k]);
+        let pos     = 0 .. required.len();
+        quote! {
+            let mut __m777 = minicbor::decode::MissingFields::new(&[#((#indices, #strs)),*]);
+            #(if !__s777[#seen] { __m777.insert(#pos) })*
+            if !__m777.is_empty() {
+                return Err(minicbor::decode::Error::MissingValues(__m777))
+            }
+        }
+    } else {
+        let seen    = required.iter();
+        let indices = required.iter().map(|k| &fields.indices[*k]);
+        let strs    = required.iter().map(|k| &field_str[*k]);
+        quote! {
+            #(if !__s777[#seen] {
+                return Err(minicbor::decode::Error::MissingValue(#indices, #strs))
+            })*
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics minicbor::decode::DecodeInPlace<'bytes> for #name #typ_generics #where_clause {
+            fn decode_into(&mut self, __d777: &mut minicbor::Decoder<'bytes>) -> core::result::Result<(), minicbor::decode::Error> {
+                #canonical
+                let mut __s777 = [false; #len];
+                #iteration
+                #(#optional)*
+                #missing
+                Ok(())
+            }
+        }
+    })
+}
+
+/// Create a `DecodeInPlace` impl for enums.
+//
+// Enum values are always replaced by a newly decoded value.
+fn on_enum_in_place(inp: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &inp.ident;
+    let g = add_lifetime(&inp.generics, gen_lifetime()?);
+    let (impl_generics, ..) = g.split_for_impl();
+    let (_, typ_generics, _) = inp.generics.split_for_impl();
+    let mut where_clause = inp.generics.where_clause.clone().unwrap_or_else(|| syn::parse_quote!(where));
+    where_clause.predicates.push(syn::parse_quote!(#name #typ_generics: minicbor::Decode<'bytes>));
+    Ok(quote! {
+        impl #impl_generics minicbor::decode::DecodeInPlace<'bytes> for #name #typ_generics #where_clause {}
+    })
+}
+
+/// Check that the item to decode is deterministically encoded, if required.
+fn gen_canonical_check(attrs: &Attributes) -> proc_macro2::TokenStream {
+    if attrs.require_canonical() {
+        quote!(__d777.probe().check_deterministic()?;)
+    } else {
+        quote!()
+    }
+}
+
+/// The error `e` of a field, mapped with its `map_err` function, if any.
+fn map_error(attrs: &Attributes) -> proc_macro2::TokenStream {
+    if let Some(f) = attrs.map_err() {
+        quote!(#f(e))
+    } else {
+        quote!(e)
+    }
+}
+
 fn gen_decode_bound() -> syn::Result<syn::TypeParamBound> {
     syn::parse_str("minicbor::Decode<'bytes>")
 }
@@ -1,6 +1,6 @@
 use crate::Mode;
-use crate::{add_bound_to_type_params, collect_type_params, is_option};
-use crate::attrs::{Attributes, CustomCodec, Encoding, Level};
+use crate::{add_bound_to_type_params, collect_type_params, enum_repr, is_option};
+use crate::attrs::{Attributes, CustomCodec, Encoding, Level, Repr};
 use crate::fields::Fields;
 use crate::variants::Variants;
 use quote::quote;
@@ -44,7 +44,7 @@ fn on_struct(inp: &mut syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream
     let blacklist = {
         let iter = data.fields.iter()
             .zip(&encode_fns)
-            .filter_map(|(f, ff)| ff.is_some().then(|| f));
+            .filter_map(|(f, ff)| ff.is_some().then_some(f));
         collect_type_params(&inp.generics, iter)
     };
 
@@ -94,6 +94,7 @@ fn on_enum(inp: &mut syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream>
     let enum_attrs    = Attributes::try_from_iter(Level::Enum, inp.attrs.iter())?;
     let enum_encoding = enum_attrs.encoding().unwrap_or_default();
     let index_only    = enum_attrs.index_only();
+    let repr          = enum_repr(name, &enum_attrs)?;
     let variants      = Variants::try_from(name.span(), data.variants.iter())?;
 
     let mut blacklist = HashSet::new();
@@ -109,11 +110,31 @@ fn on_enum(inp: &mut syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream>
         blacklist.extend({
             let iter = var.fields.iter()
                 .zip(&encode_fns)
-                .filter_map(|(f, ff)| ff.is_some().then(|| f));
+                .filter_map(|(f, ff)| ff.is_some().then_some(f));
             collect_type_params(&inp.generics, iter)
         });
         let con = &var.ident;
         let encoding = attrs.encoding().unwrap_or(enum_encoding);
+        let head = match repr {
+            Repr::Array => quote! {
+                __e777.array(2)?;
+                __e777.u32(#idx)?;
+            },
+            Repr::Map => quote! {
+                __e777.map(1)?;
+                __e777.u32(#idx)?;
+            },
+            Repr::Tag(base) => {
+                let tag = base.checked_add(idx.val().into()).ok_or_else(|| {
+                    let msg = "base tag plus variant index exceeds the max. tag number";
+                    syn::Error::new(var.ident.span(), msg)
+                })?;
+                let tag = proc_macro2::Literal::u64_suffixed(tag);
+                quote! {
+                    __e777.tag(minicbor::data::Tag::from(#tag))?;
+                }
+            }
+        };
         let row = match &var.fields {
             syn::Fields::Unit => match encoding {
                 Encoding::Array | Encoding::Map if index_only => quote! {
@@ -124,16 +145,14 @@ fn on_enum(inp: &mut syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream>
                 },
                 Encoding::Array => quote! {
                     #name::#con => {
-                        __e777.array(2)?;
-                        __e777.u32(#idx)?;
+                        #head
                         __e777.array(0)?;
                         Ok(())
                     }
                 },
                 Encoding::Map => quote! {
                     #name::#con => {
-                        __e777.array(2)?;
-                        __e777.u32(#idx)?;
+                        #head
                         __e777.map(0)?;
                         Ok(())
                     }
@@ -147,8 +166,7 @@ fn on_enum(inp: &mut syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream>
                 let Fields { idents, .. } = fields;
                 quote! {
                     #name::#con{#(#idents,)*} => {
-                        __e777.array(2)?;
-                        __e777.u32(#idx)?;
+                        #head
                         #statements
                     }
                 }
@@ -161,8 +179,7 @@ fn on_enum(inp: &mut syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream>
                 let Fields { idents, .. } = fields;
                 quote! {
                     #name::#con(#(#idents,)*) => {
-                        __e777.array(2)?;
-                        __e777.u32(#idx)?;
+                        #head
                         #statements
                     }
                 }
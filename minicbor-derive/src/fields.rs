@@ -14,6 +14,8 @@ pub struct Fields {
     pub is_name: Vec<bool>,
     /// CBOR indices of fields
     pub indices: Vec<Idx>,
+    /// additional CBOR indices accepted when decoding fields
+    pub aliases: Vec<Vec<u32>>,
     /// field types
     pub types: Vec<Type>,
     /// field attributes
@@ -27,6 +29,7 @@ impl Fields {
     {
         let mut pos     = Vec::new();
         let mut indices = Vec::new();
+        let mut aliases = Vec::new();
         let mut idents  = Vec::new();
         let mut is_name = Vec::new();
         let mut types   = Vec::new();
@@ -54,15 +57,31 @@ impl Fields {
         for (i, idx, ident, is, typ, attr) in sorted.into_iter() {
             pos.push(i);
             indices.push(idx);
+            aliases.push(attr.aliases().to_vec());
             idents.push(ident);
             is_name.push(is);
             types.push(typ);
             attrs.push(attr);
         }
 
-        idx::check_uniq(span, &indices)?;
+        let all = indices.iter().copied()
+            .chain(aliases.iter().flatten().map(|a| Idx::N(*a)))
+            .collect::<Vec<_>>();
 
-        Ok(Fields { pos, idents, is_name, indices, types, attrs })
+        idx::check_uniq(span, &all)?;
+
+        Ok(Fields { pos, idents, is_name, indices, aliases, types, attrs })
+    }
+
+    /// Match patterns of the indices a field is decoded from, i.e. its
+    /// index and aliases (if any).
+    pub fn patterns(&self) -> Vec<proc_macro2::TokenStream> {
+        self.indices.iter().zip(&self.aliases)
+            .map(|(i, a)| {
+                let a = a.iter().map(|a| proc_macro2::Literal::u32_unsuffixed(*a));
+                quote::quote!(#i #(| #a)*)
+            })
+            .collect()
     }
 
     pub fn len(&self) -> usize {
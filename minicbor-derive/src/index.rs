@@ -0,0 +1,66 @@
+use crate::variants::Variants;
+use quote::quote;
+use syn::spanned::Spanned;
+
+/// Entry point to derive `minicbor::CborIndex` on enums.
+pub fn derive_from(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    let result = match &input.data {
+        syn::Data::Enum(_)   => on_enum(&input),
+        syn::Data::Struct(s) => {
+            let msg = "deriving `minicbor::CborIndex` for a `struct` is not supported";
+            Err(syn::Error::new(s.struct_token.span(), msg))
+        }
+        syn::Data::Union(u) => {
+            let msg = "deriving `minicbor::CborIndex` for a `union` is not supported";
+            Err(syn::Error::new(u.union_token.span(), msg))
+        }
+    };
+    proc_macro::TokenStream::from(result.unwrap_or_else(|e| e.to_compile_error()))
+}
+
+/// Create a `CborIndex` impl and, if all variants are unit variants,
+/// a `FromCborIndex` impl for enums.
+fn on_enum(inp: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let data =
+        if let syn::Data::Enum(data) = &inp.data {
+            data
+        } else {
+            unreachable!("`derive_from` matched against `syn::Data::Enum`")
+        };
+
+    let name     = &inp.ident;
+    let variants = Variants::try_from(name.span(), data.variants.iter())?;
+    let idents   = data.variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+    let indices  = variants.indices.iter().map(|i| u64::from(i.val())).collect::<Vec<_>>();
+
+    let (impl_generics, typ_generics, where_clause) = inp.generics.split_for_impl();
+
+    let from_index =
+        if data.variants.iter().all(|v| matches!(v.fields, syn::Fields::Unit)) {
+            quote! {
+                impl #impl_generics minicbor::FromCborIndex for #name #typ_generics #where_clause {
+                    fn from_index(i: u64) -> Option<Self> {
+                        match i {
+                            #(#indices => Some(#name::#idents),)*
+                            _ => None
+                        }
+                    }
+                }
+            }
+        } else {
+            quote!()
+        };
+
+    Ok(quote! {
+        impl #impl_generics minicbor::CborIndex for #name #typ_generics #where_clause {
+            fn index(&self) -> u64 {
+                match *self {
+                    #(#name::#idents { .. } => #indices,)*
+                }
+            }
+        }
+
+        #from_index
+    })
+}
@@ -13,16 +13,16 @@
 //! In order to reach this goal, the encoding has the following characteristics:
 //!
 //! 1. The encoding does not contain any names, i.e. no field names, type names
-//! or variant names. Instead, every field and every constructor needs to be
-//! annotated with an (unsigned) index number, e.g. `#[n(1)]`.
+//!    or variant names. Instead, every field and every constructor needs to be
+//!    annotated with an (unsigned) index number, e.g. `#[n(1)]`.
 //!
 //! 2. Unknown fields are ignored during decoding.
 //!
 //! 3. Optional types default to `None` if their value is not present during
-//! decoding.
+//!    decoding.
 //!
 //! 4. Optional enums default to `None` if an unknown variant is encountered
-//! during decoding.
+//!    during decoding.
 //!
 //! Item **1** ensures that names can be changed freely without compatibility
 //! concerns. Item **2** ensures that new fields do not affect older software.
@@ -75,11 +75,11 @@
 //! - Renaming every identifier.
 //!
 //! - Adding optional fields to `Point`, `ConvexHull`, `State::Start` or
-//! `State::Search`.
+//!   `State::Search`.
 //!
 //! - Adding more variants to `State` *iff* `State` is only decoded as part of
-//! `ConvexHull`. Direct decoding of `State` would produce an `UnknownVariant`
-//! error for those new variants.
+//!   `ConvexHull`. Direct decoding of `State` would produce an `UnknownVariant`
+//!   error for those new variants.
 //!
 //! [1]: https://developers.google.com/protocol-buffers/
 //!
@@ -90,10 +90,18 @@
 //! - [`#[cbor(array)]`](#cborarray)
 //! - [`#[cbor(map)]`](#cbormap)
 //! - [`#[cbor(index_only)]`](#cborindex_only)
+//! - [`#[cbor(repr)]`](#cborrepr--)
+//! - [`#[cbor(accept_array)]`](#cboraccept_array)
 //! - [`#[cbor(transparent)]`](#cbortransparent)
+//! - [`#[cbor(deny_unknown_fields)]`](#cbordeny_unknown_fields)
+//! - [`#[cbor(collect_missing)]`](#cborcollect_missing)
+//! - [`#[cbor(require_canonical)]`](#cborrequire_canonical)
+//! - [`#[cbor(parser)]`](#cborparser)
+//! - [`#[cbor(heap)]`](#cborheap)
 //! - [`#[cbor(decode_with)]`](#cbordecode_with--path)
 //! - [`#[cbor(encode_with)]`](#cborencode_with--path)
 //! - [`#[cbor(with)]`](#cborwith--path)
+//! - [`#[cbor(map_err)]`](#cbormap_err--path)
 //! - [`#[cbor(decode_bound)]`](#cbordecode_bound--)
 //! - [`#[cbor(encode_bound)]`](#cborencode_bound--)
 //! - [`#[cbor(bound)]`](#cborbound)
@@ -106,10 +114,34 @@
 //! decoding input, whereas `n` produces non-borrowed values (but see section
 //! [Implicit borrowing](#implicit-borrowing) below). This means that if a type
 //! is annotated with `#[b(...)]`, all its lifetimes will be constrained to the
-//! input lifetime (`'bytes`). Further, if the type is a `std::borrow::Cow<'_, str>`
-//! or `std::borrow::Cow<'_, minicbor::bytes::ByteSlice>`, the generated code
-//! will decode the `str` or `ByteSlice` and construct a `Cow::Borrowed` variant,
-//! contrary to the regular `Cow` impl of `Decode` which produces owned values.
+//! input lifetime (`'bytes`). Further, if the type is a `std::borrow::Cow<'_, str>`,
+//! `std::borrow::Cow<'_, minicbor::bytes::ByteSlice>` or `std::borrow::Cow<'_, Path>`,
+//! the generated code will decode the `str`, `ByteSlice` or `Path` and construct
+//! a `Cow::Borrowed` variant, contrary to the regular `Cow` impl of `Decode`
+//! which produces owned values. The same holds for `std::borrow::Cow<'_, [u8]>`
+//! together with `#[cbor(with = "minicbor::bytes")]`.
+//!
+//! Fields may in addition declare one or more aliases, e.g. `#[n(1, alias = 7)]`.
+//! The generated decoder accepts the field under its index and every alias,
+//! whereas encoding always uses the index. This allows renumbering fields of
+//! long-lived protocols gradually, while still reading values produced with
+//! the old numbering. Aliases must be distinct from all other indices and
+//! aliases of the type.
+//!
+//! ```
+//! use minicbor::{Encode, Decode};
+//!
+//! #[derive(Debug, PartialEq, Encode, Decode)]
+//! #[cbor(map)]
+//! struct Config {
+//!     #[n(1, alias = 7)] timeout: u32
+//! }
+//!
+//! let old = [0xa1, 0x07, 0x18, 0x1e]; // {7: 30}
+//! assert_eq!(Config { timeout: 30 }, minicbor::decode(&old)?);
+//! assert_eq!([0xa1, 0x01, 0x18, 0x1e], minicbor::to_vec(Config { timeout: 30 })?.as_slice());
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
 //!
 //! ## `#[cbor(array)]`
 //!
@@ -135,6 +167,51 @@
 //! them. This changes the encoding to encode only the variant index (cf. section
 //! [CBOR encoding](#cbor-encoding) for details).
 //!
+//! ## `#[cbor(repr = "...")]`
+//!
+//! Enumerations may have this attribute attached to them to select how the
+//! variant index is combined with the variant value. The default,
+//! `repr = "array"`, is the 2-element array of variant index and value. With
+//! `repr = "map"` each variant is encoded as a 1-element map from variant
+//! index to value and with `repr(tag = <u64>)` the variant value is tagged
+//! with the given base tag plus the variant index (cf. section
+//! [CBOR encoding](#cbor-encoding) for details). This attribute can not be
+//! combined with [`#[cbor(index_only)]`](#cborindex_only).
+//!
+//! The base tag should be chosen such that no variant tag collides with a
+//! [registered tag][4], because other decoders would otherwise misinterpret
+//! the variant values, e.g. with a base tag of 0 the second variant would be
+//! tagged as an epoch-based date/time. Tags from 32768 upwards are assigned on
+//! a first come first served basis and are a reasonable choice if no tags
+//! have been registered for the enumeration.
+//!
+//! ## `#[cbor(accept_array)]`
+//!
+//! Enumerations with a `map` or `tag` [representation](#cborrepr--) may have
+//! this attribute attached to them. The derived `Decode` impl then accepts
+//! the default 2-element array encoding in addition to the selected one,
+//! whereas the derived `Encode` impl only produces the selected one. This
+//! allows migrating existing data to a new representation:
+//!
+//! ```
+//! use minicbor::{Decode, Encode};
+//!
+//! #[derive(Debug, PartialEq, Encode, Decode)]
+//! #[cbor(repr(tag = 32768), accept_array)]
+//! enum Shape {
+//!     #[n(0)] Circle(#[n(0)] u8),
+//!     #[n(1)] Square(#[n(0)] u8)
+//! }
+//!
+//! let legacy = minicbor::decode::<Shape>(&[0x82, 0x01, 0x81, 0x02])?;
+//! assert_eq!(Shape::Square(2), legacy);
+//! assert_eq!(vec![0xd9, 0x80, 0x01, 0x81, 0x02], minicbor::to_vec(&legacy)?);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [4]: https://www.iana.org/assignments/cbor-tags/cbor-tags.xhtml
+//!
 //! ## `#[cbor(transparent)]`
 //!
 //! This attribute can be attached to structs with exactly one field (aka newtypes).
@@ -142,6 +219,95 @@
 //! respective `encode` and `decode` calls to the inner type, i.e. the resulting
 //! CBOR representation will be identical to the one of the inner type.
 //!
+//! ## `#[cbor(deny_unknown_fields)]`
+//!
+//! By default, decoding skips over values whose index is not known to the
+//! type. With this attribute attached to a struct, enum or enum variant the
+//! generated `Decode` impl instead fails with an `UnknownIndex` error which
+//! names the index and the type. With array encoding, NULLs at unknown
+//! positions denote absent values and are still accepted. When used with an
+//! enum it applies to all its variants. Note that this gives up forward
+//! compatibility with encodings produced by newer versions of the type.
+//!
+//! ## `#[cbor(collect_missing)]`
+//!
+//! By default, decoding fails with a `MissingValue` error for the first
+//! non-optional field without a value. With this attribute attached to a
+//! struct, enum or enum variant all missing fields are collected and reported
+//! at once in a `MissingValues` error. When used with an enum it applies to
+//! all its variants. At most 64 non-optional fields are supported.
+//!
+//! ## `#[cbor(require_canonical)]`
+//!
+//! When attached to a struct or enum, decoding fails unless the whole CBOR
+//! item of the value, including nested and unknown fields, is in the
+//! deterministic encoding of RFC 8949, i.e. shortest arguments and floats,
+//! definite lengths and sorted, unique map keys. This is useful for values
+//! whose encoding is signed or hashed and applies regardless of how the
+//! surrounding data is decoded (cf. `minicbor::Decoder::check_deterministic`).
+//!
+//! ## `#[cbor(parser)]`
+//!
+//! When attached to a struct `Foo`, deriving `Decode` also generates a push
+//! parser `FooParser` with the same visibility. Instead of requiring the
+//! complete CBOR item up front, the parser accepts its bytes in arbitrary
+//! pieces, e.g. as they arrive from a non-blocking source, and decodes every
+//! field as soon as its bytes are complete. Only the bytes of the current
+//! field are buffered:
+//!
+//! ```
+//! use core::task::Poll;
+//! use minicbor::Decode;
+//!
+//! #[derive(Debug, PartialEq, Decode)]
+//! #[cbor(parser)]
+//! struct Point {
+//!     #[n(0)] x: i32,
+//!     #[n(1)] y: i32
+//! }
+//!
+//! let mut p = PointParser::new();
+//! assert!(p.feed(&[0x82, 0x20]).is_pending());
+//! assert!(matches!(p.feed(&[0x01]), Poll::Ready(Ok(Point { x: -1, y: 1 }))));
+//! ```
+//!
+//! `FooParser::feed` returns `Poll::Pending` until the value is complete.
+//! Any bytes beyond the end of the value are retained for the next one, so
+//! that a sequence of values can be parsed. Since the input is transient,
+//! the struct must not have lifetime parameters and its fields must be
+//! decodable from any lifetime. The attribute can not be combined with
+//! `#[cbor(transparent)]` or `#[cbor(require_canonical)]`. The parser
+//! requires `minicbor`'s feature `"alloc"` (cf. `minicbor::decode::Feed`).
+//!
+//! ## `#[cbor(heap)]`
+//!
+//! By default, the derived `Decode` impl of a struct holds every field value
+//! in a local `Option` variable until all fields have been decoded. For
+//! structs with very many or very large fields this results in large stack
+//! frames. With `#[cbor(heap)]` every field value is instead moved into its
+//! own heap allocation as soon as it has been decoded, so that while decoding
+//! the stack frame holds only a pointer per field instead of every value:
+//!
+//! ```
+//! use minicbor::{Encode, Decode};
+//!
+//! #[derive(Debug, PartialEq, Encode, Decode)]
+//! #[cbor(map, heap)]
+//! struct Settings {
+//!     #[n(0)] name: String,
+//!     #[n(1)] limits: [u64; 8],
+//!     #[n(2)] comment: Option<String>
+//!     // ...
+//! }
+//!
+//! let s = Settings { name: "x".into(), limits: [1; 8], comment: None };
+//! assert_eq!(s, minicbor::decode(&minicbor::to_vec(&s)?)?);
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! The encoding is not affected. The attribute can not be combined with
+//! `#[cbor(transparent)]` and requires `minicbor`'s feature `"alloc"`.
+//!
 //! ## `#[cbor(decode_with = "<path>")]`
 //!
 //! When applied to a field of type `T`, the function denoted by `<path>` will be
@@ -175,6 +341,41 @@
 //! a module that contains functions named `encode` and `decode` that satisfy the
 //! respective type signatures mentioned in `encode_with` and `decode_with`.
 //!
+//! ## `#[cbor(map_err = "<path>")]`
+//!
+//! When applied to a field, the function denoted by `<path>` is applied to
+//! any error which occurs while decoding the field value, e.g. to replace
+//! the error with a more specific one. Errors about missing or unknown fields
+//! are not affected. The function needs to be equivalent to the following
+//! type:
+//!
+//! ```no_run
+//! use minicbor::decode::Error;
+//!
+//! fn map_err(e: Error) -> Error {
+//!     todo!()
+//! }
+//! ```
+//!
+//! For example:
+//!
+//! ```
+//! use minicbor::Decode;
+//! use minicbor::decode::Error;
+//!
+//! #[derive(Debug, Decode)]
+//! struct Config {
+//!     #[cbor(n(0), map_err = "bad_port")] port: u16
+//! }
+//!
+//! fn bad_port(_: Error) -> Error {
+//!     Error::Message("`Config::port` must be a number between 0 and 65535")
+//! }
+//!
+//! let e = minicbor::decode::<Config>(b"\x81\x1a\x00\x01\x00\x00").unwrap_err();
+//! assert!(e.to_string().contains("`Config::port`"));
+//! ```
+//!
 //! ## `#[cbor(decode_bound = "...")]`
 //!
 //! When applied to a generic field, this attribute overrides any implicit type
@@ -236,6 +437,145 @@
 //! }
 //! ```
 //!
+//! # Decoding in place
+//!
+//! Structs can also derive `minicbor::decode::DecodeInPlace`, which decodes
+//! every field into the existing value, e.g. to reuse the capacity of vectors
+//! and strings when decoding messages repeatedly. The struct must implement
+//! `Decode` and every field type must implement `DecodeInPlace`, unless the
+//! field has a custom decode function, in which case it is replaced with the
+//! decoded value. Absent optional fields are set to `None`. Derived enum impls
+//! always replace the whole value.
+//!
+//! ```
+//! use minicbor::{Encode, Decode, DecodeInPlace};
+//! use minicbor::decode::DecodeInPlace as _;
+//!
+//! #[derive(Encode, Decode, DecodeInPlace)]
+//! struct Message {
+//!     #[n(0)] id: u64,
+//!     #[n(1)] tags: Vec<String>,
+//!     #[n(2)] note: Option<String>
+//! }
+//!
+//! let mut m = Message { id: 0, tags: Vec::new(), note: None };
+//! let cbor = minicbor::to_vec(Message { id: 1, tags: vec!["a".into()], note: None })?;
+//! m.decode_into(&mut minicbor::Decoder::new(&cbor))?;
+//! assert_eq!(1, m.id);
+//! assert_eq!(["a"], m.tags.as_slice());
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! # CDDL schemas
+//!
+//! Deriving `minicbor::schema::Schema` describes the CBOR produced by the
+//! derived `Encode` impl in [CDDL][2]. Structs and enums become named rules,
+//! except for generic types which are described in place, and enum variants
+//! with fields get rules of the form `Enum-Variant`. Field names are kept as
+//! array entry labels or as comments on map entries. Fields with a custom
+//! encode function are of type `any` since their encoding is not known.
+//!
+//! ```
+//! use minicbor::{Encode, Schema};
+//!
+//! #[derive(Encode, Schema)]
+//! enum Command {
+//!     #[n(0)] Stop,
+//!     #[n(1)] Move { #[n(0)] x: i32, #[n(1)] y: Option<i32> }
+//! }
+//!
+//! let expected = "\
+//! Command = [0, []]
+//!   / [1, Command-Move]
+//!
+//! Command-Move = [
+//!   x: int,
+//!   ? (
+//!     y: int / null,
+//!   ),
+//! ]
+//! ";
+//!
+//! assert_eq!(expected, minicbor::schema::cddl::<Command>());
+//! ```
+//!
+//! [2]: https://www.rfc-editor.org/rfc/rfc8610.html
+//!
+//! # Size profiles
+//!
+//! Deriving `minicbor::sizes::Describe` records which array positions or map
+//! keys the fields and enum variants of a type occupy, so that
+//! `minicbor::sizes::profile` can report how many bytes each of them adds to
+//! an encoded value. Fields with a custom encode function are treated as
+//! opaque.
+//!
+//! ```
+//! use minicbor::{Encode, Describe};
+//!
+//! #[derive(Encode, Describe)]
+//! enum Command {
+//!     #[n(0)] Stop,
+//!     #[n(1)] Say { #[n(0)] text: String }
+//! }
+//!
+//! let cbor = minicbor::to_vec(Command::Say { text: "hello".into() })?;
+//! let report = minicbor::sizes::profile::<Command>(&cbor)?;
+//! let say = report.get("Say").unwrap();
+//!
+//! assert_eq!(9, say.bytes());
+//! assert_eq!(6, say.get("text").unwrap().bytes());
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! # Variant indices
+//!
+//! Deriving `minicbor::CborIndex` for an enum gives access to the index of
+//! the variant a value would be encoded with, e.g. to dispatch on or count
+//! messages without encoding them. If the enum has only unit variants,
+//! `minicbor::FromCborIndex` is implemented as well to construct a variant
+//! from its index.
+//!
+//! ```
+//! use minicbor::{CborIndex, FromCborIndex};
+//!
+//! #[derive(Debug, PartialEq, CborIndex)]
+//! enum Level {
+//!     #[n(0)] Low,
+//!     #[n(5)] High
+//! }
+//!
+//! #[derive(CborIndex)]
+//! enum Event {
+//!     #[n(1)] Start,
+//!     #[n(2)] Alarm(#[n(0)] Level)
+//! }
+//!
+//! assert_eq!(5, Level::High.index());
+//! assert_eq!(2, Event::Alarm(Level::Low).index());
+//! assert_eq!(Some(Level::High), Level::from_index(5));
+//! assert_eq!(None, Level::from_index(1));
+//! ```
+//!
+//! # Arbitrary values
+//!
+//! With feature `"arbitrary"` (enabled by feature `"derive-arbitrary"` of
+//! minicbor), `#[derive(minicbor::Arbitrary)]` implements `Arbitrary` of the
+//! [`arbitrary`][3] crate, re-exported as `minicbor::ext::arbitrary::Arbitrary`,
+//! for structs and enums which derive `minicbor::Decode`. Deriving `Decode`
+//! alone never implements `Arbitrary`, so types which derive or implement it
+//! otherwise are not affected by the feature.
+//!
+//! Values are built from arbitrary field values, enum values from arbitrary
+//! variants, so that every generated value can be encoded and decoded again,
+//! provided that custom encode and decode functions support all values of
+//! their field type. The impl requires every field type to implement
+//! `Arbitrary` and is unusable otherwise. Types may refer to themselves,
+//! e.g. in a `Box`, but mutually recursive types can not use the impl.
+//!
+//! [3]: https://docs.rs/arbitrary
+//!
 //! # CBOR encoding
 //!
 //! The CBOR values produced by a derived `Encode` implementation are of the
@@ -283,12 +623,16 @@
 //! enums without any fields, each enum variant is encoded as a two-element
 //! array. The first element is the variant index and the second the actual
 //! variant value. Otherwise, if enums do not have fields and the `index_only`
-//! attribute is present, only the variant index is encoded:
+//! attribute is present, only the variant index is encoded. The
+//! [`#[cbor(repr)]`](#cborrepr--) attribute selects a 1-element map or a tag
+//! instead of the two-element array:
 //!
 //! ```text
 //! <<enum encoding>> =
 //!     | `array(2)` n <<struct-as-array encoding>> ; if #[cbor(array)]
 //!     | `array(2)` n <<struct-as-map encoding>>   ; if #[cbor(map)]
+//!     | `map(1)` n <<struct encoding>>            ; if #[cbor(repr = "map")]
+//!     | `tag(t + n)` <<struct encoding>>          ; if #[cbor(repr(tag = t))]
 //!     | n                                         ; if #[cbor(index_only)]
 //! ```
 //!
@@ -303,22 +647,27 @@
 //! should be encoded:
 //!
 //! - *Dense types* are types which contain only few `Option`s or their `Option`s
-//! are assumed to be `Some`s usually. They are best encoded as arrays.
+//!   are assumed to be `Some`s usually. They are best encoded as arrays.
 //!
 //! - *Sparse types* are types with many `Option`s and their `Option`s are usually
-//! `None`s. They are best encoded as maps.
+//!   `None`s. They are best encoded as maps.
 //!
 //! When selecting the encoding, future changes to the type should be considered
 //! as they may turn a dense type into a sparse one over time. This also applies
 //! to [`#[cbor(index_only)]`](#cborindex_only) which should be used only with
 //! enums which are not expected to ever have fields in their variants.
 
-#![allow(clippy::many_single_char_names)]
 
 extern crate proc_macro;
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
 mod decode;
 mod encode;
+mod index;
+mod parser;
+mod schema;
+mod sizes;
 
 pub(crate) mod attrs;
 pub(crate) mod fields;
@@ -335,6 +684,14 @@ pub fn derive_decode(input: proc_macro::TokenStream) -> proc_macro::TokenStream
     decode::derive_from(input)
 }
 
+/// Derive the `minicbor::decode::DecodeInPlace` trait for a struct or enum.
+///
+/// See the [crate] documentation for details.
+#[proc_macro_derive(DecodeInPlace, attributes(n, b, cbor))]
+pub fn derive_decode_in_place(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    decode::derive_in_place_from(input)
+}
+
 /// Derive the `minicbor::Encode` trait for a struct or enum.
 ///
 /// See the [crate] documentation for details.
@@ -343,6 +700,39 @@ pub fn derive_encode(input: proc_macro::TokenStream) -> proc_macro::TokenStream
     encode::derive_from(input)
 }
 
+/// Derive the `minicbor::schema::Schema` trait for a struct or enum.
+///
+/// See the [crate] documentation for details.
+#[proc_macro_derive(Schema, attributes(n, b, cbor))]
+pub fn derive_schema(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    schema::derive_from(input)
+}
+
+/// Derive the `minicbor::sizes::Describe` trait for a struct or enum.
+///
+/// See the [crate] documentation for details.
+#[proc_macro_derive(Describe, attributes(n, b, cbor))]
+pub fn derive_describe(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    sizes::derive_from(input)
+}
+
+/// Derive the `minicbor::ext::arbitrary::Arbitrary` trait for a struct or enum.
+///
+/// See the [crate] documentation for details.
+#[cfg(feature = "arbitrary")]
+#[proc_macro_derive(Arbitrary, attributes(n, b, cbor))]
+pub fn derive_arbitrary(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    arbitrary::derive_from(input)
+}
+
+/// Derive the `minicbor::CborIndex` trait for an enum.
+///
+/// See the [crate] documentation for details.
+#[proc_macro_derive(CborIndex, attributes(n, b, cbor))]
+pub fn derive_cbor_index(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    index::derive_from(input)
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 enum Mode {
     Encode,
@@ -398,6 +788,15 @@ fn is_str(ty: &syn::Type) -> bool {
     }
 }
 
+/// Check if the given type is a `Path`.
+fn is_path(ty: &syn::Type) -> bool {
+    if let syn::Type::Path(t) = ty {
+        t.qself.is_none() && t.path.segments.last().map(|s| s.ident == "Path").unwrap_or(false)
+    } else {
+        false
+    }
+}
+
 /// Check if the given type is a `&[u8]`.
 fn is_byte_slice(ty: &syn::Type) -> bool {
     if let syn::Type::Path(t) = ty {
@@ -422,6 +821,17 @@ fn is_byte_slice(ty: &syn::Type) -> bool {
     }
 }
 
+/// Get the representation of enum variants and check its compatibility
+/// with other enum attributes.
+fn enum_repr(name: &syn::Ident, attrs: &attrs::Attributes) -> syn::Result<attrs::Repr> {
+    let repr = attrs.repr().unwrap_or_default();
+    if repr != attrs::Repr::Array && attrs.index_only() {
+        let msg = "#[cbor(repr)] can not be combined with #[cbor(index_only)]";
+        return Err(syn::Error::new(name.span(), msg))
+    }
+    Ok(repr)
+}
+
 /// Traverse all field types and collect all type parameters along the way.
 fn collect_type_params<'a, I>(all: &syn::Generics, fields: I) -> HashSet<syn::TypeParam>
 where
@@ -0,0 +1,168 @@
+use crate::attrs::{Attributes, CustomCodec, Encoding, Level};
+use crate::decode::{gen_actions, gen_constructor, gen_inits, gen_missing_check};
+use crate::fields::Fields;
+use quote::{format_ident, quote};
+
+/// Create a push parser for structs with a `#[cbor(parser)]` attribute.
+//
+// For a struct `Foo` a type `FooParser` is generated, which holds a
+// `minicbor::decode::Feed` and a tuple of field variables (cf.
+// `gen_statements` in `decode`). Every time new bytes are fed, all
+// complete elements of the feed are decoded into the field variables.
+// Once the feed is done, the field variables are taken out and the value
+// is constructed just like `Decode` would. The parser is then ready for
+// the next value.
+pub fn derive_from(inp: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let data = match &inp.data {
+        syn::Data::Struct(data) => data,
+        syn::Data::Enum(_) | syn::Data::Union(_) => return Ok(quote!())
+    };
+
+    let name  = &inp.ident;
+    let attrs = Attributes::try_from_iter(Level::Struct, inp.attrs.iter())?;
+
+    if !attrs.parser() {
+        return Ok(quote!())
+    }
+
+    if attrs.transparent() || attrs.require_canonical() {
+        let msg = "#[cbor(parser)] can not be combined with #[cbor(transparent)] or #[cbor(require_canonical)]";
+        return Err(syn::Error::new(name.span(), msg))
+    }
+
+    if let Some(l) = inp.generics.lifetimes().next() {
+        let msg = "#[cbor(parser)] does not support types with lifetime parameters";
+        return Err(syn::Error::new(l.lifetime.span(), msg))
+    }
+
+    let fields = Fields::try_from(name.span(), data.fields.iter())?;
+
+    let decode_fns: Vec<Option<CustomCodec>> = fields.attrs.iter()
+        .map(|a| a.codec().cloned().filter(CustomCodec::is_decode))
+        .collect();
+
+    let places  = fields.idents.iter().map(|n| quote!(*#n)).collect::<Vec<_>>();
+    let actions = gen_actions(&fields, &decode_fns, &places, false)?;
+    let inits   = gen_inits(&fields);
+
+    let type_str  = name.to_string();
+    let field_str = fields.idents.iter().map(|n| format!("{}::{}", name, n)).collect::<Vec<_>>();
+
+    let (feed, skip) = match attrs.encoding().unwrap_or_default() {
+        Encoding::Array => {
+            let skip = if attrs.deny_unknown_fields() {
+                quote! {
+                    if minicbor::data::Type::Null != __d777.datatype()? {
+                        return Err(minicbor::decode::Error::UnknownIndex(__i777, #type_str))
+                    }
+                }
+            } else {
+                quote!({})
+            };
+            (quote!(minicbor::decode::Feed::array()), skip)
+        }
+        Encoding::Map => {
+            let skip = if attrs.deny_unknown_fields() {
+                quote!(return Err(minicbor::decode::Error::UnknownIndex(__i777, #type_str)))
+            } else {
+                quote!({})
+            };
+            (quote!(minicbor::decode::Feed::map()), skip)
+        }
+    };
+
+    let missing = if attrs.collect_missing() {
+        gen_missing_check(name.span(), &fields, &field_str)?
+    } else {
+        quote!()
+    };
+
+    let values  = fields.idents.iter().map(|n| quote!(#n)).collect::<Vec<_>>();
+    let result  = gen_constructor(name, &data.fields, &fields, &field_str, &values);
+    let vis     = &inp.vis;
+    let parser  = format_ident!("{}Parser", name);
+    let doc     = format!("A push parser of [`{}`] values (cf. `#[cbor(parser)]`).", name);
+    let params  = &inp.generics;
+    let patterns = fields.patterns();
+    let idents  = &fields.idents;
+    let types   = &fields.types;
+
+    let mut where_clause = inp.generics.where_clause.clone().unwrap_or_else(|| syn::parse_quote!(where));
+    for (ty, ff) in types.iter().zip(&decode_fns) {
+        if ff.is_none() {
+            where_clause.predicates.push(syn::parse_quote!(#ty: for<'__p777> minicbor::Decode<'__p777>))
+        }
+    }
+
+    let (impl_generics, typ_generics, struct_where) = inp.generics.split_for_impl();
+
+    Ok(quote! {
+        #[doc = #doc]
+        #vis struct #parser #params #struct_where {
+            __feed777: minicbor::decode::Feed,
+            __vals777: (#(core::option::Option<#types>,)*)
+        }
+
+        impl #impl_generics #parser #typ_generics #where_clause {
+            /// Create a new parser.
+            #vis fn new() -> Self {
+                #parser {
+                    __feed777: #feed,
+                    __vals777: (#(#inits,)*)
+                }
+            }
+
+            /// Feed the next bytes of the CBOR encoding to this parser.
+            ///
+            /// Returns the decoded value once it is complete. Bytes beyond the
+            /// end of the value are retained and used for the next value, which
+            /// can be obtained by feeding more or no bytes. After an error, the
+            /// parser should no longer be used.
+            #vis fn feed(&mut self, __b777: &[u8]) -> core::task::Poll<core::result::Result<#name #typ_generics, minicbor::decode::Error>> {
+                self.__feed777.push(__b777);
+                match self.__run777() {
+                    Ok(true)  => core::task::Poll::Ready(self.__finish777()),
+                    Ok(false) => core::task::Poll::Pending,
+                    Err(e)    => core::task::Poll::Ready(Err(e))
+                }
+            }
+
+            /// Get the bytes which have been fed but not consumed yet.
+            #vis fn remaining(&self) -> &[u8] {
+                self.__feed777.remaining()
+            }
+
+            fn __run777(&mut self) -> core::result::Result<bool, minicbor::decode::Error> {
+                let (#(#idents,)*) = &mut self.__vals777;
+                loop {
+                    let __i777 = match self.__feed777.poll_element() {
+                        core::task::Poll::Pending   => return Ok(false),
+                        core::task::Poll::Ready(i)  => i?
+                    };
+                    let __i777 = if let Some(i) = __i777 { i } else { return Ok(true) };
+                    {
+                        let __d777 = &mut self.__feed777.value();
+                        match __i777 {
+                            #(#patterns => #actions)*
+                            _          => #skip
+                        }
+                    }
+                    self.__feed777.consume()
+                }
+            }
+
+            fn __finish777(&mut self) -> core::result::Result<#name #typ_generics, minicbor::decode::Error> {
+                self.__feed777.reset();
+                let (#(#idents,)*) = core::mem::replace(&mut self.__vals777, (#(#inits,)*));
+                #missing
+                #result
+            }
+        }
+
+        impl #impl_generics core::default::Default for #parser #typ_generics #where_clause {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    })
+}
@@ -0,0 +1,221 @@
+use crate::{add_bound_to_type_params, collect_type_params, is_option, Mode};
+use crate::attrs::{Attributes, CustomCodec, Encoding, Level, Repr};
+use crate::fields::Fields;
+use crate::variants::Variants;
+use quote::quote;
+use std::collections::HashSet;
+use syn::spanned::Spanned;
+
+/// Entry point to derive `minicbor::schema::Schema` on structs and enums.
+pub fn derive_from(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut input = syn::parse_macro_input!(input as syn::DeriveInput);
+    let result = match &input.data {
+        syn::Data::Struct(_) => on_struct(&mut input),
+        syn::Data::Enum(_)   => on_enum(&mut input),
+        syn::Data::Union(u)  => {
+            let msg = "deriving `minicbor::schema::Schema` for a `union` is not supported";
+            Err(syn::Error::new(u.union_token.span(), msg))
+        }
+    };
+    proc_macro::TokenStream::from(result.unwrap_or_else(|e| e.to_compile_error()))
+}
+
+/// Create a `Schema` impl for (tuple) structs.
+fn on_struct(inp: &mut syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let data =
+        if let syn::Data::Struct(data) = &inp.data {
+            data
+        } else {
+            unreachable!("`derive_from` matched against `syn::Data::Struct`")
+        };
+
+    let name     = &inp.ident;
+    let attrs    = Attributes::try_from_iter(Level::Struct, inp.attrs.iter())?;
+    let encoding = attrs.encoding().unwrap_or_default();
+    let fields   = Fields::try_from(name.span(), data.fields.iter())?;
+    let generic  = inp.generics.type_params().next().is_some();
+
+    let blacklist = {
+        let iter = data.fields.iter()
+            .zip(&fields.attrs)
+            .filter_map(|(f, a)| has_encode_fn(a).then_some(f));
+        collect_type_params(&inp.generics, iter)
+    };
+
+    let body =
+        if attrs.transparent() {
+            if fields.len() != 1 {
+                let msg = "#[cbor(transparent)] requires a struct with one field";
+                return Err(syn::Error::new(inp.ident.span(), msg))
+            }
+            field_type(&fields.types[0], &fields.attrs[0])
+        } else {
+            let def = gen_fields(&fields, encoding);
+            make_rule(&name.to_string(), generic, def)
+        };
+
+    {
+        let bound  = gen_schema_bound()?;
+        let params = inp.generics.type_params_mut();
+        add_bound_to_type_params(bound, params, &blacklist, &[], Mode::Encode);
+    }
+
+    let (impl_generics, typ_generics, where_clause) = inp.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics minicbor::schema::Schema for #name #typ_generics #where_clause {
+            fn schema(__g777: &mut minicbor::schema::Generator) -> minicbor::schema::String {
+                #body
+            }
+        }
+    })
+}
+
+/// Create a `Schema` impl for enums.
+fn on_enum(inp: &mut syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let data =
+        if let syn::Data::Enum(data) = &inp.data {
+            data
+        } else {
+            unreachable!("`derive_from` matched against `syn::Data::Enum`")
+        };
+
+    let name          = &inp.ident;
+    let enum_attrs    = Attributes::try_from_iter(Level::Enum, inp.attrs.iter())?;
+    let enum_encoding = enum_attrs.encoding().unwrap_or_default();
+    let index_only    = enum_attrs.index_only();
+    let variants      = Variants::try_from(name.span(), data.variants.iter())?;
+    let generic       = inp.generics.type_params().next().is_some();
+
+    if matches!(enum_attrs.repr(), Some(Repr::Map | Repr::Tag(_))) {
+        let msg = "deriving `minicbor::schema::Schema` for an enum with #[cbor(repr)] is not supported";
+        return Err(syn::Error::new(name.span(), msg))
+    }
+
+    if data.variants.is_empty() {
+        let msg = "deriving `minicbor::schema::Schema` for an empty enum is not supported";
+        return Err(syn::Error::new(name.span(), msg))
+    }
+
+    let mut blacklist = HashSet::new();
+    let mut alternatives = Vec::new();
+    for ((var, idx), attrs) in data.variants.iter().zip(variants.indices.iter()).zip(&variants.attrs) {
+        let fields = Fields::try_from(var.ident.span(), var.fields.iter())?;
+        blacklist.extend({
+            let iter = var.fields.iter()
+                .zip(&fields.attrs)
+                .filter_map(|(f, a)| has_encode_fn(a).then_some(f));
+            collect_type_params(&inp.generics, iter)
+        });
+        let encoding = attrs.encoding().unwrap_or(enum_encoding);
+        let alt = match &var.fields {
+            syn::Fields::Unit if index_only => {
+                let s = idx.val().to_string();
+                quote!(minicbor::schema::String::from(#s))
+            }
+            syn::Fields::Unit => {
+                let s = match encoding {
+                    Encoding::Array => format!("[{}, []]", idx.val()),
+                    Encoding::Map   => format!("[{}, {{}}]", idx.val())
+                };
+                quote!(minicbor::schema::String::from(#s))
+            }
+            f @ (syn::Fields::Named(_) | syn::Fields::Unnamed(_)) if index_only => {
+                return Err(syn::Error::new(f.span(), "index_only enums must not have fields"))
+            }
+            syn::Fields::Named(_) | syn::Fields::Unnamed(_) => {
+                let def  = gen_fields(&fields, encoding);
+                let rule = make_rule(&format!("{}-{}", name, var.ident), generic, def);
+                quote!(minicbor::schema::variant(#idx, #rule))
+            }
+        };
+        alternatives.push(alt)
+    }
+
+    let body = make_rule(&name.to_string(), generic, quote! {
+        minicbor::schema::choice([#(#alternatives),*])
+    });
+
+    {
+        let bound  = gen_schema_bound()?;
+        let params = inp.generics.type_params_mut();
+        add_bound_to_type_params(bound, params, &blacklist, &[], Mode::Encode);
+    }
+
+    let (impl_generics, typ_generics, where_clause) = inp.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics minicbor::schema::Schema for #name #typ_generics #where_clause {
+            fn schema(__g777: &mut minicbor::schema::Generator) -> minicbor::schema::String {
+                #body
+            }
+        }
+    })
+}
+
+/// Generate the CDDL type of fields in the given encoding.
+///
+/// NB: The `fields` parameter is assumed to be sorted by index.
+fn gen_fields(fields: &Fields, encoding: Encoding) -> proc_macro2::TokenStream {
+    let mut items = Vec::new();
+    for (((idx, ident), (&is_name, typ)), attrs) in fields.indices.iter()
+        .zip(&fields.idents)
+        .zip(fields.is_name.iter().zip(&fields.types))
+        .zip(&fields.attrs)
+    {
+        let mut inner = None;
+        let item =
+            if is_option(typ, |t| { inner = Some(t.clone()); true }) {
+                let ty = field_type(inner.as_ref().expect("option has inner type"), attrs);
+                quote!(minicbor::schema::Field::new(#idx, #ty).optional())
+            } else {
+                let ty = field_type(typ, attrs);
+                quote!(minicbor::schema::Field::new(#idx, #ty))
+            };
+        if is_name {
+            let n = ident.to_string();
+            let n = n.trim_start_matches("r#");
+            items.push(quote!(#item.with_name(#n)))
+        } else {
+            items.push(item)
+        }
+    }
+    match encoding {
+        Encoding::Array => quote!(minicbor::schema::array(&[#(#items),*])),
+        Encoding::Map   => quote!(minicbor::schema::map(&[#(#items),*]))
+    }
+}
+
+/// Generate the CDDL type of a single field.
+///
+/// Fields with custom encode functions have an unknown type, unless they
+/// are encoded with `minicbor::bytes`.
+fn field_type(typ: &syn::Type, attrs: &Attributes) -> proc_macro2::TokenStream {
+    match attrs.codec() {
+        Some(CustomCodec::Module(p)) if quote!(#p).to_string() == "minicbor :: bytes" => {
+            quote!(minicbor::schema::String::from("bstr"))
+        }
+        Some(c) if c.is_encode() => quote!(minicbor::schema::String::from("any")),
+        _ => quote!(<#typ as minicbor::schema::Schema>::schema(__g777))
+    }
+}
+
+/// Wrap a definition into a named rule.
+///
+/// Generic types have no single definition, so their definition is used
+/// in place instead.
+fn make_rule(name: &str, generic: bool, def: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    if generic {
+        def
+    } else {
+        quote!(__g777.rule(#name, |__g777| #def))
+    }
+}
+
+fn has_encode_fn(attrs: &Attributes) -> bool {
+    attrs.codec().map(CustomCodec::is_encode).unwrap_or(false)
+}
+
+fn gen_schema_bound() -> syn::Result<syn::TypeParamBound> {
+    syn::parse_str("minicbor::schema::Schema")
+}
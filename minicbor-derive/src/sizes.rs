@@ -0,0 +1,184 @@
+use crate::{add_bound_to_type_params, collect_type_params, Mode};
+use crate::attrs::{Attributes, CustomCodec, Encoding, Level, Repr};
+use crate::fields::Fields;
+use crate::variants::Variants;
+use quote::quote;
+use std::collections::HashSet;
+use syn::spanned::Spanned;
+
+/// Entry point to derive `minicbor::sizes::Describe` on structs and enums.
+pub fn derive_from(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut input = syn::parse_macro_input!(input as syn::DeriveInput);
+    let result = match &input.data {
+        syn::Data::Struct(_) => on_struct(&mut input),
+        syn::Data::Enum(_)   => on_enum(&mut input),
+        syn::Data::Union(u)  => {
+            let msg = "deriving `minicbor::sizes::Describe` for a `union` is not supported";
+            Err(syn::Error::new(u.union_token.span(), msg))
+        }
+    };
+    proc_macro::TokenStream::from(result.unwrap_or_else(|e| e.to_compile_error()))
+}
+
+/// Create a `Describe` impl for (tuple) structs.
+fn on_struct(inp: &mut syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let data =
+        if let syn::Data::Struct(data) = &inp.data {
+            data
+        } else {
+            unreachable!("`derive_from` matched against `syn::Data::Struct`")
+        };
+
+    let name     = &inp.ident;
+    let attrs    = Attributes::try_from_iter(Level::Struct, inp.attrs.iter())?;
+    let encoding = attrs.encoding().unwrap_or_default();
+    let fields   = Fields::try_from(name.span(), data.fields.iter())?;
+
+    let blacklist = {
+        let iter = data.fields.iter()
+            .zip(&fields.attrs)
+            .filter_map(|(f, a)| has_encode_fn(a).then_some(f));
+        collect_type_params(&inp.generics, iter)
+    };
+
+    let body =
+        if attrs.transparent() {
+            if fields.len() != 1 {
+                let msg = "#[cbor(transparent)] requires a struct with one field";
+                return Err(syn::Error::new(inp.ident.span(), msg))
+            }
+            let shape = field_shape(&fields.types[0], &fields.attrs[0]);
+            quote!((#shape)())
+        } else {
+            gen_fields(&fields, encoding)
+        };
+
+    {
+        let bound  = gen_describe_bound()?;
+        let params = inp.generics.type_params_mut();
+        add_bound_to_type_params(bound, params, &blacklist, &[], Mode::Encode);
+    }
+
+    let (impl_generics, typ_generics, where_clause) = inp.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics minicbor::sizes::Describe for #name #typ_generics #where_clause {
+            fn describe() -> minicbor::sizes::Shape {
+                #body
+            }
+        }
+    })
+}
+
+/// Create a `Describe` impl for enums.
+fn on_enum(inp: &mut syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let data =
+        if let syn::Data::Enum(data) = &inp.data {
+            data
+        } else {
+            unreachable!("`derive_from` matched against `syn::Data::Enum`")
+        };
+
+    let name          = &inp.ident;
+    let enum_attrs    = Attributes::try_from_iter(Level::Enum, inp.attrs.iter())?;
+    let enum_encoding = enum_attrs.encoding().unwrap_or_default();
+    let index_only    = enum_attrs.index_only();
+    let variants      = Variants::try_from(name.span(), data.variants.iter())?;
+
+    if matches!(enum_attrs.repr(), Some(Repr::Map | Repr::Tag(_))) {
+        let msg = "deriving `minicbor::sizes::Describe` for an enum with #[cbor(repr)] is not supported";
+        return Err(syn::Error::new(name.span(), msg))
+    }
+
+    let mut blacklist = HashSet::new();
+    let mut items = Vec::new();
+    for ((var, idx), attrs) in data.variants.iter().zip(variants.indices.iter()).zip(&variants.attrs) {
+        let fields = Fields::try_from(var.ident.span(), var.fields.iter())?;
+        blacklist.extend({
+            let iter = var.fields.iter()
+                .zip(&fields.attrs)
+                .filter_map(|(f, a)| has_encode_fn(a).then_some(f));
+            collect_type_params(&inp.generics, iter)
+        });
+        let encoding = attrs.encoding().unwrap_or(enum_encoding);
+        let shape = match &var.fields {
+            syn::Fields::Unit => quote!(|| minicbor::sizes::Shape::Opaque),
+            f @ (syn::Fields::Named(_) | syn::Fields::Unnamed(_)) if index_only => {
+                return Err(syn::Error::new(f.span(), "index_only enums must not have fields"))
+            }
+            syn::Fields::Named(_) | syn::Fields::Unnamed(_) => {
+                let def = gen_fields(&fields, encoding);
+                quote!(|| #def)
+            }
+        };
+        let n = var.ident.to_string();
+        let n = n.trim_start_matches("r#");
+        items.push(quote!(minicbor::sizes::Field::new(#idx, #shape).with_name(#n)))
+    }
+
+    let body =
+        if index_only {
+            quote!(minicbor::sizes::Shape::Index([#(#items),*].to_vec()))
+        } else {
+            quote!(minicbor::sizes::Shape::Enum([#(#items),*].to_vec()))
+        };
+
+    {
+        let bound  = gen_describe_bound()?;
+        let params = inp.generics.type_params_mut();
+        add_bound_to_type_params(bound, params, &blacklist, &[], Mode::Encode);
+    }
+
+    let (impl_generics, typ_generics, where_clause) = inp.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics minicbor::sizes::Describe for #name #typ_generics #where_clause {
+            fn describe() -> minicbor::sizes::Shape {
+                #body
+            }
+        }
+    })
+}
+
+/// Generate the shape of fields in the given encoding.
+fn gen_fields(fields: &Fields, encoding: Encoding) -> proc_macro2::TokenStream {
+    let mut items = Vec::new();
+    for (((idx, ident), (&is_name, typ)), attrs) in fields.indices.iter()
+        .zip(&fields.idents)
+        .zip(fields.is_name.iter().zip(&fields.types))
+        .zip(&fields.attrs)
+    {
+        let shape = field_shape(typ, attrs);
+        if is_name {
+            let n = ident.to_string();
+            let n = n.trim_start_matches("r#");
+            items.push(quote!(minicbor::sizes::Field::new(#idx, #shape).with_name(#n)))
+        } else {
+            items.push(quote!(minicbor::sizes::Field::new(#idx, #shape)))
+        }
+    }
+    match encoding {
+        Encoding::Array => quote!(minicbor::sizes::Shape::Array([#(#items),*].to_vec())),
+        Encoding::Map   => quote!(minicbor::sizes::Shape::Map([#(#items),*].to_vec()))
+    }
+}
+
+/// Generate the function producing the shape of a single field.
+///
+/// Fields with custom encode functions are opaque since their encoding is
+/// not known.
+fn field_shape(typ: &syn::Type, attrs: &Attributes) -> proc_macro2::TokenStream {
+    if has_encode_fn(attrs) {
+        quote!(|| minicbor::sizes::Shape::Opaque)
+    } else {
+        quote!(<#typ as minicbor::sizes::Describe>::describe)
+    }
+}
+
+fn has_encode_fn(attrs: &Attributes) -> bool {
+    attrs.codec().map(CustomCodec::is_encode).unwrap_or(false)
+}
+
+fn gen_describe_bound() -> syn::Result<syn::TypeParamBound> {
+    syn::parse_str("minicbor::sizes::Describe")
+}
@@ -1,8 +1,8 @@
-use crate::Error;
+use crate::{BufferPool, CborStream, Error};
 use futures_io::AsyncRead;
-use futures_util::AsyncReadExt;
+use futures_util::{future::poll_fn, ready};
 use minicbor::Decode;
-use std::io;
+use std::{io, mem, pin::Pin, task::{Context, Poll}};
 
 /// Wraps an [`AsyncRead`] and reads length-delimited CBOR values.
 ///
@@ -12,7 +12,8 @@ pub struct AsyncReader<R> {
     reader: R,
     buffer: Vec<u8>,
     max_len: usize,
-    state: State
+    state: State,
+    pool: Option<BufferPool>
 }
 
 /// Read state.
@@ -39,7 +40,7 @@ impl<R> AsyncReader<R> {
 
     /// Create a new reader with a max. buffer size of 512KiB.
     pub fn with_buffer(reader: R, buffer: Vec<u8>) -> Self {
-        Self { reader, buffer, max_len: 512 * 1024, state: State::new() }
+        Self { reader, buffer, max_len: 512 * 1024, state: State::new(), pool: None }
     }
 
     /// Set the max. buffer size in bytes.
@@ -50,6 +51,21 @@ impl<R> AsyncReader<R> {
         self.max_len = val as usize
     }
 
+    /// Get the max. buffer size in bytes.
+    pub fn max_len(&self) -> u32 {
+        self.max_len as u32
+    }
+
+    /// Take frame buffers from the given pool.
+    ///
+    /// The buffer of a frame is returned to the pool with the next call to
+    /// [`AsyncReader::read`] or [`AsyncReader::read_frame`], i.e. once the
+    /// frame can no longer be borrowed, so that no buffer is held while
+    /// waiting for the next frame.
+    pub fn set_pool(&mut self, pool: BufferPool) {
+        self.pool = Some(pool)
+    }
+
     /// Get a reference to the inner reader.
     pub fn reader(&self) -> &R {
         &self.reader
@@ -78,44 +94,99 @@ impl<R: AsyncRead + Unpin> AsyncReader<R> {
     /// # Cancellation
     ///
     /// The future returned by `AsyncReader::read` can be dropped while still
-    /// pending. Subsequent calls to `AsyncReader::read` will resume reading
-    /// where the previous future left off.
+    /// pending, e.g. when used as a branch of `tokio::select!`. Bytes which
+    /// have already been received are kept and subsequent calls to
+    /// `AsyncReader::read` (or [`AsyncReader::read_frame`]) will resume
+    /// reading where the previous future left off, so no partial frame is
+    /// lost or misinterpreted.
     pub async fn read<'a, T: Decode<'a>>(&'a mut self) -> Result<Option<T>, Error> {
+        match self.read_frame().await? {
+            Some(b) => minicbor::decode(b).map_err(Error::Decode).map(Some),
+            None    => Ok(None)
+        }
+    }
+
+    /// Read the next CBOR value without decoding it.
+    ///
+    /// Like [`AsyncReader::read`] but returns the bytes of the CBOR item,
+    /// borrowed from the internal buffer, e.g. to decode them later or to
+    /// forward them elsewhere without copying.
+    ///
+    /// This method is cancellation safe in the same way as
+    /// [`AsyncReader::read`].
+    pub async fn read_frame(&mut self) -> Result<Option<&[u8]>, Error> {
+        if poll_fn(|cx| self.poll_fill(cx)).await? {
+            Ok(Some(&self.buffer))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Turn this reader into a [`futures_core::Stream`] of decoded values.
+    ///
+    /// See [`CborStream`] for details.
+    pub fn into_stream<T>(self) -> CborStream<R, T>
+    where
+        T: for<'a> Decode<'a>
+    {
+        CborStream::new(self)
+    }
+
+    /// Read until the buffer contains a complete frame.
+    ///
+    /// Returns `false` if the end of input is reached before a new frame.
+    pub(crate) fn poll_fill(&mut self, cx: &mut Context) -> Poll<Result<bool, Error>> {
+        if let (State::ReadLen(_, 0), Some(p)) = (&self.state, &self.pool) {
+            p.put(mem::take(&mut self.buffer))
+        }
         loop {
             match self.state {
                 State::ReadLen(buf, 4) => {
                     let len = u32::from_be_bytes(buf) as usize;
                     if len > self.max_len {
-                        return Err(Error::InvalidLen)
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(len, max_len = self.max_len, "frame length exceeds max.");
+                        return Poll::Ready(Err(Error::InvalidLen))
+                    }
+                    if let Some(p) = &self.pool {
+                        if self.buffer.capacity() == 0 {
+                            self.buffer = p.get()
+                        }
                     }
                     self.buffer.clear();
                     self.buffer.resize(len, 0u8);
                     self.state = State::ReadVal(0)
                 }
                 State::ReadLen(ref mut buf, ref mut o) => {
-                    let n = self.reader.read(&mut buf[usize::from(*o) ..]).await?;
+                    let n = ready!(Pin::new(&mut self.reader).poll_read(cx, &mut buf[usize::from(*o) ..]))?;
                     if n == 0 {
-                        return if *o == 0 {
-                            Ok(None)
+                        return Poll::Ready(if *o == 0 {
+                            Ok(false)
                         } else {
                             Err(Error::Io(io::ErrorKind::UnexpectedEof.into()))
-                        }
+                        })
                     }
                     *o += n as u8
                 }
                 State::ReadVal(o) if o >= self.buffer.len() => {
                     self.state = State::new();
-                    return minicbor::decode(&self.buffer).map_err(Error::Decode).map(Some)
+                    #[cfg(feature = "tracing")]
+                    tracing::trace!(len = self.buffer.len(), "read frame");
+                    return Poll::Ready(Ok(true))
                 }
                 State::ReadVal(ref mut o) => {
-                    let n = self.reader.read(&mut self.buffer[*o ..]).await?;
+                    let n = ready!(Pin::new(&mut self.reader).poll_read(cx, &mut self.buffer[*o ..]))?;
                     if n == 0 {
-                        return Err(Error::Io(io::ErrorKind::UnexpectedEof.into()))
+                        return Poll::Ready(Err(Error::Io(io::ErrorKind::UnexpectedEof.into())))
                     }
                     *o += n
                 }
             }
         }
     }
-}
 
+    /// Get the most recently read frame.
+    pub(crate) fn frame(&self) -> &[u8] {
+        &self.buffer
+    }
+}
@@ -1,8 +1,9 @@
-use crate::Error;
+use crate::{BufferPool, CborSink, Error};
 use futures_io::AsyncWrite;
 use futures_util::AsyncWriteExt;
+use futures_util::{future::poll_fn, ready};
 use minicbor::Encode;
-use std::io;
+use std::{io, mem, pin::Pin, task::{Context, Poll}};
 
 /// Wraps an [`AsyncWrite`] and writes length-delimited CBOR values.
 ///
@@ -12,7 +13,8 @@ pub struct AsyncWriter<W> {
     writer: W,
     buffer: Vec<u8>,
     max_len: usize,
-    state: State
+    state: State,
+    pool: Option<BufferPool>
 }
 
 /// Write state.
@@ -32,7 +34,7 @@ impl<W> AsyncWriter<W> {
 
     /// Create a new writer with a max. buffer size of 512KiB.
     pub fn with_buffer(writer: W, buffer: Vec<u8>) -> Self {
-        Self { writer, buffer, max_len: 512 * 1024, state: State::None }
+        Self { writer, buffer, max_len: 512 * 1024, state: State::None, pool: None }
     }
 
     /// Set the max. buffer size in bytes.
@@ -43,6 +45,19 @@ impl<W> AsyncWriter<W> {
         self.max_len = val as usize
     }
 
+    /// Get the max. buffer size in bytes.
+    pub fn max_len(&self) -> u32 {
+        self.max_len as u32
+    }
+
+    /// Take frame buffers from the given pool.
+    ///
+    /// A buffer is taken from the pool for every value written and returned
+    /// to the pool once the frame has been written.
+    pub fn set_pool(&mut self, pool: BufferPool) {
+        self.pool = Some(pool)
+    }
+
     /// Get a reference to the inner writer.
     pub fn writer(&self) -> &W {
         &self.writer
@@ -74,39 +89,66 @@ impl<W: AsyncWrite + Unpin> AsyncWriter<W> {
     /// possible to resume the transfer by calling [`AsyncWriter::sync`]
     /// after cancellation, which is normally called implicitly by this method.
     pub async fn write<T: Encode>(&mut self, val: T) -> Result<usize, Error> {
+        let n = self.encode(val)?;
+        self.sync().await?;
+        Ok(n)
+    }
+
+    /// Commit any buffered data to the inner `AsyncWrite`.
+    ///
+    /// This method is implicitly called by [`AsyncWriter::write`]. The only
+    /// reason to call it explicitly is to resume the write operation started
+    /// by a previously unfinished, i.e. cancelled, `AsyncWriter::write` call.
+    pub async fn sync(&mut self) -> Result<(), Error> {
+        poll_fn(|cx| self.poll_sync(cx)).await
+    }
+
+    /// Turn this writer into a [`futures_util::sink::Sink`] of values.
+    ///
+    /// See [`CborSink`] for details.
+    pub fn into_sink<T: Encode>(self) -> CborSink<W, T> {
+        CborSink::new(self)
+    }
+
+    /// Encode a value into the buffer, discarding any previous data.
+    pub(crate) fn encode<T: Encode>(&mut self, val: T) -> Result<usize, Error> {
+        if let Some(p) = &self.pool {
+            if self.buffer.capacity() == 0 {
+                self.buffer = p.get()
+            }
+        }
         self.buffer.resize(4, 0u8);
         minicbor::encode(val, &mut self.buffer)?;
         if self.buffer.len() - 4 > self.max_len {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(len = self.buffer.len() - 4, max_len = self.max_len, "frame length exceeds max.");
             return Err(Error::InvalidLen)
         }
         let prefix = (self.buffer.len() as u32 - 4).to_be_bytes();
         self.buffer[.. 4].copy_from_slice(&prefix);
         self.state = State::WriteFrom(0);
-
-        self.sync().await?;
-
         Ok(self.buffer.len() - 4)
     }
 
-    /// Commit any buffered data to the inner `AsyncWrite`.
-    ///
-    /// This method is implicitly called by [`AsyncWriter::write`]. The only
-    /// reason to call it explicitly is to resume the write operation started
-    /// by a previously unfinished, i.e. cancelled, `AsyncWriter::write` call.
-    pub async fn sync(&mut self) -> Result<(), Error> {
+    /// Write buffered data to the inner `AsyncWrite` until none is left.
+    pub(crate) fn poll_sync(&mut self, cx: &mut Context) -> Poll<Result<(), Error>> {
         loop {
             match self.state {
                 State::None => {
-                    return Ok(())
+                    self.release();
+                    return Poll::Ready(Ok(()))
                 }
                 State::WriteFrom(o) if o >= self.buffer.len() => {
+                    #[cfg(feature = "tracing")]
+                    tracing::trace!(len = self.buffer.len() - 4, "wrote frame");
                     self.state = State::None;
-                    return Ok(())
+                    self.release();
+                    return Poll::Ready(Ok(()))
                 }
                 State::WriteFrom(ref mut o) => {
-                    let n = self.writer.write(&self.buffer[*o ..]).await?;
+                    let n = ready!(Pin::new(&mut self.writer).poll_write(cx, &self.buffer[*o ..]))?;
                     if n == 0 {
-                        return Err(Error::Io(io::ErrorKind::WriteZero.into()))
+                        return Poll::Ready(Err(Error::Io(io::ErrorKind::WriteZero.into())))
                     }
                     *o += n
                 }
@@ -114,6 +156,13 @@ impl<W: AsyncWrite + Unpin> AsyncWriter<W> {
         }
     }
 
+    /// Return the buffer to the pool, if any.
+    fn release(&mut self) {
+        if let Some(p) = &self.pool {
+            p.put(mem::take(&mut self.buffer))
+        }
+    }
+
     /// Flush the inner `AsyncWrite`.
     pub async fn flush(&mut self) -> Result<(), Error> {
         self.writer.flush().await?;
@@ -0,0 +1,121 @@
+/// A checksum to protect the integrity of frames.
+///
+/// If enabled, every frame is followed by a trailer of 4 bytes (network
+/// byte order) which contains the checksum of the length prefix and the
+/// CBOR item. Both ends of a connection must use the same checksum, as
+/// the frame format does not indicate it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Checksum {
+    /// CRC-32 as used by Ethernet, zlib and PNG (a.k.a. CRC-32/ISO-HDLC).
+    Crc32,
+    /// 32-bit xxHash with seed 0.
+    XxHash32
+}
+
+impl Checksum {
+    /// The length of the checksum trailer in bytes.
+    pub const LEN: usize = 4;
+
+    /// Compute the checksum of the given bytes.
+    ///
+    /// ```
+    /// use minicbor_io::Checksum;
+    ///
+    /// assert_eq!(0xcbf43926, Checksum::Crc32.compute(b"123456789"));
+    /// assert_eq!(0x32d153ff, Checksum::XxHash32.compute(b"abc"));
+    /// ```
+    pub fn compute(self, bytes: &[u8]) -> u32 {
+        self.digest(&[bytes])
+    }
+
+    /// Compute the checksum of the concatenation of the given byte slices.
+    pub(crate) fn digest(self, parts: &[&[u8]]) -> u32 {
+        match self {
+            Checksum::Crc32    => crc32(parts),
+            Checksum::XxHash32 => {
+                let bytes = parts.iter().flat_map(|p| p.iter().copied());
+                xxhash32(parts.iter().map(|p| p.len()).sum(), bytes)
+            }
+        }
+    }
+}
+
+/// The CRC-32 of every byte value (reflected polynomial 0xedb88320).
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+            k += 1
+        }
+        table[i] = crc;
+        i += 1
+    }
+    table
+};
+
+fn crc32(parts: &[&[u8]]) -> u32 {
+    let mut crc = !0u32;
+    for b in parts.iter().flat_map(|p| p.iter()) {
+        crc = (crc >> 8) ^ CRC32_TABLE[usize::from(crc as u8 ^ b)]
+    }
+    !crc
+}
+
+const P1: u32 = 0x9e37_79b1;
+const P2: u32 = 0x85eb_ca77;
+const P3: u32 = 0xc2b2_ae3d;
+const P4: u32 = 0x27d4_eb2f;
+const P5: u32 = 0x1656_67b1;
+
+fn xxhash32(len: usize, mut bytes: impl Iterator<Item = u8>) -> u32 {
+    fn lane(bytes: &mut impl Iterator<Item = u8>) -> u32 {
+        let mut b = [0; 4];
+        for (x, y) in b.iter_mut().zip(bytes) {
+            *x = y
+        }
+        u32::from_le_bytes(b)
+    }
+
+    fn round(acc: u32, x: u32) -> u32 {
+        acc.wrapping_add(x.wrapping_mul(P2)).rotate_left(13).wrapping_mul(P1)
+    }
+
+    let mut rest = len;
+    let mut h =
+        if len >= 16 {
+            let mut v = [P1.wrapping_add(P2), P2, 0, P1.wrapping_neg()];
+            while rest >= 16 {
+                for x in &mut v {
+                    *x = round(*x, lane(&mut bytes))
+                }
+                rest -= 16
+            }
+            v[0].rotate_left(1)
+                .wrapping_add(v[1].rotate_left(7))
+                .wrapping_add(v[2].rotate_left(12))
+                .wrapping_add(v[3].rotate_left(18))
+        } else {
+            P5
+        };
+
+    h = h.wrapping_add(len as u32);
+
+    while rest >= 4 {
+        h = h.wrapping_add(lane(&mut bytes).wrapping_mul(P3)).rotate_left(17).wrapping_mul(P4);
+        rest -= 4
+    }
+    for b in bytes {
+        h = h.wrapping_add(u32::from(b).wrapping_mul(P5)).rotate_left(11).wrapping_mul(P1)
+    }
+
+    h ^= h >> 15;
+    h = h.wrapping_mul(P2);
+    h ^= h >> 13;
+    h = h.wrapping_mul(P3);
+    h ^ (h >> 16)
+}
@@ -0,0 +1,104 @@
+use crate::Error;
+use bytes::{BufMut, BytesMut};
+use minicbor::{Decode, Encode};
+use std::marker::PhantomData;
+use tokio_util::codec;
+
+/// A [`tokio_util::codec`] implementation for length-delimited CBOR values.
+///
+/// The framing is the same as with [`Reader`](crate::Reader) and
+/// [`Writer`](crate::Writer), i.e. every value is preceded by a `u32`
+/// (4 bytes in network byte order) denoting the length of the CBOR item in
+/// bytes. Any [`Encode`] type can be encoded, decoding produces values of
+/// type `T`.
+///
+/// *Requires cargo feature* `"tokio"`.
+#[derive(Debug)]
+pub struct CborCodec<T> {
+    max_len: usize,
+    _marker: PhantomData<fn() -> T>
+}
+
+impl<T> Default for CborCodec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for CborCodec<T> {
+    fn clone(&self) -> Self {
+        CborCodec { max_len: self.max_len, _marker: PhantomData }
+    }
+}
+
+impl<T> CborCodec<T> {
+    /// Create a new codec with a max. frame size of 512KiB.
+    pub fn new() -> Self {
+        CborCodec { max_len: 512 * 1024, _marker: PhantomData }
+    }
+
+    /// Set the max. frame size in bytes.
+    ///
+    /// If length values greater than this are encoded or decoded, an
+    /// [`Error::InvalidLen`] will be returned.
+    pub fn set_max_len(&mut self, val: u32) {
+        self.max_len = val as usize
+    }
+
+    /// Get the max. frame size in bytes.
+    pub fn max_len(&self) -> u32 {
+        self.max_len as u32
+    }
+}
+
+impl<T, U: Encode> codec::Encoder<U> for CborCodec<T> {
+    type Error = Error;
+
+    fn encode(&mut self, item: U, dst: &mut BytesMut) -> Result<(), Error> {
+        let start = dst.len();
+        dst.put_u32(0);
+        if let Err(e) = minicbor::encode(item, dst.writer()) {
+            dst.truncate(start);
+            return Err(Error::Encode(e))
+        }
+        let len = dst.len() - start - 4;
+        if len > self.max_len {
+            dst.truncate(start);
+            #[cfg(feature = "tracing")]
+            tracing::debug!(len, max_len = self.max_len, "frame length exceeds max.");
+            return Err(Error::InvalidLen)
+        }
+        dst[start .. start + 4].copy_from_slice(&(len as u32).to_be_bytes());
+        #[cfg(feature = "tracing")]
+        tracing::trace!(len, "wrote frame");
+        Ok(())
+    }
+}
+
+impl<T: for<'b> Decode<'b>> codec::Decoder for CborCodec<T> {
+    type Item  = T;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<T>, Error> {
+        if src.len() < 4 {
+            src.reserve(4 - src.len());
+            return Ok(None)
+        }
+        let mut buf = [0; 4];
+        buf.copy_from_slice(&src[.. 4]);
+        let len = u32::from_be_bytes(buf) as usize;
+        if len > self.max_len {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(len, max_len = self.max_len, "frame length exceeds max.");
+            return Err(Error::InvalidLen)
+        }
+        if src.len() < 4 + len {
+            src.reserve(4 + len - src.len());
+            return Ok(None)
+        }
+        let frame = src.split_to(4 + len);
+        #[cfg(feature = "tracing")]
+        tracing::trace!(len, "read frame");
+        minicbor::decode(&frame[4 ..]).map_err(Error::Decode).map(Some)
+    }
+}
@@ -0,0 +1,111 @@
+use crate::Error;
+use minicbor::{Decode, Encode};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+/// The default max. datagram size.
+///
+/// The minimum IPv6 MTU (1280 bytes) minus the sizes of the IPv6 and UDP
+/// headers, i.e. datagrams of this size are not fragmented on any path.
+const DEFAULT_MTU: usize = 1232;
+
+/// Wraps a [`std::net::UdpSocket`] and exchanges one CBOR value per datagram.
+///
+/// Contrary to [`Reader`](crate::Reader) and [`Writer`](crate::Writer),
+/// values are not preceded by a length, as the datagram itself delimits
+/// the value. A received datagram must contain exactly one CBOR value.
+#[derive(Debug)]
+pub struct DatagramSocket {
+    socket: UdpSocket,
+    buffer: Vec<u8>,
+    mtu: usize
+}
+
+impl DatagramSocket {
+    /// Create a new datagram socket with a max. datagram size of 1232 bytes.
+    pub fn new(socket: UdpSocket) -> Self {
+        Self::with_buffer(socket, Vec::new())
+    }
+
+    /// Create a new datagram socket with a max. datagram size of 1232 bytes.
+    pub fn with_buffer(socket: UdpSocket, buffer: Vec<u8>) -> Self {
+        Self { socket, buffer, mtu: DEFAULT_MTU }
+    }
+
+    /// Set the max. datagram size in bytes.
+    ///
+    /// If a value whose encoding exceeds this size is sent or a larger
+    /// datagram is received, an [`Error::InvalidLen`] will be returned.
+    pub fn set_mtu(&mut self, val: u16) {
+        self.mtu = val.into()
+    }
+
+    /// Get the max. datagram size in bytes.
+    pub fn mtu(&self) -> u16 {
+        self.mtu as u16
+    }
+
+    /// Get a reference to the inner socket.
+    pub fn socket(&self) -> &UdpSocket {
+        &self.socket
+    }
+
+    /// Deconstruct this datagram socket into the inner socket and the buffer.
+    pub fn into_parts(self) -> (UdpSocket, Vec<u8>) {
+        (self.socket, self.buffer)
+    }
+
+    /// Encode a CBOR value and send it to the given address.
+    ///
+    /// Returns the size of the datagram in bytes.
+    pub fn send_to<T: Encode, A: ToSocketAddrs>(&mut self, val: T, addr: A) -> Result<usize, Error> {
+        self.encode(val)?;
+        Ok(self.socket.send_to(&self.buffer, addr)?)
+    }
+
+    /// Encode a CBOR value and send it to the connected peer.
+    ///
+    /// Returns the size of the datagram in bytes.
+    pub fn send<T: Encode>(&mut self, val: T) -> Result<usize, Error> {
+        self.encode(val)?;
+        Ok(self.socket.send(&self.buffer)?)
+    }
+
+    /// Receive a datagram and decode its CBOR value.
+    ///
+    /// Returns the value together with the address of the sender. Bytes
+    /// following the value result in a [`minicbor::decode::Error::TrailingBytes`]
+    /// error.
+    pub fn recv_from<'a, T: Decode<'a>>(&'a mut self) -> Result<(T, SocketAddr), Error> {
+        // One more byte than allowed to detect oversized datagrams, which
+        // would otherwise be truncated silently.
+        self.buffer.resize(self.mtu + 1, 0);
+        let (n, addr) = self.socket.recv_from(&mut self.buffer)?;
+        Ok((self.decode(n)?, addr))
+    }
+
+    /// Receive a datagram from the connected peer and decode its CBOR value.
+    ///
+    /// Bytes following the value result in a
+    /// [`minicbor::decode::Error::TrailingBytes`] error.
+    pub fn recv<'a, T: Decode<'a>>(&'a mut self) -> Result<T, Error> {
+        self.buffer.resize(self.mtu + 1, 0);
+        let n = self.socket.recv(&mut self.buffer)?;
+        self.decode(n)
+    }
+
+    fn encode<T: Encode>(&mut self, val: T) -> Result<(), Error> {
+        self.buffer.clear();
+        minicbor::encode(val, &mut self.buffer)?;
+        if self.buffer.len() > self.mtu {
+            return Err(Error::InvalidLen)
+        }
+        Ok(())
+    }
+
+    fn decode<'a, T: Decode<'a>>(&'a self, n: usize) -> Result<T, Error> {
+        if n > self.mtu {
+            return Err(Error::InvalidLen)
+        }
+        minicbor::decode_exact(&self.buffer[.. n]).map_err(Error::Decode)
+    }
+}
@@ -0,0 +1,228 @@
+//! Length-delimited CBOR values over [`embedded_io`] transports.
+//!
+//! [`Reader`] and [`Writer`] use the same framing as their `std`
+//! counterparts, i.e. every value is preceded by a `u32` (4 bytes in network
+//! byte order) denoting the length of the CBOR item in bytes. Neither `std`
+//! nor `alloc` is required. Instead, both operate on a caller-provided
+//! buffer whose size determines the max. frame length. Optionally, every
+//! frame can be followed by a [`Checksum`].
+//!
+//! *Requires cargo feature* `"embedded-io"`.
+
+use core::fmt;
+use embedded_io::{Read, ReadExactError, Write};
+use minicbor::{Decode, Encode};
+
+use crate::Checksum;
+
+/// Possible read/write errors.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error<E> {
+    /// An I/O error occured.
+    Io(E),
+    /// The end of input was reached in the middle of a value.
+    UnexpectedEof,
+    /// A decoding error occured.
+    Decode(minicbor::decode::Error),
+    /// An encoding error occured.
+    Encode(&'static str),
+    /// The length preceding the CBOR value is not valid.
+    InvalidLen,
+    /// The checksum of a frame does not match its content.
+    Checksum
+}
+
+impl<E: fmt::Debug> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e)         => write!(f, "i/o error: {:?}", e),
+            Error::UnexpectedEof => f.write_str("unexpected end of input"),
+            Error::Decode(e)     => write!(f, "decode error: {}", e),
+            Error::Encode(m)     => write!(f, "encode error: {}", m),
+            Error::InvalidLen    => f.write_str("invalid length"),
+            Error::Checksum      => f.write_str("checksum mismatch")
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Debug> std::error::Error for Error<E> {}
+
+impl<E> From<ReadExactError<E>> for Error<E> {
+    fn from(e: ReadExactError<E>) -> Self {
+        match e {
+            ReadExactError::UnexpectedEof => Error::UnexpectedEof,
+            ReadExactError::Other(e)      => Error::Io(e)
+        }
+    }
+}
+
+/// Wraps an [`embedded_io::Read`] and reads length-delimited CBOR values.
+#[derive(Debug)]
+pub struct Reader<'b, R> {
+    reader: R,
+    buffer: &'b mut [u8],
+    checksum: Option<Checksum>
+}
+
+impl<'b, R> Reader<'b, R> {
+    /// Create a new reader.
+    ///
+    /// The max. length of a CBOR value is the length of the given buffer.
+    pub fn new(reader: R, buffer: &'b mut [u8]) -> Self {
+        Reader { reader, buffer, checksum: None }
+    }
+
+    /// Create a new reader which expects every frame to end with the given
+    /// checksum.
+    ///
+    /// Frames whose checksum does not match result in an [`Error::Checksum`].
+    pub fn with_checksum(reader: R, buffer: &'b mut [u8], checksum: Checksum) -> Self {
+        Reader { reader, buffer, checksum: Some(checksum) }
+    }
+
+    /// Get the checksum frames are expected to end with, if any.
+    pub fn checksum(&self) -> Option<Checksum> {
+        self.checksum
+    }
+
+    /// Get a reference to the inner reader.
+    pub fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    /// Get a mutable reference to the inner reader.
+    pub fn reader_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Deconstruct this reader into the inner reader and the buffer.
+    pub fn into_parts(self) -> (R, &'b mut [u8]) {
+        (self.reader, self.buffer)
+    }
+}
+
+impl<'b, R: Read> Reader<'b, R> {
+    /// Read the next CBOR value and decode it.
+    ///
+    /// Reading 0 bytes when decoding the length prefix results in `Ok(None)`,
+    /// otherwise either `Some` value or an error is returned.
+    pub fn read<'a, T: Decode<'a>>(&'a mut self) -> Result<Option<T>, Error<R::Error>> {
+        match self.read_frame()? {
+            Some(b) => minicbor::decode(b).map_err(Error::Decode).map(Some),
+            None    => Ok(None)
+        }
+    }
+
+    /// Read the next CBOR value without decoding it.
+    ///
+    /// Like [`Reader::read`] but returns the bytes of the CBOR item, borrowed
+    /// from the buffer.
+    pub fn read_frame(&mut self) -> Result<Option<&[u8]>, Error<R::Error>> {
+        let mut buf = [0; 4];
+        let mut len = 0;
+        while len < 4 {
+            match self.reader.read(&mut buf[len ..]).map_err(Error::Io)? {
+                0 if len == 0 => return Ok(None),
+                0 => return Err(Error::UnexpectedEof),
+                n => len += n
+            }
+        }
+        let len = u32::from_be_bytes(buf) as usize;
+        if len > self.buffer.len() {
+            return Err(Error::InvalidLen)
+        }
+        self.reader.read_exact(&mut self.buffer[.. len])?;
+        if let Some(c) = self.checksum {
+            let mut trailer = [0; Checksum::LEN];
+            self.reader.read_exact(&mut trailer)?;
+            if c.digest(&[&buf, &self.buffer[.. len]]).to_be_bytes() != trailer {
+                return Err(Error::Checksum)
+            }
+        }
+        Ok(Some(&self.buffer[.. len]))
+    }
+}
+
+/// Wraps an [`embedded_io::Write`] and writes length-delimited CBOR values.
+#[derive(Debug)]
+pub struct Writer<'b, W> {
+    writer: W,
+    buffer: &'b mut [u8],
+    checksum: Option<Checksum>
+}
+
+impl<'b, W> Writer<'b, W> {
+    /// Create a new writer.
+    ///
+    /// The max. length of a CBOR value is the length of the given buffer
+    /// minus 4 bytes for the length prefix.
+    pub fn new(writer: W, buffer: &'b mut [u8]) -> Self {
+        Writer { writer, buffer, checksum: None }
+    }
+
+    /// Create a new writer which appends the given checksum to every frame.
+    ///
+    /// The max. length of a CBOR value is the length of the given buffer
+    /// minus 4 bytes for the length prefix and 4 bytes for the checksum.
+    pub fn with_checksum(writer: W, buffer: &'b mut [u8], checksum: Checksum) -> Self {
+        Writer { writer, buffer, checksum: Some(checksum) }
+    }
+
+    /// Get the checksum appended to every frame, if any.
+    pub fn checksum(&self) -> Option<Checksum> {
+        self.checksum
+    }
+
+    /// Get a reference to the inner writer.
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    /// Get a mutable reference to the inner writer.
+    pub fn writer_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Deconstruct this writer into the inner writer and the buffer.
+    pub fn into_parts(self) -> (W, &'b mut [u8]) {
+        (self.writer, self.buffer)
+    }
+}
+
+impl<'b, W: Write> Writer<'b, W> {
+    /// Encode and write a CBOR value and return its size in bytes.
+    ///
+    /// If the value does not fit into the buffer, an [`Error::InvalidLen`]
+    /// will be returned.
+    pub fn write<T: Encode>(&mut self, val: T) -> Result<usize, Error<W::Error>> {
+        let trailer = if self.checksum.is_some() { Checksum::LEN } else { 0 };
+        if self.buffer.len() < 4 + trailer {
+            return Err(Error::InvalidLen)
+        }
+        let end = self.buffer.len() - trailer;
+        let len = {
+            let mut b = &mut self.buffer[4 .. end];
+            let n = b.len();
+            match minicbor::encode(val, &mut b) {
+                Ok(())                                   => n - b.len(),
+                Err(minicbor::encode::Error::Write(_))   => return Err(Error::InvalidLen),
+                Err(minicbor::encode::Error::Message(m)) => return Err(Error::Encode(m)),
+                Err(_)                                   => return Err(Error::Encode("unknown error"))
+            }
+        };
+        self.buffer[.. 4].copy_from_slice(&(len as u32).to_be_bytes());
+        if let Some(c) = self.checksum {
+            let sum = c.compute(&self.buffer[.. 4 + len]);
+            self.buffer[4 + len .. 4 + len + trailer].copy_from_slice(&sum.to_be_bytes())
+        }
+        self.writer.write_all(&self.buffer[.. 4 + len + trailer]).map_err(Error::Io)?;
+        Ok(len)
+    }
+
+    /// Flush the inner `Write`r.
+    pub fn flush(&mut self) -> Result<(), Error<W::Error>> {
+        self.writer.flush().map_err(Error::Io)
+    }
+}
@@ -11,7 +11,9 @@ pub enum Error {
     /// An encoding error occured.
     Encode(minicbor::encode::Error<io::Error>),
     /// The length preceding the CBOR value is not valid.
-    InvalidLen
+    InvalidLen,
+    /// The checksum of a frame does not match its content.
+    Checksum
 }
 
 impl fmt::Display for Error {
@@ -20,7 +22,8 @@ impl fmt::Display for Error {
             Error::Io(e) => write!(f, "i/o error: {}", e),
             Error::Decode(e) => write!(f, "decode error: {}", e),
             Error::Encode(e) => write!(f, "encode error: {}", e),
-            Error::InvalidLen => f.write_str("invalid length")
+            Error::InvalidLen => f.write_str("invalid length"),
+            Error::Checksum => f.write_str("checksum mismatch")
         }
     }
 }
@@ -31,7 +34,7 @@ impl std::error::Error for Error {
             Error::Io(e) => Some(e),
             Error::Decode(e) => Some(e),
             Error::Encode(e) => Some(e),
-            Error::InvalidLen => None
+            Error::InvalidLen | Error::Checksum => None
         }
     }
 }
@@ -1,19 +1,208 @@
 //! A set of I/O utilities for working with CBOR encoded values.
+//!
+//! # Framing
+//!
+//! [`Writer`] and [`Reader`] transmit CBOR values over [`std::io::Write`]
+//! and [`std::io::Read`] impls respectively. Each value is written as a
+//! frame, consisting of a `u32` length prefix (4 bytes in network byte
+//! order) followed by as many bytes of CBOR. Both ends enforce a max. frame
+//! length (512 KiB by default, see e.g. [`Reader::set_max_len`]) and fail
+//! with [`Error::InvalidLen`] if it is exceeded.
+//!
+//! ```
+//! use minicbor_io::{Reader, Writer};
+//!
+//! let mut w = Writer::new(Vec::new());
+//! w.write(&["hello", "world"])?;
+//! w.write(42u32)?;
+//!
+//! let mut r = Reader::new(std::io::Cursor::new(w.into_parts().0));
+//! assert_eq!(Some(["hello", "world"]), r.read::<[&str; 2]>()?);
+//! assert_eq!(Some(42), r.read::<u32>()?);
+//! assert_eq!(None, r.read::<u32>()?);
+//!
+//! # Ok::<_, minicbor_io::Error>(())
+//! ```
+//!
+//! To detect corrupted frames, e.g. on serial or radio links, reader and
+//! writer can be created with a [`Checksum`] (see [`Reader::with_checksum`]
+//! and [`Writer::with_checksum`]), which is then appended to every frame
+//! and verified when reading. A mismatch results in [`Error::Checksum`].
+//!
+//! [`Reader::stats`] and [`Writer::stats`] provide counters of items and
+//! bytes transferred, e.g. to export codec metrics. A callback set with
+//! [`Reader::set_hook`] or [`Writer::set_hook`] is invoked for every
+//! item read or written.
+//!
+//! Reader and writer reuse a single buffer for all frames. Servers with many
+//! connections can instead share a [`BufferPool`] between readers and
+//! writers (see [`Reader::set_pool`] and [`Writer::set_pool`]), from which
+//! a buffer is taken only for the duration of a frame, so that idle
+//! connections hold no buffer memory and a large frame does not leave a
+//! large buffer behind:
+//!
+//! ```
+//! use minicbor_io::{BufferPool, Reader, Writer};
+//!
+//! let pool = BufferPool::new();
+//!
+//! let mut w = Writer::new(Vec::new());
+//! w.set_pool(pool.clone());
+//! w.write("hello")?;
+//! assert_eq!(1, pool.len());
+//!
+//! let mut r = Reader::new(std::io::Cursor::new(w.into_parts().0));
+//! r.set_pool(pool.clone());
+//! assert_eq!(Some("hello"), r.read::<&str>()?);
+//! assert!(pool.is_empty());
+//! assert_eq!(None, r.read::<&str>()?);
+//! assert_eq!(1, pool.len());
+//!
+//! # Ok::<_, minicbor_io::Error>(())
+//! ```
+//!
+//! # CBOR sequences
+//!
+//! [`SeqReader`] reads values which are not framed but directly follow each
+//! other, e.g. from a `.cborseq` file or a pipe. The reader buffers input
+//! until a complete value is available.
+//!
+//! # Datagrams
+//!
+//! [`DatagramSocket`] sends and receives one CBOR value per UDP datagram,
+//! as used by CoAP-style and discovery protocols. Values whose encoding
+//! exceeds the max. datagram size (see [`DatagramSocket::set_mtu`]) are
+//! rejected with [`Error::InvalidLen`] and a received datagram must contain
+//! exactly one value.
+//!
+//! ```
+//! use minicbor_io::DatagramSocket;
+//! use std::net::UdpSocket;
+//!
+//! let mut a = DatagramSocket::new(UdpSocket::bind("127.0.0.1:0")?);
+//! let mut b = DatagramSocket::new(UdpSocket::bind("127.0.0.1:0")?);
+//! a.send_to(("hello", 42), b.socket().local_addr()?)?;
+//!
+//! let (val, from) = b.recv_from::<(&str, u8)>()?;
+//! assert_eq!(("hello", 42), val);
+//! assert_eq!(a.socket().local_addr()?, from);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! # Async I/O
+//!
+//! With feature `"async-io"`, [`AsyncWriter`] and [`AsyncReader`] provide
+//! the same framing on top of the [`futures_io`] traits `AsyncWrite` and
+//! `AsyncRead`, i.e. they can be used with any executor. Reading is
+//! cancellation safe, i.e. a pending read may be dropped, for instance as a
+//! losing branch of `tokio::select!`, and the next read resumes with the
+//! partially received frame (cf. [`AsyncReader::read`]).
+//!
+//! [`AsyncReader::into_stream`] and [`AsyncWriter::into_sink`] turn reader
+//! and writer into a [`CborStream`] and [`CborSink`] of typed values for use
+//! with the `Stream` and `Sink` combinators of the `futures` ecosystem.
+//!
+//! ```
+//! use futures_util::io::Cursor;
+//! use minicbor_io::{AsyncReader, AsyncWriter};
+//!
+//! let rt = tokio::runtime::Builder::new_current_thread().build()?;
+//! rt.block_on(async {
+//!     let mut w = AsyncWriter::new(Cursor::new(Vec::new()));
+//!     w.write("hello").await?;
+//!
+//!     let mut r = AsyncReader::new(Cursor::new(w.into_parts().0.into_inner()));
+//!     assert_eq!(Some("hello"), r.read::<&str>().await?);
+//!     assert_eq!(None, r.read::<&str>().await?);
+//!     Ok::<_, minicbor_io::Error>(())
+//! })?;
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Values too large to be held in memory as a whole, e.g. a struct with a
+//! byte string of several megabytes, can instead implement [`AsyncEncode`]
+//! and [`AsyncDecode`]. They are written to an [`AsyncEncoder`] and read
+//! from an [`AsyncDecoder`] piecewise, without length prefix, so that large
+//! strings can be streamed in chunks.
+//!
+//! # Embedded I/O
+//!
+//! With feature `"embedded-io"`, the [`embedded`] module provides a reader
+//! and writer with the same framing on top of the `embedded-io` traits
+//! `Read` and `Write`. They use caller-provided buffers and work without
+//! `std` or `alloc`, e.g. to exchange CBOR values with a microcontroller
+//! over a serial link. For `no_std` targets, disable the default feature
+//! `"std"`.
+//!
+//! # Tokio codec
+//!
+//! With feature `"tokio"`, [`CborCodec`] implements the `Encoder` and
+//! `Decoder` traits of [`tokio_util::codec`] with the same framing, so that
+//! it can be used with `Framed`, `FramedRead` and `FramedWrite`.
+//!
+//! # Tracing
+//!
+//! With feature `"tracing"`, frames which are read or written are reported
+//! as events of the `tracing` crate at level `TRACE` and framing errors,
+//! e.g. frames exceeding the max. length, at level `DEBUG`. The feature
+//! enables the instrumentation of encoding and decoding in `minicbor` as
+//! well.
 
 #![forbid(unsafe_code)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
+mod checksum;
+
+#[cfg(feature = "std")]
+mod datagram;
+#[cfg(feature = "std")]
 mod error;
+#[cfg(feature = "std")]
+mod pool;
+#[cfg(feature = "std")]
 mod reader;
+#[cfg(feature = "std")]
+mod seq_reader;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "std")]
 mod writer;
 
+#[cfg(feature = "embedded-io")]
+pub mod embedded;
+
 #[cfg(feature = "async-io")]
 mod async_reader;
 
 #[cfg(feature = "async-io")]
 mod async_writer;
 
+#[cfg(feature = "async-io")]
+mod stream;
+
+#[cfg(feature = "async-io")]
+mod streaming;
+
+#[cfg(feature = "tokio")]
+mod codec;
+
+pub use checksum::Checksum;
+
+#[cfg(feature = "std")]
+pub use datagram::DatagramSocket;
+#[cfg(feature = "std")]
 pub use error::Error;
+#[cfg(feature = "std")]
+pub use pool::BufferPool;
+#[cfg(feature = "std")]
 pub use reader::Reader;
+#[cfg(feature = "std")]
+pub use seq_reader::SeqReader;
+#[cfg(feature = "std")]
+pub use stats::{Event, Stats};
+#[cfg(feature = "std")]
 pub use writer::Writer;
 
 #[cfg(feature = "async-io")]
@@ -22,9 +211,17 @@ pub use async_reader::AsyncReader;
 #[cfg(feature = "async-io")]
 pub use async_writer::AsyncWriter;
 
+#[cfg(feature = "async-io")]
+pub use stream::{CborSink, CborStream};
+
+#[cfg(feature = "async-io")]
+pub use streaming::{AsyncDecode, AsyncDecoder, AsyncEncode, AsyncEncoder};
+
+#[cfg(feature = "tokio")]
+pub use codec::CborCodec;
+
 /// Ensure we can safely cast a `u32` to a `usize`.
 const __U32_FITS_INTO_USIZE: () =
-    if std::mem::size_of::<u32>() > std::mem::size_of::<usize>() {
-        let _ = ["This crate requires at least a 32-bit architecture."][1];
-    };
+    assert!(core::mem::size_of::<u32>() <= core::mem::size_of::<usize>(),
+        "This crate requires at least a 32-bit architecture.");
 
@@ -0,0 +1,108 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// The default max. number of idle buffers retained by a pool.
+const DEFAULT_MAX_BUFFERS: usize = 256;
+
+/// The default max. capacity of idle buffers retained by a pool.
+const DEFAULT_MAX_CAPACITY: usize = 64 * 1024;
+
+/// A pool of frame buffers, shared by readers and writers.
+///
+/// By default, every reader and writer owns a single buffer, which is
+/// reused for all frames and keeps the capacity of the largest frame seen.
+/// With a pool (see e.g. [`Reader::set_pool`](crate::Reader::set_pool)),
+/// a buffer is only taken from the pool while a frame is being read or
+/// written and returned afterwards, so that idle connections do not hold
+/// on to any buffer memory.
+///
+/// Returned buffers whose capacity exceeds the max. capacity are shrunk to
+/// it and buffers returned to a pool which already retains its max. number
+/// of idle buffers are dropped.
+///
+/// Cloning a pool is cheap and all clones share the same buffers.
+#[derive(Debug, Clone)]
+pub struct BufferPool {
+    inner: Arc<Mutex<Inner>>
+}
+
+#[derive(Debug)]
+struct Inner {
+    buffers: Vec<Vec<u8>>,
+    max_buffers: usize,
+    max_capacity: usize
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BufferPool {
+    /// Create a new pool which retains up to 256 idle buffers with a
+    /// capacity of at most 64KiB each.
+    pub fn new() -> Self {
+        Self::with_limits(DEFAULT_MAX_BUFFERS, DEFAULT_MAX_CAPACITY)
+    }
+
+    /// Create a new pool which retains up to `max_buffers` idle buffers
+    /// with a capacity of at most `max_capacity` bytes each.
+    pub fn with_limits(max_buffers: usize, max_capacity: usize) -> Self {
+        let inner = Inner { buffers: Vec::new(), max_buffers, max_capacity };
+        Self { inner: Arc::new(Mutex::new(inner)) }
+    }
+
+    /// Get the max. number of idle buffers retained by this pool.
+    pub fn max_buffers(&self) -> usize {
+        self.lock(|i| i.max_buffers)
+    }
+
+    /// Get the max. capacity in bytes of idle buffers retained by this pool.
+    pub fn max_capacity(&self) -> usize {
+        self.lock(|i| i.max_capacity)
+    }
+
+    /// Get the number of idle buffers in this pool.
+    pub fn len(&self) -> usize {
+        self.lock(|i| i.buffers.len())
+    }
+
+    /// Check if this pool contains no idle buffers.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Take a buffer from this pool.
+    ///
+    /// If the pool is empty, a new (unallocated) buffer is returned.
+    pub fn get(&self) -> Vec<u8> {
+        self.lock(|i| i.buffers.pop()).unwrap_or_default()
+    }
+
+    /// Return a buffer to this pool.
+    ///
+    /// The buffer is cleared and shrunk to the max. capacity if necessary.
+    /// Unallocated buffers are ignored.
+    pub fn put(&self, mut buffer: Vec<u8>) {
+        if buffer.capacity() == 0 {
+            return
+        }
+        buffer.clear();
+        self.lock(|i| {
+            if i.buffers.len() < i.max_buffers {
+                buffer.shrink_to(i.max_capacity);
+                i.buffers.push(buffer)
+            }
+        })
+    }
+
+    /// Apply a function to the pool state.
+    ///
+    /// The state is always consistent, hence a poisoned lock is ignored.
+    fn lock<F, T>(&self, f: F) -> T
+    where
+        F: FnOnce(&mut Inner) -> T
+    {
+        f(&mut self.inner.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
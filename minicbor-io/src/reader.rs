@@ -1,13 +1,35 @@
-use crate::Error;
+use crate::{BufferPool, Checksum, Error, Event, Stats};
+use crate::stats::Hook;
 use minicbor::Decode;
-use std::io;
+use std::{io, mem};
 
 /// Wraps a [`std::io::Read`] and reads length-delimited CBOR values.
 #[derive(Debug)]
 pub struct Reader<R> {
     reader: R,
     buffer: Vec<u8>,
-    max_len: usize
+    max_len: usize,
+    checksum: Option<Checksum>,
+    state: State,
+    stats: Stats,
+    hook: Hook,
+    pool: Option<BufferPool>
+}
+
+/// Read state.
+#[derive(Debug)]
+enum State {
+    /// Reading length prefix.
+    ReadLen([u8; 4], u8),
+    /// Reading CBOR item bytes (and checksum) after the given length prefix.
+    ReadVal([u8; 4], usize)
+}
+
+impl State {
+    /// Setup a new state.
+    fn new() -> Self {
+        State::ReadLen([0; 4], 0)
+    }
 }
 
 impl<R> Reader<R> {
@@ -18,7 +40,31 @@ impl<R> Reader<R> {
 
     /// Create a new reader with a max. buffer size of 512KiB.
     pub fn with_buffer(reader: R, buffer: Vec<u8>) -> Self {
-        Self { reader, buffer, max_len: 512 * 1024 }
+        Self {
+            reader,
+            buffer,
+            max_len: 512 * 1024,
+            checksum: None,
+            state: State::new(),
+            stats: Stats::default(),
+            hook: Hook::default(),
+            pool: None
+        }
+    }
+
+    /// Create a new reader which expects every frame to end with the given
+    /// checksum.
+    ///
+    /// Frames whose checksum does not match result in an [`Error::Checksum`].
+    pub fn with_checksum(reader: R, checksum: Checksum) -> Self {
+        let mut this = Self::new(reader);
+        this.checksum = Some(checksum);
+        this
+    }
+
+    /// Get the checksum frames are expected to end with, if any.
+    pub fn checksum(&self) -> Option<Checksum> {
+        self.checksum
     }
 
     /// Set the max. buffer size in bytes.
@@ -29,6 +75,35 @@ impl<R> Reader<R> {
         self.max_len = val as usize
     }
 
+    /// Get the max. buffer size in bytes.
+    pub fn max_len(&self) -> u32 {
+        self.max_len as u32
+    }
+
+    /// Take frame buffers from the given pool.
+    ///
+    /// The buffer of a frame is returned to the pool with the next call to
+    /// [`Reader::read`] or [`Reader::read_frame`], i.e. once the frame
+    /// can no longer be borrowed, so that no buffer is held while waiting
+    /// for the next frame.
+    pub fn set_pool(&mut self, pool: BufferPool) {
+        self.pool = Some(pool)
+    }
+
+    /// Get the I/O statistics of this reader.
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// Set a callback to invoke whenever an item has been read or failed
+    /// to decode.
+    pub fn set_hook<F>(&mut self, f: F)
+    where
+        F: FnMut(Event) + Send + Sync + 'static
+    {
+        self.hook = Hook::new(f)
+    }
+
     /// Get a reference to the inner reader.
     pub fn reader(&self) -> &R {
         &self.reader
@@ -49,35 +124,119 @@ impl<R: io::Read> Reader<R> {
     /// Read the next CBOR value and decode it.
     ///
     /// The value is assumed to be preceded by a `u32` (4 bytes in network
-    /// byte order) denoting the length of the CBOR item in bytes.
+    /// byte order) denoting the length of the CBOR item in bytes. Lengths
+    /// greater than the max. buffer size are rejected before any further
+    /// bytes are read.
     ///
     /// Reading 0 bytes when decoding the length prefix results in `Ok(None)`,
     /// otherwise either `Some` value or an error is returned.
+    ///
+    /// # Non-blocking I/O
+    ///
+    /// If the inner reader fails with an error, e.g. `io::ErrorKind::WouldBlock`,
+    /// all bytes read so far are retained and subsequent calls to
+    /// `Reader::read` resume reading where the previous call left off.
     pub fn read<'a, T: Decode<'a>>(&'a mut self) -> Result<Option<T>, Error> {
-        let mut buf = [0; 4];
-        let mut len = 0;
-        while len < 4 {
-            match self.reader.read(&mut buf[len ..]) {
-                Ok(0) if len == 0 =>
-                    return Ok(None),
-                Ok(0) =>
-                    return Err(Error::Io(io::ErrorKind::UnexpectedEof.into())),
-                Ok(n) =>
-                    len += n,
-                Err(e) if e.kind() == io::ErrorKind::Interrupted =>
-                    continue,
-                Err(e) =>
-                    return Err(Error::Io(e))
+        if !self.fill()? {
+            return Ok(None)
+        }
+        let Reader { buffer, stats, hook, .. } = self;
+        match minicbor::decode(buffer) {
+            Ok(v)  => Ok(Some(v)),
+            Err(e) => {
+                stats.add_decode_error();
+                hook.call(Event::DecodeError(buffer.len()));
+                Err(Error::Decode(e))
             }
         }
-        let len = u32::from_be_bytes(buf) as usize;
-        if len > self.max_len {
-            return Err(Error::InvalidLen)
+    }
+
+    /// Read the next CBOR value without decoding it.
+    ///
+    /// Like [`Reader::read`] but returns the bytes of the CBOR item,
+    /// borrowed from the internal buffer, e.g. to decode them later or to
+    /// forward them elsewhere without copying.
+    pub fn read_frame(&mut self) -> Result<Option<&[u8]>, Error> {
+        if self.fill()? {
+            Ok(Some(&self.buffer))
+        } else {
+            Ok(None)
         }
-        self.buffer.clear();
-        self.buffer.resize(len, 0u8);
-        self.reader.read_exact(&mut self.buffer)?;
-        minicbor::decode(&self.buffer).map_err(Error::Decode).map(Some)
     }
-}
 
+    /// Read until the buffer contains a complete frame.
+    ///
+    /// Returns `false` if the end of input is reached before a new frame.
+    fn fill(&mut self) -> Result<bool, Error> {
+        if let (State::ReadLen(_, 0), Some(p)) = (&self.state, &self.pool) {
+            p.put(mem::take(&mut self.buffer))
+        }
+        loop {
+            match self.state {
+                State::ReadLen(buf, 4) => {
+                    let len = u32::from_be_bytes(buf) as usize;
+                    if len > self.max_len {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(len, max_len = self.max_len, "frame length exceeds max.");
+                        return Err(Error::InvalidLen)
+                    }
+                    let trailer = if self.checksum.is_some() { Checksum::LEN } else { 0 };
+                    if let Some(p) = &self.pool {
+                        if self.buffer.capacity() == 0 {
+                            self.buffer = p.get()
+                        }
+                    }
+                    self.buffer.clear();
+                    self.buffer.resize(len + trailer, 0u8);
+                    self.state = State::ReadVal(buf, 0)
+                }
+                State::ReadLen(ref mut buf, ref mut o) => {
+                    match self.reader.read(&mut buf[usize::from(*o) ..]) {
+                        Ok(0) if *o == 0 =>
+                            return Ok(false),
+                        Ok(0) =>
+                            return Err(Error::Io(io::ErrorKind::UnexpectedEof.into())),
+                        Ok(n) =>
+                            *o += n as u8,
+                        Err(e) if e.kind() == io::ErrorKind::Interrupted =>
+                            continue,
+                        Err(e) =>
+                            return Err(Error::Io(e))
+                    }
+                }
+                State::ReadVal(prefix, o) if o >= self.buffer.len() => {
+                    self.state = State::new();
+                    let mut overhead = 4;
+                    if let Some(c) = self.checksum {
+                        let len = self.buffer.len() - Checksum::LEN;
+                        let (val, trailer) = self.buffer.split_at(len);
+                        if c.digest(&[&prefix, val]).to_be_bytes() != trailer {
+                            #[cfg(feature = "tracing")]
+                            tracing::debug!(len, "frame checksum mismatch");
+                            return Err(Error::Checksum)
+                        }
+                        self.buffer.truncate(len);
+                        overhead += Checksum::LEN
+                    }
+                    self.stats.add_item(self.buffer.len(), overhead);
+                    self.hook.call(Event::Read(self.buffer.len()));
+                    #[cfg(feature = "tracing")]
+                    tracing::trace!(len = self.buffer.len(), "read frame");
+                    return Ok(true)
+                }
+                State::ReadVal(_, ref mut o) => {
+                    match self.reader.read(&mut self.buffer[*o ..]) {
+                        Ok(0) =>
+                            return Err(Error::Io(io::ErrorKind::UnexpectedEof.into())),
+                        Ok(n) =>
+                            *o += n,
+                        Err(e) if e.kind() == io::ErrorKind::Interrupted =>
+                            continue,
+                        Err(e) =>
+                            return Err(Error::Io(e))
+                    }
+                }
+            }
+        }
+    }
+}
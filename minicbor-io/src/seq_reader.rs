@@ -0,0 +1,124 @@
+use crate::Error;
+use minicbor::{decode, Decode, Decoder};
+use std::io;
+
+/// Wraps a [`std::io::Read`] and reads a sequence of CBOR values.
+///
+/// Contrary to [`Reader`](crate::Reader), values are not expected to be
+/// preceded by a length, but follow each other directly as in a CBOR
+/// sequence ([RFC 8742][1]). Values may be split across reads of the inner
+/// reader; they are buffered until complete.
+///
+/// [1]: https://www.rfc-editor.org/rfc/rfc8742.html
+#[derive(Debug)]
+pub struct SeqReader<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    consumed: usize,
+    max_len: usize
+}
+
+/// The number of bytes to read from the inner reader at once.
+const CHUNK_SIZE: usize = 4096;
+
+impl<R> SeqReader<R> {
+    /// Create a new reader with a max. buffer size of 512KiB.
+    pub fn new(reader: R) -> Self {
+        Self::with_buffer(reader, Vec::new())
+    }
+
+    /// Create a new reader with a max. buffer size of 512KiB.
+    pub fn with_buffer(reader: R, mut buffer: Vec<u8>) -> Self {
+        buffer.clear();
+        Self { reader, buffer, consumed: 0, max_len: 512 * 1024 }
+    }
+
+    /// Set the max. buffer size in bytes.
+    ///
+    /// If a CBOR value does not fit into a buffer of this size, an
+    /// [`Error::InvalidLen`] will be returned.
+    pub fn set_max_len(&mut self, val: u32) {
+        self.max_len = val as usize
+    }
+
+    /// Get the max. buffer size in bytes.
+    pub fn max_len(&self) -> u32 {
+        self.max_len as u32
+    }
+
+    /// Get a reference to the inner reader.
+    pub fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    /// Get a mutable reference to the inner reader.
+    pub fn reader_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Deconstruct this reader into the inner reader and the buffer.
+    ///
+    /// The buffer contains bytes which have been read from the inner
+    /// reader but not yet been decoded.
+    pub fn into_parts(mut self) -> (R, Vec<u8>) {
+        self.buffer.drain(.. self.consumed);
+        (self.reader, self.buffer)
+    }
+}
+
+impl<R: io::Read> SeqReader<R> {
+    /// Read the next CBOR value and decode it.
+    ///
+    /// Reaching the end of the inner reader before any byte of a value has
+    /// been read results in `Ok(None)`, otherwise either `Some` value or an
+    /// error is returned.
+    pub fn read<'a, T: Decode<'a>>(&'a mut self) -> Result<Option<T>, Error> {
+        self.buffer.drain(.. self.consumed);
+        self.consumed = 0;
+        loop {
+            // Find the end of the next value in the buffer. If the value is
+            // incomplete, more bytes need to be read.
+            let mut d = Decoder::new(&self.buffer);
+            match d.skip() {
+                Ok(()) => {
+                    self.consumed = d.position();
+                    return minicbor::decode(&self.buffer[.. self.consumed])
+                        .map_err(Error::Decode)
+                        .map(Some)
+                }
+                Err(decode::Error::EndOfInput) => {}
+                Err(e) => return Err(Error::Decode(e))
+            }
+            if self.buffer.len() >= self.max_len {
+                return Err(Error::InvalidLen)
+            }
+            if self.fill()? == 0 {
+                return if self.buffer.is_empty() {
+                    Ok(None)
+                } else {
+                    Err(Error::Io(io::ErrorKind::UnexpectedEof.into()))
+                }
+            }
+        }
+    }
+
+    /// Read more bytes from the inner reader into the buffer.
+    fn fill(&mut self) -> Result<usize, Error> {
+        let len = self.buffer.len();
+        let add = CHUNK_SIZE.min(self.max_len - len);
+        self.buffer.resize(len + add, 0);
+        loop {
+            match self.reader.read(&mut self.buffer[len ..]) {
+                Ok(n) => {
+                    self.buffer.truncate(len + n);
+                    return Ok(n)
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.buffer.truncate(len);
+                    return Err(Error::Io(e))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::fmt;
+
+/// I/O statistics of a [`Reader`](crate::Reader) or [`Writer`](crate::Writer).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    items: u64,
+    bytes: u64,
+    decode_errors: u64,
+    max_item_len: usize
+}
+
+impl Stats {
+    /// Number of CBOR items read or written.
+    pub fn items(&self) -> u64 {
+        self.items
+    }
+
+    /// Number of bytes read or written, including length prefixes and
+    /// checksums.
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Number of items which failed to decode.
+    ///
+    /// This is always 0 for writers.
+    pub fn decode_errors(&self) -> u64 {
+        self.decode_errors
+    }
+
+    /// Length in bytes of the largest CBOR item seen.
+    pub fn max_item_len(&self) -> usize {
+        self.max_item_len
+    }
+
+    /// Account for an item of the given length, framed with `overhead`
+    /// additional bytes.
+    pub(crate) fn add_item(&mut self, len: usize, overhead: usize) {
+        self.items += 1;
+        self.bytes += (overhead + len) as u64;
+        self.max_item_len = self.max_item_len.max(len)
+    }
+
+    /// Account for an item which failed to decode.
+    pub(crate) fn add_decode_error(&mut self) {
+        self.decode_errors += 1
+    }
+}
+
+/// An I/O event reported to a hook.
+///
+/// Each event carries the length of the CBOR item in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Event {
+    /// A CBOR item has been read.
+    Read(usize),
+    /// A CBOR item has been written.
+    Written(usize),
+    /// A CBOR item could not be decoded.
+    DecodeError(usize)
+}
+
+/// An optional callback invoked for every I/O [`Event`].
+#[derive(Default)]
+pub(crate) struct Hook(Option<Box<dyn FnMut(Event) + Send + Sync>>);
+
+impl Hook {
+    pub(crate) fn new<F>(f: F) -> Self
+    where
+        F: FnMut(Event) + Send + Sync + 'static
+    {
+        Hook(Some(Box::new(f)))
+    }
+
+    pub(crate) fn call(&mut self, e: Event) {
+        if let Some(f) = &mut self.0 {
+            f(e)
+        }
+    }
+}
+
+impl fmt::Debug for Hook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Hook").field(&self.0.as_ref().map(|_| "FnMut")).finish()
+    }
+}
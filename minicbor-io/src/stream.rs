@@ -0,0 +1,118 @@
+use crate::{AsyncReader, AsyncWriter, Error};
+use futures_core::Stream;
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_util::{ready, sink::Sink};
+use minicbor::{Decode, Encode};
+use std::{fmt, marker::PhantomData, pin::Pin, task::{Context, Poll}};
+
+/// A [`Stream`] of values read with an [`AsyncReader`].
+///
+/// Created by [`AsyncReader::into_stream`]. Each item is a decoded value or
+/// an error. A decoding error only affects the current item, whereas I/O
+/// errors and [`Error::InvalidLen`] terminate the stream.
+///
+/// *Requires cargo feature* `"async-io"`.
+pub struct CborStream<R, T> {
+    reader: Option<AsyncReader<R>>,
+    _mark: PhantomData<fn() -> T>
+}
+
+impl<R, T> CborStream<R, T> {
+    pub(crate) fn new(reader: AsyncReader<R>) -> Self {
+        CborStream { reader: Some(reader), _mark: PhantomData }
+    }
+
+    /// Get back the underlying reader.
+    ///
+    /// Returns `None` if the stream has terminated with an error.
+    pub fn into_inner(self) -> Option<AsyncReader<R>> {
+        self.reader
+    }
+}
+
+impl<R: fmt::Debug, T> fmt::Debug for CborStream<R, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CborStream").field("reader", &self.reader).finish()
+    }
+}
+
+impl<R, T> Stream for CborStream<R, T>
+where
+    R: AsyncRead + Unpin,
+    T: for<'a> Decode<'a>
+{
+    type Item = Result<T, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if let Some(r) = &mut this.reader {
+            match ready!(r.poll_fill(cx)) {
+                Ok(true) => {
+                    let item = minicbor::decode(r.frame()).map_err(Error::Decode);
+                    return Poll::Ready(Some(item))
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    this.reader = None;
+                    return Poll::Ready(Some(Err(e)))
+                }
+            }
+        }
+        Poll::Ready(None)
+    }
+}
+
+/// A [`Sink`] of values written with an [`AsyncWriter`].
+///
+/// Created by [`AsyncWriter::into_sink`]. Flushing the sink writes all
+/// buffered data and flushes the inner `AsyncWrite`, closing the sink closes
+/// the inner `AsyncWrite`.
+///
+/// *Requires cargo feature* `"async-io"`.
+pub struct CborSink<W, T> {
+    writer: AsyncWriter<W>,
+    _mark: PhantomData<fn(T)>
+}
+
+impl<W, T> CborSink<W, T> {
+    pub(crate) fn new(writer: AsyncWriter<W>) -> Self {
+        CborSink { writer, _mark: PhantomData }
+    }
+
+    /// Get back the underlying writer.
+    pub fn into_inner(self) -> AsyncWriter<W> {
+        self.writer
+    }
+}
+
+impl<W: fmt::Debug, T> fmt::Debug for CborSink<W, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CborSink").field("writer", &self.writer).finish()
+    }
+}
+
+impl<W, T> Sink<T> for CborSink<W, T>
+where
+    W: AsyncWrite + Unpin,
+    T: Encode
+{
+    type Error = Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Error>> {
+        self.writer.poll_sync(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: T) -> Result<(), Error> {
+        self.writer.encode(item).map(|_| ())
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Error>> {
+        ready!(self.writer.poll_sync(cx))?;
+        Pin::new(self.writer.writer_mut()).poll_flush(cx).map_err(Error::Io)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Error>> {
+        ready!(self.as_mut().poll_flush(cx))?;
+        Pin::new(self.writer.writer_mut()).poll_close(cx).map_err(Error::Io)
+    }
+}
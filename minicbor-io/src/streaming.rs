@@ -0,0 +1,536 @@
+use crate::Error;
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_util::future::BoxFuture;
+use futures_util::{AsyncReadExt, AsyncWriteExt};
+use minicbor::data::{Tag, Type};
+use minicbor::{decode, Decode, Decoder, Encode};
+use std::io;
+
+/// The number of bytes to read from or write to the inner I/O object at once.
+const CHUNK_SIZE: usize = 8192;
+
+/// A type that can be encoded piecewise to an [`AsyncEncoder`].
+///
+/// Contrary to [`minicbor::Encode`], an implementation can write its CBOR
+/// representation in increments, e.g. a byte string of several megabytes
+/// which is streamed from a file in chunks and never held in memory as a
+/// whole.
+///
+/// *Requires cargo feature* `"async-io"`.
+///
+/// # Example
+///
+/// ```
+/// use futures_util::future::{BoxFuture, FutureExt};
+/// use futures_util::io::{AsyncRead, AsyncWrite, Cursor};
+/// use minicbor_io::{AsyncDecode, AsyncDecoder, AsyncEncode, AsyncEncoder, Error};
+///
+/// /// A file upload whose contents are copied from/to an I/O object.
+/// struct Upload<T> {
+///     name: String,
+///     size: u64,
+///     data: T
+/// }
+///
+/// impl<T: AsyncRead + Unpin + Send> AsyncEncode for Upload<T> {
+///     fn encode<'a, W>(&'a mut self, e: &'a mut AsyncEncoder<W>) -> BoxFuture<'a, Result<(), Error>>
+///     where
+///         W: AsyncWrite + Unpin + Send
+///     {
+///         async move {
+///             e.array(2).await?;
+///             e.encode(&self.name).await?;
+///             e.copy_bytes(self.size, &mut self.data).await
+///         }
+///         .boxed()
+///     }
+/// }
+///
+/// impl AsyncDecode for Upload<Vec<u8>> {
+///     fn decode<'a, R>(d: &'a mut AsyncDecoder<R>) -> BoxFuture<'a, Result<Self, Error>>
+///     where
+///         R: AsyncRead + Unpin + Send
+///     {
+///         async move {
+///             d.array().await?;
+///             let name = d.decode::<String>().await?;
+///             let mut data = Vec::new();
+///             let size = d.copy_bytes(&mut data).await?;
+///             Ok(Upload { name, size, data })
+///         }
+///         .boxed()
+///     }
+/// }
+///
+/// let rt = tokio::runtime::Builder::new_current_thread().build()?;
+/// rt.block_on(async {
+///     let content = vec![7; 100_000];
+///     let mut upload = Upload { name: "x.bin".into(), size: 100_000, data: Cursor::new(content.clone()) };
+///     let mut e = AsyncEncoder::new(Vec::new());
+///     upload.encode(&mut e).await?;
+///
+///     let mut d = AsyncDecoder::new(Cursor::new(e.into_parts().0));
+///     let upload = Upload::decode(&mut d).await?;
+///     assert_eq!("x.bin", upload.name);
+///     assert_eq!(content, upload.data);
+///     Ok::<_, Error>(())
+/// })?;
+///
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub trait AsyncEncode {
+    /// Encode this value piecewise to the given encoder.
+    fn encode<'a, W>(&'a mut self, e: &'a mut AsyncEncoder<W>) -> BoxFuture<'a, Result<(), Error>>
+    where
+        W: AsyncWrite + Unpin + Send;
+}
+
+/// A type that can be decoded piecewise from an [`AsyncDecoder`].
+///
+/// Contrary to [`minicbor::Decode`], an implementation does not require the
+/// complete CBOR item to be available in memory, but can consume it in
+/// increments as it arrives.
+///
+/// *Requires cargo feature* `"async-io"`.
+///
+/// See [`AsyncEncode`] for an example.
+pub trait AsyncDecode: Sized {
+    /// Decode a value piecewise from the given decoder.
+    fn decode<'a, R>(d: &'a mut AsyncDecoder<R>) -> BoxFuture<'a, Result<Self, Error>>
+    where
+        R: AsyncRead + Unpin + Send;
+}
+
+/// Wraps an [`AsyncWrite`] and writes CBOR items piecewise.
+///
+/// Contrary to [`AsyncWriter`](crate::AsyncWriter), values are not preceded
+/// by a length, but written as plain CBOR (or a CBOR sequence if more than
+/// one is written). Complete values can be encoded with
+/// [`AsyncEncoder::encode`], while [`AsyncEncoder::array`],
+/// [`AsyncEncoder::bytes_len`] etc. only write the head of a data item whose
+/// content follows in subsequent calls.
+///
+/// *Requires cargo feature* `"async-io"`.
+#[derive(Debug)]
+pub struct AsyncEncoder<W> {
+    writer: W,
+    buffer: Vec<u8>
+}
+
+impl<W> AsyncEncoder<W> {
+    /// Create a new encoder.
+    pub fn new(writer: W) -> Self {
+        Self::with_buffer(writer, Vec::new())
+    }
+
+    /// Create a new encoder which uses the given buffer for encoding values.
+    pub fn with_buffer(writer: W, buffer: Vec<u8>) -> Self {
+        Self { writer, buffer }
+    }
+
+    /// Get a reference to the inner writer.
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    /// Get a mutable reference to the inner writer.
+    pub fn writer_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Deconstruct this encoder into the inner writer and the buffer.
+    pub fn into_parts(self) -> (W, Vec<u8>) {
+        (self.writer, self.buffer)
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncEncoder<W> {
+    /// Encode and write a complete CBOR value.
+    ///
+    /// The value is encoded into a buffer first, i.e. this method is meant
+    /// for values of moderate size.
+    pub async fn encode<T: Encode>(&mut self, val: T) -> Result<(), Error> {
+        self.buffer.clear();
+        minicbor::encode(val, &mut self.buffer)?;
+        self.writer.write_all(&self.buffer).await?;
+        Ok(())
+    }
+
+    /// Write the head of an array with the given number of elements.
+    pub async fn array(&mut self, len: u64) -> Result<(), Error> {
+        self.head(0x80, len).await
+    }
+
+    /// Write the head of a map with the given number of entries.
+    pub async fn map(&mut self, len: u64) -> Result<(), Error> {
+        self.head(0xa0, len).await
+    }
+
+    /// Write a tag which applies to the next CBOR value.
+    pub async fn tag(&mut self, tag: Tag) -> Result<(), Error> {
+        self.buffer.clear();
+        minicbor::Encoder::new(&mut self.buffer).tag(tag)?;
+        self.writer.write_all(&self.buffer).await?;
+        Ok(())
+    }
+
+    /// Write the head of a byte string of the given length.
+    ///
+    /// The content must be written next with [`AsyncEncoder::write_all`]
+    /// and its total length must match `len`.
+    pub async fn bytes_len(&mut self, len: u64) -> Result<(), Error> {
+        self.head(0x40, len).await
+    }
+
+    /// Write the head of a text string of the given length in bytes.
+    ///
+    /// The UTF-8 encoded content must be written next with
+    /// [`AsyncEncoder::write_all`] and its total length must match `len`.
+    pub async fn str_len(&mut self, len: u64) -> Result<(), Error> {
+        self.head(0x60, len).await
+    }
+
+    /// Write raw bytes, e.g. the content of a string.
+    ///
+    /// No validation takes place, i.e. the caller is responsible for
+    /// producing well-formed CBOR.
+    pub async fn write_all(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.writer.write_all(bytes).await?;
+        Ok(())
+    }
+
+    /// Write a byte string of the given length whose content is read from
+    /// the given reader in chunks.
+    ///
+    /// If the reader ends before `len` bytes have been copied, an I/O error
+    /// of kind `UnexpectedEof` is returned.
+    pub async fn copy_bytes<R>(&mut self, len: u64, mut reader: R) -> Result<(), Error>
+    where
+        R: AsyncRead + Unpin
+    {
+        self.bytes_len(len).await?;
+        self.buffer.clear();
+        self.buffer.resize(CHUNK_SIZE, 0);
+        let mut remaining = len;
+        while remaining > 0 {
+            let k = remaining.min(CHUNK_SIZE as u64) as usize;
+            let n = reader.read(&mut self.buffer[.. k]).await?;
+            if n == 0 {
+                return Err(Error::Io(io::ErrorKind::UnexpectedEof.into()))
+            }
+            self.writer.write_all(&self.buffer[.. n]).await?;
+            remaining -= n as u64
+        }
+        Ok(())
+    }
+
+    /// Flush the inner `AsyncWrite`.
+    pub async fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush().await?;
+        Ok(())
+    }
+
+    /// Write a data item head of the given major type and argument.
+    async fn head(&mut self, major: u8, arg: u64) -> Result<(), Error> {
+        let mut b = [0; 9];
+        let n = match arg {
+            0 ..= 0x17 => {
+                b[0] = major | arg as u8;
+                1
+            }
+            0x18 ..= 0xff => {
+                b[0] = major | 24;
+                b[1] = arg as u8;
+                2
+            }
+            0x100 ..= 0xffff => {
+                b[0] = major | 25;
+                b[1 .. 3].copy_from_slice(&(arg as u16).to_be_bytes());
+                3
+            }
+            0x1_0000 ..= 0xffff_ffff => {
+                b[0] = major | 26;
+                b[1 .. 5].copy_from_slice(&(arg as u32).to_be_bytes());
+                5
+            }
+            _ => {
+                b[0] = major | 27;
+                b[1 ..].copy_from_slice(&arg.to_be_bytes());
+                9
+            }
+        };
+        self.write_all(&b[.. n]).await
+    }
+}
+
+/// Wraps an [`AsyncRead`] and reads CBOR items piecewise.
+///
+/// This is the counterpart of [`AsyncEncoder`]. Complete values can be
+/// decoded with [`AsyncDecoder::decode`]. They are buffered until complete,
+/// up to a max. buffer size (512 KiB by default). Large items can instead be
+/// consumed in increments, e.g. with [`AsyncDecoder::array`] followed by
+/// their elements or [`AsyncDecoder::bytes_len`] followed by
+/// [`AsyncDecoder::read`].
+///
+/// *Requires cargo feature* `"async-io"`.
+#[derive(Debug)]
+pub struct AsyncDecoder<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    consumed: usize,
+    max_len: usize
+}
+
+impl<R> AsyncDecoder<R> {
+    /// Create a new decoder with a max. buffer size of 512KiB.
+    pub fn new(reader: R) -> Self {
+        Self::with_buffer(reader, Vec::new())
+    }
+
+    /// Create a new decoder with a max. buffer size of 512KiB.
+    pub fn with_buffer(reader: R, mut buffer: Vec<u8>) -> Self {
+        buffer.clear();
+        Self { reader, buffer, consumed: 0, max_len: 512 * 1024 }
+    }
+
+    /// Set the max. buffer size in bytes.
+    ///
+    /// If a value decoded with [`AsyncDecoder::decode`] does not fit into a
+    /// buffer of this size, an [`Error::InvalidLen`] will be returned.
+    pub fn set_max_len(&mut self, val: u32) {
+        self.max_len = val as usize
+    }
+
+    /// Get the max. buffer size in bytes.
+    pub fn max_len(&self) -> u32 {
+        self.max_len as u32
+    }
+
+    /// Get a reference to the inner reader.
+    pub fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    /// Get a mutable reference to the inner reader.
+    pub fn reader_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Deconstruct this decoder into the inner reader and the buffer.
+    ///
+    /// The buffer contains bytes which have been read from the inner
+    /// reader but not yet been consumed.
+    pub fn into_parts(mut self) -> (R, Vec<u8>) {
+        self.buffer.drain(.. self.consumed);
+        (self.reader, self.buffer)
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncDecoder<R> {
+    /// Read and decode a complete CBOR value.
+    pub async fn decode<'a, T: Decode<'a>>(&'a mut self) -> Result<T, Error> {
+        let (n, ()) = self.item(|d| d.skip()).await?;
+        let start = self.consumed - n;
+        minicbor::decode(&self.buffer[start .. self.consumed]).map_err(Error::Decode)
+    }
+
+    /// Get the type of the next CBOR value without consuming it.
+    pub async fn datatype(&mut self) -> Result<Type, Error> {
+        self.peek(|d| d.datatype()).await
+    }
+
+    /// Read the head of an array and return its length.
+    ///
+    /// `None` denotes an array of indefinite length.
+    pub async fn array(&mut self) -> Result<Option<u64>, Error> {
+        self.item(|d| d.array()).await.map(|(_, n)| n)
+    }
+
+    /// Read the head of a map and return its length.
+    ///
+    /// `None` denotes a map of indefinite length.
+    pub async fn map(&mut self) -> Result<Option<u64>, Error> {
+        self.item(|d| d.map()).await.map(|(_, n)| n)
+    }
+
+    /// Read a tag.
+    pub async fn tag(&mut self) -> Result<Tag, Error> {
+        self.item(|d| d.tag()).await.map(|(_, t)| t)
+    }
+
+    /// Read the head of a byte string and return its length.
+    ///
+    /// `None` denotes a byte string of indefinite length. Otherwise the
+    /// content needs to be read next with [`AsyncDecoder::read`].
+    pub async fn bytes_len(&mut self) -> Result<Option<u64>, Error> {
+        self.string_len(Type::Bytes, Type::BytesIndef).await
+    }
+
+    /// Read the head of a text string and return its length in bytes.
+    ///
+    /// `None` denotes a text string of indefinite length. Otherwise the
+    /// content needs to be read next with [`AsyncDecoder::read`]. Note that
+    /// chunks of the content are not necessarily valid UTF-8 on their own.
+    pub async fn str_len(&mut self) -> Result<Option<u64>, Error> {
+        self.string_len(Type::String, Type::StringIndef).await
+    }
+
+    /// Consume the "break" which ends an item of indefinite length.
+    ///
+    /// Returns `false` and consumes nothing if the next item is not a break.
+    pub async fn end(&mut self) -> Result<bool, Error> {
+        if self.datatype().await? != Type::Break {
+            return Ok(false)
+        }
+        self.consumed += 1;
+        Ok(true)
+    }
+
+    /// Read raw bytes, e.g. the content of a string, into the given buffer.
+    ///
+    /// Returns the number of bytes read, which may be less than the length
+    /// of the buffer. 0 is returned only if the inner reader is exhausted
+    /// (or the buffer is empty). The caller must not read beyond the length
+    /// of the current string.
+    pub async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if self.consumed < self.buffer.len() {
+            let n = buf.len().min(self.buffer.len() - self.consumed);
+            buf[.. n].copy_from_slice(&self.buffer[self.consumed .. self.consumed + n]);
+            self.consumed += n;
+            return Ok(n)
+        }
+        Ok(self.reader.read(buf).await?)
+    }
+
+    /// Read a byte string and copy its content to the given writer in chunks.
+    ///
+    /// Byte strings of definite and indefinite length are supported. Returns
+    /// the total number of bytes copied.
+    pub async fn copy_bytes<W>(&mut self, mut writer: W) -> Result<u64, Error>
+    where
+        W: AsyncWrite + Unpin
+    {
+        let mut chunk = vec![0; CHUNK_SIZE];
+        if let Some(n) = self.bytes_len().await? {
+            self.copy_content(n, &mut writer, &mut chunk).await?;
+            return Ok(n)
+        }
+        let mut total = 0;
+        while !self.end().await? {
+            match self.bytes_len().await? {
+                Some(n) => {
+                    self.copy_content(n, &mut writer, &mut chunk).await?;
+                    total += n
+                }
+                None => {
+                    let e = decode::Error::TypeMismatch(Type::BytesIndef, "unexpected indefinite length");
+                    return Err(Error::Decode(e))
+                }
+            }
+        }
+        Ok(total)
+    }
+
+    /// Check if the inner reader is exhausted and no buffered bytes remain.
+    pub async fn is_eof(&mut self) -> Result<bool, Error> {
+        Ok(self.consumed == self.buffer.len() && self.fill().await? == 0)
+    }
+
+    /// Copy `len` content bytes to the writer, using `chunk` as buffer.
+    async fn copy_content<W>(&mut self, len: u64, writer: &mut W, chunk: &mut [u8]) -> Result<(), Error>
+    where
+        W: AsyncWrite + Unpin
+    {
+        let mut remaining = len;
+        while remaining > 0 {
+            let k = remaining.min(chunk.len() as u64) as usize;
+            let n = self.read(&mut chunk[.. k]).await?;
+            if n == 0 {
+                return Err(Error::Io(io::ErrorKind::UnexpectedEof.into()))
+            }
+            writer.write_all(&chunk[.. n]).await?;
+            remaining -= n as u64
+        }
+        Ok(())
+    }
+
+    /// Read the head of a string of the given definite or indefinite type.
+    async fn string_len(&mut self, def: Type, indef: Type) -> Result<Option<u64>, Error> {
+        self.item(|d| {
+            let t = d.datatype()?;
+            if t != def && t != indef {
+                return Err(decode::Error::TypeMismatch(t, "unexpected type"))
+            }
+            Ok(d.read_head()?.argument())
+        })
+        .await
+        .map(|(_, n)| n)
+    }
+
+    /// Apply `f` to the buffered bytes without consuming them.
+    ///
+    /// More bytes are read as long as `f` fails with an end of input error.
+    async fn peek<T, F>(&mut self, f: F) -> Result<T, Error>
+    where
+        F: Fn(&mut Decoder) -> Result<T, decode::Error>
+    {
+        self.apply(false, f).await.map(|(_, x)| x)
+    }
+
+    /// Like [`AsyncDecoder::peek`] but consumes the bytes `f` decoded and
+    /// returns their number.
+    async fn item<T, F>(&mut self, f: F) -> Result<(usize, T), Error>
+    where
+        F: Fn(&mut Decoder) -> Result<T, decode::Error>
+    {
+        self.apply(true, f).await
+    }
+
+    async fn apply<T, F>(&mut self, consume: bool, f: F) -> Result<(usize, T), Error>
+    where
+        F: Fn(&mut Decoder) -> Result<T, decode::Error>
+    {
+        loop {
+            let mut d = Decoder::new(&self.buffer[self.consumed ..]);
+            match f(&mut d) {
+                Ok(x) => {
+                    let n = d.position();
+                    if consume {
+                        self.consumed += n
+                    }
+                    return Ok((n, x))
+                }
+                Err(decode::Error::EndOfInput) => {}
+                Err(e) => return Err(Error::Decode(e))
+            }
+            if self.buffer.len() - self.consumed >= self.max_len {
+                return Err(Error::InvalidLen)
+            }
+            if self.fill().await? == 0 {
+                return Err(Error::Io(io::ErrorKind::UnexpectedEof.into()))
+            }
+        }
+    }
+
+    /// Discard consumed bytes and read more bytes from the inner reader.
+    async fn fill(&mut self) -> Result<usize, Error> {
+        self.buffer.drain(.. self.consumed);
+        self.consumed = 0;
+        let len = self.buffer.len();
+        let add = CHUNK_SIZE.min(self.max_len.saturating_sub(len));
+        self.buffer.resize(len + add, 0);
+        loop {
+            match self.reader.read(&mut self.buffer[len ..]).await {
+                Ok(n) => {
+                    self.buffer.truncate(len + n);
+                    return Ok(n)
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.buffer.truncate(len);
+                    return Err(Error::Io(e))
+                }
+            }
+        }
+    }
+}
@@ -1,13 +1,18 @@
-use crate::Error;
+use crate::{BufferPool, Checksum, Error, Event, Stats};
+use crate::stats::Hook;
 use minicbor::Encode;
-use std::io;
+use std::{io, mem};
 
 /// Wraps a [`std::io::Write`] and writes length-delimited CBOR values.
 #[derive(Debug)]
 pub struct Writer<W> {
     writer: W,
     buffer: Vec<u8>,
-    max_len: usize
+    max_len: usize,
+    checksum: Option<Checksum>,
+    stats: Stats,
+    hook: Hook,
+    pool: Option<BufferPool>
 }
 
 impl<W> Writer<W> {
@@ -18,7 +23,27 @@ impl<W> Writer<W> {
 
     /// Create a new writer with a max. buffer size of 512KiB.
     pub fn with_buffer(writer: W, buffer: Vec<u8>) -> Self {
-        Self { writer, buffer, max_len: 512 * 1024 }
+        Self {
+            writer,
+            buffer,
+            max_len: 512 * 1024,
+            checksum: None,
+            stats: Stats::default(),
+            hook: Hook::default(),
+            pool: None
+        }
+    }
+
+    /// Create a new writer which appends the given checksum to every frame.
+    pub fn with_checksum(writer: W, checksum: Checksum) -> Self {
+        let mut this = Self::new(writer);
+        this.checksum = Some(checksum);
+        this
+    }
+
+    /// Get the checksum appended to every frame, if any.
+    pub fn checksum(&self) -> Option<Checksum> {
+        self.checksum
     }
 
     /// Set the max. buffer size in bytes.
@@ -29,6 +54,32 @@ impl<W> Writer<W> {
         self.max_len = val as usize
     }
 
+    /// Get the max. buffer size in bytes.
+    pub fn max_len(&self) -> u32 {
+        self.max_len as u32
+    }
+
+    /// Take frame buffers from the given pool.
+    ///
+    /// A buffer is taken from the pool for every value written and returned
+    /// to the pool once the frame has been written.
+    pub fn set_pool(&mut self, pool: BufferPool) {
+        self.pool = Some(pool)
+    }
+
+    /// Get the I/O statistics of this writer.
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// Set a callback to invoke whenever an item has been written.
+    pub fn set_hook<F>(&mut self, f: F)
+    where
+        F: FnMut(Event) + Send + Sync + 'static
+    {
+        self.hook = Hook::new(f)
+    }
+
     /// Get a reference to the inner writer.
     pub fn writer(&self) -> &W {
         &self.writer
@@ -48,15 +99,16 @@ impl<W> Writer<W> {
 impl<W: io::Write> Writer<W> {
     /// Encode and write a CBOR value and return its size in bytes.
     pub fn write<T: Encode>(&mut self, val: T) -> Result<usize, Error> {
-        self.buffer.resize(4, 0u8);
-        minicbor::encode(val, &mut self.buffer)?;
-        if self.buffer.len() - 4 > self.max_len {
-            return Err(Error::InvalidLen)
+        if let Some(p) = self.pool.clone() {
+            if self.buffer.capacity() == 0 {
+                self.buffer = p.get()
+            }
+            let result = self.write_frame(val);
+            p.put(mem::take(&mut self.buffer));
+            result
+        } else {
+            self.write_frame(val)
         }
-        let prefix = (self.buffer.len() as u32 - 4).to_be_bytes();
-        self.buffer[.. 4].copy_from_slice(&prefix);
-        self.writer.write_all(&self.buffer)?;
-        Ok(self.buffer.len() - 4)
     }
 
     /// Flush the inner `Write`r.
@@ -64,4 +116,27 @@ impl<W: io::Write> Writer<W> {
         self.writer.flush()?;
         Ok(())
     }
+
+    /// Encode a CBOR value into the buffer and write it as one frame.
+    fn write_frame<T: Encode>(&mut self, val: T) -> Result<usize, Error> {
+        self.buffer.resize(4, 0u8);
+        minicbor::encode(val, &mut self.buffer)?;
+        if self.buffer.len() - 4 > self.max_len {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(len = self.buffer.len() - 4, max_len = self.max_len, "frame length exceeds max.");
+            return Err(Error::InvalidLen)
+        }
+        let len = self.buffer.len() - 4;
+        self.buffer[.. 4].copy_from_slice(&(len as u32).to_be_bytes());
+        if let Some(c) = self.checksum {
+            let sum = c.compute(&self.buffer);
+            self.buffer.extend_from_slice(&sum.to_be_bytes())
+        }
+        self.writer.write_all(&self.buffer)?;
+        self.stats.add_item(len, self.buffer.len() - len);
+        self.hook.call(Event::Written(len));
+        #[cfg(feature = "tracing")]
+        tracing::trace!(len, "wrote frame");
+        Ok(len)
+    }
 }
@@ -1,9 +1,13 @@
+use futures_util::future::{BoxFuture, FutureExt};
+use futures_util::io::{AsyncRead, AsyncWrite, Cursor};
+use futures_util::{SinkExt, StreamExt, stream};
 use minicbor::{Encode, Decode};
-use minicbor_io::{AsyncReader, AsyncWriter, Error};
+use minicbor_io::{AsyncDecode, AsyncDecoder, AsyncEncode, AsyncEncoder, AsyncReader, AsyncWriter, BufferPool, Error, Writer};
 use quickcheck::{Arbitrary, Gen};
 use rand::Rng;
 use std::io;
 use std::net::SocketAddr;
+use tokio::io::AsyncWriteExt;
 use tokio::net::{TcpListener, TcpStream};
 use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
 
@@ -105,6 +109,175 @@ async fn read_write_identity() {
     join.await.unwrap().unwrap()
 }
 
+/// Cancel pending reads at various points within a frame and check that
+/// subsequent reads resume without losing data.
+#[tokio::test]
+async fn cancelled_reads_resume() {
+    let mut w = Writer::new(Vec::new());
+    w.write("hello world").unwrap();
+    w.write(42u32).unwrap();
+    let bytes = w.into_parts().0;
+
+    let (mut tx, rx) = tokio::io::duplex(64);
+    let mut reader = AsyncReader::new(rx.compat());
+
+    // Feed the first frame in pieces, splitting both the length prefix
+    // and the value, and cancel the pending read after each piece.
+    for chunk in [&bytes[.. 2], &bytes[2 .. 7], &bytes[7 .. 12]] {
+        tx.write_all(chunk).await.unwrap();
+        tokio::select! {
+            biased;
+            _ = reader.read::<&str>() => panic!("frame is incomplete"),
+            _ = std::future::ready(()) => {}
+        }
+    }
+
+    tx.write_all(&bytes[12 ..]).await.unwrap();
+    drop(tx);
+    assert_eq!(Some("hello world"), reader.read::<&str>().await.unwrap());
+    assert_eq!(Some(42), reader.read::<u32>().await.unwrap());
+    assert_eq!(None, reader.read::<u32>().await.unwrap())
+}
+
+/// Share a buffer pool between an async writer and reader.
+#[tokio::test]
+async fn pooled_buffers() {
+    let pool = BufferPool::new();
+
+    let mut writer = AsyncWriter::new(Cursor::new(Vec::new()));
+    writer.set_pool(pool.clone());
+    writer.write("hello").await.unwrap();
+    writer.write(42u32).await.unwrap();
+    assert_eq!(1, pool.len());
+
+    let mut reader = AsyncReader::new(Cursor::new(writer.into_parts().0.into_inner()));
+    reader.set_pool(pool.clone());
+    assert_eq!(Some("hello"), reader.read::<&str>().await.unwrap());
+    assert!(pool.is_empty());
+    assert_eq!(Some(42), reader.read::<u32>().await.unwrap());
+    assert!(pool.is_empty());
+    assert_eq!(None, reader.read::<u32>().await.unwrap());
+    assert_eq!(1, pool.len())
+}
+
+/// Send values through a `CborSink` and receive them from a `CborStream`.
+#[tokio::test]
+async fn stream_sink_identity() {
+    let (tx, rx) = tokio::io::duplex(64);
+    let mut sink = AsyncWriter::new(tx.compat_write()).into_sink::<(u32, String)>();
+    let stream = AsyncReader::new(rx.compat()).into_stream::<(u32, String)>();
+
+    let items: Vec<(u32, String)> = (0 .. 100).map(|i| (i, i.to_string())).collect();
+    let send = async {
+        sink.send_all(&mut stream::iter(items.clone()).map(Ok)).await.unwrap();
+        sink.close().await.unwrap()
+    };
+    let recv = stream.map(Result::unwrap).collect::<Vec<_>>();
+    let ((), received) = tokio::join!(send, recv);
+    assert_eq!(items, received)
+}
+
+/// A value with a large byte string which is never held in memory as a
+/// whole by the encoder.
+struct Blob {
+    id: u32,
+    len: u64,
+    data: Cursor<Vec<u8>>
+}
+
+impl AsyncEncode for Blob {
+    fn encode<'a, W>(&'a mut self, e: &'a mut AsyncEncoder<W>) -> BoxFuture<'a, Result<(), Error>>
+    where
+        W: AsyncWrite + Unpin + Send
+    {
+        async move {
+            e.array(2).await?;
+            e.encode(self.id).await?;
+            e.copy_bytes(self.len, &mut self.data).await
+        }
+        .boxed()
+    }
+}
+
+impl AsyncDecode for Blob {
+    fn decode<'a, R>(d: &'a mut AsyncDecoder<R>) -> BoxFuture<'a, Result<Self, Error>>
+    where
+        R: AsyncRead + Unpin + Send
+    {
+        async move {
+            assert_eq!(Some(2), d.array().await?);
+            let id = d.decode().await?;
+            let mut data = Cursor::new(Vec::new());
+            let len = d.copy_bytes(&mut data).await?;
+            data.set_position(0);
+            Ok(Blob { id, len, data })
+        }
+        .boxed()
+    }
+}
+
+/// Stream values larger than the decoder's max. buffer size piecewise.
+#[tokio::test]
+async fn piecewise_identity() {
+    let (tx, rx) = tokio::io::duplex(64);
+    let mut e = AsyncEncoder::new(tx.compat_write());
+    let mut d = AsyncDecoder::new(rx.compat());
+    d.set_max_len(1024);
+
+    let content: Vec<u8> = (0 .. 4 * 1024 * 1024).map(|i| i as u8).collect();
+    let send = async {
+        for id in 0 .. 3 {
+            let mut b = Blob { id, len: content.len() as u64, data: Cursor::new(content.clone()) };
+            b.encode(&mut e).await.unwrap()
+        }
+        e.encode("done").await.unwrap();
+        e.flush().await.unwrap();
+        drop(e)
+    };
+    let recv = async {
+        for id in 0 .. 3 {
+            let b = Blob::decode(&mut d).await.unwrap();
+            assert_eq!(id, b.id);
+            assert_eq!(content.len() as u64, b.len);
+            assert!(content == b.data.into_inner())
+        }
+        assert_eq!("done", d.decode::<&str>().await.unwrap());
+        assert!(d.is_eof().await.unwrap())
+    };
+    tokio::join!(send, recv);
+}
+
+#[tokio::test]
+async fn piecewise_decoding() {
+    // ["ab", (_ h'0102', h'03'), "xyz", 1]
+    let cbor = b"\x84\x62ab\x5f\x42\x01\x02\x41\x03\xff\x63xyz\x01".to_vec();
+    let mut d = AsyncDecoder::new(Cursor::new(cbor));
+    assert_eq!(Some(4), d.array().await.unwrap());
+    assert!(d.bytes_len().await.is_err());
+    assert_eq!(Some(2), d.str_len().await.unwrap());
+    let mut buf = [0; 2];
+    assert_eq!(2, d.read(&mut buf).await.unwrap());
+    assert_eq!(b"ab", &buf);
+    let mut out = Vec::new();
+    assert_eq!(3, d.copy_bytes(&mut out).await.unwrap());
+    assert_eq!(vec![1, 2, 3], out);
+    assert!(!d.end().await.unwrap());
+    assert_eq!("xyz", d.decode::<&str>().await.unwrap());
+    assert!(!d.is_eof().await.unwrap());
+    assert_eq!(1, d.decode::<u8>().await.unwrap());
+    assert!(d.is_eof().await.unwrap());
+    assert!(matches!(d.decode::<u8>().await, Err(Error::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof));
+
+    // Complete values must fit into the buffer.
+    let mut e = AsyncEncoder::new(Vec::new());
+    e.encode([0u8; 16]).await.unwrap();
+    e.str_len(5).await.unwrap();
+    e.write_all(b"hello").await.unwrap();
+    let mut d = AsyncDecoder::new(Cursor::new(e.into_parts().0));
+    d.set_max_len(16);
+    assert!(matches!(d.decode::<[u8; 16]>().await, Err(Error::InvalidLen)))
+}
+
 #[derive(Debug, Encode, Decode)]
 enum Command<T> {
     #[n(0)] Stop,
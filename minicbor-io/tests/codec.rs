@@ -0,0 +1,46 @@
+use bytes::BytesMut;
+use minicbor_io::{CborCodec, Error};
+use tokio_util::codec::{Decoder, Encoder};
+
+quickcheck::quickcheck! {
+    fn encode_decode_identity(data: Vec<(u64, String)>) -> bool {
+        let mut c = CborCodec::<Vec<(u64, String)>>::new();
+        let mut b = BytesMut::new();
+        c.encode(&data, &mut b).unwrap();
+        let val = c.decode(&mut b).unwrap().unwrap();
+        val == data && b.is_empty()
+    }
+}
+
+#[test]
+fn partial_frames() {
+    let mut c = CborCodec::<String>::new();
+    let mut b = BytesMut::new();
+    c.encode("hello", &mut b).unwrap();
+    c.encode("world", &mut b).unwrap();
+
+    let all = b.split();
+    for i in 0 .. all.len() {
+        b.extend_from_slice(&all[i .. i + 1]);
+        match c.decode(&mut b).unwrap() {
+            None => {}
+            Some(s) if i == 9  => assert_eq!("hello", s),
+            Some(s) if i == 19 => assert_eq!("world", s),
+            Some(s) => panic!("unexpected value {} at {}", s, i)
+        }
+    }
+    assert!(b.is_empty())
+}
+
+#[test]
+fn max_len() {
+    let mut c = CborCodec::<String>::new();
+    c.set_max_len(4);
+    let mut b = BytesMut::new();
+    assert!(matches!(c.encode("hello", &mut b), Err(Error::InvalidLen)));
+    assert!(b.is_empty());
+
+    let mut d = CborCodec::<String>::new();
+    d.encode("hello", &mut b).unwrap();
+    assert!(matches!(c.decode(&mut b), Err(Error::InvalidLen)))
+}
@@ -0,0 +1,67 @@
+use minicbor_io::Checksum;
+use minicbor_io::embedded::{Error, Reader, Writer};
+
+#[test]
+fn read_write_identity() {
+    let mut out = [0; 64];
+    let mut buf = [0; 16];
+
+    let mut sink = &mut out[..];
+    let mut w = Writer::new(&mut sink, &mut buf);
+    assert_eq!(6, w.write("hello").unwrap());
+    assert_eq!(3, w.write(1000u32).unwrap());
+    let rest = sink.len();
+    let n = out.len() - rest;
+
+    let mut buf = [0; 8];
+    let mut r = Reader::new(&out[.. n], &mut buf);
+    assert_eq!(Some("hello"), r.read::<&str>().unwrap());
+    assert_eq!(Some(1000), r.read::<u32>().unwrap());
+    assert_eq!(None, r.read::<u32>().unwrap())
+}
+
+#[test]
+fn buffer_limits() {
+    let mut out = [0; 64];
+    let mut buf = [0; 12];
+    let mut w = Writer::new(&mut out[..], &mut buf);
+    assert!(matches!(w.write("hello world"), Err(Error::InvalidLen)));
+    w.write("hello").unwrap();
+    let mut buf = [0; 4];
+    let mut r = Reader::new(&out[..], &mut buf);
+    assert!(matches!(r.read::<&str>(), Err(Error::InvalidLen)));
+
+    let mut buf = [0; 8];
+    let mut r = Reader::new(&out[.. 6], &mut buf);
+    assert!(matches!(r.read::<&str>(), Err(Error::UnexpectedEof)))
+}
+
+#[test]
+fn checksums() {
+    let mut out = [0; 64];
+    let mut buf = [0; 14];
+
+    let mut sink = &mut out[..];
+    let mut w = Writer::with_checksum(&mut sink, &mut buf, Checksum::Crc32);
+    assert!(matches!(w.write("hello world"), Err(Error::InvalidLen)));
+    assert_eq!(6, w.write("hello").unwrap());
+    let rest = sink.len();
+    let n = out.len() - rest;
+    assert_eq!(14, n);
+    assert_eq!(&out[10 .. 14], &Checksum::Crc32.compute(&out[.. 10]).to_be_bytes());
+
+    let mut buf = [0; 8];
+    let mut r = Reader::with_checksum(&out[.. n], &mut buf, Checksum::Crc32);
+    assert_eq!(Some("hello"), r.read::<&str>().unwrap());
+    assert_eq!(None, r.read::<&str>().unwrap());
+
+    let mut r = Reader::with_checksum(&out[.. n], &mut buf, Checksum::XxHash32);
+    assert!(matches!(r.read::<&str>(), Err(Error::Checksum)));
+
+    out[5] ^= 1;
+    let mut r = Reader::with_checksum(&out[.. n], &mut buf, Checksum::Crc32);
+    assert!(matches!(r.read::<&str>(), Err(Error::Checksum)));
+
+    let mut r = Reader::with_checksum(&out[.. n - 1], &mut buf, Checksum::Crc32);
+    assert!(matches!(r.read::<&str>(), Err(Error::UnexpectedEof)))
+}
@@ -1,8 +1,24 @@
 use minicbor::bytes::ByteSlice;
-use minicbor_io::{Reader, Writer};
+use minicbor::decode;
+use minicbor_io::{BufferPool, Checksum, DatagramSocket, Error, Event, Reader, SeqReader, Writer};
+use std::net::UdpSocket;
 use std::io;
+use std::sync::{Arc, Mutex};
 
 quickcheck::quickcheck! {
+    fn seq_read_identity(data: Vec<(u64, String)>) -> bool {
+        let mut bytes = Vec::new();
+        for x in &data {
+            minicbor::encode(x, &mut bytes).unwrap();
+        }
+        let mut r = SeqReader::new(OneByte(io::Cursor::new(bytes)));
+        let mut out = Vec::new();
+        while let Some(x) = r.read::<(u64, String)>().unwrap() {
+            out.push(x)
+        }
+        out == data
+    }
+
     fn read_write_bytes_identity(data: Vec<u8>) -> bool {
         let mut d = data;
         d.truncate(512 * 1024);
@@ -25,3 +41,276 @@ quickcheck::quickcheck! {
         val == num
     }
 }
+
+#[test]
+fn seq_read_errors() {
+    // An incomplete array at the end of the input.
+    let mut r = SeqReader::new(io::Cursor::new(vec![0x01, 0x82, 0x01]));
+    assert_eq!(Some(1), r.read::<u8>().unwrap());
+    assert!(matches!(r.read::<Vec<u8>>(), Err(Error::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof));
+
+    // A value exceeding the max. buffer size.
+    let mut bytes = Vec::new();
+    minicbor::encode([0u8; 16], &mut bytes).unwrap();
+    let mut r = SeqReader::new(io::Cursor::new(bytes));
+    r.set_max_len(8);
+    assert!(matches!(r.read::<[u8; 16]>(), Err(Error::InvalidLen)))
+}
+
+#[test]
+fn read_resumes_after_would_block() {
+    let mut w = Writer::new(Vec::new());
+    w.write("hello").unwrap();
+    w.write("world").unwrap();
+
+    let mut r = Reader::new(Blocking(OneByte(io::Cursor::new(w.into_parts().0)), false));
+    let mut out = Vec::new();
+    loop {
+        match r.read::<String>() {
+            Ok(Some(s)) => out.push(s),
+            Ok(None)    => break,
+            Err(Error::Io(e)) if e.kind() == io::ErrorKind::WouldBlock => continue,
+            Err(e)      => panic!("{}", e)
+        }
+    }
+    assert_eq!(vec!["hello", "world"], out)
+}
+
+#[test]
+fn read_rejects_large_frames() {
+    let mut r = Reader::new(io::Cursor::new(vec![0xff, 0xff, 0xff, 0xff]));
+    assert!(matches!(r.read::<&str>(), Err(Error::InvalidLen)))
+}
+
+#[test]
+fn read_frame_borrows_buffer() {
+    let mut w = Writer::new(Vec::new());
+    w.write(("a", 1u8)).unwrap();
+
+    let mut r = Reader::new(io::Cursor::new(w.into_parts().0));
+    let frame = r.read_frame().unwrap().unwrap();
+    assert_eq!(&[0x82, 0x61, b'a', 0x01][..], frame);
+    let (s, n): (&str, u8) = minicbor::decode(frame).unwrap();
+    assert_eq!(("a", 1), (s, n));
+    assert!(r.read_frame().unwrap().is_none())
+}
+
+#[test]
+fn stats_and_hooks() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let mut w = Writer::new(Vec::new());
+    w.set_hook({
+        let events = events.clone();
+        move |e| events.lock().unwrap().push(e)
+    });
+    w.write("abc").unwrap();
+    w.write(1u8).unwrap();
+    assert_eq!(2, w.stats().items());
+    assert_eq!(13, w.stats().bytes());
+    assert_eq!(4, w.stats().max_item_len());
+
+    let mut r = Reader::new(io::Cursor::new(w.into_parts().0));
+    r.set_hook({
+        let events = events.clone();
+        move |e| events.lock().unwrap().push(e)
+    });
+    assert!(matches!(r.read::<u8>(), Err(Error::Decode(_))));
+    assert_eq!(Some(1), r.read::<u8>().unwrap());
+    assert_eq!(None, r.read::<u8>().unwrap());
+    assert_eq!(2, r.stats().items());
+    assert_eq!(13, r.stats().bytes());
+    assert_eq!(1, r.stats().decode_errors());
+    assert_eq!(4, r.stats().max_item_len());
+
+    let expected = [
+        Event::Written(4),
+        Event::Written(1),
+        Event::Read(4),
+        Event::DecodeError(4),
+        Event::Read(1)
+    ];
+    assert_eq!(&expected[..], &events.lock().unwrap()[..]);
+}
+
+#[test]
+fn tracing_events() {
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{field::{Field, Visit}, Event as TraceEvent, Metadata, Subscriber};
+
+    /// A subscriber which collects the messages of all events.
+    struct Messages(Arc<Mutex<Vec<String>>>);
+
+    impl Visit for &Messages {
+        fn record_debug(&mut self, f: &Field, v: &dyn std::fmt::Debug) {
+            if f.name() == "message" {
+                self.0.lock().unwrap().push(format!("{:?}", v))
+            }
+        }
+    }
+
+    impl Subscriber for Messages {
+        fn enabled(&self, _: &Metadata) -> bool { true }
+        fn new_span(&self, _: &Attributes) -> Id { Id::from_u64(1) }
+        fn record(&self, _: &Id, _: &Record) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, e: &TraceEvent) { e.record(&mut &*self) }
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    let messages = Arc::new(Mutex::new(Vec::new()));
+
+    tracing::subscriber::with_default(Messages(messages.clone()), || {
+        let mut w = Writer::new(Vec::new());
+        w.write("abc").unwrap();
+        let mut r = Reader::new(io::Cursor::new(w.into_parts().0));
+        assert!(r.read::<u8>().is_err());
+        let mut r = Reader::new(io::Cursor::new(vec![0, 0, 0, 2, 0x18, 0x20]));
+        r.set_max_len(1);
+        assert!(r.read::<u8>().is_err())
+    });
+
+    let expected = ["wrote frame", "read frame", "decode error", "frame length exceeds max."];
+    assert_eq!(&expected[..], &messages.lock().unwrap()[..]);
+}
+
+/// A reader which fails with `WouldBlock` on every other call.
+struct Blocking<R>(R, bool);
+
+impl<R: io::Read> io::Read for Blocking<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.1 = !self.1;
+        if self.1 {
+            return Err(io::ErrorKind::WouldBlock.into())
+        }
+        self.0.read(buf)
+    }
+}
+
+/// A reader which returns at most one byte at a time.
+struct OneByte<R>(R);
+
+impl<R: io::Read> io::Read for OneByte<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = buf.len().min(1);
+        self.0.read(&mut buf[.. n])
+    }
+}
+
+#[test]
+fn checksums() {
+    // Known answers for inputs longer than one xxHash stripe.
+    let input = b"Nobody inspects the spammish repetition";
+    assert_eq!(0xe2293b2f, Checksum::XxHash32.compute(input));
+    assert_eq!(0x02cc5d05, Checksum::XxHash32.compute(b""));
+    assert_eq!(0x00000000, Checksum::Crc32.compute(b""));
+    assert_eq!(0xad4270ed, Checksum::Crc32.compute(input));
+    let bytes = (0 ..= 255).cycle().take(1024).collect::<Vec<u8>>();
+    assert_eq!(0xb70b4c26, Checksum::Crc32.compute(&bytes));
+
+    for c in [Checksum::Crc32, Checksum::XxHash32] {
+        let mut w = Writer::with_checksum(Vec::new(), c);
+        w.write("hello").unwrap();
+        w.write((1u8, "world")).unwrap();
+        assert_eq!(2, w.stats().items());
+        let bytes = w.into_parts().0;
+        assert_eq!(&bytes[10 .. 14], &c.compute(&bytes[.. 10]).to_be_bytes());
+
+        let mut r = Reader::with_checksum(OneByte(io::Cursor::new(bytes.clone())), c);
+        assert_eq!(Some(c), r.checksum());
+        assert_eq!(Some("hello"), r.read::<&str>().unwrap());
+        assert_eq!(Some((1, "world")), r.read::<(u8, &str)>().unwrap());
+        assert_eq!(None, r.read::<u8>().unwrap());
+        assert_eq!(bytes.len() as u64, r.stats().bytes());
+
+        // A corrupted byte is detected.
+        let mut corrupt = bytes.clone();
+        corrupt[6] ^= 1;
+        let mut r = Reader::with_checksum(io::Cursor::new(corrupt), c);
+        assert!(matches!(r.read::<&str>(), Err(Error::Checksum)));
+
+        // A reader without checksum does not understand the frames.
+        let mut r = Reader::new(io::Cursor::new(bytes));
+        assert_eq!(Some("hello"), r.read::<&str>().unwrap());
+        assert!(r.read::<(u8, &str)>().is_err())
+    }
+}
+
+#[test]
+fn buffer_pool() {
+    let pool = BufferPool::with_limits(2, 64);
+
+    // Writers return their buffers to the pool after every frame.
+    let mut writers = (0 .. 3).map(|_| {
+        let mut w = Writer::new(Vec::new());
+        w.set_pool(pool.clone());
+        w
+    })
+    .collect::<Vec<_>>();
+    for w in &mut writers {
+        w.write("hello").unwrap();
+        assert_eq!(1, pool.len())
+    }
+    writers[0].write([0u8; 128].as_slice()).unwrap();
+    assert_eq!(1, pool.len());
+
+    // Large buffers are shrunk and at most 2 idle buffers are retained.
+    let b1 = pool.get();
+    assert!(b1.capacity() <= 64);
+    pool.put(vec![0; 1024]);
+    pool.put(vec![0; 1024]);
+    pool.put(vec![0; 1024]);
+    assert_eq!(2, pool.len());
+    assert!(pool.get().capacity() <= 64);
+    pool.put(b1);
+    assert_eq!(2, pool.len());
+
+    // Readers only hold a buffer while a frame can be borrowed.
+    let pool = BufferPool::new();
+    let frames = writers.swap_remove(0).into_parts().0;
+    let mut r = Reader::new(Blocking(OneByte(io::Cursor::new(frames)), false));
+    r.set_pool(pool.clone());
+    let mut out = Vec::new();
+    loop {
+        match r.read_frame() {
+            Ok(Some(_)) => out.push(pool.len()),
+            Ok(None)    => break,
+            Err(Error::Io(e)) if e.kind() == io::ErrorKind::WouldBlock => continue,
+            Err(e)      => panic!("{}", e)
+        }
+    }
+    assert_eq!(vec![0, 0], out);
+    assert_eq!(1, pool.len());
+    assert_eq!(0, r.into_parts().1.capacity())
+}
+
+#[test]
+fn datagrams() {
+    let mut a = DatagramSocket::new(UdpSocket::bind("127.0.0.1:0").unwrap());
+    let mut b = DatagramSocket::new(UdpSocket::bind("127.0.0.1:0").unwrap());
+    let addr_a = a.socket().local_addr().unwrap();
+    let addr_b = b.socket().local_addr().unwrap();
+    a.set_mtu(16);
+    assert_eq!(16, a.mtu());
+
+    assert_eq!(8, a.send_to((1u8, "hello"), addr_b).unwrap());
+    assert_eq!(((1, "hello"), addr_a), b.recv_from::<(u8, &str)>().unwrap());
+
+    // Values exceeding the MTU are not sent.
+    assert!(matches!(a.send_to("too long for the mtu", addr_b), Err(Error::InvalidLen)));
+
+    // Oversized datagrams and trailing bytes are rejected.
+    b.socket().send_to(&[0x40; 20], addr_a).unwrap();
+    assert!(matches!(a.recv_from::<&str>(), Err(Error::InvalidLen)));
+    b.socket().send_to(&[0x01, 0x02], addr_a).unwrap();
+    assert!(matches!(a.recv_from::<u8>(), Err(Error::Decode(decode::Error::TrailingBytes(1)))));
+
+    // Connected sockets.
+    a.socket().connect(addr_b).unwrap();
+    b.socket().connect(addr_a).unwrap();
+    a.send(42u32).unwrap();
+    assert_eq!(42, b.recv::<u32>().unwrap());
+    b.send("pong").unwrap();
+    assert_eq!("pong", a.recv::<&str>().unwrap())
+}
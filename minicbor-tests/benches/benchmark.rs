@@ -1,5 +1,7 @@
 use criterion::{criterion_group, criterion_main, Criterion};
-use minicbor::{Encode, Decode};
+use minicbor::{Encode, Decode, Decoder};
+use minicbor::data::Type;
+use minicbor::value::{Interner, Value};
 use rand::{distributions::Alphanumeric, prelude::*};
 use serde::{Serialize, Deserialize};
 use std::{borrow::Cow, iter};
@@ -66,6 +68,74 @@ fn benchmark(c: &mut Criterion) {
         let _: AddressBook = minicbor::decode(&book_bytes_minicbor).unwrap();
     }));
     group.finish();
+
+    let mut group = c.benchmark_group("value");
+    let records = gen_records(1000);
+    group.bench_function("naive", |b| b.iter(|| {
+        let _: Naive = minicbor::decode(&records).unwrap();
+    }));
+    group.bench_function("value", |b| b.iter(|| {
+        let _: Value = minicbor::decode(&records).unwrap();
+    }));
+    let mut interner = Interner::new();
+    group.bench_function("value-interned", |b| b.iter(|| {
+        let _: Value = interner.decode(&mut Decoder::new(&records)).unwrap();
+    }));
+    group.finish();
+}
+
+/// A straightforward CBOR value type to compare `Value` with.
+#[allow(dead_code)]
+#[derive(Debug)]
+enum Naive {
+    Int(i128),
+    Float(f64),
+    Bool(bool),
+    Null,
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Naive>),
+    Map(Vec<(Naive, Naive)>),
+    Tag(minicbor::data::Tag, Box<Naive>)
+}
+
+impl<'b> Decode<'b> for Naive {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, minicbor::decode::Error> {
+        match d.datatype()? {
+            Type::Bool => Ok(Naive::Bool(d.bool()?)),
+            Type::Null => { d.skip()?; Ok(Naive::Null) }
+            Type::U8 | Type::U16 | Type::U32 | Type::U64 => Ok(Naive::Int(d.u64()?.into())),
+            Type::I8 | Type::I16 | Type::I32 | Type::I64 => Ok(Naive::Int(d.i64()?.into())),
+            Type::F16 | Type::F32 | Type::F64 => Ok(Naive::Float(d.f64()?)),
+            Type::Bytes  => Ok(Naive::Bytes(d.bytes()?.to_vec())),
+            Type::String => Ok(Naive::Text(d.str()?.to_string())),
+            Type::Array  => Ok(Naive::Array(d.array_iter()?.collect::<Result<_, _>>()?)),
+            Type::Map    => Ok(Naive::Map(d.map_iter()?.collect::<Result<_, _>>()?)),
+            Type::Tag    => Ok(Naive::Tag(d.tag()?, Box::new(Naive::decode(d)?))),
+            t => Err(minicbor::decode::Error::TypeMismatch(t, "unsupported type"))
+        }
+    }
+}
+
+/// Generate an array of JSON-like records with the same keys.
+fn gen_records(n: usize) -> Vec<u8> {
+    let mut g = rand::thread_rng();
+    let mut e = minicbor::Encoder::new(Vec::new());
+    e.array(n as u64).unwrap();
+    for i in 0 .. n {
+        e.map(5).unwrap()
+            .str("id").unwrap().u64(i as u64).unwrap()
+            .str("name").unwrap().str(&gen_string(&mut g, 12)).unwrap()
+            .str("description of the record").unwrap().str(&gen_string(&mut g, 64)).unwrap()
+            .str("balance").unwrap().f64(g.gen()).unwrap()
+            .str("tags").unwrap().array(3).unwrap()
+                .str("a").unwrap().str("bb").unwrap().i32(-7).unwrap();
+    }
+    e.into_inner()
+}
+
+fn gen_string(g: &mut ThreadRng, n: usize) -> String {
+    iter::repeat_with(|| char::from(g.sample(Alphanumeric))).take(n).collect()
 }
 
 fn gen_addressbook(n: usize) -> AddressBook<'static> {
@@ -0,0 +1,51 @@
+use minicbor::{CborIndex, Decode, Encode, FromCborIndex};
+use std::marker::PhantomData;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, CborIndex)]
+#[cbor(index_only)]
+enum Kind {
+    #[n(3)] A,
+    #[n(1)] B,
+    #[n(7)] C
+}
+
+#[derive(Debug, PartialEq, Encode, Decode, CborIndex)]
+enum Message<'a, T> {
+    #[n(0)] Ping,
+    #[n(4)] Data(#[n(0)] T),
+    #[b(9)] Text { #[b(0)] text: &'a str },
+    #[n(2)] Kind(#[n(0)] Kind)
+}
+
+#[derive(Debug, PartialEq, CborIndex)]
+enum Empty {}
+
+#[derive(Debug, PartialEq, CborIndex)]
+enum Phantom<T> {
+    #[n(0)] A,
+    #[n(1)] B(#[n(0)] PhantomData<T>)
+}
+
+#[test]
+fn index_matches_encoding() {
+    for k in [Kind::A, Kind::B, Kind::C] {
+        let cbor = minicbor::to_vec(k).unwrap();
+        assert_eq!(k.index(), minicbor::decode::<u64>(&cbor).unwrap());
+        assert_eq!(Some(k), Kind::from_index(k.index()))
+    }
+    assert_eq!(None, Kind::from_index(0));
+    assert_eq!(None, Kind::from_index(u64::MAX));
+
+    let msgs = [Message::Ping, Message::Data(1u8), Message::Text { text: "x" }, Message::Kind(Kind::C)];
+    for (m, i) in msgs.iter().zip([0, 4, 9, 2]) {
+        assert_eq!(i, m.index());
+        let cbor = minicbor::to_vec(m).unwrap();
+        let mut d = minicbor::Decoder::new(&cbor);
+        assert_eq!(Some(2), d.array().unwrap());
+        assert_eq!(i, d.u64().unwrap())
+    }
+
+    assert_eq!(0, Phantom::<u8>::A.index());
+    assert_eq!(1, Phantom::<u8>::B(PhantomData).index());
+    assert_eq!(None, <Empty as FromCborIndex>::from_index(0))
+}
@@ -0,0 +1,52 @@
+use minicbor::cbor;
+use minicbor::data::Tag;
+use minicbor::value::Value;
+use minicbor::Encoder;
+
+#[test]
+fn scalars() {
+    assert_eq!(Value::null(), cbor!(null));
+    assert_eq!(Value::undefined(), cbor!(undefined));
+    assert_eq!(Value::simple(99), cbor!(simple(99)));
+    assert_eq!(Value::from(true), cbor!(true));
+    assert_eq!(Value::from(-7), cbor!(-7));
+    assert_eq!(Value::from(1.5), cbor!(1.5));
+    assert_eq!(Value::from("abc"), cbor!("abc"));
+    assert_eq!(Value::bytes(b"abc"), cbor!(b"abc"));
+    assert_eq!(Value::bytes(&[0xde, 0xad, 0xbe, 0xef]), cbor!(h("DE AD be ef")));
+    assert_eq!(Value::tag(1, Value::from(0)), cbor!(1(0)))
+}
+
+#[test]
+fn containers() {
+    assert_eq!(Value::array(vec![]), cbor!([]));
+    assert_eq!(Value::map(vec![]), cbor!({}));
+
+    let x = 42u8;
+    let v = cbor!([1, [null, [], {}], { "a" => x, [1] => {2 => 3}, }, 24(h("01")), undefined,]);
+    let mut e = Encoder::new(Vec::new());
+    e.array(5).unwrap()
+        .u8(1).unwrap()
+        .array(3).unwrap().null().unwrap().array(0).unwrap().map(0).unwrap()
+        .map(2).unwrap()
+            .str("a").unwrap().u8(42).unwrap()
+            .array(1).unwrap().u8(1).unwrap().map(1).unwrap().u8(2).unwrap().u8(3).unwrap()
+        .tag(Tag::Cbor).unwrap().bytes(&[1]).unwrap()
+        .undefined().unwrap();
+    assert_eq!(e.into_inner(), minicbor::to_vec(&v).unwrap())
+}
+
+#[test]
+fn interpolation() {
+    let name = String::from("minicbor");
+    let n = -1i64;
+    let v = cbor!({ "name" => name.clone(), "n" => n, "sum" => 1 + 2, 1 + 1 => simple(16), "t" => 0("x") });
+    let expected = Value::map(vec![
+        (Value::from("name"), Value::from(name)),
+        (Value::from("n"), Value::from(-1)),
+        (Value::from("sum"), Value::from(3)),
+        (Value::from(2), Value::simple(16)),
+        (Value::from("t"), Value::tag(0, Value::from("x")))
+    ]);
+    assert_eq!(expected, v)
+}
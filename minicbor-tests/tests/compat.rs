@@ -1,5 +1,7 @@
 //! Test forward and backward compatibility.
 
+#![allow(clippy::disallowed_names, clippy::match_like_matches_macro)]
+
 use minicbor::{Encode, Decode};
 use quickcheck::{Arbitrary, Gen, quickcheck};
 use std::{borrow::Cow, fmt};
@@ -334,3 +336,74 @@ impl Arbitrary for Enum3<'static> {
         }
     }
 }
+
+#[test]
+fn unknown_variant_and_index_errors() {
+    #[derive(Debug, Encode, Decode)]
+    #[cbor(deny_unknown_fields)]
+    struct Strict {
+        #[n(0)] field_a: u32
+    }
+
+    #[derive(Debug, Encode, Decode)]
+    #[cbor(map, deny_unknown_fields)]
+    struct StrictMap {
+        #[n(0)] field_a: u32
+    }
+
+    let v2 = Version2 { field_a: 1, field_b: Some(Cow::Borrowed("x")), field_c: None };
+    let bytes = minicbor::to_vec(&v2).unwrap();
+
+    match minicbor::decode::<Strict>(&bytes) {
+        Err(minicbor::decode::Error::UnknownIndex(1, "Strict")) => {}
+        other => panic!("unexpected result: {:?}", other)
+    }
+    assert!(minicbor::decode::<Version3>(&bytes).is_ok());
+
+    let v2 = Version2 { field_a: 1, field_b: None, field_c: None };
+    let bytes = minicbor::to_vec(&v2).unwrap();
+    assert!(minicbor::decode::<Strict>(&bytes).is_ok());
+
+    let bytes = minicbor::to_vec(&Enum3::Con2 { foo: 1, bar: None }).unwrap();
+    match minicbor::decode::<Enum2>(&bytes) {
+        Err(minicbor::decode::Error::UnknownVariant(1, "Enum2")) => {}
+        other => panic!("unexpected result: {:?}", other)
+    }
+
+    // {0: 1, 7: 1}
+    let bytes = [0xa2, 0x00, 0x01, 0x07, 0x01];
+    match minicbor::decode::<StrictMap>(&bytes) {
+        Err(minicbor::decode::Error::UnknownIndex(7, "StrictMap")) => {}
+        other => panic!("unexpected result: {:?}", other)
+    }
+}
+
+#[test]
+fn all_missing_values() {
+    #[derive(Debug, Encode, Decode)]
+    #[cbor(collect_missing)]
+    struct Required {
+        #[n(0)] field_a: u32,
+        #[n(1)] field_b: Option<bool>,
+        #[n(2)] field_c: bool,
+        #[n(4)] field_d: u8
+    }
+
+    let bytes = minicbor::to_vec(&Version1 { field_a: 1, field_b: None }).unwrap();
+    match minicbor::decode::<Required>(&bytes) {
+        Err(minicbor::decode::Error::MissingValues(m)) => {
+            assert_eq!(2, m.len());
+            let v = m.iter().collect::<Vec<_>>();
+            assert_eq!(vec![(2, "Required::field_c"), (4, "Required::field_d")], v)
+        }
+        other => panic!("unexpected result: {:?}", other)
+    }
+
+    let bytes = minicbor::to_vec(&Version3 { field_a: 1, field_c: Some(true) }).unwrap();
+    match minicbor::decode::<Required>(&bytes) {
+        Err(e@minicbor::decode::Error::MissingValues(_)) => {
+            assert_eq!("missing values: Required::field_d (index 4)", e.to_string())
+        }
+        other => panic!("unexpected result: {:?}", other)
+    }
+}
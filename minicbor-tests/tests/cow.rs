@@ -0,0 +1,67 @@
+//! Test `Cow` impls and borrowing of `Cow` fields.
+
+use minicbor::{Decode, Encode};
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn cow_slices_are_arrays() {
+    let xs: Cow<[u16]> = Cow::Borrowed(&[1, 1000]);
+    let cbor = minicbor::to_vec(&xs).unwrap();
+    assert_eq!(minicbor::to_vec([1u16, 1000]).unwrap(), cbor);
+    let ys: Cow<[u16]> = minicbor::decode(&cbor).unwrap();
+    assert!(matches!(ys, Cow::Owned(_)));
+    assert_eq!(xs, ys)
+}
+
+#[test]
+fn paths() {
+    let p = Path::new("/tmp/file.cbor");
+    let cbor = minicbor::to_vec(p).unwrap();
+    assert_eq!(minicbor::to_vec("/tmp/file.cbor").unwrap(), cbor);
+    assert_eq!(cbor, minicbor::to_vec(p.to_path_buf()).unwrap());
+    assert_eq!(p, minicbor::decode::<&Path>(&cbor).unwrap());
+    assert_eq!(p, minicbor::decode::<PathBuf>(&cbor).unwrap());
+    assert_eq!(p, minicbor::decode::<Cow<Path>>(&cbor).unwrap())
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_paths_are_rejected() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let p = Path::new(OsStr::from_bytes(b"/tmp/\xff"));
+    assert!(minicbor::to_vec(p).is_err())
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+struct Borrowing<'a> {
+    #[b(0)] path: Cow<'a, Path>,
+    #[cbor(n(1), with = "minicbor::bytes")] data: Cow<'a, [u8]>,
+    #[cbor(n(2), with = "minicbor::bytes")] owned: Vec<u8>
+}
+
+#[test]
+fn borrowed_cow_fields() {
+    let b = Borrowing {
+        path: Cow::Owned(PathBuf::from("a/b")),
+        data: Cow::Owned(vec![1, 2, 3]),
+        owned: vec![4]
+    };
+    let cbor = minicbor::to_vec(&b).unwrap();
+    assert_eq!(b"\x83\x63a/b\x43\x01\x02\x03\x41\x04", cbor.as_slice());
+    let c: Borrowing = minicbor::decode(&cbor).unwrap();
+    assert!(matches!(c.path, Cow::Borrowed(_)));
+    assert!(matches!(c.data, Cow::Borrowed(_)));
+    assert_eq!(b, c)
+}
+
+#[test]
+fn indefinite_bytes_are_owned() {
+    let cbor = b"\x83\x61a\x5f\x41\x01\x42\x02\x03\xff\x40";
+    let c: Borrowing = minicbor::decode(cbor).unwrap();
+    assert!(matches!(c.path, Cow::Borrowed(_)));
+    assert!(matches!(c.data, Cow::Owned(_)));
+    assert_eq!([1, 2, 3], &*c.data)
+}
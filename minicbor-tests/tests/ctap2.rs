@@ -0,0 +1,56 @@
+use minicbor::ctap2;
+use std::collections::BTreeMap;
+
+fn hex(s: &str) -> Vec<u8> {
+    hex::decode(s).unwrap()
+}
+
+#[test]
+fn normalise() {
+    let cases = [
+        ("1817",                        "17"),
+        ("5f4101420203ff",              "43010203"),
+        ("9f0102ff",                    "820102"),
+        ("a3616101182001200f",          "a3182001200f616101"),
+        ("a2626262006161f5",            "a26161f562626200"),
+        ("a241000120f5",                "a220f5410001"),
+        ("fb3ff8000000000000",          "f93e00"),
+        ("fb3ff0000000000001",          "fb3ff0000000000001"),
+        ("fa7fc00001",                  "f97e00"),
+        ("f7",                          "f7"),
+        ("a10102",                      "a10102")
+    ];
+    for (input, expected) in cases.iter() {
+        let input = hex(input);
+        let expected = hex(expected);
+        assert_eq!(expected, ctap2::normalise(&input).unwrap());
+        assert!(ctap2::validate(&expected).is_ok());
+        assert_eq!(input == expected, ctap2::validate(&input).is_ok())
+    }
+}
+
+#[test]
+fn reject() {
+    let cases = [
+        "c11a514b67b0",         // tag
+        "a201020103",           // duplicate key
+        "0102",                 // trailing bytes
+        "5f01ff"                // invalid chunk
+    ];
+    for input in cases.iter() {
+        assert!(ctap2::normalise(&hex(input)).is_err(), "{}", input);
+        assert!(ctap2::validate(&hex(input)).is_err(), "{}", input)
+    }
+}
+
+#[test]
+fn encode_decode() {
+    let mut m = BTreeMap::new();
+    m.insert(-2i32, "b");
+    m.insert(1000, "c");
+    m.insert(3, "a");
+    let cbor = ctap2::to_vec(&m).unwrap();
+    assert_eq!(hex("a3036161 1903e86163 216162".replace(' ', "").as_str()), cbor);
+    assert_eq!(m, ctap2::decode::<BTreeMap<i32, &str>>(&cbor).unwrap());
+    assert!(ctap2::decode::<BTreeMap<i32, &str>>(&minicbor::to_vec(&m).unwrap()).is_err())
+}
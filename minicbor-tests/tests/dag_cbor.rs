@@ -0,0 +1,73 @@
+use minicbor::dag_cbor::{self, Cid};
+use std::collections::BTreeMap;
+
+fn hex(s: &str) -> Vec<u8> {
+    hex::decode(s).unwrap()
+}
+
+#[test]
+fn normalise() {
+    let cases = [
+        ("1817",                    "17"),
+        ("3a00000001",              "21"),
+        ("5f4101420203ff",          "43010203"),
+        ("7f61616162ff",            "626162"  ),
+        ("9f0102ff",                "820102"),
+        ("bf6162016161f5ff",        "a26161f5616201"  ),
+        ("a3626262006161016163f6",  "a36161016163f662626200"),
+        ("f93e00",                  "fb3ff8000000000000"),
+        ("fa3fc00000",              "fb3ff8000000000000"),
+        ("d82a450001020304",        "d82a450001020304"),
+        ("820102",                  "820102")
+    ];
+    for (input, expected) in cases.iter() {
+        let input = hex(input);
+        let expected = hex(&expected.replace(' ', ""));
+        assert_eq!(expected, dag_cbor::normalise(&input).unwrap());
+        assert!(dag_cbor::validate(&expected).is_ok());
+        assert_eq!(input == expected, dag_cbor::validate(&input).is_ok())
+    }
+}
+
+#[test]
+fn reject() {
+    let cases = [
+        "f7",                   // undefined
+        "f0",                   // unassigned simple value
+        "f97e00",               // NaN
+        "fa7f800000",           // infinity
+        "a10102",               // non-text key
+        "a2616101616102",       // duplicate key
+        "c11a514b67b0",         // tag 1
+        "0102",                 // trailing bytes
+        "5f01ff"                // invalid chunk
+    ];
+    for input in cases.iter() {
+        assert!(dag_cbor::normalise(&hex(input)).is_err(), "{}", input);
+        assert!(dag_cbor::validate(&hex(input)).is_err(), "{}", input)
+    }
+}
+
+#[test]
+fn encode_decode() {
+    let mut m = BTreeMap::new();
+    m.insert("zz", vec![1.5f32]);
+    m.insert("aaa", vec![]);
+    let cbor = dag_cbor::to_vec(&m).unwrap();
+    assert_eq!(hex("a2627a7a81fb3ff800000000000063616161 80".replace(' ', "").as_str()), cbor);
+    let n: BTreeMap<&str, Vec<f64>> = dag_cbor::decode(&cbor).unwrap();
+    assert_eq!(n["zz"], [1.5]);
+    assert!(dag_cbor::decode::<BTreeMap<&str, Vec<f32>>>(&minicbor::to_vec(&m).unwrap()).is_err());
+    assert!(dag_cbor::to_vec(f32::INFINITY).is_err())
+}
+
+#[test]
+fn cid() {
+    let cid = Cid::new(vec![1, 0x71, 0x12, 0x20]);
+    let cbor = dag_cbor::to_vec(&cid).unwrap();
+    assert_eq!(hex("d82a450001711220"), cbor);
+    assert_eq!(cid, dag_cbor::decode(&cbor).unwrap());
+    assert!(dag_cbor::decode::<Cid>(&hex("d82a4101")).is_err());
+    assert!(dag_cbor::decode::<Cid>(&hex("d82a4100")).is_err());
+    assert!(dag_cbor::decode::<Cid>(&hex("d82b4100")).is_err())
+}
@@ -0,0 +1,85 @@
+use arbitrary::{Arbitrary, Unstructured};
+use minicbor::{Decode, Encode};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+#[derive(Debug, PartialEq, Encode, Decode, minicbor::Arbitrary)]
+#[cbor(map)]
+struct Message<'a> {
+    #[n(0)] id: u64,
+    #[b(1)] text: Cow<'a, str>,
+    #[n(2)] kind: Kind,
+    #[n(3)] payload: Option<Payload<u16>>,
+    #[n(5)] attrs: BTreeMap<String, i32>,
+    #[cbor(n(6), with = "minicbor::bytes")] raw: Vec<u8>
+}
+
+#[derive(Debug, PartialEq, Encode, Decode, minicbor::Arbitrary)]
+#[cbor(index_only)]
+enum Kind {
+    #[n(0)] Request,
+    #[n(4)] Response
+}
+
+#[derive(Debug, PartialEq, Encode, Decode, minicbor::Arbitrary)]
+enum Payload<T> {
+    #[n(0)] Empty,
+    #[n(1)] Values(#[n(0)] Vec<T>, #[n(1)] bool),
+    #[n(2)] Nested { #[n(0)] inner: Box<Payload<T>> }
+}
+
+#[derive(Debug, PartialEq, Encode, Decode, minicbor::Arbitrary)]
+#[cbor(transparent)]
+struct Id(#[n(0)] u32);
+
+fn round_trip<'a, T>(data: &'a [u8])
+where
+    T: Arbitrary<'a> + Encode + for<'b> Decode<'b> + PartialEq + Debug
+{
+    let mut u = Unstructured::new(data);
+    if let Ok(x) = T::arbitrary(&mut u) {
+        let cbor = minicbor::to_vec(&x).unwrap();
+        assert_eq!(x, minicbor::decode::<T>(&cbor).unwrap())
+    }
+}
+
+#[test]
+fn arbitrary_values_round_trip() {
+    let mut rng = StdRng::seed_from_u64(0x4d1c_b0a2);
+    let mut kinds = [0; 2];
+    for _ in 0 .. 500 {
+        let len  = rng.gen_range(0 .. 256);
+        let data = (0 .. len).map(|_| rng.gen()).collect::<Vec<u8>>();
+        round_trip::<Payload<u16>>(&data);
+        round_trip::<Id>(&data);
+        round_trip::<Kind>(&data);
+
+        let mut u = Unstructured::new(&data);
+        if let Ok(m) = Message::arbitrary(&mut u) {
+            let cbor = minicbor::to_vec(&m).unwrap();
+            assert_eq!(m, minicbor::decode::<Message>(&cbor).unwrap());
+            kinds[(m.kind == Kind::Response) as usize] += 1
+        }
+    }
+    assert!(kinds.iter().all(|n| *n > 0))
+}
+
+// Deriving `Decode` does not implement `Arbitrary`, so it can be derived
+// with the `arbitrary` crate instead.
+#[derive(Debug, PartialEq, Encode, Decode, Arbitrary)]
+enum Other {
+    #[n(0)] A(#[n(0)] u8),
+    #[n(1)] B { #[n(0)] x: Option<bool> }
+}
+
+#[test]
+fn decode_with_arbitrary_derive() {
+    let mut rng = StdRng::seed_from_u64(0x1f2e_3d4c);
+    for _ in 0 .. 100 {
+        let data = (0 .. 16).map(|_| rng.gen()).collect::<Vec<u8>>();
+        round_trip::<Other>(&data)
+    }
+}
@@ -0,0 +1,116 @@
+use minicbor::{Decode, DecodeInPlace, Decoder, Encode};
+use minicbor::decode::DecodeInPlace as _;
+
+fn check(cbor: &[u8]) -> bool {
+    let mut d = Decoder::new(cbor);
+    d.check_deterministic().is_ok() && d.position() == cbor.len()
+}
+
+#[test]
+fn deterministic_items() {
+    for h in [
+        "00", "17", "1818", "190100", "1a00010000", "1b0000000100000000", "37", "3818",
+        "40", "4101", "60", "6161", "80", "820102", "a0", "a201000200", "a2016161416100",
+        "a261610162616100", "c11a514b67b0", "f4", "f6", "f820", "f93c00", "f97e00",
+        "fa47c35000", "fa7fc00001", "fb3ff199999999999a", "fb7ff8000000000001"
+    ] {
+        assert!(check(&hex::decode(h).unwrap()), "{}", h)
+    }
+}
+
+#[test]
+fn non_deterministic_items() {
+    for h in [
+        // arguments not in shortest form
+        "1817", "1900ff", "1a0000ffff", "1b00000000ffffffff", "3817", "5800", "7800",
+        "9800", "b800", "d800f6", "c11900ff",
+        // indefinite lengths
+        "5fff", "7fff", "9fff", "bfff", "ff",
+        // map keys not sorted or not unique
+        "a202000100", "a201000100", "a2616201616100", "a2410100410000",
+        // floats not in shortest form
+        "fa3f800000", "fb3ff0000000000000", "fb4059000000000000", "fa7fc00000",
+        "fb7ff8000000000000",
+        // malformed
+        "f801", "fc", "1c", "6161ff"
+    ] {
+        assert!(!check(&hex::decode(h).unwrap()), "{}", h)
+    }
+    // Nested items are checked too.
+    assert!(!check(&hex::decode("81a202000100").unwrap()));
+    assert!(!check(&hex::decode("a1811817f6").unwrap()))
+}
+
+#[test]
+fn max_depth() {
+    let mut d = Decoder::new(&[0x81, 0x81, 0x81, 0x00]);
+    d.set_max_depth(3);
+    assert!(d.check_deterministic().is_err());
+    d.set_position(0);
+    d.set_max_depth(4);
+    assert!(d.check_deterministic().is_ok());
+
+    // Deeply nested items do not exhaust the call stack.
+    let mut x = vec![0x81; 100_000];
+    x.push(0x00);
+    let mut d = Decoder::new(&x);
+    d.set_max_depth(usize::MAX);
+    assert!(d.check_deterministic().is_ok());
+    assert_eq!(x.len(), d.position())
+}
+
+#[derive(Debug, PartialEq, Encode, Decode, DecodeInPlace)]
+#[cbor(map, require_canonical)]
+struct Signed {
+    #[cbor(n(0), with = "minicbor::bytes")] payload: Vec<u8>,
+    #[n(1)] nonce: u64
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+struct Envelope {
+    #[n(0)] signed: Signed,
+    #[n(1)] note: u32
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+#[cbor(require_canonical)]
+enum Message {
+    #[n(0)] Ping,
+    #[n(1)] Data(#[n(0)] u32)
+}
+
+#[derive(Debug, PartialEq, Encode, Decode, DecodeInPlace)]
+#[cbor(transparent, require_canonical)]
+struct Id(#[n(0)] u64);
+
+#[test]
+fn derived_types() {
+    let s = Signed { payload: vec![1, 2], nonce: 100 };
+    let cbor = minicbor::to_vec(&s).unwrap();
+    assert_eq!(s, minicbor::decode(&cbor).unwrap());
+
+    // {0: h'0102', 1: 100} with a non-minimal nonce, unsorted keys, indefinite bytes
+    // or a non-minimal unknown field
+    for h in ["a2004201020119 0064", "a2011864004201 02", "a2005f420102ff011864", "a30042010201186403190001"] {
+        let b = hex::decode(h.replace(' ', "")).unwrap();
+        assert!(minicbor::decode::<Signed>(&b).is_err(), "{}", h);
+        let mut t = Signed { payload: Vec::new(), nonce: 0 };
+        assert!(t.decode_into(&mut Decoder::new(&b)).is_err(), "{}", h)
+    }
+
+    // Only the annotated sub-structure is checked.
+    let e = hex::decode("829f a2004201020118 64 ff 1900 01".replace(' ', "")).unwrap();
+    assert!(minicbor::decode::<Envelope>(&e).is_err());
+    let e = hex::decode("82 a2004201020118 64 1900 01".replace(' ', "")).unwrap();
+    let v: Envelope = minicbor::decode(&e).unwrap();
+    assert_eq!(Envelope { signed: s, note: 1 }, v);
+
+    assert_eq!(Message::Data(1), minicbor::decode(b"\x82\x01\x81\x01").unwrap());
+    assert!(minicbor::decode::<Message>(b"\x82\x01\x81\x18\x01").is_err());
+    assert!(minicbor::decode::<Message>(b"\x82\x01\x9f\x01\xff").is_err());
+
+    assert_eq!(Id(24), minicbor::decode(b"\x18\x18").unwrap());
+    assert!(minicbor::decode::<Id>(b"\x19\x00\x18").is_err());
+    let mut id = Id(0);
+    assert!(id.decode_into(&mut Decoder::new(b"\x19\x00\x18")).is_err())
+}
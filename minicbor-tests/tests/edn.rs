@@ -0,0 +1,94 @@
+use minicbor::edn::{from_edn, value_from_edn, Error};
+use minicbor::value::Value;
+
+fn hex(s: &str) -> Vec<u8> {
+    hex::decode(s).unwrap()
+}
+
+#[test]
+fn edn_to_cbor() {
+    let cases = [
+        ("0",                               "00"),
+        ("-0",                              "00"),
+        ("+24",                             "1818"),
+        ("-1000",                           "3903e7"),
+        ("0x10, 0o10, 0b10",                "100802"),
+        ("-0x10",                           "2f"),
+        ("18446744073709551615",            "1bffffffffffffffff"),
+        ("18446744073709551616",            "c249010000000000000000"),
+        ("-18446744073709551616",           "3bffffffffffffffff"),
+        ("-18446744073709551617",           "c349010000000000000000"),
+        ("1_0, 1_1, 1_2, 1_3",              "18011900011a000000011b0000000000000001"),
+        ("1.5, 1.5_2, 1.5_3",               "f93e00fa3fc00000fb3ff8000000000000"),
+        ("1.1, 1e5, -0.0",                  "fb3ff199999999999afa47c35000f98000"),
+        ("Infinity, -Infinity, NaN",        "f97c00f9fc00f97e00"),
+        ("simple(0), simple(19), simple(32)", "e0f3f820"),
+        ("false, true, null, undefined",    "f4f5f6f7"),
+        ("1_1(h'')",                        "d9000140"),
+        ("'abc'",                           "43616263"),
+        ("h'0A 0b' h'ff'",                  "430a0bff"),
+        ("\"a\" 'b' h'63'",                 "63616263"),
+        ("h'01 /one/ 02 # two\n 03'",       "43010203"),
+        ("b64'AQID', b64'-_8', b64'+/8='",  "4301020342fbff42fbff"),
+        ("b32'MFRGG', h32'C5H66'",          "4361626343616263"),
+        ("<<1, 2>>",                        "420102"),
+        ("<<>>",                            "40"),
+        ("[_ <<[]>>]",                      "9f4180ff"),
+        ("(_ 'a', h'62')",                  "5f41614162ff"),
+        ("(_ \"a\", \"b\")",                "7f61616162ff"),
+        ("''_, \"\"_",                      "5fff7fff"),
+        ("[1, 2,]",                         "820102"),
+        ("{1: 2, 3: 4,}",                   "a201020304"),
+        ("{_ 1: [_ ]}",                     "bf019fffff"),
+        ("\"\\u00e9\\ud83d\\ude00\"",       "66c3a9f09f9880"),
+        ("/ comment / 1 # another\n, 2",    "0102"),
+        ("",                                ""),
+    ];
+    for (edn, cbor) in cases.iter() {
+        assert_eq!(hex(cbor), from_edn(edn).expect(edn), "{}", edn)
+    }
+}
+
+#[test]
+fn roundtrip_display() {
+    let cases = [
+        r#"0"#, r#"-1000"#, r#"18446744073709551615"#, r#"-18446744073709551616"#,
+        r#"0e0"#, r#"-0e0"#, r#"1.1e0"#, r#"6.5504e4"#, r#"3.4028235e38"#, r#"1e300"#,
+        r#"5.9604645e-8"#, r#"Infinity"#, r#"-Infinity"#, r#"NaN"#,
+        r#"simple(16)"#, r#"simple(255)"#, r#"0("2013-03-21T20:04:00Z")"#,
+        r#"1(1.3638962405e9)"#, r#"24(h'64 49 45 54 46')"#, r#"h''"#, r#""\"\\""#,
+        r#""a\nb\u0001""#, r#"(_ h'01 02', h'03 04 05')"#, r#"(_ "strea", "ming")"#,
+        r#"[_ 1, [2, 3], [_ 4, 5]]"#, r#"{_ "Fun": true, "Amt": -2}"#, r#"''_"#, r#"{_ }"#
+    ];
+    for edn in cases.iter() {
+        let cbor = from_edn(edn).expect(edn);
+        assert_eq!(*edn, minicbor::display(&cbor).to_string());
+    }
+    let seq = cases.join(", ");
+    let cbor = from_edn(&seq).unwrap();
+    assert_eq!(seq, minicbor::display(&cbor).to_string());
+}
+
+#[test]
+fn values() {
+    let v = value_from_edn(r#"{"a": [1, -2, h'ff'], 3: 4("x")}"#).unwrap();
+    let x = minicbor::cbor!({"a" => [1, -2, h("ff")], 3 => 4("x")});
+    assert_eq!(x, v);
+    assert_eq!(Value::from(1.5), value_from_edn("1.5_3").unwrap());
+    assert!(matches!(value_from_edn("1, 2"), Err(Error::Decode(minicbor::decode::Error::TrailingBytes(1)))));
+    assert!(matches!(value_from_edn(""), Err(Error::Decode(_))))
+}
+
+#[test]
+fn errors() {
+    let cases = [
+        "[1 2]", "[1,,2]", "{1}", "{1: }", "1,", "(_ )", "(_ 'a', \"b\")", "h'0'", "h'xy'",
+        "b64'A'", "x'00'", "foo", "simple(24)", "simple(256)", "\"\\ud800\"", "\"a", "<<1",
+        "1.", "1e", "0x", "1_4", "-NaN", "/ unterminated", "[_ 1"
+    ];
+    for edn in cases.iter() {
+        assert!(matches!(from_edn(edn), Err(Error::Syntax(..))), "{}: {:?}", edn, from_edn(edn))
+    }
+    assert!(from_edn(&"[".repeat(5000)).is_err());
+    assert!(from_edn(&"<<".repeat(5000)).is_err())
+}
@@ -0,0 +1,82 @@
+use minicbor::{Decode, Encode};
+use minicbor::decode::Error;
+use minicbor::bytes::ByteVec;
+use minicbor::encoded::{Base64, Base64Url, EncodedCbor, Hex, RawCbor};
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+struct Envelope<'a> {
+    #[n(0)] protected: EncodedCbor<Header>,
+    #[b(1)] payload: RawCbor<'a>
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+#[cbor(map)]
+struct Header {
+    #[n(1)] alg: i32,
+    #[n(4)] kid: Option<Vec<u8>>
+}
+
+#[test]
+fn encoded_cbor_identity() {
+    let payload = minicbor::to_vec(["a", "b"]).unwrap();
+    let envelope = Envelope {
+        protected: EncodedCbor(Header { alg: -7, kid: Some(vec![1, 2]) }),
+        payload: RawCbor::new(&payload).unwrap()
+    };
+    let cbor = minicbor::to_vec(&envelope).unwrap();
+    assert_eq!(b"\x82\xd8\x18\x47\xa2\x01\x26\x04\x82\x01\x02\xd8\x18\x45\x82\x61a\x61b", cbor.as_slice());
+    let decoded: Envelope = minicbor::decode(&cbor).unwrap();
+    assert_eq!(envelope, decoded);
+    assert_eq!(["a", "b"], decoded.payload.decode_item::<[&str; 2]>().unwrap())
+}
+
+#[test]
+fn encoded_cbor_errors() {
+    // Missing or wrong tag.
+    assert!(matches!(minicbor::decode::<EncodedCbor<u8>>(b"\x41\x01"), Err(Error::TypeMismatch(..))));
+    assert!(matches!(minicbor::decode::<EncodedCbor<u8>>(b"\xd8\x19\x41\x01"), Err(Error::Message(_))));
+    // The byte string must contain exactly one well-formed item.
+    assert!(matches!(minicbor::decode::<EncodedCbor<u8>>(b"\xd8\x18\x42\x01\x02"), Err(Error::TrailingBytes(1))));
+    assert!(matches!(minicbor::decode::<RawCbor>(b"\xd8\x18\x42\x01\x02"), Err(Error::TrailingBytes(1))));
+    assert!(matches!(minicbor::decode::<RawCbor>(b"\xd8\x18\x41\x18"), Err(Error::EndOfInput)));
+    assert!(RawCbor::new(b"").is_err());
+    assert!(RawCbor::new(b"\x9f\xff\xff").is_err());
+    // Indefinite-length items may be nested in definite-length ones.
+    assert!(RawCbor::new(b"\x81\x9f\x01\xff").is_ok());
+    // Encoding errors of the inner value are propagated.
+    let mut e = minicbor::Encoder::new(Vec::new());
+    e.set_reject_non_finite(true);
+    assert!(e.encode(EncodedCbor(f64::NAN)).is_err());
+    assert!(e.into_inner().is_empty())
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+struct Keys {
+    #[n(0)] a: Base64Url<ByteVec>,
+    #[n(1)] b: Base64<ByteVec>,
+    #[n(2)] c: Hex<ByteVec>
+}
+
+#[test]
+fn expected_encodings() {
+    let keys = Keys {
+        a: Base64Url(vec![0xfb, 0xff].into()),
+        b: Base64(vec![0xfb, 0xff].into()),
+        c: Hex(vec![0xfb, 0xff].into())
+    };
+    let cbor = minicbor::to_vec(&keys).unwrap();
+    assert_eq!(b"\x83\xd5\x42\xfb\xff\xd6\x42\xfb\xff\xd7\x42\xfb\xff", cbor.as_slice());
+    assert_eq!(keys, minicbor::decode(&cbor).unwrap());
+    assert_eq!(r#"["-_8","+/8=","fbff"]"#, minicbor::json::to_json(&cbor).unwrap());
+
+    let diag = minicbor::display(&cbor).to_string();
+    assert_eq!("[21(b64'-_8'), 22(b64'+/8='), 23(h'fb ff')]", diag);
+    assert_eq!(cbor, minicbor::edn::from_edn(&diag).unwrap());
+
+    // The tag applies to all byte strings in the tagged item.
+    let cbor = minicbor::to_vec(Base64Url((ByteVec::from(vec![1]), [ByteVec::from(vec![2])]))).unwrap();
+    assert_eq!("21([b64'AQ', [b64'Ag']])", minicbor::display(&cbor).to_string());
+
+    assert!(matches!(minicbor::decode::<Hex<ByteVec>>(b"\xd6\x41\x00"), Err(Error::Message(_))));
+    assert!(matches!(minicbor::decode::<Base64<u8>>(b"\x01"), Err(Error::TypeMismatch(..))))
+}
@@ -257,3 +257,237 @@ fn regular_enum() {
     assert!(matches!(d.skip(), Err(minicbor::decode::Error::EndOfInput)))
 }
 
+
+#[test]
+fn encode_definite_lengths() {
+    use minicbor::Encoder;
+    use minicbor::data::Tag;
+    use minicbor::encode::{Error, Write};
+
+    /// Encodes the same items with indefinite (`true`) or definite lengths.
+    struct Doc(bool);
+
+    impl Encode for Doc {
+        fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
+            if self.0 { e.begin_map()?; } else { e.map(5)?; }
+            e.str("a")?;
+            if self.0 { e.begin_array()?; } else { e.array(3)?; }
+            e.u8(1)?.array(2)?.u8(2)?.u8(3)?.tag(Tag::Unassigned(1))?.u8(4)?;
+            if self.0 { e.end()?; }
+            e.str("b")?;
+            if self.0 {
+                e.begin_bytes()?.bytes(b"ab")?.bytes(&[])?.bytes(b"cd")?.end()?;
+            } else {
+                e.bytes(b"abcd")?;
+            }
+            e.str("c")?;
+            if self.0 {
+                e.tag(Tag::Unassigned(2))?.begin_str()?.str("x")?.str("yz")?.end()?;
+            } else {
+                e.tag(Tag::Unassigned(2))?.str("xyz")?;
+            }
+            e.str("d")?;
+            if self.0 { e.begin_array()?.end()?; } else { e.array(0)?; }
+            e.str("e")?;
+            if self.0 { e.begin_array()?; } else { e.array(300)?; }
+            for i in 0 .. 300u16 {
+                e.u16(i)?;
+            }
+            if self.0 { e.end()?; }
+            if self.0 { e.end()?; }
+            Ok(())
+        }
+    }
+
+    let mut v = Vec::new();
+    minicbor::encode_definite(Doc(true), &mut v).unwrap();
+    assert_eq!(minicbor::to_vec(Doc(false)).unwrap(), v);
+
+    let mut v = Vec::new();
+    minicbor::encode_definite(Doc(false), &mut v).unwrap();
+    assert_eq!(minicbor::to_vec(Doc(false)).unwrap(), v);
+
+    struct Unbalanced;
+
+    impl Encode for Unbalanced {
+        fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
+            e.begin_array()?.u8(1)?.ok()
+        }
+    }
+
+    assert!(minicbor::encode_definite(Unbalanced, Vec::new()).is_err())
+}
+
+#[test]
+fn const_encoding() {
+    use minicbor::data::Tag;
+    use minicbor::encode::ConstEncoder;
+    use minicbor::Encoder;
+
+    const HEADER: ConstEncoder<64> = ConstEncoder::new()
+        .array(9)
+        .u8(24)
+        .i16(-300)
+        .u64(u64::MAX)
+        .bool(true)
+        .null()
+        .simple(99)
+        .tag(Tag::Uri).str("a")
+        .bytes(&[1, 2])
+        .begin_map().str("x").f64(1.5).end();
+
+    static BYTES: [u8; HEADER.len()] = {
+        let mut a = [0; HEADER.len()];
+        let b = HEADER.as_bytes();
+        let mut i = 0;
+        while i < b.len() {
+            a[i] = b[i];
+            i += 1
+        }
+        a
+    };
+
+    let mut e = Encoder::new(Vec::new());
+    e.array(9).unwrap()
+        .u8(24).unwrap()
+        .i16(-300).unwrap()
+        .u64(u64::MAX).unwrap()
+        .bool(true).unwrap()
+        .null().unwrap()
+        .simple(99).unwrap()
+        .tag(Tag::Uri).unwrap().str("a").unwrap()
+        .bytes(&[1, 2]).unwrap()
+        .begin_map().unwrap().str("x").unwrap().f64(1.5).unwrap().end().unwrap();
+    let expected = e.into_inner();
+
+    assert_eq!(expected.as_slice(), HEADER.as_bytes());
+    assert_eq!(expected.as_slice(), &BYTES[..]);
+
+    for n in [0, 23, 24, 255, 256, 65535, 65536, u64::from(u32::MAX), u64::from(u32::MAX) + 1, u64::MAX] {
+        let c = ConstEncoder::<9>::new().u64(n);
+        assert_eq!(minicbor::to_vec(n).unwrap().as_slice(), c.as_bytes())
+    }
+}
+
+#[test]
+fn map_from_iterator() {
+    use minicbor::Encoder;
+    use std::collections::BTreeMap;
+
+    let pairs = [(1u8, "one"), (2, "two"), (3, "three")];
+    let map = pairs.iter().cloned().collect::<BTreeMap<_, _>>();
+
+    let mut e = Encoder::new(Vec::new());
+    e.map_iter(pairs.iter().cloned()).unwrap();
+    assert_eq!(minicbor::to_vec(&map).unwrap(), e.into_inner());
+
+    let mut e = Encoder::new(Vec::new());
+    e.map_iter_indef(pairs.iter().cloned().filter(|(k, _)| k % 2 == 1)).unwrap();
+    assert_eq!(b"\xbf\x01\x63one\x03\x65three\xff", e.into_inner().as_slice());
+
+    let mut e = Encoder::new(Vec::new());
+    e.map_iter(std::iter::empty::<(u8, u8)>()).unwrap();
+    assert_eq!(&[0xa0], e.into_inner().as_slice());
+
+    /// An iterator whose length is wrong.
+    struct Liar(usize, std::ops::Range<u8>);
+
+    impl Iterator for Liar {
+        type Item = (u8, u8);
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.1.next().map(|i| (i, i))
+        }
+    }
+
+    impl ExactSizeIterator for Liar {
+        fn len(&self) -> usize {
+            self.0
+        }
+    }
+
+    assert!(Encoder::new(Vec::new()).map_iter(Liar(1, 0 .. 2)).is_err());
+    assert!(Encoder::new(Vec::new()).map_iter(Liar(3, 0 .. 2)).is_err())
+}
+
+#[test]
+fn bignums() {
+    let cases: &[(i128, &[u8])] = &[
+        (0, &[0x00]),
+        (-1, &[0x20]),
+        (u64::MAX.into(), &[0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+        (-1 - i128::from(u64::MAX), &[0x3b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+        (1 << 64, &[0xc2, 0x49, 0x01, 0, 0, 0, 0, 0, 0, 0, 0]),
+        (-1 - (1 << 64), &[0xc3, 0x49, 0x01, 0, 0, 0, 0, 0, 0, 0, 0]),
+        (i128::MIN, &[0xc3, 0x50, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff])
+    ];
+    for (n, cbor) in cases {
+        assert_eq!(*cbor, minicbor::to_vec(n).unwrap().as_slice(), "{}", n);
+        assert_eq!(*n, minicbor::decode::<i128>(cbor).unwrap(), "{}", n)
+    }
+
+    let cbor = minicbor::to_vec(u128::MAX).unwrap();
+    assert_eq!(&[0xc2, 0x50], &cbor[.. 2]);
+    assert_eq!(u128::MAX, minicbor::decode::<u128>(&cbor).unwrap());
+    assert!(minicbor::decode::<i128>(&cbor).is_err());
+
+    // Leading zeros and indefinite lengths are accepted.
+    assert_eq!(258, minicbor::decode::<u128>(&[0xc2, 0x43, 0x00, 0x01, 0x02]).unwrap());
+    assert_eq!(-259, minicbor::decode::<i128>(&[0xc3, 0x5f, 0x41, 0x01, 0x41, 0x02, 0xff]).unwrap());
+
+    // Too large, negative or unexpected tags are rejected.
+    let mut too_large = vec![0xc2, 0x51, 0x01];
+    too_large.extend_from_slice(&[0; 16]);
+    assert!(minicbor::decode::<u128>(&too_large).is_err());
+    assert!(minicbor::decode::<u128>(&[0x20]).is_err());
+    assert!(minicbor::decode::<u128>(&[0xc3, 0x41, 0x01]).is_err());
+    assert!(minicbor::decode::<i128>(&[0xc1, 0x01]).is_err())
+}
+
+#[test]
+fn nonzero_rejects_zero() {
+    use std::num::*;
+
+    fn message<T: for<'a> minicbor::Decode<'a>>() -> String {
+        minicbor::decode::<T>(&[0x00]).err().unwrap().to_string()
+    }
+
+    assert_eq!("unexpected 0 when decoding a `NonZeroUsize`", message::<NonZeroUsize>());
+    assert_eq!("unexpected 0 when decoding a `NonZeroIsize`", message::<NonZeroIsize>());
+    assert_eq!("unexpected 0 when decoding a `NonZeroU128`", message::<NonZeroU128>());
+    assert_eq!("unexpected 0 when decoding a `NonZeroI128`", message::<NonZeroI128>());
+    assert_eq!("unexpected 0 when decoding a `NonZeroU128`", {
+        minicbor::decode::<NonZeroU128>(&[0xc2, 0x42, 0x00, 0x00]).err().unwrap().to_string()
+    });
+
+    let n = NonZeroI128::new(i128::MIN).unwrap();
+    assert_eq!(n, minicbor::decode(&minicbor::to_vec(n).unwrap()).unwrap());
+    let n = NonZeroIsize::new(-5).unwrap();
+    assert_eq!(&[0x24], minicbor::to_vec(n).unwrap().as_slice())
+}
+
+#[test]
+fn raw_items() {
+    use minicbor::Encoder;
+    use std::collections::BTreeMap;
+
+    let cached = minicbor::to_vec(BTreeMap::from([(1u8, "a")])).unwrap();
+    let mut e = Encoder::new(Vec::new());
+    e.array(3).unwrap()
+        .raw(&cached).unwrap()
+        .raw(&[NULL]).unwrap()
+        .raw(b"\x9f\x01\xff").unwrap();
+    let cbor = e.into_inner();
+    assert_eq!(b"\x83\xa1\x01\x61a\xf6\x9f\x01\xff", cbor.as_slice());
+    let (m, n, v): (BTreeMap<u8, String>, Option<u8>, Vec<u8>) = minicbor::decode(&cbor).unwrap();
+    assert_eq!(Some(&"a".to_string()), m.get(&1));
+    assert_eq!(None, n);
+    assert_eq!(vec![1], v)
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "not a single well-formed cbor item")]
+fn raw_items_are_checked() {
+    minicbor::Encoder::new(Vec::new()).raw(&[0x01, 0x02]).unwrap();
+}
@@ -0,0 +1,100 @@
+use minicbor::{Decode, Encode};
+use minicbor::decode::Error;
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+enum Legacy {
+    #[n(0)] A,
+    #[n(1)] B(#[n(0)] u8, #[n(1)] String),
+    #[n(2)] #[cbor(map)] C { #[n(0)] x: Option<u16> }
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+#[cbor(repr = "map", accept_array)]
+enum AsMap {
+    #[n(0)] A,
+    #[n(1)] B(#[n(0)] u8, #[n(1)] String),
+    #[n(2)] #[cbor(map)] C { #[n(0)] x: Option<u16> }
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+#[cbor(repr(tag = 32768), accept_array)]
+enum AsTag {
+    #[n(0)] A,
+    #[n(1)] B(#[n(0)] u8, #[n(1)] String),
+    #[n(2)] #[cbor(map)] C { #[n(0)] x: Option<u16> }
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+#[cbor(repr(tag = 0))]
+enum TagOnly<'a> {
+    #[n(100)] A(#[b(0)] &'a str),
+    #[n(0xffff_ffff)] B
+}
+
+#[test]
+fn encodes_target_format() {
+    assert_eq!(b"\xa1\x00\x80", &minicbor::to_vec(AsMap::A).unwrap()[..]);
+    assert_eq!(b"\xa1\x01\x82\x07\x61x", &minicbor::to_vec(AsMap::B(7, "x".into())).unwrap()[..]);
+    assert_eq!(b"\xa1\x02\xa1\x00\x01", &minicbor::to_vec(AsMap::C { x: Some(1) }).unwrap()[..]);
+    assert_eq!(b"\xd9\x80\x00\x80", &minicbor::to_vec(AsTag::A).unwrap()[..]);
+    assert_eq!(b"\xd9\x80\x01\x82\x07\x61x", &minicbor::to_vec(AsTag::B(7, "x".into())).unwrap()[..]);
+    assert_eq!(b"\xd9\x80\x02\xa0", &minicbor::to_vec(AsTag::C { x: None }).unwrap()[..]);
+    assert_eq!(b"\xd8\x64\x81\x61y", &minicbor::to_vec(TagOnly::A("y")).unwrap()[..]);
+    assert_eq!(b"\xda\xff\xff\xff\xff\x80", &minicbor::to_vec(TagOnly::B).unwrap()[..])
+}
+
+#[test]
+fn decodes_legacy_and_target_format() {
+    let values = [
+        (Legacy::A, AsMap::A, AsTag::A),
+        (Legacy::B(7, "x".into()), AsMap::B(7, "x".into()), AsTag::B(7, "x".into())),
+        (Legacy::C { x: Some(1) }, AsMap::C { x: Some(1) }, AsTag::C { x: Some(1) }),
+        (Legacy::C { x: None }, AsMap::C { x: None }, AsTag::C { x: None })
+    ];
+    for (l, m, t) in values {
+        let legacy = minicbor::to_vec(&l).unwrap();
+        assert_eq!(m, minicbor::decode(&legacy).unwrap());
+        assert_eq!(t, minicbor::decode(&legacy).unwrap());
+        assert_eq!(m, minicbor::decode(&minicbor::to_vec(&m).unwrap()).unwrap());
+        assert_eq!(t, minicbor::decode(&minicbor::to_vec(&t).unwrap()).unwrap())
+    }
+    for v in [TagOnly::A("y"), TagOnly::B] {
+        assert_eq!(v, minicbor::decode(&minicbor::to_vec(&v).unwrap()).unwrap())
+    }
+}
+
+#[test]
+fn rejects_other_formats() {
+    match minicbor::decode::<TagOnly>(b"\x82\x18\x64\x81\x61y") {
+        Err(Error::TypeMismatch(..)) => {}
+        other => panic!("unexpected result: {:?}", other)
+    }
+    match minicbor::decode::<AsMap>(b"\xc0\x80") {
+        Err(Error::TypeMismatch(..)) => {}
+        other => panic!("unexpected result: {:?}", other)
+    }
+    match minicbor::decode::<AsMap>(b"\xa2\x00\x80\x00\x80") {
+        Err(Error::Message("expected enum (1-element map)")) => {}
+        other => panic!("unexpected result: {:?}", other)
+    }
+    match minicbor::decode::<AsTag>(b"\xa1\x00\x80") {
+        Err(Error::TypeMismatch(..)) => {}
+        other => panic!("unexpected result: {:?}", other)
+    }
+    match minicbor::decode::<AsTag>(b"\x83\x00\x80\x80") {
+        Err(Error::Message("expected enum (2-element array)")) => {}
+        other => panic!("unexpected result: {:?}", other)
+    }
+    match minicbor::decode::<AsTag>(b"\xd9\x80\x03\x80") {
+        Err(Error::UnknownVariant(3, "AsTag")) => {}
+        other => panic!("unexpected result: {:?}", other)
+    }
+    match minicbor::decode::<AsTag>(b"\xc1\x82\x07\x61x") {
+        Err(Error::Message("expected enum (tag >= base tag)")) => {}
+        other => panic!("unexpected result: {:?}", other)
+    }
+    match minicbor::decode::<AsTag>(b"\xdb\x00\x00\x00\x01\x00\x00\x80\x00\x80") {
+        Err(Error::Overflow(0x1_0000_8000, _)) => {}
+        other => panic!("unexpected result: {:?}", other)
+    }
+}
@@ -0,0 +1,591 @@
+use minicbor::{Decode, Encode};
+
+#[test]
+fn chrono_datetime() {
+    use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+    use minicbor::ext::chrono::Epoch;
+
+    let t = Utc.with_ymd_and_hms(2013, 3, 21, 20, 4, 0).unwrap();
+    let rfc3339 = hex::decode("c074323031332d30332d32315432303a30343a30305a").unwrap();
+    let epoch = hex::decode("c11a514b67b0").unwrap();
+
+    assert_eq!(rfc3339, minicbor::to_vec(t).unwrap());
+    assert_eq!(rfc3339, minicbor::to_vec(t.naive_utc()).unwrap());
+    assert_eq!(epoch, minicbor::to_vec(Epoch(t)).unwrap());
+    assert_eq!(epoch, minicbor::to_vec(Epoch(t.naive_utc())).unwrap());
+
+    for cbor in [&rfc3339, &epoch] {
+        assert_eq!(t, minicbor::decode::<DateTime<Utc>>(cbor).unwrap());
+        assert_eq!(t.naive_utc(), minicbor::decode::<NaiveDateTime>(cbor).unwrap());
+        assert_eq!(Epoch(t), minicbor::decode(cbor).unwrap());
+    }
+
+    // Fractional seconds and offsets.
+    let t = Utc.with_ymd_and_hms(2013, 3, 21, 20, 4, 0).unwrap() + chrono::Duration::milliseconds(500);
+    let cbor = minicbor::to_vec(Epoch(t)).unwrap();
+    assert_eq!(hex::decode("c1fb41d452d9ec200000").unwrap(), cbor);
+    assert_eq!(t, minicbor::decode::<DateTime<Utc>>(&cbor).unwrap());
+    assert_eq!(t, minicbor::decode::<DateTime<Utc>>(&minicbor::to_vec(t).unwrap()).unwrap());
+
+    let mut e = minicbor::Encoder::new(Vec::new());
+    e.tag(minicbor::data::Tag::DateTime).unwrap().str("2013-03-21T22:04:00.5+02:00").unwrap();
+    assert_eq!(t, minicbor::decode::<DateTime<Utc>>(&e.into_inner()).unwrap());
+
+    // Errors.
+    let mut e = minicbor::Encoder::new(Vec::new());
+    e.tag(minicbor::data::Tag::DateTime).unwrap().str("yesterday").unwrap();
+    assert!(minicbor::decode::<DateTime<Utc>>(&e.into_inner()).is_err());
+    assert!(minicbor::decode::<DateTime<Utc>>(&[0xc1, 0xfb, 0x7f, 0xf0, 0, 0, 0, 0, 0, 0]).is_err());
+    assert!(minicbor::decode::<DateTime<Utc>>(&[0x00]).is_err());
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct Event {
+        #[n(0)] at: DateTime<Utc>,
+        #[cbor(n(1), with = "minicbor::ext::chrono::epoch")] seen: NaiveDateTime
+    }
+
+    let e = Event { at: t, seen: t.naive_utc() };
+    assert_eq!(e, minicbor::decode(&minicbor::to_vec(&e).unwrap()).unwrap())
+}
+
+#[test]
+fn time_datetime() {
+    use minicbor::ext::time::Epoch;
+    use time::{Duration, OffsetDateTime, PrimitiveDateTime};
+    use time::macros::datetime;
+
+    let t = datetime!(2013-03-21 20:04:00 UTC);
+    let rfc3339 = hex::decode("c074323031332d30332d32315432303a30343a30305a").unwrap();
+    let epoch = hex::decode("c11a514b67b0").unwrap();
+
+    assert_eq!(rfc3339, minicbor::to_vec(t).unwrap());
+    assert_eq!(rfc3339, minicbor::to_vec(datetime!(2013-03-21 22:04:00 +2)).unwrap());
+    assert_eq!(rfc3339, minicbor::to_vec(datetime!(2013-03-21 20:04:00)).unwrap());
+    assert_eq!(epoch, minicbor::to_vec(Epoch(t)).unwrap());
+    assert_eq!(epoch, minicbor::to_vec(Epoch(datetime!(2013-03-21 20:04:00))).unwrap());
+
+    for cbor in [&rfc3339, &epoch] {
+        assert_eq!(t, minicbor::decode::<OffsetDateTime>(cbor).unwrap());
+        assert_eq!(datetime!(2013-03-21 20:04:00), minicbor::decode::<PrimitiveDateTime>(cbor).unwrap());
+        assert_eq!(Epoch(t), minicbor::decode(cbor).unwrap());
+    }
+
+    // Fractional seconds, which match the encoding of chrono.
+    let t = t + Duration::milliseconds(500);
+    let cbor = minicbor::to_vec(Epoch(t)).unwrap();
+    assert_eq!(hex::decode("c1fb41d452d9ec200000").unwrap(), cbor);
+    assert_eq!(t, minicbor::decode::<OffsetDateTime>(&cbor).unwrap());
+    for (t, s) in [
+        (t, "2013-03-21T20:04:00.500Z"),
+        (t + Duration::microseconds(1), "2013-03-21T20:04:00.500001Z"),
+        (t + Duration::nanoseconds(1), "2013-03-21T20:04:00.500000001Z")
+    ] {
+        let cbor = minicbor::to_vec(t).unwrap();
+        let mut d = minicbor::Decoder::new(&cbor);
+        assert_eq!(minicbor::data::Tag::DateTime, d.tag().unwrap());
+        assert_eq!(s, d.str().unwrap());
+        assert_eq!(t, minicbor::decode::<OffsetDateTime>(&cbor).unwrap());
+    }
+
+    // Errors.
+    assert!(minicbor::to_vec(datetime!(-0001-01-01 00:00:00 UTC)).is_err());
+    assert!(minicbor::decode::<OffsetDateTime>(&[0xc1, 0xfb, 0x7f, 0xf0, 0, 0, 0, 0, 0, 0]).is_err());
+    assert!(minicbor::decode::<OffsetDateTime>(&[0x00]).is_err());
+}
+
+#[test]
+fn rust_decimal() {
+    use rust_decimal::Decimal;
+
+    for (x, cbor) in [
+        (Decimal::new(27315, 2), "c48221196ab3"),
+        (Decimal::new(-27315, 2), "c48221396ab2"),
+        (Decimal::ZERO, "c4820000"),
+        (Decimal::MAX, "c48200c24cffffffffffffffffffffffff"),
+        (Decimal::MIN, "c48200c34cfffffffffffffffffffffffe"),
+        (Decimal::from_i128_with_scale(u64::MAX.into(), 28), "c482381b1bffffffffffffffff"),
+        (Decimal::from_i128_with_scale(-i128::from(u64::MAX) - 1, 3), "c482223bffffffffffffffff")
+    ] {
+        let cbor = hex::decode(cbor).unwrap();
+        assert_eq!(cbor, minicbor::to_vec(x).unwrap());
+        assert_eq!(x, minicbor::decode::<Decimal>(&cbor).unwrap())
+    }
+
+    // Positive exponents and excess scale are normalised.
+    for (x, cbor) in [
+        (Decimal::new(1500, 0), "c482020f"),
+        (Decimal::from_i128_with_scale(15, 28), "c482381d1905dc")
+    ] {
+        assert_eq!(x, minicbor::decode::<Decimal>(&hex::decode(cbor).unwrap()).unwrap())
+    }
+
+    // Overflows.
+    assert!(minicbor::decode::<Decimal>(&hex::decode("c482181d01").unwrap()).is_err());
+    assert!(minicbor::decode::<Decimal>(&hex::decode("c48200c24d01000000000000000000000000").unwrap()).is_err());
+    assert!(minicbor::decode::<Decimal>(&hex::decode("c4823863c24cffffffffffffffffffffffff").unwrap()).is_err());
+    assert!(minicbor::decode::<Decimal>(&hex::decode("c48200fa3f800000").unwrap()).is_err());
+}
+
+#[test]
+fn bigdecimal() {
+    use bigdecimal::BigDecimal;
+    use num_bigint::BigInt;
+
+    for (x, cbor) in [
+        (BigDecimal::new(27315.into(), 2), "d901088221196ab3"),
+        (BigDecimal::new((-27315).into(), 2), "d901088221396ab2"),
+        (BigDecimal::new(15.into(), -3), "d9010882030f"),
+        (BigDecimal::new(BigInt::from(u128::MAX), 40), "d90108823827c250ffffffffffffffffffffffffffffffff"),
+        (BigDecimal::new(1.into(), i64::MIN), "d90108821b800000000000000001")
+    ] {
+        let cbor = hex::decode(cbor).unwrap();
+        assert_eq!(cbor, minicbor::to_vec(&x).unwrap());
+        assert_eq!(x, minicbor::decode::<BigDecimal>(&cbor).unwrap())
+    }
+
+    // Decimal fractions with tag 4 and bigfloats with tags 5 and 265.
+    for (x, cbor) in [
+        (BigDecimal::new(27315.into(), 2), "c48221196ab3"),
+        (BigDecimal::new(75.into(), 2), "c5822103"),
+        (BigDecimal::new(12.into(), 0), "d90109820203"),
+        (BigDecimal::new((-5).into(), 1), "c5822020")
+    ] {
+        assert_eq!(x, minicbor::decode::<BigDecimal>(&hex::decode(cbor).unwrap()).unwrap())
+    }
+
+    // Exponents out of range and malformed items.
+    assert!(minicbor::decode::<BigDecimal>(&hex::decode("d90108823bffffffffffffffff01").unwrap()).is_err());
+    assert!(minicbor::decode::<BigDecimal>(&hex::decode("d901088220c2490100000000000000000001").unwrap()).is_ok());
+    assert!(minicbor::decode::<BigDecimal>(&hex::decode("c5821a0001000001").unwrap()).is_err());
+    assert!(minicbor::decode::<BigDecimal>(&hex::decode("c48301020304").unwrap()).is_err());
+    assert!(minicbor::decode::<BigDecimal>(&hex::decode("c6820102").unwrap()).is_err());
+}
+
+#[test]
+fn num_bigint() {
+    use num_bigint::{BigInt, BigUint};
+
+    for (x, cbor) in [
+        (BigInt::from(0), "00"),
+        (BigInt::from(-1), "20"),
+        (BigInt::from(u64::MAX), "1bffffffffffffffff"),
+        (-BigInt::from(u64::MAX) - 1, "3bffffffffffffffff"),
+        (BigInt::from(u64::MAX) + 1, "c249010000000000000000"),
+        (-BigInt::from(u64::MAX) - 2, "c349010000000000000000"),
+        (BigInt::from(i128::MIN), "c3507fffffffffffffffffffffffffffffff")
+    ] {
+        let cbor = hex::decode(cbor).unwrap();
+        assert_eq!(cbor, minicbor::to_vec(&x).unwrap());
+        assert_eq!(x, minicbor::decode::<BigInt>(&cbor).unwrap());
+        if let Some(u) = x.to_biguint() {
+            assert_eq!(cbor, minicbor::to_vec(&u).unwrap());
+            assert_eq!(u, minicbor::decode::<BigUint>(&cbor).unwrap())
+        } else {
+            assert!(minicbor::decode::<BigUint>(&cbor).is_err())
+        }
+    }
+
+    // Indefinite-length and non-minimal bignums.
+    let x = minicbor::decode::<BigUint>(&hex::decode("c25f4100420001ff").unwrap()).unwrap();
+    assert_eq!(BigUint::from(1u8), x);
+
+    // Interoperability with the regular integer types.
+    let cbor = minicbor::to_vec(i64::MIN).unwrap();
+    assert_eq!(BigInt::from(i64::MIN), minicbor::decode::<BigInt>(&cbor).unwrap());
+    let cbor = minicbor::to_vec(BigInt::from(i64::MIN)).unwrap();
+    assert_eq!(i64::MIN, minicbor::decode::<i64>(&cbor).unwrap());
+
+    assert!(minicbor::decode::<BigInt>(&hex::decode("c4820000").unwrap()).is_err());
+    assert!(minicbor::decode::<BigInt>(&hex::decode("f5").unwrap()).is_err());
+}
+
+#[test]
+fn bytes() {
+    use bytes::{Bytes, BytesMut};
+
+    let b = Bytes::from_static(b"hello");
+    let cbor = minicbor::to_vec(&b).unwrap();
+    assert_eq!(b"\x45hello", cbor.as_slice());
+    assert_eq!(cbor, minicbor::to_vec(BytesMut::from(&b[..])).unwrap());
+    assert_eq!(b, minicbor::decode::<Bytes>(&cbor).unwrap());
+    assert_eq!(b, minicbor::decode::<BytesMut>(&cbor).unwrap());
+
+    // Indefinite-length byte strings.
+    let cbor = b"\x5f\x42he\x43llo\xff";
+    assert_eq!(b, minicbor::decode::<Bytes>(cbor).unwrap());
+
+    // Zero-copy slicing.
+    let input = Bytes::from(minicbor::to_vec((&b, "x", &b)).unwrap());
+    let mut d = minicbor::Decoder::new(&input);
+    assert_eq!(Some(3), d.array().unwrap());
+    let x = minicbor::ext::bytes::slice(&input, &mut d).unwrap();
+    assert_eq!(b, x);
+    assert_eq!(input[2 ..].as_ptr(), x.as_ptr());
+    assert_eq!("x", d.str().unwrap());
+    assert_eq!(b, minicbor::ext::bytes::slice(&input, &mut d).unwrap());
+
+    // Copies when sliced from other input or from indefinite byte strings.
+    let mut d = minicbor::Decoder::new(cbor);
+    assert_eq!(b, minicbor::ext::bytes::slice(&input, &mut d).unwrap());
+    let other = Bytes::from_static(b"\x45hello");
+    let mut d = minicbor::Decoder::new(&other);
+    let x = minicbor::ext::bytes::slice(&input, &mut d).unwrap();
+    assert_eq!(b, x);
+    assert_ne!(other[1 ..].as_ptr(), x.as_ptr());
+}
+
+#[test]
+fn heapless() {
+    use heapless::{FnvIndexMap, String, Vec};
+
+    let v: Vec<u16, 4> = Vec::from_slice(&[1, 2, 1000]).unwrap();
+    let cbor = minicbor::to_vec(&v).unwrap();
+    assert_eq!(minicbor::to_vec([1u16, 2, 1000].as_slice()).unwrap(), cbor);
+    assert_eq!(v, minicbor::decode::<Vec<u16, 4>>(&cbor).unwrap());
+    assert_eq!(v, minicbor::decode::<Vec<u16, 3>>(&cbor).unwrap());
+    assert!(minicbor::decode::<Vec<u16, 2>>(&cbor).is_err());
+    assert_eq!(v, minicbor::decode::<Vec<u16, 4>>(b"\x9f\x01\x02\x19\x03\xe8\xff").unwrap());
+
+    let mut s: String<8> = String::new();
+    s.push_str("hello").unwrap();
+    let cbor = minicbor::to_vec(&s).unwrap();
+    assert_eq!(b"\x65hello", cbor.as_slice());
+    assert_eq!(s, minicbor::decode::<String<5>>(&cbor).unwrap());
+    assert!(minicbor::decode::<String<4>>(&cbor).is_err());
+
+    let mut m: FnvIndexMap<u8, bool, 4> = FnvIndexMap::new();
+    m.insert(1, true).unwrap();
+    m.insert(2, false).unwrap();
+    m.insert(3, true).unwrap();
+    let cbor = minicbor::to_vec(&m).unwrap();
+    assert_eq!(b"\xa3\x01\xf5\x02\xf4\x03\xf5", cbor.as_slice());
+    assert_eq!(m, minicbor::decode::<FnvIndexMap<u8, bool, 4>>(&cbor).unwrap());
+    assert!(minicbor::decode::<FnvIndexMap<u8, bool, 2>>(&cbor).is_err());
+}
+
+#[test]
+fn arrayvec() {
+    use arrayvec::{ArrayString, ArrayVec};
+
+    let v: ArrayVec<u16, 4> = [1, 2, 1000].iter().copied().collect();
+    let cbor = minicbor::to_vec(&v).unwrap();
+    assert_eq!(minicbor::to_vec([1u16, 2, 1000].as_slice()).unwrap(), cbor);
+    assert_eq!(v, minicbor::decode::<ArrayVec<u16, 4>>(&cbor).unwrap());
+    assert_eq!(v.as_slice(), minicbor::decode::<ArrayVec<u16, 3>>(&cbor).unwrap().as_slice());
+    assert!(minicbor::decode::<ArrayVec<u16, 2>>(&cbor).is_err());
+    assert_eq!(v, minicbor::decode::<ArrayVec<u16, 4>>(b"\x9f\x01\x02\x19\x03\xe8\xff").unwrap());
+
+    let s = ArrayString::<8>::from("hello").unwrap();
+    let cbor = minicbor::to_vec(s).unwrap();
+    assert_eq!(b"\x65hello", cbor.as_slice());
+    assert_eq!("hello", minicbor::decode::<ArrayString<5>>(&cbor).unwrap().as_str());
+    assert!(minicbor::decode::<ArrayString<4>>(&cbor).is_err());
+}
+
+#[test]
+fn smallvec() {
+    use smallvec::{smallvec, SmallVec};
+
+    let v: SmallVec<[u16; 2]> = smallvec![1, 2, 1000];
+    let cbor = minicbor::to_vec(&v).unwrap();
+    assert_eq!(minicbor::to_vec([1u16, 2, 1000].as_slice()).unwrap(), cbor);
+    assert_eq!(v, minicbor::decode::<SmallVec<[u16; 2]>>(&cbor).unwrap());
+    assert!(!minicbor::decode::<SmallVec<[u16; 32]>>(&cbor).unwrap().spilled());
+    assert!(minicbor::decode::<SmallVec<[u16; 2]>>(&cbor).unwrap().spilled());
+    assert_eq!(v, minicbor::decode::<SmallVec<[u16; 17]>>(b"\x9f\x01\x02\x19\x03\xe8\xff").unwrap());
+}
+
+#[test]
+fn small_strings() {
+    use compact_str::CompactString;
+    use smol_str::SmolStr;
+
+    for s in ["", "hello", "a string which is too long to be stored inline"] {
+        let cbor = minicbor::to_vec(s).unwrap();
+        assert_eq!(cbor, minicbor::to_vec(SmolStr::new(s)).unwrap());
+        assert_eq!(cbor, minicbor::to_vec(CompactString::new(s)).unwrap());
+        assert_eq!(s, minicbor::decode::<SmolStr>(&cbor).unwrap());
+        assert_eq!(s, minicbor::decode::<CompactString>(&cbor).unwrap());
+    }
+    assert!(!minicbor::decode::<SmolStr>(b"\x65hello").unwrap().is_heap_allocated());
+    assert!(!minicbor::decode::<CompactString>(b"\x65hello").unwrap().is_heap_allocated());
+    assert!(minicbor::decode::<SmolStr>(b"\x45hello").is_err());
+}
+
+#[test]
+fn hashbrown() {
+    use hashbrown::{HashMap, HashSet};
+
+    let m: HashMap<u8, String> = (0 .. 20).map(|i| (i, i.to_string())).collect();
+    let cbor = minicbor::to_vec(&m).unwrap();
+    assert_eq!(m, minicbor::decode::<HashMap<u8, String>>(&cbor).unwrap());
+    let std: std::collections::HashMap<u8, String> = minicbor::decode(&cbor).unwrap();
+    assert_eq!(m, minicbor::decode::<HashMap<_, _>>(&minicbor::to_vec(&std).unwrap()).unwrap());
+
+    let s: HashSet<u8> = (0 .. 20).collect();
+    let cbor = minicbor::to_vec(&s).unwrap();
+    assert_eq!(s, minicbor::decode::<HashSet<u8>>(&cbor).unwrap());
+    let r: HashSet<u8, std::collections::hash_map::RandomState> = minicbor::decode(&cbor).unwrap();
+    assert!(s.iter().all(|x| r.contains(x)) && s.len() == r.len());
+}
+
+#[test]
+fn ipnet() {
+    use ipnet::{IpNet, Ipv4Net, Ipv6Net};
+
+    // Networks without host bits use the prefix form, others the interface form.
+    let prefix = hex::decode("d8368218384620010db81234").unwrap();
+    let interface = hex::decode("d8368250fe80000000000000020202fffffe03031840").unwrap();
+
+    let net: Ipv6Net = "2001:db8:1234::/56".parse().unwrap();
+    assert_eq!(prefix, minicbor::to_vec(net).unwrap());
+    assert_eq!(net, minicbor::decode::<Ipv6Net>(&prefix).unwrap());
+    assert_eq!(IpNet::V6(net), minicbor::decode::<IpNet>(&prefix).unwrap());
+
+    let net: Ipv6Net = "fe80::202:2ff:fffe:303/64".parse().unwrap();
+    assert_eq!(interface, minicbor::to_vec(net).unwrap());
+    assert_eq!(net, minicbor::decode::<Ipv6Net>(&interface).unwrap());
+    assert_eq!(IpNet::V6(net), minicbor::decode::<IpNet>(&interface).unwrap());
+
+    for s in ["0.0.0.0/0", "10.0.0.0/8", "192.0.2.1/24", "255.255.255.255/32"] {
+        let net: Ipv4Net = s.parse().unwrap();
+        let cbor = minicbor::to_vec(net).unwrap();
+        assert_eq!(cbor, minicbor::to_vec(IpNet::V4(net)).unwrap());
+        assert_eq!(net, minicbor::decode::<Ipv4Net>(&cbor).unwrap());
+        assert_eq!(IpNet::V4(net), minicbor::decode::<IpNet>(&cbor).unwrap());
+        assert!(minicbor::decode::<Ipv6Net>(&cbor).is_err())
+    }
+
+    // Prefix length too large, bits set after the prefix length.
+    assert!(minicbor::decode::<Ipv4Net>(b"\xd8\x34\x82\x18\x21\x41\x0a").is_err());
+    assert!(minicbor::decode::<Ipv4Net>(b"\xd8\x34\x82\x08\x42\x0a\x01").is_err())
+}
+
+#[test]
+fn url() {
+    use url::Url;
+
+    let u = Url::parse("https://example.com/a?b=c#d").unwrap();
+    let cbor = minicbor::to_vec(&u).unwrap();
+    let mut d = minicbor::Decoder::new(&cbor);
+    assert_eq!(minicbor::data::Tag::Uri, d.tag().unwrap());
+    assert_eq!("https://example.com/a?b=c#d", d.str().unwrap());
+    assert_eq!(u, minicbor::decode::<Url>(&cbor).unwrap());
+
+    assert!(minicbor::decode::<Url>(b"\x74https://example.com/").is_err());
+    assert!(minicbor::decode::<Url>(b"\xd8\x20\x63foo").is_err());
+}
+
+#[test]
+fn semver() {
+    use semver::{Version, VersionReq};
+
+    let v = Version::parse("1.2.3-alpha.1+build.5").unwrap();
+    let cbor = minicbor::to_vec(&v).unwrap();
+    assert_eq!(minicbor::to_vec("1.2.3-alpha.1+build.5").unwrap(), cbor);
+    assert_eq!(v, minicbor::decode::<Version>(&cbor).unwrap());
+
+    let r = VersionReq::parse(">=1.2.0, <2").unwrap();
+    let cbor = minicbor::to_vec(&r).unwrap();
+    assert_eq!(minicbor::to_vec(">=1.2.0, <2").unwrap(), cbor);
+    assert_eq!(r, minicbor::decode::<VersionReq>(&cbor).unwrap());
+
+    assert!(minicbor::decode::<Version>(b"\x631.2").is_err());
+    assert!(minicbor::decode::<VersionReq>(b"\x62=>").is_err());
+    assert!(minicbor::decode::<Version>(b"\x01").is_err());
+}
+
+#[test]
+fn arbitrary_cbor() {
+    use arbitrary::{Arbitrary, Unstructured};
+    use minicbor::data::Type;
+    use minicbor::encoded::RawCbor;
+    use minicbor::ext::arbitrary::{CanonicalCbor, Cbor};
+    use rand::{Rng, SeedableRng};
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0x3141);
+    let mut seed = vec![0; 4096];
+
+    for _ in 0 .. 1000 {
+        rng.fill(&mut seed[..]);
+
+        // `RawCbor` checks well-formedness independently of `Decoder::skip`,
+        // which may not support every nesting of indefinite lengths.
+        let Cbor(bytes) = Cbor::arbitrary(&mut Unstructured::new(&seed)).unwrap();
+        RawCbor::new(&bytes).unwrap();
+
+        let CanonicalCbor(bytes) = CanonicalCbor::arbitrary(&mut Unstructured::new(&seed)).unwrap();
+        RawCbor::new(&bytes).unwrap();
+        for t in minicbor::decode::Tokenizer::new(&bytes) {
+            assert!(!matches!(t.unwrap(), minicbor::decode::Token::BeginArray
+                | minicbor::decode::Token::BeginMap
+                | minicbor::decode::Token::BeginBytes
+                | minicbor::decode::Token::BeginString
+                | minicbor::decode::Token::Break))
+        }
+        let mut d = minicbor::Decoder::new(&bytes);
+        if d.datatype().unwrap() == Type::F64 {
+            let x = d.f64().unwrap();
+            assert!(x.is_finite() && f64::from(x as f32) != x)
+        }
+    }
+}
+
+#[test]
+fn quickcheck_cbor() {
+    use minicbor::decode::{Token, Tokenizer};
+    use minicbor::ext::quickcheck::{CanonicalCbor, Cbor};
+    use quickcheck::{Arbitrary, Gen};
+
+    // Independent of `Decoder::skip`, which may not support every nesting
+    // of indefinite lengths.
+    fn well_formed(b: &[u8]) -> bool {
+        minicbor::encoded::RawCbor::new(b).is_ok()
+    }
+
+    fn canonical(b: &[u8]) -> bool {
+        Tokenizer::new(b).all(|t| match t {
+            Ok(Token::BeginArray) | Ok(Token::BeginMap) | Ok(Token::BeginBytes) | Ok(Token::BeginString) => false,
+            Ok(_)  => true,
+            Err(_) => false
+        })
+    }
+
+    fn prop(c: Cbor, d: CanonicalCbor) -> bool {
+        well_formed(c.as_ref()) && well_formed(d.as_ref()) && canonical(d.as_ref())
+    }
+
+    quickcheck::quickcheck(prop as fn(Cbor, CanonicalCbor) -> bool);
+
+    // Shrinking produces well-formed items in deterministic encoding.
+    let mut g = Gen::from_size_and_seed(20, 0x3141);
+    for _ in 0 .. 100 {
+        let c = Cbor::arbitrary(&mut g);
+        for s in c.shrink().take(100) {
+            assert!(well_formed(s.as_ref()) && canonical(s.as_ref()));
+        }
+    }
+}
+
+#[test]
+fn ciborium() {
+    use ciborium::value::{Integer, Value};
+    use std::convert::TryFrom;
+
+    let v = Value::Array(vec![
+        Value::Integer(u64::MAX.into()),
+        Value::Integer(Integer::try_from(-18446744073709551616i128).unwrap()),
+        Value::Integer((-1).into()),
+        Value::Float(1.5),
+        Value::Bool(true),
+        Value::Null,
+        Value::Bytes(vec![0; 30]),
+        Value::Text("text".into()),
+        Value::Map(vec![(Value::Integer(1.into()), Value::Tag(24, Box::new(Value::Bytes(vec![1]))))])
+    ]);
+
+    let cbor = minicbor::to_vec(&v).unwrap();
+    assert_eq!(v, minicbor::decode::<Value>(&cbor).unwrap());
+
+    // Values decoded by minicbor are the same as those decoded by ciborium.
+    let input = hex::decode("9f1b00000000000000ff3bffffffffffffffff5f4101ff7f6161ff80a0c1f93e00f4ff").unwrap();
+    let expected: Value = ciborium::from_reader(&input[..]).unwrap();
+    assert_eq!(expected, minicbor::decode::<Value>(&input).unwrap());
+
+    assert!(minicbor::decode::<Value>(&[0xf7]).is_err());
+    assert!(minicbor::decode::<Value>(&[0xf0]).is_err())
+}
+
+#[test]
+fn ciborium_value() {
+    use ciborium::Value as CValue;
+    use minicbor::value::Value;
+    use std::convert::TryFrom;
+
+    let v = Value::array([
+        Value::from(u64::MAX),
+        Value::negative(u64::MAX),
+        Value::from(-1),
+        Value::from(1.5),
+        Value::from(true),
+        Value::null(),
+        Value::bytes(&[0; 30]),
+        Value::from("a text string which is not inlined"),
+        Value::map([(Value::from(1), Value::tag(24, Value::bytes(b"\x01")))])
+    ]);
+
+    let c = CValue::try_from(&v).unwrap();
+    assert_eq!(Some(-18446744073709551616), c.as_array().and_then(|a| a[1].as_integer()).map(i128::from));
+    assert_eq!(v, Value::from(c.clone()));
+
+    // Both sides encode to the same CBOR.
+    let mut cbor = Vec::new();
+    ciborium::into_writer(&c, &mut cbor).unwrap();
+    assert_eq!(minicbor::to_vec(&v).unwrap(), cbor);
+    assert_eq!(v, minicbor::decode::<Value>(&cbor).unwrap());
+
+    assert!(CValue::try_from(Value::undefined()).is_err());
+    assert!(CValue::try_from(Value::array([Value::simple(16)])).is_err());
+}
+
+#[test]
+fn bumpalo() {
+    use bumpalo::Bump;
+    use minicbor::Decoder;
+    use minicbor::ext::bumpalo::{bytes, str, string, vec, vec_with};
+
+    let arena = Bump::new();
+
+    let cbor = hex::decode("7f6261626163ff").unwrap();
+    assert_eq!("abc", str(&mut Decoder::new(&cbor), &arena).unwrap());
+    assert_eq!("abc", string(&mut Decoder::new(&cbor), &arena).unwrap().as_str());
+    assert!(bytes(&mut Decoder::new(&cbor), &arena).is_err());
+
+    let cbor = hex::decode("5f4101420203ff").unwrap();
+    assert_eq!(&[1, 2, 3][..], bytes(&mut Decoder::new(&cbor), &arena).unwrap());
+
+    let cbor = hex::decode("9f010203ff").unwrap();
+    let v = vec::<u8>(&mut Decoder::new(&cbor), &arena).unwrap();
+    assert_eq!(&[1, 2, 3][..], v.as_slice());
+    assert_eq!(minicbor::to_vec([1, 2, 3]).unwrap(), minicbor::to_vec(&v).unwrap());
+
+    let cbor = minicbor::to_vec(["x", "yz"]).unwrap();
+    let mut d = Decoder::new(&cbor);
+    let v = vec_with(&mut d, &arena, |d| string(d, &arena)).unwrap();
+    assert_eq!(cbor, minicbor::to_vec(&v).unwrap());
+    assert!(arena.allocated_bytes() > 0)
+}
+
+#[test]
+fn rayon() {
+    use minicbor::Decoder;
+    use minicbor::ext::rayon::decode_array;
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct Point {
+        #[n(0)] x: i64,
+        #[n(1)] y: i64,
+        #[n(2)] label: String
+    }
+
+    let points = (0 .. 5000).map(|i| Point { x: i, y: -i, label: i.to_string() }).collect::<Vec<_>>();
+    let mut cbor = minicbor::to_vec(&points).unwrap();
+    cbor.push(0xf6);
+    let mut d = Decoder::new(&cbor);
+    assert_eq!(points, decode_array::<Point>(&mut d).unwrap());
+    assert_eq!(cbor.len() - 1, d.position());
+
+    let cbor = hex::decode("9f0102183203ff").unwrap();
+    let mut d = Decoder::new(&cbor);
+    assert_eq!(vec![1, 2, 50, 3], decode_array::<u8>(&mut d).unwrap());
+    assert_eq!(cbor.len(), d.position());
+
+    // Elements of the wrong type or not completely decoded.
+    let cbor = minicbor::to_vec((1, "a")).unwrap();
+    assert!(decode_array::<u8>(&mut Decoder::new(&cbor)).is_err());
+    let cbor = minicbor::to_vec([[1, 2], [3, 4]]).unwrap();
+    assert!(decode_array::<(u8,)>(&mut Decoder::new(&cbor)).is_err());
+
+    // Malformed elements are detected before decoding.
+    assert!(decode_array::<u8>(&mut Decoder::new(&[0x82, 0x01, 0x1c])).is_err())
+}
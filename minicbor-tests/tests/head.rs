@@ -0,0 +1,62 @@
+use minicbor::Decoder;
+use minicbor::data::Type;
+
+/// Input, major type, additional information, argument, size and data type.
+type Case = (&'static [u8], u8, u8, Option<u64>, usize, Type);
+
+#[test]
+fn read_head() {
+    let cases: &[Case] = &[
+        (&[0x00],                     0, 0,  Some(0),              1, Type::U8),
+        (&[0x17],                     0, 23, Some(23),             1, Type::U8),
+        (&[0x18, 0x00],               0, 24, Some(0),              2, Type::U8),
+        (&[0x39, 0x01, 0x00],         1, 25, Some(256),            3, Type::I16),
+        (&[0x5a, 0, 0, 0, 4],         2, 26, Some(4),              5, Type::Bytes),
+        (&[0x7f],                     3, 31, None,                 1, Type::StringIndef),
+        (&[0x9b, 0, 0, 0, 0, 0, 0, 0, 1], 4, 27, Some(1),          9, Type::Array),
+        (&[0xbf],                     5, 31, None,                 1, Type::MapIndef),
+        (&[0xd8, 0x2a],               6, 24, Some(42),             2, Type::Tag),
+        (&[0xf5],                     7, 21, Some(21),             1, Type::Bool),
+        (&[0xf8, 0xff],               7, 24, Some(255),            2, Type::Simple),
+        (&[0xf9, 0x3c, 0x00],         7, 25, Some(0x3c00),         3, Type::F16),
+        (&[0xfb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0], 7, 27, Some(0x3ff8_0000_0000_0000), 9, Type::F64)
+    ];
+    for (cbor, major, info, arg, size, ty) in cases {
+        let mut d = Decoder::new(cbor);
+        let h = d.read_head().unwrap();
+        assert_eq!(*major, h.major(), "{:02x?}", cbor);
+        assert_eq!(*info, h.info(), "{:02x?}", cbor);
+        assert_eq!(*arg, h.argument(), "{:02x?}", cbor);
+        assert_eq!(*size, h.size(), "{:02x?}", cbor);
+        assert_eq!(*ty, h.datatype(), "{:02x?}", cbor);
+        assert_eq!(*size, d.position(), "{:02x?}", cbor)
+    }
+}
+
+#[test]
+fn read_head_leaves_content() {
+    let cbor = [0x82, 0x63, 0x61, 0x62, 0x63, 0xc1, 0x01];
+    let mut d = Decoder::new(&cbor);
+    assert_eq!(Some(2), d.read_head().unwrap().argument());
+    assert_eq!(Some(3), d.read_head().unwrap().argument());
+    assert_eq!("abc", std::str::from_utf8(&cbor[d.position() .. d.position() + 3]).unwrap());
+    d.set_position(d.position() + 3);
+    assert_eq!(6, d.read_head().unwrap().major());
+    assert_eq!(1, d.u8().unwrap())
+}
+
+#[test]
+fn read_head_errors() {
+    for b in [0x1c, 0x3d, 0x5e, 0xfc, 0xfd, 0xfe].iter() {
+        assert!(Decoder::new(&[*b]).read_head().is_err(), "{:02x}", b)
+    }
+    // Indefinite lengths of integers and tags, and breaks.
+    for b in [[0x1f], [0x3f], [0xdf], [0xff]].iter() {
+        let mut d = Decoder::new(b);
+        assert!(d.read_head().is_err(), "{:02x?}", b);
+        assert_eq!(0, d.position(), "{:02x?}", b)
+    }
+    assert!(Decoder::new(&[]).read_head().is_err());
+    assert!(Decoder::new(&[0x19, 0x01]).read_head().is_err());
+    assert!(Decoder::new(&[0xfb, 0, 0, 0]).read_head().is_err())
+}
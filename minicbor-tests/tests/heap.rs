@@ -0,0 +1,80 @@
+#![forbid(unsafe_code)]
+
+use minicbor::{Decode, Encode};
+use minicbor::decode::Error;
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+#[cbor(map)]
+struct OnStack {
+    #[n(0)] name: String,
+    #[n(1)] data: [u64; 16],
+    #[n(2)] flag: Option<bool>,
+    #[n(3)] tags: Vec<u32>
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+#[cbor(map, heap)]
+struct OnHeap {
+    #[n(0)] name: String,
+    #[n(1)] data: [u64; 16],
+    #[n(2)] flag: Option<bool>,
+    #[n(3)] tags: Vec<u32>
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+#[cbor(heap)]
+struct Tuple<'a, T>(#[n(0)] T, #[b(1)] &'a str, #[n(2)] Option<u8>);
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+#[cbor(heap, collect_missing, deny_unknown_fields)]
+struct Strict {
+    #[n(0)] a: u8,
+    #[n(1)] b: Option<u8>,
+    #[n(2)] c: u8
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+#[cbor(heap)]
+struct Empty {}
+
+#[test]
+fn same_as_stack_decoding() {
+    for flag in [None, Some(true)] {
+        let s = OnStack { name: "x".into(), data: [7; 16], flag, tags: vec![1, 2] };
+        let h = OnHeap { name: "x".into(), data: [7; 16], flag, tags: vec![1, 2] };
+        let cbor = minicbor::to_vec(&s).unwrap();
+        assert_eq!(cbor, minicbor::to_vec(&h).unwrap());
+        assert_eq!(h, minicbor::decode(&cbor).unwrap())
+    }
+}
+
+#[test]
+fn tuple_with_generics() {
+    let t = Tuple(vec![1u8, 2], "hello", None);
+    let cbor = minicbor::to_vec(&t).unwrap();
+    assert_eq!(t, minicbor::decode(&cbor).unwrap());
+    assert_eq!(Empty {}, minicbor::decode(&minicbor::to_vec(Empty {}).unwrap()).unwrap())
+}
+
+#[test]
+fn missing_and_unknown_fields() {
+    match minicbor::decode::<OnHeap>(b"\xa1\x00\x61x") {
+        Err(Error::MissingValue(1, "OnHeap::data")) => {}
+        other => panic!("unexpected result: {:?}", other)
+    }
+
+    match minicbor::decode::<Strict>(b"\x80") {
+        Err(Error::MissingValues(m)) => {
+            let v = m.iter().collect::<Vec<_>>();
+            assert_eq!(vec![(0, "Strict::a"), (2, "Strict::c")], v)
+        }
+        other => panic!("unexpected result: {:?}", other)
+    }
+
+    match minicbor::decode::<Strict>(b"\x84\x01\x01\x01\x01") {
+        Err(Error::UnknownIndex(3, "Strict")) => {}
+        other => panic!("unexpected result: {:?}", other)
+    }
+
+    assert_eq!(Strict { a: 1, b: None, c: 2 }, minicbor::decode(b"\x83\x01\xf6\x02").unwrap())
+}
@@ -48,6 +48,16 @@ fn i64() {
     quickcheck(identity as fn(i64) -> bool)
 }
 
+#[test]
+fn u128() {
+    quickcheck(identity as fn(u128) -> bool)
+}
+
+#[test]
+fn i128() {
+    quickcheck(identity as fn(i128) -> bool)
+}
+
 #[test]
 fn nonzero_u8() {
     quickcheck(identity as fn(core::num::NonZeroU8) -> bool)
@@ -68,6 +78,16 @@ fn nonzero_u64() {
     quickcheck(identity as fn(core::num::NonZeroU64) -> bool)
 }
 
+#[test]
+fn nonzero_u128() {
+    quickcheck(identity as fn(core::num::NonZeroU128) -> bool)
+}
+
+#[test]
+fn nonzero_usize() {
+    quickcheck(identity as fn(core::num::NonZeroUsize) -> bool)
+}
+
 #[test]
 fn f16() {
     fn property(arg: f32) -> bool {
@@ -82,7 +102,7 @@ fn f16() {
 #[test]
 fn f32() {
     fn property(arg: f32) -> bool {
-        let vec = minicbor::to_vec(&arg).unwrap();
+        let vec = minicbor::to_vec(arg).unwrap();
         let val: f32 = minicbor::decode(&vec).unwrap();
         arg.to_bits() == val.to_bits()
     }
@@ -92,7 +112,7 @@ fn f32() {
 #[test]
 fn f64() {
     fn property(arg: f64) -> bool {
-        let vec = minicbor::to_vec(&arg).unwrap();
+        let vec = minicbor::to_vec(arg).unwrap();
         let val: f64 = minicbor::decode(&vec).unwrap();
         arg.to_bits() == val.to_bits()
     }
@@ -175,7 +195,7 @@ fn byte_array() {
     use minicbor::bytes::ByteArray;
 
     let arg = ByteArray::from([1,2,3,4,5,6,7,8]);
-    let vec = minicbor::to_vec(&arg).unwrap();
+    let vec = minicbor::to_vec(arg).unwrap();
     assert_eq!(Some(Type::Bytes), Decoder::new(&vec).datatype().ok());
     let val: ByteArray<8> = minicbor::decode(&vec).unwrap();
     assert_eq!(arg, val)
@@ -214,8 +234,8 @@ fn binaryheap() {
     fn property(arg: BinaryHeap<i32>) -> bool {
         let vec = minicbor::to_vec(&arg).unwrap();
         let val: BinaryHeap<i32> = minicbor::decode(&vec).unwrap();
-        let a = BTreeSet::from_iter(arg.into_iter());
-        let b = BTreeSet::from_iter(val.into_iter());
+        let a = BTreeSet::from_iter(arg);
+        let b = BTreeSet::from_iter(val);
         a == b
     }
 
@@ -237,6 +257,27 @@ fn boxed() {
     quickcheck(identity as fn(x: Box<u32>) -> bool)
 }
 
+#[test]
+fn arc() {
+    quickcheck(identity as fn(x: std::sync::Arc<u32>) -> bool)
+}
+
+#[test]
+fn boxed_str() {
+    fn property(arg: String) -> bool {
+        identity(arg.into_boxed_str())
+    }
+    quickcheck(property as fn(String) -> bool)
+}
+
+#[test]
+fn boxed_slice() {
+    fn property(arg: Vec<u32>) -> bool {
+        identity(arg.into_boxed_slice())
+    }
+    quickcheck(property as fn(Vec<u32>) -> bool)
+}
+
 #[test]
 fn duration() {
     quickcheck(identity as fn(std::time::Duration) -> bool)
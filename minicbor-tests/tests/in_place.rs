@@ -0,0 +1,119 @@
+use minicbor::{Decode, DecodeInPlace, Decoder, Encode};
+use minicbor::decode::{self, DecodeInPlace as _};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode, DecodeInPlace)]
+struct Message<'a> {
+    #[n(0)] id: u64,
+    #[n(1)] tags: Vec<String>,
+    #[n(2)] body: Option<Vec<u8>>,
+    #[b(3)] name: Cow<'a, str>,
+    #[n(4)] meta: BTreeMap<u8, String>,
+    #[n(5)] kind: Option<Kind>
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode, DecodeInPlace)]
+enum Kind {
+    #[n(0)] A,
+    #[n(1)] B(#[n(0)] u8)
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode, DecodeInPlace)]
+#[cbor(map)]
+struct Sparse {
+    #[n(0)] a: Option<String>,
+    #[n(1)] b: Vec<u32>
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode, DecodeInPlace)]
+#[cbor(transparent)]
+struct Wrapper(#[n(0)] Vec<u32>);
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode, DecodeInPlace)]
+struct Tuple(#[n(0)] String, #[n(1)] Vec<u8>);
+
+#[test]
+fn vec_reuses_capacity() {
+    let mut v: Vec<String> = Vec::with_capacity(16);
+    v.push(String::with_capacity(32));
+    let cap = (v.capacity(), v[0].capacity());
+    for msg in [vec!["abc", "def"], vec!["x"]] {
+        let cbor = minicbor::to_vec(&msg).unwrap();
+        v.decode_into(&mut Decoder::new(&cbor)).unwrap();
+        assert_eq!(msg, v);
+    }
+    v.decode_into(&mut Decoder::new(&minicbor::to_vec(["y"]).unwrap())).unwrap();
+    assert_eq!(cap, (v.capacity(), v[0].capacity()))
+}
+
+#[test]
+fn indefinite_arrays() {
+    let mut v: Vec<u8> = vec![9, 9, 9];
+    v.decode_into(&mut Decoder::new(&[0x9f, 1, 2, 0xff])).unwrap();
+    assert_eq!([1, 2], v.as_slice());
+
+    let mut a = [0u8; 2];
+    a.decode_into(&mut Decoder::new(&[0x9f, 1, 2, 0xff])).unwrap();
+    assert_eq!([1, 2], a);
+    assert!(a.decode_into(&mut Decoder::new(&[0x83, 1, 2, 3])).is_err())
+}
+
+#[test]
+fn derived_struct() {
+    let msgs = [
+        Message {
+            id: 1,
+            tags: vec!["a".into(), "b".into()],
+            body: Some(vec![1, 2, 3]),
+            name: Cow::Borrowed("first"),
+            meta: vec![(1, "x".to_string())].into_iter().collect(),
+            kind: Some(Kind::B(7))
+        },
+        Message {
+            id: 2,
+            tags: vec!["c".into()],
+            body: None,
+            name: Cow::Borrowed("second"),
+            meta: BTreeMap::new(),
+            kind: None
+        }
+    ];
+    let cbor = msgs.iter().map(|m| minicbor::to_vec(m).unwrap()).collect::<Vec<_>>();
+    let mut m = Message {
+        id: 0,
+        tags: Vec::new(),
+        body: None,
+        name: Cow::Borrowed(""),
+        meta: BTreeMap::new(),
+        kind: None
+    };
+    for (x, b) in msgs.iter().zip(&cbor) {
+        m.decode_into(&mut Decoder::new(b)).unwrap();
+        assert_eq!(x, &m)
+    }
+}
+
+#[test]
+fn absent_fields() {
+    let mut s = Sparse { a: Some("a".into()), b: vec![1] };
+    s.decode_into(&mut Decoder::new(&[0xa1, 1, 0x81, 2])).unwrap();
+    assert_eq!(Sparse { a: None, b: vec![2] }, s);
+
+    match s.decode_into(&mut Decoder::new(&[0xa0])) {
+        Err(decode::Error::MissingValue(1, "Sparse::b")) => {}
+        other => panic!("unexpected result: {:?}", other)
+    }
+}
+
+#[test]
+fn transparent_and_tuple() {
+    let mut w = Wrapper(vec![1, 2, 3]);
+    w.decode_into(&mut Decoder::new(&minicbor::to_vec(Wrapper(vec![4])).unwrap())).unwrap();
+    assert_eq!(Wrapper(vec![4]), w);
+
+    let mut t = Tuple(String::new(), Vec::new());
+    let x = Tuple("abc".into(), vec![1, 2]);
+    t.decode_into(&mut Decoder::new(&minicbor::to_vec(&x).unwrap())).unwrap();
+    assert_eq!(x, t)
+}
@@ -0,0 +1,42 @@
+use core::task::Poll;
+use minicbor::{Decode, DecodeInPlace, Encode};
+use minicbor::decode::DecodeInPlace as _;
+
+#[derive(Debug, PartialEq, Encode, Decode, DecodeInPlace)]
+#[cbor(map, parser)]
+struct Config {
+    #[n(1)] name: String,
+    #[cbor(n(2, alias = 7, alias = 8))] timeout: u32,
+    #[n(3, alias = 9)] retries: Option<u8>
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+enum Message {
+    #[n(0)] Ping(#[n(1, alias = 0)] u64)
+}
+
+#[test]
+fn aliases_are_decoded() {
+    let expected = Config { name: "x".into(), timeout: 30, retries: Some(3) };
+
+    let new = minicbor::to_vec(&expected).unwrap();
+    assert_eq!(b"\xa3\x01\x61x\x02\x18\x1e\x03\x03", new.as_slice());
+
+    for old in [&b"\xa3\x01\x61x\x07\x18\x1e\x09\x03"[..], b"\xa3\x01\x61x\x08\x18\x1e\x03\x03"] {
+        assert_eq!(expected, minicbor::decode(old).unwrap());
+
+        let mut c = Config { name: String::new(), timeout: 0, retries: None };
+        c.decode_into(&mut minicbor::Decoder::new(old)).unwrap();
+        assert_eq!(expected, c);
+
+        let mut p = ConfigParser::new();
+        assert!(matches!(p.feed(old), Poll::Ready(Ok(ref c)) if *c == expected))
+    }
+}
+
+#[test]
+fn aliases_in_arrays() {
+    // Element 0 is the alias of index 1, so the field is taken from there.
+    assert_eq!(Message::Ping(5), minicbor::decode(b"\x82\x00\x81\x05").unwrap());
+    assert_eq!(b"\x82\x00\x82\xf6\x05", minicbor::to_vec(Message::Ping(5)).unwrap().as_slice())
+}
@@ -77,7 +77,7 @@ fn gen_value(g: &mut Gen, rem: usize) -> Value {
         0 => Value::Null,
         1 => Value::Bool(true),
         2 => Value::Bool(false),
-        3 => Value::Integer(r.gen::<i128>() % std::i64::MAX as i128),
+        3 => Value::Integer(r.gen::<i128>() % i64::MAX as i128),
         4 => Value::Float(r.gen()),
         5 => Value::Bytes(Arbitrary::arbitrary(g)),
         6 => Value::Text(Arbitrary::arbitrary(g)),
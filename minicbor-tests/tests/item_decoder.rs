@@ -0,0 +1,69 @@
+use minicbor::Decoder;
+
+#[test]
+fn item_decoder_is_bounded() {
+    // [[1, 2], "abc", 3]
+    let cbor = [0x83, 0x82, 0x01, 0x02, 0x63, 0x61, 0x62, 0x63, 0x03];
+    let mut d = Decoder::new(&cbor);
+    assert_eq!(Some(3), d.array().unwrap());
+
+    let mut item = d.item_decoder().unwrap();
+    assert_eq!(4, d.position());
+    assert_eq!(0, item.position());
+    assert_eq!(Some(2), item.array().unwrap());
+    assert_eq!(1, item.u8().unwrap());
+    assert_eq!(2, item.u8().unwrap());
+    assert!(matches!(item.u8(), Err(minicbor::decode::Error::EndOfInput)));
+
+    // A decoder which reads too little does not affect the parent either.
+    let mut item = d.item_decoder().unwrap();
+    assert_eq!(8, d.position());
+    assert_eq!(Some(3), item.read_head().unwrap().argument());
+
+    assert_eq!(3, d.u8().unwrap());
+    assert!(d.item_decoder().is_err())
+}
+
+#[test]
+fn item_decoder_settings_and_errors() {
+    let mut d = Decoder::new(&[0x9f, 0x81, 0x81, 0x00, 0xff, 0x82, 0x00]);
+    d.set_max_depth(2);
+    d.set_reject_non_finite(true);
+    // Without a nesting stack, partial skip support does not check the depth.
+    if cfg!(not(feature = "__test-partial-skip-support")) {
+        assert!(d.item_decoder().is_err());
+        assert_eq!(0, d.position());
+    }
+
+    d.set_max_depth(3);
+    let mut item = d.item_decoder().unwrap();
+    assert_eq!(3, item.max_depth());
+    assert!(item.reject_non_finite());
+    let v: Vec<Vec<Vec<u8>>> = item.decode().unwrap();
+    assert_eq!(vec![vec![vec![0]]], v);
+    assert_eq!(5, d.position());
+
+    // Truncated input.
+    assert!(d.item_decoder().is_err());
+    assert_eq!(5, d.position())
+}
+
+#[test]
+fn input_and_remaining() {
+    let cbor = [0x82, 0x01, 0x63, 0x61, 0x62, 0x63, 0x03];
+    let mut d = Decoder::new(&cbor);
+    assert_eq!(&cbor, d.remaining());
+    assert_eq!(Some(2), d.array().unwrap());
+    assert_eq!(&cbor[1 ..], d.remaining());
+
+    // An item decoder's input is the item only.
+    let item = d.item_decoder().unwrap();
+    assert_eq!(&[0x01], item.input());
+    assert_eq!(&cbor[2 ..], d.remaining());
+    assert_eq!("abc", d.str().unwrap());
+    assert_eq!(&[0x03], d.remaining());
+    assert_eq!(&cbor, d.input());
+
+    d.set_position(100);
+    assert!(d.remaining().is_empty())
+}
@@ -0,0 +1,78 @@
+use minicbor::json::{from_json, to_json, Error};
+
+fn hex(s: &str) -> Vec<u8> {
+    hex::decode(s).unwrap()
+}
+
+#[test]
+fn cbor_to_json() {
+    let cases = [
+        ("00",                                  "0"),
+        ("3bffffffffffffffff",                  "-18446744073709551616"),
+        ("f93e00",                              "1.5"),
+        ("f9fbff",                              "-65504.0"),
+        ("f90001",                              "5.960464477539063e-8"),
+        ("f97c00",                              "null"),
+        ("fa7fc00000",                          "null"),
+        ("fb3ff8000000000000",                  "1.5"),
+        ("f7",                                  "null"),
+        ("f0",                                  "null"),
+        ("f5",                                  "true"),
+        ("4401020304",                          "\"AQIDBA\""),
+        ("d64401020304",                        "\"AQIDBA==\""),
+        ("d74401020304",                        "\"01020304\""),
+        ("d7824101d5824102d6410f",              "[\"01\",[\"Ag\",\"Dw==\"]]"),
+        ("c249010000000000000000",              "\"AQAAAAAAAAAA\""),
+        ("c349010000000000000000",              "\"~AQAAAAAAAAAA\""),
+        ("c11a514b67b0",                        "1363896240"),
+        ("6a22615c0a01c3a9e282ac",              "\"\\\"a\\\\\\n\\u0001é€\""),
+        ("7f61616162ff",                        "\"ab\""),
+        ("a20102206178",                        "{\"1\":2,\"-1\":\"x\"}"),
+        ("bf6161f6ff",                          "{\"a\":null}"),
+        ("80",                                  "[]")
+    ];
+    for (cbor, json) in cases.iter() {
+        assert_eq!(*json, to_json(&hex(cbor)).expect(cbor), "{}", cbor)
+    }
+}
+
+#[test]
+fn json_to_cbor() {
+    let cases = [
+        ("0",                       "00"),
+        ("-0",                      "00"),
+        ("-1",                      "20"),
+        ("18446744073709551615",    "1bffffffffffffffff"),
+        ("18446744073709551616",    "c249010000000000000000"),
+        ("-18446744073709551616",   "3bffffffffffffffff"),
+        ("-18446744073709551617",   "c349010000000000000000"),
+        ("1.5",                     "fb3ff8000000000000"),
+        ("1e2",                     "fb4059000000000000"),
+        ("\"\\u00e9\\ud83d\\ude00\"", "66c3a9f09f9880"),
+        ("\"a\\\"\\/\"",            "6361222f"),
+        (" [ true , false,null ] ", "83f5f4f6"),
+        ("{\"a\": {}, \"b\": []}",  "a26161a0616280"),
+    ];
+    for (json, cbor) in cases.iter() {
+        assert_eq!(hex(cbor), from_json(json).unwrap(), "{}", json)
+    }
+}
+
+#[test]
+fn roundtrip() {
+    let json = r#"{"name":"x","values":[1,-2,3.25,1e100],"nested":{"ok":true,"none":null}}"#;
+    assert_eq!(json, to_json(&from_json(json).unwrap()).unwrap())
+}
+
+#[test]
+fn errors() {
+    for json in ["", "[1,]", "{1:2}", "01", "[1 2]", "\"\\x\"", "\"\\ud800\"", "tru", "\"a\u{1}\"", "1."].iter() {
+        assert!(matches!(from_json(json), Err(Error::Syntax(..))), "{}", json)
+    }
+    assert!(from_json(&"[".repeat(5000)).is_err());
+
+    assert!(matches!(to_json(&hex("0000")), Err(Error::Decode(minicbor::decode::Error::TrailingBytes(1)))));
+    assert!(matches!(to_json(&hex("a1410102")), Err(Error::Unsupported(_))));
+    assert!(matches!(to_json(&hex("ff")), Err(Error::Decode(_))));
+    assert!(to_json(&[0x81; 5000]).is_err())
+}
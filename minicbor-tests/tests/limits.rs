@@ -0,0 +1,114 @@
+//! Test that decoding adversarial input stays within resource limits.
+
+use minicbor::{decode, Decoder};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// An array header claiming 2^64 - 1 elements followed by a single one.
+const HUGE_ARRAY: [u8; 10] = [0x9b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+
+/// A map header claiming 2^32 - 1 entries followed by a single one.
+const HUGE_MAP: [u8; 7] = [0xba, 0xff, 0xff, 0xff, 0xff, 0x01, 0x02];
+
+#[test]
+fn bogus_array_length_does_not_allocate() {
+    assert!(matches!(minicbor::decode::<Vec<u8>>(&HUGE_ARRAY), Err(decode::Error::EndOfInput)));
+    assert!(matches!(minicbor::decode::<VecDeque<u64>>(&HUGE_ARRAY), Err(decode::Error::EndOfInput)));
+    assert!(minicbor::decode::<Vec<[u64; 16]>>(&HUGE_ARRAY).is_err())
+}
+
+#[test]
+fn large_elements_do_not_multiply_allocation() {
+    // 1 MiB of one-byte items, each of which decodes to a 4 KiB value.
+    let mut input = vec![0x9a, 0x00, 0x10, 0x00, 0x00];
+    input.resize(input.len() + 0x10_0000, 0x40);
+    assert!(minicbor::decode::<Vec<minicbor::bytes::ByteArray<4096>>>(&input).is_err());
+    assert!(minicbor::decode::<VecDeque<minicbor::bytes::ByteArray<4096>>>(&input).is_err())
+}
+
+#[test]
+fn bogus_map_length_does_not_allocate() {
+    assert!(matches!(minicbor::decode::<HashMap<u8, u8>>(&HUGE_MAP), Err(decode::Error::EndOfInput)));
+    assert!(matches!(minicbor::decode::<BTreeMap<u8, u8>>(&HUGE_MAP), Err(decode::Error::EndOfInput)))
+}
+
+#[test]
+fn size_hint_is_capped_by_input() {
+    let mut d = Decoder::new(&HUGE_ARRAY);
+    let i = d.array_iter::<u8>().unwrap();
+    assert_eq!((1, Some(usize::MAX)), i.size_hint());
+
+    let mut d = Decoder::new(&HUGE_MAP);
+    let i = d.map_iter::<u8, u8>().unwrap();
+    assert_eq!((1, Some(u32::MAX as usize)), i.size_hint());
+
+    let mut d = Decoder::new(&[0x83, 1, 2, 3]);
+    let i = d.array_iter::<u8>().unwrap();
+    assert_eq!((3, Some(3)), i.size_hint())
+}
+
+#[cfg(not(feature = "__test-partial-skip-support"))]
+#[test]
+fn skip_respects_max_depth() {
+    // [[[ ... 0 ... ]]] nested 5000 times.
+    let mut input = vec![0x81; 5000];
+    input.push(0);
+
+    let mut d = Decoder::new(&input);
+    assert!(d.skip().is_err());
+
+    let mut d = Decoder::new(&input);
+    d.set_max_depth(5000);
+    assert!(d.skip().is_ok());
+    assert_eq!(input.len(), d.position());
+
+    // [_ [_ [_ ... ] ] ] nested 5000 times.
+    let mut input = vec![0x9f; 5000];
+    input.extend(vec![0xff; 5000]);
+
+    let mut d = Decoder::new(&input);
+    assert!(d.skip().is_err());
+
+    let mut d = Decoder::new(&input);
+    d.set_max_depth(5000);
+    assert!(d.skip().is_ok());
+    assert_eq!(input.len(), d.position())
+}
+
+#[cfg(not(feature = "__test-partial-skip-support"))]
+#[test]
+fn skip_rejects_misplaced_break() {
+    let mut d = Decoder::new(&[0x82, 0x01, 0xff]);
+    assert!(d.skip().is_err())
+}
+
+#[test]
+fn huge_string_lengths_are_rejected() {
+    // Byte and text strings claiming 2^64 - 1 bytes.
+    let bytes = [0x5b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+    let text  = [0x7b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+    assert!(Decoder::new(&bytes).bytes().is_err());
+    assert!(Decoder::new(&text).str().is_err());
+    assert!(Decoder::new(&bytes).skip().is_err());
+    for x in Decoder::new(&bytes).bytes_iter().unwrap() {
+        assert!(x.is_err())
+    }
+}
+
+#[test]
+fn usize_and_isize_are_range_checked() {
+    let n = minicbor::to_vec(u64::MAX).unwrap();
+    let r = minicbor::decode::<usize>(&n);
+    if usize::BITS < 64 {
+        assert!(matches!(r, Err(decode::Error::Overflow(..))))
+    } else {
+        assert_eq!(usize::MAX, r.unwrap())
+    }
+    let n = minicbor::to_vec(i64::MIN).unwrap();
+    let r = minicbor::decode::<isize>(&n);
+    if isize::BITS < 64 {
+        assert!(matches!(r, Err(decode::Error::Overflow(..))))
+    } else {
+        assert_eq!(isize::MIN, r.unwrap())
+    }
+    assert!(minicbor::decode::<std::num::NonZeroUsize>(&[0x00]).is_err())
+}
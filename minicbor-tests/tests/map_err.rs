@@ -0,0 +1,83 @@
+use minicbor::{Decode, DecodeInPlace, Encode};
+use minicbor::decode::{DecodeInPlace as _, Error};
+use std::borrow::Cow;
+
+fn invalid_port(_: Error) -> Error {
+    Error::Message("invalid port")
+}
+
+fn invalid_name(e: Error) -> Error {
+    match e {
+        Error::Utf8(_) => Error::Message("invalid name"),
+        other          => other
+    }
+}
+
+#[derive(Debug, PartialEq, Encode, Decode, DecodeInPlace)]
+#[cbor(map)]
+struct Service<'a> {
+    #[cbor(n(0), map_err = "invalid_port")] port: u16,
+    #[cbor(b(1), map_err = "invalid_name")] name: Cow<'a, str>,
+    #[cbor(n(2), map_err = "invalid_port")] backup: Option<u16>,
+    #[n(3)] weight: u8
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+enum Endpoint {
+    #[n(0)] Port(#[cbor(n(0), map_err = "invalid_port")] u16)
+}
+
+#[derive(Debug, PartialEq, Encode, Decode, DecodeInPlace)]
+#[cbor(transparent)]
+struct Port(#[cbor(n(0), map_err = "invalid_port")] u16);
+
+fn message(e: Error) -> &'static str {
+    match e {
+        Error::Message(m) => m,
+        other             => panic!("unexpected error: {}", other)
+    }
+}
+
+#[test]
+fn field_errors_are_mapped() {
+    // {0: 65536, 1: "a", 3: 1}
+    let e = minicbor::decode::<Service>(b"\xa3\x00\x1a\x00\x01\x00\x00\x01\x61\x61\x03\x01").unwrap_err();
+    assert_eq!("invalid port", message(e));
+
+    // {0: 80, 1: "\xff", 3: 1}
+    let e = minicbor::decode::<Service>(b"\xa3\x00\x18\x50\x01\x61\xff\x03\x01").unwrap_err();
+    assert_eq!("invalid name", message(e));
+
+    // {0: 80, 1: "a", 2: -1, 3: 1}
+    let e = minicbor::decode::<Service>(b"\xa4\x00\x18\x50\x01\x61\x61\x02\x20\x03\x01").unwrap_err();
+    assert_eq!("invalid port", message(e));
+
+    // Other fields and missing values are not affected.
+    let e = minicbor::decode::<Service>(b"\xa3\x00\x18\x50\x01\x61\x61\x03\x19\x01\x00").unwrap_err();
+    assert!(matches!(e, Error::TypeMismatch(..)));
+    let e = minicbor::decode::<Service>(b"\xa2\x01\x61\x61\x03\x01").unwrap_err();
+    assert!(matches!(e, Error::MissingValue(0, _)));
+
+    let s = Service { port: 80, name: Cow::Borrowed("a"), backup: Some(81), weight: 1 };
+    let cbor = minicbor::to_vec(&s).unwrap();
+    assert_eq!(s, minicbor::decode(&cbor).unwrap());
+
+    let e = minicbor::decode::<Endpoint>(b"\x82\x00\x81\x20").unwrap_err();
+    assert_eq!("invalid port", message(e));
+
+    let e = minicbor::decode::<Port>(b"\x20").unwrap_err();
+    assert_eq!("invalid port", message(e));
+    assert_eq!(Port(80), minicbor::decode(b"\x18\x50").unwrap())
+}
+
+#[test]
+fn field_errors_are_mapped_in_place() {
+    let mut s = Service { port: 0, name: Cow::Borrowed(""), backup: None, weight: 0 };
+    let cbor = b"\xa3\x00\x1a\x00\x01\x00\x00\x01\x61\x61\x03\x01";
+    let e = s.decode_into(&mut minicbor::Decoder::new(cbor)).unwrap_err();
+    assert_eq!("invalid port", message(e));
+
+    let mut p = Port(0);
+    let e = p.decode_into(&mut minicbor::Decoder::new(b"\x20")).unwrap_err();
+    assert_eq!("invalid port", message(e))
+}
@@ -0,0 +1,88 @@
+use minicbor::{Decode, Encode};
+use minicbor::net::{Address, Interface, Prefix};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+fn v6(s: &str) -> Ipv6Addr {
+    s.parse().unwrap()
+}
+
+#[test]
+fn addresses() {
+    let a = IpAddr::V6(v6("2001:db8:1234:deed:beef:cafe:face:feed"));
+    let cbor = minicbor::to_vec(Address(a)).unwrap();
+    assert_eq!(&hex::decode("d8365020010db81234deedbeefcafefacefeed").unwrap(), &cbor);
+    assert_eq!(Address(a), minicbor::decode(&cbor).unwrap());
+
+    let a = Ipv4Addr::new(192, 0, 2, 1);
+    let cbor = minicbor::to_vec(Address(a)).unwrap();
+    assert_eq!(Address(IpAddr::V4(a)), minicbor::decode(&cbor).unwrap());
+    assert!(minicbor::decode::<Address<Ipv6Addr>>(&cbor).is_err());
+
+    // Wrong length, tag or plain byte strings are rejected.
+    assert!(minicbor::decode::<Address<IpAddr>>(b"\xd8\x34\x43\xc0\x00\x02").is_err());
+    assert!(minicbor::decode::<Address<IpAddr>>(b"\xd8\x36\x44\xc0\x00\x02\x01").is_err());
+    assert!(minicbor::decode::<Address<IpAddr>>(b"\xd8\x35\x44\xc0\x00\x02\x01").is_err());
+    assert!(minicbor::decode::<Address<IpAddr>>(b"\x44\xc0\x00\x02\x01").is_err())
+}
+
+#[test]
+fn prefixes() {
+    let p = Prefix::new(v6("2001:db8:1234:ffff::"), 48).unwrap();
+    assert_eq!(v6("2001:db8:1234::"), p.addr());
+    assert_eq!(48, p.prefix_len());
+    let cbor = minicbor::to_vec(p).unwrap();
+    assert_eq!(&hex::decode("d8368218304620010db81234").unwrap(), &cbor);
+    assert_eq!(p, minicbor::decode(&cbor).unwrap());
+
+    // Trailing zero bytes are omitted, partial bytes are masked.
+    let p = Prefix::new(Ipv4Addr::new(10, 0, 255, 255), 20).unwrap();
+    assert_eq!(Ipv4Addr::new(10, 0, 240, 0), p.addr());
+    assert_eq!(b"\xd8\x34\x82\x14\x43\x0a\x00\xf0", minicbor::to_vec(p).unwrap().as_slice());
+    let p = Prefix::new(Ipv4Addr::new(10, 0, 0, 0), 24).unwrap();
+    assert_eq!(b"\xd8\x34\x82\x18\x18\x41\x0a", minicbor::to_vec(p).unwrap().as_slice());
+    let p = Prefix::new(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 0).unwrap();
+    assert_eq!(b"\xd8\x34\x82\x00\x40", minicbor::to_vec(p).unwrap().as_slice());
+    assert_eq!(p, minicbor::decode(b"\xd8\x34\x82\x00\x40").unwrap());
+
+    assert!(Prefix::new(Ipv4Addr::LOCALHOST, 33).is_none());
+    assert!(Prefix::new(Ipv6Addr::LOCALHOST, 129).is_none());
+
+    // Bits after the prefix length must be zero.
+    assert!(minicbor::decode::<Prefix<Ipv4Addr>>(b"\xd8\x34\x82\x14\x43\x0a\x00\xf8").is_err());
+    assert!(minicbor::decode::<Prefix<Ipv4Addr>>(b"\xd8\x34\x82\x08\x42\x0a\x01").is_err());
+    // Prefix length and bytes must fit the address.
+    assert!(minicbor::decode::<Prefix<Ipv4Addr>>(b"\xd8\x34\x82\x18\x21\x41\x0a").is_err());
+    assert!(minicbor::decode::<Prefix<Ipv4Addr>>(b"\xd8\x34\x82\x18\x20\x45\x01\x02\x03\x04\x05").is_err())
+}
+
+#[test]
+fn interfaces() {
+    let i = Interface::new(Ipv4Addr::new(192, 0, 2, 1), 24).unwrap();
+    assert_eq!(Prefix::new(Ipv4Addr::new(192, 0, 2, 0), 24).unwrap(), i.prefix());
+    let cbor = minicbor::to_vec(i).unwrap();
+    assert_eq!(b"\xd8\x34\x82\x44\xc0\x00\x02\x01\x18\x18", cbor.as_slice());
+    assert_eq!(i, minicbor::decode(&cbor).unwrap());
+
+    let i = Interface::new(v6("2001:db8:1234:deed:beef:cafe:face:feed"), 56).unwrap();
+    let cbor = minicbor::to_vec(i).unwrap();
+    assert_eq!(&hex::decode("d836825020010db81234deedbeefcafefacefeed1838").unwrap(), &cbor);
+    assert_eq!(i, minicbor::decode(&cbor).unwrap());
+
+    assert!(Interface::new(Ipv4Addr::LOCALHOST, 33).is_none());
+    // A prefix is not an interface.
+    assert!(minicbor::decode::<Interface<Ipv4Addr>>(b"\xd8\x34\x82\x18\x18\x43\xc0\x00\x02").is_err())
+}
+
+#[test]
+fn with_module() {
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct Peer {
+        #[cbor(n(0), with = "minicbor::net::address")] addr: IpAddr,
+        #[n(1)] port: u16
+    }
+
+    let p = Peer { addr: Ipv4Addr::new(127, 0, 0, 1).into(), port: 80 };
+    let cbor = minicbor::to_vec(&p).unwrap();
+    assert_eq!(b"\x82\xd8\x34\x44\x7f\x00\x00\x01\x18\x50", cbor.as_slice());
+    assert_eq!(p, minicbor::decode(&cbor).unwrap())
+}
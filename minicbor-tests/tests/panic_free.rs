@@ -0,0 +1,108 @@
+use minicbor::{Decode, DecodeInPlace, Decoder};
+use minicbor::bytes::ByteVec;
+use minicbor::data::Type;
+use minicbor::decode::DecodeInPlace as _;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Decode, DecodeInPlace)]
+struct Record<'a> {
+    #[n(0)] a: u8,
+    #[b(1)] b: &'a str,
+    #[n(2)] c: Option<Vec<i64>>,
+    #[n(5)] d: [u16; 3]
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Decode)]
+#[cbor(map, deny_unknown_fields, collect_missing)]
+struct Strict {
+    #[n(0)] a: i128,
+    #[n(1)] b: BTreeMap<String, ByteVec>,
+    #[n(2)] c: (f32, char, bool)
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Decode)]
+enum Choice {
+    #[n(0)] A(#[n(0)] u64),
+    #[n(1)] B { #[n(0)] x: Option<Box<Choice>> },
+    #[n(2)] C
+}
+
+struct Ignore;
+
+impl minicbor::visit::Visitor<'_> for Ignore {}
+
+/// Decode the input in every way we can think of, ignoring the results.
+fn decode_all(b: &[u8]) {
+    let _ = minicbor::decode::<Record>(b);
+    let _ = minicbor::decode::<Strict>(b);
+    let _ = minicbor::decode::<Choice>(b);
+    let _ = minicbor::decode::<(u8, i8, u32, i16, i32, i64, u128)>(b);
+    let _ = minicbor::decode::<Vec<(f64, Option<&str>)>>(b);
+    let _ = minicbor::decode::<[Option<u32>; 2]>(b);
+
+    let mut r = Record { a: 0, b: "", c: None, d: [0; 3] };
+    let _ = r.decode_into(&mut Decoder::new(b));
+
+    let mut d = Decoder::new(b);
+    let mut bytes = [0; 8];
+    let _ = d.bytes_into(&mut bytes);
+    let _ = Decoder::new(b).str_into(&mut bytes);
+    let _ = Decoder::new(b).skip();
+    let _ = Decoder::new(b).check_deterministic();
+    let _ = Decoder::new(b).read_head();
+    let _ = Decoder::new(b).simple();
+    let _ = Decoder::new(b).item_decoder();
+    let _ = minicbor::display(b).to_string();
+    let _ = format!("{:#}", minicbor::display(b));
+    let _ = minicbor::decode::Tokenizer::new(b).take_while(Result::is_ok).count();
+    let _ = minicbor::walk(b, &mut Ignore);
+
+    let mut d = Decoder::new(b);
+    while d.datatype().map(|t| t != Type::Break).unwrap_or(false) && d.skip().is_ok() {}
+}
+
+quickcheck::quickcheck! {
+    fn arbitrary_input(bytes: Vec<u8>) -> bool {
+        decode_all(&bytes);
+        true
+    }
+
+    // Valid heads followed by arbitrary bytes are more likely to reach
+    // nested decoding paths than completely random input.
+    fn arbitrary_nested_input(heads: Vec<u8>, bytes: Vec<u8>) -> bool {
+        let mut input = heads.into_iter().map(|h| 0x80 + h % 0x60).collect::<Vec<_>>();
+        input.extend(bytes);
+        decode_all(&input);
+        true
+    }
+}
+
+#[test]
+fn edge_cases() {
+    for b in [
+        &b""[..],
+        b"\x9f",                                  // unterminated array
+        b"\x9b\xff\xff\xff\xff\xff\xff\xff\xff",  // huge array length
+        b"\xbb\xff\xff\xff\xff\xff\xff\xff\xff",  // huge map length
+        b"\x5b\xff\xff\xff\xff\xff\xff\xff\xff",  // huge byte string length
+        b"\x3b\xff\xff\xff\xff\xff\xff\xff\xff",  // smallest negative integer
+        b"\xc3\x51\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00",
+        b"\x5f\x41\x00\x7f\xff",                  // mixed indefinite string chunks
+        b"\xf9\x7c\x00",                          // f16 infinity
+        b"\xff",
+        b"\x1c"
+    ] {
+        decode_all(b)
+    }
+
+    assert_eq!(i64::MIN, minicbor::decode::<i64>(b"\x3b\x7f\xff\xff\xff\xff\xff\xff\xff").unwrap());
+    assert_eq!(-24, minicbor::decode::<i8>(b"\x37").unwrap());
+    assert_eq!(-256, minicbor::decode::<i16>(b"\x38\xff").unwrap());
+    assert_eq!(i128::MIN, minicbor::decode::<i128>(b"\xc3\x50\x7f\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff").unwrap());
+
+    let h = Decoder::new(b"\x1a\x00\x01\x00\x00").read_head().unwrap();
+    assert_eq!(5, h.size());
+    assert_eq!(Some(0x10000), h.argument())
+}
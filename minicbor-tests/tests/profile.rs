@@ -0,0 +1,173 @@
+use minicbor::{Decoder, Encoder};
+use minicbor::decode::Error;
+use minicbor::encode::Write;
+use minicbor::profile::{self, CoreDeterministic, Ctap2, DagCbor, DCbor, DuplicateKeys, KeyOrder, Profile};
+use std::collections::HashMap;
+
+/// A lenient profile which keeps argument widths and the order of map keys
+/// and drops all but the last entry of duplicate keys.
+struct Lenient;
+
+impl Profile for Lenient {
+    fn shortest_arguments(&self) -> bool {
+        false
+    }
+
+    fn key_order(&self) -> KeyOrder {
+        KeyOrder::Preserve
+    }
+
+    fn duplicate_keys(&self) -> DuplicateKeys {
+        DuplicateKeys::KeepLast
+    }
+}
+
+#[test]
+fn core_deterministic() {
+    // Shortest arguments, definite lengths and sorted keys.
+    assert_eq!(b"\x01", profile::normalise(&CoreDeterministic, b"\x18\x01").unwrap().as_slice());
+    assert_eq!(b"\x82\x01\x02", profile::normalise(&CoreDeterministic, b"\x9f\x01\x02\xff").unwrap().as_slice());
+    assert_eq!(b"\xa2\x0a\x00\x20\x00", profile::normalise(&CoreDeterministic, b"\xa2\x20\x00\x0a\x00").unwrap().as_slice());
+    assert_eq!(b"\xf9\x3e\x00", profile::normalise(&CoreDeterministic, b"\xfb\x3f\xf8\x00\x00\x00\x00\x00\x00").unwrap().as_slice());
+    assert!(matches!(profile::normalise(&CoreDeterministic, b"\xa2\x01\x00\x01\x01"), Err(Error::Message(_))));
+    assert!(profile::validate(&CoreDeterministic, b"\xa2\x20\x00\x0a\x00").is_err());
+    assert!(profile::validate(&CoreDeterministic, b"\xc1\x01").is_ok());
+    assert!(matches!(profile::validate(&CoreDeterministic, b"\x01\x02"), Err(Error::TrailingBytes(1))))
+}
+
+#[test]
+fn dcbor_numeric_reduction() {
+    let n = |b: &[u8]| profile::normalise(&DCbor, b).unwrap();
+    assert_eq!(b"\x02", n(b"\xf9\x40\x00").as_slice());
+    assert_eq!(b"\x00", n(b"\xf9\x80\x00").as_slice());
+    assert_eq!(b"\x22", n(b"\xfb\xc0\x08\x00\x00\x00\x00\x00\x00").as_slice());
+    assert_eq!(b"\x1b\xff\xff\xff\xff\xff\xff\xf8\x00", n(b"\xfb\x43\xef\xff\xff\xff\xff\xff\xff").as_slice());
+    assert_eq!(b"\xfa\x5f\x80\x00\x00", n(b"\xfb\x43\xf0\x00\x00\x00\x00\x00\x00").as_slice());
+    assert_eq!(b"\xf9\x3e\x00", n(b"\xfb\x3f\xf8\x00\x00\x00\x00\x00\x00").as_slice());
+    assert_eq!(b"\xf9\x7e\x00", n(b"\xfa\x7f\xc0\x00\x01").as_slice());
+    assert_eq!(b"\xf9\x7c\x00", n(b"\xfa\x7f\x80\x00\x00").as_slice());
+    assert!(profile::normalise(&DCbor, b"\xf7").is_err());
+    assert_eq!(2u8, profile::decode(&DCbor, b"\x02").unwrap());
+    assert!(profile::decode::<_, f32>(&DCbor, b"\xf9\x40\x00").is_err())
+}
+
+#[test]
+fn builtin_profiles_agree_with_modules() {
+    let inputs: &[&[u8]] = &[
+        b"\xa3\x20\x61x\x0a\x61y\x01\x61z",
+        b"\xa2\x62bb\x01\x61a\x02",
+        b"\xfa\x3f\xc0\x00\x00",
+        b"\xc1\x01",
+        b"\xf7"
+    ];
+    for b in inputs {
+        assert_eq!(minicbor::ctap2::normalise(b).ok(), profile::normalise(&Ctap2, b).ok());
+        assert_eq!(minicbor::dag_cbor::normalise(b).ok(), profile::normalise(&DagCbor, b).ok())
+    }
+}
+
+#[test]
+fn custom_profile() {
+    // Argument widths and key order are kept, only the last duplicate survives.
+    let n = |b: &[u8]| profile::normalise(&Lenient, b).unwrap();
+    assert_eq!(b"\x18\x01", n(b"\x18\x01").as_slice());
+    assert_eq!(b"\xa2\x20\x00\x0a\x00", n(b"\xa2\x20\x00\x0a\x00").as_slice());
+    assert_eq!(b"\xa2\x02\x01\x01\x02", n(b"\xa3\x01\x00\x02\x01\x01\x02").as_slice());
+    assert_eq!(b"\x82\x01\x02", n(b"\x9f\x01\x02\xff").as_slice());
+
+    // Profiles can be used via references and trait objects.
+    let p: &dyn Profile = &Lenient;
+    assert!(profile::validate(p, b"\x19\x00\x01").is_ok());
+    assert!(profile::validate(&p, b"\x19\x00\x01").is_ok());
+    assert!(profile::validate(&CoreDeterministic, b"\x19\x00\x01").is_err())
+}
+
+#[test]
+fn decoder_check_profile() {
+    let mut d = Decoder::new(b"\x82\x01\xf9\x3e\x00\x82\x01\xf9\x40\x00");
+    d.check_profile(&DCbor).unwrap();
+    assert!(d.probe().check_profile(&DCbor).is_err());
+    assert!(d.probe().check_profile(&CoreDeterministic).is_ok());
+    assert_eq!(5, d.position());
+    d.skip().unwrap();
+
+    let mut d = Decoder::new(b"\x81\x81\x81\x00");
+    d.set_max_depth(2);
+    assert!(d.check_profile(&CoreDeterministic).is_err())
+}
+
+#[test]
+fn encoder_with_profile() {
+    let mut m = HashMap::new();
+    m.insert("b", 1.0);
+    m.insert("a", 1.5);
+    m.insert("c", f64::NAN);
+
+    let mut e = Encoder::with_profile(&DCbor, Vec::new());
+    e.encode(&m).unwrap();
+    let cbor = e.into_inner().into_inner().unwrap();
+    assert_eq!(b"\xa3\x61a\xf9\x3e\x00\x61b\x01\x61c\xf9\x7e\x00", cbor.as_slice());
+    assert_eq!(cbor, profile::to_vec(&DCbor, &m).unwrap());
+
+    // Indefinite lengths are made definite.
+    let mut e = Encoder::with_profile(&CoreDeterministic, Vec::new());
+    e.begin_array().unwrap().u8(1).unwrap().begin_str().unwrap().str("a").unwrap().str("b").unwrap().end().unwrap().end().unwrap();
+    assert_eq!(b"\x82\x01\x62ab", e.into_inner().into_inner().unwrap().as_slice());
+
+    // Items which can not be normalised are rejected while encoding.
+    let mut e = Encoder::with_profile(&DCbor, Vec::new());
+    assert!(e.undefined().is_err());
+    let mut e = Encoder::with_profile(&CoreDeterministic, Vec::new());
+    assert!(e.map(2).unwrap().u8(1).unwrap().u8(0).unwrap().u8(1).unwrap().u8(1).is_err());
+
+    // Incomplete items are reported.
+    let mut e = Encoder::with_profile(&CoreDeterministic, Vec::new());
+    e.array(2).unwrap().u8(1).unwrap();
+    assert!(e.into_inner().into_inner().is_err())
+}
+
+#[test]
+fn writer_agrees_with_normalise() {
+    let inputs: &[&[u8]] = &[
+        b"\x18\x01",
+        b"\xa3\x20\x61x\x0a\x61y\x01\x61z",
+        b"\xbf\x62bb\x9f\x01\xff\x61a\xc1\x19\x00\x02\xff",
+        b"\x7f\x62\xc3\xa4\x61b\xff",
+        b"\x5f\x41\x01\x40\x42\x02\x03\xff",
+        b"\xfb\x3f\xf8\x00\x00\x00\x00\x00\x00",
+        b"\x82\xa1\x01\x02\x80"
+    ];
+    for b in inputs {
+        let expected = profile::normalise(&CoreDeterministic, b).unwrap();
+        assert!(profile::validate(&CoreDeterministic, &expected).is_ok());
+
+        // Feeding the writer byte by byte gives the same result.
+        let mut w = profile::Writer::new(&CoreDeterministic, Vec::new());
+        for x in b.iter() {
+            w.write_all(&[*x]).unwrap()
+        }
+        assert_eq!(expected, w.into_inner().unwrap())
+    }
+    let mut w = profile::Writer::new(&CoreDeterministic, Vec::new());
+    assert!(w.write_all(b"\x62\xc3").is_ok());
+    assert!(w.write_all(b"\x28").is_err())
+}
+
+#[test]
+fn decoder_with_profile() {
+    let input = b"\x82\x01\xf9\x3e\x00\x61a";
+    let mut d = Decoder::with_profile(&DCbor, input).unwrap();
+    assert_eq!(0, d.position());
+    assert_eq!(Some(2), d.array().unwrap());
+    assert_eq!(1, d.u8().unwrap());
+    assert_eq!(1.5, d.f32().unwrap());
+    assert_eq!("a", d.str().unwrap());
+
+    // Every item is validated.
+    assert!(Decoder::with_profile(&DCbor, b"\x01\xf9\x40\x00").is_err());
+    assert!(Decoder::with_profile(&CoreDeterministic, b"\xa2\x01\x00\x01\x01").is_err());
+    assert!(Decoder::with_profile(&CoreDeterministic, b"\xa2\x0a\x00\x20\x00").is_ok());
+    assert!(Decoder::with_profile(&CoreDeterministic, b"\xa2\x20\x00\x0a\x00").is_err());
+    assert!(Decoder::with_profile(&CoreDeterministic, b"\x9f\xff").is_err());
+    assert!(Decoder::with_profile(&DagCbor, b"\xa1\x01\x00").is_err())
+}
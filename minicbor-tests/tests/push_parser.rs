@@ -0,0 +1,95 @@
+use core::task::Poll;
+use minicbor::{Decode, Encode};
+use minicbor::decode::Error;
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+#[cbor(parser)]
+struct Record {
+    #[n(0)] name: String,
+    #[n(1)] tags: Vec<u32>,
+    #[n(2)] note: Option<String>,
+    #[n(4)] data: Vec<u8>
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+#[cbor(map, parser)]
+pub struct Point<T> {
+    #[n(1)] x: T,
+    #[n(2)] y: T
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+#[cbor(parser)]
+struct Pair(#[n(0)] u8, #[cbor(n(1), with = "minicbor::bytes")] Vec<u8>);
+
+fn record(n: usize) -> Record {
+    Record {
+        name: format!("record #{}", n),
+        tags: (0 .. n as u32).collect(),
+        note: if n.is_multiple_of(2) { Some("even".into()) } else { None },
+        data: vec![n as u8; n * 10]
+    }
+}
+
+#[test]
+fn byte_by_byte() {
+    let r = record(7);
+    let cbor = minicbor::to_vec(&r).unwrap();
+    let mut p = RecordParser::new();
+    for b in &cbor[.. cbor.len() - 1] {
+        assert!(p.feed(std::slice::from_ref(b)).is_pending())
+    }
+    assert_eq!(Poll::Ready(Ok(r)), p.feed(&cbor[cbor.len() - 1 ..]).map_err(|e| e.to_string()));
+    assert!(p.remaining().is_empty())
+}
+
+#[test]
+fn sequence_of_values_in_arbitrary_chunks() {
+    let values = (0 .. 20).map(record).collect::<Vec<_>>();
+    let mut cbor = Vec::new();
+    for v in &values {
+        minicbor::encode(v, &mut cbor).unwrap()
+    }
+    for size in [1, 3, 64, 4096] {
+        let mut p = RecordParser::default();
+        let mut out = Vec::new();
+        for chunk in cbor.chunks(size) {
+            let mut input = chunk;
+            while let Poll::Ready(v) = p.feed(input) {
+                out.push(v.unwrap());
+                input = &[]
+            }
+        }
+        assert_eq!(values, out)
+    }
+}
+
+#[test]
+fn map_encoding_and_generics() {
+    let pt = Point { x: -1i64, y: 1000 };
+    let cbor = minicbor::to_vec(&pt).unwrap();
+    let mut p = PointParser::<i64>::new();
+    assert!(p.feed(&cbor[.. 3]).is_pending());
+    assert!(matches!(p.feed(&cbor[3 ..]), Poll::Ready(Ok(x)) if x == pt));
+
+    // Unknown keys are skipped, indefinite lengths are supported.
+    let mut p = PointParser::<u8>::new();
+    assert!(matches!(p.feed(b"\xbf\x01\x01\x03\x82\x00\x00\x02\x02\xff"), Poll::Ready(Ok(Point { x: 1, y: 2 }))));
+    // Missing values are reported.
+    assert!(matches!(p.feed(b"\xa1\x01\x01"), Poll::Ready(Err(Error::MissingValue(2, "Point::y")))))
+}
+
+#[test]
+fn tuple_structs_and_errors() {
+    let pair = Pair(1, vec![1, 2, 3]);
+    let cbor = minicbor::to_vec(&pair).unwrap();
+    let mut p = PairParser::new();
+    assert!(matches!(p.feed(&cbor), Poll::Ready(Ok(x)) if x == pair));
+
+    // A type mismatch of a field is reported as soon as the field is complete.
+    let mut p = PairParser::new();
+    assert!(matches!(p.feed(b"\x82\x61a"), Poll::Ready(Err(Error::TypeMismatch(..)))));
+    // As is any other head than an array.
+    let mut p = PairParser::new();
+    assert!(matches!(p.feed(b"\xa0"), Poll::Ready(Err(Error::TypeMismatch(..)))))
+}
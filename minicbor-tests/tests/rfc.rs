@@ -58,15 +58,15 @@ fn rfc_tv_float() {
     decode("f90001", 5.960464477539063e-08);
     decode("f90400", 6.103515625e-05);
     decode("f9c400", -4.0);
-    decode("f97c00", std::f64::INFINITY);
-    decode("f9fc00", std::f64::NEG_INFINITY);
-    decode("fa7f800000", std::f64::INFINITY);
-    decode("faff800000", std::f64::NEG_INFINITY);
-    decode("fb7ff0000000000000", std::f64::INFINITY);
-    decode("fbfff0000000000000", std::f64::NEG_INFINITY);
+    decode("f97c00", f64::INFINITY);
+    decode("f9fc00", f64::NEG_INFINITY);
+    decode("fa7f800000", f64::INFINITY);
+    decode("faff800000", f64::NEG_INFINITY);
+    decode("fb7ff0000000000000", f64::INFINITY);
+    decode("fbfff0000000000000", f64::NEG_INFINITY);
 
     roundtrip!(f32, "fa47c35000", 100000.0);
-    roundtrip!(f32, "fa7f7fffff", 3.4028234663852886e+38);
+    roundtrip!(f32, "fa7f7fffff", 3.402_823_5e38);
     roundtrip!(f64, "fb3ff199999999999a", 1.1);
     roundtrip!(f64, "fb7e37e43c8800759c", 1.0e+300);
     roundtrip!(f64, "fbc010666666666666", -4.1);
@@ -79,6 +79,73 @@ fn rfc_tv_float() {
     }
 }
 
+#[test]
+fn non_finite_floats_rejected() {
+    for s in &["f97c00", "f97e00", "f9fc00", "fa7fc00000", "fb7ff0000000000000"] {
+        let x = hex::decode(s).unwrap();
+        let mut d = Decoder::new(&x);
+        assert!(d.clone().f64().unwrap().is_nan() || d.clone().f64().unwrap().is_infinite());
+        d.set_reject_non_finite(true);
+        assert!(d.clone().f64().is_err());
+        assert!(d.f32().is_err())
+    }
+
+    let x = hex::decode("f93e00").unwrap();
+    let mut d = Decoder::new(&x);
+    d.set_reject_non_finite(true);
+    assert_eq!(1.5, d.f32().unwrap());
+
+    let mut e = Encoder::new(Vec::new());
+    e.set_reject_non_finite(true);
+    assert!(e.f64(f64::NAN).is_err());
+    assert!(e.f32(f32::INFINITY).is_err());
+    assert!(e.f16(f32::NEG_INFINITY).is_err());
+    assert!(e.f64(1.5).is_ok());
+    assert_eq!(vec![0xfb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0], e.into_inner())
+}
+
+#[test]
+fn trusted_utf8() {
+    let x = hex::decode("7f6261626161ff").unwrap();
+    let mut d = Decoder::new(&x);
+    assert!(!d.trusted_utf8());
+    unsafe { d.set_trusted_utf8(true) }
+    assert!(d.trusted_utf8());
+    assert_eq!(vec!["ab", "a"], d.clone().str_iter().unwrap().collect::<Result<Vec<_>, _>>().unwrap());
+    d.set_position(1);
+    assert_eq!("ab", d.str().unwrap())
+}
+
+#[test]
+fn utf8_errors() {
+    // A string long enough for the SIMD validation of feature "simdutf8".
+    let s = "\u{e4}bc".repeat(40);
+    let mut x = vec![0x78, s.len() as u8];
+    x.extend_from_slice(s.as_bytes());
+    assert_eq!(s, minicbor::decode::<&str>(&x).unwrap());
+
+    // Errors report the offset of the first invalid byte.
+    x[2 + 101] = 0xff;
+    match minicbor::decode::<&str>(&x) {
+        Err(minicbor::decode::Error::Utf8(e)) => assert_eq!(100, e.valid_up_to()),
+        other => panic!("unexpected result: {:?}", other)
+    }
+}
+
+#[test]
+fn owned_strings() {
+    let x = hex::decode("7f6261626161ff").unwrap();
+    assert_eq!("aba", minicbor::decode::<String>(&x).unwrap());
+    assert_eq!("aba", &*minicbor::decode::<Box<str>>(&x).unwrap());
+    assert_eq!("aba", minicbor::decode::<std::borrow::Cow<str>>(&x).unwrap());
+
+    // Each segment must be valid UTF-8 on its own.
+    let x = hex::decode("7f61c361a4ff").unwrap();
+    assert!(minicbor::decode::<String>(&x).is_err());
+    let x = hex::decode("7f62c3a4ff").unwrap();
+    assert_eq!("\u{e4}", minicbor::decode::<String>(&x).unwrap())
+}
+
 #[test]
 fn rfc_tv_small() {
     roundtrip!(bool, "f4", false);
@@ -536,7 +603,7 @@ fn rfc_tv_map() {
     let mut d = Decoder::new(&x);
     assert_eq!(None, d.map().unwrap());
     assert_eq!("Fun", d.str().unwrap());
-    assert_eq!(true, d.bool().unwrap());
+    assert!(d.bool().unwrap());
     assert_eq!("Amt", d.str().unwrap());
     assert_eq!(-2, d.i8().unwrap());
     assert_eq!(Type::Break, d.datatype().unwrap());
@@ -571,11 +638,7 @@ fn display() {
         (r#"-100"#,                         "3863"),
         (r#"-1000"#,                        "3903e7"),
         (r#"0e0"#,                          "f90000"),
-        // https://github.com/rust-lang/rust/pull/78618 has changed the format
-        // of f32/f64 to keep the minus sign for -0. This is currently in
-        // nightly, but not yet in stable, so we temporarily disable the
-        // following test. TODO: Enable the test again.
-        // (r#"-0e0"#,                          "f98000"),
+        (r#"-0e0"#,                         "f98000"),
         (r#"1e0"#,                          "f93c00"),
         (r#"1.1e0"#,                        "fb3ff199999999999a"),
         (r#"1.5e0"#,                        "f93e00"),
@@ -587,15 +650,15 @@ fn display() {
         (r#"6.1035156e-5"#,                 "f90400"),
         (r#"-4e0"#,                         "f9c400"),
         (r#"-4.1e0"#,                       "fbc010666666666666"),
-        (r#"inf"#,                          "f97c00"),
+        (r#"Infinity"#,                     "f97c00"),
         (r#"NaN"#,                          "f97e00"),
-        (r#"-inf"#,                         "f9fc00"),
-        (r#"inf"#,                          "fa7f800000"),
+        (r#"-Infinity"#,                    "f9fc00"),
+        (r#"Infinity"#,                     "fa7f800000"),
         (r#"NaN"#,                          "fa7fc00000"),
-        (r#"-inf"#,                         "faff800000"),
-        (r#"inf"#,                          "fb7ff0000000000000"),
+        (r#"-Infinity"#,                    "faff800000"),
+        (r#"Infinity"#,                     "fb7ff0000000000000"),
         (r#"NaN"#,                          "fb7ff8000000000000"),
-        (r#"-inf"#,                         "fbfff0000000000000"),
+        (r#"-Infinity"#,                    "fbfff0000000000000"),
         (r#"false"#,                        "f4"),
         (r#"true"#,                         "f5"),
         (r#"null"#,                         "f6"),
@@ -613,7 +676,7 @@ fn display() {
         (r#""""#,                           "60"),
         (r#""a""#,                          "6161"),
         (r#""IETF""#,                       "6449455446"),
-        (r#"""\""#,                         "62225c"),
+        (r#""\"\\""#,                       "62225c"),
         (r#"[]"#,                           "80"),
         (r#"[1, 2, 3]"#,                    "83010203"),
         (r#"[1, [2, 3], [4, 5]]"#,          "8301820203820405"),
@@ -647,14 +710,74 @@ fn display() {
         assert_eq!(*s, format!("{}", minicbor::display(&h)), "failed input: \"{}\"", x)
     }
 
-    let mut ss = String::new();
-    let mut xx = String::new();
+    let ss = xs.iter().map(|(s, _)| *s).collect::<Vec<_>>().join(", ");
+    let xx = xs.iter().map(|(_, x)| *x).collect::<String>();
 
+    let h = hex::decode(xx).unwrap();
+    assert_eq!(ss, format!("{}", minicbor::display(&h)))
+}
+
+#[test]
+fn display_edge_cases() {
+    let xs = &[
+        (r#"-18446744073709551616"#,        "3bffffffffffffffff"),
+        (r#""a\nb\u0001""#,                 "64610a6201"),
+        (r#"""_"#,                          "7fff"),
+        (r#"''_"#,                          "5fff"),
+        (r#"{_ }"#,                         "bfff"),
+        (r#"1(2(3))"#,                      "c1c203"),
+        (r#"[1,  !!! decoding error: end of input bytes"#, "8201")
+    ];
     for (s, x) in xs {
-        ss.push_str(s);
-        xx.push_str(x)
+        let h = hex::decode(x).unwrap();
+        assert_eq!(*s, format!("{}", minicbor::display(&h)), "failed input: \"{}\"", x)
+    }
+}
+
+#[test]
+fn rfc8949_appendix_a() {
+    use minicbor::decode::{Token, Tokenizer};
+    use minicbor::testvectors::{check_roundtrip, APPENDIX_A};
+
+    // Re-encode every token as it was decoded.
+    fn reencode(b: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut e = Encoder::new(Vec::new());
+        for t in Tokenizer::new(b) {
+            match t? {
+                Token::Bool(x)     => e.bool(x)?,
+                Token::U8(x)       => e.u8(x)?,
+                Token::U16(x)      => e.u16(x)?,
+                Token::U32(x)      => e.u32(x)?,
+                Token::U64(x)      => e.u64(x)?,
+                Token::I8(x)       => e.i8(x)?,
+                Token::I16(x)      => e.i16(x)?,
+                Token::I32(x)      => e.i32(x)?,
+                Token::I64(x)      => e.i64(x)?,
+                Token::F16(x)      => e.f16(x)?,
+                Token::F32(x)      => e.f32(x)?,
+                Token::F64(x)      => e.f64(x)?,
+                Token::Bytes(x)    => e.bytes(x)?,
+                Token::String(x)   => e.str(x)?,
+                Token::Array(n)    => e.array(n)?,
+                Token::Map(n)      => e.map(n)?,
+                Token::Tag(x)      => e.tag(x)?,
+                Token::Simple(x)   => e.simple(x)?,
+                Token::Break       => e.end()?,
+                Token::Null        => e.null()?,
+                Token::Undefined   => e.undefined()?,
+                Token::BeginBytes  => e.begin_bytes()?,
+                Token::BeginString => e.begin_str()?,
+                Token::BeginArray  => e.begin_array()?,
+                Token::BeginMap    => e.begin_map()?
+            };
+        }
+        Ok(e.into_inner())
     }
 
-    let h = hex::decode(xx).unwrap();
-    assert_eq!(ss, format!("{}", minicbor::display(&h)))
+    // -2^64 is outside of the `i64` range.
+    let vectors = APPENDIX_A.iter().filter(|v| v.diagnostic != "-18446744073709551616");
+
+    if let Err(e) = check_roundtrip(vectors, reencode) {
+        panic!("{}", e)
+    }
 }
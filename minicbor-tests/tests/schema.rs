@@ -0,0 +1,163 @@
+use minicbor::{Encode, Schema};
+use minicbor::schema::cddl;
+use std::collections::BTreeMap;
+
+#[test]
+fn std_types() {
+    assert_eq!("root = [* uint]\n", cddl::<Vec<u8>>());
+    assert_eq!("root = {* tstr => [int, float / null]}\n", cddl::<BTreeMap<String, (i64, Option<f32>)>>());
+    assert_eq!("root = [3*3 bool]\n", cddl::<[bool; 3]>());
+    assert_eq!("root = bstr .size 4\n", cddl::<minicbor::bytes::ByteArray<4>>());
+    assert_eq!("root = [0, tstr] / [1, []]\n", cddl::<Result<Box<str>, ()>>())
+}
+
+#[test]
+fn array_struct() {
+    #[derive(Encode, Schema)]
+    struct A {
+        #[n(1)] a: u8,
+        #[n(2)] b: Option<u8>,
+        #[n(3)] c: String,
+        #[n(5)] d: Option<bool>,
+        #[n(6)] r#e: Option<Vec<u8>>
+    }
+
+    let expected = "\
+A = [
+  null,
+  a: uint,
+  b: uint / null,
+  c: tstr,
+  ? (
+    null,
+    d: bool / null,
+    ? (
+      e: [* uint] / null,
+    ),
+  ),
+]
+";
+    assert_eq!(expected, cddl::<A>())
+}
+
+#[test]
+fn map_and_tuple_structs() {
+    #[derive(Encode, Schema)]
+    struct T(#[n(0)] u32, #[n(1)] Option<T2>);
+
+    #[derive(Encode, Schema)]
+    #[cbor(map)]
+    struct T2 {
+        #[n(0)] x: i8,
+        #[n(1)] y: Option<Box<T>>
+    }
+
+    #[derive(Encode, Schema)]
+    #[cbor(map)]
+    struct Empty {}
+
+    let expected = "\
+T = [
+  uint,
+  ? (
+    T2 / null,
+  ),
+]
+
+T2 = {
+  0 => int,  ; x
+  ? 1 => T,  ; y
+}
+";
+    assert_eq!(expected, cddl::<T>());
+    assert_eq!("Empty = {}\n", cddl::<Empty>())
+}
+
+#[test]
+fn enums() {
+    #[allow(dead_code)]
+    #[derive(Encode, Schema)]
+    #[cbor(map)]
+    enum E {
+        #[n(0)] A,
+        #[n(1)] B(#[n(0)] u8),
+        #[n(2)] #[cbor(array)] C { #[n(0)] c: Kind }
+    }
+
+    #[allow(dead_code)]
+    #[derive(Encode, Schema)]
+    #[cbor(index_only)]
+    enum Kind {
+        #[n(3)] X,
+        #[n(7)] Y
+    }
+
+    let expected = "\
+E = [0, {}]
+  / [1, E-B]
+  / [2, E-C]
+
+E-B = {
+  0 => uint,
+}
+
+E-C = [
+  c: Kind,
+]
+
+Kind = 3
+  / 7
+";
+    assert_eq!(expected, cddl::<E>())
+}
+
+#[test]
+fn generic_types_are_inlined() {
+    #[derive(Encode, Schema)]
+    #[cbor(map)]
+    struct W<T> {
+        #[n(0)] inner: T
+    }
+
+    #[derive(Encode, Schema)]
+    struct S {
+        #[n(0)] w: W<Vec<String>>
+    }
+
+    let expected = "\
+S = [
+  w: {
+    0 => [* tstr],  ; inner
+  },
+]
+";
+    assert_eq!(expected, cddl::<S>())
+}
+
+#[test]
+fn custom_codecs() {
+    #[derive(Encode, Schema)]
+    #[cbor(transparent)]
+    struct Id(#[n(0)] u64);
+
+    #[derive(Encode, Schema)]
+    struct C {
+        #[n(0)] id: Id,
+        #[cbor(n(1), with = "minicbor::bytes")] data: Vec<u8>,
+        #[cbor(n(2), encode_with = "encode_custom")] custom: u8
+    }
+
+    fn encode_custom<W: minicbor::encode::Write>(x: &u8, e: &mut minicbor::Encoder<W>) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.str(&x.to_string())?.ok()
+    }
+
+    let expected = "\
+C = [
+  id: uint,
+  data: bstr,
+  custom: any,
+]
+";
+    assert_eq!(expected, cddl::<C>());
+    assert_eq!("root = uint\n", cddl::<Id>())
+}
@@ -0,0 +1,192 @@
+use minicbor::serde::{de, decode, to_vec};
+use minicbor::Encoder;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Record<'a> {
+    id: u64,
+    name: &'a str,
+    #[serde(with = "serde_bytes_compat")]
+    data: Vec<u8>,
+    tags: Vec<String>,
+    parent: Option<Box<Record<'a>>>,
+    shape: Shape,
+    attrs: BTreeMap<String, i32>
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Line(i16, i16),
+    Rect { w: u32, h: u32 }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Newtype(u8);
+
+/// Serialize `Vec<u8>` as CBOR bytes instead of an array of integers.
+mod serde_bytes_compat {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(b: &[u8], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_bytes(b)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        <&[u8]>::deserialize(d).map(Vec::from)
+    }
+}
+
+#[test]
+fn roundtrip() {
+    let mut attrs = BTreeMap::new();
+    attrs.insert("a".to_string(), -1);
+    attrs.insert("b".to_string(), 100_000);
+    let r = Record {
+        id: 1,
+        name: "outer",
+        data: vec![1, 2, 3],
+        tags: vec!["x".into(), "y".into()],
+        parent: Some(Box::new(Record {
+            id: 2,
+            name: "inner",
+            data: Vec::new(),
+            tags: Vec::new(),
+            parent: None,
+            shape: Shape::Rect { w: 3, h: 4 },
+            attrs: BTreeMap::new()
+        })),
+        shape: Shape::Line(-1, 1),
+        attrs
+    };
+    let bytes = to_vec(&r).unwrap();
+    assert_eq!(r, decode::<Record>(&bytes).unwrap());
+
+    for s in [Shape::Empty, Shape::Circle(1.5), Shape::Line(0, -7), Shape::Rect { w: 0, h: 1 }] {
+        let bytes = to_vec(&s).unwrap();
+        assert_eq!(s, decode::<Shape>(&bytes).unwrap())
+    }
+
+    let bytes = to_vec(&Newtype(7)).unwrap();
+    assert_eq!(vec![7], bytes);
+    assert_eq!(Newtype(7), decode(&bytes).unwrap());
+}
+
+#[test]
+fn data_model_mapping() {
+    assert_eq!(vec![0xf6], to_vec(&()).unwrap());
+    assert_eq!(vec![0xf6], to_vec(&None::<u8>).unwrap());
+    assert_eq!(vec![0x61, 0x61], to_vec(&'a').unwrap());
+    assert_eq!(vec![0x65, b'E', b'm', b'p', b't', b'y'], to_vec(&Shape::Empty).unwrap());
+    assert_eq!(vec![0xa1, 0x61, b'w', 0x01], {
+        let mut m = BTreeMap::new();
+        m.insert("w", 1);
+        to_vec(&m).unwrap()
+    });
+    assert_eq!(minicbor::to_vec(u64::MAX).unwrap(), to_vec(&u128::from(u64::MAX)).unwrap());
+    assert!(to_vec(&(u128::from(u64::MAX) + 1)).is_err())
+}
+
+#[test]
+fn decode_minicbor_encoded() {
+    // Indefinite-length containers and strings, plus a tag to be ignored.
+    let mut e = Encoder::new(Vec::new());
+    e.begin_map().unwrap()
+        .str("w").unwrap().u8(2).unwrap()
+        .begin_str().unwrap().str("h").unwrap().end().unwrap()
+        .tag(minicbor::data::Tag::Unassigned(1000)).unwrap().u8(3).unwrap()
+        .end().unwrap();
+    let mut bytes = vec![0xa1, 0x64, b'R', b'e', b'c', b't'];
+    bytes.extend_from_slice(&e.into_inner());
+    assert_eq!(Shape::Rect { w: 2, h: 3 }, decode(&bytes).unwrap());
+
+    let mut e = Encoder::new(Vec::new());
+    e.begin_array().unwrap().i8(-1).unwrap().u32(70000).unwrap().end().unwrap();
+    assert_eq!((-1, 70000), decode::<(i8, u32)>(&e.into_inner()).unwrap());
+}
+
+#[test]
+fn decode_errors() {
+    // Too many elements.
+    let bytes = minicbor::to_vec([1u8, 2, 3]).unwrap();
+    assert!(matches!(decode::<(u8, u8)>(&bytes), Err(de::Error::Decode(_))));
+
+    // Missing struct field.
+    let mut e = Encoder::new(Vec::new());
+    e.map(1).unwrap().str("Rect").unwrap().map(1).unwrap().str("w").unwrap().u8(1).unwrap();
+    assert!(matches!(decode::<Shape>(&e.into_inner()), Err(de::Error::Custom(_))));
+
+    // Type mismatch.
+    let bytes = minicbor::to_vec("x").unwrap();
+    assert!(matches!(decode::<Newtype>(&bytes), Err(de::Error::Custom(_))));
+
+    // Excessive nesting.
+    let bytes = [0x81; 1000];
+    assert!(decode::<serde::de::IgnoredAny>(&bytes).is_err());
+    assert!(decode::<Vec<Vec<Vec<()>>>>(&bytes).is_err());
+}
+
+#[test]
+fn transcode_json() {
+    let json = r#"{"a":[1,-2,3.5,null,true],"b":{"c":"d"},"e":[]}"#;
+
+    let mut s = minicbor::serde::Serializer::new(Vec::new());
+    serde_transcode::transcode(&mut serde_json::Deserializer::from_str(json), &mut s).unwrap();
+    let cbor = s.into_inner();
+
+    let mut out = Vec::new();
+    let mut d = minicbor::serde::Deserializer::new(&cbor);
+    serde_transcode::transcode(&mut d, &mut serde_json::Serializer::new(&mut out)).unwrap();
+    d.end().unwrap();
+    assert_eq!(json.as_bytes(), out.as_slice());
+
+    // Trailing bytes are detected.
+    let mut cbor = minicbor::to_vec(1u8).unwrap();
+    cbor.push(0x02);
+    let mut d = minicbor::serde::Deserializer::new(&cbor);
+    serde_transcode::transcode(&mut d, &mut serde_json::Serializer::new(Vec::new())).unwrap();
+    assert!(matches!(d.end(), Err(de::Error::Decode(minicbor::decode::Error::TrailingBytes(1)))))
+}
+
+#[test]
+fn serde_cbor_compat() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Item {
+        a: u8,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        b: Option<u8>,
+        c: Shape
+    }
+
+    let items = [
+        Item { a: 1, b: None, c: Shape::Empty },
+        Item { a: 2, b: Some(3), c: Shape::Line(0, -7) },
+        Item { a: 3, b: None, c: Shape::Rect { w: 0, h: 1 } },
+        Item { a: 4, b: Some(5), c: Shape::Circle(1.1) }
+    ];
+
+    for x in &items {
+        let bytes = to_vec(x).unwrap();
+        assert_eq!(*x, serde_cbor::from_slice::<Item>(&bytes).unwrap());
+        let bytes = serde_cbor::to_vec(x).unwrap();
+        assert_eq!(*x, decode::<Item>(&bytes).unwrap());
+
+        let mut p = minicbor::serde::Serializer::new(Vec::new()).packed_format();
+        x.serialize(&mut p).unwrap();
+        let bytes = p.into_inner();
+        assert_eq!(*x, serde_cbor::from_slice::<Item>(&bytes).unwrap());
+        let bytes = serde_cbor::ser::to_vec_packed(x).unwrap();
+        assert_eq!(*x, decode::<Item>(&bytes).unwrap())
+    }
+
+    // Apart from floats, which `serde_cbor` encodes in the shortest form,
+    // the encodings are identical.
+    for x in &items[.. 3] {
+        assert_eq!(serde_cbor::to_vec(x).unwrap(), to_vec(x).unwrap());
+        let mut p = minicbor::serde::Serializer::new(Vec::new()).packed_format();
+        x.serialize(&mut p).unwrap();
+        assert_eq!(serde_cbor::ser::to_vec_packed(x).unwrap(), p.into_inner())
+    }
+}
@@ -0,0 +1,158 @@
+use minicbor::{Describe, Encode};
+use minicbor::sizes::profile;
+use std::collections::BTreeMap;
+
+#[test]
+fn array_struct() {
+    #[derive(Encode, Describe)]
+    struct A {
+        #[n(0)] a: u8,
+        #[n(2)] b: Option<String>,
+        #[n(3)] c: Option<u32>
+    }
+
+    // [100, null, "abc"]
+    let cbor = minicbor::to_vec(A { a: 100, b: Some("abc".into()), c: None }).unwrap();
+    let r = profile::<A>(&cbor).unwrap();
+    assert_eq!(cbor.len(), r.bytes());
+    assert_eq!(None, r.index());
+    assert_eq!(2, r.fields().len());
+    assert_eq!((Some(0), Some("a"), 2), info(r.get("a").unwrap()));
+    assert_eq!((Some(2), Some("b"), 4), info(r.get("b").unwrap()));
+    assert!(r.get("c").is_none());
+    assert_eq!(r.get("b"), r.get_index(2))
+}
+
+#[test]
+fn map_struct_includes_keys() {
+    #[derive(Encode, Describe)]
+    #[cbor(map)]
+    struct M {
+        #[n(1)] x: u64,
+        #[n(24)] y: Vec<u8>,
+        #[cbor(n(30), with = "minicbor::bytes")] z: Vec<u8>
+    }
+
+    let cbor = minicbor::to_vec(M { x: 0x1_0000, y: vec![1, 2], z: vec![0; 3] }).unwrap();
+    let r = profile::<M>(&cbor).unwrap();
+    assert_eq!(18, r.bytes());
+    assert_eq!(6, r.get("x").unwrap().bytes());
+    assert_eq!(5, r.get("y").unwrap().bytes());
+    assert_eq!(6, r.get("z").unwrap().bytes());
+    assert!(r.get("z").unwrap().fields().is_empty())
+}
+
+#[test]
+fn nested_values_are_summed_up() {
+    #[derive(Encode, Describe)]
+    struct Point(#[n(0)] i32, #[n(1)] i32);
+
+    #[derive(Encode, Describe)]
+    #[cbor(map)]
+    struct Paths {
+        #[n(0)] paths: BTreeMap<String, Vec<Point>>
+    }
+
+    let mut paths = BTreeMap::new();
+    paths.insert("a".to_string(), vec![Point(1, -1000), Point(2, 3)]);
+    paths.insert("b".to_string(), vec![Point(-30, 4)]);
+    let cbor = minicbor::to_vec(Paths { paths }).unwrap();
+    let r = profile::<Paths>(&cbor).unwrap();
+    let p = r.get("paths").unwrap();
+    assert_eq!(cbor.len() - 1, p.bytes());
+    assert_eq!(2, p.fields().len());
+    assert_eq!(4, p.get_index(0).unwrap().bytes());
+    assert_eq!(5, p.get_index(1).unwrap().bytes())
+}
+
+#[test]
+fn enums() {
+    #[allow(dead_code)]
+    #[derive(Encode, Describe)]
+    enum E {
+        #[n(0)] A,
+        #[n(1)] #[cbor(map)] B { #[n(0)] b: u8 },
+        #[n(2)] C(#[n(0)] Kind)
+    }
+
+    #[allow(dead_code)]
+    #[derive(Encode, Describe)]
+    #[cbor(index_only)]
+    enum Kind {
+        #[n(3)] X,
+        #[n(30)] Y
+    }
+
+    let cbor = minicbor::to_vec([E::A, E::B { b: 0xff }, E::C(Kind::Y), E::C(Kind::X)]).unwrap();
+    let r = profile::<Vec<E>>(&cbor).unwrap();
+    assert_eq!(3, r.fields().len());
+    assert_eq!((Some(0), Some("A"), 3), info(r.get("A").unwrap()));
+    assert_eq!((Some(1), Some("B"), 6), info(r.get("B").unwrap()));
+    assert_eq!(3, r.get("B").and_then(|b| b.get("b")).unwrap().bytes());
+    let c = r.get("C").unwrap();
+    assert_eq!(9, c.bytes());
+    assert_eq!(3, c.get_index(0).unwrap().bytes());
+    assert_eq!(2, c.get_index(0).and_then(|k| k.get("Y")).unwrap().bytes());
+    assert_eq!(1, c.get_index(0).and_then(|k| k.get("X")).unwrap().bytes())
+}
+
+#[test]
+fn transparent_and_generic() {
+    #[derive(Encode, Describe)]
+    #[cbor(transparent)]
+    struct Wrapper(#[n(0)] Inner<String>);
+
+    #[derive(Encode, Describe)]
+    struct Inner<T> {
+        #[n(0)] value: T,
+        #[n(1)] extra: Result<bool, u8>
+    }
+
+    let cbor = minicbor::to_vec(Wrapper(Inner { value: "hi".into(), extra: Ok(true) })).unwrap();
+    let r = profile::<Wrapper>(&cbor).unwrap();
+    assert_eq!(3, r.get("value").unwrap().bytes());
+    assert_eq!(3, r.get("extra").and_then(|e| e.get("Ok")).unwrap().bytes())
+}
+
+#[test]
+fn unexpected_input() {
+    #[derive(Encode, Describe)]
+    struct S {
+        #[n(0)] a: u8
+    }
+
+    // A map instead of an array.
+    let r = profile::<S>(&[0xa1, 0x00, 0x01]).unwrap();
+    assert_eq!(3, r.bytes());
+    assert!(r.fields().is_empty());
+
+    // Indefinite length with an unknown trailing element.
+    let r = profile::<S>(&[0x9f, 0x01, 0x02, 0xff]).unwrap();
+    assert_eq!(4, r.bytes());
+    assert_eq!(1, r.get("a").unwrap().bytes());
+
+    assert!(profile::<S>(&[0x82, 0x01]).is_err())
+}
+
+#[test]
+fn display() {
+    #[derive(Encode, Describe)]
+    struct S {
+        #[n(0)] a: u8,
+        #[n(1)] b: (String, u8)
+    }
+
+    let cbor = minicbor::to_vec(S { a: 1, b: ("abcdef".into(), 2) }).unwrap();
+    let expected = "\
+11 bytes
+  0 a: 1 bytes (9.1%)
+  1 b: 9 bytes (81.8%)
+    0: 7 bytes (63.6%)
+    1: 1 bytes (9.1%)
+";
+    assert_eq!(expected, profile::<S>(&cbor).unwrap().to_string())
+}
+
+fn info(r: &minicbor::sizes::Report) -> (Option<u64>, Option<&str>, usize) {
+    (r.index(), r.name(), r.bytes())
+}
@@ -103,8 +103,8 @@ impl Encode for Cbor {
                 e.tag(*t)?;
                 e.encode(v)?.ok()
             }
-            Cbor::String(s) => e.str(&s)?.ok(),
-            Cbor::Bytes(b)  => e.bytes(&b)?.ok(),
+            Cbor::String(s) => e.str(s)?.ok(),
+            Cbor::Bytes(b)  => e.bytes(b)?.ok(),
             Cbor::StringIndef(v) => {
                 e.begin_str()?;
                 for s in v {
@@ -139,7 +139,7 @@ impl<'b> Decode<'b> for Cbor {
                 }
             }
             Type::ArrayIndef => {
-                if let None = d.array()? {
+                if d.array()?.is_none() {
                     let mut v = Vec::new();
                     while Type::Break != d.datatype()? {
                         v.push(Self::decode(d)?)
@@ -164,7 +164,7 @@ impl<'b> Decode<'b> for Cbor {
                 }
             }
             Type::MapIndef => {
-                if let None = d.map()? {
+                if d.map()?.is_none() {
                     let mut m = BTreeMap::new();
                     while Type::Break != d.datatype()? {
                         let k = Self::decode(d)?;
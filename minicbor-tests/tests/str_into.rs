@@ -0,0 +1,44 @@
+use minicbor::Decoder;
+use minicbor::decode::Error;
+
+#[test]
+fn str_into_buffer() {
+    let mut buf = [0; 8];
+
+    let mut d = Decoder::new(b"\x65hello\x60");
+    assert_eq!("hello", d.str_into(&mut buf).unwrap());
+    assert_eq!("", d.str_into(&mut buf).unwrap());
+
+    // Segmented strings are concatenated.
+    let mut d = Decoder::new(b"\x7f\x62ab\x60\x63cde\xff\x01");
+    assert_eq!("abcde", d.str_into(&mut buf).unwrap());
+    assert_eq!(1, d.u8().unwrap());
+
+    // Too small buffers report the required size and leave the position.
+    let mut d = Decoder::new(b"\x7f\x65hello\x65world\xff");
+    assert!(matches!(d.str_into(&mut buf), Err(Error::BufferTooSmall(10))));
+    assert_eq!(0, d.position());
+    let mut buf = [0; 10];
+    assert_eq!("helloworld", d.str_into(&mut buf).unwrap());
+
+    // Invalid UTF-8 and other types are rejected.
+    assert!(matches!(Decoder::new(b"\x62\xc3\x28").str_into(&mut buf), Err(Error::Utf8(_))));
+    assert!(matches!(Decoder::new(b"\x42ab").str_into(&mut buf), Err(Error::TypeMismatch(..))));
+    assert!(matches!(Decoder::new(b"\x7f\x42ab\xff").str_into(&mut buf), Err(Error::TypeMismatch(..))))
+}
+
+#[test]
+fn bytes_into_buffer() {
+    let mut buf = [0; 4];
+
+    let mut d = Decoder::new(b"\x43\x01\x02\x03\x5f\x41\x04\x42\x05\x06\xff");
+    assert_eq!(&[1, 2, 3], d.bytes_into(&mut buf).unwrap());
+    assert_eq!(&[4, 5, 6], d.bytes_into(&mut buf).unwrap());
+
+    let mut d = Decoder::new(b"\x45\x01\x02\x03\x04\x05");
+    assert!(matches!(d.bytes_into(&mut buf), Err(Error::BufferTooSmall(5))));
+    assert_eq!(0, d.position());
+    assert!(matches!(d.bytes_into(&mut []), Err(Error::BufferTooSmall(5))));
+    assert!(matches!(Decoder::new(b"\x61a").bytes_into(&mut buf), Err(Error::TypeMismatch(..))));
+    assert!(matches!(Decoder::new(b"\x45\x01").bytes_into(&mut buf), Err(Error::EndOfInput)))
+}
@@ -0,0 +1,82 @@
+use std::cmp::Ordering;
+
+fn hex(s: &str) -> Vec<u8> {
+    hex::decode(s.replace(' ', "")).unwrap()
+}
+
+#[test]
+fn cmp_is_bytewise() {
+    let mut keys = ["6161", "20", "1864", "0a", "f4", "8100", "626161"]
+        .iter()
+        .map(|s| hex(s))
+        .collect::<Vec<_>>();
+    keys.sort_by(|a, b| minicbor::cmp(a, b));
+    let expected = ["0a", "1864", "20", "6161", "626161", "8100", "f4"]
+        .iter()
+        .map(|s| hex(s))
+        .collect::<Vec<_>>();
+    assert_eq!(expected, keys);
+    assert_eq!(Ordering::Equal, minicbor::cmp(&hex("820102"), &hex("820102")))
+}
+
+#[test]
+fn structurally_equal() {
+    let cases = [
+        ("01",                  "1801"),
+        ("01",                  "1b0000000000000001"),
+        ("20",                  "3800"),
+        ("c101",                "d80101"),
+        ("f93e00",              "fa3fc00000"),
+        ("f93e00",              "fb3ff8000000000000"),
+        ("f97e00",              "fb7ff8000000000000"),
+        ("f5",                  "f5"),
+        ("f0",                  "f0"),
+        ("43010203",            "5f4101420203ff"),
+        ("43010203",            "5f41014040420203ff"),
+        ("626162",              "7f61616162ff"),
+        ("820102",              "9f0102ff"),
+        ("9f9fffff",            "8180"),
+        ("a2616101616202",      "bf6161016162 1802ff"),
+        ("",                    ""),
+        ("01 02",               "1801 1802")
+    ];
+    for (a, b) in cases.iter() {
+        let (a, b) = (hex(a), hex(b));
+        assert!(minicbor::eq(&a, &b).unwrap(), "{} == {}", minicbor::display(&a), minicbor::display(&b));
+        assert!(minicbor::eq(&b, &a).unwrap(), "{} == {}", minicbor::display(&b), minicbor::display(&a))
+    }
+}
+
+#[test]
+fn structurally_different() {
+    let cases = [
+        ("01",                  "02"),
+        ("01",                  "21"),
+        ("01",                  "f93c00"),
+        ("f4",                  "f5"),
+        ("f6",                  "f7"),
+        ("f4",                  "f0"),
+        ("c101",                "c201"),
+        ("43010203",            "5f41014102ff"),
+        ("43010203",            "63010203"),
+        ("626162",              "7f6161ff"),
+        ("820102",              "9f01ff"),
+        ("820102",              "9f010203ff"),
+        ("8101",                "a10101"),
+        ("a2616101616202",      "a2616202616101"),
+        ("01",                  "01 02"),
+        ("",                    "00")
+    ];
+    for (a, b) in cases.iter() {
+        let (a, b) = (hex(a), hex(b));
+        assert!(!minicbor::eq(&a, &b).unwrap(), "{} != {}", minicbor::display(&a), minicbor::display(&b));
+        assert!(!minicbor::eq(&b, &a).unwrap(), "{} != {}", minicbor::display(&b), minicbor::display(&a))
+    }
+}
+
+#[test]
+fn malformed_input() {
+    assert!(minicbor::eq(&hex("8201"), &hex("8201")).is_err());
+    assert!(minicbor::eq(&hex("ff"), &hex("ff")).is_err());
+    assert!(minicbor::eq(&hex("9f01"), &hex("9f01")).is_err())
+}
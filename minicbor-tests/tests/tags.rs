@@ -0,0 +1,31 @@
+use minicbor::data::{Tag, TagInfo};
+
+#[test]
+fn lookup() {
+    assert_eq!(Some("standard date-time string"), TagInfo::lookup(0).map(TagInfo::name));
+    assert_eq!(Some("RFC 8746"), TagInfo::lookup(41).map(TagInfo::spec));
+    assert_eq!(Some(55799), TagInfo::lookup(55799).map(TagInfo::number));
+    assert!(TagInfo::lookup(6).is_none());
+    assert!(TagInfo::lookup(76).is_none());
+    assert!(TagInfo::lookup(u64::MAX).is_none());
+
+    assert_eq!(Some("encoded CBOR data item"), Tag::Cbor.name());
+    assert_eq!(Some("RFC 9164"), Tag::Unassigned(54).spec());
+    assert_eq!(TagInfo::lookup(2), Tag::PosBignum.info());
+    assert!(Tag::Unassigned(7).info().is_none())
+}
+
+#[test]
+fn annotated_display() {
+    // 1(1363896240), [24(h'01'), 12345(0)]
+    let cbor = hex::decode("c11a514b67b082d8184101d9303900").unwrap();
+    let plain = minicbor::display(&cbor).to_string();
+    assert_eq!("1(1363896240), [24(h'01'), 12345(0)]", plain);
+    let annotated = format!("{:#}", minicbor::display(&cbor));
+    assert_eq!("1(/ epoch-based date-time / 1363896240), \
+                [24(/ encoded CBOR data item / h'01'), 12345(0)]", annotated);
+
+    // The annotations are comments in extended diagnostic notation.
+    assert_eq!(minicbor::edn::from_edn(&plain).unwrap(), minicbor::edn::from_edn(&annotated).unwrap());
+    assert_eq!(&cbor, &minicbor::edn::from_edn(&annotated).unwrap())
+}
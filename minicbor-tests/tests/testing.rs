@@ -0,0 +1,134 @@
+use minicbor::{Decode, Encode};
+use minicbor::testing::{assert_encodes_to, assert_encodes_to_hex, assert_roundtrip};
+use std::panic::{catch_unwind, UnwindSafe};
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+struct Person {
+    #[n(0)] name: String,
+    #[n(1)] age: Option<u8>
+}
+
+/// A type whose decode impl loses information.
+#[derive(Debug, PartialEq, Encode, Decode)]
+struct Lossy(#[n(0)] u32, #[cbor(n(1), decode_with = "always_false")] bool);
+
+fn always_false(d: &mut minicbor::Decoder<'_>) -> Result<bool, minicbor::decode::Error> {
+    d.skip()?;
+    Ok(false)
+}
+
+/// A type whose decode impl does not consume all encoded bytes.
+#[derive(Debug, PartialEq)]
+struct Partial;
+
+impl Encode for Partial {
+    fn encode<W: minicbor::encode::Write>(&self, e: &mut minicbor::Encoder<W>) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.u8(1)?.u8(2)?.ok()
+    }
+}
+
+impl<'b> Decode<'b> for Partial {
+    fn decode(d: &mut minicbor::Decoder<'b>) -> Result<Self, minicbor::decode::Error> {
+        d.u8()?;
+        Ok(Partial)
+    }
+}
+
+fn panic_message<F: FnOnce() + UnwindSafe>(f: F) -> String {
+    let e = catch_unwind(f).unwrap_err();
+    e.downcast_ref::<String>().cloned().expect("formatted panic message")
+}
+
+#[test]
+fn roundtrip() {
+    let cbor = assert_roundtrip(Person { name: "alice".into(), age: Some(30) });
+    assert_eq!(b"\x82\x65alice\x18\x1e", cbor.as_slice());
+    assert_roundtrip(vec![Some(1i64), None, Some(-1)]);
+    assert_roundtrip((String::from("x"), 1.5f32, [true, false]));
+}
+
+#[test]
+fn roundtrip_mismatch() {
+    let m = panic_message(|| { assert_roundtrip(Lossy(7, true)); });
+    assert_eq!("\
+roundtrip mismatch
+  value:    Lossy(7, true)
+  decoded:  Lossy(7, false)
+  cbor:     [7, true]
+            8207f5", m)
+}
+
+#[test]
+fn roundtrip_trailing_bytes() {
+    let m = panic_message(|| { assert_roundtrip(Partial); });
+    assert_eq!("\
+decoding left 1 trailing byte(s)
+  value:    Partial
+  cbor:     1, 2
+            0102", m)
+}
+
+#[test]
+fn roundtrip_decode_error() {
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct Changed {
+        #[cbor(n(0), encode_with = "encode_str")] x: u8
+    }
+
+    fn encode_str<W: minicbor::encode::Write>(_: &u8, e: &mut minicbor::Encoder<W>) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.str("x")?.ok()
+    }
+
+    let m = panic_message(|| { assert_roundtrip(Changed { x: 1 }); });
+    assert_eq!("\
+failed to decode
+  value:    Changed { x: 1 }
+  cbor:     [\"x\"]
+            816178
+  error:    unexpected type: string, expected u8", m)
+}
+
+#[test]
+fn encodes_to() {
+    assert_encodes_to(Person { name: "bob".into(), age: None }, b"\x81\x63bob");
+    assert_encodes_to(&[1u16, 1000][..], &[0x82, 0x01, 0x19, 0x03, 0xe8])
+}
+
+#[test]
+fn encodes_to_mismatch() {
+    let m = panic_message(|| assert_encodes_to([1u8, 2], &[0x82, 0x01, 0x03]));
+    assert_eq!("\
+encoding mismatch
+  expected: [1, 3]
+            820103
+  actual:   [1, 2]
+            820102
+  first difference at byte 2", m);
+
+    let m = panic_message(|| assert_encodes_to("ab", b"\x63abc"));
+    assert!(m.ends_with("first difference at byte 0"), "{}", m);
+
+    let m = panic_message(|| assert_encodes_to((1u8, 2u8), &[0x82, 0x01]));
+    assert!(m.contains("expected: [1,  !!! decoding error: end of input bytes"), "{}", m);
+    assert!(m.ends_with("first difference at byte 2"), "{}", m)
+}
+
+#[test]
+fn hex() {
+    // Examples from RFC 8949, Appendix A.
+    assert_encodes_to_hex(1000000u32, "0x1a000f4240");
+    assert_encodes_to_hex(-1000i16, "0x3903e7");
+    assert_encodes_to_hex(["a", "b"], "82 61 61\n 61 62");
+    assert_encodes_to_hex(Person { name: "bob".into(), age: None }, "8163626F62");
+
+    let m = panic_message(|| assert_encodes_to_hex(1u8, "0x02"));
+    assert!(m.starts_with("encoding mismatch"), "{}", m);
+    let m = panic_message(|| assert_encodes_to_hex(1u8, "0g"));
+    assert_eq!("invalid hex \"0g\": invalid hex digit", m);
+
+    let bytes = minicbor::from_hex(" 0x00 ff 7F ").unwrap();
+    assert_eq!(vec![0x00, 0xff, 0x7f], bytes);
+    assert_eq!("00ff7f", minicbor::display_hex(&bytes).to_string());
+    assert_eq!("", minicbor::display_hex(&[]).to_string());
+    assert!(minicbor::from_hex("abc").is_err())
+}
@@ -0,0 +1,72 @@
+use minicbor::{Decode, Encode};
+use minicbor::time::{Epoch, Seconds};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[test]
+fn seconds() {
+    let cases: &[(Duration, &[u8])] = &[
+        (Duration::ZERO, &[0x00]),
+        (Duration::from_secs(3600), &[0x19, 0x0e, 0x10]),
+        (Duration::from_secs(u64::MAX), &[0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+        (Duration::from_millis(250), &[0xfb, 0x3f, 0xd0, 0, 0, 0, 0, 0, 0])
+    ];
+    for (d, cbor) in cases {
+        assert_eq!(*cbor, minicbor::to_vec(Seconds(*d)).unwrap().as_slice(), "{:?}", d);
+        assert_eq!(Seconds(*d), minicbor::decode(cbor).unwrap(), "{:?}", d)
+    }
+
+    // Half and single precision floats are accepted.
+    assert_eq!(Seconds(Duration::from_millis(1500)), minicbor::decode(&[0xf9, 0x3e, 0x00]).unwrap());
+    assert_eq!(Seconds(Duration::from_millis(500)), minicbor::decode(&[0xfa, 0x3f, 0, 0, 0]).unwrap());
+
+    // Negative, non-finite and too large values are rejected.
+    assert!(minicbor::decode::<Seconds>(&[0x20]).is_err());
+    assert!(minicbor::decode::<Seconds>(&[0xf9, 0xbc, 0x00]).is_err());
+    assert!(minicbor::decode::<Seconds>(&[0xf9, 0x7e, 0x00]).is_err());
+    assert!(minicbor::decode::<Seconds>(&[0xf9, 0x7c, 0x00]).is_err());
+    assert!(minicbor::decode::<Seconds>(&[0xc2, 0x49, 0x01, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+    assert!(minicbor::decode::<Seconds>(&[0x60]).is_err())
+}
+
+#[test]
+fn epoch() {
+    let cases: &[(SystemTime, &[u8])] = &[
+        (UNIX_EPOCH, &[0xc1, 0x00]),
+        (UNIX_EPOCH + Duration::from_secs(1363896240), &[0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0]),
+        (UNIX_EPOCH + Duration::from_millis(1363896240500), &[0xc1, 0xfb, 0x41, 0xd4, 0x52, 0xd9, 0xec, 0x20, 0, 0]),
+        (UNIX_EPOCH - Duration::from_secs(86400), &[0xc1, 0x3a, 0x00, 0x01, 0x51, 0x7f]),
+        (UNIX_EPOCH - Duration::from_millis(1500), &[0xc1, 0xfb, 0xbf, 0xf8, 0, 0, 0, 0, 0, 0])
+    ];
+    for (t, cbor) in cases {
+        assert_eq!(*cbor, minicbor::to_vec(Epoch(*t)).unwrap().as_slice(), "{:?}", t);
+        assert_eq!(Epoch(*t), minicbor::decode(cbor).unwrap(), "{:?}", t)
+    }
+
+    let now = SystemTime::now();
+    let Epoch(t) = minicbor::decode(&minicbor::to_vec(Epoch(now)).unwrap()).unwrap();
+    let diff = t.duration_since(now).or_else(|e| Ok::<_, ()>(e.duration())).unwrap();
+    assert!(diff < Duration::from_micros(1));
+
+    // Tag 1 is required.
+    assert!(minicbor::decode::<Epoch>(&[0x1a, 0x51, 0x4b, 0x67, 0xb0]).is_err());
+    assert!(minicbor::decode::<Epoch>(b"\xc0\x742013-03-21T20:04:00Z").is_err())
+}
+
+#[test]
+fn with_modules() {
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct Event {
+        #[cbor(n(0), with = "minicbor::time::epoch")] at: SystemTime,
+        #[cbor(n(1), with = "minicbor::time::seconds")] took: Duration,
+        #[n(2)] timeout: Duration
+    }
+
+    let e = Event {
+        at: UNIX_EPOCH + Duration::from_secs(10),
+        took: Duration::from_secs(2),
+        timeout: Duration::from_secs(5)
+    };
+    let cbor = minicbor::to_vec(&e).unwrap();
+    assert_eq!(b"\x83\xc1\x0a\x02\x82\x05\x00", cbor.as_slice());
+    assert_eq!(e, minicbor::decode(&cbor).unwrap())
+}
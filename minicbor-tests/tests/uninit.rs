@@ -0,0 +1,31 @@
+use core::mem::MaybeUninit;
+use minicbor::{Encoder, encode::{Error, write::UninitWriter}};
+
+#[test]
+fn to_slice_uninit_identity() {
+    let value = (1u8, "hello", vec![1u32, 2, 3]);
+    let mut buf = [MaybeUninit::uninit(); 64];
+    let cbor = minicbor::to_slice_uninit(&value, &mut buf).unwrap();
+    assert_eq!(minicbor::to_vec(&value).unwrap(), cbor);
+    assert_eq!(value, minicbor::decode(cbor).unwrap())
+}
+
+#[test]
+fn to_slice_uninit_too_small() {
+    let mut buf = [MaybeUninit::uninit(); 4];
+    assert!(matches!(minicbor::to_slice_uninit("hello", &mut buf), Err(Error::Write(_))));
+    let mut buf = [MaybeUninit::uninit(); 6];
+    assert_eq!(b"\x65hello", minicbor::to_slice_uninit("hello", &mut buf).unwrap())
+}
+
+#[test]
+fn uninit_writer_prefix() {
+    let mut buf = [MaybeUninit::uninit(); 8];
+    let mut w = UninitWriter::new(&mut buf);
+    assert!(w.is_empty());
+    Encoder::new(&mut w).u8(1).unwrap().str("ab").unwrap();
+    assert_eq!(4, w.len());
+    assert_eq!(b"\x01\x62ab", w.initialized());
+    assert!(Encoder::new(&mut w).u32(0x1000_0000).is_err());
+    assert_eq!(b"\x01\x62ab", &w.into_initialized()[.. 4])
+}
@@ -0,0 +1,218 @@
+use minicbor::{Decoder, Encoder};
+use minicbor::value::{Interner, Value, ValueRef};
+
+#[test]
+fn value_size() {
+    assert_eq!(24, std::mem::size_of::<Value>())
+}
+
+#[test]
+fn inline_and_allocated_strings() {
+    let short = "a".repeat(22);
+    let long  = "a".repeat(23);
+    for s in [&short, &long] {
+        let v = Value::from(s.as_str());
+        assert_eq!(ValueRef::Text(s), v.view());
+        let w: Value = minicbor::decode(&minicbor::to_vec(&v).unwrap()).unwrap();
+        assert_eq!(v, w);
+        let b = Value::bytes(s.as_bytes());
+        assert_eq!(ValueRef::Bytes(s.as_bytes()), b.view())
+    }
+}
+
+#[test]
+fn integers() {
+    for n in [0, 1, 23, 24, 255, 256, 65536, i64::MAX, -1, -24, -25, -256, -257, i64::MIN] {
+        let v = Value::from(n);
+        let cbor = minicbor::to_vec(&v).unwrap();
+        assert_eq!(minicbor::to_vec(n).unwrap(), cbor);
+        assert_eq!(v, minicbor::decode::<Value>(&cbor).unwrap())
+    }
+    let min = Value::negative(u64::MAX);
+    let cbor = minicbor::to_vec(&min).unwrap();
+    assert_eq!([0x3b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff], cbor.as_slice());
+    assert_eq!(min, minicbor::decode::<Value>(&cbor).unwrap())
+}
+
+#[test]
+fn shortest_floats() {
+    let cases: Vec<(f64, &[u8])> = vec![
+        (0.0, &[0xf9, 0, 0]),
+        (1.5, &[0xf9, 0x3e, 0]),
+        (f64::INFINITY, &[0xf9, 0x7c, 0]),
+        (f64::NAN, &[0xf9, 0x7e, 0]),
+        (100000.0, &[0xfa, 0x47, 0xc3, 0x50, 0]),
+        (1.1, &[0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a])
+    ];
+    for (x, b) in cases {
+        assert_eq!(b, minicbor::to_vec(Value::from(x)).unwrap().as_slice());
+        match minicbor::decode::<Value>(b).unwrap().view() {
+            ValueRef::Float(y) if x.is_nan() => assert!(y.is_nan()),
+            ValueRef::Float(y) => assert_eq!(x, y),
+            other => panic!("unexpected value: {:?}", other)
+        }
+    }
+}
+
+#[test]
+fn nested_round_trip() {
+    let v = Value::map(vec![
+        (Value::from("b"), Value::array(vec![Value::null(), Value::undefined(), Value::from(true)])),
+        (Value::from("a"), Value::tag(32, Value::from("https://example.com"))),
+        (Value::from("a"), Value::simple(99)),
+        (Value::from(1), Value::bytes(&[1, 2, 3]))
+    ]);
+    let cbor = minicbor::to_vec(&v).unwrap();
+    let w: Value = minicbor::decode(&cbor).unwrap();
+    assert_eq!(v, w);
+    assert_eq!(cbor, minicbor::to_vec(&w).unwrap())
+}
+
+#[test]
+fn indefinite_lengths() {
+    let mut e = Encoder::new(Vec::new());
+    e.begin_map().unwrap()
+        .str("x").unwrap().begin_array().unwrap().u8(1).unwrap().u8(2).unwrap().end().unwrap()
+        .str("y").unwrap().begin_str().unwrap().str("ab").unwrap().str("cd").unwrap().end().unwrap()
+        .end().unwrap();
+    let v: Value = minicbor::decode(&e.into_inner()).unwrap();
+    let expected = Value::map(vec![
+        (Value::from("x"), Value::array(vec![Value::from(1), Value::from(2)])),
+        (Value::from("y"), Value::from("abcd"))
+    ]);
+    assert_eq!(expected, v)
+}
+
+#[test]
+fn depth_limit() {
+    let mut cbor = vec![0x81; 20];
+    cbor.push(0);
+    let mut d = Decoder::new(&cbor);
+    d.set_max_depth(10);
+    assert!(d.decode::<Value>().is_err());
+    assert!(minicbor::decode::<Value>(&cbor).is_ok())
+}
+
+#[test]
+fn interned_keys() {
+    let key = "a key which does not fit inline";
+    let cbor = minicbor::to_vec(vec![(key, 1)].into_iter().collect::<std::collections::BTreeMap<_, _>>()).unwrap();
+    let mut interner = Interner::new();
+    let a = interner.decode(&mut Decoder::new(&cbor)).unwrap();
+    let b = interner.decode(&mut Decoder::new(&cbor)).unwrap();
+    assert_eq!(a, b);
+    assert_eq!(1, interner.len());
+    if let (ValueRef::Map(x), ValueRef::Map(y)) = (a.view(), b.view()) {
+        match (x[0].0.view(), y[0].0.view()) {
+            (ValueRef::Text(s), ValueRef::Text(t)) => assert!(std::ptr::eq(s, t)),
+            other => panic!("unexpected keys: {:?}", other)
+        }
+    } else {
+        panic!("expected maps")
+    }
+}
+
+#[test]
+fn indexing() {
+    // {"items": [{"name": "a", 1: -5}], "ok": true, h'00': 1.5}
+    let v = Value::map([])
+        .with_entry("items", Value::array([]).with_item(Value::map([]).with_entry("name", "a").with_entry(1u8, -5)))
+        .with_entry("ok", true)
+        .with_entry(&[0u8][..], 1.5);
+
+    assert_eq!(Some("a"), v["items"][0]["name"].as_str());
+    assert_eq!(Some(-5), v["items"][0][1].as_i64());
+    assert_eq!(None, v["items"][0][1].as_u64());
+    assert_eq!(Some(true), v["ok"].as_bool());
+    assert_eq!(Some(1.5), v[Value::bytes(&[0])].as_f64());
+    assert!(v["missing"][3]["x"].is_null());
+    assert!(v[0].is_null());
+
+    assert_eq!(None, v.get("items").and_then(|i| i.get(1)));
+    assert_eq!(Some(1), v.get("items").and_then(|i| i.as_array()).map(|a| a.len()));
+    assert_eq!(Some(3), v.as_map().map(|m| m.len()));
+    assert_eq!(None, Value::from("x").get(0));
+    assert_eq!(None, Value::from(1).as_str());
+
+    let key = String::from("ok");
+    assert_eq!(Some(&Value::from(true)), v.get(key.as_str()))
+}
+
+#[test]
+fn typed_getters() {
+    assert_eq!(Some(u64::MAX), Value::from(u64::MAX).as_u64());
+    assert_eq!(None, Value::from(u64::MAX).as_i64());
+    assert_eq!(Some(i64::MIN), Value::from(i64::MIN).as_i64());
+    assert_eq!(None, Value::negative(u64::MAX).as_i64());
+    assert_eq!(Some(&b"bytes"[..]), Value::bytes(b"bytes").as_bytes());
+    let long = "a text string which is not stored inline";
+    assert_eq!(Some(long), Value::from(long).as_str());
+    assert_eq!(None, Value::from(long).as_bytes());
+    assert!(Value::null().is_null());
+    assert!(!Value::undefined().is_null())
+}
+
+#[test]
+fn mutation() {
+    let mut v = Value::map([]).with_entry("list", Value::array([]));
+    v.get_mut("list").and_then(Value::as_array_mut).unwrap().push(Value::from(1));
+    *v.get_mut("list").and_then(|l| l.get_mut(0)).unwrap() = Value::from("one");
+    v.as_map_mut().unwrap().push((Value::from(2), Value::null()));
+    *v.get_mut(2).unwrap() = Value::from(false);
+    assert!(v.get_mut("x").is_none());
+
+    let w = Value::map([]).with_entry("list", Value::array([Value::from("one")])).with_entry(2u8, false);
+    assert_eq!(w, v);
+    assert_eq!(minicbor::to_vec(&w).unwrap(), minicbor::to_vec(&v).unwrap())
+}
+
+#[test]
+#[should_panic(expected = "requires a map")]
+fn with_entry_on_array() {
+    let _ = Value::array([]).with_entry(1u8, 2u8);
+}
+
+#[test]
+fn canonical_input_round_trips_byte_for_byte() {
+    let inputs = [
+        // integers, including the extremes
+        "00", "17", "1818", "1bffffffffffffffff", "20", "3bffffffffffffffff",
+        // floats in their shortest form, incl. signed zero, infinities and NaN
+        "f93e00", "f98000", "f97c00", "f9fc00", "f97e00", "fa47c35000", "fb3ff199999999999a",
+        // simple values
+        "f4", "f5", "f6", "f7", "f0", "f8ff",
+        // strings
+        "40", "4401020304", "60", "6449455446", "781d6120746578742077686963682069732073746f726564206f7574736964",
+        // maps preserve entry order and duplicate keys
+        "a2616201616100", "a3010102020103", "a2616101616102",
+        // tags wrap arbitrary items, including tags and large tag numbers
+        "c11a514b67b0", "d82582f6a0", "c1c1c100", "db000000010000000080", "d9d9f7a1f5f4",
+        // nesting
+        "83a08180a18180a0"
+    ];
+    for i in inputs.iter() {
+        let cbor = hex::decode(i).unwrap();
+        let v: Value = minicbor::decode(&cbor).unwrap();
+        assert_eq!(cbor, minicbor::to_vec(&v).unwrap(), "{}", i)
+    }
+}
+
+#[test]
+fn other_input_round_trips_losslessly() {
+    let inputs = [
+        // non-shortest integers, lengths and tags
+        "1801", "1a00000001", "3900ff", "5800", "780161", "9800", "b90000", "d80100",
+        // non-shortest floats
+        "fb3ff8000000000000", "fa3fc00000", "fa7fc00000", "fb7ff8000000000001",
+        // indefinite lengths
+        "5f4101420203ff", "7f616161626163ff", "9f01829f02ff03ff", "bf010203bf0304ffff", "5fff"
+    ];
+    for i in inputs.iter() {
+        let cbor = hex::decode(i).unwrap();
+        let v: Value = minicbor::decode(&cbor).unwrap();
+        let out = minicbor::to_vec(&v).unwrap();
+        assert!(minicbor::eq(&cbor, &out).unwrap(), "{} != {}", i, hex::encode(&out));
+        let w: Value = minicbor::decode(&out).unwrap();
+        assert_eq!(out, minicbor::to_vec(&w).unwrap(), "{}", i)
+    }
+}
@@ -0,0 +1,166 @@
+use minicbor::data::Tag;
+use minicbor::decode::Error;
+use minicbor::visit::{Control, Visitor};
+
+/// Records every callback as a string, prefixed with the depth.
+#[derive(Default)]
+struct Recorder {
+    events: Vec<String>,
+    skip: Option<&'static str>,
+    stop: Option<&'static str>
+}
+
+impl Recorder {
+    fn record(&mut self, depth: usize, event: String) -> Control {
+        let ctrl =
+            if Some(event.as_str()) == self.skip {
+                Control::Skip
+            } else if Some(event.as_str()) == self.stop {
+                Control::Stop
+            } else {
+                Control::Continue
+            };
+        self.events.push(format!("{}:{}", depth, event));
+        ctrl
+    }
+}
+
+impl<'b> Visitor<'b> for Recorder {
+    fn on_bool(&mut self, depth: usize, val: bool) -> Control {
+        self.record(depth, val.to_string())
+    }
+
+    fn on_null(&mut self, depth: usize) -> Control {
+        self.record(depth, "null".into())
+    }
+
+    fn on_undefined(&mut self, depth: usize) -> Control {
+        self.record(depth, "undefined".into())
+    }
+
+    fn on_simple(&mut self, depth: usize, val: u8) -> Control {
+        self.record(depth, format!("simple({})", val))
+    }
+
+    fn on_int(&mut self, depth: usize, val: i128) -> Control {
+        self.record(depth, val.to_string())
+    }
+
+    fn on_float(&mut self, depth: usize, val: f64) -> Control {
+        self.record(depth, format!("{:?}", val))
+    }
+
+    fn on_bytes(&mut self, depth: usize, val: &'b [u8]) -> Control {
+        self.record(depth, format!("h'{}'", minicbor::display_hex(val)))
+    }
+
+    fn on_str(&mut self, depth: usize, val: &'b str) -> Control {
+        self.record(depth, format!("{:?}", val))
+    }
+
+    fn begin_bytes(&mut self, depth: usize) -> Control {
+        self.record(depth, "(_ bytes".into())
+    }
+
+    fn end_bytes(&mut self, depth: usize) -> Control {
+        self.record(depth, "bytes)".into())
+    }
+
+    fn begin_str(&mut self, depth: usize) -> Control {
+        self.record(depth, "(_ str".into())
+    }
+
+    fn end_str(&mut self, depth: usize) -> Control {
+        self.record(depth, "str)".into())
+    }
+
+    fn begin_array(&mut self, depth: usize, len: Option<u64>) -> Control {
+        self.record(depth, format!("[{:?}", len))
+    }
+
+    fn end_array(&mut self, depth: usize) -> Control {
+        self.record(depth, "]".into())
+    }
+
+    fn begin_map(&mut self, depth: usize, len: Option<u64>) -> Control {
+        self.record(depth, format!("{{{:?}", len))
+    }
+
+    fn end_map(&mut self, depth: usize) -> Control {
+        self.record(depth, "}".into())
+    }
+
+    fn on_tag(&mut self, depth: usize, tag: Tag) -> Control {
+        self.record(depth, format!("tag({})", u64::from(tag)))
+    }
+}
+
+fn walk(hex: &str, skip: Option<&'static str>, stop: Option<&'static str>) -> Result<Vec<String>, Error> {
+    let cbor = minicbor::from_hex(hex).unwrap();
+    let mut r = Recorder { skip, stop, ..Recorder::default() };
+    minicbor::walk(&cbor, &mut r)?;
+    Ok(r.events)
+}
+
+#[test]
+fn all_items() {
+    // [1, -1, "a", h'ff', {true: null}, 1(1.5)], (_ "b", "c"), simple(16), undefined, -18446744073709551616
+    let events = walk("86 01 20 61 61 41 ff a1 f5 f6 c1 f9 3e 00 7f 61 62 61 63 ff f0 f7 3b ffffffffffffffff", None, None).unwrap();
+    let expected = [
+        "0:[Some(6)", "1:1", "1:-1", "1:\"a\"", "1:h'ff'", "1:{Some(1)", "2:true", "2:null", "1:}",
+        "1:tag(1)", "2:1.5", "0:]",
+        "0:(_ str", "1:\"b\"", "1:\"c\"", "0:str)",
+        "0:simple(16)", "0:undefined", "0:-18446744073709551616"
+    ];
+    assert_eq!(&expected[..], &events[..])
+}
+
+#[test]
+fn indefinite_containers() {
+    // [_ {_ 1: (_ h'01', h'02')}, 0.5]
+    let events = walk("9f bf 01 5f 41 01 41 02 ff ff fb 3fe0000000000000 ff", None, None).unwrap();
+    let expected = [
+        "0:[None", "1:{None", "2:1", "2:(_ bytes", "3:h'01'", "3:h'02'", "2:bytes)", "1:}", "1:0.5", "0:]"
+    ];
+    assert_eq!(&expected[..], &events[..])
+}
+
+#[test]
+fn skip_subtrees() {
+    // [[1, 2], 1([3]), (_ "x"), 4]
+    let hex = "84 82 01 02 c1 81 03 7f 61 78 ff 04";
+    let events = walk(hex, Some("[Some(2)"), None).unwrap();
+    assert_eq!(vec!["0:[Some(4)", "1:[Some(2)", "1:tag(1)", "2:[Some(1)", "3:3", "2:]", "1:(_ str", "2:\"x\"", "1:str)", "1:4", "0:]"], events);
+
+    let events = walk(hex, Some("tag(1)"), None).unwrap();
+    assert_eq!(vec!["0:[Some(4)", "1:[Some(2)", "2:1", "2:2", "1:]", "1:tag(1)", "1:(_ str", "2:\"x\"", "1:str)", "1:4", "0:]"], events);
+
+    let events = walk(hex, Some("(_ str"), None).unwrap();
+    assert_eq!(vec!["0:[Some(4)", "1:[Some(2)", "2:1", "2:2", "1:]", "1:tag(1)", "2:[Some(1)", "3:3", "2:]", "1:(_ str", "1:4", "0:]"], events);
+
+    let events = walk("a1 01 02 03", Some("{Some(1)"), None).unwrap();
+    assert_eq!(vec!["0:{Some(1)", "0:3"], events);
+
+    // Skipped items must still be well-formed.
+    assert!(walk("82 81 ff 01", Some("[Some(2)"), None).is_err())
+}
+
+#[test]
+fn stop_early() {
+    let events = walk("83 01 82 02 03 04 05", None, Some("2")).unwrap();
+    assert_eq!(vec!["0:[Some(3)", "1:1", "1:[Some(2)", "2:2"], events);
+
+    // Input after the point of stopping is not checked.
+    let events = walk("01 ff", None, Some("1")).unwrap();
+    assert_eq!(vec!["0:1"], events);
+    let events = walk("7f 61 78 00", None, Some("(_ str")).unwrap();
+    assert_eq!(vec!["0:(_ str"], events)
+}
+
+#[test]
+fn malformed_input() {
+    assert!(matches!(walk("ff", None, None), Err(Error::TypeMismatch(..))));
+    assert!(matches!(walk("82 01", None, None), Err(Error::EndOfInput)));
+    let nested = "81".repeat(minicbor::decode::DEFAULT_MAX_DEPTH + 1) + "00";
+    assert!(matches!(walk(&nested, None, None), Err(Error::Message(_))))
+}
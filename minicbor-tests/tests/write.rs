@@ -0,0 +1,213 @@
+//! Test `Write` adapters.
+
+use minicbor::Encoder;
+use minicbor::encode::write::{Buffered, DigestWriter, LenWriter, Tee};
+use sha2::{Digest, Sha256};
+
+#[test]
+fn tee_mirrors_and_hashes() {
+    let value = (1u8, "hello", [1.5f64, 2.5]);
+
+    let w = Tee::new(Vec::new(), Tee::new(LenWriter::new(), DigestWriter::new(Sha256::new())));
+    let mut e = Encoder::new(w);
+    e.encode(value).unwrap();
+    let (bytes, rest) = e.into_inner().into_inner();
+    let (len, digest) = rest.into_inner();
+
+    assert_eq!(minicbor::to_vec(value).unwrap(), bytes);
+    assert_eq!(bytes.len(), len.len());
+    assert_eq!(Sha256::digest(&bytes), digest.into_inner().finalize())
+}
+
+/// A writer which records the length of every write.
+#[derive(Default)]
+struct Writes(Vec<u8>, Vec<usize>);
+
+impl std::io::Write for Writes {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.extend_from_slice(buf);
+        self.1.push(buf.len());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn buffered_batches_small_writes() {
+    let value = (0 .. 100u32).map(|i| (i, "item")).collect::<Vec<_>>();
+    let large = vec![7u8; 40];
+
+    let mut e = Encoder::new(Buffered::<_, 32>::new(Writes::default()));
+    e.encode(&value).unwrap();
+    e.bytes(&large).unwrap();
+    e.u8(1).unwrap();
+    let mut w = e.into_inner();
+    assert_eq!(1, w.buffer().len());
+    std::io::Write::flush(&mut w).unwrap();
+    assert!(w.buffer().is_empty());
+    let Writes(bytes, writes) = w.into_inner().unwrap();
+
+    let mut expected = minicbor::to_vec(&value).unwrap();
+    Encoder::new(&mut expected).bytes(&large).unwrap();
+    expected.push(1);
+    assert_eq!(expected, bytes);
+    assert!(writes.iter().all(|n| *n <= 32 || *n == large.len()));
+    assert!(writes.len() < bytes.len() / 16)
+}
+
+#[test]
+fn heads_are_written_at_once() {
+    let mut e = Encoder::new(Writes::default());
+    e.u64(u64::MAX).unwrap().i32(-70000).unwrap().f64(1.5).unwrap().array(1000).unwrap();
+    let Writes(bytes, writes) = e.into_inner();
+    assert_eq!(vec![9, 5, 9, 3], writes);
+    assert_eq!(26, bytes.len())
+}
+
+/// A writer which accepts at most 5 bytes per call, from multiple slices.
+#[derive(Default)]
+struct Trickle(Vec<u8>, usize);
+
+impl std::io::Write for Trickle {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.write_vectored(&[std::io::IoSlice::new(buf)])
+    }
+
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> std::io::Result<usize> {
+        self.1 += 1;
+        let mut n = 0;
+        for b in bufs {
+            let k = b.len().min(5 - n);
+            self.0.extend_from_slice(&b[.. k]);
+            n += k
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn vectored_writes() {
+    let value = ("a", "", "hello world", minicbor::bytes::ByteVec::from(vec![7; 30]), ["x"; 3]);
+    let mut e = Encoder::new(Trickle::default());
+    e.encode(&value).unwrap();
+    let Trickle(bytes, _) = e.into_inner();
+    assert_eq!(minicbor::to_vec(&value).unwrap(), bytes);
+
+    // Head and content are written together.
+    let mut e = Encoder::new(Trickle::default());
+    e.str("hello world").unwrap();
+    let Trickle(bytes, calls) = e.into_inner();
+    assert_eq!(b"\x6bhello world", bytes.as_slice());
+    assert_eq!(3, calls)
+}
+
+/// A writer which records the number of slices of every call.
+#[derive(Default)]
+struct Vectors(Vec<u8>, Vec<usize>);
+
+impl std::io::Write for Vectors {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.write_vectored(&[std::io::IoSlice::new(buf)])
+    }
+
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> std::io::Result<usize> {
+        self.1.push(bufs.iter().filter(|b| !b.is_empty()).count());
+        bufs.iter().for_each(|b| self.0.extend_from_slice(b));
+        Ok(bufs.iter().map(|b| b.len()).sum())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn buffered_vectored_writes() {
+    use minicbor::encode::write::Buffered;
+
+    // Small strings are buffered, larger ones are written together with
+    // the buffered bytes in a single call.
+    let mut e = Encoder::new(Buffered::<_, 16>::new(Vectors::default()));
+    e.array(3).unwrap().str("abc").unwrap().bytes(&[1; 40]).unwrap().u8(1).unwrap();
+    let Vectors(bytes, calls) = e.into_inner().into_inner().unwrap();
+    let mut expected = b"\x83\x63abc\x58\x28".to_vec();
+    expected.extend_from_slice(&[1; 40]);
+    expected.push(1);
+    assert_eq!(expected, bytes);
+    assert_eq!(vec![3, 1], calls);
+
+    // Partial writes of the inner writer are continued.
+    let value = ("hello", minicbor::bytes::ByteVec::from(vec![7; 30]), ["world"; 3]);
+    let mut e = Encoder::new(Buffered::<_, 8>::new(Trickle::default()));
+    e.encode(&value).unwrap();
+    let Trickle(bytes, _) = e.into_inner().into_inner().unwrap();
+    assert_eq!(minicbor::to_vec(&value).unwrap(), bytes)
+}
+
+/// A writer which counts flushes and appends their number when finished.
+#[derive(Default)]
+struct Trailer {
+    bytes: std::rc::Rc<std::cell::RefCell<Vec<u8>>>,
+    flushes: u8
+}
+
+impl minicbor::encode::Write for Trailer {
+    type Error = std::convert::Infallible;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.bytes.borrow_mut().extend_from_slice(buf);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.flushes += 1;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), Self::Error> {
+        self.bytes.borrow_mut().push(self.flushes);
+        Ok(())
+    }
+}
+
+#[test]
+fn flush_and_finish() {
+    let w = Trailer::default();
+    let bytes = w.bytes.clone();
+    let mut e = Encoder::new(w);
+    e.u8(1).unwrap().flush().unwrap().u8(2).unwrap().flush().unwrap();
+    e.finish().unwrap();
+    assert_eq!(vec![1, 2, 2], *bytes.borrow());
+
+    let mut e = Encoder::new(Buffered::<_, 32>::new(Writes::default()));
+    e.str("hello").unwrap().flush().unwrap();
+    let w = e.into_inner();
+    assert!(w.buffer().is_empty());
+    assert_eq!(b"\x65hello", w.get_ref().0.as_slice())
+}
+
+#[test]
+fn vectored_writes_of_empty_slices() {
+    use minicbor::encode::Write;
+
+    // Nothing is written if all slices are empty, not even to a full sink.
+    let mut v = Vectors::default();
+    Write::write_all_vectored(&mut v, &[b"", b"", b"", b"", b""]).unwrap();
+    assert!(v.1.is_empty());
+    let mut full: &mut [u8] = &mut [];
+    assert!(Write::write_all_vectored(&mut full, &[b"", b""]).is_ok());
+    assert!(Write::write_all_vectored(&mut full, &[b"", b"a"]).is_err());
+
+    // Leading empty slices are skipped.
+    let mut v = Vectors::default();
+    Write::write_all_vectored(&mut v, &[b"", b"", b"", b"", b"ab", b"", b"c"]).unwrap();
+    assert_eq!(b"abc", v.0.as_slice());
+    assert_eq!(vec![2], v.1)
+}
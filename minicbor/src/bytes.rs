@@ -9,9 +9,9 @@
 //! If the feature "derive" is present, specialised traits `EncodeBytes` and
 //! `DecodeBytes` are also provided. These are implemented for the
 //! aforementioned newtypes as well as for their `Option` variations and
-//! regular `&[u8]`, `[u8; N]` and `Vec<u8>`. They enable the direct use of
-//! `&[u8]`, `[u8; N]` and `Vec<u8>` in types deriving `Encode` and `Decode`
-//! if used with a `#[cbor(with = "minicbor::bytes")]` annotation.
+//! regular `&[u8]`, `[u8; N]`, `Vec<u8>` and `Cow<[u8]>`. They enable the
+//! direct use of these types in types deriving `Encode` and `Decode` if used
+//! with a `#[cbor(with = "minicbor::bytes")]` annotation.
 
 use crate::decode::{self, Decode, Decoder};
 use crate::encode::{self, Encode, Encoder, Write};
@@ -261,7 +261,7 @@ pub trait DecodeBytes<'b>: Sized {
 }
 
 #[cfg(feature = "derive")]
-impl<'a, T: EncodeBytes + ?Sized> EncodeBytes for &'a T {
+impl<T: EncodeBytes + ?Sized> EncodeBytes for &T {
     fn encode_bytes<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
         (**self).encode_bytes(e)
     }
@@ -281,14 +281,14 @@ impl<'a, 'b: 'a> DecodeBytes<'b> for &'a [u8] {
     }
 }
 
-#[cfg(all(feature = "derive"))]
+#[cfg(feature = "derive")]
 impl<const N: usize> EncodeBytes for [u8; N] {
     fn encode_bytes<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
         e.bytes(&self[..])?.ok()
     }
 }
 
-#[cfg(all(feature = "derive"))]
+#[cfg(feature = "derive")]
 impl<'b, const N: usize> DecodeBytes<'b> for [u8; N] {
     fn decode_bytes(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
         ByteArray::decode(d).map(ByteArray::into)
@@ -309,6 +309,29 @@ impl<'b> DecodeBytes<'b> for Vec<u8> {
     }
 }
 
+#[cfg(all(feature = "alloc", feature = "derive"))]
+impl EncodeBytes for alloc::borrow::Cow<'_, [u8]> {
+    fn encode_bytes<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        e.bytes(self)?.ok()
+    }
+}
+
+/// Borrows from the decoding input, except for byte strings of indefinite
+/// length whose chunks are copied into an owned vector.
+#[cfg(all(feature = "alloc", feature = "derive"))]
+impl<'a, 'b: 'a> DecodeBytes<'b> for alloc::borrow::Cow<'a, [u8]> {
+    fn decode_bytes(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        if d.datatype()? == crate::data::Type::BytesIndef {
+            let mut v = Vec::new();
+            for b in d.bytes_iter()? {
+                v.extend_from_slice(b?)
+            }
+            return Ok(alloc::borrow::Cow::Owned(v))
+        }
+        d.bytes().map(alloc::borrow::Cow::Borrowed)
+    }
+}
+
 #[cfg(feature = "derive")]
 impl EncodeBytes for ByteSlice {
     fn encode_bytes<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
@@ -323,14 +346,14 @@ impl<'a, 'b: 'a> DecodeBytes<'b> for &'a ByteSlice {
     }
 }
 
-#[cfg(all(feature = "derive"))]
+#[cfg(feature = "derive")]
 impl<const N: usize> EncodeBytes for ByteArray<N> {
     fn encode_bytes<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
         Self::encode(self, e)
     }
 }
 
-#[cfg(all(feature = "derive"))]
+#[cfg(feature = "derive")]
 impl<'b, const N: usize> DecodeBytes<'b> for ByteArray<N> {
     fn decode_bytes(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
         Self::decode(d)
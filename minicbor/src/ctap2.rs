@@ -0,0 +1,75 @@
+//! Support for the [canonical CBOR form of CTAP2][1], as used by FIDO
+//! authenticators and WebAuthn relying parties.
+//!
+//! CTAP2 restricts CBOR as follows:
+//!
+//! - Integers and lengths use the shortest possible encoding.
+//! - Indefinite-length items are not allowed.
+//! - Map keys are unique and sorted by their major type first, then by the
+//!   length of their encoding, then bytewise. This differs from the
+//!   deterministic encoding of [RFC 8949][2], which sorts keys bytewise.
+//! - Tags are not allowed.
+//!
+//! Floats, which CTAP2 does not use, are encoded in the shortest form which
+//! preserves their value.
+//!
+//! [`to_vec`] encodes values and [`normalise`]s the result, i.e. it sorts
+//! map entries and makes lengths definite, but fails if the value can not
+//! be represented. [`decode`] only accepts input which already follows all
+//! rules.
+//!
+//! *Requires feature* `"ctap2"`.
+//!
+//! ```
+//! use minicbor::ctap2;
+//! use std::collections::BTreeMap;
+//!
+//! let mut m = BTreeMap::new();
+//! m.insert(-1, "x");
+//! m.insert(10, "y");
+//! m.insert(1, "z");
+//!
+//! let cbor = ctap2::to_vec(&m)?;
+//! assert_eq!(b"\xa3\x01\x61z\x0a\x61y\x20\x61x", cbor.as_slice());
+//! assert_eq!(m, ctap2::decode::<BTreeMap<i8, &str>>(&cbor)?);
+//! assert!(ctap2::decode::<BTreeMap<i8, &str>>(&minicbor::to_vec(&m)?).is_err());
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [1]: https://fidoalliance.org/specs/fido-v2.1-ps-20210615/fido-client-to-authenticator-protocol-v2.1-ps-20210615.html#ctap2-canonical-cbor-encoding-form
+//! [2]: https://www.rfc-editor.org/rfc/rfc8949.html#section-4.2.1
+
+use alloc::vec::Vec;
+use crate::decode::{self, Decode};
+use crate::encode::{self, Encode, Write};
+use crate::profile::{self, Ctap2};
+
+/// Encode a value in CTAP2 canonical form.
+///
+/// The value is encoded as usual and then normalised, which fails with an
+/// [`encode::Error::Message`] if the value can not be represented.
+pub fn to_vec<T: Encode>(x: T) -> Result<Vec<u8>, encode::Error<<Vec<u8> as Write>::Error>> {
+    profile::to_vec(&Ctap2, x)
+}
+
+/// Convert a single CBOR item to CTAP2 canonical form.
+///
+/// Fails if the item is malformed or can not be represented, e.g. because
+/// of duplicate map keys or tags.
+pub fn normalise(b: &[u8]) -> Result<Vec<u8>, decode::Error> {
+    profile::normalise(&Ctap2, b)
+}
+
+/// Decode a value in CTAP2 canonical form.
+///
+/// The input must contain a single item which follows all rules.
+pub fn decode<'b, T: Decode<'b>>(b: &'b [u8]) -> Result<T, decode::Error> {
+    validate(b)?;
+    crate::decode(b)
+}
+
+/// Check that the input contains a single item in CTAP2 canonical form.
+pub fn validate(b: &[u8]) -> Result<(), decode::Error> {
+    profile::validate(&Ctap2, b)
+}
@@ -0,0 +1,130 @@
+//! Support for [DAG-CBOR][1], the strict CBOR profile of IPLD.
+//!
+//! DAG-CBOR restricts CBOR as follows:
+//!
+//! - Integers and lengths use the shortest possible encoding.
+//! - Indefinite-length items are not allowed.
+//! - Map keys are text strings, unique, and sorted by the length of their
+//!   encoding first, then bytewise.
+//! - Floats are always encoded as `f64`. NaN and infinities are not allowed.
+//! - The only allowed simple values are `false`, `true` and `null`.
+//! - The only allowed tag is 42, which denotes a [`Cid`].
+//!
+//! [`to_vec`] encodes values and [`normalise`]s the result, i.e. it sorts
+//! map entries, makes lengths definite and widens floats, but fails if the
+//! value can not be represented in DAG-CBOR. [`decode`] only accepts input
+//! which already follows all rules.
+//!
+//! *Requires feature* `"dag-cbor"`.
+//!
+//! ```
+//! use minicbor::dag_cbor::{self, Cid};
+//! use std::collections::HashMap;
+//!
+//! let mut m = HashMap::new();
+//! m.insert("bb", Cid::new(vec![1, 0x71, 0, 0]));
+//! m.insert("a", Cid::new(vec![1, 0x71, 0, 1]));
+//!
+//! let cbor = dag_cbor::to_vec(&m)?;
+//! assert_eq!(b"\xa2\x61a\xd8\x2a\x45\x00\x01\x71\x00\x01\x62bb\xd8\x2a\x45\x00\x01\x71\x00\x00", cbor.as_slice());
+//! assert_eq!(m, dag_cbor::decode::<HashMap<&str, Cid>>(&cbor)?);
+//!
+//! assert!(dag_cbor::decode::<f32>(&minicbor::to_vec(1.5f32)?).is_err());
+//! assert!(dag_cbor::to_vec(f64::NAN).is_err());
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [1]: https://ipld.io/specs/codecs/dag-cbor/spec/
+
+use alloc::vec::Vec;
+use crate::data::Tag;
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+use crate::profile::{self, DagCbor};
+
+/// The CBOR tag of CIDs.
+pub const CID_TAG: u64 = 42;
+
+/// Encode a value as DAG-CBOR.
+///
+/// The value is encoded as usual and then normalised, which fails with an
+/// [`encode::Error::Message`] if the value can not be represented.
+pub fn to_vec<T: Encode>(x: T) -> Result<Vec<u8>, encode::Error<<Vec<u8> as Write>::Error>> {
+    profile::to_vec(&DagCbor, x)
+}
+
+/// Convert a single CBOR item to DAG-CBOR.
+///
+/// Fails if the item is malformed or can not be represented, e.g. because
+/// of non-text or duplicate map keys, other tags or non-finite floats.
+pub fn normalise(b: &[u8]) -> Result<Vec<u8>, decode::Error> {
+    profile::normalise(&DagCbor, b)
+}
+
+/// Decode a value from DAG-CBOR.
+///
+/// The input must contain a single item which follows all DAG-CBOR rules.
+pub fn decode<'b, T: Decode<'b>>(b: &'b [u8]) -> Result<T, decode::Error> {
+    validate(b)?;
+    crate::decode(b)
+}
+
+/// Check that the input contains a single item which follows all DAG-CBOR
+/// rules.
+pub fn validate(b: &[u8]) -> Result<(), decode::Error> {
+    profile::validate(&DagCbor, b)
+}
+
+/// A content identifier (CID) in its binary form.
+///
+/// CIDs are encoded as byte strings with tag 42. The bytes are prefixed
+/// with `0x00`, the multibase prefix of the identity encoding, which is
+/// added when encoding and required and removed when decoding.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Cid(Vec<u8>);
+
+impl Cid {
+    /// Create a CID from its binary form (without multibase prefix).
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Cid(bytes)
+    }
+
+    /// Get the binary form of this CID.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Get back the binary form of this CID.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl AsRef<[u8]> for Cid {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Encode for Cid {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        let mut b = Vec::with_capacity(self.0.len() + 1);
+        b.push(0);
+        b.extend_from_slice(&self.0);
+        e.tag(Tag::Unassigned(CID_TAG))?.bytes(&b)?.ok()
+    }
+}
+
+impl<'b> Decode<'b> for Cid {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        if d.tag()? != Tag::Unassigned(CID_TAG) {
+            return Err(decode::Error::Message("expected tag 42 for cid"))
+        }
+        match d.bytes()? {
+            [0, cid @ ..] if !cid.is_empty() => Ok(Cid(cid.to_vec())),
+            [0] => Err(decode::Error::Message("empty cid")),
+            _   => Err(decode::Error::Message("missing multibase prefix 0x00 of cid"))
+        }
+    }
+}
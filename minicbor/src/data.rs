@@ -4,6 +4,7 @@ use core::fmt;
 
 /// CBOR data types.
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Type {
     Bool,
     Null,
@@ -99,8 +100,65 @@ impl Type {
     }
 }
 
+/// The head of a CBOR data item.
+///
+/// Every data item begins with an initial byte, consisting of the major
+/// type (highest 3 bits) and the additional information (lowest 5 bits),
+/// possibly followed by 1, 2, 4 or 8 bytes of argument. The argument is
+/// not interpreted, i.e. depending on the major type it may be an integer,
+/// a length, a tag number, a simple value or the bits of a float.
+///
+/// Returned from [`Decoder::read_head`](crate::Decoder::read_head).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Head {
+    initial: u8,
+    argument: u64,
+    len: u8
+}
+
+impl Head {
+    pub(crate) fn new(initial: u8, argument: u64, len: u8) -> Self {
+        Head { initial, argument, len }
+    }
+
+    /// Get the major type (0 ..= 7).
+    pub fn major(&self) -> u8 {
+        self.initial >> 5
+    }
+
+    /// Get the additional information (0 ..= 31).
+    pub fn info(&self) -> u8 {
+        self.initial & 0b000_11111
+    }
+
+    /// Get the argument.
+    ///
+    /// For additional information values below 24 this is the value of
+    /// the additional information itself. If the item has an indefinite
+    /// length (or is a break), `None` is returned.
+    pub fn argument(&self) -> Option<u64> {
+        if self.info() == 31 {
+            None
+        } else {
+            Some(self.argument)
+        }
+    }
+
+    /// Get the number of bytes of the head, i.e. 1, 2, 3, 5 or 9.
+    pub fn size(&self) -> usize {
+        usize::from(self.len)
+    }
+
+    /// Get the data type of the item this head belongs to.
+    pub fn datatype(&self) -> Type {
+        Type::read(self.initial)
+    }
+}
+
 /// CBOR data item tag.
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Tag {
     DateTime,
     Timestamp,
@@ -142,7 +200,7 @@ impl Tag {
         }
     }
 
-    pub(crate) fn numeric(self) -> u64 {
+    pub(crate) const fn numeric(self) -> u64 {
         match self {
             Tag::DateTime      => 0x00,
             Tag::Timestamp     => 0x01,
@@ -162,5 +220,158 @@ impl Tag {
             Tag::Unassigned(n) => n
         }
     }
+
+    /// Get the registry entry of this tag.
+    ///
+    /// See [`TagInfo::lookup`] for details.
+    pub fn info(self) -> Option<&'static TagInfo> {
+        TagInfo::lookup(self.numeric())
+    }
+
+    /// Get the name of this tag if it is registered.
+    pub fn name(self) -> Option<&'static str> {
+        self.info().map(TagInfo::name)
+    }
+
+    /// Get the specification of this tag if it is registered.
+    pub fn spec(self) -> Option<&'static str> {
+        self.info().map(TagInfo::spec)
+    }
+}
+
+impl From<u64> for Tag {
+    fn from(n: u64) -> Self {
+        Tag::from(n)
+    }
 }
 
+impl From<Tag> for u64 {
+    fn from(t: Tag) -> Self {
+        t.numeric()
+    }
+}
+
+/// The registry entry of a tag.
+///
+/// minicbor contains a table of commonly used tags of the [IANA registry][1]
+/// which can be queried with [`TagInfo::lookup`] or [`Tag::info`]:
+///
+/// ```
+/// use minicbor::data::{Tag, TagInfo};
+///
+/// let t = TagInfo::lookup(52).unwrap();
+/// assert_eq!("IPv4 address or prefix", t.name());
+/// assert_eq!("RFC 9164", t.spec());
+///
+/// assert_eq!(Some("epoch-based date-time"), Tag::Timestamp.name());
+/// assert_eq!(None, Tag::Unassigned(12345).name());
+/// ```
+///
+/// [1]: https://www.iana.org/assignments/cbor-tags/cbor-tags.xhtml
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct TagInfo {
+    number: u64,
+    name: &'static str,
+    spec: &'static str
+}
+
+impl TagInfo {
+    const fn new(number: u64, name: &'static str, spec: &'static str) -> Self {
+        TagInfo { number, name, spec }
+    }
+
+    /// Look up the registry entry of a tag number.
+    ///
+    /// Returns `None` for tag numbers which are not registered or not
+    /// contained in minicbor's table.
+    pub fn lookup(n: u64) -> Option<&'static TagInfo> {
+        REGISTRY.binary_search_by_key(&n, |t| t.number).ok().map(|i| &REGISTRY[i])
+    }
+
+    /// Get the tag number.
+    pub fn number(&self) -> u64 {
+        self.number
+    }
+
+    /// Get a short description of the tag's semantics.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Get the specification of the tag, i.e. an RFC number or a URL.
+    pub fn spec(&self) -> &'static str {
+        self.spec
+    }
+}
+
+/// Registered tags, sorted by tag number.
+static REGISTRY: &[TagInfo] = &[
+    TagInfo::new(0, "standard date-time string", "RFC 8949"),
+    TagInfo::new(1, "epoch-based date-time", "RFC 8949"),
+    TagInfo::new(2, "unsigned bignum", "RFC 8949"),
+    TagInfo::new(3, "negative bignum", "RFC 8949"),
+    TagInfo::new(4, "decimal fraction", "RFC 8949"),
+    TagInfo::new(5, "bigfloat", "RFC 8949"),
+    TagInfo::new(16, "COSE_Encrypt0", "RFC 9052"),
+    TagInfo::new(17, "COSE_Mac0", "RFC 9052"),
+    TagInfo::new(18, "COSE_Sign1", "RFC 9052"),
+    TagInfo::new(19, "COSE_Countersignature", "RFC 9338"),
+    TagInfo::new(21, "expected conversion to base64url encoding", "RFC 8949"),
+    TagInfo::new(22, "expected conversion to base64 encoding", "RFC 8949"),
+    TagInfo::new(23, "expected conversion to base16 encoding", "RFC 8949"),
+    TagInfo::new(24, "encoded CBOR data item", "RFC 8949"),
+    TagInfo::new(25, "reference to a previously seen string", "http://cbor.schmorp.de/stringref"),
+    TagInfo::new(28, "shared value", "http://cbor.schmorp.de/value-sharing"),
+    TagInfo::new(29, "reference to a shared value", "http://cbor.schmorp.de/value-sharing"),
+    TagInfo::new(30, "rational number", "http://peteroupc.github.io/CBOR/rational.html"),
+    TagInfo::new(32, "URI", "RFC 8949"),
+    TagInfo::new(33, "base64url-encoded text", "RFC 8949"),
+    TagInfo::new(34, "base64-encoded text", "RFC 8949"),
+    TagInfo::new(35, "regular expression", "RFC 7049"),
+    TagInfo::new(36, "MIME message", "RFC 7049"),
+    TagInfo::new(37, "binary UUID", "https://github.com/lucas-clemente/cbor-specs/blob/master/uuid.md"),
+    TagInfo::new(38, "language-tagged string", "http://peteroupc.github.io/CBOR/langtags.html"),
+    TagInfo::new(40, "multi-dimensional array, row-major order", "RFC 8746"),
+    TagInfo::new(41, "homogeneous array", "RFC 8746"),
+    TagInfo::new(42, "IPLD content identifier", "https://github.com/ipld/cid-cbor/"),
+    TagInfo::new(52, "IPv4 address or prefix", "RFC 9164"),
+    TagInfo::new(54, "IPv6 address or prefix", "RFC 9164"),
+    TagInfo::new(61, "CBOR Web Token", "RFC 8392"),
+    TagInfo::new(63, "encoded CBOR sequence", "RFC 8742"),
+    TagInfo::new(64, "uint8 typed array", "RFC 8746"),
+    TagInfo::new(65, "uint16 big endian typed array", "RFC 8746"),
+    TagInfo::new(66, "uint32 big endian typed array", "RFC 8746"),
+    TagInfo::new(67, "uint64 big endian typed array", "RFC 8746"),
+    TagInfo::new(68, "uint8 typed array, clamped arithmetic", "RFC 8746"),
+    TagInfo::new(69, "uint16 little endian typed array", "RFC 8746"),
+    TagInfo::new(70, "uint32 little endian typed array", "RFC 8746"),
+    TagInfo::new(71, "uint64 little endian typed array", "RFC 8746"),
+    TagInfo::new(72, "sint8 typed array", "RFC 8746"),
+    TagInfo::new(73, "sint16 big endian typed array", "RFC 8746"),
+    TagInfo::new(74, "sint32 big endian typed array", "RFC 8746"),
+    TagInfo::new(75, "sint64 big endian typed array", "RFC 8746"),
+    TagInfo::new(77, "sint16 little endian typed array", "RFC 8746"),
+    TagInfo::new(78, "sint32 little endian typed array", "RFC 8746"),
+    TagInfo::new(79, "sint64 little endian typed array", "RFC 8746"),
+    TagInfo::new(80, "binary16 big endian typed array", "RFC 8746"),
+    TagInfo::new(81, "binary32 big endian typed array", "RFC 8746"),
+    TagInfo::new(82, "binary64 big endian typed array", "RFC 8746"),
+    TagInfo::new(83, "binary128 big endian typed array", "RFC 8746"),
+    TagInfo::new(84, "binary16 little endian typed array", "RFC 8746"),
+    TagInfo::new(85, "binary32 little endian typed array", "RFC 8746"),
+    TagInfo::new(86, "binary64 little endian typed array", "RFC 8746"),
+    TagInfo::new(87, "binary128 little endian typed array", "RFC 8746"),
+    TagInfo::new(96, "COSE_Encrypt", "RFC 9052"),
+    TagInfo::new(97, "COSE_Mac", "RFC 9052"),
+    TagInfo::new(98, "COSE_Sign", "RFC 9052"),
+    TagInfo::new(100, "days since the epoch date 1970-01-01", "RFC 8943"),
+    TagInfo::new(256, "string references namespace", "http://cbor.schmorp.de/stringref"),
+    TagInfo::new(258, "mathematical finite set", "https://github.com/input-output-hk/cbor-sets-spec/blob/master/CBOR_SETS.md"),
+    TagInfo::new(1001, "extended time", "RFC 9581"),
+    TagInfo::new(1002, "duration", "RFC 9581"),
+    TagInfo::new(1003, "period", "RFC 9581"),
+    TagInfo::new(1004, "full-date string", "RFC 8943"),
+    TagInfo::new(1040, "multi-dimensional array, column-major order", "RFC 8746"),
+    TagInfo::new(55799, "self-described CBOR", "RFC 8949")
+];
+
@@ -1,13 +1,48 @@
 //! Traits and types for decoding CBOR.
 //!
-//! This module defines the trait [`Decode`] and the actual [`Decoder`].
+//! This module defines the traits [`Decode`] and [`DecodeInPlace`] and the
+//! actual [`Decoder`].
+//!
+//! Decoding never panics, whatever the input. Malformed or unexpected
+//! input results in an [`Error`] instead. The only limits are the max.
+//! nesting depth (cf. [`Decoder::set_max_depth`]) and the call stack usage
+//! of recursive `Decode` impls.
+
+// Code which may panic, e.g. indexing, unchecked arithmetic and truncating
+// casts, is rejected by clippy if the internal feature "__check-panic-free"
+// is enabled (cf. `make check-panic-free`).
+#![cfg_attr(feature = "__check-panic-free", deny(
+    clippy::arithmetic_side_effects,
+    clippy::cast_possible_truncation,
+    clippy::expect_used,
+    clippy::indexing_slicing,
+    clippy::panic,
+    clippy::todo,
+    clippy::unimplemented,
+    clippy::unreachable,
+    clippy::unwrap_used
+))]
 
 mod decoder;
+pub(crate) mod diagnostic;
 mod error;
+mod in_place;
 
-pub use decoder::{Decoder, Probe};
+pub use decoder::{Decoder, Probe, DEFAULT_MAX_DEPTH};
 pub use decoder::{ArrayIter, BytesIter, MapIter, StrIter};
-pub use error::Error;
+pub use error::{Error, MissingFields};
+pub use in_place::DecodeInPlace;
+
+#[cfg(feature = "alloc")]
+pub(crate) use decoder::from_utf8;
+
+pub(crate) use diagnostic::Diagnostic;
+
+#[cfg(feature = "alloc")]
+mod feed;
+
+#[cfg(feature = "alloc")]
+pub use feed::Feed;
 
 #[cfg(feature = "half")]
 mod tokens;
@@ -35,6 +70,28 @@ impl<'b, T: Decode<'b>> Decode<'b> for alloc::rc::Rc<T> {
     }
 }
 
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl<'b, T: Decode<'b>> Decode<'b> for alloc::sync::Arc<T> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
+        T::decode(d).map(alloc::sync::Arc::new)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'b> Decode<'b> for alloc::boxed::Box<str> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
+        alloc::string::String::decode(d).map(alloc::string::String::into_boxed_str)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'b, T: Decode<'b>> Decode<'b> for alloc::boxed::Box<[T]> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
+        let v: alloc::vec::Vec<T> = d.decode()?;
+        Ok(v.into_boxed_slice())
+    }
+}
+
 impl<'b, T: Decode<'b>> Decode<'b> for core::cell::RefCell<T> {
     fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
         T::decode(d).map(core::cell::RefCell::new)
@@ -59,10 +116,26 @@ where
     }
 }
 
+#[cfg(feature = "std")]
+impl<'a, 'b: 'a> Decode<'b> for &'a std::path::Path {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
+        d.str().map(std::path::Path::new)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'b> Decode<'b> for std::path::PathBuf {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
+        d.decode::<alloc::string::String>().map(std::path::PathBuf::from)
+    }
+}
+
 #[cfg(feature = "alloc")]
 impl<'b> Decode<'b> for alloc::string::String {
     fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
-        d.str().map(alloc::string::String::from)
+        let mut s = alloc::string::String::new();
+        d.push_str(&mut s)?;
+        Ok(s)
     }
 }
 
@@ -88,7 +161,7 @@ where
         match d.u32()? {
             0 => T::decode(d).map(Ok),
             1 => E::decode(d).map(Err),
-            n => Err(Error::UnknownVariant(n))
+            n => Err(Error::UnknownVariant(n, "Result"))
         }
     }
 }
@@ -100,7 +173,7 @@ where
 {
     fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
         let iter: ArrayIter<T> = d.array_iter()?;
-        let mut v = alloc::collections::BinaryHeap::new();
+        let mut v = alloc::collections::BinaryHeap::with_capacity(capacity::<T>(iter.size_hint().0));
         for x in iter {
             v.push(x?)
         }
@@ -115,7 +188,7 @@ where
 {
     fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
         let iter: ArrayIter<T> = d.array_iter()?;
-        let mut v = std::collections::HashSet::new();
+        let mut v = std::collections::HashSet::with_capacity(capacity::<T>(iter.size_hint().0));
         for x in iter {
             v.insert(x?);
         }
@@ -145,8 +218,8 @@ where
     V: Decode<'b>
 {
     fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
-        let mut m = std::collections::HashMap::new();
         let iter: MapIter<K, V> = d.map_iter()?;
+        let mut m = std::collections::HashMap::with_capacity(capacity::<(K, V)>(iter.size_hint().0));
         for x in iter {
             let (k, v) = x?;
             m.insert(k, v);
@@ -190,59 +263,21 @@ impl<'b> Decode<'b> for () {
     }
 }
 
-#[cfg(target_pointer_width = "32")]
-impl<'b> Decode<'b> for usize {
-    fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
-        d.u32().map(|n| n as usize)
-    }
-}
+// `usize` and `isize` are decoded from their 64-bit counterparts, so that
+// every target accepts the same encodings. Values which do not fit into the
+// target's pointer width produce an `Overflow` error.
 
-#[cfg(target_pointer_width = "64")]
 impl<'b> Decode<'b> for usize {
     fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
-        d.u64().map(|n| n as usize)
-    }
-}
-
-#[cfg(all(not(feature = "std"),target_pointer_width = "32"))]
-impl<'b> Decode<'b> for core::num::NonZeroUsize {
-    fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
-        d.u32().and_then(|n| core::num::NonZeroUsize::new(n as usize).ok_or_else(|| Error::Message("NonZeroSize creation error")))
-    }
-}
-
-#[cfg(all(not(feature = "std"),target_pointer_width = "64"))]
-impl<'b> Decode<'b> for core::num::NonZeroUsize {
-    fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
-        d.u64().and_then(|n| core::num::NonZeroUsize::new(n as usize).ok_or_else(|| Error::Message("NonZeroSize creation error")))
-    }
-}
-
-#[cfg(all(feature = "std",target_pointer_width = "32"))]
-impl<'b> Decode<'b> for std::num::NonZeroUsize {
-    fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
-        d.u32().and_then(|n| std::num::NonZeroUsize::new(n as usize).ok_or_else(|| Error::Message("NonZeroSize creation error")))
-    }
-}
-
-#[cfg(all(feature = "std",target_pointer_width = "64"))]
-impl<'b> Decode<'b> for std::num::NonZeroUsize {
-    fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
-        d.u64().and_then(|n| std::num::NonZeroUsize::new(n as usize).ok_or_else(|| Error::Message("NonZeroSize creation error")))
-    }
-}
-
-#[cfg(target_pointer_width = "32")]
-impl<'b> Decode<'b> for isize {
-    fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
-        d.i32().map(|n| n as isize)
+        let n = d.u64()?;
+        core::convert::TryFrom::try_from(n).map_err(|_| Error::Overflow(n, "u64->usize"))
     }
 }
 
-#[cfg(target_pointer_width = "64")]
 impl<'b> Decode<'b> for isize {
     fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
-        d.i64().map(|n| n as isize)
+        let n = d.i64()?;
+        core::convert::TryFrom::try_from(n).map_err(|_| Error::Overflow(n.unsigned_abs(), "i64->isize"))
     }
 }
 
@@ -258,7 +293,7 @@ macro_rules! decode_basic {
     }
 }
 
-decode_basic!(u8 i8 u16 i16 u32 i32 u64 i64 bool f32 f64 char);
+decode_basic!(u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 bool f32 f64 char);
 
 macro_rules! decode_nonzero {
     ($($t:ty, $msg:expr)*) => {
@@ -273,24 +308,44 @@ macro_rules! decode_nonzero {
 }
 
 decode_nonzero! {
-    core::num::NonZeroU8,  "unexpected 0 when decoding a `NonZeroU8`"
-    core::num::NonZeroU16, "unexpected 0 when decoding a `NonZeroU16`"
-    core::num::NonZeroU32, "unexpected 0 when decoding a `NonZeroU32`"
-    core::num::NonZeroU64, "unexpected 0 when decoding a `NonZeroU64`"
-    core::num::NonZeroI8,  "unexpected 0 when decoding a `NonZeroI8`"
-    core::num::NonZeroI16, "unexpected 0 when decoding a `NonZeroI16`"
-    core::num::NonZeroI32, "unexpected 0 when decoding a `NonZeroI32`"
-    core::num::NonZeroI64, "unexpected 0 when decoding a `NonZeroI64`"
+    core::num::NonZeroU8,     "unexpected 0 when decoding a `NonZeroU8`"
+    core::num::NonZeroU16,    "unexpected 0 when decoding a `NonZeroU16`"
+    core::num::NonZeroU32,    "unexpected 0 when decoding a `NonZeroU32`"
+    core::num::NonZeroU64,    "unexpected 0 when decoding a `NonZeroU64`"
+    core::num::NonZeroU128,   "unexpected 0 when decoding a `NonZeroU128`"
+    core::num::NonZeroUsize,  "unexpected 0 when decoding a `NonZeroUsize`"
+    core::num::NonZeroI8,     "unexpected 0 when decoding a `NonZeroI8`"
+    core::num::NonZeroI16,    "unexpected 0 when decoding a `NonZeroI16`"
+    core::num::NonZeroI32,    "unexpected 0 when decoding a `NonZeroI32`"
+    core::num::NonZeroI64,    "unexpected 0 when decoding a `NonZeroI64`"
+    core::num::NonZeroI128,   "unexpected 0 when decoding a `NonZeroI128`"
+    core::num::NonZeroIsize,  "unexpected 0 when decoding a `NonZeroIsize`"
+}
+
+/// The max. number of bytes to pre-allocate for a decoded collection.
+#[cfg(feature = "alloc")]
+const MAX_PREALLOC: usize = 64 * 1024;
+
+/// Get the number of elements to pre-allocate for a collection of `hint`
+/// elements (cf. `ArrayIter::size_hint`).
+///
+/// Even if the hint is capped by the remaining input, every element may
+/// require much more memory than its encoding, so the allocation is
+/// also limited to `MAX_PREALLOC` bytes. Collections grow as needed
+/// beyond that.
+#[cfg(feature = "alloc")]
+fn capacity<T>(hint: usize) -> usize {
+    MAX_PREALLOC.checked_div(core::mem::size_of::<T>()).map_or(hint, |n| n.min(hint))
 }
 
 #[cfg(feature = "alloc")]
 macro_rules! decode_sequential {
-    ($($t:ty, $push:ident)*) => {
+    ($($t:ty, $push:ident, $new:expr)*) => {
         $(
             impl<'b, T: Decode<'b>> Decode<'b> for $t {
                 fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
                     let iter: ArrayIter<T> = d.array_iter()?;
-                    let mut v = <$t>::new();
+                    let mut v: $t = $new(capacity::<T>(iter.size_hint().0));
                     for x in iter {
                         v.$push(x?)
                     }
@@ -303,9 +358,9 @@ macro_rules! decode_sequential {
 
 #[cfg(feature = "alloc")]
 decode_sequential! {
-    alloc::vec::Vec<T>, push
-    alloc::collections::VecDeque<T>, push_back
-    alloc::collections::LinkedList<T>, push_back
+    alloc::vec::Vec<T>, push, alloc::vec::Vec::with_capacity
+    alloc::collections::VecDeque<T>, push_back, alloc::collections::VecDeque::with_capacity
+    alloc::collections::LinkedList<T>, push_back, |_| alloc::collections::LinkedList::new()
 }
 
 macro_rules! decode_arrays {
@@ -315,16 +370,16 @@ macro_rules! decode_arrays {
                 fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
                     let iter: ArrayIter<T> = d.array_iter()?;
                     let mut a: [T; $n] = Default::default();
-                    let mut i = 0;
+                    let mut slots = a.iter_mut();
                     for x in iter {
-                        if i >= a.len() {
+                        if let Some(slot) = slots.next() {
+                            *slot = x?
+                        } else {
                             let msg = concat!("array has more than ", $n, " elements");
                             return Err(Error::Message(msg))
                         }
-                        a[i] = x?;
-                        i += 1;
                     }
-                    if i < a.len() {
+                    if slots.next().is_some() {
                         let msg = concat!("array has less than ", $n, " elements");
                         return Err(Error::Message(msg))
                     }
@@ -384,13 +439,13 @@ macro_rules! decode_fields {
                 }
             }
             None => {
-                let mut i = 0;
+                let mut i = 0u64;
                 while $d.datatype()? != crate::data::Type::Break {
                     match i {
                         $($n => $x = Some(Decode::decode($d)?),)*
                         _    => $d.limited_skip()?
                     }
-                    i += 1
+                    i = i.saturating_add(1)
                 }
                 $d.limited_skip()?
             }
@@ -414,69 +469,63 @@ impl<'b> Decode<'b> for core::time::Duration {
     }
 }
 
-#[cfg(feature = "std")]
-impl<'b> Decode<'b> for std::net::IpAddr {
+impl<'b> Decode<'b> for core::net::IpAddr {
     fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
         if Some(2) != d.array()? {
             return Err(Error::Message("expected enum (2-element array)"))
         }
         match d.u32()? {
-            0 => Ok(std::net::Ipv4Addr::decode(d)?.into()),
-            1 => Ok(std::net::Ipv6Addr::decode(d)?.into()),
-            n => Err(Error::UnknownVariant(n))
+            0 => Ok(core::net::Ipv4Addr::decode(d)?.into()),
+            1 => Ok(core::net::Ipv6Addr::decode(d)?.into()),
+            n => Err(Error::UnknownVariant(n, "IpAddr"))
         }
     }
 }
 
-#[cfg(feature = "std")]
-impl<'b> Decode<'b> for std::net::Ipv4Addr {
+impl<'b> Decode<'b> for core::net::Ipv4Addr {
     fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
         let octets: [u8; 4] = Decode::decode(d)?;
         Ok(octets.into())
     }
 }
 
-#[cfg(feature = "std")]
-impl<'b> Decode<'b> for std::net::Ipv6Addr {
+impl<'b> Decode<'b> for core::net::Ipv6Addr {
     fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
         let octets: [u8; 16] = Decode::decode(d)?;
         Ok(octets.into())
     }
 }
 
-#[cfg(feature = "std")]
-impl<'b> Decode<'b> for std::net::SocketAddr {
+impl<'b> Decode<'b> for core::net::SocketAddr {
     fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
         if Some(2) != d.array()? {
             return Err(Error::Message("expected enum (2-element array)"))
         }
         match d.u32()? {
-            0 => Ok(std::net::SocketAddrV4::decode(d)?.into()),
-            1 => Ok(std::net::SocketAddrV6::decode(d)?.into()),
-            n => Err(Error::UnknownVariant(n))
+            0 => Ok(core::net::SocketAddrV4::decode(d)?.into()),
+            1 => Ok(core::net::SocketAddrV6::decode(d)?.into()),
+            n => Err(Error::UnknownVariant(n, "SocketAddr"))
         }
     }
 }
 
-#[cfg(feature = "std")]
-impl<'b> Decode<'b> for std::net::SocketAddrV4 {
+impl<'b> Decode<'b> for core::net::SocketAddrV4 {
     fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
         decode_fields! { d |
-            0 ip   => std::net::Ipv4Addr ; "SocketAddrV4::ip"
-            1 port => u16                ; "SocketAddrV4::port"
+            0 ip   => core::net::Ipv4Addr ; "SocketAddrV4::ip"
+            1 port => u16                 ; "SocketAddrV4::port"
         }
-        Ok(std::net::SocketAddrV4::new(ip, port))
+        Ok(core::net::SocketAddrV4::new(ip, port))
     }
 }
 
-#[cfg(feature = "std")]
-impl<'b> Decode<'b> for std::net::SocketAddrV6 {
+impl<'b> Decode<'b> for core::net::SocketAddrV6 {
     fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
         decode_fields! { d |
-            0 ip   => std::net::Ipv6Addr ; "SocketAddrV6::ip"
-            1 port => u16                ; "SocketAddrV6::port"
+            0 ip   => core::net::Ipv6Addr ; "SocketAddrV6::ip"
+            1 port => u16                 ; "SocketAddrV6::port"
         }
-        Ok(std::net::SocketAddrV6::new(ip, port, 0, 0))
+        Ok(core::net::SocketAddrV6::new(ip, port, 0, 0))
     }
 }
 
@@ -1,10 +1,10 @@
 #![allow(clippy::unusual_byte_groupings)]
 
 use crate::{ARRAY, BREAK, BYTES, MAP, SIMPLE, TAGGED, TEXT, SIGNED, UNSIGNED};
-use crate::data::{Tag, Type};
+use crate::data::{Head, Tag, Type};
 use crate::decode::{Decode, Error};
-use core::{char, f32, i8, i16, i32, i64};
-use core::{convert::TryInto, marker, str};
+use core::char;
+use core::{convert::{TryFrom, TryInto}, marker, str};
 
 // Convert an expression of an unsigned int type to a signed int type.
 //
@@ -17,7 +17,7 @@ macro_rules! u_to_i {
         if val > $max {
             Err(Error::Overflow(u64::from(val), $msg))
         } else {
-            Ok(-1 - val as $typ)
+            Ok(!(val as $typ))
         }
     }}
 }
@@ -39,22 +39,131 @@ macro_rules! u_as_i {
     }}
 }
 
+/// The default max. nesting depth of arrays and maps (cf. [`Decoder::set_max_depth`]).
+pub const DEFAULT_MAX_DEPTH: usize = 1024;
+
 /// A non-allocating CBOR decoder.
 #[derive(Debug, Clone)]
 pub struct Decoder<'b> {
     buf: &'b [u8],
-    pos: usize
+    pos: usize,
+    max_depth: usize,
+    reject_non_finite: bool,
+    trusted_utf8: bool
 }
 
 impl<'b> Decoder<'b> {
     /// Construct a `Decoder` for the given byte slice.
     pub fn new(bytes: &'b [u8]) -> Self {
-        Decoder { buf: bytes, pos: 0 }
+        Decoder {
+            buf: bytes,
+            pos: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            reject_non_finite: false,
+            trusted_utf8: false
+        }
+    }
+
+    /// Construct a `Decoder` for the given byte slice, which must only
+    /// contain items following the given [`Profile`].
+    ///
+    /// All items are validated up front and in a single pass, i.e. without
+    /// normalising them (cf. [`Decoder::check_profile`]). The decoder starts
+    /// at the beginning of the input.
+    ///
+    /// ```
+    /// use minicbor::Decoder;
+    /// use minicbor::profile::{CoreDeterministic, DCbor};
+    ///
+    /// let input = [0xf9, 0x40, 0x00];
+    /// assert_eq!(2.0, Decoder::with_profile(&CoreDeterministic, &input)?.f32()?);
+    /// assert!(Decoder::with_profile(&DCbor, &input).is_err());
+    ///
+    /// # Ok::<_, minicbor::decode::Error>(())
+    /// ```
+    ///
+    /// *Requires feature* `"alloc"`.
+    ///
+    /// [`Profile`]: crate::profile::Profile
+    #[cfg(feature = "alloc")]
+    pub fn with_profile<P>(p: &P, bytes: &'b [u8]) -> Result<Self, Error>
+    where
+        P: crate::profile::Profile + ?Sized
+    {
+        let mut d = Decoder::new(bytes);
+        while d.position() < bytes.len() {
+            d.check_profile(p)?
+        }
+        d.set_position(0);
+        Ok(d)
+    }
+
+    /// Set the max. nesting depth of arrays and maps.
+    ///
+    /// This limit applies when skipping over CBOR items. Nesting deeper
+    /// than this results in an error. The default value is
+    /// [`DEFAULT_MAX_DEPTH`].
+    ///
+    /// **NB**: With feature-flag `"partial-skip-support"` but without
+    /// `"alloc"`, [`Decoder::skip`] keeps no nesting stack and does not
+    /// enforce this limit.
+    pub fn set_max_depth(&mut self, n: usize) {
+        self.max_depth = n
+    }
+
+    /// Get the max. nesting depth of arrays and maps.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Reject NaN and infinite floating point values.
+    ///
+    /// If set, decoding such a value results in an error. By default they
+    /// are accepted.
+    pub fn set_reject_non_finite(&mut self, val: bool) {
+        self.reject_non_finite = val
+    }
+
+    /// Are NaN and infinite floating point values rejected?
+    pub fn reject_non_finite(&self) -> bool {
+        self.reject_non_finite
+    }
+
+    /// Skip UTF-8 validation of text strings.
+    ///
+    /// For trusted input, e.g. data this process has encoded itself, this
+    /// avoids the cost of validating every decoded text string. By default
+    /// text strings are validated. For untrusted input, feature `"simdutf8"`
+    /// speeds up validation instead.
+    ///
+    /// # Safety
+    ///
+    /// If set, all text strings which are decoded must be valid UTF-8.
+    /// Decoding an invalid one results in undefined behaviour.
+    pub unsafe fn set_trusted_utf8(&mut self, val: bool) {
+        self.trusted_utf8 = val
+    }
+
+    /// Is the UTF-8 validation of text strings skipped?
+    pub fn trusted_utf8(&self) -> bool {
+        self.trusted_utf8
     }
 
     /// Decode any type that implements [`Decode`].
+    ///
+    /// With feature `"tracing"`, decoding happens in a span at level
+    /// `TRACE` and errors are reported as events at level `DEBUG`, both
+    /// with the type to decode and the decoder position.
     pub fn decode<T: Decode<'b>>(&mut self) -> Result<T, Error> {
-        T::decode(self)
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("decode", ty = core::any::type_name::<T>(), offset = self.pos).entered();
+        let result = T::decode(self);
+        #[cfg(feature = "tracing")]
+        if let Err(e) = &result {
+            let datatype = self.datatype().ok();
+            tracing::debug!(offset = self.pos, ?datatype, error = %e, "decode error")
+        }
+        result
     }
 
     /// Get the current decode position.
@@ -67,6 +176,30 @@ impl<'b> Decoder<'b> {
         self.pos = pos
     }
 
+    /// Get the complete input of this decoder.
+    pub fn input(&self) -> &'b [u8] {
+        self.buf
+    }
+
+    /// Get the input bytes after the current position.
+    ///
+    /// If the position is beyond the end of the input, the slice is empty.
+    ///
+    /// ```
+    /// use minicbor::Decoder;
+    ///
+    /// let input = [0x82, 0x01, 0x02, 0x03];
+    /// let mut d = Decoder::new(&input);
+    /// d.skip()?;
+    /// assert_eq!(&[0x03], d.remaining());
+    /// assert_eq!(&input, d.input());
+    ///
+    /// # Ok::<_, minicbor::decode::Error>(())
+    /// ```
+    pub fn remaining(&self) -> &'b [u8] {
+        self.buf.get(self.pos ..).unwrap_or(&[])
+    }
+
     /// Get a decoding probe to look ahead what is coming next.
     ///
     /// This will not affect the decoding state of `self` and after the
@@ -79,6 +212,36 @@ impl<'b> Decoder<'b> {
         }
     }
 
+    /// Split off the next CBOR item into a decoder of its own.
+    ///
+    /// The returned decoder operates on exactly the bytes of the next item
+    /// and has the same settings as `self`, which moves past the item. Code
+    /// using the returned decoder can therefore not read beyond the item
+    /// and leave `self` in an inconsistent state.
+    ///
+    /// Positions of the returned decoder are relative to the start of the
+    /// item. If the next item is not well-formed, an error is returned and
+    /// the position of `self` does not change. Whether the item exceeds the
+    /// max. nesting depth is checked as by [`Decoder::skip`].
+    ///
+    /// Requires feature `"alloc"` or `"partial-skip-support"` (cf.
+    /// [`Decoder::skip`]).
+    #[cfg(any(feature = "alloc", feature = "partial-skip-support"))]
+    pub fn item_decoder(&mut self) -> Result<Decoder<'b>, Error> {
+        let start = self.pos;
+        if let Err(e) = self.skip() {
+            self.pos = start;
+            return Err(e)
+        }
+        Ok(Decoder {
+            buf: self.buf.get(start .. self.pos).unwrap_or_default(),
+            pos: 0,
+            max_depth: self.max_depth,
+            reject_non_finite: self.reject_non_finite,
+            trusted_utf8: self.trusted_utf8
+        })
+    }
+
     /// Decode a `bool` value.
     pub fn bool(&mut self) -> Result<bool, Error> {
         match self.read()? {
@@ -129,7 +292,7 @@ impl<'b> Decoder<'b> {
         match self.read()? {
             n @ 0x00 ..= 0x17 => Ok(n as i8),
             0x18              => u_as_i!(self.read()?, i8, i8::MAX as u8, "u8->i8"),
-            n @ 0x20 ..= 0x37 => Ok(-1 - (n - 0x20) as i8),
+            n @ 0x20 ..= 0x37 => Ok(!(info_of(n) as i8)),
             0x38              => u_to_i!(self.read()?, i8, i8::MAX as u8, "u8->i8"),
             b                 => Err(Error::TypeMismatch(Type::read(b), "expected i8"))
         }
@@ -141,8 +304,8 @@ impl<'b> Decoder<'b> {
             n @ 0x00 ..= 0x17 => Ok(i16::from(n)),
             0x18              => self.read().map(i16::from),
             0x19              => u_as_i!(self.read_slice(2).map(read_u16)?, i16, i16::MAX as u16, "u16->i16"),
-            n @ 0x20 ..= 0x37 => Ok(-1 - i16::from(n - 0x20)),
-            0x38              => self.read().map(|n| -1 - i16::from(n)),
+            n @ 0x20 ..= 0x37 => Ok(!i16::from(info_of(n))),
+            0x38              => self.read().map(|n| !i16::from(n)),
             0x39              => u_to_i!(self.read_slice(2).map(read_u16)?, i16, i16::MAX as u16, "u16->i16"),
             b                 => Err(Error::TypeMismatch(Type::read(b), "expected i16"))
         }
@@ -155,9 +318,9 @@ impl<'b> Decoder<'b> {
             0x18              => self.read().map(i32::from),
             0x19              => self.read_slice(2).map(read_u16).map(i32::from),
             0x1a              => u_as_i!(self.read_slice(4).map(read_u32)?, i32, i32::MAX as u32, "u32->i32"),
-            n @ 0x20 ..= 0x37 => Ok(-1 - i32::from(n - 0x20)),
-            0x38              => self.read().map(|n| -1 - i32::from(n)),
-            0x39              => self.read_slice(2).map(read_u16).map(|n| -1 - i32::from(n)),
+            n @ 0x20 ..= 0x37 => Ok(!i32::from(info_of(n))),
+            0x38              => self.read().map(|n| !i32::from(n)),
+            0x39              => self.read_slice(2).map(read_u16).map(|n| !i32::from(n)),
             0x3a              => u_to_i!(self.read_slice(4).map(read_u32)?, i32, i32::MAX as u32, "u32->i32"),
             b                 => Err(Error::TypeMismatch(Type::read(b), "expected i32"))
         }
@@ -171,15 +334,52 @@ impl<'b> Decoder<'b> {
             0x19              => self.read_slice(2).map(read_u16).map(i64::from),
             0x1a              => self.read_slice(4).map(read_u32).map(i64::from),
             0x1b              => u_as_i!(self.read_slice(8).map(read_u64)?, i64, i64::MAX as u64, "u64->i64"),
-            n @ 0x20 ..= 0x37 => Ok(-1 - i64::from(n - 0x20)),
-            0x38              => self.read().map(|n| -1 - i64::from(n)),
-            0x39              => self.read_slice(2).map(read_u16).map(|n| -1 - i64::from(n)),
-            0x3a              => self.read_slice(4).map(read_u32).map(|n| -1 - i64::from(n)),
+            n @ 0x20 ..= 0x37 => Ok(!i64::from(info_of(n))),
+            0x38              => self.read().map(|n| !i64::from(n)),
+            0x39              => self.read_slice(2).map(read_u16).map(|n| !i64::from(n)),
+            0x3a              => self.read_slice(4).map(read_u32).map(|n| !i64::from(n)),
             0x3b              => u_to_i!(self.read_slice(8).map(read_u64)?, i64, i64::MAX as u64, "u64->i64"),
             b                 => Err(Error::TypeMismatch(Type::read(b), "expected i64"))
         }
     }
 
+    /// Decode a `u128` value.
+    ///
+    /// Besides unsigned integers this accepts positive bignums (tag 2)
+    /// whose value fits into 128 bits.
+    pub fn u128(&mut self) -> Result<u128, Error> {
+        let b = self.read()?;
+        match type_of(b) {
+            UNSIGNED => self.unsigned(info_of(b)).map(u128::from),
+            TAGGED   => match self.unsigned(info_of(b)).map(Tag::from)? {
+                Tag::PosBignum => self.bignum(),
+                _              => Err(Error::TypeMismatch(Type::Tag, "expected u128"))
+            }
+            _        => Err(Error::TypeMismatch(Type::read(b), "expected u128"))
+        }
+    }
+
+    /// Decode an `i128` value.
+    ///
+    /// Besides integers this accepts positive (tag 2) and negative (tag 3)
+    /// bignums whose value fits into an `i128`.
+    pub fn i128(&mut self) -> Result<i128, Error> {
+        let b = self.read()?;
+        match type_of(b) {
+            UNSIGNED => self.unsigned(info_of(b)).map(i128::from),
+            SIGNED   => self.unsigned(info_of(b)).map(|n| !i128::from(n)),
+            TAGGED   => match self.unsigned(info_of(b)).map(Tag::from)? {
+                Tag::PosBignum => i128::try_from(self.bignum()?)
+                    .map_err(|_| Error::Message("bignum exceeds the range of i128")),
+                Tag::NegBignum => i128::try_from(self.bignum()?)
+                    .map(|n| !n)
+                    .map_err(|_| Error::Message("bignum exceeds the range of i128")),
+                _ => Err(Error::TypeMismatch(Type::Tag, "expected i128"))
+            }
+            _        => Err(Error::TypeMismatch(Type::read(b), "expected i128"))
+        }
+    }
+
     /// Decode a half float (`f16`) and return it in an `f32`.
     ///
     /// Only available when the feature `half` is present.
@@ -191,7 +391,7 @@ impl<'b> Decoder<'b> {
         }
         let mut n = [0; 2];
         n.copy_from_slice(self.read_slice(2)?);
-        Ok(half::f16::from_bits(u16::from_be_bytes(n)).to_f32())
+        self.check_finite(half::f16::from_bits(u16::from_be_bytes(n)).to_f32())
     }
 
     /// Decode an `f32` value.
@@ -203,7 +403,7 @@ impl<'b> Decoder<'b> {
                 self.read()?;
                 let mut n = [0; 4];
                 n.copy_from_slice(self.read_slice(4)?);
-                Ok(f32::from_be_bytes(n))
+                self.check_finite(f32::from_be_bytes(n))
             }
             b => Err(Error::TypeMismatch(Type::read(b), "expected f32"))
         }
@@ -219,7 +419,7 @@ impl<'b> Decoder<'b> {
                 self.read()?;
                 let mut n = [0; 8];
                 n.copy_from_slice(self.read_slice(8)?);
-                Ok(f64::from_be_bytes(n))
+                self.check_finite(f64::from_be_bytes(n))
             }
             b => Err(Error::TypeMismatch(Type::read(b), "expected f64"))
         }
@@ -263,6 +463,44 @@ impl<'b> Decoder<'b> {
         }
     }
 
+    /// Decode a byte slice of definite or indefinite length into `buf`.
+    ///
+    /// Returns the prefix of `buf` containing the bytes. If they do not fit,
+    /// an [`Error::BufferTooSmall`] with the required length is returned
+    /// and the decoder position is left unchanged, so that decoding can be
+    /// retried with a larger buffer.
+    ///
+    /// ```
+    /// use minicbor::Decoder;
+    ///
+    /// let mut buf = [0; 2];
+    /// let mut d = Decoder::new(b"\x43abc");
+    /// assert!(matches!(d.bytes_into(&mut buf), Err(minicbor::decode::Error::BufferTooSmall(3))));
+    /// let mut buf = [0; 16];
+    /// assert_eq!(b"abc", d.bytes_into(&mut buf)?);
+    ///
+    /// # Ok::<_, minicbor::decode::Error>(())
+    /// ```
+    pub fn bytes_into<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], Error> {
+        let p = self.pos;
+        let mut n = 0usize;
+        let mut fits = true;
+        for x in self.bytes_iter()? {
+            let x = x?;
+            let end = n.saturating_add(x.len());
+            match buf.get_mut(n .. end) {
+                Some(b) if fits => b.copy_from_slice(x),
+                _               => fits = false
+            }
+            n = end
+        }
+        if !fits {
+            self.pos = p;
+            return Err(Error::BufferTooSmall(n))
+        }
+        Ok(buf.get(.. n).unwrap_or_default())
+    }
+
     /// Decode a string slice.
     ///
     /// This only decodes string slices of definite lengths.
@@ -274,7 +512,7 @@ impl<'b> Decoder<'b> {
         }
         let n = u64_to_usize(self.unsigned(info_of(b))?)?;
         let d = self.read_slice(n)?;
-        str::from_utf8(d).map_err(Error::from)
+        self.utf8(d)
     }
 
     /// Iterate over string slices.
@@ -296,6 +534,59 @@ impl<'b> Decoder<'b> {
         }
     }
 
+    /// Decode a text string of definite or indefinite length into `buf`.
+    ///
+    /// Returns the string, which is stored in a prefix of `buf`. If it does
+    /// not fit, an [`Error::BufferTooSmall`] with the required length in
+    /// bytes is returned and the decoder position is left unchanged, so that
+    /// decoding can be retried with a larger buffer.
+    ///
+    /// ```
+    /// use minicbor::Decoder;
+    ///
+    /// let mut buf = [0; 16];
+    /// let mut d = Decoder::new(b"\x7f\x62he\x63llo\xff");
+    /// assert_eq!("hello", d.str_into(&mut buf)?);
+    ///
+    /// # Ok::<_, minicbor::decode::Error>(())
+    /// ```
+    pub fn str_into<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a str, Error> {
+        let p = self.pos;
+        let mut n = 0usize;
+        let mut fits = true;
+        for x in self.str_iter()? {
+            let x = x?;
+            let end = n.saturating_add(x.len());
+            match buf.get_mut(n .. end) {
+                Some(b) if fits => b.copy_from_slice(x.as_bytes()),
+                _               => fits = false
+            }
+            n = end
+        }
+        if !fits {
+            self.pos = p;
+            return Err(Error::BufferTooSmall(n))
+        }
+        // Safety: every segment has been validated as UTF-8 (or is trusted
+        // to be, cf. `set_trusted_utf8`) and a concatenation of valid UTF-8
+        // strings is valid UTF-8.
+        Ok(unsafe { str::from_utf8_unchecked(buf.get(.. n).unwrap_or_default()) })
+    }
+
+    /// Append a text string of definite or indefinite length to `s`.
+    ///
+    /// Every segment is validated once as it is decoded and then copied
+    /// without validating the concatenation again. Since each segment must
+    /// be valid UTF-8 on its own, this is equivalent to validating the
+    /// whole string.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn push_str(&mut self, s: &mut alloc::string::String) -> Result<(), Error> {
+        for x in self.str_iter()? {
+            s.push_str(x?)
+        }
+        Ok(())
+    }
+
     /// Begin decoding an array.
     ///
     /// CBOR arrays are heterogenous collections and may be of indefinite
@@ -367,7 +658,7 @@ impl<'b> Decoder<'b> {
     /// Decode a CBOR simple value.
     pub fn simple(&mut self) -> Result<u8, Error> {
         match self.read()? {
-            n @ SIMPLE ..= 0xf3 => Ok(n - SIMPLE),
+            n @ SIMPLE ..= 0xf3 => Ok(info_of(n)),
             0xf8                => self.read(),
             n                   => Err(Error::TypeMismatch(Type::read(n), "expected simple value"))
         }
@@ -378,79 +669,78 @@ impl<'b> Decoder<'b> {
         self.current().map(Type::read)
     }
 
+    /// Decode the head of the current CBOR data item.
+    ///
+    /// Only the initial byte and the argument bytes are consumed, i.e. for
+    /// strings the decoder is positioned at the string contents and for
+    /// arrays, maps and tags at their first nested item.
+    ///
+    /// Additional information values 28, 29 and 30 are reserved and produce
+    /// an error. Value 31 denotes an indefinite length and is only valid for
+    /// byte and text strings, arrays and maps. In particular, a break is not
+    /// the head of a data item and produces an error, too. When iterating
+    /// over the items of indefinite-length arrays and maps, check for it
+    /// with [`Decoder::datatype`] instead.
+    pub fn read_head(&mut self) -> Result<Head, Error> {
+        let b = self.current()?;
+        let (x, len) = match info_of(b) {
+            0x1c ..= 0x1e => return Err(Error::TypeMismatch(Type::read(b), "reserved additional information")),
+            0x1f          => {
+                match type_of(b) {
+                    BYTES | TEXT | ARRAY | MAP => {}
+                    SIMPLE => return Err(Error::TypeMismatch(Type::Break, "unexpected break")),
+                    _      => return Err(Error::TypeMismatch(Type::read(b), "invalid indefinite length"))
+                }
+                self.read()?;
+                (0, 1)
+            }
+            n             => {
+                self.read()?;
+                let len = match n { 0x18 => 2, 0x19 => 3, 0x1a => 5, 0x1b => 9, _ => 1 };
+                (self.unsigned(n)?, len)
+            }
+        };
+        Ok(Head::new(b, x, len))
+    }
+
     /// Skip over the current CBOR value.
+    ///
+    /// Skipping does not recurse, i.e. the call stack usage is constant
+    /// regardless of the input. Nested arrays and maps are tracked on an
+    /// explicit stack whose size is bounded by the max. nesting depth (see
+    /// [`Decoder::set_max_depth`]). Exceeding it results in an error.
     #[cfg(all(feature = "alloc", not(feature = "__test-partial-skip-support")))]
     pub fn skip(&mut self) -> Result<(), Error> {
-        // Unless we encounter indefinite-length arrays or maps inside of regular
-        // maps or arrays we only need to count how many more CBOR items we need
-        // to skip (initially starting with 1) or how many more break bytes we
-        // need to expect (initially starting with 0).
-        //
-        // If we do need to handle indefinite items (other than bytes or strings),
-        // inside of regular maps or arrays, we switch to using a stack of length
-        // information, starting with the remaining number of potential breaks we
-        // are still expecting and the number of items we still need to skip over
-        // at that point.
-
-        let mut nrounds = 1u64; // number of iterations over array and map elements
-        let mut irounds = 0u64; // number of indefinite iterations
+        // For every array or map we are currently in, the stack contains the
+        // number of items left to skip (`Some`) or `None` if the container is
+        // of indefinite length and we are waiting for a break byte.
         let mut stack: alloc::vec::Vec<Option<u64>> = alloc::vec::Vec::new();
 
-        while nrounds > 0 || irounds > 0 || !stack.is_empty() {
+        loop {
             match self.current()? {
                 UNSIGNED ..= 0x1b => { self.u64()?; }
                 SIGNED   ..= 0x3b => { self.i64()?; }
-                BYTES    ..= 0x5f => { for _ in self.bytes_iter()? {} }
-                TEXT     ..= 0x7f => { for _ in self.str_iter()? {} }
+                BYTES    ..= 0x5f => { for x in self.bytes_iter()? { x?; } }
+                TEXT     ..= 0x7f => { for x in self.str_iter()? { x?; } }
                 ARRAY    ..= 0x9f =>
                     match self.array()? {
                         Some(0) => {}
-                        Some(n) =>
-                            if nrounds == 0 && irounds == 0 {
-                                stack.push(Some(n))
-                            } else {
-                                nrounds = nrounds.saturating_add(n)
-                            }
-                        None =>
-                            if nrounds == 0 && irounds == 0 {
-                                stack.push(None)
-                            } else if nrounds < 2 {
-                                irounds = irounds.saturating_add(1)
-                            } else {
-                                for _ in 0 .. irounds {
-                                    stack.push(None)
-                                }
-                                stack.push(Some(nrounds - 1));
-                                stack.push(None);
-                                nrounds = 0;
-                                irounds = 0
-                            }
+                        n => {
+                            self.push_level(&mut stack, n)?;
+                            continue
+                        }
                     }
                 MAP ..= 0xbf =>
                     match self.map()? {
                         Some(0) => {}
-                        Some(n) =>
-                            if nrounds == 0 && irounds == 0 {
-                                stack.push(Some(n.saturating_mul(2)))
-                            } else {
-                                nrounds = nrounds.saturating_add(n.saturating_mul(2))
-                            }
-                        None =>
-                            if nrounds == 0 && irounds == 0 {
-                                stack.push(None)
-                            } else if nrounds < 2 {
-                                irounds = irounds.saturating_add(1)
-                            } else {
-                                for _ in 0 .. irounds {
-                                    stack.push(None)
-                                }
-                                stack.push(Some(nrounds - 1));
-                                stack.push(None);
-                                nrounds = 0;
-                                irounds = 0
-                            }
+                        n => {
+                            self.push_level(&mut stack, n.map(|n| n.saturating_mul(2)))?;
+                            continue
+                        }
                     }
                 TAGGED ..= 0xdb => {
+                    // A tag is followed by the tagged item, so we skip
+                    // the tag and move on without counting it as an item.
                     self.read().and_then(|n| self.unsigned(info_of(n)))?;
                     continue
                 }
@@ -459,30 +749,41 @@ impl<'b> Decoder<'b> {
                 }
                 BREAK => {
                     self.read()?;
-                    if nrounds == 0 && irounds == 0 {
-                        if let Some(None) = stack.last() {
-                            stack.pop();
+                    match stack.last() {
+                        Some(None) => { stack.pop(); }
+                        Some(Some(_)) => {
+                            return Err(Error::TypeMismatch(Type::Break, "unexpected break"))
                         }
-                    } else {
-                        irounds = irounds.saturating_sub(1)
+                        None => return Ok(())
                     }
                 }
                 other => return Err(Error::TypeMismatch(Type::read(other), "unknown type"))
             }
-            if nrounds == 0 && irounds == 0 {
-                while let Some(Some(0)) = stack.last() {
-                     stack.pop();
-                }
+            // We have skipped over a complete item. Account for it in the
+            // enclosing container and close every container which is done.
+            loop {
                 match stack.last_mut() {
-                    Some(Some(n)) => { *n -= 1 }
-                    Some(None)    => {}
-                    None          => break
+                    None => return Ok(()),
+                    Some(None) => break,
+                    Some(Some(n)) => {
+                        *n = n.saturating_sub(1);
+                        if *n > 0 {
+                            break
+                        }
+                        stack.pop();
+                    }
                 }
-            } else {
-                nrounds = nrounds.saturating_sub(1)
             }
         }
+    }
 
+    /// Enter a nested array or map while skipping, respecting the max. depth.
+    #[cfg(all(feature = "alloc", not(feature = "__test-partial-skip-support")))]
+    fn push_level(&self, stack: &mut alloc::vec::Vec<Option<u64>>, n: Option<u64>) -> Result<(), Error> {
+        if stack.len() >= self.max_depth {
+            return Err(Error::Message("maximum nesting depth exceeded"))
+        }
+        stack.push(n);
         Ok(())
     }
 
@@ -490,7 +791,8 @@ impl<'b> Decoder<'b> {
     ///
     /// **NB**: With feature-flag `"partial-skip-support"`, `Decoder::skip`
     /// does not support arrays or maps of indefinite-length inside of
-    /// regular maps or arrays.
+    /// regular maps or arrays. It uses constant stack space and does not
+    /// enforce the max. nesting depth (cf. [`Decoder::set_max_depth`]).
     #[cfg(
         any(
             all(not(feature = "alloc"), feature = "partial-skip-support"),
@@ -515,8 +817,8 @@ impl<'b> Decoder<'b> {
             match self.current()? {
                 UNSIGNED ..= 0x1b => { self.u64()?; }
                 SIGNED   ..= 0x3b => { self.i64()?; }
-                BYTES    ..= 0x5f => { for _ in self.bytes_iter()? {} }
-                TEXT     ..= 0x7f => { for _ in self.str_iter()? {} }
+                BYTES    ..= 0x5f => { for x in self.bytes_iter()? { x?; } }
+                TEXT     ..= 0x7f => { for x in self.str_iter()? { x?; } }
                 ARRAY    ..= 0x9f =>
                     if let Some(n) = self.array()? {
                         nrounds = nrounds.saturating_add(n)
@@ -552,6 +854,188 @@ impl<'b> Decoder<'b> {
         Ok(())
     }
 
+    /// Check that the next CBOR item is deterministically encoded.
+    ///
+    /// The item must satisfy the core requirements of [RFC 8949, section
+    /// 4.2.1][1], i.e. all arguments and floats are encoded in their shortest
+    /// form, all lengths are definite and map keys are unique and sorted in
+    /// bytewise lexicographic order of their encoding (cf. [`crate::cmp`]).
+    ///
+    /// On success the decoder moves past the item. Like [`Decoder::skip`],
+    /// the check does not recurse but tracks nested items on an explicit
+    /// stack whose size is bounded by the max. nesting depth (see
+    /// [`Decoder::set_max_depth`]). To check an item before decoding it, use
+    /// a [`Decoder::probe`]:
+    ///
+    /// ```
+    /// use minicbor::Decoder;
+    ///
+    /// let mut d = Decoder::new(&[0xa2, 0x01, 0x00, 0x02, 0x00]);
+    /// assert!(d.probe().check_deterministic().is_ok());
+    /// assert_eq!(Some(2), d.map()?);
+    ///
+    /// let mut d = Decoder::new(&[0xa2, 0x02, 0x00, 0x01, 0x00]);
+    /// assert!(d.probe().check_deterministic().is_err());
+    ///
+    /// let mut d = Decoder::new(&[0x18, 0x17]);
+    /// assert!(d.check_deterministic().is_err());
+    ///
+    /// # Ok::<_, minicbor::decode::Error>(())
+    /// ```
+    ///
+    /// *Requires feature* `"alloc"`.
+    ///
+    /// [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-4.2.1
+    #[cfg(feature = "alloc")]
+    pub fn check_deterministic(&mut self) -> Result<(), Error> {
+        // For every array, map or tag we are currently in, the stack contains
+        // the number of items left to check. Maps count keys and values
+        // separately and remember where the current key starts and the
+        // previous key, to check that keys are unique and sorted.
+        let mut stack: alloc::vec::Vec<Nested<'b>> = alloc::vec::Vec::new();
+
+        loop {
+            if stack.len() >= self.max_depth {
+                return Err(Error::Message("maximum nesting depth exceeded"))
+            }
+            if let Some(Nested { items, key: Some((start, _)) }) = stack.last_mut() {
+                if *items % 2 == 0 {
+                    *start = self.pos
+                }
+            }
+            let b = self.read()?;
+            if info_of(b) == 31 {
+                return Err(Error::Message("indefinite length or break in deterministic encoding"))
+            }
+            if type_of(b) == SIMPLE {
+                self.deterministic_simple(b)?
+            } else {
+                let n = self.unsigned(info_of(b))?;
+                let min = match info_of(b) {
+                    24 => 24,
+                    25 => 0x100,
+                    26 => 0x1_0000,
+                    27 => 0x1_0000_0000,
+                    _  => 0
+                };
+                if n < min {
+                    return Err(Error::Message("argument is not in its shortest form"))
+                }
+                let nested = match type_of(b) {
+                    BYTES => {
+                        self.read_slice(u64_to_usize(n)?)?;
+                        None
+                    }
+                    TEXT => {
+                        from_utf8(self.read_slice(u64_to_usize(n)?)?)?;
+                        None
+                    }
+                    ARRAY  => Some(Nested { items: n, key: None }),
+                    MAP    => Some(Nested { items: n.saturating_mul(2), key: Some((self.pos, None)) }),
+                    TAGGED => Some(Nested { items: 1, key: None }),
+                    _      => None
+                };
+                if let Some(x) = nested.filter(|x| x.items > 0) {
+                    stack.push(x);
+                    continue
+                }
+            }
+            // We have checked a complete item. Account for it in the
+            // enclosing container and close every container which is done.
+            loop {
+                match stack.last_mut() {
+                    None => return Ok(()),
+                    Some(Nested { items, key }) => {
+                        if let Some((start, prev)) = key {
+                            if *items % 2 == 0 {
+                                let k = self.buf.get(*start .. self.pos).unwrap_or_default();
+                                if prev.map(|p| p >= k).unwrap_or(false) {
+                                    return Err(Error::Message("map keys are not unique and sorted"))
+                                }
+                                *prev = Some(k)
+                            }
+                        }
+                        *items = items.saturating_sub(1);
+                        if *items > 0 {
+                            break
+                        }
+                        stack.pop();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Check that the next CBOR item follows the given [`Profile`].
+    ///
+    /// On success the decoder moves past the item. As with
+    /// [`Decoder::check_deterministic`], use a [`Decoder::probe`] to check an
+    /// item before decoding it:
+    ///
+    /// ```
+    /// use minicbor::Decoder;
+    /// use minicbor::profile::{CoreDeterministic, DCbor};
+    ///
+    /// let mut d = Decoder::new(&[0xf9, 0x40, 0x00]);
+    /// assert!(d.probe().check_profile(&CoreDeterministic).is_ok());
+    /// assert!(d.probe().check_profile(&DCbor).is_err());
+    /// assert_eq!(2.0, d.f32()?);
+    ///
+    /// # Ok::<_, minicbor::decode::Error>(())
+    /// ```
+    ///
+    /// *Requires feature* `"alloc"`.
+    ///
+    /// [`Profile`]: crate::profile::Profile
+    #[cfg(feature = "alloc")]
+    pub fn check_profile<P: crate::profile::Profile + ?Sized>(&mut self, p: &P) -> Result<(), Error> {
+        crate::profile::check(p, self)
+    }
+
+    /// Check that the simple value or float beginning with `b` is in its
+    /// shortest form.
+    #[cfg(feature = "alloc")]
+    fn deterministic_simple(&mut self, b: u8) -> Result<(), Error> {
+        match info_of(b) {
+            0 ..= 23 => Ok(()),
+            24 => if self.read()? < 32 {
+                Err(Error::Message("invalid simple value"))
+            } else {
+                Ok(())
+            }
+            25 => self.read_slice(2).map(|_| ()),
+            26 => {
+                let b = self.read_slice(4).map(read_u32)?;
+                let x = f32::from_bits(b);
+                let shorter = if x.is_nan() { b & 0x1fff == 0 } else { crate::ext::f16_bits(x).is_some() };
+                if shorter {
+                    return Err(Error::Message("float is not in its shortest form"))
+                }
+                Ok(())
+            }
+            27 => {
+                let b = self.read_slice(8).map(read_u64)?;
+                let x = f64::from_bits(b);
+                // Float casts are lossy by intention here and never panic.
+                #[allow(clippy::cast_possible_truncation)]
+                let shorter = if x.is_nan() { b & 0x1fff_ffff == 0 } else { f64::from(x as f32) == x };
+                if shorter {
+                    return Err(Error::Message("float is not in its shortest form"))
+                }
+                Ok(())
+            }
+            _ => Err(Error::TypeMismatch(Type::read(b), "unknown type"))
+        }
+    }
+
+    /// Check that `x` is finite if non-finite values are rejected.
+    fn check_finite<T: Copy + Into<f64>>(&self, x: T) -> Result<T, Error> {
+        if self.reject_non_finite && !x.into().is_finite() {
+            return Err(Error::Message("non-finite floating point value"))
+        }
+        Ok(x)
+    }
+
     /// Decode a `u64` value beginning with `b`.
     fn unsigned(&mut self, b: u8) -> Result<u64, Error> {
         match b {
@@ -574,21 +1058,59 @@ impl<'b> Decoder<'b> {
 
     /// Consume and return the byte at the current position.
     fn read(&mut self) -> Result<u8, Error> {
-        if let Some(b) = self.buf.get(self.pos) {
-            self.pos += 1;
-            return Ok(*b)
+        let b = self.current()?;
+        self.pos = self.pos.saturating_add(1);
+        Ok(b)
+    }
+
+    /// Decode the argument `n` of a negative integer with value `-1 - n`.
+    ///
+    /// Unlike [`Decoder::i64`] this covers the whole range of negative
+    /// integers.
+    pub(crate) fn negative(&mut self) -> Result<u64, Error> {
+        let b = self.read()?;
+        if SIGNED != type_of(b) {
+            return Err(Error::TypeMismatch(Type::read(b), "expected negative integer"))
         }
-        Err(Error::EndOfInput)
+        self.unsigned(info_of(b))
+    }
+
+    /// Decode the byte string content of a bignum.
+    ///
+    /// Leading zero bytes are accepted, definite and indefinite lengths are
+    /// supported.
+    fn bignum(&mut self) -> Result<u128, Error> {
+        let mut n = 0u128;
+        for b in self.bytes_iter()? {
+            for x in b? {
+                if n >> 120 != 0 {
+                    return Err(Error::Message("bignum exceeds 128 bits"))
+                }
+                n = n << 8 | u128::from(*x)
+            }
+        }
+        Ok(n)
     }
 
     /// Consume and return *n* bytes starting at the current position.
-    fn read_slice(&mut self, n: usize) -> Result<&'b [u8], Error> {
-        if let Some(b) = self.buf.get(self.pos .. self.pos + n) {
-            self.pos += n;
+    pub(crate) fn read_slice(&mut self, n: usize) -> Result<&'b [u8], Error> {
+        let end = self.pos.checked_add(n).ok_or(Error::EndOfInput)?;
+        if let Some(b) = self.buf.get(self.pos .. end) {
+            self.pos = end;
             return Ok(b)
         }
         Err(Error::EndOfInput)
     }
+
+    /// Interpret the given bytes as UTF-8, unless the input is trusted.
+    fn utf8(&self, b: &'b [u8]) -> Result<&'b str, Error> {
+        if self.trusted_utf8 {
+            // Safety: the caller of `set_trusted_utf8` guarantees that
+            // all text strings are valid UTF-8.
+            return Ok(unsafe { str::from_utf8_unchecked(b) })
+        }
+        from_utf8(b)
+    }
 }
 
 /// An iterator over byte slices.
@@ -641,7 +1163,7 @@ impl<'a, 'b> Iterator for StrIter<'a, 'b> {
             Some(0) => None,
             Some(n) => {
                 self.len = Some(0);
-                Some(self.decoder.read_slice(n).and_then(|d| str::from_utf8(d).map_err(Error::from)))
+                Some(self.decoder.read_slice(n).and_then(|d| self.decoder.utf8(d)))
             }
         }
     }
@@ -664,13 +1186,27 @@ impl<'a, 'b, T: Decode<'b>> Iterator for ArrayIter<'a, 'b, T> {
         match self.len {
             None => match self.decoder.current() {
                 Ok(BREAK) => self.decoder.read().map(|_| None).transpose(),
-                Ok(_)     => Some(T::decode(&mut self.decoder)),
+                Ok(_)     => Some(T::decode(self.decoder)),
                 Err(e)    => Some(Err(e))
             }
             Some(0) => None,
             Some(n) => {
-                self.len = Some(n - 1);
-                Some(T::decode(&mut self.decoder))
+                self.len = Some(n.saturating_sub(1));
+                Some(T::decode(self.decoder))
+            }
+        }
+    }
+
+    // The lower bound is capped by the number of remaining input bytes as
+    // every element requires at least one byte. Note that a decoded element
+    // may still be much larger than its encoding, so collections using the
+    // lower bound to pre-allocate capacity should limit it further.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.len {
+            None    => (0, None),
+            Some(n) => {
+                let n = usize::try_from(n).unwrap_or(usize::MAX);
+                (n.min(self.decoder.remaining().len()), Some(n))
             }
         }
     }
@@ -704,13 +1240,24 @@ where
         match self.len {
             None => match self.decoder.current() {
                 Ok(BREAK) => self.decoder.read().map(|_| None).transpose(),
-                Ok(_)  => Some(pair(&mut self.decoder)),
+                Ok(_)  => Some(pair(self.decoder)),
                 Err(e) => Some(Err(e))
             }
             Some(0) => None,
             Some(n) => {
-                self.len = Some(n - 1);
-                Some(pair(&mut self.decoder))
+                self.len = Some(n.saturating_sub(1));
+                Some(pair(self.decoder))
+            }
+        }
+    }
+
+    // Every entry requires at least two bytes (see `ArrayIter::size_hint`).
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.len {
+            None    => (0, None),
+            Some(n) => {
+                let n = usize::try_from(n).unwrap_or(usize::MAX);
+                (n.min(self.decoder.remaining().len() / 2), Some(n))
             }
         }
     }
@@ -775,7 +1322,31 @@ fn info_of(b: u8) -> u8 {
     b & 0b000_11111
 }
 
+/// An array, map or tag inside of which [`Decoder::check_deterministic`] is.
+#[cfg(feature = "alloc")]
+struct Nested<'b> {
+    /// The number of items left to check.
+    items: u64,
+    /// For maps, the start of the current key and the previous key.
+    key: Option<(usize, Option<&'b [u8]>)>
+}
+
+/// Validate UTF-8, with SIMD instructions if feature `"simdutf8"` is enabled.
+pub(crate) fn from_utf8(b: &[u8]) -> Result<&str, Error> {
+    #[cfg(feature = "simdutf8")]
+    if let Ok(s) = simdutf8::basic::from_utf8(b) {
+        return Ok(s)
+    }
+    // The error of `simdutf8::basic` has no details, so invalid input is
+    // validated again to report where it fails.
+    str::from_utf8(b).map_err(Error::from)
+}
+
+/// Convert a CBOR length to `usize`.
+///
+/// Lengths which exceed the address space of the target produce an error
+/// instead of being truncated.
 fn u64_to_usize(n: u64) -> Result<usize, Error> {
-    n.try_into().map_err(|_| Error::Overflow(n, "u64->usize"))
+    n.try_into().map_err(|_| Error::Overflow(n, "length exceeds platform limits"))
 }
 
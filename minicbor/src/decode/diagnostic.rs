@@ -0,0 +1,278 @@
+//! Display of CBOR items in diagnostic notation.
+
+use core::fmt::{self, Write};
+use crate::data::{Tag, Type};
+use crate::decode::{Decoder, Error};
+use crate::structural::has_next;
+
+/// The base64 alphabet.
+pub(crate) const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// The base64url alphabet.
+pub(crate) const BASE64_URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Displays the CBOR items of a decoder in diagnostic notation.
+///
+/// Items are rendered recursively, i.e. without allocating, but with a
+/// nesting depth bounded by the max. depth of the decoder. Multiple items
+/// (a CBOR sequence) are separated by commas. Byte strings are shown in
+/// base16, unless nested in a tag 21 or 22, which selects base64url or
+/// base64 respectively.
+#[derive(Debug, Clone)]
+pub(crate) struct Diagnostic<'b> {
+    decoder: Decoder<'b>
+}
+
+impl<'b> Diagnostic<'b> {
+    pub(crate) fn new(decoder: Decoder<'b>) -> Self {
+        Diagnostic { decoder }
+    }
+}
+
+impl fmt::Display for Diagnostic<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut d = self.decoder.clone();
+        let mut first = true;
+        loop {
+            match d.datatype() {
+                Err(Error::EndOfInput) => return Ok(()),
+                _ if first => first = false,
+                _          => f.write_str(", ")?
+            }
+            match item(&mut d, f, Base::Base16, 0) {
+                Ok(())                  => {}
+                Err(Failure::Format(e)) => return Err(e),
+                Err(Failure::Decode(e)) => return write!(f, " !!! decoding error: {}", e)
+            }
+        }
+    }
+}
+
+/// Rendering errors.
+enum Failure {
+    Format(fmt::Error),
+    Decode(Error)
+}
+
+impl From<fmt::Error> for Failure {
+    fn from(e: fmt::Error) -> Self {
+        Failure::Format(e)
+    }
+}
+
+impl From<Error> for Failure {
+    fn from(e: Error) -> Self {
+        Failure::Decode(e)
+    }
+}
+
+/// The encoding of byte strings (cf. tags 21 to 23).
+#[derive(Debug, Clone, Copy)]
+enum Base {
+    Base16,
+    Base64Url,
+    Base64
+}
+
+/// Render the next item.
+fn item(d: &mut Decoder, f: &mut fmt::Formatter, base: Base, depth: usize) -> Result<(), Failure> {
+    if depth >= d.max_depth() {
+        return Err(Error::Message("maximum nesting depth exceeded").into())
+    }
+    match d.datatype()? {
+        Type::Bool      => write!(f, "{}", d.bool()?)?,
+        Type::Null      => { skip_byte(d); f.write_str("null")? }
+        Type::Undefined => { skip_byte(d); f.write_str("undefined")? }
+        Type::Simple    => write!(f, "simple({})", d.simple()?)?,
+        Type::U8 | Type::U16 | Type::U32 | Type::U64 => write!(f, "{}", d.u64()?)?,
+        Type::I8 | Type::I16 | Type::I32 | Type::I64 => write!(f, "-{}", u128::from(d.negative()?) + 1)?,
+        Type::F16 => {
+            let b = d.read_slice(3)?;
+            let h = b.iter().skip(1).fold(0, |h, x| h << 8 | u16::from(*x));
+            // Every `f16` value can be represented exactly as `f32`.
+            #[allow(clippy::cast_possible_truncation)]
+            let x = crate::ext::f16_to_f64(h) as f32;
+            float(f, x)?
+        }
+        Type::F32       => float(f, d.f32()?)?,
+        Type::F64       => float(f, d.f64()?)?,
+        Type::Bytes     => bytes(f, d.bytes()?, base)?,
+        Type::String    => text(f, d.str()?)?,
+        Type::BytesIndef | Type::StringIndef => {
+            let is_text = d.datatype()? == Type::StringIndef;
+            skip_byte(d);
+            if d.datatype()? == Type::Break {
+                skip_byte(d);
+                f.write_str(if is_text { "\"\"_" } else { "''_" })?;
+                return Ok(())
+            }
+            f.write_str("(_ ")?;
+            let mut first = true;
+            while !at_break(d)? {
+                if !first {
+                    f.write_str(", ")?
+                }
+                first = false;
+                if is_text {
+                    text(f, d.str()?)?
+                } else {
+                    bytes(f, d.bytes()?, base)?
+                }
+            }
+            f.write_char(')')?
+        }
+        Type::Array | Type::ArrayIndef => {
+            let len = d.array()?;
+            f.write_str(if len.is_some() { "[" } else { "[_ " })?;
+            let mut i = 0;
+            while has_next(d, len, i)? {
+                if i > 0 {
+                    f.write_str(", ")?
+                }
+                item(d, f, base, depth.saturating_add(1))?;
+                i = i.saturating_add(1)
+            }
+            f.write_char(']')?
+        }
+        Type::Map | Type::MapIndef => {
+            let len = d.map()?;
+            f.write_str(if len.is_some() { "{" } else { "{_ " })?;
+            let mut i = 0;
+            while has_next(d, len, i)? {
+                if i > 0 {
+                    f.write_str(", ")?
+                }
+                item(d, f, base, depth.saturating_add(1))?;
+                f.write_str(": ")?;
+                item(d, f, base, depth.saturating_add(1))?;
+                i = i.saturating_add(1)
+            }
+            f.write_char('}')?
+        }
+        Type::Tag => {
+            let t = d.tag()?;
+            write!(f, "{}(", t.numeric())?;
+            if f.alternate() {
+                if let Some(name) = t.name() {
+                    write!(f, "/ {} / ", name)?
+                }
+            }
+            let base = match t {
+                Tag::ToBase64Url => Base::Base64Url,
+                Tag::ToBase64    => Base::Base64,
+                Tag::ToBase16    => Base::Base16,
+                _                => base
+            };
+            item(d, f, base, depth.saturating_add(1))?;
+            f.write_char(')')?
+        }
+        t @ Type::Break      => return Err(Error::TypeMismatch(t, "unexpected break").into()),
+        t @ Type::Unknown(_) => return Err(Error::TypeMismatch(t, "unknown cbor type").into())
+    }
+    Ok(())
+}
+
+/// Render a floating point number.
+///
+/// Finite values are shown in scientific notation using the shortest
+/// representation which preserves the value at the precision it was
+/// encoded with.
+fn float<F>(f: &mut fmt::Formatter, x: F) -> fmt::Result
+where
+    F: Into<f64> + fmt::LowerExp + Copy
+{
+    let y: f64 = x.into();
+    if y.is_nan() {
+        f.write_str("NaN")
+    } else if y.is_infinite() {
+        f.write_str(if y > 0.0 { "Infinity" } else { "-Infinity" })
+    } else {
+        write!(f, "{:e}", x)
+    }
+}
+
+/// Render a byte string in base16 or base64.
+fn bytes(f: &mut fmt::Formatter, b: &[u8], base: Base) -> fmt::Result {
+    match base {
+        Base::Base16    => {}
+        Base::Base64Url => {
+            f.write_str("b64'")?;
+            base64(f, b, BASE64_URL, false)?;
+            return f.write_char('\'')
+        }
+        Base::Base64 => {
+            f.write_str("b64'")?;
+            base64(f, b, BASE64, true)?;
+            return f.write_char('\'')
+        }
+    }
+    f.write_str("h'")?;
+    for (i, x) in b.iter().enumerate() {
+        if i > 0 {
+            f.write_char(' ')?
+        }
+        write!(f, "{:02x}", x)?
+    }
+    f.write_char('\'')
+}
+
+/// Write bytes in base64 with the given alphabet and optional padding.
+//
+// Shifts and indices are bounded by the chunk size of 3 bytes and the mask
+// of 6 bits.
+#[allow(clippy::arithmetic_side_effects, clippy::indexing_slicing)]
+pub(crate) fn base64<W: Write>(f: &mut W, b: &[u8], alphabet: &[u8; 64], pad: bool) -> fmt::Result {
+    for chunk in b.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, x)| n | u32::from(*x) << (16 - 8 * i));
+        for i in 0 .. chunk.len() + 1 {
+            f.write_char(char::from(alphabet[(n >> (18 - 6 * i) & 0x3f) as usize]))?
+        }
+        if pad {
+            for _ in chunk.len() .. 3 {
+                f.write_char('=')?
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Render a text string with JSON escapes.
+fn text(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        let esc = match c {
+            '"'  => "\\\"",
+            '\\' => "\\\\",
+            '\n' => "\\n",
+            '\r' => "\\r",
+            '\t' => "\\t",
+            '\u{8}'  => "\\b",
+            '\u{c}'  => "\\f",
+            '\0' ..= '\u{1f}' => "",
+            _ => continue
+        };
+        f.write_str(&s[start .. i])?;
+        if esc.is_empty() {
+            write!(f, "\\u{:04x}", u32::from(c))?
+        } else {
+            f.write_str(esc)?
+        }
+        start = i.saturating_add(c.len_utf8())
+    }
+    f.write_str(&s[start ..])?;
+    f.write_char('"')
+}
+
+/// Consume the next byte if it is a break.
+fn at_break(d: &mut Decoder) -> Result<bool, Error> {
+    if d.datatype()? == Type::Break {
+        skip_byte(d);
+        return Ok(true)
+    }
+    Ok(false)
+}
+
+fn skip_byte(d: &mut Decoder) {
+    d.set_position(d.position().saturating_add(1))
+}
@@ -2,6 +2,9 @@ use core::{fmt, str};
 use crate::data::Type;
 
 /// Decoding errors.
+///
+/// All variants carry only `'static` strings and plain numeric data, so
+/// errors can be created, copied and reported without heap allocation.
 #[non_exhaustive]
 #[derive(Debug, Clone)]
 pub enum Error {
@@ -15,10 +18,18 @@ pub enum Error {
     Overflow(u64, &'static str),
     /// An unexpected type was encountered.
     TypeMismatch(Type, &'static str),
-    /// An unknown enum variant was encountered.
-    UnknownVariant(u32),
+    /// An unknown enum variant was encountered in the named type.
+    UnknownVariant(u32, &'static str),
+    /// An unknown field index was encountered in the named type.
+    UnknownIndex(u64, &'static str),
     /// A value was missing at the specified index.
     MissingValue(u32, &'static str),
+    /// Several values were missing.
+    MissingValues(MissingFields),
+    /// Bytes remain after the value, starting at the given offset.
+    TrailingBytes(usize),
+    /// A buffer is too small, the value requires the given number of bytes.
+    BufferTooSmall(usize),
     /// Generic error message.
     Message(&'static str)
 }
@@ -26,14 +37,38 @@ pub enum Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Error::EndOfInput         => f.write_str("end of input bytes"),
-            Error::InvalidChar(n)     => write!(f, "invalid char: {:#x?}", n),
-            Error::Utf8(e)            => write!(f, "invalid utf-8: {}", e),
-            Error::Overflow(n, m)     => write!(f, "{}: {} overflows target type", m, n),
-            Error::TypeMismatch(t, m) => write!(f, "unexpected type: {}, {}", t, m),
-            Error::UnknownVariant(n)  => write!(f, "unknown enum variant {}", n),
-            Error::MissingValue(n, s) => write!(f, "missing value at index {} for {}", n, s),
-            Error::Message(m)         => write!(f, "{}", m)
+            Error::EndOfInput           => f.write_str("end of input bytes"),
+            Error::InvalidChar(n)       => write!(f, "invalid char: {:#x?}", n),
+            Error::Utf8(e)              => write!(f, "invalid utf-8: {}", e),
+            Error::Overflow(n, m)       => write!(f, "{}: {} overflows target type", m, n),
+            Error::TypeMismatch(t, m)   => write!(f, "unexpected type: {}, {}", t, m),
+            Error::UnknownVariant(n, s) => write!(f, "unknown variant {} for {}", n, s),
+            Error::UnknownIndex(n, s)   => write!(f, "unknown index {} for {}", n, s),
+            Error::MissingValue(n, s)   => write!(f, "missing value at index {} for {}", n, s),
+            Error::MissingValues(m)     => write!(f, "missing values: {}", m),
+            Error::TrailingBytes(n)     => write!(f, "trailing bytes at offset {}", n),
+            Error::BufferTooSmall(n)    => write!(f, "buffer too small, {} bytes required", n),
+            Error::Message(m)           => write!(f, "{}", m)
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Error {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Error::EndOfInput           => defmt::write!(f, "end of input bytes"),
+            Error::InvalidChar(n)       => defmt::write!(f, "invalid char: {=u32:#x}", n),
+            Error::Utf8(e)              => defmt::write!(f, "invalid utf-8 after {=usize} valid bytes", e.valid_up_to()),
+            Error::Overflow(n, m)       => defmt::write!(f, "{=str}: {=u64} overflows target type", m, n),
+            Error::TypeMismatch(t, m)   => defmt::write!(f, "unexpected type: {}, {=str}", t, m),
+            Error::UnknownVariant(n, s) => defmt::write!(f, "unknown variant {=u32} for {=str}", n, s),
+            Error::UnknownIndex(n, s)   => defmt::write!(f, "unknown index {=u64} for {=str}", n, s),
+            Error::MissingValue(n, s)   => defmt::write!(f, "missing value at index {=u32} for {=str}", n, s),
+            Error::MissingValues(m)     => defmt::write!(f, "missing values: {}", m),
+            Error::TrailingBytes(n)     => defmt::write!(f, "trailing bytes at offset {=usize}", n),
+            Error::BufferTooSmall(n)    => defmt::write!(f, "buffer too small, {=usize} bytes required", n),
+            Error::Message(m)           => defmt::write!(f, "{=str}", m)
         }
     }
 }
@@ -42,13 +77,17 @@ impl fmt::Display for Error {
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            Error::Utf8(e) => Some(e),
+            Error::Utf8(e)              => Some(e),
             | Error::EndOfInput
             | Error::InvalidChar(_)
             | Error::Overflow(..)
             | Error::TypeMismatch(..)
-            | Error::UnknownVariant(_)
+            | Error::UnknownVariant(..)
+            | Error::UnknownIndex(..)
             | Error::MissingValue(..)
+            | Error::MissingValues(_)
+            | Error::TrailingBytes(_)
+            | Error::BufferTooSmall(_)
             | Error::Message(_)
             => None
         }
@@ -61,3 +100,76 @@ impl From<str::Utf8Error> for Error {
     }
 }
 
+
+/// The set of fields found missing when decoding a value.
+///
+/// It refers to a static table of `(index, name)` pairs of all fields which
+/// may be missing and records which of those were in fact absent. At most
+/// [`MissingFields::CAPACITY`] table entries can be tracked.
+#[derive(Debug, Clone, Copy)]
+pub struct MissingFields {
+    table: &'static [(u32, &'static str)],
+    mask: u64
+}
+
+impl MissingFields {
+    /// The max. number of table entries.
+    pub const CAPACITY: usize = 64;
+
+    /// Create an empty set of missing fields over the given table.
+    ///
+    /// Entries beyond [`MissingFields::CAPACITY`] are ignored.
+    pub const fn new(table: &'static [(u32, &'static str)]) -> Self {
+        MissingFields { table, mask: 0 }
+    }
+
+    /// Mark the table entry at position `i` as missing.
+    pub fn insert(&mut self, i: usize) {
+        if i < self.table.len() && i < Self::CAPACITY {
+            self.mask |= 1 << i
+        }
+    }
+
+    /// Is no field missing?
+    pub fn is_empty(&self) -> bool {
+        self.mask == 0
+    }
+
+    /// The number of missing fields.
+    pub fn len(&self) -> usize {
+        self.mask.count_ones() as usize
+    }
+
+    /// Iterate over the indices and names of all missing fields.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &'static str)> + '_ {
+        self.table.iter()
+            .take(Self::CAPACITY)
+            .enumerate()
+            .filter(move |(i, _)| self.mask & (1 << i) != 0)
+            .map(|(_, f)| *f)
+    }
+}
+
+impl fmt::Display for MissingFields {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (n, s)) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?
+            }
+            write!(f, "{} (index {})", s, n)?
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for MissingFields {
+    fn format(&self, f: defmt::Formatter) {
+        for (i, (n, s)) in self.iter().enumerate() {
+            if i > 0 {
+                defmt::write!(f, ", ")
+            }
+            defmt::write!(f, "{=str} (index {=u32})", s, n)
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use crate::data::Type;
+use crate::decode::{Decoder, Error};
+use alloc::vec::Vec;
+use core::ops::Range;
+use core::task::Poll;
+
+/// The input buffer of a push parser.
+///
+/// A `Feed` receives bytes of a CBOR array or map in arbitrary pieces and
+/// yields its elements as soon as they are complete. Only the bytes of the
+/// current, incomplete element are buffered, not the whole array or map.
+///
+/// This type is the runtime support of parsers generated with
+/// `#[cbor(parser)]` (see [`minicbor_derive`](https://docs.rs/minicbor-derive)),
+/// but can also be used directly:
+///
+/// ```
+/// use core::task::Poll;
+/// use minicbor::decode::Feed;
+///
+/// let cbor = minicbor::to_vec(("hello", 42u8))?;
+/// let mut f = Feed::array();
+/// let mut out = Vec::new();
+/// for b in &cbor {
+///     f.push(core::slice::from_ref(b));
+///     while let Poll::Ready(i) = f.poll_element() {
+///         match i? {
+///             Some(0) => out.push(f.value().str()?.to_string()),
+///             Some(_) => out.push(f.value().u8()?.to_string()),
+///             None    => break
+///         }
+///         f.consume()
+///     }
+/// }
+/// assert!(f.is_done());
+/// assert_eq!(["hello", "42"], out.as_slice());
+///
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Feed {
+    buffer: Vec<u8>,
+    pos: usize,
+    map: bool,
+    state: State
+}
+
+#[derive(Debug, Clone)]
+enum State {
+    /// Waiting for the array or map head.
+    Head,
+    /// Within the array or map.
+    Body {
+        /// The number of remaining elements (`None` if indefinite).
+        remaining: Option<u64>,
+        /// The array index of the next element.
+        index: u64,
+        /// The byte range of the current element's value, if complete.
+        current: Option<Range<usize>>
+    },
+    /// The array or map has been read completely.
+    Done
+}
+
+impl Feed {
+    /// Create a feed which expects a CBOR array whose elements are
+    /// identified by their position.
+    pub fn array() -> Self {
+        Feed { buffer: Vec::new(), pos: 0, map: false, state: State::Head }
+    }
+
+    /// Create a feed which expects a CBOR map whose entries are identified
+    /// by their `u32` keys.
+    pub fn map() -> Self {
+        Feed { buffer: Vec::new(), pos: 0, map: true, state: State::Head }
+    }
+
+    /// Append bytes to the buffer.
+    pub fn push(&mut self, bytes: &[u8]) {
+        if self.pos > 0 && self.current().is_none() {
+            self.buffer.drain(.. self.pos);
+            self.pos = 0
+        }
+        self.buffer.extend_from_slice(bytes)
+    }
+
+    /// Advance to the next complete element.
+    ///
+    /// Returns `Poll::Pending` if more bytes are required, the index of the
+    /// element if its value is complete (cf. [`Feed::value`]) or `None` if
+    /// the end of the array or map has been reached.
+    ///
+    /// Every element needs to be consumed with [`Feed::consume`] before
+    /// advancing to the next one.
+    pub fn poll_element(&mut self) -> Poll<Result<Option<u64>, Error>> {
+        loop {
+            let mut d = Decoder::new(self.buffer.get(self.pos ..).unwrap_or_default());
+            match &mut self.state {
+                State::Head => {
+                    let len = if self.map { d.map() } else { d.array() };
+                    match len {
+                        Ok(n) => {
+                            self.pos = self.pos.saturating_add(d.position());
+                            self.state = State::Body { remaining: n, index: 0, current: None }
+                        }
+                        Err(Error::EndOfInput) => return Poll::Pending,
+                        Err(e) => return Poll::Ready(Err(e))
+                    }
+                }
+                State::Body { current: Some(_), .. } => {
+                    return Poll::Ready(Err(Error::Message("current element has not been consumed")))
+                }
+                State::Body { remaining: Some(0), .. } => {
+                    self.state = State::Done
+                }
+                State::Body { remaining, index, current } => {
+                    if remaining.is_none() {
+                        match d.datatype() {
+                            Ok(Type::Break) => {
+                                self.pos = self.pos.saturating_add(1);
+                                self.state = State::Done;
+                                continue
+                            }
+                            Ok(_) => {}
+                            Err(Error::EndOfInput) => return Poll::Pending,
+                            Err(e) => return Poll::Ready(Err(e))
+                        }
+                    }
+                    let i = if self.map {
+                        match d.u32() {
+                            Ok(k) => u64::from(k),
+                            Err(Error::EndOfInput) => return Poll::Pending,
+                            Err(e) => return Poll::Ready(Err(e))
+                        }
+                    } else {
+                        *index
+                    };
+                    let start = d.position();
+                    match d.skip() {
+                        Ok(()) => {}
+                        Err(Error::EndOfInput) => return Poll::Pending,
+                        Err(e) => return Poll::Ready(Err(e))
+                    }
+                    let end = d.position();
+                    *current = Some(self.pos.saturating_add(start) .. self.pos.saturating_add(end));
+                    *index = index.saturating_add(1);
+                    if let Some(n) = remaining {
+                        *n = n.saturating_sub(1)
+                    }
+                    return Poll::Ready(Ok(Some(i)))
+                }
+                State::Done => return Poll::Ready(Ok(None))
+            }
+        }
+    }
+
+    /// Get a decoder of the current element's value.
+    ///
+    /// The decoder is limited to the bytes of the value. If there is no
+    /// current element, the decoder is empty.
+    pub fn value(&self) -> Decoder<'_> {
+        match self.current() {
+            Some(r) => Decoder::new(self.buffer.get(r).unwrap_or_default()),
+            None    => Decoder::new(&[])
+        }
+    }
+
+    /// Consume the current element, regardless of how much of its value
+    /// has been decoded.
+    pub fn consume(&mut self) {
+        if let State::Body { current, .. } = &mut self.state {
+            if let Some(r) = current.take() {
+                self.pos = r.end
+            }
+        }
+    }
+
+    /// Has the array or map been read completely?
+    pub fn is_done(&self) -> bool {
+        matches!(self.state, State::Done)
+    }
+
+    /// Prepare for the next array or map.
+    ///
+    /// Bytes which have been pushed but not consumed yet, e.g. the beginning
+    /// of the next value, are retained.
+    pub fn reset(&mut self) {
+        self.consume();
+        self.buffer.drain(.. self.pos);
+        self.pos = 0;
+        self.state = State::Head
+    }
+
+    /// Get the bytes which have been pushed but not consumed yet.
+    pub fn remaining(&self) -> &[u8] {
+        self.buffer.get(self.pos ..).unwrap_or_default()
+    }
+
+    fn current(&self) -> Option<Range<usize>> {
+        if let State::Body { current, .. } = &self.state {
+            current.clone()
+        } else {
+            None
+        }
+    }
+}
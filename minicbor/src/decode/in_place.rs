@@ -0,0 +1,273 @@
+use crate::data::Type;
+use crate::decode::{Decode, Decoder, Error};
+use crate::structural::has_next;
+
+/// A type that can be decoded from CBOR into an existing value.
+///
+/// Decoding in place allows long-running consumers to reuse the
+/// allocations of a value, e.g. the capacity of a `Vec` or `String`,
+/// instead of allocating a fresh value for every message. The provided
+/// method simply replaces the value with a newly decoded one, so types
+/// without allocations to reuse can implement this trait with an empty
+/// `impl` block. It can also be derived for structs (see
+/// [`minicbor_derive`](https://docs.rs/minicbor-derive)).
+///
+/// If decoding fails, the value may have been partially updated.
+///
+/// ```
+/// use minicbor::decode::DecodeInPlace;
+/// use minicbor::Decoder;
+///
+/// let mut v: Vec<String> = Vec::new();
+/// for msg in [vec!["a", "b"], vec!["c"]] {
+///     let cbor = minicbor::to_vec(&msg)?;
+///     v.decode_into(&mut Decoder::new(&cbor))?;
+///     assert_eq!(msg, v);
+/// }
+///
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub trait DecodeInPlace<'b>: Decode<'b> {
+    /// Decode a value into `self` using the given `Decoder`.
+    fn decode_into(&mut self, d: &mut Decoder<'b>) -> Result<(), Error> {
+        *self = Self::decode(d)?;
+        Ok(())
+    }
+}
+
+impl<'b, T: DecodeInPlace<'b>> DecodeInPlace<'b> for Option<T> {
+    fn decode_into(&mut self, d: &mut Decoder<'b>) -> Result<(), Error> {
+        if Type::Null == d.datatype()? {
+            d.limited_skip()?;
+            *self = None;
+            return Ok(())
+        }
+        match self {
+            Some(x) => x.decode_into(d),
+            None    => {
+                *self = Some(T::decode(d)?);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'b, T: DecodeInPlace<'b>> DecodeInPlace<'b> for alloc::boxed::Box<T> {
+    fn decode_into(&mut self, d: &mut Decoder<'b>) -> Result<(), Error> {
+        (**self).decode_into(d)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'b> DecodeInPlace<'b> for alloc::string::String {
+    fn decode_into(&mut self, d: &mut Decoder<'b>) -> Result<(), Error> {
+        self.clear();
+        d.push_str(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'b> DecodeInPlace<'b> for crate::bytes::ByteVec {
+    fn decode_into(&mut self, d: &mut Decoder<'b>) -> Result<(), Error> {
+        let b = d.bytes()?;
+        self.clear();
+        self.extend_from_slice(b);
+        Ok(())
+    }
+}
+
+/// Existing elements are decoded in place, additional ones are appended
+/// and superfluous ones are removed.
+#[cfg(feature = "alloc")]
+impl<'b, T: DecodeInPlace<'b>> DecodeInPlace<'b> for alloc::vec::Vec<T> {
+    fn decode_into(&mut self, d: &mut Decoder<'b>) -> Result<(), Error> {
+        let len = d.array()?;
+        let mut i = 0;
+        while has_next(d, len, i as u64)? {
+            if let Some(x) = self.get_mut(i) {
+                x.decode_into(d)?
+            } else {
+                self.push(T::decode(d)?)
+            }
+            i = i.saturating_add(1)
+        }
+        self.truncate(i);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+macro_rules! decode_in_place_sequential {
+    ($($t:ty, $push:ident $(, $bound:path)?)*) => {
+        $(
+            impl<'b, T: Decode<'b> $(+ $bound)?> DecodeInPlace<'b> for $t {
+                fn decode_into(&mut self, d: &mut Decoder<'b>) -> Result<(), Error> {
+                    self.clear();
+                    for x in d.array_iter()? {
+                        self.$push(x?);
+                    }
+                    Ok(())
+                }
+            }
+        )*
+    }
+}
+
+#[cfg(feature = "alloc")]
+decode_in_place_sequential! {
+    alloc::collections::VecDeque<T>, push_back
+    alloc::collections::LinkedList<T>, push_back
+    alloc::collections::BinaryHeap<T>, push, Ord
+    alloc::collections::BTreeSet<T>, insert, Ord
+}
+
+#[cfg(feature = "std")]
+impl<'b, T> DecodeInPlace<'b> for std::collections::HashSet<T>
+where
+    T: Decode<'b> + Eq + std::hash::Hash
+{
+    fn decode_into(&mut self, d: &mut Decoder<'b>) -> Result<(), Error> {
+        self.clear();
+        for x in d.array_iter()? {
+            self.insert(x?);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'b, K, V> DecodeInPlace<'b> for std::collections::HashMap<K, V>
+where
+    K: Decode<'b> + Eq + std::hash::Hash,
+    V: Decode<'b>
+{
+    fn decode_into(&mut self, d: &mut Decoder<'b>) -> Result<(), Error> {
+        self.clear();
+        for x in d.map_iter()? {
+            let (k, v) = x?;
+            self.insert(k, v);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'b, K, V> DecodeInPlace<'b> for alloc::collections::BTreeMap<K, V>
+where
+    K: Decode<'b> + Eq + Ord,
+    V: Decode<'b>
+{
+    fn decode_into(&mut self, d: &mut Decoder<'b>) -> Result<(), Error> {
+        self.clear();
+        for x in d.map_iter()? {
+            let (k, v) = x?;
+            self.insert(k, v);
+        }
+        Ok(())
+    }
+}
+
+macro_rules! decode_in_place_replace {
+    ($($t:ty),*) => {
+        $(impl<'b> DecodeInPlace<'b> for $t {})*
+    }
+}
+
+decode_in_place_replace! {
+    u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize, bool, f32, f64, char, (),
+    core::num::NonZeroU8, core::num::NonZeroU16, core::num::NonZeroU32, core::num::NonZeroU64,
+    core::num::NonZeroI8, core::num::NonZeroI16, core::num::NonZeroI32, core::num::NonZeroI64,
+    core::num::NonZeroU128, core::num::NonZeroI128, core::num::NonZeroUsize, core::num::NonZeroIsize,
+    core::time::Duration,
+    core::net::IpAddr, core::net::Ipv4Addr, core::net::Ipv6Addr,
+    core::net::SocketAddr, core::net::SocketAddrV4, core::net::SocketAddrV6
+}
+
+impl<'a, 'b: 'a> DecodeInPlace<'b> for &'a str {}
+
+impl<'a, 'b: 'a> DecodeInPlace<'b> for &'a crate::bytes::ByteSlice {}
+
+impl<'b, const N: usize> DecodeInPlace<'b> for crate::bytes::ByteArray<N> {}
+
+impl<'b, T> DecodeInPlace<'b> for core::marker::PhantomData<T> {}
+
+impl<'b, T: Decode<'b>, E: Decode<'b>> DecodeInPlace<'b> for Result<T, E> {}
+
+macro_rules! decode_in_place_arrays {
+    ($($n:expr)*) => {
+        $(
+            impl<'b, T: DecodeInPlace<'b> + Default> DecodeInPlace<'b> for [T; $n] {
+                fn decode_into(&mut self, d: &mut Decoder<'b>) -> Result<(), Error> {
+                    let len = d.array()?;
+                    let mut i = 0;
+                    while has_next(d, len, i as u64)? {
+                        if let Some(x) = self.get_mut(i) {
+                            x.decode_into(d)?
+                        } else {
+                            let msg = concat!("array has more than ", $n, " elements");
+                            return Err(Error::Message(msg))
+                        }
+                        i = i.saturating_add(1)
+                    }
+                    if i < self.len() {
+                        let msg = concat!("array has less than ", $n, " elements");
+                        return Err(Error::Message(msg))
+                    }
+                    Ok(())
+                }
+            }
+        )*
+    }
+}
+
+decode_in_place_arrays!(0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16);
+
+#[cfg(feature = "alloc")]
+impl<'b, T> DecodeInPlace<'b> for alloc::borrow::Cow<'_, T>
+where
+    T: alloc::borrow::ToOwned + ?Sized,
+    T::Owned: Decode<'b>
+{}
+
+#[cfg(feature = "alloc")]
+impl<'b> DecodeInPlace<'b> for alloc::boxed::Box<str> {}
+
+#[cfg(feature = "std")]
+impl<'b> DecodeInPlace<'b> for std::path::PathBuf {}
+
+#[cfg(feature = "alloc")]
+impl<'b, T: Decode<'b>> DecodeInPlace<'b> for alloc::boxed::Box<[T]> {}
+
+#[cfg(feature = "alloc")]
+impl<'b, T: Decode<'b>> DecodeInPlace<'b> for alloc::rc::Rc<T> {}
+
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl<'b, T: Decode<'b>> DecodeInPlace<'b> for alloc::sync::Arc<T> {}
+
+impl<'b, T: Decode<'b>> DecodeInPlace<'b> for core::cell::RefCell<T> {}
+
+macro_rules! decode_in_place_tuples {
+    ($( ($($t:ident)+) )+) => {
+        $(impl<'b, $($t: Decode<'b>),+> DecodeInPlace<'b> for ($($t,)+) {})+
+    }
+}
+
+decode_in_place_tuples! {
+    (A)
+    (A B)
+    (A B C)
+    (A B C D)
+    (A B C D E)
+    (A B C D E F)
+    (A B C D E F G)
+    (A B C D E F G H)
+    (A B C D E F G H I)
+    (A B C D E F G H I J)
+    (A B C D E F G H I J K)
+    (A B C D E F G H I J K L)
+    (A B C D E F G H I J K L M)
+    (A B C D E F G H I J K L M N)
+    (A B C D E F G H I J K L M N O)
+    (A B C D E F G H I J K L M N O P)
+}
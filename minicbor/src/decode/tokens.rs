@@ -1,5 +1,6 @@
 //! Generic CBOR tokenization.
 
+use core::convert::TryFrom;
 use core::fmt;
 use crate::Decoder;
 use crate::data::{Tag, Type};
@@ -87,9 +88,9 @@ impl<'b> Tokenizer<'b> {
             Type::U16          => self.decoder.u16().map(Token::U16),
             Type::U32          => self.decoder.u32().map(Token::U32),
             Type::U64          => self.decoder.u64().map(Token::U64),
-            Type::I8           => self.decoder.i8().map(Token::I8),
-            Type::I16          => self.decoder.i16().map(Token::I16),
-            Type::I32          => self.decoder.i32().map(Token::I32),
+            Type::I8           => self.decoder.i16().map(|n| i8::try_from(n).map_or(Token::I16(n), Token::I8)),
+            Type::I16          => self.decoder.i32().map(|n| i16::try_from(n).map_or(Token::I32(n), Token::I16)),
+            Type::I32          => self.decoder.i64().map(|n| i32::try_from(n).map_or(Token::I64(n), Token::I32)),
             Type::I64          => self.decoder.i64().map(Token::I64),
             Type::F16          => self.decoder.f16().map(Token::F16),
             Type::F32          => self.decoder.f32().map(Token::F32),
@@ -122,150 +123,16 @@ impl<'b> Tokenizer<'b> {
     }
 
     fn skip_byte(&mut self) {
-        self.decoder.set_position(self.decoder.position() + 1)
+        self.decoder.set_position(self.decoder.position().saturating_add(1))
     }
 }
 
-#[cfg(feature = "alloc")]
+/// Display the remaining tokens in diagnostic notation.
+///
+/// See [`display`](crate::display) for details.
 impl fmt::Display for Tokenizer<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        /// Control stack element.
-        enum E {
-            N,               // get next token
-            T,               // tag
-            A(Option<u64>),  // array
-            M(Option<u64>),  // map
-            B,               // indefinite bytes
-            D,               // indefinite text
-            S(&'static str), // display string
-            X(&'static str)  // display string (unless next token is BREAK)
-        }
-
-        let mut iter = Tokenizer::from(self.decoder.clone()).peekable();
-        let mut stack = alloc::vec::Vec::new();
-
-        while iter.peek().is_some() {
-            stack.push(E::N);
-            while let Some(elt) = stack.pop() {
-                match elt {
-                    E::N => match iter.next() {
-                        Some(Ok(Token::Array(n))) => {
-                            stack.push(E::A(Some(n)));
-                            f.write_str("[")?
-                        }
-                        Some(Ok(Token::Map(n))) => {
-                            stack.push(E::M(Some(n)));
-                            f.write_str("{")?
-                        }
-                        Some(Ok(Token::BeginArray)) => {
-                            stack.push(E::A(None));
-                            f.write_str("[_ ")?
-                        }
-                        Some(Ok(Token::BeginMap)) => {
-                            stack.push(E::M(None));
-                            f.write_str("{_ ")?
-                        }
-                        Some(Ok(Token::BeginBytes)) => if let Some(Ok(Token::Break)) = iter.peek() {
-                            iter.next();
-                            f.write_str("''_")?
-                        } else {
-                            stack.push(E::B);
-                            f.write_str("(_ ")?
-                        }
-                        Some(Ok(Token::BeginString)) => if let Some(Ok(Token::Break)) = iter.peek() {
-                            iter.next();
-                            f.write_str("\"\"_")?
-                        } else {
-                            stack.push(E::D);
-                            f.write_str("(_ ")?
-                        }
-                        Some(Ok(Token::Tag(t))) => {
-                            stack.push(E::T);
-                            write!(f, "{}(", t.numeric())?
-                        }
-                        Some(Ok(t))  => t.fmt(f)?,
-                        Some(Err(e)) => {
-                            write!(f, " !!! decoding error: {}", e)?;
-                            return Ok(())
-                        }
-                        None => continue
-                    }
-                    E::S(s) => f.write_str(s)?,
-                    E::X(s) => match iter.peek() {
-                        Some(Ok(Token::Break)) | None => continue,
-                        Some(Ok(_))  => f.write_str(s)?,
-                        Some(Err(e)) => {
-                            write!(f, " !!! decoding error: {}", e)?;
-                            return Ok(())
-                        }
-                    }
-                    E::T => {
-                        stack.push(E::S(")"));
-                        stack.push(E::N)
-                    }
-                    E::A(Some(0)) => f.write_str("]")?,
-                    E::A(Some(1)) => {
-                        stack.push(E::A(Some(0)));
-                        stack.push(E::N)
-                    }
-                    E::A(Some(n)) => {
-                        stack.push(E::A(Some(n - 1)));
-                        stack.push(E::S(", "));
-                        stack.push(E::N)
-                    }
-                    E::A(None) => if let Some(Ok(Token::Break)) = iter.peek() {
-                        iter.next();
-                        f.write_str("]")?
-                    } else {
-                        stack.push(E::A(None));
-                        stack.push(E::X(", "));
-                        stack.push(E::N)
-                    }
-                    E::M(Some(0)) => f.write_str("}")?,
-                    E::M(Some(1)) => {
-                        stack.push(E::M(Some(0)));
-                        stack.push(E::N);
-                        stack.push(E::S(": "));
-                        stack.push(E::N)
-                    }
-                    E::M(Some(n)) => {
-                        stack.push(E::M(Some(n - 1)));
-                        stack.push(E::S(", "));
-                        stack.push(E::N);
-                        stack.push(E::S(": "));
-                        stack.push(E::N)
-                    }
-                    E::M(None) => if let Some(Ok(Token::Break)) = iter.peek() {
-                        iter.next();
-                        f.write_str("}")?
-                    } else {
-                        stack.push(E::M(None));
-                        stack.push(E::X(", "));
-                        stack.push(E::N);
-                        stack.push(E::S(": "));
-                        stack.push(E::N)
-                    }
-                    E::B => if let Some(Ok(Token::Break)) = iter.peek() {
-                        iter.next();
-                        f.write_str(")")?
-                    } else {
-                        stack.push(E::B);
-                        stack.push(E::X(", "));
-                        stack.push(E::N)
-                    }
-                    E::D => if let Some(Ok(Token::Break)) = iter.peek() {
-                        iter.next();
-                        f.write_str(")")?
-                    } else {
-                        stack.push(E::D);
-                        stack.push(E::X(", "));
-                        stack.push(E::N)
-                    }
-                }
-            }
-        }
-
-        Ok(())
+        crate::decode::Diagnostic::new(self.decoder.clone()).fmt(f)
     }
 }
 
@@ -287,13 +154,12 @@ impl fmt::Display for Tokenizer<'_> {
 /// - Tags are displayed with `T(t)` where `t` is the tag number.
 /// - Simple values are displayed as `simple(n)` where `n` denotes the numeric
 ///   value.
-/// - Indefinite items start with:
+/// - Indefinite items end with `]` when a `Token::Break` is encountered. All
+///   tokens in between belong to the indefinite container. They start with:
 ///     * `?B[` for byte strings,
 ///     * `?S[` for text strings,
 ///     * `?A[` for arrays,
-///     * `?M[` for maps,
-///   and end with `]` when a `Token::Break` is encountered. All tokens
-///   in between belong to the indefinite container.
+///     * `?M[` for maps.
 /// - `Token::Null` is displayed as `null` and `Token::Undefined` as `undefined`.
 impl fmt::Display for Token<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -324,14 +190,11 @@ impl fmt::Display for Token<'_> {
             Token::BeginMap    => f.write_str("?M["),
             Token::Bytes(b)    => {
                 f.write_str("h'")?;
-                let mut i = b.len();
-                for x in *b {
-                    if i > 1 {
-                        write!(f, "{:02x} ", x)?
-                    } else {
-                        write!(f, "{:02x}", x)?
+                for (i, x) in b.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" ")?
                     }
-                    i -= 1;
+                    write!(f, "{:02x}", x)?
                 }
                 f.write_str("'")
             }
@@ -0,0 +1,709 @@
+//! Parsing of CBOR diagnostic notation.
+//!
+//! [`from_edn`] parses text in the extended diagnostic notation (EDN) of
+//! [RFC 8949, section 8][1] and [RFC 8610, appendix G][2] and produces the
+//! encoded CBOR items. It is the inverse of [`display`](crate::display).
+//! The following syntax is supported:
+//!
+//! - Integers in decimal, hexadecimal (`0x`), octal (`0o`) and binary (`0b`)
+//!   notation. Integers outside of the range of major types 0 and 1 become
+//!   bignums (tags 2 and 3).
+//! - Floats in decimal and scientific notation, as well as `Infinity`,
+//!   `-Infinity` and `NaN`. Floats are encoded in the shortest form which
+//!   preserves their value.
+//! - Encoding indicators `_0` to `_3` after integers, tag numbers and floats
+//!   which select the size of the argument, e.g. `1_1` is encoded as
+//!   `0x19 0x00 0x01` and `1.5_3` as an `f64`.
+//! - `false`, `true`, `null`, `undefined` and `simple(n)`.
+//! - Text strings in double quotes with JSON escapes.
+//! - Byte strings as UTF-8 text in single quotes (`'abc'`), in base16
+//!   (`h'...'`), base32 (`b32'...'`), base32hex (`h32'...'`) and base64 or
+//!   base64url (`b64'...'`). Encoded strings may contain whitespace.
+//! - Adjacent strings are concatenated. The result is a text string if the
+//!   first part is one and a byte string otherwise.
+//! - Embedded CBOR sequences in `<<` and `>>`, which become byte strings.
+//! - Arrays `[...]`, maps `{k: v, ...}`, indefinite-length arrays `[_ ...]`
+//!   and maps `{_ ...}`, and indefinite-length strings `(_ ...)`, `''_`
+//!   and `""_`.
+//! - Tags in the form `n(item)`.
+//! - Comments in slashes (`/ ... /`) and from `#` to the end of the line.
+//!
+//! Multiple items separated by commas form a CBOR sequence. Arrays, maps,
+//! tags and embedded CBOR may be nested up to [`MAX_DEPTH`] levels deep.
+//!
+//! *Requires feature* `"edn"`.
+//!
+//! ```
+//! use minicbor::edn::{from_edn, value_from_edn};
+//! use minicbor::value::Value;
+//!
+//! let cbor = from_edn(r#"{1: "a", 2: [true, h'DEADBEEF', <<1, 2>>], 3: 1(1.5_3)}"#)?;
+//! assert_eq!(r#"{1: "a", 2: [true, h'de ad be ef', h'01 02'], 3: 1(1.5e0)}"#, minicbor::display(&cbor).to_string());
+//!
+//! let value = value_from_edn("[1, -2, 'xyz']")?;
+//! assert_eq!(Value::array(vec![Value::from(1), Value::from(-2), Value::bytes(b"xyz")]), value);
+//!
+//! # Ok::<_, minicbor::edn::Error>(())
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-8
+//! [2]: https://www.rfc-editor.org/rfc/rfc8610.html#appendix-G
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use crate::decode::{self, Decoder};
+use crate::encode::{self, Encoder};
+use crate::value::Value;
+use crate::{UNSIGNED, SIGNED, BYTES, TEXT, ARRAY, MAP, TAGGED, SIMPLE, BREAK};
+
+/// The max. nesting depth of arrays, maps, tags and embedded CBOR.
+pub const MAX_DEPTH: usize = 256;
+
+/// Parse errors.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// Decoding the parsed CBOR failed.
+    Decode(decode::Error),
+    /// Encoding the parsed CBOR failed.
+    Encode(&'static str),
+    /// The EDN input is invalid at the given byte offset.
+    Syntax(usize, &'static str)
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Decode(e)    => write!(f, "decode error: {}", e),
+            Error::Encode(m)    => write!(f, "encode error: {}", m),
+            Error::Syntax(n, m) => write!(f, "invalid edn at offset {}: {}", n, m)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Decode(e) => Some(e),
+            _                => None
+        }
+    }
+}
+
+impl From<decode::Error> for Error {
+    fn from(e: decode::Error) -> Self {
+        Error::Decode(e)
+    }
+}
+
+impl<E> From<encode::Error<E>> for Error {
+    fn from(e: encode::Error<E>) -> Self {
+        match e {
+            encode::Error::Message(m) => Error::Encode(m),
+            _                         => Error::Encode("failed to write cbor")
+        }
+    }
+}
+
+/// Parse a sequence of CBOR items in diagnostic notation.
+pub fn from_edn(edn: &str) -> Result<Vec<u8>, Error> {
+    let mut p = Parser { input: edn.as_bytes(), pos: 0 };
+    let mut v = Vec::new();
+    p.space()?;
+    if p.pos < p.input.len() {
+        p.sequence(&mut v, None, 0)?;
+    }
+    Ok(v)
+}
+
+/// Parse a single CBOR item in diagnostic notation into a [`Value`].
+pub fn value_from_edn(edn: &str) -> Result<Value, Error> {
+    let cbor = from_edn(edn)?;
+    let mut d = Decoder::new(&cbor);
+    let v = d.decode()?;
+    if d.position() < cbor.len() {
+        return Err(decode::Error::TrailingBytes(d.position()).into())
+    }
+    Ok(v)
+}
+
+/// An EDN parser producing CBOR.
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize
+}
+
+impl<'a> Parser<'a> {
+    /// Parse comma-separated items until `end` (or the end of input).
+    ///
+    /// Returns the number of items.
+    fn sequence(&mut self, out: &mut Vec<u8>, end: Option<&[u8]>, depth: usize) -> Result<u64, Error> {
+        let mut n = 0;
+        loop {
+            self.space()?;
+            match end {
+                Some(e) if self.input[self.pos ..].starts_with(e) => {
+                    self.pos += e.len();
+                    return Ok(n)
+                }
+                None if self.pos == self.input.len() => return Ok(n),
+                _ => {}
+            }
+            if n > 0 {
+                self.expect(b',')?;
+                self.space()?;
+                // Allow a trailing comma before a closing bracket.
+                if let Some(e) = end {
+                    if self.input[self.pos ..].starts_with(e) {
+                        continue
+                    }
+                }
+            }
+            self.item(out, depth)?;
+            n += 1
+        }
+    }
+
+    fn item(&mut self, out: &mut Vec<u8>, depth: usize) -> Result<(), Error> {
+        if depth >= MAX_DEPTH {
+            return Err(Error::Syntax(self.pos, "maximum nesting depth exceeded"))
+        }
+        self.space()?;
+        match self.peek()? {
+            b'[' => {
+                self.pos += 1;
+                if self.eat(b'_') {
+                    out.push(ARRAY | 31);
+                    self.sequence(out, Some(b"]"), depth + 1)?;
+                    out.push(BREAK)
+                } else {
+                    let mut items = Vec::new();
+                    let n = self.sequence(&mut items, Some(b"]"), depth + 1)?;
+                    Encoder::new(&mut *out).array(n)?;
+                    out.extend_from_slice(&items)
+                }
+            }
+            b'{' => {
+                self.pos += 1;
+                if self.eat(b'_') {
+                    out.push(MAP | 31);
+                    self.entries(out, depth + 1)?;
+                    out.push(BREAK)
+                } else {
+                    let mut items = Vec::new();
+                    let n = self.entries(&mut items, depth + 1)?;
+                    Encoder::new(&mut *out).map(n)?;
+                    out.extend_from_slice(&items)
+                }
+            }
+            b'(' => {
+                self.pos += 1;
+                self.expect(b'_')?;
+                self.chunks(out)?
+            }
+            b'<' => {
+                self.literal("<<")?;
+                let mut items = Vec::new();
+                self.sequence(&mut items, Some(b">>"), depth + 1)?;
+                Encoder::new(out).bytes(&items)?;
+            }
+            b'"' | b'\'' => self.strings(out)?,
+            b'-' | b'+' | b'0' ..= b'9' => self.number(out, depth)?,
+            b'a' ..= b'z' | b'A' ..= b'Z' => {
+                let start = self.pos;
+                let word = self.word();
+                if self.peek_is(b'\'') {
+                    self.pos = start;
+                    return self.strings(out)
+                }
+                match word {
+                    b"false"     => out.push(SIMPLE | 20),
+                    b"true"      => out.push(SIMPLE | 21),
+                    b"null"      => out.push(SIMPLE | 22),
+                    b"undefined" => out.push(SIMPLE | 23),
+                    b"Infinity"  => self.float(out, f64::INFINITY)?,
+                    b"NaN"       => self.float(out, f64::NAN)?,
+                    b"simple"    => {
+                        self.expect(b'(')?;
+                        self.space()?;
+                        let at = self.pos;
+                        let n = self.integer()?;
+                        self.space()?;
+                        self.expect(b')')?;
+                        match u8::try_from(n) {
+                            Ok(n @ 0 ..= 23)    => out.push(SIMPLE | n),
+                            Ok(n @ 32 ..= 255)  => out.extend_from_slice(&[SIMPLE | 24, n]),
+                            _ => return Err(Error::Syntax(at, "invalid simple value"))
+                        }
+                    }
+                    _ => return Err(Error::Syntax(start, "unknown identifier"))
+                }
+            }
+            _ => return Err(Error::Syntax(self.pos, "unexpected character"))
+        }
+        Ok(())
+    }
+
+    /// Parse map entries until `}` and return their number.
+    fn entries(&mut self, out: &mut Vec<u8>, depth: usize) -> Result<u64, Error> {
+        let mut n = 0;
+        loop {
+            self.space()?;
+            if self.eat(b'}') {
+                return Ok(n)
+            }
+            if n > 0 {
+                self.expect(b',')?;
+                self.space()?;
+                if self.eat(b'}') {
+                    return Ok(n)
+                }
+            }
+            self.item(out, depth)?;
+            self.space()?;
+            self.expect(b':')?;
+            self.item(out, depth)?;
+            n += 1
+        }
+    }
+
+    /// Parse the chunks of an indefinite-length string until `)`.
+    fn chunks(&mut self, out: &mut Vec<u8>) -> Result<(), Error> {
+        let start = self.pos;
+        let mut chunks = Vec::new();
+        let mut major = None;
+        loop {
+            self.space()?;
+            if self.eat(b')') {
+                break
+            }
+            if major.is_some() {
+                self.expect(b',')?;
+                self.space()?;
+            }
+            let at = self.pos;
+            let (is_text, b) = self.string()?;
+            let m = if is_text { TEXT } else { BYTES };
+            if *major.get_or_insert(m) != m {
+                return Err(Error::Syntax(at, "mixed chunk types in indefinite-length string"))
+            }
+            if is_text {
+                let s = core::str::from_utf8(&b).map_err(|_| Error::Syntax(at, "invalid utf-8"))?;
+                Encoder::new(&mut chunks).str(s)?;
+            } else {
+                Encoder::new(&mut chunks).bytes(&b)?;
+            }
+        }
+        match major {
+            Some(m) => out.push(m | 31),
+            None    => return Err(Error::Syntax(start, "empty indefinite-length string"))
+        }
+        out.extend_from_slice(&chunks);
+        out.push(BREAK);
+        Ok(())
+    }
+
+    /// Parse one or more adjacent string literals.
+    fn strings(&mut self, out: &mut Vec<u8>) -> Result<(), Error> {
+        let start = self.pos;
+        let (is_text, mut b) = self.string()?;
+        let mut parts = 1;
+        loop {
+            let end = self.pos;
+            self.space()?;
+            if self.starts_string() {
+                b.extend_from_slice(&self.string()?.1);
+                parts += 1
+            } else {
+                self.pos = end;
+                break
+            }
+        }
+        if parts == 1 && b.is_empty() && self.eat(b'_') {
+            out.extend_from_slice(&[if is_text { TEXT } else { BYTES } | 31, BREAK]);
+            return Ok(())
+        }
+        if is_text {
+            let s = core::str::from_utf8(&b).map_err(|_| Error::Syntax(start, "invalid utf-8"))?;
+            Encoder::new(out).str(s)?;
+        } else {
+            Encoder::new(out).bytes(&b)?;
+        }
+        Ok(())
+    }
+
+    /// Does a string literal start at the current position?
+    fn starts_string(&self) -> bool {
+        let rest = &self.input[self.pos ..];
+        [&b"\""[..], b"'", b"h'", b"b32'", b"h32'", b"b64'"].iter().any(|p| rest.starts_with(p))
+    }
+
+    /// Parse a string literal and return if it is a text string.
+    fn string(&mut self) -> Result<(bool, Vec<u8>), Error> {
+        let start = self.pos;
+        let prefix = self.word();
+        if !prefix.is_empty() {
+            self.expect(b'\'')?;
+            let b = match prefix {
+                b"h"   => self.base(4, true, |c| char::from(c).to_digit(16))?,
+                b"b32" => self.base(5, false, |c| match c.to_ascii_uppercase() {
+                    x @ b'A' ..= b'Z' => Some(u32::from(x - b'A')),
+                    x @ b'2' ..= b'7' => Some(u32::from(x - b'2') + 26),
+                    _ => None
+                })?,
+                b"h32" => self.base(5, false, |c| char::from(c).to_digit(32))?,
+                b"b64" => self.base(6, false, |c| match c {
+                    b'A' ..= b'Z' => Some(u32::from(c - b'A')),
+                    b'a' ..= b'z' => Some(u32::from(c - b'a') + 26),
+                    b'0' ..= b'9' => Some(u32::from(c - b'0') + 52),
+                    b'+' | b'-'   => Some(62),
+                    b'/' | b'_'   => Some(63),
+                    _ => None
+                })?,
+                _ => return Err(Error::Syntax(start, "unknown string prefix"))
+            };
+            return Ok((false, b))
+        }
+        match self.next()? {
+            b'"'  => Ok((true, self.quoted(b'"')?)),
+            b'\'' => Ok((false, self.quoted(b'\'')?)),
+            _     => Err(Error::Syntax(start, "expected string"))
+        }
+    }
+
+    /// Parse the content of a quoted string up to the closing quote.
+    fn quoted(&mut self, quote: u8) -> Result<Vec<u8>, Error> {
+        let mut b = Vec::new();
+        loop {
+            match self.next()? {
+                q if q == quote => return Ok(b),
+                b'\\' => {
+                    let c = match self.next()? {
+                        b'"'  => '"',
+                        b'\'' => '\'',
+                        b'\\' => '\\',
+                        b'/'  => '/',
+                        b'b'  => '\u{8}',
+                        b'f'  => '\u{c}',
+                        b'n'  => '\n',
+                        b'r'  => '\r',
+                        b't'  => '\t',
+                        b'u'  => self.unicode()?,
+                        _ => return Err(Error::Syntax(self.pos - 1, "invalid escape"))
+                    };
+                    let mut buf = [0; 4];
+                    b.extend_from_slice(c.encode_utf8(&mut buf).as_bytes())
+                }
+                x => b.push(x)
+            }
+        }
+    }
+
+    /// Parse the hex digits of a `\u` escape, including surrogate pairs.
+    fn unicode(&mut self) -> Result<char, Error> {
+        let hi = self.hex4()?;
+        let c = if (0xd800 .. 0xdc00).contains(&hi) {
+            self.literal("\\u").map_err(|_| Error::Syntax(self.pos, "unpaired surrogate"))?;
+            let lo = self.hex4()?;
+            if !(0xdc00 .. 0xe000).contains(&lo) {
+                return Err(Error::Syntax(self.pos, "unpaired surrogate"))
+            }
+            0x10000 + ((hi - 0xd800) << 10) + (lo - 0xdc00)
+        } else {
+            hi
+        };
+        char::from_u32(c).ok_or(Error::Syntax(self.pos, "invalid unicode escape"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, Error> {
+        let mut n = 0;
+        for _ in 0 .. 4 {
+            let d = char::from(self.next()?).to_digit(16).ok_or(Error::Syntax(self.pos - 1, "invalid hex digit"))?;
+            n = n << 4 | d
+        }
+        Ok(n)
+    }
+
+    /// Decode a base16, base32 or base64 string with `bits` bits per digit.
+    ///
+    /// Whitespace, padding and, if enabled, comments are ignored.
+    fn base<F>(&mut self, bits: u32, comments: bool, digit: F) -> Result<Vec<u8>, Error>
+    where
+        F: Fn(u8) -> Option<u32>
+    {
+        let mut b = Vec::new();
+        let mut acc = 0u32;
+        let mut n = 0;
+        loop {
+            if comments {
+                self.space()?
+            }
+            match self.next()? {
+                b' ' | b'\t' | b'\n' | b'\r' => continue,
+                b'\'' => break,
+                b'='  => continue,
+                c => {
+                    let d = digit(c).ok_or(Error::Syntax(self.pos - 1, "invalid digit"))?;
+                    acc = acc << bits | d;
+                    n += bits;
+                    if n >= 8 {
+                        n -= 8;
+                        b.push((acc >> n) as u8);
+                        acc &= (1 << n) - 1
+                    }
+                }
+            }
+        }
+        if n >= bits {
+            return Err(Error::Syntax(self.pos - 1, "incomplete encoded string"))
+        }
+        Ok(b)
+    }
+
+    /// Parse a number, possibly followed by an encoding indicator, or a tag.
+    fn number(&mut self, out: &mut Vec<u8>, depth: usize) -> Result<(), Error> {
+        let start = self.pos;
+        let neg = match self.peek()? {
+            b'-' => { self.pos += 1; true }
+            b'+' => { self.pos += 1; false }
+            _    => false
+        };
+        if self.input[self.pos ..].starts_with(b"Infinity") {
+            self.pos += "Infinity".len();
+            return self.float(out, if neg { f64::NEG_INFINITY } else { f64::INFINITY })
+        }
+        let radix = match self.input.get(self.pos .. self.pos + 2) {
+            Some(b"0x" | b"0X") => 16,
+            Some(b"0o" | b"0O") => 8,
+            Some(b"0b" | b"0B") => 2,
+            _                   => 10
+        };
+        if radix != 10 {
+            self.pos += 2
+        }
+        let digits = self.pos;
+        while let Some(c) = self.input.get(self.pos) {
+            if char::from(*c).to_digit(radix).is_none() {
+                break
+            }
+            self.pos += 1
+        }
+        if self.pos == digits {
+            return Err(Error::Syntax(self.pos, "expected digit"))
+        }
+        let mut is_float = false;
+        if radix == 10 {
+            if self.peek_is(b'.') {
+                self.pos += 1;
+                self.expect_digits()?;
+                is_float = true
+            }
+            if self.peek_is(b'e') || self.peek_is(b'E') {
+                self.pos += 1;
+                if self.peek_is(b'+') || self.peek_is(b'-') {
+                    self.pos += 1
+                }
+                self.expect_digits()?;
+                is_float = true
+            }
+        }
+        let end = self.pos;
+        if is_float {
+            // The number consists of ASCII characters only.
+            let text = core::str::from_utf8(&self.input[start .. end]).map_err(decode::Error::Utf8)?;
+            let x: f64 = text.parse().map_err(|_| Error::Syntax(start, "invalid number"))?;
+            return self.float(out, x)
+        }
+        let n = self.input[digits .. end].iter().try_fold(0u128, |n, d| {
+            n.checked_mul(u128::from(radix))?.checked_add(u128::from(char::from(*d).to_digit(radix)?))
+        });
+        let n = n.ok_or(Error::Syntax(start, "integer too large"))?;
+        let indicator = self.indicator()?;
+        if self.peek_is(b'(') {
+            if neg || self.input[start] == b'+' {
+                return Err(Error::Syntax(start, "invalid tag number"))
+            }
+            let n = u64::try_from(n).map_err(|_| Error::Syntax(start, "tag number too large"))?;
+            self.pos += 1;
+            head(out, TAGGED, n, indicator).map_err(|m| Error::Syntax(start, m))?;
+            self.item(out, depth + 1)?;
+            self.space()?;
+            return self.expect(b')')
+        }
+        let (major, arg) = match (neg, n) {
+            (_, 0)     => (UNSIGNED, 0),
+            (false, n) => (UNSIGNED, n),
+            (true, n)  => (SIGNED, n - 1)
+        };
+        match u64::try_from(arg) {
+            Ok(a)  => head(out, major, a, indicator).map_err(|m| Error::Syntax(start, m)),
+            Err(_) if indicator.is_none() => {
+                let b = arg.to_be_bytes();
+                let i = b.iter().position(|x| *x != 0).unwrap_or(b.len());
+                let tag = if major == UNSIGNED { 2 } else { 3 };
+                Encoder::new(out).type_len(TAGGED, tag)?.bytes(&b[i ..])?;
+                Ok(())
+            }
+            Err(_) => Err(Error::Syntax(start, "integer too large for encoding indicator"))
+        }
+    }
+
+    /// Encode a float, honouring an encoding indicator.
+    fn float(&mut self, out: &mut Vec<u8>, x: f64) -> Result<(), Error> {
+        let at = self.pos;
+        match self.indicator()? {
+            None => crate::value::float(&mut Encoder::new(out), x)?,
+            Some(1) => {
+                let h = if x.is_nan() {
+                    Some(0x7e00)
+                } else if f64::from(x as f32) == x {
+                    crate::ext::f16_bits(x as f32)
+                } else {
+                    None
+                };
+                let h = h.ok_or(Error::Syntax(at, "float not representable as f16"))?;
+                out.extend_from_slice(&[SIMPLE | 25, (h >> 8) as u8, h as u8])
+            }
+            Some(2) => {
+                if !x.is_nan() && f64::from(x as f32) != x {
+                    return Err(Error::Syntax(at, "float not representable as f32"))
+                }
+                Encoder::new(out).f32(x as f32)?;
+            }
+            Some(3) => { Encoder::new(out).f64(x)?; }
+            Some(_) => return Err(Error::Syntax(at, "invalid encoding indicator for float"))
+        }
+        Ok(())
+    }
+
+    /// Parse an optional encoding indicator `_0` to `_3`.
+    fn indicator(&mut self) -> Result<Option<u8>, Error> {
+        if let Some(&[b'_', d]) = self.input.get(self.pos .. self.pos + 2) {
+            if let b'0' ..= b'3' = d {
+                self.pos += 2;
+                return Ok(Some(d - b'0'))
+            }
+            return Err(Error::Syntax(self.pos, "invalid encoding indicator"))
+        }
+        Ok(None)
+    }
+
+    /// Parse an unsigned decimal integer.
+    fn integer(&mut self) -> Result<u64, Error> {
+        let start = self.pos;
+        let mut n = 0u64;
+        while let Some(c @ b'0' ..= b'9') = self.input.get(self.pos) {
+            n = n.checked_mul(10)
+                .and_then(|n| n.checked_add(u64::from(c - b'0')))
+                .ok_or(Error::Syntax(start, "integer too large"))?;
+            self.pos += 1
+        }
+        if self.pos == start {
+            return Err(Error::Syntax(start, "expected digit"))
+        }
+        Ok(n)
+    }
+
+    /// Consume an identifier of ASCII letters and digits.
+    fn word(&mut self) -> &'a [u8] {
+        let start = self.pos;
+        if let Some(b'a' ..= b'z' | b'A' ..= b'Z') = self.input.get(self.pos) {
+            while let Some(b'a' ..= b'z' | b'A' ..= b'Z' | b'0' ..= b'9') = self.input.get(self.pos) {
+                self.pos += 1
+            }
+        }
+        &self.input[start .. self.pos]
+    }
+
+    /// Skip over whitespace and comments.
+    fn space(&mut self) -> Result<(), Error> {
+        loop {
+            match self.input.get(self.pos) {
+                Some(b' ' | b'\t' | b'\n' | b'\r') => self.pos += 1,
+                Some(b'/') => {
+                    let start = self.pos;
+                    self.pos += 1;
+                    while self.next().map_err(|_| Error::Syntax(start, "unterminated comment"))? != b'/' {}
+                }
+                Some(b'#') => {
+                    while let Some(c) = self.input.get(self.pos) {
+                        self.pos += 1;
+                        if *c == b'\n' {
+                            break
+                        }
+                    }
+                }
+                _ => return Ok(())
+            }
+        }
+    }
+
+    fn peek(&self) -> Result<u8, Error> {
+        self.input.get(self.pos).copied().ok_or(Error::Syntax(self.pos, "unexpected end of input"))
+    }
+
+    fn peek_is(&self, b: u8) -> bool {
+        self.input.get(self.pos) == Some(&b)
+    }
+
+    fn next(&mut self) -> Result<u8, Error> {
+        let b = self.peek()?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    /// Consume `b` if it is the next character.
+    fn eat(&mut self, b: u8) -> bool {
+        if self.peek_is(b) {
+            self.pos += 1;
+            return true
+        }
+        false
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), Error> {
+        if !self.eat(b) {
+            return Err(Error::Syntax(self.pos, "unexpected character"))
+        }
+        Ok(())
+    }
+
+    fn literal(&mut self, lit: &'static str) -> Result<(), Error> {
+        if !self.input[self.pos ..].starts_with(lit.as_bytes()) {
+            return Err(Error::Syntax(self.pos, "invalid literal"))
+        }
+        self.pos += lit.len();
+        Ok(())
+    }
+
+    fn expect_digits(&mut self) -> Result<(), Error> {
+        let start = self.pos;
+        while let Some(b'0' ..= b'9') = self.input.get(self.pos) {
+            self.pos += 1
+        }
+        if self.pos == start {
+            return Err(Error::Syntax(self.pos, "expected digit"))
+        }
+        Ok(())
+    }
+}
+
+/// Write a data item head, honouring an encoding indicator.
+fn head(out: &mut Vec<u8>, major: u8, n: u64, indicator: Option<u8>) -> Result<(), &'static str> {
+    match indicator {
+        None => {
+            Encoder::new(out).type_len(major, n).map_err(|_| "failed to write cbor")?;
+        }
+        Some(i) => {
+            let size = 1usize << i;
+            if size < 8 && n >> (8 * size) != 0 {
+                return Err("integer too large for encoding indicator")
+            }
+            out.push(major | (24 + i));
+            out.extend_from_slice(&n.to_be_bytes()[8 - size ..])
+        }
+    }
+    Ok(())
+}
@@ -1,12 +1,18 @@
 //! Traits and types for encoding CBOR.
 //!
 //! This module defines the trait [`Encode`] and the actual [`Encoder`].
-//! It also defines a [`Write`] trait to store the encoded bytes.
+//! It also defines a [`Write`] trait to store the encoded bytes and a
+//! [`ConstEncoder`] to encode constant data at compile time.
 
+mod constant;
 mod encoder;
 mod error;
 pub mod write;
 
+#[cfg(feature = "alloc")]
+pub(crate) mod definite;
+
+pub use constant::ConstEncoder;
 pub use encoder::Encoder;
 pub use error::Error;
 pub use write::Write;
@@ -48,6 +54,13 @@ impl<T: Encode + ?Sized> Encode for alloc::rc::Rc<T> {
     }
 }
 
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl<T: Encode + ?Sized> Encode for alloc::sync::Arc<T> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
+        (**self).encode(e)
+    }
+}
+
 impl<T: Encode + ?Sized> Encode for core::cell::RefCell<T> {
     fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
         self.borrow().encode(e)
@@ -98,6 +111,26 @@ where
     }
 }
 
+/// Paths are encoded as text strings. Paths which are not valid UTF-8
+/// produce an error.
+#[cfg(feature = "std")]
+impl Encode for std::path::Path {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
+        if let Some(s) = self.to_str() {
+            e.str(s)?.ok()
+        } else {
+            Err(Error::Message("path is not valid utf-8"))
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Encode for std::path::PathBuf {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
+        self.as_path().encode(e)
+    }
+}
+
 
 #[cfg(feature = "std")]
 impl<K, V> Encode for std::collections::HashMap<K, V>
@@ -143,58 +176,16 @@ impl Encode for () {
     }
 }
 
-#[cfg(target_pointer_width = "32")]
-impl Encode for usize {
-    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
-        e.u32(*self as u32)?.ok()
-    }
-}
+// `usize` and `isize` are encoded as their 64-bit counterparts (which uses
+// the smallest possible representation) so that the encoding is the same on
+// every target.
 
-#[cfg(target_pointer_width = "64")]
 impl Encode for usize {
     fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
         e.u64(*self as u64)?.ok()
     }
 }
 
-#[cfg(all(not(feature = "std"),target_pointer_width = "32"))]
-impl Encode for core::num::NonZeroUsize {
-    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
-        e.u32(self.get() as u32)?.ok()
-    }
-}
-
-#[cfg(all(not(feature = "std"),target_pointer_width = "64"))]
-impl Encode for core::num::NonZeroUsize {
-    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
-        e.u64(self.get() as u64)?.ok()
-    }
-}
-
-#[cfg(all(feature = "std",target_pointer_width = "32"))]
-impl Encode for std::num::NonZeroUsize {
-    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
-        e.u32(self.get() as u32)?.ok()
-    }
-}
-
-#[cfg(all(feature = "std",target_pointer_width = "64"))]
-impl Encode for std::num::NonZeroUsize {
-    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
-        e.u64(self.get() as u64)?.ok()
-    }
-}
-
-
-
-#[cfg(target_pointer_width = "32")]
-impl Encode for isize {
-    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
-        e.i32(*self as i32)?.ok()
-    }
-}
-
-#[cfg(target_pointer_width = "64")]
 impl Encode for isize {
     fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
         e.i64(*self as i64)?.ok()
@@ -214,7 +205,7 @@ macro_rules! encode_basic {
     }
 }
 
-encode_basic!(u8 i8 u16 i16 u32 i32 u64 i64 bool f32 f64 char);
+encode_basic!(u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 bool f32 f64 char);
 
 macro_rules! encode_nonzero {
     ($($t:ty)*) => {
@@ -233,10 +224,14 @@ encode_nonzero! {
     core::num::NonZeroU16
     core::num::NonZeroU32
     core::num::NonZeroU64
+    core::num::NonZeroU128
+    core::num::NonZeroUsize
     core::num::NonZeroI8
     core::num::NonZeroI16
     core::num::NonZeroI32
     core::num::NonZeroI64
+    core::num::NonZeroI128
+    core::num::NonZeroIsize
 }
 
 macro_rules! encode_sequential {
@@ -331,44 +326,39 @@ impl Encode for core::time::Duration {
     }
 }
 
-#[cfg(feature = "std")]
-impl Encode for std::net::IpAddr {
+impl Encode for core::net::IpAddr {
     fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
         e.array(2)?;
         match self {
-            std::net::IpAddr::V4(a) => e.u32(0)?.encode(a)?.ok(),
-            std::net::IpAddr::V6(a) => e.u32(1)?.encode(a)?.ok()
+            core::net::IpAddr::V4(a) => e.u32(0)?.encode(a)?.ok(),
+            core::net::IpAddr::V6(a) => e.u32(1)?.encode(a)?.ok()
         }
     }
 }
 
-#[cfg(feature = "std")]
-impl Encode for std::net::Ipv4Addr {
+impl Encode for core::net::Ipv4Addr {
     fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
         self.octets().encode(e)
     }
 }
 
-#[cfg(feature = "std")]
-impl Encode for std::net::Ipv6Addr {
+impl Encode for core::net::Ipv6Addr {
     fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
         self.octets().encode(e)
     }
 }
 
-#[cfg(feature = "std")]
-impl Encode for std::net::SocketAddr {
+impl Encode for core::net::SocketAddr {
     fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
         e.array(2)?;
         match self {
-            std::net::SocketAddr::V4(a) => e.u32(0)?.encode(a)?.ok(),
-            std::net::SocketAddr::V6(a) => e.u32(1)?.encode(a)?.ok()
+            core::net::SocketAddr::V4(a) => e.u32(0)?.encode(a)?.ok(),
+            core::net::SocketAddr::V6(a) => e.u32(1)?.encode(a)?.ok()
         }
     }
 }
 
-#[cfg(feature = "std")]
-impl Encode for std::net::SocketAddrV4 {
+impl Encode for core::net::SocketAddrV4 {
     fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
         e.array(2)?
             .encode(self.ip())?
@@ -377,8 +367,7 @@ impl Encode for std::net::SocketAddrV4 {
     }
 }
 
-#[cfg(feature = "std")]
-impl Encode for std::net::SocketAddrV6 {
+impl Encode for core::net::SocketAddrV6 {
     fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
         e.array(2)?
             .encode(self.ip())?
@@ -0,0 +1,234 @@
+use crate::data::Tag;
+use crate::{UNSIGNED, SIGNED, BYTES, TEXT, ARRAY, MAP, TAGGED, SIMPLE, BREAK};
+use super::encoder::head;
+
+/// An encoder which can be used in constant expressions.
+///
+/// `ConstEncoder` writes into a fixed-size buffer of `N` bytes and all its
+/// methods are `const fn`s, so protocol constants like fixed message headers
+/// or well-known messages can be encoded at compile time and stored in
+/// `static` or `const` items. Methods take and return the encoder by value
+/// to allow chaining.
+///
+/// Encoding more than `N` bytes panics, which in a constant expression
+/// results in a compilation error.
+///
+/// ```
+/// use minicbor::encode::ConstEncoder;
+///
+/// static HELLO: [u8; 8] = ConstEncoder::new()
+///     .map(1)
+///     .u8(1)
+///     .str("hello")
+///     .into_array();
+///
+/// let mut e = minicbor::Encoder::new(Vec::new());
+/// e.map(1)?.u8(1)?.str("hello")?;
+/// assert_eq!(e.into_inner(), HELLO);
+///
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ConstEncoder<const N: usize> {
+    buf: [u8; N],
+    len: usize
+}
+
+impl<const N: usize> Default for ConstEncoder<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> ConstEncoder<N> {
+    /// Create a new, empty encoder.
+    pub const fn new() -> Self {
+        ConstEncoder { buf: [0; N], len: 0 }
+    }
+
+    /// Get the number of bytes encoded so far.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Have no bytes been encoded yet?
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the encoded bytes.
+    pub const fn as_bytes(&self) -> &[u8] {
+        self.buf.split_at(self.len).0
+    }
+
+    /// Get the buffer which must have been filled completely.
+    ///
+    /// # Panics
+    ///
+    /// If fewer than `N` bytes have been encoded.
+    pub const fn into_array(self) -> [u8; N] {
+        assert!(self.len == N, "ConstEncoder buffer not filled completely");
+        self.buf
+    }
+
+    /// Encode a `u8` value.
+    pub const fn u8(self, x: u8) -> Self {
+        self.type_len(UNSIGNED, x as u64)
+    }
+
+    /// Encode an `i8` value.
+    pub const fn i8(self, x: i8) -> Self {
+        self.i64(x as i64)
+    }
+
+    /// Encode a `u16` value.
+    pub const fn u16(self, x: u16) -> Self {
+        self.type_len(UNSIGNED, x as u64)
+    }
+
+    /// Encode an `i16` value.
+    pub const fn i16(self, x: i16) -> Self {
+        self.i64(x as i64)
+    }
+
+    /// Encode a `u32` value.
+    pub const fn u32(self, x: u32) -> Self {
+        self.type_len(UNSIGNED, x as u64)
+    }
+
+    /// Encode an `i32` value.
+    pub const fn i32(self, x: i32) -> Self {
+        self.i64(x as i64)
+    }
+
+    /// Encode a `u64` value.
+    pub const fn u64(self, x: u64) -> Self {
+        self.type_len(UNSIGNED, x)
+    }
+
+    /// Encode an `i64` value.
+    pub const fn i64(self, x: i64) -> Self {
+        if x >= 0 {
+            self.type_len(UNSIGNED, x as u64)
+        } else {
+            self.type_len(SIGNED, (-1 - x) as u64)
+        }
+    }
+
+    /// Encode a CBOR `null` value.
+    pub const fn null(self) -> Self {
+        self.put(&[SIMPLE | 22])
+    }
+
+    /// Encode a CBOR `undefined` value.
+    pub const fn undefined(self) -> Self {
+        self.put(&[SIMPLE | 23])
+    }
+
+    /// Encode a CBOR simple value.
+    pub const fn simple(self, x: u8) -> Self {
+        if x < 0x14 {
+            self.put(&[SIMPLE | x])
+        } else {
+            self.put(&[SIMPLE | 24, x])
+        }
+    }
+
+    /// Encode an `f32` value.
+    pub const fn f32(self, x: f32) -> Self {
+        let [a, b, c, d] = x.to_bits().to_be_bytes();
+        self.put(&[SIMPLE | 26, a, b, c, d])
+    }
+
+    /// Encode an `f64` value.
+    pub const fn f64(self, x: f64) -> Self {
+        let [a, b, c, d, e, f, g, h] = x.to_bits().to_be_bytes();
+        self.put(&[SIMPLE | 27, a, b, c, d, e, f, g, h])
+    }
+
+    /// Encode a `bool` value.
+    pub const fn bool(self, x: bool) -> Self {
+        self.put(&[SIMPLE | if x { 0x15 } else { 0x14 }])
+    }
+
+    /// Encode a `char` value.
+    pub const fn char(self, x: char) -> Self {
+        self.u32(x as u32)
+    }
+
+    /// Encode a CBOR tag.
+    pub const fn tag(self, x: Tag) -> Self {
+        self.type_len(TAGGED, x.numeric())
+    }
+
+    /// Encode a byte slice.
+    pub const fn bytes(self, x: &[u8]) -> Self {
+        self.type_len(BYTES, x.len() as u64).put(x)
+    }
+
+    /// Encode a string slice.
+    pub const fn str(self, x: &str) -> Self {
+        self.type_len(TEXT, x.len() as u64).put(x.as_bytes())
+    }
+
+    /// Begin encoding an array with `len` elements.
+    pub const fn array(self, len: u64) -> Self {
+        self.type_len(ARRAY, len)
+    }
+
+    /// Begin encoding a map with `len` entries.
+    pub const fn map(self, len: u64) -> Self {
+        self.type_len(MAP, len)
+    }
+
+    /// Begin encoding an array of unknown size.
+    ///
+    /// Use [`ConstEncoder::end`] to terminate the array.
+    pub const fn begin_array(self) -> Self {
+        self.put(&[ARRAY | 31])
+    }
+
+    /// Begin encoding an indefinite number of byte slices.
+    ///
+    /// Use [`ConstEncoder::end`] to terminate.
+    pub const fn begin_bytes(self) -> Self {
+        self.put(&[BYTES | 31])
+    }
+
+    /// Begin encoding a map of unknown size.
+    ///
+    /// Use [`ConstEncoder::end`] to terminate the map.
+    pub const fn begin_map(self) -> Self {
+        self.put(&[MAP | 31])
+    }
+
+    /// Begin encoding an indefinite number of string slices.
+    ///
+    /// Use [`ConstEncoder::end`] to terminate.
+    pub const fn begin_str(self) -> Self {
+        self.put(&[TEXT | 31])
+    }
+
+    /// Terminate an indefinite collection.
+    pub const fn end(self) -> Self {
+        self.put(&[BREAK])
+    }
+
+    /// Write type and length information.
+    const fn type_len(self, t: u8, x: u64) -> Self {
+        let (h, n) = head(t, x);
+        self.put(h.split_at(n).0)
+    }
+
+    /// Append the given bytes to the buffer.
+    const fn put(mut self, b: &[u8]) -> Self {
+        assert!(b.len() <= N - self.len, "ConstEncoder buffer too small");
+        let mut i = 0;
+        while i < b.len() {
+            self.buf[self.len + i] = b[i];
+            i += 1
+        }
+        self.len += b.len();
+        self
+    }
+}
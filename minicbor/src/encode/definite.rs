@@ -0,0 +1,306 @@
+//! Two-pass encoding with definite lengths.
+//!
+//! A value is encoded twice. The first pass writes to [`Measure`], which
+//! parses the produced CBOR and records the lengths of all indefinite-length
+//! arrays, maps and strings. The second pass writes to [`Rewrite`], which
+//! passes the encoding on to the actual writer, but replaces every
+//! indefinite-length head with a definite one and drops string chunk heads
+//! and breaks.
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use crate::encode::{encoder, Encode, Encoder, Error, Write};
+use crate::{BYTES, TEXT, ARRAY, MAP, TAGGED, SIMPLE, BREAK};
+
+/// Encode a value with definite lengths only.
+pub(crate) fn encode<T, W>(x: T, w: W) -> Result<(), Error<W::Error>>
+where
+    T: Encode,
+    W: Write
+{
+    let mut m = Encoder::new(Measure(Walker::new(Vec::new(), true)));
+    x.encode(&mut m).map_err(|e| match e {
+        Error::Write(m) | Error::Message(m) => Error::Message(m)
+    })?;
+    let lengths = m.into_inner().0.finish().map_err(Error::Message)?;
+
+    let mut r = Encoder::new(Rewrite { writer: w, walker: Walker::new(lengths, false) });
+    x.encode(&mut r).map_err(|e| match e {
+        Error::Write(Failure::Write(e))   => Error::Write(e),
+        Error::Write(Failure::Invalid(m)) => Error::Message(m),
+        Error::Message(m)                 => Error::Message(m)
+    })?;
+    r.into_inner().walker.finish().map_err(Error::Message)?;
+    Ok(())
+}
+
+/// The outcome of a data item head.
+#[derive(Debug, Clone, Copy)]
+enum Action {
+    /// The head starts an indefinite-length item with the given length.
+    Begin(u64),
+    /// The head is a string chunk or a break.
+    Drop,
+    /// The head is a regular head.
+    Keep
+}
+
+/// The parts of an encoding.
+#[derive(Debug)]
+enum Event<'a> {
+    /// A complete data item head.
+    Head(&'a [u8], Action),
+    /// (Some of) the content bytes of a string.
+    Payload(&'a [u8])
+}
+
+/// A container which is currently being encoded.
+#[derive(Debug, Clone, Copy)]
+enum Frame {
+    /// A definite-length array or map with the number of remaining items.
+    Definite(u64),
+    /// An indefinite-length array or map with its length slot.
+    Indefinite(usize, bool),
+    /// An indefinite-length string with its length slot and major type.
+    Str(usize, u8),
+    /// A tag whose item has not started yet.
+    Tag
+}
+
+/// Incremental parser of an encoding.
+#[derive(Debug)]
+struct Walker {
+    /// The head being parsed.
+    head: [u8; 9],
+    /// Number of head bytes parsed so far.
+    head_len: usize,
+    /// Number of string bytes which remain.
+    payload: u64,
+    /// The enclosing containers.
+    stack: Vec<Frame>,
+    /// The lengths of indefinite-length items in order of appearance.
+    lengths: Vec<u64>,
+    /// Number of indefinite-length items so far.
+    slots: usize,
+    /// Are lengths recorded (first pass) or looked up (second pass)?
+    measure: bool
+}
+
+impl Walker {
+    fn new(lengths: Vec<u64>, measure: bool) -> Self {
+        Walker {
+            head: [0; 9],
+            head_len: 0,
+            payload: 0,
+            stack: Vec::new(),
+            lengths,
+            slots: 0,
+            measure
+        }
+    }
+
+    /// Feed the given bytes to the parser and pass every event to `f`.
+    fn feed<E, F>(&mut self, mut buf: &[u8], mut f: F) -> Result<(), E>
+    where
+        F: FnMut(Event) -> Result<(), E>,
+        E: From<&'static str>
+    {
+        while !buf.is_empty() {
+            if self.payload > 0 {
+                let n = usize::try_from(self.payload).map_or(buf.len(), |p| p.min(buf.len()));
+                f(Event::Payload(&buf[.. n]))?;
+                self.payload -= n as u64;
+                buf = &buf[n ..];
+                continue
+            }
+            self.head[self.head_len] = buf[0];
+            self.head_len += 1;
+            buf = &buf[1 ..];
+            let len = match self.head[0] & 0x1f {
+                24 => 2,
+                25 => 3,
+                26 => 5,
+                27 => 9,
+                28 ..= 30 => return Err(E::from("reserved additional information")),
+                _  => 1
+            };
+            if self.head_len < len {
+                continue
+            }
+            let head = self.head;
+            self.head_len = 0;
+            let action = self.on_head(&head[.. len]).map_err(E::from)?;
+            f(Event::Head(&head[.. len], action))?
+        }
+        Ok(())
+    }
+
+    /// Process a complete head.
+    fn on_head(&mut self, head: &[u8]) -> Result<Action, &'static str> {
+        let major = head[0] & 0xe0;
+        let info  = head[0] & 0x1f;
+        let init  = if info < 24 { u64::from(info) } else { 0 };
+        let arg   = head[1 ..].iter().fold(init, |n, x| n << 8 | u64::from(*x));
+
+        if let Some(Frame::Str(slot, m)) = self.stack.last().copied() {
+            if head[0] == BREAK {
+                self.stack.pop();
+                return Ok(Action::Drop)
+            }
+            if major != m || info == 31 {
+                return Err("invalid chunk in indefinite-length string")
+            }
+            if self.measure {
+                self.lengths[slot] = self.lengths[slot].checked_add(arg).ok_or("string length overflow")?
+            }
+            self.payload = arg;
+            return Ok(Action::Drop)
+        }
+
+        if head[0] == BREAK {
+            self.pop_complete();
+            return match self.stack.pop() {
+                Some(Frame::Indefinite(slot, true)) if self.measure => {
+                    if self.lengths[slot] & 1 == 1 {
+                        return Err("map entry without value")
+                    }
+                    self.lengths[slot] /= 2;
+                    Ok(Action::Drop)
+                }
+                Some(Frame::Indefinite(..)) => Ok(Action::Drop),
+                _ => Err("unexpected break")
+            }
+        }
+
+        if let Some(Frame::Tag) = self.stack.last() {
+            self.stack.pop();
+        } else {
+            self.pop_complete();
+            self.start_item()?
+        }
+
+        match (major, info) {
+            (BYTES | TEXT, 31) => {
+                let slot = self.begin()?;
+                self.stack.push(Frame::Str(slot, major));
+                Ok(Action::Begin(self.lengths[slot]))
+            }
+            (BYTES | TEXT, _) => {
+                self.payload = arg;
+                Ok(Action::Keep)
+            }
+            (ARRAY | MAP, 31) => {
+                let slot = self.begin()?;
+                self.stack.push(Frame::Indefinite(slot, major == MAP));
+                Ok(Action::Begin(self.lengths[slot]))
+            }
+            (ARRAY | MAP, _) => {
+                let n = if major == MAP { arg.checked_mul(2).ok_or("map length overflow")? } else { arg };
+                self.stack.push(Frame::Definite(n));
+                Ok(Action::Keep)
+            }
+            (TAGGED, 31) => Err("unexpected indefinite length"),
+            (TAGGED, _)  => {
+                self.stack.push(Frame::Tag);
+                Ok(Action::Keep)
+            }
+            (SIMPLE, _) => Ok(Action::Keep),
+            (_, 31)     => Err("unexpected indefinite length"),
+            _           => Ok(Action::Keep)
+        }
+    }
+
+    /// Allocate the length slot of a new indefinite-length item.
+    fn begin(&mut self) -> Result<usize, &'static str> {
+        if self.measure {
+            self.lengths.push(0)
+        } else if self.slots >= self.lengths.len() {
+            return Err("encoding changed between passes")
+        }
+        self.slots += 1;
+        Ok(self.slots - 1)
+    }
+
+    /// Remove all definite-length containers whose items are complete.
+    fn pop_complete(&mut self) {
+        while let Some(Frame::Definite(0)) = self.stack.last() {
+            self.stack.pop();
+        }
+    }
+
+    /// Account for a new item in the enclosing container.
+    fn start_item(&mut self) -> Result<(), &'static str> {
+        match self.stack.last_mut() {
+            Some(Frame::Definite(n)) => {
+                *n = n.checked_sub(1).ok_or("too many items in definite-length container")?
+            }
+            Some(Frame::Indefinite(slot, _)) if self.measure => {
+                self.lengths[*slot] += 1
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Check that the encoding is complete and get back the lengths.
+    fn finish(mut self) -> Result<Vec<u64>, &'static str> {
+        self.pop_complete();
+        if self.head_len > 0 || self.payload > 0 || !self.stack.is_empty() {
+            return Err("incomplete cbor item")
+        }
+        if !self.measure && self.slots != self.lengths.len() {
+            return Err("encoding changed between passes")
+        }
+        Ok(self.lengths)
+    }
+}
+
+/// A [`Write`] impl which records the lengths of indefinite-length items.
+struct Measure(Walker);
+
+impl Write for Measure {
+    type Error = &'static str;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.0.feed(buf, |_| Ok(()))
+    }
+}
+
+/// A [`Write`] impl which replaces indefinite-length heads with definite ones.
+struct Rewrite<W> {
+    writer: W,
+    walker: Walker
+}
+
+/// The error of a [`Rewrite`] writer.
+enum Failure<E> {
+    Write(E),
+    Invalid(&'static str)
+}
+
+impl<E> From<&'static str> for Failure<E> {
+    fn from(m: &'static str) -> Self {
+        Failure::Invalid(m)
+    }
+}
+
+impl<W: Write> Write for Rewrite<W> {
+    type Error = Failure<W::Error>;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        let w = &mut self.writer;
+        self.walker.feed(buf, |event| match event {
+            Event::Head(head, Action::Begin(n)) => {
+                let (h, k) = encoder::head(head[0] & 0xe0, n);
+                w.write_all(&h[.. k]).map_err(Failure::Write)
+            }
+            Event::Head(_, Action::Drop)    => Ok(()),
+            Event::Head(head, Action::Keep) => w.write_all(head).map_err(Failure::Write),
+            Event::Payload(p)               => w.write_all(p).map_err(Failure::Write)
+        })
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.writer.flush().map_err(Failure::Write)
+    }
+}
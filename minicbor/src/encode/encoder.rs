@@ -1,10 +1,13 @@
-use crate::{SIGNED, BYTES, TEXT, ARRAY, MAP, TAGGED, SIMPLE};
+use crate::{UNSIGNED, SIGNED, BYTES, TEXT, ARRAY, MAP, TAGGED, SIMPLE};
 use crate::data::Tag;
 use crate::encode::{Encode, Error, Write};
 
 /// A non-allocating CBOR encoder writing encoded bytes to the given [`Write`] sink.
 #[derive(Debug, Clone)]
-pub struct Encoder<W> { writer: W }
+pub struct Encoder<W> {
+    writer: W,
+    reject_non_finite: bool
+}
 
 impl<W> AsRef<W> for Encoder<W> {
     fn as_ref(&self) -> &W {
@@ -15,7 +18,20 @@ impl<W> AsRef<W> for Encoder<W> {
 impl<W: Write> Encoder<W> {
     /// Construct an `Encoder` that writes to the given [`Write`] sink.
     pub fn new(writer: W) -> Encoder<W> {
-        Encoder { writer }
+        Encoder { writer, reject_non_finite: false }
+    }
+
+    /// Reject NaN and infinite floating point values.
+    ///
+    /// If set, encoding such a value results in an error. By default they
+    /// are accepted.
+    pub fn set_reject_non_finite(&mut self, val: bool) {
+        self.reject_non_finite = val
+    }
+
+    /// Are NaN and infinite floating point values rejected?
+    pub fn reject_non_finite(&self) -> bool {
+        self.reject_non_finite
     }
 
     /// Get back the [`Write`] impl.
@@ -24,97 +40,94 @@ impl<W: Write> Encoder<W> {
     }
 
     /// Encode any type that implements [`Encode`].
+    ///
+    /// With feature `"tracing"`, encoding happens in a span at level
+    /// `TRACE` and errors are reported as events at level `DEBUG`, both
+    /// with the type to encode.
     pub fn encode<T: Encode>(&mut self, x: T) -> Result<&mut Self, Error<W::Error>> {
-        x.encode(self)?;
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("encode", ty = core::any::type_name::<T>()).entered();
+        let result = x.encode(self);
+        #[cfg(feature = "tracing")]
+        if let Err(e) = &result {
+            match e {
+                Error::Write(_)   => tracing::debug!(error = "write error", "encode error"),
+                Error::Message(m) => tracing::debug!(error = %m, "encode error")
+            }
+        }
+        result?;
         Ok(self)
     }
 
     /// Encode a `u8` value.
     pub fn u8(&mut self, x: u8) -> Result<&mut Self, Error<W::Error>> {
-        if let 0 ..= 0x17 = x {
-            self.put(&[x])
-        } else {
-            self.put(&[24, x])
-        }
+        self.type_len(UNSIGNED, u64::from(x))
     }
 
     /// Encode an `i8` value.
     pub fn i8(&mut self, x: i8) -> Result<&mut Self, Error<W::Error>> {
-        if x >= 0 {
-            return self.u8(x as u8)
-        }
-        match (-1 - x) as u8 {
-            n @ 0 ..= 0x17 => self.put(&[SIGNED | n]),
-            n              => self.put(&[SIGNED | 24, n])
-        }
+        self.i64(i64::from(x))
     }
 
     /// Encode a `u16` value.
     pub fn u16(&mut self, x: u16) -> Result<&mut Self, Error<W::Error>> {
-        match x {
-            0    ..= 0x17 => self.put(&[x as u8]),
-            0x18 ..= 0xff => self.put(&[24, x as u8]),
-            _             => self.put(&[25])?.put(&x.to_be_bytes()[..])
-        }
+        self.type_len(UNSIGNED, u64::from(x))
     }
 
     /// Encode an `i16` value.
     pub fn i16(&mut self, x: i16) -> Result<&mut Self, Error<W::Error>> {
-        if x >= 0 {
-            return self.u16(x as u16)
-        }
-        match (-1 - x) as u16 {
-            n @ 0    ..= 0x17 => self.put(&[SIGNED | n as u8]),
-            n @ 0x18 ..= 0xff => self.put(&[SIGNED | 24, n as u8]),
-            n                 => self.put(&[SIGNED | 25])?.put(&n.to_be_bytes()[..])
-        }
+        self.i64(i64::from(x))
     }
 
     /// Encode a `u32` value.
     pub fn u32(&mut self, x: u32) -> Result<&mut Self, Error<W::Error>> {
-        match x {
-            0     ..= 0x17   => self.put(&[x as u8]),
-            0x18  ..= 0xff   => self.put(&[24, x as u8]),
-            0x100 ..= 0xffff => self.put(&[25])?.put(&(x as u16).to_be_bytes()[..]),
-            _                => self.put(&[26])?.put(&x.to_be_bytes()[..])
-        }
+        self.type_len(UNSIGNED, u64::from(x))
     }
 
     /// Encode an `i32` value.
     pub fn i32(&mut self, x: i32) -> Result<&mut Self, Error<W::Error>> {
-        if x >= 0 {
-            return self.u32(x as u32)
-        }
-        match (-1 - x) as u32 {
-            n @ 0     ..= 0x17   => self.put(&[SIGNED | n as u8]),
-            n @ 0x18  ..= 0xff   => self.put(&[SIGNED | 24, n as u8]),
-            n @ 0x100 ..= 0xffff => self.put(&[SIGNED | 25])?.put(&(n as u16).to_be_bytes()[..]),
-            n                    => self.put(&[SIGNED | 26])?.put(&n.to_be_bytes()[..])
-        }
+        self.i64(i64::from(x))
     }
 
     /// Encode a `u64` value.
     pub fn u64(&mut self, x: u64) -> Result<&mut Self, Error<W::Error>> {
-        match x {
-            0        ..= 0x17        => self.put(&[x as u8]),
-            0x18     ..= 0xff        => self.put(&[24, x as u8]),
-            0x100    ..= 0xffff      => self.put(&[25])?.put(&(x as u16).to_be_bytes()[..]),
-            0x1_0000 ..= 0xffff_ffff => self.put(&[26])?.put(&(x as u32).to_be_bytes()[..]),
-            _                        => self.put(&[27])?.put(&x.to_be_bytes()[..])
-        }
+        self.type_len(UNSIGNED, x)
     }
 
     /// Encode an `i64` value.
     pub fn i64(&mut self, x: i64) -> Result<&mut Self, Error<W::Error>> {
         if x >= 0 {
-            return self.u64(x as u64)
+            self.type_len(UNSIGNED, x as u64)
+        } else {
+            self.type_len(SIGNED, (-1 - x) as u64)
         }
-        match (-1 - x) as u64 {
-            n @ 0        ..= 0x17        => self.put(&[SIGNED | n as u8]),
-            n @ 0x18     ..= 0xff        => self.put(&[SIGNED | 24, n as u8]),
-            n @ 0x100    ..= 0xffff      => self.put(&[SIGNED | 25])?.put(&(n as u16).to_be_bytes()[..]),
-            n @ 0x1_0000 ..= 0xffff_ffff => self.put(&[SIGNED | 26])?.put(&(n as u32).to_be_bytes()[..]),
-            n                            => self.put(&[SIGNED | 27])?.put(&n.to_be_bytes()[..])
+    }
+
+    /// Encode a `u128` value.
+    ///
+    /// Values beyond the range of `u64` are encoded as positive bignums
+    /// (tag 2) with a byte string of minimal length.
+    pub fn u128(&mut self, x: u128) -> Result<&mut Self, Error<W::Error>> {
+        if x <= u128::from(u64::MAX) {
+            self.type_len(UNSIGNED, x as u64)
+        } else {
+            self.tag(Tag::PosBignum)?.bignum(x)
+        }
+    }
+
+    /// Encode an `i128` value.
+    ///
+    /// Values beyond the range of CBOR integers (major types 0 and 1)
+    /// are encoded as positive (tag 2) or negative (tag 3) bignums.
+    pub fn i128(&mut self, x: i128) -> Result<&mut Self, Error<W::Error>> {
+        if x >= 0 {
+            return self.u128(x as u128)
+        }
+        let n = (-1 - x) as u128;
+        if n <= u128::from(u64::MAX) {
+            self.type_len(SIGNED, n as u64)
+        } else {
+            self.tag(Tag::NegBignum)?.bignum(n)
         }
     }
 
@@ -156,18 +169,24 @@ impl<W: Write> Encoder<W> {
     /// [1]: https://crates.io/crates/half
     #[cfg(feature = "half")]
     pub fn f16(&mut self, x: f32) -> Result<&mut Self, Error<W::Error>> {
-        let n = half::f16::from_f32(x).to_bits();
-        self.put(&[SIMPLE | 25])?.put(&n.to_be_bytes()[..])
+        self.check_finite(x.into())?;
+        let [a, b] = half::f16::from_f32(x).to_bits().to_be_bytes();
+        self.put(&[SIMPLE | 25, a, b])
     }
 
     /// Encode an `f32` value.
     pub fn f32(&mut self, x: f32) -> Result<&mut Self, Error<W::Error>> {
-        self.put(&[SIMPLE | 26])?.put(&x.to_be_bytes()[..])
+        self.check_finite(x.into())?;
+        let [a, b, c, d] = x.to_be_bytes();
+        self.put(&[SIMPLE | 26, a, b, c, d])
     }
 
     /// Encode an `f64` value.
     pub fn f64(&mut self, x: f64) -> Result<&mut Self, Error<W::Error>> {
-        self.put(&[SIMPLE | 27])?.put(&x.to_be_bytes()[..])
+        self.check_finite(x)?;
+        let mut b = [SIMPLE | 27, 0, 0, 0, 0, 0, 0, 0, 0];
+        b[1 ..].copy_from_slice(&x.to_be_bytes());
+        self.put(&b)
     }
 
     /// Encode a `bool` value.
@@ -187,12 +206,16 @@ impl<W: Write> Encoder<W> {
 
     /// Encode a byte slice.
     pub fn bytes(&mut self, x: &[u8]) -> Result<&mut Self, Error<W::Error>> {
-        self.type_len(BYTES, x.len() as u64)?.put(x)
+        let (h, n) = head(BYTES, x.len() as u64);
+        self.writer.write_all_vectored(&[&h[.. n], x]).map_err(Error::Write)?;
+        Ok(self)
     }
 
     /// Encode a string slice.
     pub fn str(&mut self, x: &str) -> Result<&mut Self, Error<W::Error>> {
-        self.type_len(TEXT, x.len() as u64)?.put(x.as_bytes())
+        let (h, n) = head(TEXT, x.len() as u64);
+        self.writer.write_all_vectored(&[&h[.. n], x.as_bytes()]).map_err(Error::Write)?;
+        Ok(self)
     }
 
     /// Begin encoding an array with `len` elements.
@@ -233,31 +256,211 @@ impl<W: Write> Encoder<W> {
         self.put(&[0x7f])
     }
 
+    /// Encode a map from an iterator of key-value pairs.
+    ///
+    /// The number of map entries is the length of the iterator. No map type
+    /// needs to be constructed, e.g. sorted vectors or slices of pairs can be
+    /// encoded directly:
+    ///
+    /// ```
+    /// use minicbor::Encoder;
+    ///
+    /// let entries = [(1u8, "a"), (2, "b")];
+    ///
+    /// let mut e = Encoder::new(Vec::new());
+    /// e.map_iter(entries.iter().map(|(k, v)| (k, v)))?;
+    /// assert_eq!(b"\xa2\x01\x61a\x02\x61b", e.as_ref().as_slice());
+    ///
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// An error is returned if the iterator yields a different number of
+    /// pairs than its length indicates.
+    pub fn map_iter<I, K, V>(&mut self, iter: I) -> Result<&mut Self, Error<W::Error>>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        I::IntoIter: ExactSizeIterator,
+        K: Encode,
+        V: Encode
+    {
+        let iter = iter.into_iter();
+        let len = iter.len();
+        self.map(len as u64)?;
+        let mut n = 0;
+        for (k, v) in iter {
+            if n == len {
+                return Err(Error::Message("iterator yields more items than its length"))
+            }
+            k.encode(self)?;
+            v.encode(self)?;
+            n += 1
+        }
+        if n != len {
+            return Err(Error::Message("iterator yields fewer items than its length"))
+        }
+        Ok(self)
+    }
+
+    /// Encode a map of indefinite length from an iterator of key-value pairs.
+    ///
+    /// Unlike [`Encoder::map_iter`] this does not need to know the number of
+    /// entries up front.
+    pub fn map_iter_indef<I, K, V>(&mut self, iter: I) -> Result<&mut Self, Error<W::Error>>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Encode,
+        V: Encode
+    {
+        self.begin_map()?;
+        for (k, v) in iter {
+            k.encode(self)?;
+            v.encode(self)?
+        }
+        self.end()
+    }
+
+    /// Write an already encoded CBOR item verbatim.
+    ///
+    /// This allows splicing pre-encoded parts, e.g. cached canonical
+    /// encodings or signed payloads, into the output as a data item, for
+    /// instance as an array element:
+    ///
+    /// ```
+    /// use minicbor::Encoder;
+    ///
+    /// let signed = minicbor::to_vec(("payload", 42u8))?;
+    ///
+    /// let mut e = Encoder::new(Vec::new());
+    /// e.array(2)?.raw(&signed)?.bytes(&[0xab, 0xcd])?;
+    /// assert_eq!(b"\x82\x82\x67payload\x18\x2a\x42\xab\xcd", e.as_ref().as_slice());
+    ///
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// The bytes must be exactly one well-formed CBOR item, otherwise the
+    /// output will not be well-formed either. With debug assertions and
+    /// feature `"alloc"` enabled, this is checked and violations panic.
+    pub fn raw(&mut self, cbor: &[u8]) -> Result<&mut Self, Error<W::Error>> {
+        #[cfg(feature = "alloc")]
+        debug_assert!(is_item(cbor), "raw bytes are not a single well-formed cbor item");
+        self.put(cbor)
+    }
+
     /// Terminate an indefinite collection.
     pub fn end(&mut self) -> Result<&mut Self, Error<W::Error>> {
         self.put(&[0xff])
     }
 
+    /// Flush the [`Write`] impl.
+    ///
+    /// See [`Write::flush`] for details.
+    pub fn flush(&mut self) -> Result<&mut Self, Error<W::Error>> {
+        self.writer.flush().map_err(Error::Write)?;
+        Ok(self)
+    }
+
+    /// Complete encoding and finish the [`Write`] impl.
+    ///
+    /// Unlike [`Encoder::end`], which terminates an indefinite collection,
+    /// this marks the end of all output. See [`Write::finish`] for details.
+    pub fn finish(self) -> Result<(), Error<W::Error>> {
+        self.writer.finish().map_err(Error::Write)
+    }
+
     /// Syntactic sugar for `Ok(())`.
     pub fn ok(&mut self) -> Result<(), Error<W::Error>> {
         Ok(())
     }
 
+    /// Check that `x` is finite if non-finite values are rejected.
+    fn check_finite(&self, x: f64) -> Result<(), Error<W::Error>> {
+        if self.reject_non_finite && !x.is_finite() {
+            return Err(Error::Message("non-finite floating point value"))
+        }
+        Ok(())
+    }
+
     /// Write the encoded byte slice.
-    fn put(&mut self, b: &[u8]) -> Result<&mut Self, Error<W::Error>> {
+    pub(crate) fn put(&mut self, b: &[u8]) -> Result<&mut Self, Error<W::Error>> {
         self.writer.write_all(b).map_err(Error::Write)?;
         Ok(self)
     }
 
     /// Write type and length information.
-    fn type_len(&mut self, t: u8, x: u64) -> Result<&mut Self, Error<W::Error>> {
-        match x {
-            0        ..= 0x17        => self.put(&[t | x as u8]),
-            0x18     ..= 0xff        => self.put(&[t | 24, x as u8]),
-            0x100    ..= 0xffff      => self.put(&[t | 25])?.put(&(x as u16).to_be_bytes()[..]),
-            0x1_0000 ..= 0xffff_ffff => self.put(&[t | 26])?.put(&(x as u32).to_be_bytes()),
-            _                        => self.put(&[t | 27])?.put(&x.to_be_bytes()[..])
-        }
+    pub(crate) fn type_len(&mut self, t: u8, x: u64) -> Result<&mut Self, Error<W::Error>> {
+        let (h, n) = head(t, x);
+        self.put(&h[.. n])
+    }
+
+    /// Write the bignum content of `x`, i.e. its big-endian bytes without
+    /// leading zeros.
+    fn bignum(&mut self, x: u128) -> Result<&mut Self, Error<W::Error>> {
+        let b = x.to_be_bytes();
+        self.bytes(&b[x.leading_zeros() as usize / 8 ..])
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<'p, P, W> Encoder<crate::profile::Writer<'p, P, W>>
+where
+    P: crate::profile::Profile + ?Sized,
+    W: Write
+{
+    /// Construct an `Encoder` whose output follows the given [`Profile`].
+    ///
+    /// Items are normalised while they are written, e.g. arguments use
+    /// their shortest form and floats are encoded as the profile requires
+    /// (cf. [`profile::Writer`]). Only maps and items of indefinite length
+    /// are buffered until they are complete. Items which can not be
+    /// normalised, e.g. because of disallowed tags, result in an error.
+    ///
+    /// ```
+    /// use minicbor::Encoder;
+    /// use minicbor::profile::DCbor;
+    ///
+    /// let mut e = Encoder::with_profile(&DCbor, Vec::new());
+    /// e.map(2)?.str("b")?.f64(1.0)?.str("a")?.f32(1.5)?;
+    /// let cbor = e.into_inner().into_inner()?;
+    /// assert_eq!(b"\xa2\x61a\xf9\x3e\x00\x61b\x01", cbor.as_slice());
+    ///
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// *Requires feature* `"alloc"`.
+    ///
+    /// [`Profile`]: crate::profile::Profile
+    /// [`profile::Writer`]: crate::profile::Writer
+    pub fn with_profile(p: &'p P, writer: W) -> Self {
+        Encoder::new(crate::profile::Writer::new(p, writer))
+    }
+}
+
+/// Assemble the initial byte and shortest argument of a data item.
+///
+/// Returns the head buffer and the number of bytes used.
+pub(crate) const fn head(t: u8, x: u64) -> ([u8; 9], usize) {
+    let (info, n) = match x {
+        0 ..= 0x17                => (x as u8, 0),
+        0x18 ..= 0xff             => (24, 1),
+        0x100 ..= 0xffff          => (25, 2),
+        0x1_0000 ..= 0xffff_ffff  => (26, 4),
+        _                         => (27, 8)
+    };
+    let mut h = [0; 9];
+    h[0] = t | info;
+    let b = x.to_be_bytes();
+    let mut i = 0;
+    while i < n {
+        h[1 + i] = b[8 - n + i];
+        i += 1
+    }
+    (h, n + 1)
+}
+
+
+/// Check that the bytes are exactly one well-formed CBOR item.
+#[cfg(feature = "alloc")]
+fn is_item(b: &[u8]) -> bool {
+    let mut d = crate::Decoder::new(b);
+    d.skip().is_ok() && d.position() == b.len()
+}
@@ -5,13 +5,26 @@ use core::fmt;
 #[derive(Debug, Clone)]
 pub enum Error<W> {
     /// Error writing bytes to a `Write` impl.
-    Write(W)
+    Write(W),
+    /// Generic error message.
+    Message(&'static str)
 }
 
 impl<W: fmt::Display> fmt::Display for Error<W> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Error::Write(e) => write!(f, "write error: {}", e),
+            Error::Write(e)   => write!(f, "write error: {}", e),
+            Error::Message(m) => f.write_str(m)
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<W: defmt::Format> defmt::Format for Error<W> {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Error::Write(e)   => defmt::write!(f, "write error: {}", e),
+            Error::Message(m) => defmt::write!(f, "{=str}", m)
         }
     }
 }
@@ -20,7 +33,8 @@ impl<W: fmt::Display> fmt::Display for Error<W> {
 impl<W: std::error::Error + 'static> std::error::Error for Error<W> {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            Error::Write(e) => Some(e)
+            Error::Write(e)   => Some(e),
+            Error::Message(_) => None
         }
     }
 }
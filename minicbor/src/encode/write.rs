@@ -4,11 +4,46 @@
 //! are made impls of [`Write`] too.
 
 /// A type that accepts byte slices for writing.
+///
+/// Writers which buffer or transform their input, e.g. by compressing or
+/// hashing it, may need to do some work after the last byte has been
+/// written. [`Write::flush`] and [`Write::finish`] give them a defined
+/// point to do so.
 pub trait Write {
     type Error;
 
     /// Write the whole byte slice.
     fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error>;
+
+    /// Write all byte slices in order.
+    ///
+    /// The default implementation calls [`Write::write_all`] for every
+    /// slice. Impls which support vectored I/O may write them at once.
+    fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> Result<(), Self::Error> {
+        for b in bufs {
+            self.write_all(b)?
+        }
+        Ok(())
+    }
+
+    /// Pass all buffered bytes on to the final destination.
+    ///
+    /// The default implementation does nothing.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Complete writing.
+    ///
+    /// This is called after the last byte has been written. Writers which
+    /// have to emit trailing data or release resources do so here. The
+    /// default implementation calls [`Write::flush`].
+    fn finish(mut self) -> Result<(), Self::Error>
+    where
+        Self: Sized
+    {
+        self.flush()
+    }
 }
 
 #[cfg(feature = "std")]
@@ -18,6 +53,45 @@ impl<W: std::io::Write> Write for W {
     fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
         std::io::Write::write_all(self, buf)
     }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::Write::flush(self)
+    }
+
+    fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> std::io::Result<()> {
+        use std::io::{ErrorKind, IoSlice};
+
+        // The current slice and the offset of its first unwritten byte.
+        let (mut i, mut offset) = (0, 0);
+        loop {
+            // Skip empty slices, so that we stop once only those are left.
+            while i < bufs.len() && offset == bufs[i].len() {
+                i += 1;
+                offset = 0
+            }
+            if i == bufs.len() {
+                return Ok(())
+            }
+            let mut slices = [IoSlice::new(&[]); 4];
+            let mut k = 0;
+            for b in core::iter::once(&bufs[i][offset ..]).chain(bufs[i + 1 ..].iter().copied()).take(4) {
+                slices[k] = IoSlice::new(b);
+                k += 1
+            }
+            let mut n = match self.write_vectored(&slices[.. k]) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(n) => n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e)
+            };
+            while i < bufs.len() && n >= bufs[i].len() - offset {
+                n -= bufs[i].len() - offset;
+                offset = 0;
+                i += 1
+            }
+            offset += n
+        }
+    }
 }
 
 #[cfg(not(feature = "std"))]
@@ -27,6 +101,14 @@ impl<W: Write + ?Sized> Write for &mut W {
     fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
         (**self).write_all(buf)
     }
+
+    fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> Result<(), Self::Error> {
+        (**self).write_all_vectored(bufs)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
 }
 
 #[cfg(not(feature = "std"))]
@@ -55,6 +137,461 @@ impl Write for alloc::vec::Vec<u8> {
     }
 }
 
+/// A [`Write`] impl which discards all bytes and only counts them.
+///
+/// This allows computing the length of an encoding without buffering it.
+/// If the feature `std` is present `LenWriter` is a `std::io::Write` impl.
+///
+/// ```
+/// use minicbor::{Encoder, encode::write::LenWriter};
+///
+/// let mut e = Encoder::new(LenWriter::new());
+/// e.encode(["hello", "world"])?;
+/// assert_eq!(13, e.into_inner().len());
+///
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LenWriter(usize);
+
+impl LenWriter {
+    /// Create a new `LenWriter` with a count of 0.
+    pub fn new() -> Self {
+        LenWriter(0)
+    }
+
+    /// Get the number of bytes written so far.
+    pub fn len(&self) -> usize {
+        self.0
+    }
+
+    /// Have no bytes been written so far?
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::io::Write for LenWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 = self.0.saturating_add(buf.len());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Write for LenWriter {
+    type Error = core::convert::Infallible;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.0 = self.0.saturating_add(buf.len());
+        Ok(())
+    }
+}
+
+/// A [`Write`] impl over uninitialised memory.
+///
+/// Bytes are written to the beginning of a `&mut [MaybeUninit<u8>]`, e.g. a
+/// DMA or pool buffer, without zeroing it first. Only the prefix which has
+/// been written is ever exposed as initialised bytes. If the feature `std` is
+/// present `UninitWriter` is a `std::io::Write` impl, which fails with
+/// `ErrorKind::WriteZero` if the slice is full, otherwise writing fails with
+/// [`EndOfSlice`] (cf. [`crate::to_slice_uninit`]).
+///
+/// ```
+/// use core::mem::MaybeUninit;
+/// use minicbor::{Encoder, encode::write::UninitWriter};
+///
+/// let mut buf = [MaybeUninit::uninit(); 16];
+/// let mut e = Encoder::new(UninitWriter::new(&mut buf));
+/// e.encode(["hello", "world"])?;
+/// assert_eq!(13, e.into_inner().into_initialized().len());
+///
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct UninitWriter<'a> {
+    buf: &'a mut [core::mem::MaybeUninit<u8>],
+    len: usize
+}
+
+impl<'a> UninitWriter<'a> {
+    /// Create a new `UninitWriter` which writes to the beginning of `buf`.
+    pub fn new(buf: &'a mut [core::mem::MaybeUninit<u8>]) -> Self {
+        UninitWriter { buf, len: 0 }
+    }
+
+    /// Get the number of bytes written so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Have no bytes been written so far?
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the bytes written so far.
+    pub fn initialized(&self) -> &[u8] {
+        // Safety: The first `len` elements have been initialised by
+        // `put` and `MaybeUninit<u8>` has the same layout as `u8`.
+        unsafe { core::slice::from_raw_parts(self.buf.as_ptr().cast(), self.len) }
+    }
+
+    /// Get back the bytes written, i.e. the initialised prefix of the slice.
+    pub fn into_initialized(self) -> &'a mut [u8] {
+        // Safety: See `initialized`.
+        unsafe { core::slice::from_raw_parts_mut(self.buf.as_mut_ptr().cast(), self.len) }
+    }
+
+    /// Write as many bytes of `buf` as fit and return their number.
+    fn put(&mut self, buf: &[u8]) -> usize {
+        let n = buf.len().min(self.buf.len() - self.len);
+        for (d, s) in self.buf[self.len .. self.len + n].iter_mut().zip(buf) {
+            *d = core::mem::MaybeUninit::new(*s)
+        }
+        self.len += n;
+        n
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::io::Write for UninitWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Ok(self.put(buf))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Write for UninitWriter<'_> {
+    type Error = EndOfSlice;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        if self.buf.len() - self.len < buf.len() {
+            return Err(EndOfSlice(()))
+        }
+        self.put(buf);
+        Ok(())
+    }
+}
+
+/// A [`Write`] impl which writes every byte slice to two inner writers.
+///
+/// This allows e.g. mirroring or hashing an encoding while it is written.
+/// If the feature `std` is present `Tee` is a `std::io::Write` impl if both
+/// inner writers are.
+#[derive(Debug, Default, Clone)]
+pub struct Tee<A, B>(A, B);
+
+impl<A, B> Tee<A, B> {
+    /// Create a new `Tee` writing to `a` and then to `b`.
+    pub fn new(a: A, b: B) -> Self {
+        Tee(a, b)
+    }
+
+    /// Get references to the inner writers.
+    pub fn get_ref(&self) -> (&A, &B) {
+        (&self.0, &self.1)
+    }
+
+    /// Get mutable references to the inner writers.
+    pub fn get_mut(&mut self) -> (&mut A, &mut B) {
+        (&mut self.0, &mut self.1)
+    }
+
+    /// Get back the inner writers.
+    pub fn into_inner(self) -> (A, B) {
+        (self.0, self.1)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A: std::io::Write, B: std::io::Write> std::io::Write for Tee<A, B> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write_all(buf)?;
+        self.1.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()?;
+        self.1.flush()
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<A: Write, B: Write> Write for Tee<A, B> {
+    type Error = TeeError<A::Error, B::Error>;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.0.write_all(buf).map_err(TeeError::First)?;
+        self.1.write_all(buf).map_err(TeeError::Second)
+    }
+
+    fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> Result<(), Self::Error> {
+        self.0.write_all_vectored(bufs).map_err(TeeError::First)?;
+        self.1.write_all_vectored(bufs).map_err(TeeError::Second)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.0.flush().map_err(TeeError::First)?;
+        self.1.flush().map_err(TeeError::Second)
+    }
+
+    fn finish(self) -> Result<(), Self::Error> {
+        self.0.finish().map_err(TeeError::First)?;
+        self.1.finish().map_err(TeeError::Second)
+    }
+}
+
+/// The error of a [`Tee`] writer.
+#[cfg(not(feature = "std"))]
+#[derive(Debug)]
+pub enum TeeError<A, B> {
+    /// Writing to the first inner writer failed.
+    First(A),
+    /// Writing to the second inner writer failed.
+    Second(B)
+}
+
+#[cfg(not(feature = "std"))]
+impl<A: core::fmt::Display, B: core::fmt::Display> core::fmt::Display for TeeError<A, B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            TeeError::First(e)  => write!(f, "first writer: {}", e),
+            TeeError::Second(e) => write!(f, "second writer: {}", e)
+        }
+    }
+}
+
+/// A [`Write`] impl which collects bytes in a fixed-size buffer before
+/// writing them to an inner writer.
+///
+/// Encoding produces many small writes, which are slow if every one of them
+/// is passed on to e.g. a socket or file. `Buffered` passes bytes on only
+/// when its buffer of `N` bytes is full, except for byte slices which do not
+/// fit into the buffer, which are written directly. Vectored writes, e.g. of
+/// a string's head and content, which do not fit into the buffer are passed
+/// on together with the buffered bytes in a single vectored write of the
+/// inner writer.
+///
+/// Buffered bytes must be written explicitly with `flush` or `finish`, or by
+/// getting back the inner writer with [`Buffered::into_inner`]; they are
+/// discarded when a `Buffered` is dropped. `flush` also flushes the inner
+/// writer. If the feature `std` is present `Buffered` is a `std::io::Write`
+/// impl if the inner writer is.
+///
+/// ```
+/// use minicbor::{Encoder, encode::write::Buffered};
+///
+/// let mut e = Encoder::new(Buffered::<_, 64>::new(Vec::new()));
+/// e.encode(["hello", "world"])?;
+/// let w = e.into_inner();
+/// assert_eq!(13, w.buffer().len());
+/// let v = w.into_inner()?;
+/// assert_eq!(minicbor::to_vec(["hello", "world"])?, v);
+///
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Buffered<W, const N: usize = 512> {
+    writer: W,
+    buf: [u8; N],
+    len: usize
+}
+
+impl<W, const N: usize> Buffered<W, N> {
+    /// Create a new `Buffered` writer around the given inner writer.
+    pub fn new(w: W) -> Self {
+        Buffered { writer: w, buf: [0; N], len: 0 }
+    }
+
+    /// Get the bytes which have been buffered but not yet written.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf[.. self.len]
+    }
+
+    /// Get a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Get a mutable reference to the inner writer.
+    ///
+    /// Writing to the inner writer directly bypasses the buffered bytes.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+}
+
+impl<W: Write, const N: usize> Buffered<W, N> {
+    /// Write all buffered bytes and get back the inner writer.
+    pub fn into_inner(mut self) -> Result<W, W::Error> {
+        self.write_buffer()?;
+        Ok(self.writer)
+    }
+
+    /// Write all buffered bytes to the inner writer.
+    fn write_buffer(&mut self) -> Result<(), W::Error> {
+        if self.len > 0 {
+            self.writer.write_all(&self.buf[.. self.len])?;
+            self.len = 0
+        }
+        Ok(())
+    }
+
+    /// Buffer the given bytes or write them to the inner writer.
+    fn buffered_write(&mut self, buf: &[u8]) -> Result<(), W::Error> {
+        if buf.len() > N - self.len {
+            self.write_buffer()?
+        }
+        if buf.len() >= N {
+            self.writer.write_all(buf)
+        } else {
+            self.buf[self.len .. self.len + buf.len()].copy_from_slice(buf);
+            self.len += buf.len();
+            Ok(())
+        }
+    }
+
+    /// Buffer the given byte slices or write them to the inner writer,
+    /// preceded by the buffered bytes.
+    fn buffered_write_vectored(&mut self, bufs: &[&[u8]]) -> Result<(), W::Error> {
+        let total = bufs.iter().map(|b| b.len()).sum::<usize>();
+        if total <= N - self.len {
+            for b in bufs {
+                self.buf[self.len .. self.len + b.len()].copy_from_slice(b);
+                self.len += b.len()
+            }
+            return Ok(())
+        }
+        if bufs.len() > 4 {
+            for b in bufs {
+                self.buffered_write(b)?
+            }
+            return Ok(())
+        }
+        let mut all: [&[u8]; 5] = [&[]; 5];
+        all[0] = &self.buf[.. self.len];
+        all[1 .. 1 + bufs.len()].copy_from_slice(bufs);
+        self.writer.write_all_vectored(&all[.. 1 + bufs.len()])?;
+        self.len = 0;
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<W: Write, const N: usize> Buffered<W, N> {
+    /// Write all buffered bytes to the inner writer and flush it.
+    pub fn flush(&mut self) -> Result<(), W::Error> {
+        self.write_buffer()?;
+        self.writer.flush()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write, const N: usize> std::io::Write for Buffered<W, N> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffered_write(buf)?;
+        Ok(buf.len())
+    }
+
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> std::io::Result<usize> {
+        let mut slices: [&[u8]; 4] = [&[]; 4];
+        let mut k = 0;
+        for b in bufs.iter().take(4) {
+            slices[k] = b;
+            k += 1
+        }
+        self.buffered_write_vectored(&slices[.. k])?;
+        Ok(slices[.. k].iter().map(|b| b.len()).sum())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.write_buffer()?;
+        self.writer.flush()
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<W: Write, const N: usize> Write for Buffered<W, N> {
+    type Error = W::Error;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.buffered_write(buf)
+    }
+
+    fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> Result<(), Self::Error> {
+        self.buffered_write_vectored(bufs)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Buffered::flush(self)
+    }
+
+    fn finish(mut self) -> Result<(), Self::Error> {
+        self.write_buffer()?;
+        self.writer.finish()
+    }
+}
+
+/// A [`Write`] impl which feeds all bytes into a hash function.
+///
+/// If the feature `std` is present `DigestWriter` is a `std::io::Write` impl.
+///
+/// *Requires feature* `"digest"`.
+#[cfg(feature = "digest")]
+#[derive(Debug, Default, Clone)]
+pub struct DigestWriter<D>(D);
+
+#[cfg(feature = "digest")]
+impl<D> DigestWriter<D> {
+    /// Create a new `DigestWriter` updating the given digest.
+    pub fn new(d: D) -> Self {
+        DigestWriter(d)
+    }
+
+    /// Get a reference to the inner digest.
+    pub fn get_ref(&self) -> &D {
+        &self.0
+    }
+
+    /// Get back the inner digest.
+    pub fn into_inner(self) -> D {
+        self.0
+    }
+}
+
+#[cfg(all(feature = "digest", feature = "std"))]
+impl<D: digest::Update> std::io::Write for DigestWriter<D> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "digest", not(feature = "std")))]
+impl<D: digest::Update> Write for DigestWriter<D> {
+    type Error = core::convert::Infallible;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.0.update(buf);
+        Ok(())
+    }
+}
+
 /// An error indicating the end of a slice.
 #[derive(Debug)]
 pub struct EndOfSlice(());
@@ -65,3 +602,10 @@ impl core::fmt::Display for EndOfSlice {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for EndOfSlice {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "end of slice")
+    }
+}
+
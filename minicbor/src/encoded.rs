@@ -0,0 +1,184 @@
+//! Embedded CBOR and expected later encodings (cf. [RFC 8949, section 3.4.5][1]).
+//!
+//! Several formats, e.g. COSE, nest a CBOR item in a byte string with tag 24,
+//! so that it can be processed (e.g. hashed or signed) as a whole:
+//!
+//! - [`EncodedCbor`] encodes its value as a tag-24 byte string containing
+//!   the nested CBOR and decodes the value from such a byte string.
+//! - [`RawCbor`] exposes the nested CBOR bytes of a tag-24 byte string
+//!   without decoding them.
+//!
+//! ```
+//! use minicbor::encoded::{EncodedCbor, RawCbor};
+//!
+//! let cbor = minicbor::to_vec(EncodedCbor(("hello", 42)))?;
+//! assert_eq!(b"\xd8\x18\x49\x82\x65hello\x18\x2a", cbor.as_slice());
+//!
+//! let EncodedCbor(x) = minicbor::decode(&cbor)?;
+//! assert_eq!(("hello", 42), x);
+//!
+//! let raw: RawCbor = minicbor::decode(&cbor)?;
+//! assert_eq!(b"\x82\x65hello\x18\x2a", raw.as_bytes());
+//! assert_eq!(("hello", 42), raw.decode_item()?);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Tags 21 to 23 indicate that byte strings in the tagged item are expected
+//! to be converted to base64url, base64 or base16 if the item is converted
+//! to JSON (cf. module `json`). [`Base64Url`], [`Base64`] and [`Hex`] add
+//! these tags when encoding and require them when decoding. The diagnostic
+//! notation of [`crate::display`] shows such byte strings as `b64'...'` or
+//! `h'...'` respectively.
+//!
+//! ```
+//! use minicbor::encoded::Base64Url;
+//! use minicbor::bytes::ByteSlice;
+//!
+//! let b: &ByteSlice = b"hello".as_slice().into();
+//! let cbor = minicbor::to_vec(Base64Url(b))?;
+//! assert_eq!(b"\xd5\x45hello", cbor.as_slice());
+//! assert_eq!("21(b64'aGVsbG8')", minicbor::display(&cbor).to_string());
+//!
+//! let Base64Url(x): Base64Url<&ByteSlice> = minicbor::decode(&cbor)?;
+//! assert_eq!(b"hello", &x[..]);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-3.4.5
+
+use crate::BYTES;
+use crate::data::Tag;
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+use crate::encode::write::LenWriter;
+
+/// A value which is encoded as nested CBOR in a byte string with tag 24.
+///
+/// Encoding does not allocate. The value is encoded twice, once to determine
+/// the length of the byte string and once to write the nested CBOR. Decoding
+/// requires the byte string to contain exactly one CBOR item.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EncodedCbor<T>(pub T);
+
+impl<T> EncodedCbor<T> {
+    /// Get back the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: Encode> Encode for EncodedCbor<T> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        let len = {
+            let mut n = Encoder::new(LenWriter::new());
+            n.set_reject_non_finite(e.reject_non_finite());
+            self.0.encode(&mut n).map_err(|err| match err {
+                encode::Error::Write(_)   => encode::Error::Message("failed to determine length"),
+                encode::Error::Message(m) => encode::Error::Message(m)
+            })?;
+            n.into_inner().len()
+        };
+        e.tag(Tag::Cbor)?.type_len(BYTES, len as u64)?;
+        self.0.encode(e)
+    }
+}
+
+impl<'b, T: Decode<'b>> Decode<'b> for EncodedCbor<T> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        let raw = RawCbor::decode(d)?;
+        raw.decode_item().map(EncodedCbor)
+    }
+}
+
+/// The nested CBOR bytes of a byte string with tag 24.
+///
+/// Decoding checks that the bytes are exactly one well-formed CBOR item,
+/// encoding writes them as they are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RawCbor<'b>(&'b [u8]);
+
+impl<'b> RawCbor<'b> {
+    /// Create a `RawCbor` value from the given bytes.
+    ///
+    /// Fails if the bytes are not exactly one well-formed CBOR item.
+    pub fn new(cbor: &'b [u8]) -> Result<Self, decode::Error> {
+        let mut d = Decoder::new(cbor);
+        crate::visit::check_item(&mut d)?;
+        if d.position() < cbor.len() {
+            return Err(decode::Error::TrailingBytes(d.position()))
+        }
+        Ok(RawCbor(cbor))
+    }
+
+    /// Get the nested CBOR bytes.
+    pub fn as_bytes(&self) -> &'b [u8] {
+        self.0
+    }
+
+    /// Decode the nested CBOR item.
+    pub fn decode_item<T: Decode<'b>>(&self) -> Result<T, decode::Error> {
+        crate::decode_exact(self.0)
+    }
+}
+
+impl Encode for RawCbor<'_> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        e.tag(Tag::Cbor)?.bytes(self.0)?.ok()
+    }
+}
+
+impl<'b> Decode<'b> for RawCbor<'b> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        if d.tag()? != Tag::Cbor {
+            return Err(decode::Error::Message("expected tag 24 for encoded cbor"))
+        }
+        RawCbor::new(d.bytes()?)
+    }
+}
+
+macro_rules! expected_encoding {
+    ($(#[$doc:meta])* $name:ident, $tag:expr, $msg:expr) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name<T>(pub T);
+
+        impl<T> $name<T> {
+            /// Get back the inner value.
+            pub fn into_inner(self) -> T {
+                self.0
+            }
+        }
+
+        impl<T: Encode> Encode for $name<T> {
+            fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+                e.tag($tag)?.encode(&self.0)?.ok()
+            }
+        }
+
+        impl<'b, T: Decode<'b>> Decode<'b> for $name<T> {
+            fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+                if d.tag()? != $tag {
+                    return Err(decode::Error::Message($msg))
+                }
+                T::decode(d).map($name)
+            }
+        }
+    }
+}
+
+expected_encoding! {
+    /// A value whose byte strings are expected to be converted to base64url (tag 21).
+    Base64Url, Tag::ToBase64Url, "expected tag 21 for base64url encoding"
+}
+
+expected_encoding! {
+    /// A value whose byte strings are expected to be converted to base64 (tag 22).
+    Base64, Tag::ToBase64, "expected tag 22 for base64 encoding"
+}
+
+expected_encoding! {
+    /// A value whose byte strings are expected to be converted to base16 (tag 23).
+    Hex, Tag::ToBase16, "expected tag 23 for base16 encoding"
+}
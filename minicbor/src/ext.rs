@@ -0,0 +1,110 @@
+//! Support for types of third-party crates.
+//!
+//! The `Encode` and `Decode` impls of each integration require the cargo
+//! feature of the same name as the crate, e.g. `"chrono"`.
+
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+
+#[cfg(feature = "arrayvec")]
+pub mod arrayvec;
+
+#[cfg(feature = "bigdecimal")]
+pub mod bigdecimal;
+
+#[cfg(feature = "bumpalo")]
+pub mod bumpalo;
+
+#[cfg(feature = "bytes")]
+pub mod bytes;
+
+#[cfg(feature = "ciborium")]
+pub mod ciborium;
+
+#[cfg(feature = "chrono")]
+pub mod chrono;
+
+#[cfg(feature = "compact_str")]
+pub mod compact_str;
+
+#[cfg(feature = "hashbrown")]
+pub mod hashbrown;
+
+#[cfg(feature = "heapless")]
+pub mod heapless;
+
+#[cfg(feature = "ipnet")]
+pub mod ipnet;
+
+#[cfg(feature = "num-bigint")]
+pub mod num_bigint;
+
+#[cfg(feature = "quickcheck")]
+pub mod quickcheck;
+
+#[cfg(feature = "rayon")]
+pub mod rayon;
+
+#[cfg(feature = "rust_decimal")]
+pub mod rust_decimal;
+
+#[cfg(feature = "semver")]
+pub mod semver;
+
+#[cfg(feature = "smallvec")]
+pub mod smallvec;
+
+#[cfg(feature = "smol_str")]
+pub mod smol_str;
+
+#[cfg(feature = "time")]
+pub mod time;
+
+#[cfg(feature = "url")]
+pub mod url;
+
+/// Get the bits of an `f16` with the exact value of the given non-NaN `f32`.
+#[cfg(feature = "alloc")]
+pub(crate) fn f16_bits(x: f32) -> Option<u16> {
+    let b = x.to_bits();
+    let sign = ((b >> 16) & 0x8000) as u16;
+    let exp = ((b >> 23) & 0xff) as i32;
+    let man = b & 0x7f_ffff;
+    if exp == 0xff {
+        return Some(sign | 0x7c00)
+    }
+    if exp == 0 {
+        return if man == 0 { Some(sign) } else { None }
+    }
+    let e = exp - 127;
+    if (-14 ..= 15).contains(&e) {
+        if man & 0x1fff != 0 {
+            return None
+        }
+        return Some(sign | ((e + 15) as u16) << 10 | (man >> 13) as u16)
+    }
+    if (-24 .. -14).contains(&e) {
+        let full = 0x80_0000 | man;
+        let shift = (-1 - e) as u32;
+        if full & ((1 << shift) - 1) != 0 {
+            return None
+        }
+        return Some(sign | (full >> shift) as u16)
+    }
+    None
+}
+
+/// Convert the bits of an `f16` to an `f64`.
+pub(crate) fn f16_to_f64(h: u16) -> f64 {
+    let sign = u64::from(h >> 15) << 63;
+    let exp = u64::from((h >> 10) & 0x1f);
+    let man = u64::from(h & 0x3ff);
+    match exp {
+        0  => {
+            let x = man as f64 * 5.960_464_477_539_063e-8; // 2^-24
+            if sign == 0 { x } else { -x }
+        }
+        31 => f64::from_bits(sign | 0x7ff0_0000_0000_0000 | man << 42),
+        _  => f64::from_bits(sign | (exp + 1008) << 52 | man << 42)
+    }
+}
@@ -0,0 +1,286 @@
+//! Generation of CBOR items with the [`arbitrary`][::arbitrary] crate.
+//!
+//! [`Cbor`] and [`CanonicalCbor`] implement [`Arbitrary`] and hold the
+//! bytes of a single well-formed CBOR item, which makes them suitable inputs
+//! for structured fuzzing of decoders and round-trip tests.
+//!
+//! - [`Cbor`] covers the whole syntax, including indefinite lengths and
+//!   arguments encoded in more bytes than necessary.
+//! - [`CanonicalCbor`] only produces items in the deterministic encoding of
+//!   [RFC 8949, section 4.2.1][1], i.e. with preferred serialisation of
+//!   arguments and floats, definite lengths and map keys sorted and unique.
+//!
+//! Items are nested at most [`MAX_DEPTH`] levels deep. Negative integers
+//! are limited to the range of `i64`, like in [`Decoder`][crate::Decoder].
+//!
+//! *Requires feature* `"arbitrary"`.
+//!
+//! With feature `"derive-arbitrary"`, `#[derive(minicbor::Arbitrary)]`
+//! implements [`Arbitrary`] for types which derive [`Decode`][crate::Decode]
+//! (see [`minicbor_derive`][2]), so that round-trip fuzz targets can be
+//! written for every message type:
+//!
+//! ```
+//! # #[cfg(feature = "derive-arbitrary")] {
+//! use arbitrary::{Arbitrary, Unstructured};
+//! use minicbor::{Decode, Encode};
+//!
+//! #[derive(Debug, PartialEq, Encode, Decode, minicbor::Arbitrary)]
+//! enum Command {
+//!     #[n(0)] Stop,
+//!     #[n(1)] Move { #[n(0)] x: i32, #[n(1)] y: Option<i32> }
+//! }
+//!
+//! let mut u = Unstructured::new(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+//! let c = Command::arbitrary(&mut u).unwrap();
+//! let v: Command = minicbor::decode(&minicbor::to_vec(&c).unwrap()).unwrap();
+//! assert_eq!(c, v);
+//! # }
+//! ```
+//!
+//! ```
+//! use arbitrary::{Arbitrary, Unstructured};
+//! use minicbor::encoded::RawCbor;
+//! use minicbor::ext::arbitrary::Cbor;
+//!
+//! let mut u = Unstructured::new(b"\x06\x02\x01\x03\x02");
+//! let Cbor(bytes) = Cbor::arbitrary(&mut u)?;
+//! assert!(RawCbor::new(&bytes).is_ok());
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-4.2.1
+//! [2]: https://docs.rs/minicbor-derive
+
+#[doc(no_inline)]
+pub use ::arbitrary::{Arbitrary, Result, Unstructured};
+
+use alloc::vec::Vec;
+use crate::ext::f16_bits;
+use crate::{UNSIGNED, SIGNED, BYTES, TEXT, ARRAY, MAP, TAGGED, SIMPLE, BREAK};
+
+/// The max. nesting depth of generated items.
+pub const MAX_DEPTH: usize = 16;
+
+/// The bytes of an arbitrary well-formed CBOR item.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cbor(pub Vec<u8>);
+
+/// The bytes of an arbitrary CBOR item in deterministic encoding.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CanonicalCbor(pub Vec<u8>);
+
+impl<'a> Arbitrary<'a> for Cbor {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut v = Vec::new();
+        Generator { u, canonical: false }.item(&mut v, 0)?;
+        Ok(Cbor(v))
+    }
+}
+
+impl<'a> Arbitrary<'a> for CanonicalCbor {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut v = Vec::new();
+        Generator { u, canonical: true }.item(&mut v, 0)?;
+        Ok(CanonicalCbor(v))
+    }
+}
+
+struct Generator<'u, 'a> {
+    u: &'u mut Unstructured<'a>,
+    canonical: bool
+}
+
+impl<'u, 'a> Generator<'u, 'a> {
+    /// Generate a single data item.
+    fn item(&mut self, buf: &mut Vec<u8>, depth: usize) -> Result<()> {
+        let max = if depth < MAX_DEPTH { 8 } else { 5 };
+        match self.u.int_in_range(0 ..= max)? {
+            0 => {
+                let n = self.u.arbitrary()?;
+                self.head(buf, UNSIGNED, n)
+            }
+            1 => {
+                let n = self.u.int_in_range(0 ..= i64::MAX as u64)?;
+                self.head(buf, SIGNED, n)
+            }
+            2 => self.simple(buf),
+            3 => self.float(buf),
+            4 => self.string(buf, BYTES),
+            5 => self.string(buf, TEXT),
+            6 => {
+                let n = self.u.arbitrary()?;
+                self.head(buf, TAGGED, n)?;
+                self.item(buf, depth + 1)
+            }
+            7 => {
+                let n = self.u.int_in_range(0 ..= 4)?;
+                let indef = self.indefinite(buf, ARRAY, n)?;
+                for _ in 0 .. n {
+                    self.item(buf, depth + 1)?
+                }
+                if indef {
+                    buf.push(BREAK)
+                }
+                Ok(())
+            }
+            _ => self.map(buf, depth)
+        }
+    }
+
+    /// Generate a map.
+    ///
+    /// Deterministically encoded maps have unique keys, sorted by their
+    /// bytewise lexicographic order.
+    fn map(&mut self, buf: &mut Vec<u8>, depth: usize) -> Result<()> {
+        let n = self.u.int_in_range(0 ..= 4)?;
+        if !self.canonical {
+            let indef = self.indefinite(buf, MAP, n)?;
+            for _ in 0 .. 2 * n {
+                self.item(buf, depth + 1)?
+            }
+            if indef {
+                buf.push(BREAK)
+            }
+            return Ok(())
+        }
+        let mut entries = Vec::with_capacity(n as usize);
+        for _ in 0 .. n {
+            let mut k = Vec::new();
+            let mut v = Vec::new();
+            self.item(&mut k, depth + 1)?;
+            self.item(&mut v, depth + 1)?;
+            entries.push((k, v))
+        }
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries.dedup_by(|a, b| a.0 == b.0);
+        self.head(buf, MAP, entries.len() as u64)?;
+        for (k, v) in entries {
+            buf.extend_from_slice(&k);
+            buf.extend_from_slice(&v)
+        }
+        Ok(())
+    }
+
+    /// Generate a byte or text string, possibly of indefinite length.
+    fn string(&mut self, buf: &mut Vec<u8>, major: u8) -> Result<()> {
+        if self.canonical || !self.u.arbitrary()? {
+            return self.chunk(buf, major)
+        }
+        buf.push(major | 31);
+        for _ in 0 .. self.u.int_in_range(0 ..= 3)? {
+            self.chunk(buf, major)?
+        }
+        buf.push(BREAK);
+        Ok(())
+    }
+
+    /// Generate a definite-length byte or text string.
+    fn chunk(&mut self, buf: &mut Vec<u8>, major: u8) -> Result<()> {
+        let b = if major == TEXT {
+            <&str>::arbitrary(self.u)?.as_bytes()
+        } else {
+            <&[u8]>::arbitrary(self.u)?
+        };
+        self.head(buf, major, b.len() as u64)?;
+        buf.extend_from_slice(b);
+        Ok(())
+    }
+
+    /// Generate a simple value, including `false`, `true`, `null` and
+    /// `undefined`.
+    fn simple(&mut self, buf: &mut Vec<u8>) -> Result<()> {
+        match self.u.int_in_range(0u8 ..= 23 + 224)? {
+            n @ 0 ..= 23 => buf.push(SIMPLE | n),
+            n            => buf.extend_from_slice(&[SIMPLE | 24, n + 8])
+        }
+        Ok(())
+    }
+
+    /// Generate a half, single or double precision float.
+    ///
+    /// Deterministically encoded floats use the shortest encoding which
+    /// preserves the value and NaN is always encoded as `0xf97e00`.
+    fn float(&mut self, buf: &mut Vec<u8>) -> Result<()> {
+        let x = match self.u.int_in_range(0 ..= 2)? {
+            0 => {
+                let mut h = u16::arbitrary(self.u)?;
+                if self.canonical && h & 0x7c00 == 0x7c00 && h & 0x3ff != 0 {
+                    h = 0x7e00
+                }
+                buf.push(SIMPLE | 25);
+                buf.extend_from_slice(&h.to_be_bytes());
+                return Ok(())
+            }
+            1 => f64::from(f32::arbitrary(self.u)?),
+            _ => f64::arbitrary(self.u)?
+        };
+        if !self.canonical {
+            if self.u.arbitrary()? && !x.is_nan() && f64::from(x as f32) == x {
+                buf.push(SIMPLE | 26);
+                buf.extend_from_slice(&(x as f32).to_be_bytes())
+            } else {
+                buf.push(SIMPLE | 27);
+                buf.extend_from_slice(&x.to_be_bytes())
+            }
+            return Ok(())
+        }
+        if x.is_nan() {
+            buf.extend_from_slice(&[SIMPLE | 25, 0x7e, 0]);
+        } else if f64::from(x as f32) != x {
+            buf.push(SIMPLE | 27);
+            buf.extend_from_slice(&x.to_be_bytes())
+        } else if let Some(h) = f16_bits(x as f32) {
+            buf.push(SIMPLE | 25);
+            buf.extend_from_slice(&h.to_be_bytes())
+        } else {
+            buf.push(SIMPLE | 26);
+            buf.extend_from_slice(&(x as f32).to_be_bytes())
+        }
+        Ok(())
+    }
+
+    /// Write the initial byte of an array or map, which is of indefinite
+    /// length if `true` is returned.
+    fn indefinite(&mut self, buf: &mut Vec<u8>, major: u8, len: u64) -> Result<bool> {
+        if !self.canonical && self.u.arbitrary()? {
+            buf.push(major | 31);
+            return Ok(true)
+        }
+        self.head(buf, major, len)?;
+        Ok(false)
+    }
+
+    /// Write the initial byte and argument of a data item.
+    ///
+    /// Unless canonical, the argument may be encoded in more bytes than
+    /// necessary.
+    fn head(&mut self, buf: &mut Vec<u8>, major: u8, n: u64) -> Result<()> {
+        let min = match n {
+            0        ..= 0x17        => 0,
+            0x18     ..= 0xff        => 1,
+            0x100    ..= 0xffff      => 2,
+            0x1_0000 ..= 0xffff_ffff => 3,
+            _                        => 4
+        };
+        let w = if self.canonical { min } else { self.u.int_in_range(min ..= 4)? };
+        match w {
+            0 => buf.push(major | n as u8),
+            1 => buf.extend_from_slice(&[major | 24, n as u8]),
+            2 => {
+                buf.push(major | 25);
+                buf.extend_from_slice(&(n as u16).to_be_bytes())
+            }
+            3 => {
+                buf.push(major | 26);
+                buf.extend_from_slice(&(n as u32).to_be_bytes())
+            }
+            _ => {
+                buf.push(major | 27);
+                buf.extend_from_slice(&n.to_be_bytes())
+            }
+        }
+        Ok(())
+    }
+}
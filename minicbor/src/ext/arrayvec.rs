@@ -0,0 +1,58 @@
+//! Support for [`ArrayVec`] and [`ArrayString`] of the [`arrayvec`][::arrayvec]
+//! crate.
+//!
+//! `ArrayVec` is encoded as an array and `ArrayString` as a text string.
+//! Decoding data which exceeds the capacity results in an error.
+//!
+//! *Requires feature* `"arrayvec"`.
+//!
+//! ```
+//! use arrayvec::{ArrayString, ArrayVec};
+//!
+//! let v = ArrayVec::from([1u8, 2, 3]);
+//!
+//! let cbor = minicbor::to_vec(&v)?;
+//! assert_eq!(b"\x83\x01\x02\x03", cbor.as_slice());
+//! assert_eq!(v, minicbor::decode::<ArrayVec<u8, 3>>(&cbor)?);
+//! assert!(minicbor::decode::<ArrayVec<u8, 2>>(&cbor).is_err());
+//!
+//! let cbor = minicbor::to_vec("hello")?;
+//! assert_eq!("hello", minicbor::decode::<ArrayString<8>>(&cbor)?.as_str());
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use ::arrayvec::{ArrayString, ArrayVec};
+use crate::decode::{self, ArrayIter, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+
+impl<T: Encode, const N: usize> Encode for ArrayVec<T, N> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        self.as_slice().encode(e)
+    }
+}
+
+impl<'b, T: Decode<'b>, const N: usize> Decode<'b> for ArrayVec<T, N> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        let iter: ArrayIter<T> = d.array_iter()?;
+        let mut v = ArrayVec::new();
+        for x in iter {
+            if v.try_push(x?).is_err() {
+                return Err(decode::Error::Message("ArrayVec capacity exceeded"))
+            }
+        }
+        Ok(v)
+    }
+}
+
+impl<const N: usize> Encode for ArrayString<N> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        e.str(self)?.ok()
+    }
+}
+
+impl<'b, const N: usize> Decode<'b> for ArrayString<N> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        ArrayString::from(d.str()?).map_err(|_| decode::Error::Message("ArrayString capacity exceeded"))
+    }
+}
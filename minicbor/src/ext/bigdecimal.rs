@@ -0,0 +1,87 @@
+//! Support for [`BigDecimal`] of the [`bigdecimal`] crate.
+//!
+//! Decimals are encoded as decimal fractions with arbitrary exponent, i.e.
+//! with tag 264 as an array `[exponent, mantissa]` (cf. [RFC 8949, section
+//! 3.4.4][1] and the [IANA registry][2]). Mantissas which do not fit into a
+//! CBOR integer are encoded as bignums (tags 2 and 3).
+//!
+//! Decoding accepts decimal fractions (tags 4 and 264) as well as bigfloats
+//! (tags 5 and 265), whose values are always exactly representable by a
+//! `BigDecimal`. Exponents of bigfloats are limited to ±[`MAX_BIGFLOAT_EXP`]
+//! to bound the size of the resulting mantissa.
+//!
+//! *Requires feature* `"bigdecimal"`.
+//!
+//! ```
+//! use bigdecimal::BigDecimal;
+//!
+//! let x = BigDecimal::new(27315.into(), 2); // 273.15
+//!
+//! let cbor = minicbor::to_vec(&x)?;
+//! assert_eq!(b"\xd9\x01\x08\x82\x21\x19\x6a\xb3", cbor.as_slice());
+//! assert_eq!(x, minicbor::decode::<BigDecimal>(&cbor)?);
+//!
+//! // The bigfloat 3 * 2^-2.
+//! let y = minicbor::decode::<BigDecimal>(b"\xc5\x82\x21\x03")?;
+//! assert_eq!(BigDecimal::new(75.into(), 2), y);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-3.4.4
+//! [2]: https://www.iana.org/assignments/cbor-tags/cbor-tags.xhtml
+
+use ::bigdecimal::BigDecimal;
+use ::bigdecimal::num_bigint::{BigInt, Sign};
+use core::convert::TryFrom;
+use crate::data::Tag;
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+
+/// Tag of decimal fractions with arbitrary exponent.
+const DECIMAL: u64 = 264;
+
+/// Tag of bigfloats with arbitrary exponent.
+const BIGFLOAT: u64 = 265;
+
+/// The max. absolute exponent of bigfloats which are decoded.
+pub const MAX_BIGFLOAT_EXP: u32 = 0x4000;
+
+impl Encode for BigDecimal {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        let (m, scale) = self.as_bigint_and_scale();
+        e.tag(Tag::from(DECIMAL))?.array(2)?;
+        e.encode(BigInt::from(-i128::from(scale)))?.encode(&*m)?.ok()
+    }
+}
+
+impl<'b> Decode<'b> for BigDecimal {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        let base10 = match d.tag()?.numeric() {
+            4 | DECIMAL  => true,
+            5 | BIGFLOAT => false,
+            _ => return Err(decode::Error::Message("expected tag 4, 5, 264 or 265 for decimal"))
+        };
+        match d.array()? {
+            Some(2) => {}
+            Some(_) => return Err(decode::Error::Message("expected array of length 2 for decimal")),
+            None    => return Err(decode::Error::Message("expected definite array for decimal"))
+        }
+        let exp = d.decode::<BigInt>()?;
+        let m = d.decode::<BigInt>()?;
+        if base10 {
+            let scale = i64::try_from(-exp).map_err(|_| decode::Error::Message("decimal exponent out of range"))?;
+            return Ok(BigDecimal::new(m, scale))
+        }
+        let k = u32::try_from(exp.magnitude())
+            .ok()
+            .filter(|k| *k <= MAX_BIGFLOAT_EXP)
+            .ok_or(decode::Error::Message("bigfloat exponent out of range"))?;
+        if exp.sign() != Sign::Minus {
+            Ok(BigDecimal::new(m << k, 0))
+        } else {
+            // m * 2^-k = m * 5^k * 10^-k
+            Ok(BigDecimal::new(m * BigInt::from(5).pow(k), i64::from(k)))
+        }
+    }
+}
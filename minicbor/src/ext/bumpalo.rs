@@ -0,0 +1,95 @@
+//! Support for decoding into a [`bumpalo`][::bumpalo] arena.
+//!
+//! The functions of this module decode strings, byte strings and arrays
+//! into memory allocated from a caller-provided [`Bump`] arena instead of
+//! the global allocator. Resetting the arena releases all of it at once,
+//! e.g. after each request of a server. [`Encode`] is implemented for the
+//! arena-allocated [`Vec`] and [`String`] collections.
+//!
+//! *Requires feature* `"bumpalo"`.
+//!
+//! ```
+//! use bumpalo::Bump;
+//! use minicbor::Decoder;
+//! use minicbor::ext::bumpalo::{str, vec_with};
+//!
+//! let cbor = minicbor::to_vec(["hello", "world"])?;
+//! let arena = Bump::new();
+//!
+//! let mut d = Decoder::new(&cbor);
+//! let v = vec_with(&mut d, &arena, |d| str(d, &arena))?;
+//! assert_eq!(["hello", "world"], v.as_slice());
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use ::bumpalo::Bump;
+use ::bumpalo::collections::{String, Vec};
+use crate::data::Type;
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+
+impl<T: Encode> Encode for Vec<'_, T> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        self.as_slice().encode(e)
+    }
+}
+
+impl Encode for String<'_> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        e.str(self)?.ok()
+    }
+}
+
+/// Decode a (possibly indefinite-length) text string into the arena.
+pub fn str<'a>(d: &mut Decoder<'_>, arena: &'a Bump) -> Result<&'a str, decode::Error> {
+    string(d, arena).map(String::into_bump_str)
+}
+
+/// Decode a (possibly indefinite-length) byte string into the arena.
+pub fn bytes<'a>(d: &mut Decoder<'_>, arena: &'a Bump) -> Result<&'a [u8], decode::Error> {
+    let mut v = Vec::new_in(arena);
+    for b in d.bytes_iter()? {
+        v.extend_from_slice(b?)
+    }
+    Ok(v.into_bump_slice())
+}
+
+/// Decode a (possibly indefinite-length) text string into an arena string.
+pub fn string<'a>(d: &mut Decoder<'_>, arena: &'a Bump) -> Result<String<'a>, decode::Error> {
+    let mut s = String::new_in(arena);
+    for x in d.str_iter()? {
+        s.push_str(x?)
+    }
+    Ok(s)
+}
+
+/// Decode an array into an arena vector.
+pub fn vec<'a, 'b, T>(d: &mut Decoder<'b>, arena: &'a Bump) -> Result<Vec<'a, T>, decode::Error>
+where
+    T: Decode<'b>
+{
+    vec_with(d, arena, T::decode)
+}
+
+/// Decode an array into an arena vector, using the given function to
+/// decode each element.
+///
+/// This allows elements to be allocated from the arena as well.
+pub fn vec_with<'a, 'b, T, F>(d: &mut Decoder<'b>, arena: &'a Bump, mut f: F) -> Result<Vec<'a, T>, decode::Error>
+where
+    F: FnMut(&mut Decoder<'b>) -> Result<T, decode::Error>
+{
+    let mut v = Vec::new_in(arena);
+    if let Some(n) = d.array()? {
+        for _ in 0 .. n {
+            v.push(f(d)?)
+        }
+    } else {
+        while d.datatype()? != Type::Break {
+            v.push(f(d)?)
+        }
+        d.set_position(d.position() + 1)
+    }
+    Ok(v)
+}
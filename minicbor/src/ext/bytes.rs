@@ -0,0 +1,74 @@
+//! Support for [`Bytes`] and [`BytesMut`] of the [`bytes`][::bytes] crate.
+//!
+//! Both types are encoded as CBOR byte strings. Decoding copies the bytes
+//! from the input. If the input itself is held in a `Bytes` value, [`slice`]
+//! can be used instead to get a cheap, reference-counted slice of it.
+//!
+//! *Requires feature* `"bytes"`.
+//!
+//! ```
+//! use bytes::Bytes;
+//! use minicbor::Decoder;
+//!
+//! let input = Bytes::from(minicbor::to_vec(Bytes::from_static(b"hello"))?);
+//! assert_eq!(b"\x45hello", &input[..]);
+//!
+//! let mut d = Decoder::new(&input);
+//! let b = minicbor::ext::bytes::slice(&input, &mut d)?;
+//! assert_eq!(&b"hello"[..], b);
+//! assert_eq!(input[1 ..].as_ptr(), b.as_ptr());
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use ::bytes::{Bytes, BytesMut};
+use crate::data::Type;
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+
+impl Encode for Bytes {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        e.bytes(self)?.ok()
+    }
+}
+
+impl<'b> Decode<'b> for Bytes {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        BytesMut::decode(d).map(BytesMut::freeze)
+    }
+}
+
+impl Encode for BytesMut {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        e.bytes(self)?.ok()
+    }
+}
+
+impl<'b> Decode<'b> for BytesMut {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        let mut v = BytesMut::new();
+        for b in d.bytes_iter()? {
+            v.extend_from_slice(b?)
+        }
+        Ok(v)
+    }
+}
+
+/// Decode a byte string as a slice of `input` without copying.
+///
+/// `input` should be the `Bytes` value the decoder reads from. Byte strings
+/// of definite length within `input` are returned as [`Bytes::slice_ref`]
+/// slices. Indefinite-length byte strings, or byte strings outside of
+/// `input`, are copied.
+pub fn slice(input: &Bytes, d: &mut Decoder<'_>) -> Result<Bytes, decode::Error> {
+    if d.datatype()? != Type::Bytes {
+        return Bytes::decode(d)
+    }
+    let b = d.bytes()?;
+    let range = input.as_ptr_range();
+    if range.start <= b.as_ptr() && b.as_ptr_range().end <= range.end {
+        Ok(input.slice_ref(b))
+    } else {
+        Ok(Bytes::copy_from_slice(b))
+    }
+}
@@ -0,0 +1,189 @@
+//! Support for date and time types of the [`chrono`] crate.
+//!
+//! [`DateTime<Utc>`] and [`NaiveDateTime`] are encoded as RFC 3339 strings
+//! with tag 0 (cf. [RFC 8949, section 3.4.1][1]). Naive date/times are
+//! considered to be in UTC. To encode a value as seconds since the UNIX
+//! epoch with tag 1 instead (cf. [RFC 8949, section 3.4.2][2]), wrap it in
+//! [`Epoch`] or use `#[cbor(with = "minicbor::ext::chrono::epoch")]` when
+//! deriving. Decoding accepts both representations.
+//!
+//! *Requires feature* `"chrono"`.
+//!
+//! ```
+//! use chrono::{TimeZone, Utc};
+//! use minicbor::ext::chrono::Epoch;
+//!
+//! let t = Utc.with_ymd_and_hms(2013, 3, 21, 20, 4, 0).unwrap();
+//!
+//! let cbor = minicbor::to_vec(t)?;
+//! assert_eq!(b"\xc0\x742013-03-21T20:04:00Z", cbor.as_slice());
+//!
+//! let cbor = minicbor::to_vec(Epoch(t))?;
+//! assert_eq!(b"\xc1\x1a\x51\x4b\x67\xb0", cbor.as_slice());
+//! assert_eq!(t, minicbor::decode(&cbor)?);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-3.4.1
+//! [2]: https://www.rfc-editor.org/rfc/rfc8949.html#section-3.4.2
+
+use ::chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use crate::data::{Tag, Type};
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+
+/// Encode a date/time as seconds since the UNIX epoch (tag 1).
+///
+/// Date/times without fractional seconds are encoded as integers, all
+/// others as `f64` values which may lose sub-microsecond precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Epoch<T>(pub T);
+
+/// Date/time types supported by [`Epoch`] and [`epoch`].
+///
+/// This trait is sealed and implemented for `DateTime<Utc>` and
+/// `NaiveDateTime`.
+pub trait Instant: sealed::Sealed {}
+
+mod sealed {
+    use super::*;
+
+    pub trait Sealed: Sized {
+        fn to_utc(&self) -> DateTime<Utc>;
+        fn from_utc(t: DateTime<Utc>) -> Self;
+    }
+
+    impl Sealed for DateTime<Utc> {
+        fn to_utc(&self) -> DateTime<Utc> {
+            *self
+        }
+
+        fn from_utc(t: DateTime<Utc>) -> Self {
+            t
+        }
+    }
+
+    impl Sealed for NaiveDateTime {
+        fn to_utc(&self) -> DateTime<Utc> {
+            self.and_utc()
+        }
+
+        fn from_utc(t: DateTime<Utc>) -> Self {
+            t.naive_utc()
+        }
+    }
+}
+
+use sealed::Sealed;
+
+impl Instant for DateTime<Utc> {}
+impl Instant for NaiveDateTime {}
+
+fn encode_rfc3339<W: Write>(t: DateTime<Utc>, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+    e.tag(Tag::DateTime)?.str(&t.to_rfc3339_opts(SecondsFormat::AutoSi, true))?.ok()
+}
+
+fn encode_epoch<W: Write>(t: DateTime<Utc>, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+    e.tag(Tag::Timestamp)?;
+    if t.timestamp_subsec_nanos() == 0 {
+        e.i64(t.timestamp())?.ok()
+    } else {
+        e.f64(t.timestamp() as f64 + f64::from(t.timestamp_subsec_nanos()) / 1e9)?.ok()
+    }
+}
+
+/// Decode a date/time with tag 0 or 1.
+fn decode_any(d: &mut Decoder<'_>) -> Result<DateTime<Utc>, decode::Error> {
+    match d.tag()? {
+        Tag::DateTime => {
+            DateTime::parse_from_rfc3339(d.str()?)
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|_| decode::Error::Message("invalid rfc3339 date/time"))
+        }
+        Tag::Timestamp => match d.datatype()? {
+            Type::F16 | Type::F32 | Type::F64 => {
+                let x = d.f64()?;
+                let s = x.floor();
+                if !(i64::MIN as f64 ..= i64::MAX as f64).contains(&s) {
+                    return Err(decode::Error::Message("timestamp out of range"))
+                }
+                let n = ((x - s) * 1e9).round().min(999_999_999.0) as u32;
+                DateTime::from_timestamp(s as i64, n).ok_or(decode::Error::Message("timestamp out of range"))
+            }
+            _ => {
+                DateTime::from_timestamp(d.i64()?, 0).ok_or(decode::Error::Message("timestamp out of range"))
+            }
+        }
+        _ => Err(decode::Error::Message("expected tag 0 or 1 for date/time"))
+    }
+}
+
+impl Encode for DateTime<Utc> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        encode_rfc3339(*self, e)
+    }
+}
+
+impl<'b> Decode<'b> for DateTime<Utc> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        decode_any(d)
+    }
+}
+
+impl Encode for NaiveDateTime {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        encode_rfc3339(self.to_utc(), e)
+    }
+}
+
+impl<'b> Decode<'b> for NaiveDateTime {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        decode_any(d).map(Sealed::from_utc)
+    }
+}
+
+impl Encode for Epoch<DateTime<Utc>> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        encode_epoch(self.0, e)
+    }
+}
+
+impl<'b> Decode<'b> for Epoch<DateTime<Utc>> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        decode_any(d).map(Epoch)
+    }
+}
+
+impl Encode for Epoch<NaiveDateTime> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        encode_epoch(self.0.to_utc(), e)
+    }
+}
+
+impl<'b> Decode<'b> for Epoch<NaiveDateTime> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        decode_any(d).map(|t| Epoch(Sealed::from_utc(t)))
+    }
+}
+
+/// Encode and decode date/times as seconds since the UNIX epoch.
+///
+/// For use in `#[cbor(with = "minicbor::ext::chrono::epoch")]`.
+pub mod epoch {
+    use super::*;
+
+    /// Encode a date/time as seconds since the UNIX epoch (tag 1).
+    pub fn encode<T, W>(t: &T, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>>
+    where
+        T: Instant,
+        W: Write
+    {
+        encode_epoch(t.to_utc(), e)
+    }
+
+    /// Decode a date/time with tag 0 or 1.
+    pub fn decode<T: Instant>(d: &mut Decoder<'_>) -> Result<T, decode::Error> {
+        decode_any(d).map(Sealed::from_utc)
+    }
+}
@@ -0,0 +1,227 @@
+//! Support for the `Value` type of the [`ciborium`] crate.
+//!
+//! A `ciborium::Value` can be encoded with an [`Encoder`] and decoded from
+//! a [`Decoder`] directly, i.e. without serialising it to bytes with
+//! `ciborium` first. Decoding fails for `undefined` and simple values other
+//! than booleans and `null`, which `ciborium` can not represent.
+//!
+//! In addition, every `ciborium` value can be converted into a [`Value`].
+//! The reverse conversion fails for the same values as decoding.
+//!
+//! *Requires feature* `"ciborium"`.
+//!
+//! ```
+//! use core::convert::TryFrom;
+//! use minicbor::value::Value;
+//!
+//! let c = ciborium::Value::Map(vec![(ciborium::Value::Text("n".into()), ciborium::Value::Integer((-2).into()))]);
+//! let cbor = minicbor::to_vec(&c)?;
+//! assert_eq!(b"\xa1\x61n\x21", cbor.as_slice());
+//! assert_eq!(c, minicbor::decode::<ciborium::Value>(&cbor)?);
+//! assert!(minicbor::decode::<ciborium::Value>(b"\xf7").is_err());
+//!
+//! let v = Value::map([(Value::from("n"), Value::from(-2)), (Value::from("x"), Value::tag(1, Value::from(3)))]);
+//! let c = ciborium::Value::try_from(&v)?;
+//! assert_eq!(Some(&ciborium::Value::Integer((-2).into())), c.as_map().map(|m| &m[0].1));
+//! assert_eq!(v, Value::from(c));
+//!
+//! assert!(ciborium::Value::try_from(Value::undefined()).is_err());
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use ::ciborium::value::{Integer, Value as CValue};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use crate::SIGNED;
+use crate::data::{Tag, Type};
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+use crate::structural::has_next;
+use crate::value::{Value, ValueRef};
+
+/// The error of converting a [`Value`] which has no `ciborium` counterpart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unsupported(&'static str);
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unsupported by ciborium: {}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Unsupported {}
+
+impl Encode for CValue {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        match self {
+            CValue::Integer(n) => {
+                let n = i128::from(*n);
+                match u64::try_from(n) {
+                    Ok(n)  => e.u64(n)?.ok(),
+                    Err(_) => e.type_len(SIGNED, (-1 - n) as u64)?.ok()
+                }
+            }
+            CValue::Bytes(b)  => e.bytes(b)?.ok(),
+            CValue::Float(x)  => e.f64(*x)?.ok(),
+            CValue::Text(s)   => e.str(s)?.ok(),
+            CValue::Bool(b)   => e.bool(*b)?.ok(),
+            CValue::Null      => e.null()?.ok(),
+            CValue::Tag(t, v) => e.tag(Tag::from(*t))?.encode(&**v)?.ok(),
+            CValue::Array(a)  => {
+                e.array(a.len() as u64)?;
+                for x in a {
+                    x.encode(e)?
+                }
+                Ok(())
+            }
+            CValue::Map(m) => {
+                e.map(m.len() as u64)?;
+                for (k, v) in m {
+                    k.encode(e)?;
+                    v.encode(e)?
+                }
+                Ok(())
+            }
+            // `ciborium::Value` is non-exhaustive.
+            _ => Err(encode::Error::Message("unsupported ciborium value"))
+        }
+    }
+}
+
+impl<'b> Decode<'b> for CValue {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        decode_value(d, 0)
+    }
+}
+
+fn decode_value(d: &mut Decoder<'_>, depth: usize) -> Result<CValue, decode::Error> {
+    if depth >= d.max_depth() {
+        return Err(decode::Error::Message("maximum nesting depth exceeded"))
+    }
+    match d.datatype()? {
+        Type::U8 | Type::U16 | Type::U32 | Type::U64 => Ok(CValue::Integer(d.u64()?.into())),
+        Type::I8 | Type::I16 | Type::I32 | Type::I64 => {
+            let p = d.position();
+            match d.i64() {
+                Ok(n) => Ok(CValue::Integer(n.into())),
+                Err(decode::Error::Overflow(..)) => {
+                    d.set_position(p + 1);
+                    let mut b = [0; 8];
+                    b.copy_from_slice(d.read_slice(8)?);
+                    let n = -1 - i128::from(u64::from_be_bytes(b));
+                    Integer::try_from(n)
+                        .map(CValue::Integer)
+                        .map_err(|_| decode::Error::Message("integer out of range"))
+                }
+                Err(e) => Err(e)
+            }
+        }
+        Type::F16 => Ok(CValue::Float(d.f16()?.into())),
+        Type::F32 => Ok(CValue::Float(d.f32()?.into())),
+        Type::F64 => Ok(CValue::Float(d.f64()?)),
+        Type::Bool => Ok(CValue::Bool(d.bool()?)),
+        Type::Null => { d.read_slice(1)?; Ok(CValue::Null) }
+        Type::Bytes | Type::BytesIndef => {
+            let mut v = Vec::new();
+            for b in d.bytes_iter()? {
+                v.extend_from_slice(b?)
+            }
+            Ok(CValue::Bytes(v))
+        }
+        Type::String | Type::StringIndef => {
+            let mut s = String::new();
+            for x in d.str_iter()? {
+                s.push_str(x?)
+            }
+            Ok(CValue::Text(s))
+        }
+        Type::Tag => {
+            let t = d.tag()?;
+            Ok(CValue::Tag(t.numeric(), Box::new(decode_value(d, depth + 1)?)))
+        }
+        Type::Array | Type::ArrayIndef => {
+            let len = d.array()?;
+            let mut a = Vec::new();
+            while has_next(d, len, a.len() as u64)? {
+                a.push(decode_value(d, depth + 1)?)
+            }
+            Ok(CValue::Array(a))
+        }
+        Type::Map | Type::MapIndef => {
+            let len = d.map()?;
+            let mut m = Vec::new();
+            while has_next(d, len, m.len() as u64)? {
+                let k = decode_value(d, depth + 1)?;
+                let v = decode_value(d, depth + 1)?;
+                m.push((k, v))
+            }
+            Ok(CValue::Map(m))
+        }
+        Type::Undefined => Err(decode::Error::Message("undefined is not supported by ciborium")),
+        Type::Simple    => Err(decode::Error::Message("simple values are not supported by ciborium")),
+        t => Err(decode::Error::TypeMismatch(t, "unexpected type"))
+    }
+}
+
+impl From<CValue> for Value {
+    fn from(v: CValue) -> Self {
+        match v {
+            CValue::Integer(n) => {
+                let n = i128::from(n);
+                match u64::try_from(n) {
+                    Ok(n)  => Value::from(n),
+                    Err(_) => Value::negative((-1 - n) as u64)
+                }
+            }
+            CValue::Bytes(b)  => Value::bytes(&b),
+            CValue::Float(x)  => Value::from(x),
+            CValue::Text(s)   => Value::from(s),
+            CValue::Bool(b)   => Value::from(b),
+            CValue::Null      => Value::null(),
+            CValue::Tag(t, v) => Value::tag(t, Value::from(*v)),
+            CValue::Array(a)  => Value::array(a.into_iter().map(Value::from)),
+            CValue::Map(m)    => Value::map(m.into_iter().map(|(k, v)| (Value::from(k), Value::from(v)))),
+            // `ciborium::Value` is non-exhaustive.
+            _ => Value::undefined()
+        }
+    }
+}
+
+impl TryFrom<&Value> for CValue {
+    type Error = Unsupported;
+
+    fn try_from(v: &Value) -> Result<Self, Self::Error> {
+        match v.view() {
+            ValueRef::Unsigned(n) => Ok(CValue::Integer(n.into())),
+            ValueRef::Negative(n) => Integer::try_from(-1 - i128::from(n))
+                .map(CValue::Integer)
+                .map_err(|_| Unsupported("integer out of range")),
+            ValueRef::Float(x)    => Ok(CValue::Float(x)),
+            ValueRef::Bool(b)     => Ok(CValue::Bool(b)),
+            ValueRef::Null        => Ok(CValue::Null),
+            ValueRef::Undefined   => Err(Unsupported("undefined")),
+            ValueRef::Simple(_)   => Err(Unsupported("simple value")),
+            ValueRef::Bytes(b)    => Ok(CValue::Bytes(b.into())),
+            ValueRef::Text(s)     => Ok(CValue::Text(s.into())),
+            ValueRef::Array(a)    => a.iter().map(CValue::try_from).collect::<Result<_, _>>().map(CValue::Array),
+            ValueRef::Map(m)      => m.iter()
+                .map(|(k, v)| Ok((CValue::try_from(k)?, CValue::try_from(v)?)))
+                .collect::<Result<_, _>>()
+                .map(CValue::Map),
+            ValueRef::Tag(t, v)   => Ok(CValue::Tag(t, Box::new(CValue::try_from(v)?)))
+        }
+    }
+}
+
+impl TryFrom<Value> for CValue {
+    type Error = Unsupported;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        CValue::try_from(&v)
+    }
+}
@@ -0,0 +1,34 @@
+//! Support for [`CompactString`] of the [`compact_str`][::compact_str] crate.
+//!
+//! `CompactString` is encoded as a text string. Short strings are decoded
+//! without allocating.
+//!
+//! *Requires feature* `"compact_str"`.
+//!
+//! ```
+//! use compact_str::CompactString;
+//!
+//! let s = CompactString::new("hello");
+//!
+//! let cbor = minicbor::to_vec(&s)?;
+//! assert_eq!(b"\x65hello", cbor.as_slice());
+//! assert_eq!(s, minicbor::decode::<CompactString>(&cbor)?);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use ::compact_str::CompactString;
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+
+impl Encode for CompactString {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        e.str(self)?.ok()
+    }
+}
+
+impl<'b> Decode<'b> for CompactString {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        d.str().map(CompactString::new)
+    }
+}
@@ -0,0 +1,86 @@
+//! Support for [`HashMap`] and [`HashSet`] of the [`hashbrown`][::hashbrown]
+//! crate.
+//!
+//! They are encoded like their `std` counterparts, i.e. as a map and as an
+//! array respectively, but are available without `std`. Any hasher which
+//! implements `Default` is supported.
+//!
+//! *Requires feature* `"hashbrown"`.
+//!
+//! ```
+//! use hashbrown::HashMap;
+//!
+//! let mut m = HashMap::new();
+//! m.insert(1u8, "one");
+//!
+//! let cbor = minicbor::to_vec(&m)?;
+//! assert_eq!(b"\xa1\x01\x63one", cbor.as_slice());
+//! assert_eq!(m, minicbor::decode::<HashMap<u8, &str>>(&cbor)?);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use ::hashbrown::{HashMap, HashSet};
+use core::hash::{BuildHasher, Hash};
+use crate::decode::{self, ArrayIter, Decode, Decoder, MapIter};
+use crate::encode::{self, Encode, Encoder, Write};
+
+impl<K, V, S> Encode for HashMap<K, V, S>
+where
+    K: Encode + Eq + Hash,
+    V: Encode
+{
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        e.map(self.len() as u64)?;
+        for (k, v) in self {
+            k.encode(e)?;
+            v.encode(e)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'b, K, V, S> Decode<'b> for HashMap<K, V, S>
+where
+    K: Decode<'b> + Eq + Hash,
+    V: Decode<'b>,
+    S: BuildHasher + Default
+{
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        let iter: MapIter<K, V> = d.map_iter()?;
+        let mut m = HashMap::with_capacity_and_hasher(iter.size_hint().0, S::default());
+        for x in iter {
+            let (k, v) = x?;
+            m.insert(k, v);
+        }
+        Ok(m)
+    }
+}
+
+impl<T, S> Encode for HashSet<T, S>
+where
+    T: Encode + Eq + Hash
+{
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        e.array(self.len() as u64)?;
+        for x in self {
+            x.encode(e)?
+        }
+        Ok(())
+    }
+}
+
+impl<'b, T, S> Decode<'b> for HashSet<T, S>
+where
+    T: Decode<'b> + Eq + Hash,
+    S: BuildHasher + Default
+{
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        let iter: ArrayIter<T> = d.array_iter()?;
+        let mut v = HashSet::with_capacity_and_hasher(iter.size_hint().0, S::default());
+        for x in iter {
+            v.insert(x?);
+        }
+        Ok(v)
+    }
+}
@@ -0,0 +1,99 @@
+//! Support for collections of the [`heapless`][::heapless] crate.
+//!
+//! [`Vec`] is encoded as an array, [`String`] as a text string and
+//! [`IndexMap`] (e.g. [`FnvIndexMap`][::heapless::FnvIndexMap]) as a map,
+//! in the same way as their `alloc` counterparts. Decoding data which
+//! exceeds the capacity of a collection results in an error.
+//!
+//! *Requires feature* `"heapless"`.
+//!
+//! ```
+//! use heapless::{String, Vec};
+//!
+//! let v: Vec<u8, 4> = Vec::from_slice(&[1, 2, 3]).unwrap();
+//!
+//! let cbor = minicbor::to_vec(&v)?;
+//! assert_eq!(b"\x83\x01\x02\x03", cbor.as_slice());
+//! assert_eq!(v, minicbor::decode::<Vec<u8, 4>>(&cbor)?);
+//! assert!(minicbor::decode::<Vec<u8, 2>>(&cbor).is_err());
+//!
+//! let cbor = minicbor::to_vec("hello")?;
+//! assert_eq!("hello", minicbor::decode::<String<8>>(&cbor)?);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use ::heapless::{IndexMap, String, Vec};
+use core::hash::{BuildHasher, Hash};
+use crate::decode::{self, ArrayIter, Decode, Decoder, MapIter};
+use crate::encode::{self, Encode, Encoder, Write};
+
+impl<T: Encode, const N: usize> Encode for Vec<T, N> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        self.as_slice().encode(e)
+    }
+}
+
+impl<'b, T: Decode<'b>, const N: usize> Decode<'b> for Vec<T, N> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        let iter: ArrayIter<T> = d.array_iter()?;
+        let mut v = Vec::new();
+        for x in iter {
+            if v.push(x?).is_err() {
+                return Err(decode::Error::Message("heapless::Vec capacity exceeded"))
+            }
+        }
+        Ok(v)
+    }
+}
+
+impl<const N: usize> Encode for String<N> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        e.str(self)?.ok()
+    }
+}
+
+impl<'b, const N: usize> Decode<'b> for String<N> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        let mut s = String::new();
+        if s.push_str(d.str()?).is_err() {
+            return Err(decode::Error::Message("heapless::String capacity exceeded"))
+        }
+        Ok(s)
+    }
+}
+
+impl<K, V, S, const N: usize> Encode for IndexMap<K, V, S, N>
+where
+    K: Encode + Eq + Hash,
+    V: Encode,
+    S: BuildHasher
+{
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        e.map(self.len() as u64)?;
+        for (k, v) in self {
+            k.encode(e)?;
+            v.encode(e)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'b, K, V, S, const N: usize> Decode<'b> for IndexMap<K, V, S, N>
+where
+    K: Decode<'b> + Eq + Hash,
+    V: Decode<'b>,
+    S: BuildHasher + Default
+{
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        let iter: MapIter<K, V> = d.map_iter()?;
+        let mut m = IndexMap::default();
+        for x in iter {
+            let (k, v) = x?;
+            if m.insert(k, v).is_err() {
+                return Err(decode::Error::Message("heapless::IndexMap capacity exceeded"))
+            }
+        }
+        Ok(m)
+    }
+}
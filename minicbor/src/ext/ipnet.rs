@@ -0,0 +1,101 @@
+//! Support for [`IpNet`], [`Ipv4Net`] and [`Ipv6Net`] of the
+//! [`ipnet`][::ipnet] crate.
+//!
+//! Networks are encoded with tag 52 (IPv4) or tag 54 (IPv6) as defined in
+//! [RFC 9164][1] (cf. module [`crate::net`]). If the address has no bits
+//! set after the prefix length, the network is encoded in prefix form
+//! (cf. [`Prefix`]), otherwise in interface form (cf. [`Interface`]), so
+//! that the address is preserved. Decoding accepts both forms.
+//!
+//! *Requires feature* `"ipnet"`.
+//!
+//! ```
+//! use ipnet::Ipv4Net;
+//!
+//! let net: Ipv4Net = "192.0.2.0/24".parse()?;
+//! let cbor = minicbor::to_vec(net)?;
+//! assert_eq!(b"\xd8\x34\x82\x18\x18\x43\xc0\x00\x02", cbor.as_slice());
+//! assert_eq!(net, minicbor::decode::<Ipv4Net>(&cbor)?);
+//!
+//! let net: Ipv4Net = "192.0.2.1/24".parse()?;
+//! let cbor = minicbor::to_vec(net)?;
+//! assert_eq!(b"\xd8\x34\x82\x44\xc0\x00\x02\x01\x18\x18", cbor.as_slice());
+//! assert_eq!(net, minicbor::decode::<Ipv4Net>(&cbor)?);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc9164.html
+
+use ::ipnet::{IpNet, Ipv4Net, Ipv6Net};
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use crate::data::Type;
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+use crate::net::{Interface, Ip, Prefix};
+
+/// Encode an address and prefix length in prefix or interface form.
+fn encode_net<T, W>(addr: T, network: T, len: u8, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>>
+where
+    T: Ip + PartialEq,
+    W: Write
+{
+    if addr == network {
+        Prefix::new(addr, len).ok_or(encode::Error::Message("invalid ip prefix length"))?.encode(e)
+    } else {
+        Interface::new(addr, len).ok_or(encode::Error::Message("invalid ip prefix length"))?.encode(e)
+    }
+}
+
+/// Decode an address and prefix length in prefix or interface form.
+fn decode_net<T: Ip>(d: &mut Decoder<'_>) -> Result<(T, u8), decode::Error> {
+    let mut p = d.probe();
+    p.tag()?;
+    p.array()?;
+    if matches!(p.datatype()?, Type::Bytes | Type::BytesIndef) {
+        let i = Interface::<T>::decode(d)?;
+        Ok((i.addr(), i.prefix_len()))
+    } else {
+        let p = Prefix::<T>::decode(d)?;
+        Ok((p.addr(), p.prefix_len()))
+    }
+}
+
+impl Encode for IpNet {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        encode_net(self.addr(), self.network(), self.prefix_len(), e)
+    }
+}
+
+impl<'b> Decode<'b> for IpNet {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        let (a, n) = decode_net::<IpAddr>(d)?;
+        IpNet::new(a, n).map_err(|_| decode::Error::Message("invalid ip prefix length"))
+    }
+}
+
+impl Encode for Ipv4Net {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        encode_net(self.addr(), self.network(), self.prefix_len(), e)
+    }
+}
+
+impl<'b> Decode<'b> for Ipv4Net {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        let (a, n) = decode_net::<Ipv4Addr>(d)?;
+        Ipv4Net::new(a, n).map_err(|_| decode::Error::Message("invalid ip prefix length"))
+    }
+}
+
+impl Encode for Ipv6Net {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        encode_net(self.addr(), self.network(), self.prefix_len(), e)
+    }
+}
+
+impl<'b> Decode<'b> for Ipv6Net {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        let (a, n) = decode_net::<Ipv6Addr>(d)?;
+        Ipv6Net::new(a, n).map_err(|_| decode::Error::Message("invalid ip prefix length"))
+    }
+}
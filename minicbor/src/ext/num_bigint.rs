@@ -0,0 +1,108 @@
+//! Support for [`BigUint`] and [`BigInt`] of the [`num_bigint`] crate.
+//!
+//! Values which fit into a CBOR integer are encoded as such, all others
+//! as bignums, i.e. byte strings with tag 2 (unsigned) or tag 3 (negative)
+//! (cf. [RFC 8949, section 3.4.3][1]). Decoding accepts both representations,
+//! so values interoperate with the regular integer types as long as they
+//! are in range.
+//!
+//! *Requires feature* `"num-bigint"`.
+//!
+//! ```
+//! use num_bigint::BigInt;
+//!
+//! let x = BigInt::from(u64::MAX) + 1;
+//!
+//! let cbor = minicbor::to_vec(&x)?;
+//! assert_eq!(b"\xc2\x49\x01\x00\x00\x00\x00\x00\x00\x00\x00", cbor.as_slice());
+//! assert_eq!(x, minicbor::decode::<BigInt>(&cbor)?);
+//!
+//! let cbor = minicbor::to_vec(-42)?;
+//! assert_eq!(BigInt::from(-42), minicbor::decode::<BigInt>(&cbor)?);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-3.4.3
+
+use ::num_bigint::{BigInt, BigUint, Sign};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use crate::{SIGNED, TAGGED};
+use crate::data::{Tag, Type};
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+
+/// Encode `n` (if `neg` is false) or `-1 - n` (if `neg` is true).
+fn encode_int<W: Write>(neg: bool, n: &BigUint, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+    match (u64::try_from(n), neg) {
+        (Ok(x), false) => e.u64(x)?.ok(),
+        (Ok(x), true)  => e.type_len(SIGNED, x)?.ok(),
+        (Err(_), _)    => e.type_len(TAGGED, if neg { 3 } else { 2 })?.bytes(&n.to_bytes_be())?.ok()
+    }
+}
+
+/// Decode an integer or bignum as `(neg, n)` denoting `n` or `-1 - n`.
+fn decode_int(d: &mut Decoder<'_>) -> Result<(bool, BigUint), decode::Error> {
+    match d.datatype()? {
+        Type::U8 | Type::U16 | Type::U32 | Type::U64 => Ok((false, d.u64()?.into())),
+        Type::I8 | Type::I16 | Type::I32 | Type::I64 => {
+            let p = d.position();
+            match d.i64() {
+                Ok(n) if n < 0 => Ok((true, ((-1 - n) as u64).into())),
+                Ok(n) => Ok((false, (n as u64).into())),
+                Err(decode::Error::Overflow(..)) => {
+                    d.set_position(p + 1);
+                    Ok((true, BigUint::from_bytes_be(d.read_slice(8)?)))
+                }
+                Err(e) => Err(e)
+            }
+        }
+        Type::Tag => {
+            let neg = match d.tag()? {
+                Tag::PosBignum => false,
+                Tag::NegBignum => true,
+                _ => return Err(decode::Error::Message("expected tag 2 or 3 for bignum"))
+            };
+            let mut v = Vec::new();
+            for b in d.bytes_iter()? {
+                v.extend_from_slice(b?)
+            }
+            Ok((neg, BigUint::from_bytes_be(&v)))
+        }
+        t => Err(decode::Error::TypeMismatch(t, "expected integer or bignum"))
+    }
+}
+
+impl Encode for BigUint {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        encode_int(false, self, e)
+    }
+}
+
+impl<'b> Decode<'b> for BigUint {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        match decode_int(d)? {
+            (false, n) => Ok(n),
+            (true,  _) => Err(decode::Error::Message("negative integer for BigUint"))
+        }
+    }
+}
+
+impl Encode for BigInt {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        match self.sign() {
+            Sign::Minus => encode_int(true, &(self.magnitude() - 1u8), e),
+            _           => encode_int(false, self.magnitude(), e)
+        }
+    }
+}
+
+impl<'b> Decode<'b> for BigInt {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        match decode_int(d)? {
+            (false, n) => Ok(BigInt::from(n)),
+            (true,  n) => Ok(-BigInt::from(n) - 1)
+        }
+    }
+}
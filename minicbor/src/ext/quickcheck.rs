@@ -0,0 +1,369 @@
+//! Generation of CBOR items with the [`quickcheck`][::quickcheck] crate.
+//!
+//! [`Cbor`] and [`CanonicalCbor`] implement [`Arbitrary`] and hold the
+//! bytes of a single well-formed CBOR item, like their counterparts in
+//! `minicbor::ext::arbitrary` (with feature `"arbitrary"`):
+//!
+//! - [`Cbor`] covers the whole syntax, including indefinite lengths and
+//!   arguments encoded in more bytes than necessary.
+//! - [`CanonicalCbor`] only produces items in the deterministic encoding of
+//!   [RFC 8949, section 4.2.1][1].
+//!
+//! Shrinking yields smaller well-formed items in deterministic encoding,
+//! e.g. elements of arrays and maps, shorter containers and strings, and
+//! smaller numbers. Items are nested at most [`MAX_DEPTH`] levels deep and
+//! negative integers are limited to the range of `i64`.
+//!
+//! *Requires feature* `"quickcheck"`.
+//!
+//! ```
+//! use minicbor::encoded::RawCbor;
+//! use minicbor::ext::quickcheck::Cbor;
+//! use quickcheck::{Arbitrary, Gen};
+//!
+//! let c = Cbor::arbitrary(&mut Gen::from_size_and_seed(10, 42));
+//! assert!(RawCbor::new(c.as_ref()).is_ok());
+//! for s in c.shrink() {
+//!     assert!(RawCbor::new(s.as_ref()).is_ok());
+//! }
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-4.2.1
+
+use ::quickcheck::{Arbitrary, Gen};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::{fmt, iter};
+use crate::ext::f16_bits;
+use crate::{UNSIGNED, SIGNED, BYTES, TEXT, ARRAY, MAP, TAGGED, SIMPLE, BREAK};
+
+/// The max. nesting depth of generated items.
+pub const MAX_DEPTH: usize = 16;
+
+/// The bytes of an arbitrary well-formed CBOR item.
+#[derive(Clone)]
+pub struct Cbor {
+    bytes: Vec<u8>,
+    item: Item
+}
+
+/// The bytes of an arbitrary CBOR item in deterministic encoding.
+#[derive(Clone)]
+pub struct CanonicalCbor {
+    bytes: Vec<u8>,
+    item: Item
+}
+
+impl Cbor {
+    /// Get the CBOR bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+impl CanonicalCbor {
+    /// Get the CBOR bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+impl AsRef<[u8]> for Cbor {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl AsRef<[u8]> for CanonicalCbor {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl fmt::Debug for Cbor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Cbor").field(&self.bytes).finish()
+    }
+}
+
+impl fmt::Debug for CanonicalCbor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("CanonicalCbor").field(&self.bytes).finish()
+    }
+}
+
+impl Arbitrary for Cbor {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let item = Item::generate(g, 0);
+        let mut bytes = Vec::new();
+        item.encode(&mut bytes, &mut Some(g));
+        Cbor { bytes, item }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(self.item.shrink().map(|item| {
+            let mut bytes = Vec::new();
+            item.encode(&mut bytes, &mut None);
+            Cbor { bytes, item }
+        }))
+    }
+}
+
+impl Arbitrary for CanonicalCbor {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let item = Item::generate(g, 0);
+        let mut bytes = Vec::new();
+        item.encode(&mut bytes, &mut None);
+        CanonicalCbor { bytes, item }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(self.item.shrink().map(|item| {
+            let mut bytes = Vec::new();
+            item.encode(&mut bytes, &mut None);
+            CanonicalCbor { bytes, item }
+        }))
+    }
+}
+
+/// A CBOR data item, independent of its encoding.
+#[derive(Clone, Debug)]
+enum Item {
+    Unsigned(u64),
+    /// The negative integer `-1 - n`.
+    Negative(u64),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Item>),
+    Map(Vec<(Item, Item)>),
+    Tag(u64, Box<Item>),
+    Simple(u8),
+    Float(f64)
+}
+
+impl Item {
+    fn generate(g: &mut Gen, depth: usize) -> Self {
+        let n = if depth < MAX_DEPTH { 9 } else { 6 };
+        match usize::arbitrary(g) % n {
+            0 => Item::Unsigned(u64::arbitrary(g)),
+            1 => Item::Negative(u64::arbitrary(g) >> 1),
+            2 => Item::Bytes(Vec::arbitrary(g)),
+            3 => Item::Text(String::arbitrary(g)),
+            4 => match u8::arbitrary(g) {
+                n @ 24 ..= 31 => Item::Simple(n - 24),
+                n             => Item::Simple(n)
+            }
+            5 => Item::Float(f64::arbitrary(g)),
+            6 => Item::Tag(u64::arbitrary(g), Box::new(Item::generate(g, depth + 1))),
+            7 => {
+                let len = usize::arbitrary(g) % 5;
+                Item::Array((0 .. len).map(|_| Item::generate(g, depth + 1)).collect())
+            }
+            _ => {
+                let len = usize::arbitrary(g) % 5;
+                Item::Map((0 .. len).map(|_| (Item::generate(g, depth + 1), Item::generate(g, depth + 1))).collect())
+            }
+        }
+    }
+
+    /// Encode this item.
+    ///
+    /// Without a generator, the deterministic encoding is used. Otherwise
+    /// encoding choices are made at random.
+    fn encode(&self, buf: &mut Vec<u8>, g: &mut Option<&mut Gen>) {
+        match self {
+            Item::Unsigned(n) => head(buf, UNSIGNED, *n, g),
+            Item::Negative(n) => head(buf, SIGNED, *n, g),
+            Item::Bytes(b)    => string(buf, BYTES, b, g),
+            Item::Text(s)     => string(buf, TEXT, s.as_bytes(), g),
+            Item::Simple(n)   => {
+                if *n < 24 {
+                    buf.push(SIMPLE | n)
+                } else {
+                    buf.extend_from_slice(&[SIMPLE | 24, *n])
+                }
+            }
+            Item::Float(x)    => float(buf, *x, g),
+            Item::Tag(t, x)   => {
+                head(buf, TAGGED, *t, g);
+                x.encode(buf, g)
+            }
+            Item::Array(a)    => {
+                let indef = indefinite(buf, ARRAY, a.len(), g);
+                for x in a {
+                    x.encode(buf, g)
+                }
+                if indef {
+                    buf.push(BREAK)
+                }
+            }
+            Item::Map(m) if g.is_some() => {
+                let indef = indefinite(buf, MAP, m.len(), g);
+                for (k, v) in m {
+                    k.encode(buf, g);
+                    v.encode(buf, g)
+                }
+                if indef {
+                    buf.push(BREAK)
+                }
+            }
+            Item::Map(m) => {
+                let mut entries: Vec<(Vec<u8>, Vec<u8>)> = m.iter()
+                    .map(|(k, v)| {
+                        let mut kb = Vec::new();
+                        let mut vb = Vec::new();
+                        k.encode(&mut kb, g);
+                        v.encode(&mut vb, g);
+                        (kb, vb)
+                    })
+                    .collect();
+                entries.sort_by(|a, b| a.0.cmp(&b.0));
+                entries.dedup_by(|a, b| a.0 == b.0);
+                head(buf, MAP, entries.len() as u64, g);
+                for (k, v) in entries {
+                    buf.extend_from_slice(&k);
+                    buf.extend_from_slice(&v)
+                }
+            }
+        }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Item>> {
+        match self {
+            Item::Unsigned(n) => Box::new(n.shrink().map(Item::Unsigned)),
+            Item::Negative(n) => Box::new(n.shrink().map(Item::Negative)),
+            Item::Bytes(b)    => Box::new(b.shrink().map(Item::Bytes)),
+            Item::Text(s)     => Box::new(s.shrink().map(Item::Text)),
+            Item::Simple(n)   => Box::new(n.shrink().filter(|n| *n < 24 || *n > 31).map(Item::Simple)),
+            Item::Float(x)    => Box::new(x.shrink().map(Item::Float)),
+            Item::Tag(t, x)   => {
+                let t = *t;
+                let x = Item::clone(x);
+                let tags = {
+                    let x = x.clone();
+                    t.shrink().map(move |t| Item::Tag(t, Box::new(x.clone())))
+                };
+                let inner = x.shrink().map(move |x| Item::Tag(t, Box::new(x)));
+                Box::new(iter::once(x).chain(tags).chain(inner))
+            }
+            Item::Array(a)    => {
+                Box::new(a.clone().into_iter().chain(a.shrink().map(Item::Array)))
+            }
+            Item::Map(m)      => {
+                let entries = m.clone().into_iter().flat_map(|(k, v)| iter::once(k).chain(iter::once(v)));
+                Box::new(entries.chain(m.shrink().map(Item::Map)))
+            }
+        }
+    }
+}
+
+impl Arbitrary for Item {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Item::generate(g, 0)
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Item::shrink(self)
+    }
+}
+
+/// Write the initial byte and argument of a data item.
+fn head(buf: &mut Vec<u8>, major: u8, n: u64, g: &mut Option<&mut Gen>) {
+    let min = match n {
+        0        ..= 0x17        => 0,
+        0x18     ..= 0xff        => 1,
+        0x100    ..= 0xffff      => 2,
+        0x1_0000 ..= 0xffff_ffff => 3,
+        _                        => 4
+    };
+    let w = match g {
+        Some(g) => min + usize::arbitrary(g) % (5 - min),
+        None    => min
+    };
+    match w {
+        0 => buf.push(major | n as u8),
+        1 => buf.extend_from_slice(&[major | 24, n as u8]),
+        2 => {
+            buf.push(major | 25);
+            buf.extend_from_slice(&(n as u16).to_be_bytes())
+        }
+        3 => {
+            buf.push(major | 26);
+            buf.extend_from_slice(&(n as u32).to_be_bytes())
+        }
+        _ => {
+            buf.push(major | 27);
+            buf.extend_from_slice(&n.to_be_bytes())
+        }
+    }
+}
+
+/// Write the initial byte of an array or map, which is of indefinite
+/// length if `true` is returned.
+fn indefinite(buf: &mut Vec<u8>, major: u8, len: usize, g: &mut Option<&mut Gen>) -> bool {
+    if let Some(g) = g {
+        if bool::arbitrary(g) {
+            buf.push(major | 31);
+            return true
+        }
+    }
+    head(buf, major, len as u64, g);
+    false
+}
+
+/// Write a byte or text string, possibly as indefinite-length chunks.
+fn string(buf: &mut Vec<u8>, major: u8, b: &[u8], g: &mut Option<&mut Gen>) {
+    if let Some(g) = g {
+        if bool::arbitrary(g) {
+            buf.push(major | 31);
+            let mut rest = b;
+            while !rest.is_empty() {
+                let mut i = 1 + usize::arbitrary(g) % rest.len();
+                if major == TEXT {
+                    while !is_char_boundary(rest, i) {
+                        i += 1
+                    }
+                }
+                let (chunk, r) = rest.split_at(i);
+                head(buf, major, chunk.len() as u64, &mut None);
+                buf.extend_from_slice(chunk);
+                rest = r
+            }
+            buf.push(BREAK);
+            return
+        }
+    }
+    head(buf, major, b.len() as u64, g);
+    buf.extend_from_slice(b)
+}
+
+fn is_char_boundary(b: &[u8], i: usize) -> bool {
+    i >= b.len() || (b[i] as i8) >= -0x40
+}
+
+/// Write a float.
+///
+/// The deterministic encoding uses the shortest float which preserves the
+/// value and encodes NaN as `0xf97e00`.
+fn float(buf: &mut Vec<u8>, x: f64, g: &mut Option<&mut Gen>) {
+    if let Some(g) = g {
+        if bool::arbitrary(g) {
+            buf.push(SIMPLE | 27);
+            buf.extend_from_slice(&x.to_be_bytes());
+            return
+        }
+    }
+    if x.is_nan() {
+        buf.extend_from_slice(&[SIMPLE | 25, 0x7e, 0])
+    } else if f64::from(x as f32) != x {
+        buf.push(SIMPLE | 27);
+        buf.extend_from_slice(&x.to_be_bytes())
+    } else if let Some(h) = f16_bits(x as f32) {
+        buf.push(SIMPLE | 25);
+        buf.extend_from_slice(&h.to_be_bytes())
+    } else {
+        buf.push(SIMPLE | 26);
+        buf.extend_from_slice(&(x as f32).to_be_bytes())
+    }
+}
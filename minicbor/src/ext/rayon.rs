@@ -0,0 +1,77 @@
+//! Parallel decoding of large arrays with [`rayon`][::rayon].
+//!
+//! [`decode_array`] first scans the array to find the boundaries of its
+//! elements, which also checks that they are well-formed. The elements are
+//! then decoded in parallel on the rayon thread pool. This pays off for
+//! arrays with many elements of the same type whose decoding takes more
+//! time than skipping over them, e.g. structs with many fields.
+//!
+//! *Requires feature* `"rayon"`.
+//!
+//! ```
+//! use minicbor::Decoder;
+//! use minicbor::ext::rayon::decode_array;
+//!
+//! let points = (0 .. 1000u32).map(|i| (i, i * 2)).collect::<Vec<_>>();
+//! let cbor = minicbor::to_vec(&points)?;
+//!
+//! let v: Vec<(u32, u32)> = decode_array(&mut Decoder::new(&cbor))?;
+//! assert_eq!(points, v);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use ::rayon::prelude::*;
+use crate::data::Type;
+use crate::decode::{Decode, Decoder, Error};
+
+/// The min. number of elements decoded by a single rayon task.
+const MIN_CHUNK: usize = 256;
+
+/// Decode an array in parallel.
+///
+/// The decoder must be positioned at the array, which may be of definite
+/// or indefinite length. On success, the decoder is positioned after it.
+/// Every element is decoded with a clone of the given decoder, i.e. with
+/// the same configuration, and must be decoded completely by `T::decode`.
+pub fn decode_array<'b, T>(d: &mut Decoder<'b>) -> Result<Vec<T>, Error>
+where
+    T: Decode<'b> + Send
+{
+    let bounds = scan(d)?;
+    let elements = d.clone();
+    bounds.par_windows(2)
+        .with_min_len(MIN_CHUNK)
+        .map(|w| {
+            let mut d = elements.clone();
+            d.set_position(w[0]);
+            let x = T::decode(&mut d)?;
+            if d.position() != w[1] {
+                return Err(Error::Message("array element not completely decoded"))
+            }
+            Ok(x)
+        })
+        .collect()
+}
+
+/// Find the start positions of all array elements and the end of the last.
+///
+/// Moves the decoder to the end of the array.
+fn scan(d: &mut Decoder) -> Result<Vec<usize>, Error> {
+    let mut bounds = Vec::new();
+    if let Some(n) = d.array()? {
+        for _ in 0 .. n {
+            bounds.push(d.position());
+            d.skip()?
+        }
+        bounds.push(d.position());
+    } else {
+        while d.datatype()? != Type::Break {
+            bounds.push(d.position());
+            d.skip()?
+        }
+        bounds.push(d.position());
+        d.set_position(d.position() + 1)
+    }
+    Ok(bounds)
+}
@@ -0,0 +1,119 @@
+//! Support for [`Decimal`] of the [`rust_decimal`] crate.
+//!
+//! Decimals are encoded as decimal fractions with tag 4, i.e. as an array
+//! `[exponent, mantissa]` (cf. [RFC 8949, section 3.4.4][1]). Mantissas
+//! which do not fit into a CBOR integer are encoded as bignums (tags 2 and 3).
+//!
+//! Decoding accepts any decimal fraction whose value can be represented by
+//! a `Decimal` without loss of precision, and fails with an error otherwise.
+//!
+//! *Requires feature* `"rust_decimal"`.
+//!
+//! ```
+//! use rust_decimal::Decimal;
+//!
+//! let x = Decimal::new(27315, 2); // 273.15
+//!
+//! let cbor = minicbor::to_vec(x)?;
+//! assert_eq!(b"\xc4\x82\x21\x19\x6a\xb3", cbor.as_slice());
+//! assert_eq!(x, minicbor::decode::<Decimal>(&cbor)?);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-3.4.4
+
+use ::rust_decimal::Decimal;
+use core::convert::TryFrom;
+use crate::{SIGNED, TAGGED};
+use crate::data::{Tag, Type};
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+
+impl Encode for Decimal {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        e.tag(Tag::Decimal)?.array(2)?.i64(-i64::from(self.scale()))?;
+        let m = self.mantissa();
+        if let Ok(n) = u64::try_from(m) {
+            e.u64(n)?.ok()
+        } else if let Ok(n) = u64::try_from(-1 - m) {
+            e.type_len(SIGNED, n)?.ok()
+        } else {
+            let (t, n) = if m < 0 { (3, (-1 - m) as u128) } else { (2, m as u128) };
+            let b = n.to_be_bytes();
+            let z = (n.leading_zeros() / 8) as usize;
+            e.type_len(TAGGED, t)?.bytes(&b[z ..])?.ok()
+        }
+    }
+}
+
+impl<'b> Decode<'b> for Decimal {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        if d.tag()? != Tag::Decimal {
+            return Err(decode::Error::Message("expected tag 4 for decimal"))
+        }
+        match d.array()? {
+            Some(2) => {}
+            Some(_) => return Err(decode::Error::Message("expected array of length 2 for decimal")),
+            None    => return Err(decode::Error::Message("expected definite array for decimal"))
+        }
+        let exp = d.i64()?;
+        let mut m = mantissa(d)?;
+        if m == 0 {
+            return Ok(Decimal::ZERO)
+        }
+        let scale = if exp >= 0 {
+            for _ in 0 .. exp {
+                m = m.checked_mul(10).ok_or(decode::Error::Message("decimal out of range"))?
+            }
+            0
+        } else {
+            let mut s = exp.unsigned_abs();
+            while s > u64::from(Decimal::MAX_SCALE) && m % 10 == 0 {
+                m /= 10;
+                s -= 1
+            }
+            u32::try_from(s).map_err(|_| decode::Error::Message("decimal scale out of range"))?
+        };
+        Decimal::try_from_i128_with_scale(m, scale).map_err(|_| decode::Error::Message("decimal out of range"))
+    }
+}
+
+/// Decode an integer or a bignum mantissa.
+fn mantissa(d: &mut Decoder<'_>) -> Result<i128, decode::Error> {
+    match d.datatype()? {
+        Type::U8 | Type::U16 | Type::U32 | Type::U64 => Ok(d.u64()?.into()),
+        Type::I8 | Type::I16 | Type::I32 | Type::I64 => {
+            let p = d.position();
+            match d.i64() {
+                Ok(n) => Ok(n.into()),
+                Err(decode::Error::Overflow(..)) => {
+                    d.set_position(p + 1);
+                    let mut a = [0; 8];
+                    a.copy_from_slice(d.read_slice(8)?);
+                    Ok(-1 - i128::from(u64::from_be_bytes(a)))
+                }
+                Err(e) => Err(e)
+            }
+        }
+        Type::Tag => {
+            let neg = match d.tag()? {
+                Tag::PosBignum => false,
+                Tag::NegBignum => true,
+                _ => return Err(decode::Error::Message("expected integer or bignum mantissa"))
+            };
+            let mut n = 0u128;
+            for b in d.bytes_iter()? {
+                for x in b? {
+                    if n >> 120 != 0 {
+                        return Err(decode::Error::Message("decimal out of range"))
+                    }
+                    n = n << 8 | u128::from(*x)
+                }
+            }
+            let n = i128::try_from(n).map_err(|_| decode::Error::Message("decimal out of range"))?;
+            Ok(if neg { -1 - n } else { n })
+        }
+        t => Err(decode::Error::TypeMismatch(t, "expected integer or bignum mantissa"))
+    }
+}
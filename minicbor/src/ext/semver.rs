@@ -0,0 +1,51 @@
+//! Support for [`Version`] and [`VersionReq`] of the [`semver`][::semver]
+//! crate.
+//!
+//! Both are encoded as text strings in their usual notation and parsed
+//! when decoded, i.e. invalid versions result in a decoding error.
+//!
+//! *Requires feature* `"semver"`.
+//!
+//! ```
+//! use semver::{Version, VersionReq};
+//!
+//! let v = Version::new(1, 2, 3);
+//!
+//! let cbor = minicbor::to_vec(&v)?;
+//! assert_eq!(b"\x651.2.3", cbor.as_slice());
+//! assert_eq!(v, minicbor::decode::<Version>(&cbor)?);
+//!
+//! let cbor = minicbor::to_vec("^1.2")?;
+//! assert!(minicbor::decode::<VersionReq>(&cbor)?.matches(&v));
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use ::semver::{Version, VersionReq};
+use alloc::string::ToString;
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+
+impl Encode for Version {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        e.str(&self.to_string())?.ok()
+    }
+}
+
+impl<'b> Decode<'b> for Version {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        Version::parse(d.str()?).map_err(|_| decode::Error::Message("invalid semver version"))
+    }
+}
+
+impl Encode for VersionReq {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        e.str(&self.to_string())?.ok()
+    }
+}
+
+impl<'b> Decode<'b> for VersionReq {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        VersionReq::parse(d.str()?).map_err(|_| decode::Error::Message("invalid semver version requirement"))
+    }
+}
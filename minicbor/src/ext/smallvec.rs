@@ -0,0 +1,40 @@
+//! Support for [`SmallVec`] of the [`smallvec`][::smallvec] crate.
+//!
+//! `SmallVec<[T; N]>` is encoded as an array, like `Vec<T>`, for any
+//! inline capacity `N`. Decoding spills to the heap if the array has more
+//! than `N` elements.
+//!
+//! *Requires feature* `"smallvec"`.
+//!
+//! ```
+//! use smallvec::{smallvec, SmallVec};
+//!
+//! let v: SmallVec<[u8; 2]> = smallvec![1, 2, 3];
+//!
+//! let cbor = minicbor::to_vec(&v)?;
+//! assert_eq!(b"\x83\x01\x02\x03", cbor.as_slice());
+//! assert_eq!(v, minicbor::decode::<SmallVec<[u8; 2]>>(&cbor)?);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use ::smallvec::SmallVec;
+use crate::decode::{self, ArrayIter, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+
+impl<T: Encode, const N: usize> Encode for SmallVec<[T; N]> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        self.as_slice().encode(e)
+    }
+}
+
+impl<'b, T: Decode<'b>, const N: usize> Decode<'b> for SmallVec<[T; N]> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        let iter: ArrayIter<T> = d.array_iter()?;
+        let mut v = SmallVec::new();
+        for x in iter {
+            v.push(x?)
+        }
+        Ok(v)
+    }
+}
@@ -0,0 +1,34 @@
+//! Support for [`SmolStr`] of the [`smol_str`][::smol_str] crate.
+//!
+//! `SmolStr` is encoded as a text string. Short strings are decoded
+//! without allocating.
+//!
+//! *Requires feature* `"smol_str"`.
+//!
+//! ```
+//! use smol_str::SmolStr;
+//!
+//! let s = SmolStr::new("hello");
+//!
+//! let cbor = minicbor::to_vec(&s)?;
+//! assert_eq!(b"\x65hello", cbor.as_slice());
+//! assert_eq!(s, minicbor::decode::<SmolStr>(&cbor)?);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use ::smol_str::SmolStr;
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+
+impl Encode for SmolStr {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        e.str(self)?.ok()
+    }
+}
+
+impl<'b> Decode<'b> for SmolStr {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        d.str().map(SmolStr::new)
+    }
+}
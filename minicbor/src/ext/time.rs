@@ -0,0 +1,230 @@
+//! Support for date and time types of the [`time`] crate.
+//!
+//! [`OffsetDateTime`] and [`PrimitiveDateTime`] are encoded as RFC 3339
+//! strings in UTC with tag 0 (cf. [RFC 8949, section 3.4.1][1]). Primitive
+//! date/times are considered to be in UTC. To encode a value as seconds
+//! since the UNIX epoch with tag 1 instead (cf. [RFC 8949, section 3.4.2][2]),
+//! wrap it in [`Epoch`] or use `#[cbor(with = "minicbor::ext::time::epoch")]`
+//! when deriving. Decoding accepts both representations.
+//!
+//! Only years from 0 to 9999 can be encoded as RFC 3339 strings.
+//!
+//! *Requires feature* `"time"`.
+//!
+//! ```
+//! use minicbor::ext::time::Epoch;
+//! use time::OffsetDateTime;
+//!
+//! let t = OffsetDateTime::from_unix_timestamp(1363896240)?;
+//!
+//! let cbor = minicbor::to_vec(t)?;
+//! assert_eq!(b"\xc0\x742013-03-21T20:04:00Z", cbor.as_slice());
+//!
+//! let cbor = minicbor::to_vec(Epoch(t))?;
+//! assert_eq!(b"\xc1\x1a\x51\x4b\x67\xb0", cbor.as_slice());
+//! assert_eq!(t, minicbor::decode::<OffsetDateTime>(&cbor)?);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-3.4.1
+//! [2]: https://www.rfc-editor.org/rfc/rfc8949.html#section-3.4.2
+
+use ::time::{OffsetDateTime, PrimitiveDateTime, UtcOffset};
+use ::time::format_description::well_known::Rfc3339;
+use crate::data::{Tag, Type};
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+
+/// Encode a date/time as seconds since the UNIX epoch (tag 1).
+///
+/// Date/times without fractional seconds are encoded as integers, all
+/// others as `f64` values which may lose sub-microsecond precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Epoch<T>(pub T);
+
+/// Date/time types supported by [`Epoch`] and [`epoch`].
+///
+/// This trait is sealed and implemented for `OffsetDateTime` and
+/// `PrimitiveDateTime`.
+pub trait Instant: sealed::Sealed {}
+
+mod sealed {
+    use super::*;
+
+    pub trait Sealed: Sized {
+        fn to_utc(&self) -> OffsetDateTime;
+        fn from_utc(t: OffsetDateTime) -> Self;
+    }
+
+    impl Sealed for OffsetDateTime {
+        fn to_utc(&self) -> OffsetDateTime {
+            self.to_offset(UtcOffset::UTC)
+        }
+
+        fn from_utc(t: OffsetDateTime) -> Self {
+            t
+        }
+    }
+
+    impl Sealed for PrimitiveDateTime {
+        fn to_utc(&self) -> OffsetDateTime {
+            self.assume_utc()
+        }
+
+        fn from_utc(t: OffsetDateTime) -> Self {
+            let t = t.to_offset(UtcOffset::UTC);
+            PrimitiveDateTime::new(t.date(), t.time())
+        }
+    }
+}
+
+use sealed::Sealed;
+
+impl Instant for OffsetDateTime {}
+impl Instant for PrimitiveDateTime {}
+
+fn encode_rfc3339<W: Write>(t: OffsetDateTime, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+    let t = t.to_offset(UtcOffset::UTC);
+    let year = t.year();
+    if !(0 ..= 9999).contains(&year) {
+        return Err(encode::Error::Message("year out of range for rfc3339"))
+    }
+    // YYYY-MM-DDTHH:MM:SS.nnnnnnnnnZ
+    let mut buf = [0u8; 30];
+    let mut n = 0;
+    let mut put = |x: u32, digits: u32, sep: Option<u8>| {
+        for i in (0 .. digits).rev() {
+            buf[n] = b'0' + (x / 10u32.pow(i) % 10) as u8;
+            n += 1
+        }
+        if let Some(s) = sep {
+            buf[n] = s;
+            n += 1
+        }
+    };
+    put(year as u32, 4, Some(b'-'));
+    put(u8::from(t.month()).into(), 2, Some(b'-'));
+    put(t.day().into(), 2, Some(b'T'));
+    put(t.hour().into(), 2, Some(b':'));
+    put(t.minute().into(), 2, Some(b':'));
+    let nanos = t.nanosecond();
+    if nanos == 0 {
+        put(t.second().into(), 2, Some(b'Z'))
+    } else {
+        put(t.second().into(), 2, Some(b'.'));
+        match (nanos % 1_000_000, nanos % 1_000) {
+            (0, _) => put(nanos / 1_000_000, 3, Some(b'Z')),
+            (_, 0) => put(nanos / 1_000, 6, Some(b'Z')),
+            _      => put(nanos, 9, Some(b'Z'))
+        }
+    }
+    // All bytes written are ASCII.
+    let s = core::str::from_utf8(&buf[.. n]).map_err(|_| encode::Error::Message("invalid rfc3339 string"))?;
+    e.tag(Tag::DateTime)?.str(s)?.ok()
+}
+
+fn encode_epoch<W: Write>(t: OffsetDateTime, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+    e.tag(Tag::Timestamp)?;
+    if t.nanosecond() == 0 {
+        e.i64(t.unix_timestamp())?.ok()
+    } else {
+        e.f64(t.unix_timestamp() as f64 + f64::from(t.nanosecond()) / 1e9)?.ok()
+    }
+}
+
+/// Decode a date/time with tag 0 or 1.
+fn decode_any(d: &mut Decoder<'_>) -> Result<OffsetDateTime, decode::Error> {
+    match d.tag()? {
+        Tag::DateTime => {
+            OffsetDateTime::parse(d.str()?, &Rfc3339)
+                .map_err(|_| decode::Error::Message("invalid rfc3339 date/time"))
+        }
+        Tag::Timestamp => match d.datatype()? {
+            Type::F16 | Type::F32 | Type::F64 => {
+                let x = d.f64()?;
+                let s = x.floor();
+                if !(i64::MIN as f64 ..= i64::MAX as f64).contains(&s) {
+                    return Err(decode::Error::Message("timestamp out of range"))
+                }
+                let n = ((x - s) * 1e9).round().min(999_999_999.0) as i128;
+                OffsetDateTime::from_unix_timestamp_nanos(i128::from(s as i64) * 1_000_000_000 + n)
+                    .map_err(|_| decode::Error::Message("timestamp out of range"))
+            }
+            _ => {
+                OffsetDateTime::from_unix_timestamp(d.i64()?)
+                    .map_err(|_| decode::Error::Message("timestamp out of range"))
+            }
+        }
+        _ => Err(decode::Error::Message("expected tag 0 or 1 for date/time"))
+    }
+}
+
+impl Encode for OffsetDateTime {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        encode_rfc3339(*self, e)
+    }
+}
+
+impl<'b> Decode<'b> for OffsetDateTime {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        decode_any(d)
+    }
+}
+
+impl Encode for PrimitiveDateTime {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        encode_rfc3339(self.to_utc(), e)
+    }
+}
+
+impl<'b> Decode<'b> for PrimitiveDateTime {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        decode_any(d).map(Sealed::from_utc)
+    }
+}
+
+impl Encode for Epoch<OffsetDateTime> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        encode_epoch(self.0, e)
+    }
+}
+
+impl<'b> Decode<'b> for Epoch<OffsetDateTime> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        decode_any(d).map(Epoch)
+    }
+}
+
+impl Encode for Epoch<PrimitiveDateTime> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        encode_epoch(self.0.to_utc(), e)
+    }
+}
+
+impl<'b> Decode<'b> for Epoch<PrimitiveDateTime> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        decode_any(d).map(|t| Epoch(Sealed::from_utc(t)))
+    }
+}
+
+/// Encode and decode date/times as seconds since the UNIX epoch.
+///
+/// For use in `#[cbor(with = "minicbor::ext::time::epoch")]`.
+pub mod epoch {
+    use super::*;
+
+    /// Encode a date/time as seconds since the UNIX epoch (tag 1).
+    pub fn encode<T, W>(t: &T, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>>
+    where
+        T: Instant,
+        W: Write
+    {
+        encode_epoch(t.to_utc(), e)
+    }
+
+    /// Decode a date/time with tag 0 or 1.
+    pub fn decode<T: Instant>(d: &mut Decoder<'_>) -> Result<T, decode::Error> {
+        decode_any(d).map(Sealed::from_utc)
+    }
+}
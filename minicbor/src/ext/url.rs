@@ -0,0 +1,41 @@
+//! Support for [`Url`] of the [`url`][::url] crate.
+//!
+//! URLs are encoded as text strings with tag 32 (cf. [RFC 8949, section
+//! 3.4.5.3][1]). Decoding requires the tag and parses the string, i.e.
+//! invalid URLs result in a decoding error.
+//!
+//! *Requires feature* `"url"`.
+//!
+//! ```
+//! use url::Url;
+//!
+//! let u = Url::parse("https://example.com/")?;
+//!
+//! let cbor = minicbor::to_vec(&u)?;
+//! assert_eq!(b"\xd8\x20\x74https://example.com/", cbor.as_slice());
+//! assert_eq!(u, minicbor::decode::<Url>(&cbor)?);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-3.4.5.3
+
+use ::url::Url;
+use crate::data::Tag;
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+
+impl Encode for Url {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        e.tag(Tag::Uri)?.str(self.as_str())?.ok()
+    }
+}
+
+impl<'b> Decode<'b> for Url {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        if d.tag()? != Tag::Uri {
+            return Err(decode::Error::Message("expected tag 32 for url"))
+        }
+        Url::parse(d.str()?).map_err(|_| decode::Error::Message("invalid url"))
+    }
+}
@@ -0,0 +1,580 @@
+//! Conversion between CBOR and JSON.
+//!
+//! [`to_json`] and [`from_json`] follow the rules of [RFC 8949, section 6][1]:
+//!
+//! - Integers and floats are converted to JSON numbers, non-finite floats
+//!   to `null`. Bignums (tags 2 and 3) become base64url encoded strings,
+//!   prefixed with `~` if negative.
+//! - Byte strings become base64url encoded strings without padding, unless
+//!   nested in a tag 21, 22 or 23, which selects base64url, base64 (with
+//!   padding) or base16 respectively.
+//! - Text strings, arrays and maps are converted to JSON strings, arrays and
+//!   objects. Map keys must be text strings or integers, the latter being
+//!   converted to decimal strings.
+//! - `true`, `false` and `null` are kept, `undefined` and other simple
+//!   values become `null`. Other tags are dropped, i.e. only their content
+//!   is converted.
+//!
+//! In the opposite direction, JSON integers become CBOR integers (or bignums
+//! if out of range of major types 0 and 1), numbers with a fraction or an
+//! exponent become `f64` values and strings, arrays and objects are mapped
+//! to definite-length text strings, arrays and maps.
+//!
+//! Arrays, maps and tags may be nested up to [`MAX_DEPTH`] levels deep.
+//!
+//! *Requires feature* `"json"`.
+//!
+//! ```
+//! use minicbor::json::{from_json, to_json};
+//!
+//! let cbor = from_json(r#"{"a": [1, -2.5, "x", null]}"#)?;
+//! assert_eq!(r#"{"a":[1,-2.5,"x",null]}"#, to_json(&cbor)?);
+//!
+//! # Ok::<_, minicbor::json::Error>(())
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-6
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write as _};
+use crate::data::{Tag, Type};
+use crate::decode::{self, Decoder};
+use crate::decode::diagnostic::{base64, BASE64, BASE64_URL};
+use crate::encode::{self, Encoder};
+use crate::structural::has_next;
+use crate::{SIGNED, TAGGED};
+
+/// The max. nesting depth of arrays, maps and tags.
+pub const MAX_DEPTH: usize = 256;
+
+/// Conversion errors.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// Decoding the CBOR input failed.
+    Decode(decode::Error),
+    /// Encoding the CBOR output failed.
+    Encode(&'static str),
+    /// The JSON input is invalid at the given byte offset.
+    Syntax(usize, &'static str),
+    /// A CBOR item has no JSON representation.
+    Unsupported(&'static str)
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Decode(e)      => write!(f, "decode error: {}", e),
+            Error::Encode(m)      => write!(f, "encode error: {}", m),
+            Error::Syntax(n, m)   => write!(f, "invalid json at offset {}: {}", n, m),
+            Error::Unsupported(m) => write!(f, "unsupported: {}", m)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Decode(e) => Some(e),
+            _                => None
+        }
+    }
+}
+
+impl From<decode::Error> for Error {
+    fn from(e: decode::Error) -> Self {
+        Error::Decode(e)
+    }
+}
+
+impl<E> From<encode::Error<E>> for Error {
+    fn from(e: encode::Error<E>) -> Self {
+        match e {
+            encode::Error::Message(m) => Error::Encode(m),
+            _                         => Error::Encode("failed to write cbor")
+        }
+    }
+}
+
+impl From<fmt::Error> for Error {
+    fn from(_: fmt::Error) -> Self {
+        Error::Encode("failed to write json")
+    }
+}
+
+/// Convert a single CBOR item to JSON.
+///
+/// The whole input must be consumed, otherwise a
+/// [`decode::Error::TrailingBytes`] error is returned.
+pub fn to_json(cbor: &[u8]) -> Result<String, Error> {
+    let mut d = Decoder::new(cbor);
+    let mut s = String::new();
+    cbor_to_json(&mut d, &mut s, Encoding::Base64Url, 0)?;
+    if d.position() < cbor.len() {
+        return Err(decode::Error::TrailingBytes(d.position()).into())
+    }
+    Ok(s)
+}
+
+/// Convert a JSON value to CBOR.
+pub fn from_json(json: &str) -> Result<Vec<u8>, Error> {
+    let mut p = Parser { input: json.as_bytes(), pos: 0 };
+    let mut v = Vec::new();
+    p.value(&mut v, 0)?;
+    p.whitespace();
+    if p.pos < p.input.len() {
+        return Err(Error::Syntax(p.pos, "trailing characters"))
+    }
+    Ok(v)
+}
+
+/// The encoding of byte strings.
+#[derive(Debug, Clone, Copy)]
+enum Encoding {
+    Base64Url,
+    Base64,
+    Base16
+}
+
+fn cbor_to_json(d: &mut Decoder, s: &mut String, enc: Encoding, depth: usize) -> Result<(), Error> {
+    if depth >= MAX_DEPTH {
+        return Err(decode::Error::Message("maximum nesting depth exceeded").into())
+    }
+    match d.datatype()? {
+        Type::Bool => write!(s, "{}", d.bool()?)?,
+        Type::Null | Type::Undefined | Type::Simple => {
+            d.skip()?;
+            s.push_str("null")
+        }
+        Type::U8 | Type::U16 | Type::U32 | Type::U64 => write!(s, "{}", d.u64()?)?,
+        Type::I8 | Type::I16 | Type::I32 | Type::I64 => write!(s, "{}", negative(d)?)?,
+        Type::F16 => float(s, crate::ext::f16_to_f64(u16::from_be_bytes(raw(d)?)))?,
+        Type::F32 => {
+            let x = d.f32()?;
+            if x.is_finite() { write!(s, "{:?}", x)? } else { s.push_str("null") }
+        }
+        Type::F64 => float(s, d.f64()?)?,
+        Type::Bytes | Type::BytesIndef => {
+            let mut b = Vec::new();
+            for x in d.bytes_iter()? {
+                b.extend_from_slice(x?)
+            }
+            s.push('"');
+            encode_bytes(s, &b, enc);
+            s.push('"')
+        }
+        Type::String | Type::StringIndef => {
+            s.push('"');
+            for x in d.str_iter()? {
+                escape(s, x?)
+            }
+            s.push('"')
+        }
+        Type::Array | Type::ArrayIndef => {
+            let len = d.array()?;
+            s.push('[');
+            let mut i = 0;
+            while has_next(d, len, i)? {
+                if i > 0 {
+                    s.push(',')
+                }
+                cbor_to_json(d, s, enc, depth + 1)?;
+                i += 1
+            }
+            s.push(']')
+        }
+        Type::Map | Type::MapIndef => {
+            let len = d.map()?;
+            s.push('{');
+            let mut i = 0;
+            while has_next(d, len, i)? {
+                if i > 0 {
+                    s.push(',')
+                }
+                match d.datatype()? {
+                    Type::String | Type::StringIndef => cbor_to_json(d, s, enc, depth + 1)?,
+                    Type::U8 | Type::U16 | Type::U32 | Type::U64 => write!(s, "\"{}\"", d.u64()?)?,
+                    Type::I8 | Type::I16 | Type::I32 | Type::I64 => write!(s, "\"{}\"", negative(d)?)?,
+                    _ => return Err(Error::Unsupported("map key must be a string or an integer"))
+                }
+                s.push(':');
+                cbor_to_json(d, s, enc, depth + 1)?;
+                i += 1
+            }
+            s.push('}')
+        }
+        Type::Tag => match d.tag()? {
+            t @ Tag::PosBignum | t @ Tag::NegBignum => {
+                s.push('"');
+                if t == Tag::NegBignum {
+                    s.push('~')
+                }
+                let mut b = Vec::new();
+                for x in d.bytes_iter()? {
+                    b.extend_from_slice(x?)
+                }
+                encode_bytes(s, &b, Encoding::Base64Url);
+                s.push('"')
+            }
+            Tag::ToBase64Url => cbor_to_json(d, s, Encoding::Base64Url, depth + 1)?,
+            Tag::ToBase64    => cbor_to_json(d, s, Encoding::Base64, depth + 1)?,
+            Tag::ToBase16    => cbor_to_json(d, s, Encoding::Base16, depth + 1)?,
+            _                => cbor_to_json(d, s, enc, depth + 1)?
+        }
+        t @ Type::Break | t @ Type::Unknown(_) => {
+            return Err(decode::Error::TypeMismatch(t, "unexpected type").into())
+        }
+    }
+    Ok(())
+}
+
+/// Decode any negative integer of major type 1.
+fn negative(d: &mut Decoder) -> Result<i128, Error> {
+    let p = d.position();
+    match d.i64() {
+        Ok(n) => Ok(n.into()),
+        Err(decode::Error::Overflow(..)) => {
+            d.set_position(p);
+            let n = u64::from_be_bytes(raw(d)?);
+            Ok(-1 - i128::from(n))
+        }
+        Err(e) => Err(e.into())
+    }
+}
+
+/// Read the argument bytes following the current initial byte.
+fn raw<const N: usize>(d: &mut Decoder) -> Result<[u8; N], Error> {
+    let mut a = [0; N];
+    d.set_position(d.position() + 1);
+    a.copy_from_slice(d.read_slice(N)?);
+    Ok(a)
+}
+
+fn float(s: &mut String, x: f64) -> Result<(), Error> {
+    if x.is_finite() {
+        write!(s, "{:?}", x)?
+    } else {
+        s.push_str("null")
+    }
+    Ok(())
+}
+
+fn escape(s: &mut String, x: &str) {
+    for c in x.chars() {
+        match c {
+            '"'  => s.push_str("\\\""),
+            '\\' => s.push_str("\\\\"),
+            '\n' => s.push_str("\\n"),
+            '\r' => s.push_str("\\r"),
+            '\t' => s.push_str("\\t"),
+            '\u{8}' => s.push_str("\\b"),
+            '\u{c}' => s.push_str("\\f"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(s, "\\u{:04x}", c as u32);
+            }
+            c => s.push(c)
+        }
+    }
+}
+
+const BASE16: &[u8; 16] = b"0123456789abcdef";
+
+fn encode_bytes(s: &mut String, b: &[u8], enc: Encoding) {
+    match enc {
+        Encoding::Base16 => for x in b {
+            s.push(char::from(BASE16[usize::from(x >> 4)]));
+            s.push(char::from(BASE16[usize::from(x & 0xf)]))
+        }
+        Encoding::Base64Url => { let _ = base64(s, b, BASE64_URL, false); }
+        Encoding::Base64    => { let _ = base64(s, b, BASE64, true); }
+    }
+}
+
+/// A JSON parser producing CBOR.
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize
+}
+
+impl<'a> Parser<'a> {
+    fn value(&mut self, out: &mut Vec<u8>, depth: usize) -> Result<(), Error> {
+        if depth >= MAX_DEPTH {
+            return Err(Error::Syntax(self.pos, "maximum nesting depth exceeded"))
+        }
+        self.whitespace();
+        match self.peek()? {
+            b'{' => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                let mut n = 0;
+                if !self.close(b'}')? {
+                    loop {
+                        self.whitespace();
+                        if self.peek()? != b'"' {
+                            return Err(Error::Syntax(self.pos, "expected string"))
+                        }
+                        self.value(&mut items, depth + 1)?;
+                        self.whitespace();
+                        self.expect(b':')?;
+                        self.value(&mut items, depth + 1)?;
+                        n += 1;
+                        if self.separator(b'}')? {
+                            break
+                        }
+                    }
+                }
+                Encoder::new(&mut *out).map(n)?;
+                out.extend_from_slice(&items)
+            }
+            b'[' => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                let mut n = 0;
+                if !self.close(b']')? {
+                    loop {
+                        self.value(&mut items, depth + 1)?;
+                        n += 1;
+                        if self.separator(b']')? {
+                            break
+                        }
+                    }
+                }
+                Encoder::new(&mut *out).array(n)?;
+                out.extend_from_slice(&items)
+            }
+            b'"' => {
+                let s = self.string()?;
+                Encoder::new(out).str(&s)?;
+            }
+            b't' => {
+                self.literal("true")?;
+                Encoder::new(out).bool(true)?;
+            }
+            b'f' => {
+                self.literal("false")?;
+                Encoder::new(out).bool(false)?;
+            }
+            b'n' => {
+                self.literal("null")?;
+                Encoder::new(out).null()?;
+            }
+            b'-' | b'0' ..= b'9' => self.number(out)?,
+            _ => return Err(Error::Syntax(self.pos, "unexpected character"))
+        }
+        Ok(())
+    }
+
+    fn whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.input.get(self.pos) {
+            self.pos += 1
+        }
+    }
+
+    fn peek(&self) -> Result<u8, Error> {
+        self.input.get(self.pos).copied().ok_or(Error::Syntax(self.pos, "unexpected end of input"))
+    }
+
+    fn next(&mut self) -> Result<u8, Error> {
+        let b = self.peek()?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), Error> {
+        if self.peek()? != b {
+            return Err(Error::Syntax(self.pos, "unexpected character"))
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// Consume the closing bracket of an empty array or object.
+    fn close(&mut self, b: u8) -> Result<bool, Error> {
+        self.whitespace();
+        if self.peek()? == b {
+            self.pos += 1;
+            return Ok(true)
+        }
+        Ok(false)
+    }
+
+    /// Consume a `,` (returning `false`) or the closing bracket (returning `true`).
+    fn separator(&mut self, b: u8) -> Result<bool, Error> {
+        self.whitespace();
+        match self.next()? {
+            b','          => Ok(false),
+            x if x == b   => Ok(true),
+            _             => Err(Error::Syntax(self.pos - 1, "expected ',' or closing bracket"))
+        }
+    }
+
+    fn literal(&mut self, lit: &'static str) -> Result<(), Error> {
+        if !self.input[self.pos ..].starts_with(lit.as_bytes()) {
+            return Err(Error::Syntax(self.pos, "invalid literal"))
+        }
+        self.pos += lit.len();
+        Ok(())
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.expect(b'"')?;
+        let mut s = String::new();
+        loop {
+            let start = self.pos;
+            while let Some(&b) = self.input.get(self.pos) {
+                if b == b'"' || b == b'\\' || b < 0x20 {
+                    break
+                }
+                self.pos += 1
+            }
+            // The input is a `&str` and we only stop at ASCII characters.
+            s.push_str(core::str::from_utf8(&self.input[start .. self.pos]).map_err(decode::Error::Utf8)?);
+            match self.next()? {
+                b'"'  => return Ok(s),
+                b'\\' => match self.next()? {
+                    b'"'  => s.push('"'),
+                    b'\\' => s.push('\\'),
+                    b'/'  => s.push('/'),
+                    b'b'  => s.push('\u{8}'),
+                    b'f'  => s.push('\u{c}'),
+                    b'n'  => s.push('\n'),
+                    b'r'  => s.push('\r'),
+                    b't'  => s.push('\t'),
+                    b'u'  => {
+                        let hi = self.hex4()?;
+                        let c = if (0xd800 .. 0xdc00).contains(&hi) {
+                            self.literal("\\u").map_err(|_| Error::Syntax(self.pos, "unpaired surrogate"))?;
+                            let lo = self.hex4()?;
+                            if !(0xdc00 .. 0xe000).contains(&lo) {
+                                return Err(Error::Syntax(self.pos, "unpaired surrogate"))
+                            }
+                            0x10000 + ((hi - 0xd800) << 10) + (lo - 0xdc00)
+                        } else {
+                            hi
+                        };
+                        s.push(char::from_u32(c).ok_or(Error::Syntax(self.pos, "invalid unicode escape"))?)
+                    }
+                    _ => return Err(Error::Syntax(self.pos - 1, "invalid escape"))
+                }
+                _ => return Err(Error::Syntax(self.pos - 1, "control character in string"))
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, Error> {
+        let mut n = 0;
+        for _ in 0 .. 4 {
+            let d = char::from(self.next()?).to_digit(16).ok_or(Error::Syntax(self.pos - 1, "invalid hex digit"))?;
+            n = n << 4 | d
+        }
+        Ok(n)
+    }
+
+    fn number(&mut self, out: &mut Vec<u8>) -> Result<(), Error> {
+        let start = self.pos;
+        let neg = self.peek()? == b'-';
+        if neg {
+            self.pos += 1
+        }
+        let int_start = self.pos;
+        match self.next()? {
+            b'0'         => {}
+            b'1' ..= b'9' => self.digits(),
+            _            => return Err(Error::Syntax(self.pos - 1, "expected digit"))
+        }
+        let int_end = self.pos;
+        let mut is_float = false;
+        if self.input.get(self.pos) == Some(&b'.') {
+            self.pos += 1;
+            self.expect_digits()?;
+            is_float = true
+        }
+        if let Some(b'e' | b'E') = self.input.get(self.pos) {
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.input.get(self.pos) {
+                self.pos += 1
+            }
+            self.expect_digits()?;
+            is_float = true
+        }
+        // The number consists of ASCII characters only.
+        let text = core::str::from_utf8(&self.input[start .. self.pos]).map_err(decode::Error::Utf8)?;
+        if is_float {
+            let x: f64 = text.parse().map_err(|_| Error::Syntax(start, "invalid number"))?;
+            Encoder::new(out).f64(x)?;
+            return Ok(())
+        }
+        let digits = &self.input[int_start .. int_end];
+        let mut e = Encoder::new(out);
+        match (parse_u64(digits), neg) {
+            (Some(0), _)     => { e.u8(0)?; }
+            (Some(n), false) => { e.u64(n)?; }
+            (Some(n), true)  => { e.type_len(SIGNED, n - 1)?; }
+            (None, false) => {
+                e.type_len(TAGGED, 2)?.bytes(&decimal_to_bytes(digits))?;
+            }
+            (None, true) => {
+                let mut b = decimal_to_bytes(digits);
+                decrement(&mut b);
+                if b.len() <= 8 {
+                    let n = b.iter().fold(0, |n, x| n << 8 | u64::from(*x));
+                    e.type_len(SIGNED, n)?;
+                } else {
+                    e.type_len(TAGGED, 3)?.bytes(&b)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn digits(&mut self) {
+        while let Some(b'0' ..= b'9') = self.input.get(self.pos) {
+            self.pos += 1
+        }
+    }
+
+    fn expect_digits(&mut self) -> Result<(), Error> {
+        if !self.peek()?.is_ascii_digit() {
+            return Err(Error::Syntax(self.pos, "expected digit"))
+        }
+        self.digits();
+        Ok(())
+    }
+}
+
+fn parse_u64(digits: &[u8]) -> Option<u64> {
+    digits.iter().try_fold(0u64, |n, d| n.checked_mul(10)?.checked_add(u64::from(d - b'0')))
+}
+
+/// Convert decimal digits to big-endian bytes.
+fn decimal_to_bytes(digits: &[u8]) -> Vec<u8> {
+    let mut b: Vec<u8> = Vec::new();
+    for d in digits {
+        let mut carry = u32::from(d - b'0');
+        for x in b.iter_mut().rev() {
+            let v = u32::from(*x) * 10 + carry;
+            *x = v as u8;
+            carry = v >> 8
+        }
+        if carry > 0 {
+            b.insert(0, carry as u8)
+        }
+    }
+    b
+}
+
+/// Subtract 1 from a non-zero big-endian number.
+fn decrement(b: &mut Vec<u8>) {
+    for x in b.iter_mut().rev() {
+        let (v, borrow) = x.overflowing_sub(1);
+        *x = v;
+        if !borrow {
+            break
+        }
+    }
+    if b.len() > 1 && b[0] == 0 {
+        b.remove(0);
+    }
+}
@@ -3,12 +3,12 @@
 //! The crate is organised around the following entities:
 //!
 //! - [`Encoder`] and [`Decoder`] for type-directed encoding and decoding
-//! of values.
+//!   of values.
 //!
 //! - [`Encode`] and [`Decode`] traits which can be implemented for any
-//! type that should be encoded to or decoded from CBOR. They are similar
-//! to [serde]'s `Serialize` and `Deserialize` traits but do not abstract
-//! over the encoder/decoder.
+//!   type that should be encoded to or decoded from CBOR. They are similar
+//!   to [serde]'s `Serialize` and `Deserialize` traits but do not abstract
+//!   over the encoder/decoder.
 //!
 //! Encoding and decoding proceeds in a type-directed way, i.e.  by calling
 //! methods for expected data item types, e.g. [`Decoder::u32`] or
@@ -17,7 +17,8 @@
 //! [`data::Type`] that can represent every possible CBOR type and decoding
 //! can thus proceed based on this information. It is also possible to just
 //! tokenize the input bytes using a [`Tokenizer`](decode::Tokenizer), i.e.
-//! an `Iterator` over CBOR [`Token`](decode::Token)s.
+//! an `Iterator` over CBOR [`Token`](decode::Token)s, or to [`walk`] over
+//! them with a [`Visitor`](visit::Visitor).
 //!
 //! Optionally, `Encode` and `Decode` can be derived for structs and enums
 //! using the respective derive macros (*requires feature* `"derive"`).
@@ -31,10 +32,18 @@
 //!
 //! The following feature flags are supported:
 //!
-//! - `"alloc"`: Enables most collection types in a `no_std` environment.
+//! - `"alloc"`: Enables most collection types in a `no_std` environment,
+//!   i.e. `Box`, `Rc`, `Arc`, `String`, `Vec`, `VecDeque`, `LinkedList`,
+//!   `BinaryHeap`, `BTreeSet`, `BTreeMap` and owned `Cow`s, as well as
+//!   [`to_vec`], the dynamic [`value::Value`] type, CDDL generation with
+//!   [`schema::Schema`], size profiles with [`sizes::Describe`], encoding
+//!   profiles like dCBOR with [`profile::Profile`], the test
+//!   assertions of [`testing`] and full support for [`Decoder::skip`]. Only
+//!   the `alloc` crate is required, i.e. heap-equipped embedded targets can
+//!   use this feature without depending on `std`.
 //!
 //! - `"std"`: Implies `"alloc"` and enables more functionality that depends
-//!   on the `std` crate.
+//!   on the `std` crate, e.g. encoding `SystemTime` with [`time::Epoch`].
 //!
 //! - `"derive"`: Implies `"alloc"` and allows deriving [`Encode`] and
 //!   [`Decode`] traits.
@@ -50,6 +59,113 @@
 //!   indefinite-length CBOR maps and arrays inside of regular CBOR maps and
 //!   arrays.
 //!
+//! - `"simdutf8"`: Validates UTF-8 text strings with SIMD instructions of
+//!   the `simdutf8` crate, which is considerably faster for long strings.
+//!   With `"std"` the instructions are selected at runtime, otherwise at
+//!   compile time according to the enabled target features.
+//!
+//! - `"testvectors"`: Provides the [`testvectors`] module with the examples
+//!   of RFC 8949, Appendix A to check codecs and transports for conformance.
+//!
+//! - `"serde"`: Implies `"alloc"` and provides the [`serde`](crate::serde)
+//!   module with a serde `Serializer` and `Deserializer` on top of
+//!   [`Encoder`] and [`Decoder`].
+//!
+//! - `"json"`: Implies `"alloc"` and provides the [`json`] module to convert
+//!   between CBOR and JSON.
+//!
+//! - `"edn"`: Implies `"alloc"` and provides the [`edn`] module to parse
+//!   CBOR diagnostic notation.
+//!
+//! - `"dag-cbor"`: Implies `"alloc"` and provides the [`dag_cbor`] module
+//!   to encode and decode the strict DAG-CBOR profile of IPLD.
+//!
+//! - `"ctap2"`: Implies `"alloc"` and provides the [`ctap2`] module to
+//!   encode and decode the canonical CBOR form of CTAP2 and WebAuthn.
+//!
+//! - `"wasm"`: Implies `"alloc"` and `"half"` and provides the [`wasm`]
+//!   module to convert between CBOR and JavaScript values of `wasm-bindgen`.
+//!
+//! - `"chrono"`: Implies `"alloc"` and implements [`Encode`] and [`Decode`]
+//!   for date/time types of the `chrono` crate (see [`ext::chrono`]).
+//!
+//! - `"smallvec"`: Implements [`Encode`] and [`Decode`] for `SmallVec` of the
+//!   `smallvec` crate with any inline capacity (see [`ext::smallvec`]).
+//!
+//! - `"smol_str"`, `"compact_str"`: Implement [`Encode`] and [`Decode`] for
+//!   `SmolStr` and `CompactString` respectively (see [`ext::smol_str`] and
+//!   [`ext::compact_str`]).
+//!
+//! - `"time"`: Implements [`Encode`] and [`Decode`] for date/time types of
+//!   the `time` crate (see [`ext::time`]).
+//!
+//! - `"hashbrown"`: Implements [`Encode`] and [`Decode`] for `HashMap` and
+//!   `HashSet` of the `hashbrown` crate, which do not require `std` (see
+//!   [`ext::hashbrown`]).
+//!
+//! - `"heapless"`: Implements [`Encode`] and [`Decode`] for `Vec`, `String`
+//!   and `FnvIndexMap` of the `heapless` crate (see [`ext::heapless`]).
+//!
+//! - `"ipnet"`: Implements [`Encode`] and [`Decode`] for `IpNet`, `Ipv4Net`
+//!   and `Ipv6Net` of the `ipnet` crate with the tags of RFC 9164 (see
+//!   [`ext::ipnet`]).
+//!
+//! - `"num-bigint"`: Implies `"alloc"` and implements [`Encode`] and [`Decode`]
+//!   for `BigUint` and `BigInt` of the `num-bigint` crate (see
+//!   [`ext::num_bigint`]).
+//!
+//! - `"bigdecimal"`: Implies `"num-bigint"` and implements [`Encode`] and
+//!   [`Decode`] for `BigDecimal` of the `bigdecimal` crate with tags 264 and
+//!   265 (see [`ext::bigdecimal`]).
+//!
+//! - `"rust_decimal"`: Implements [`Encode`] and [`Decode`] for `Decimal` of
+//!   the `rust_decimal` crate (see [`ext::rust_decimal`]).
+//!
+//! - `"arrayvec"`: Implements [`Encode`] and [`Decode`] for `ArrayVec` and
+//!   `ArrayString` of the `arrayvec` crate (see [`ext::arrayvec`]).
+//!
+//! - `"bytes"`: Implements [`Encode`] and [`Decode`] for `Bytes` and `BytesMut`
+//!   of the `bytes` crate (see [`ext::bytes`]).
+//!
+//! - `"url"`: Implements [`Encode`] and [`Decode`] for `Url` of the `url`
+//!   crate using tag 32 (see [`ext::url`]).
+//!
+//! - `"semver"`: Implies `"alloc"` and implements [`Encode`] and [`Decode`] for
+//!   `Version` and `VersionReq` of the `semver` crate (see [`ext::semver`]).
+//!
+//! - `"arbitrary"`: Implies `"alloc"` and provides generators of well-formed
+//!   CBOR items for structured fuzzing (see [`ext::arbitrary`]).
+//!
+//! - `"derive-arbitrary"`: Implies `"derive"` and `"arbitrary"` and provides
+//!   the derive macro `Arbitrary` for types with a derived [`Decode`] impl
+//!   (see [`ext::arbitrary`]).
+//!
+//! - `"quickcheck"`: Implies `"alloc"` and provides generators of well-formed
+//!   CBOR items for property testing (see [`ext::quickcheck`]).
+//!
+//! - `"ciborium"`: Implies `"alloc"` and `"half"` and provides `Encode`
+//!   and `Decode` impls for the `Value` of the `ciborium` crate as well as
+//!   conversions between it and [`value::Value`] (see [`ext::ciborium`]).
+//!
+//! - `"tracing"`: Implies `"alloc"` and instruments [`Decoder::decode`] and
+//!   [`Encoder::encode`] with spans and error events of the `tracing` crate.
+//!
+//! - `"bumpalo"`: Provides functions to decode strings, byte strings and
+//!   arrays into a `bumpalo` arena (see [`ext::bumpalo`]).
+//!
+//! - `"rayon"`: Implies `"std"` and provides parallel decoding of large
+//!   arrays on the `rayon` thread pool (see [`ext::rayon`]).
+//!
+//! - `"digest"`: Provides [`encode::write::DigestWriter`] to hash encoded
+//!   bytes while they are written.
+//!
+//! - `"defmt"`: Implements [`defmt::Format`][2] for [`encode::Error`],
+//!   [`decode::Error`], [`data::Type`], [`data::Tag`] and
+//!   [`encode::write::EndOfSlice`] for efficient logging on embedded
+//!   targets.
+//!
+//! [2]: https://docs.rs/defmt/latest/defmt/trait.Format.html
+//!
 //! # Example: generic encoding and decoding
 //!
 //! ```
@@ -133,8 +249,9 @@
 //! [CBOR]: https://tools.ietf.org/html/rfc7049
 //! [serde]: https://serde.rs
 
-#![forbid(unused_imports, unused_variables)]
-#![allow(clippy::needless_lifetimes)]
+#![cfg_attr(not(feature = "tracing"), forbid(unused_imports, unused_variables))]
+// The macros of `tracing` allow unused imports internally.
+#![cfg_attr(feature = "tracing", deny(unused_imports, unused_variables))]
 #![cfg_attr(not(feature = "std"), no_std)]
 
 #[cfg(feature = "alloc")]
@@ -145,6 +262,54 @@ pub mod data;
 pub mod decode;
 pub mod encode;
 
+#[cfg(feature = "json")]
+pub mod json;
+
+#[cfg(feature = "edn")]
+pub mod edn;
+
+#[cfg(feature = "dag-cbor")]
+pub mod dag_cbor;
+
+#[cfg(feature = "ctap2")]
+pub mod ctap2;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "alloc")]
+pub mod value;
+
+#[cfg(feature = "alloc")]
+pub mod schema;
+
+#[cfg(feature = "alloc")]
+pub mod sizes;
+
+#[cfg(feature = "alloc")]
+pub mod testing;
+
+pub mod time;
+
+pub mod encoded;
+
+pub mod net;
+
+#[cfg(feature = "alloc")]
+pub mod profile;
+
+pub mod ext;
+
+pub mod visit;
+
+mod structural;
+
+#[cfg(feature = "serde")]
+pub mod serde;
+
+#[cfg(feature = "testvectors")]
+pub mod testvectors;
+
 const UNSIGNED: u8 = 0x00;
 const SIGNED: u8   = 0x20;
 const BYTES: u8    = 0x40;
@@ -161,6 +326,32 @@ pub use encode::{Encode, Encoder};
 #[cfg(feature = "derive")]
 pub use minicbor_derive::*;
 
+/// Items used by code generated by `minicbor-derive`.
+#[doc(hidden)]
+#[cfg(feature = "alloc")]
+pub mod __private {
+    pub use alloc::boxed::Box;
+}
+
+/// Types whose values map to CBOR indices.
+///
+/// This is implemented by enums deriving `CborIndex` (*requires feature*
+/// `"derive"`) and provides the `#[n(...)]` index of a value's variant
+/// without encoding the value.
+pub trait CborIndex {
+    /// Get the index of this value.
+    fn index(&self) -> u64;
+}
+
+/// Types whose values can be created from CBOR indices.
+///
+/// Enums with only unit variants implement this trait when deriving
+/// `CborIndex`.
+pub trait FromCborIndex: CborIndex + Sized {
+    /// Get the value of the given index, if any.
+    fn from_index(i: u64) -> Option<Self>;
+}
+
 /// Decode a type implementing [`Decode`] from the given byte slice.
 pub fn decode<'b, T>(b: &'b [u8]) -> Result<T, decode::Error>
 where
@@ -169,6 +360,33 @@ where
     Decoder::new(b).decode()
 }
 
+/// Decode a type implementing [`Decode`] from the given byte slice and
+/// require that the whole slice is consumed.
+///
+/// If bytes remain after the value has been decoded, a
+/// [`decode::Error::TrailingBytes`] error with the offset of the first
+/// trailing byte is returned.
+///
+/// ```
+/// use minicbor::decode::Error;
+///
+/// assert_eq!(1u8, minicbor::decode_exact(&[0x01])?);
+/// assert!(matches!(minicbor::decode_exact::<u8>(&[0x01, 0x02]), Err(Error::TrailingBytes(1))));
+///
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn decode_exact<'b, T>(b: &'b [u8]) -> Result<T, decode::Error>
+where
+    T: Decode<'b>
+{
+    let mut d = Decoder::new(b);
+    let x = d.decode()?;
+    if d.position() < b.len() {
+        return Err(decode::Error::TrailingBytes(d.position()))
+    }
+    Ok(x)
+}
+
 /// Encode a type implementing [`Encode`] to the given [`encode::Write`] impl.
 pub fn encode<T, W>(x: T, w: W) -> Result<(), encode::Error<W::Error>>
 where
@@ -178,22 +396,86 @@ where
     Encoder::new(w).encode(x)?.ok()
 }
 
+/// Encode a type implementing [`Encode`] with definite lengths only.
+///
+/// The value is encoded twice. The first pass computes the lengths of all
+/// indefinite-length arrays, maps and strings produced by the [`Encode`]
+/// impl, the second pass writes the encoding with definite lengths instead
+/// to the given [`encode::Write`] impl. Neither pass buffers the encoding,
+/// but both must produce the same items, i.e. the [`Encode`] impl must be
+/// deterministic.
+///
+/// *Requires feature* `"alloc"`.
+///
+/// ```
+/// use minicbor::{Encode, Encoder};
+/// use minicbor::encode::{Error, Write};
+///
+/// struct Numbers(u8);
+///
+/// impl Encode for Numbers {
+///     fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
+///         e.begin_array()?;
+///         for i in 0 .. self.0 {
+///             e.u8(i)?;
+///         }
+///         e.end()?.ok()
+///     }
+/// }
+///
+/// let mut v = Vec::new();
+/// minicbor::encode_definite(Numbers(3), &mut v)?;
+/// assert_eq!([0x83, 0, 1, 2], v.as_slice());
+///
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_definite<T, W>(x: T, w: W) -> Result<(), encode::Error<W::Error>>
+where
+    T: Encode,
+    W: encode::Write
+{
+    encode::definite::encode(x, w)
+}
+
 /// Encode a type implementing [`Encode`] and return the encoded byte vector.
 ///
-/// *Requires feature* `"std"`.
-#[cfg(feature = "std")]
-pub fn to_vec<T>(x: T) -> Result<Vec<u8>, encode::Error<std::io::Error>>
+/// *Requires feature* `"alloc"`.
+#[cfg(feature = "alloc")]
+pub fn to_vec<T>(x: T) -> Result<alloc::vec::Vec<u8>, encode::Error<<alloc::vec::Vec<u8> as encode::Write>::Error>>
 where
     T: Encode
 {
-    let mut e = Encoder::new(Vec::new());
+    let mut e = Encoder::new(alloc::vec::Vec::new());
     x.encode(&mut e)?;
     Ok(e.into_inner())
 }
 
-/// Display the given CBOR bytes in [diagnostic notation][1].
+/// Encode a type implementing [`Encode`] into uninitialised memory.
 ///
-/// *Requires features* `"alloc"` and `"half"`.
+/// Returns the prefix of `buf` which contains the encoded bytes. The rest of
+/// `buf` is left untouched (cf. [`encode::write::UninitWriter`]).
+///
+/// ```
+/// use core::mem::MaybeUninit;
+///
+/// let mut buf = [MaybeUninit::uninit(); 32];
+/// let cbor = minicbor::to_slice_uninit(("hello", 42), &mut buf)?;
+/// assert_eq!(b"\x82\x65hello\x18\x2a", cbor);
+///
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn to_slice_uninit<'a, T>(x: T, buf: &'a mut [core::mem::MaybeUninit<u8>])
+    -> Result<&'a mut [u8], encode::Error<<encode::write::UninitWriter<'a> as encode::Write>::Error>>
+where
+    T: Encode
+{
+    let mut e = Encoder::new(encode::write::UninitWriter::new(buf));
+    x.encode(&mut e)?;
+    Ok(e.into_inner().into_initialized())
+}
+
+/// Display the given CBOR bytes in [diagnostic notation][1].
 ///
 /// Quick syntax summary:
 ///
@@ -202,26 +484,175 @@ where
 /// - Indefinite maps start with `{_` instead of `{`.
 /// - Indefinite arrays start with `[_` instead of `[`.
 /// - Bytes are hex encoded and enclosed in `h'` and `'`.
-/// - Strings are enclosed in double quotes.
+/// - Strings are enclosed in double quotes and escaped as in JSON.
 /// - Numbers and booleans are displayed as in Rust but floats are always
 ///   shown in scientific notation (this differs slightly from the RFC
-///   format).
+///   format). Non-finite floats are shown as `Infinity`, `-Infinity` and
+///   `NaN`.
 /// - Indefinite bytes are enclosed in `(_` and `)` except for the empty
 ///   sequence which is shown as `''_`.
 /// - Indefinite strings are enclosed in `(_` and `)` except for the empty
 ///   sequence which is shown as `""_`.
 /// - Tagged values are enclosed in `t(` and `)` where `t` is the numeric
-///   tag value.
+///   tag value. With the alternate flag (`{:#}`) the names of registered
+///   tags are added as comments, e.g. `1(/ epoch-based date-time / 0)`
+///   (cf. [`data::TagInfo`]).
 /// - Simple values are shown as `simple(n)` where `n` is the numeric
 ///   simple value.
 /// - Undefined and null are shown as `undefined` and `null`.
+/// - Multiple items in sequence are separated by commas.
 ///
-/// No error is produced should decoding fail, the error message
-/// becomes part of the display.
+/// Displaying does not allocate. No error is produced should decoding
+/// fail, the error message becomes part of the display.
 ///
 /// [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-8
-#[cfg(all(feature = "alloc", feature = "half"))]
 pub fn display<'b>(cbor: &'b [u8]) -> impl core::fmt::Display + 'b {
-    decode::Tokenizer::new(cbor)
+    decode::Diagnostic::new(Decoder::new(cbor))
+}
+
+/// Display the given bytes as lowercase hex digits.
+///
+/// Displaying does not allocate, which makes this suitable for logging
+/// encoded values. The inverse is [`from_hex`].
+///
+/// ```
+/// let cbor = minicbor::to_vec((1u8, "a"))?;
+/// assert_eq!("82016161", minicbor::display_hex(&cbor).to_string());
+///
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn display_hex<'b>(bytes: &'b [u8]) -> impl core::fmt::Display + 'b {
+    struct Hex<'b>(&'b [u8]);
+
+    impl core::fmt::Display for Hex<'_> {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            for b in self.0 {
+                write!(f, "{:02x}", b)?
+            }
+            Ok(())
+        }
+    }
+
+    Hex(bytes)
+}
+
+/// Parse bytes from hex digits.
+///
+/// Upper- and lowercase digits are accepted and ASCII whitespace as well as
+/// a leading `0x` are ignored, so that test vectors can be copied from
+/// RFCs as they are (cf. [`testing::assert_encodes_to_hex`]).
+///
+/// ```
+/// assert_eq!(vec![0x82, 0x01, 0x61, 0x61], minicbor::from_hex("82 01 61 61")?);
+/// assert_eq!(vec![0x19, 0x03, 0xe8], minicbor::from_hex("0x1903E8")?);
+/// assert!(minicbor::from_hex("0x123").is_err());
+///
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "alloc")]
+pub fn from_hex(s: &str) -> Result<alloc::vec::Vec<u8>, decode::Error> {
+    fn digit(c: u8) -> Result<u8, decode::Error> {
+        match c {
+            b'0' ..= b'9' => Ok(c - b'0'),
+            b'a' ..= b'f' => Ok(c - b'a' + 10),
+            b'A' ..= b'F' => Ok(c - b'A' + 10),
+            _ => Err(decode::Error::Message("invalid hex digit"))
+        }
+    }
+    let s = s.trim_start();
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    let mut digits = s.bytes().filter(|c| !c.is_ascii_whitespace());
+    let mut bytes = alloc::vec::Vec::with_capacity(s.len() / 2);
+    while let Some(hi) = digits.next() {
+        let lo = digits.next().ok_or(decode::Error::Message("odd number of hex digits"))?;
+        bytes.push(digit(hi)? << 4 | digit(lo)?)
+    }
+    Ok(bytes)
+}
+
+/// Compare two encoded CBOR items in deterministic order.
+///
+/// Items are ordered by the bytewise lexicographic order of the encoded
+/// bytes ([RFC 8949 §4.2.1][1]). This can be used to sort or deduplicate
+/// the keys of canonical maps.
+///
+/// ```
+/// use core::cmp::Ordering;
+///
+/// let a = [0x0a];       // 10
+/// let b = [0x18, 0x64]; // 100
+/// let c = [0x20];       // -1
+/// let d = [0x61, 0x7a]; // "z"
+///
+/// assert_eq!(Ordering::Less, minicbor::cmp(&a, &b));
+/// assert_eq!(Ordering::Less, minicbor::cmp(&b, &c));
+/// assert_eq!(Ordering::Less, minicbor::cmp(&c, &d));
+/// ```
+///
+/// [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-4.2.1
+pub fn cmp(a: &[u8], b: &[u8]) -> core::cmp::Ordering {
+    a.cmp(b)
+}
+
+/// Check if two CBOR sequences are structurally equal.
+///
+/// Items are considered equal if they denote the same data model value
+/// irrespective of their encoding. In particular the following
+/// differences are ignored:
+///
+/// - The width of integers, lengths and tags, e.g. `0x01` equals `0x1801`.
+/// - The width of floats, which are compared by value, e.g. 1.5 as half
+///   precision float equals 1.5 as double precision float.
+/// - Definite vs. indefinite lengths of strings, arrays and maps.
+///
+/// The order of map entries is significant. Comparing does not allocate.
+/// An error is returned if either input is not well-formed.
+///
+/// ```
+/// assert!(minicbor::eq(&[0x01], &[0x18, 0x01])?);
+/// assert!(minicbor::eq(&[0x82, 0x01, 0x02], &[0x9f, 0x01, 0x02, 0xff])?);
+/// assert!(minicbor::eq(&[0xf9, 0x3e, 0x00], &[0xfb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0])?);
+/// assert!(!minicbor::eq(&[0x01], &[0xf9, 0x3c, 0x00])?);
+/// # Ok::<_, minicbor::decode::Error>(())
+/// ```
+pub fn eq(a: &[u8], b: &[u8]) -> Result<bool, decode::Error> {
+    structural::eq(a, b)
+}
+
+/// Walk over a CBOR sequence and invoke the callbacks of a visitor.
+///
+/// Every item is passed to the matching [`visit::Visitor`] callback
+/// together with its nesting depth. Arrays, maps, tags and indefinite-length
+/// strings can be skipped as a whole by returning [`visit::Control::Skip`]
+/// and the walk ends early with [`visit::Control::Stop`]. Walking does not
+/// allocate. An error is returned if the input is not well-formed.
+///
+/// ```
+/// use minicbor::visit::{Control, Visitor};
+///
+/// /// Collects all strings outside of maps.
+/// struct Strings<'b>(Vec<&'b str>);
+///
+/// impl<'b> Visitor<'b> for Strings<'b> {
+///     fn on_str(&mut self, _: usize, s: &'b str) -> Control {
+///         self.0.push(s);
+///         Control::Continue
+///     }
+///
+///     fn begin_map(&mut self, _: usize, _: Option<u64>) -> Control {
+///         Control::Skip
+///     }
+/// }
+///
+/// let map = std::collections::BTreeMap::from([("b", 1)]);
+/// let cbor = minicbor::to_vec(("a", map, ["c"]))?;
+/// let mut v = Strings(Vec::new());
+/// minicbor::walk(&cbor, &mut v)?;
+/// assert_eq!(vec!["a", "c"], v.0);
+///
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn walk<'b, V: visit::Visitor<'b> + ?Sized>(bytes: &'b [u8], v: &mut V) -> Result<(), decode::Error> {
+    visit::walk(bytes, v)
 }
 
@@ -0,0 +1,328 @@
+//! IP addresses and prefixes with the tags of [RFC 9164][1].
+//!
+//! The `Encode` and `Decode` impls of `IpAddr`, `Ipv4Addr` and `Ipv6Addr`
+//! use an array layout which is specific to minicbor. The types here use
+//! tag 52 (IPv4) and tag 54 (IPv6) with the standardised byte string forms
+//! instead:
+//!
+//! - [`Address`] (or `#[cbor(with = "minicbor::net::address")]`) encodes
+//!   an address as a byte string of 4 or 16 bytes.
+//! - [`Prefix`] encodes a network prefix as an array of the prefix length
+//!   and the prefix bytes without trailing zero bytes.
+//! - [`Interface`] encodes an address together with the prefix length of
+//!   its network as an array.
+//!
+//! All types are generic over the address type, i.e. `IpAddr`, `Ipv4Addr`
+//! or `Ipv6Addr`. Zone identifiers and ports are not supported.
+//!
+//! ```
+//! use core::net::Ipv4Addr;
+//! use minicbor::net::{Address, Interface, Prefix};
+//!
+//! let a = Ipv4Addr::new(192, 0, 2, 1);
+//!
+//! let cbor = minicbor::to_vec(Address(a))?;
+//! assert_eq!(b"\xd8\x34\x44\xc0\x00\x02\x01", cbor.as_slice());
+//!
+//! let p = Prefix::new(a, 24).unwrap();
+//! assert_eq!(Ipv4Addr::new(192, 0, 2, 0), p.addr());
+//!
+//! let cbor = minicbor::to_vec(p)?;
+//! assert_eq!(b"\xd8\x34\x82\x18\x18\x43\xc0\x00\x02", cbor.as_slice());
+//! assert_eq!(p, minicbor::decode(&cbor)?);
+//!
+//! let cbor = minicbor::to_vec(Interface::new(a, 24).unwrap())?;
+//! assert_eq!(b"\xd8\x34\x82\x44\xc0\x00\x02\x01\x18\x18", cbor.as_slice());
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc9164.html
+
+use core::convert::TryFrom;
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use crate::data::Tag;
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+
+/// IPv4 address or prefix.
+const IPV4: Tag = Tag::Unassigned(52);
+
+/// IPv6 address or prefix.
+const IPV6: Tag = Tag::Unassigned(54);
+
+/// Encode an IP address as tagged byte string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Address<T>(pub T);
+
+/// A network prefix, i.e. an address and a prefix length.
+///
+/// The address bits after the prefix length are always zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Prefix<T> {
+    addr: T,
+    len: u8
+}
+
+/// An interface address, i.e. an address and the prefix length of its
+/// network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interface<T> {
+    addr: T,
+    len: u8
+}
+
+/// Address types supported by [`Address`], [`Prefix`] and [`Interface`].
+///
+/// This trait is sealed and implemented for `IpAddr`, `Ipv4Addr` and
+/// `Ipv6Addr`.
+pub trait Ip: sealed::Sealed {}
+
+mod sealed {
+    use super::*;
+
+    pub trait Sealed: Copy {
+        fn to_ip(self) -> IpAddr;
+        fn from_ip(a: IpAddr) -> Result<Self, decode::Error>;
+    }
+
+    impl Sealed for IpAddr {
+        fn to_ip(self) -> IpAddr {
+            self
+        }
+
+        fn from_ip(a: IpAddr) -> Result<Self, decode::Error> {
+            Ok(a)
+        }
+    }
+
+    impl Sealed for Ipv4Addr {
+        fn to_ip(self) -> IpAddr {
+            IpAddr::V4(self)
+        }
+
+        fn from_ip(a: IpAddr) -> Result<Self, decode::Error> {
+            match a {
+                IpAddr::V4(a) => Ok(a),
+                IpAddr::V6(_) => Err(decode::Error::Message("expected ipv4 address (tag 52)"))
+            }
+        }
+    }
+
+    impl Sealed for Ipv6Addr {
+        fn to_ip(self) -> IpAddr {
+            IpAddr::V6(self)
+        }
+
+        fn from_ip(a: IpAddr) -> Result<Self, decode::Error> {
+            match a {
+                IpAddr::V6(a) => Ok(a),
+                IpAddr::V4(_) => Err(decode::Error::Message("expected ipv6 address (tag 54)"))
+            }
+        }
+    }
+}
+
+impl Ip for IpAddr {}
+impl Ip for Ipv4Addr {}
+impl Ip for Ipv6Addr {}
+
+impl<T: Ip> Prefix<T> {
+    /// Create a prefix of the given length.
+    ///
+    /// Address bits after the prefix length are set to zero. Returns `None`
+    /// if the length exceeds the number of address bits.
+    pub fn new(addr: T, len: u8) -> Option<Self> {
+        let (mut b, n, _) = octets(addr.to_ip());
+        if usize::from(len) > n * 8 {
+            return None
+        }
+        mask(&mut b[.. n], len);
+        let addr = T::from_ip(from_octets(&b[.. n])).ok()?;
+        Some(Prefix { addr, len })
+    }
+
+    /// The prefix address.
+    pub fn addr(&self) -> T {
+        self.addr
+    }
+
+    /// The prefix length in bits.
+    pub fn prefix_len(&self) -> u8 {
+        self.len
+    }
+}
+
+impl<T: Ip> Interface<T> {
+    /// Create an interface address with the given prefix length.
+    ///
+    /// Returns `None` if the length exceeds the number of address bits.
+    pub fn new(addr: T, len: u8) -> Option<Self> {
+        let (_, n, _) = octets(addr.to_ip());
+        if usize::from(len) > n * 8 {
+            return None
+        }
+        Some(Interface { addr, len })
+    }
+
+    /// The interface address.
+    pub fn addr(&self) -> T {
+        self.addr
+    }
+
+    /// The prefix length of the interface's network in bits.
+    pub fn prefix_len(&self) -> u8 {
+        self.len
+    }
+
+    /// The prefix of the interface's network.
+    pub fn prefix(&self) -> Prefix<T> {
+        Prefix::new(self.addr, self.len).expect("valid prefix length")
+    }
+}
+
+/// Get the octets, their number and the tag of an address.
+fn octets(a: IpAddr) -> ([u8; 16], usize, Tag) {
+    let mut b = [0; 16];
+    match a {
+        IpAddr::V4(a) => {
+            b[.. 4].copy_from_slice(&a.octets());
+            (b, 4, IPV4)
+        }
+        IpAddr::V6(a) => (a.octets(), 16, IPV6)
+    }
+}
+
+/// Create an address from 4 or 16 octets.
+fn from_octets(b: &[u8]) -> IpAddr {
+    if let Ok(a) = <[u8; 4]>::try_from(b) {
+        IpAddr::V4(a.into())
+    } else {
+        let mut a = [0; 16];
+        a.copy_from_slice(b);
+        IpAddr::V6(a.into())
+    }
+}
+
+/// Clear all bits after the given prefix length.
+fn mask(b: &mut [u8], len: u8) {
+    for (i, x) in b.iter_mut().enumerate() {
+        let k = usize::from(len).saturating_sub(i * 8).min(8) as u32;
+        *x &= !0xffu8.checked_shr(k).unwrap_or(0)
+    }
+}
+
+/// Decode tag 52 or 54 and return the number of address octets.
+fn decode_tag(d: &mut Decoder<'_>) -> Result<usize, decode::Error> {
+    match d.tag()? {
+        IPV4 => Ok(4),
+        IPV6 => Ok(16),
+        _    => Err(decode::Error::Message("expected tag 52 or 54 for ip address"))
+    }
+}
+
+/// Decode the octets of a full address.
+fn decode_octets(d: &mut Decoder<'_>, n: usize) -> Result<IpAddr, decode::Error> {
+    let b = d.bytes()?;
+    if b.len() != n {
+        return Err(decode::Error::Message("invalid ip address length"))
+    }
+    Ok(from_octets(b))
+}
+
+/// Decode a prefix length of an address with `n` octets.
+fn decode_prefix_len(d: &mut Decoder<'_>, n: usize) -> Result<u8, decode::Error> {
+    let len = d.u8()?;
+    if usize::from(len) > n * 8 {
+        return Err(decode::Error::Message("invalid ip prefix length"))
+    }
+    Ok(len)
+}
+
+impl<T: Ip> Encode for Address<T> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        address::encode(&self.0, e)
+    }
+}
+
+impl<'b, T: Ip> Decode<'b> for Address<T> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        address::decode(d).map(Address)
+    }
+}
+
+impl<T: Ip> Encode for Prefix<T> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        let (b, _, t) = octets(self.addr.to_ip());
+        let mut end = usize::from(self.len).div_ceil(8);
+        while end > 0 && b[end - 1] == 0 {
+            end -= 1
+        }
+        e.tag(t)?.array(2)?.u8(self.len)?.bytes(&b[.. end])?.ok()
+    }
+}
+
+impl<'b, T: Ip> Decode<'b> for Prefix<T> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        let n = decode_tag(d)?;
+        if Some(2) != d.array()? {
+            return Err(decode::Error::Message("expected ip prefix (2-element array)"))
+        }
+        let len = decode_prefix_len(d, n)?;
+        let p = d.bytes()?;
+        if p.len() > n {
+            return Err(decode::Error::Message("invalid ip prefix length"))
+        }
+        let mut b = [0; 16];
+        b[.. p.len()].copy_from_slice(p);
+        let mut m = b;
+        mask(&mut m[.. n], len);
+        if m != b {
+            return Err(decode::Error::Message("ip prefix has bits set after its length"))
+        }
+        let addr = T::from_ip(from_octets(&b[.. n]))?;
+        Ok(Prefix { addr, len })
+    }
+}
+
+impl<T: Ip> Encode for Interface<T> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        let (b, n, t) = octets(self.addr.to_ip());
+        e.tag(t)?.array(2)?.bytes(&b[.. n])?.u8(self.len)?.ok()
+    }
+}
+
+impl<'b, T: Ip> Decode<'b> for Interface<T> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        let n = decode_tag(d)?;
+        if Some(2) != d.array()? {
+            return Err(decode::Error::Message("expected ip interface (2-element array)"))
+        }
+        let addr = T::from_ip(decode_octets(d, n)?)?;
+        let len = decode_prefix_len(d, n)?;
+        Ok(Interface { addr, len })
+    }
+}
+
+/// Encode and decode IP addresses as tagged byte strings.
+///
+/// For use in `#[cbor(with = "minicbor::net::address")]`.
+pub mod address {
+    use super::*;
+
+    /// Encode an address with tag 52 or 54.
+    pub fn encode<T, W>(a: &T, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>>
+    where
+        T: Ip,
+        W: Write
+    {
+        let (b, n, t) = octets(a.to_ip());
+        e.tag(t)?.bytes(&b[.. n])?.ok()
+    }
+
+    /// Decode an address with tag 52 or 54.
+    pub fn decode<T: Ip>(d: &mut Decoder<'_>) -> Result<T, decode::Error> {
+        let n = decode_tag(d)?;
+        T::from_ip(decode_octets(d, n)?)
+    }
+}
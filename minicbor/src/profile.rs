@@ -0,0 +1,1041 @@
+//! Encoding profiles, i.e. sets of rules CBOR items must follow.
+//!
+//! Many protocols restrict CBOR to a canonical subset, so that every value
+//! has exactly one encoding. A [`Profile`] describes such a subset, i.e.
+//! whether arguments use their shortest form, how map keys are ordered,
+//! which tags and simple values are allowed, how duplicate map keys are
+//! handled and how floats are encoded. The following profiles are built in:
+//!
+//! - [`CoreDeterministic`]: the core deterministic encoding requirements of
+//!   [RFC 8949, section 4.2.1][1]. This is also the default of every profile
+//!   method.
+//! - [`DCbor`]: the [dCBOR][2] application profile, which in addition
+//!   reduces integral floats to integers and only allows the simple values
+//!   `false`, `true` and `null`.
+//! - [`Ctap2`]: the [canonical CBOR form of CTAP2][3] (cf. [`crate::ctap2`]
+//!   with feature `"ctap2"`).
+//! - [`DagCbor`]: [DAG-CBOR][4], the strict CBOR profile of IPLD (cf.
+//!   [`crate::dag_cbor`] with feature `"dag-cbor"`).
+//!
+//! Applications with bespoke interoperability rules can implement `Profile`
+//! themselves, overriding only the methods which differ from the defaults.
+//!
+//! An item is *normalised* according to a profile by re-encoding it with
+//! definite lengths and the arguments, map entries and floats the profile
+//! requires. Items which violate the rules in ways that can not be normalised
+//! (e.g. disallowed tags or duplicate map keys) result in an error. An item
+//! is *valid* if normalising it does not change it.
+//!
+//! [`Encoder::with_profile`] normalises items while they are written (cf.
+//! [`Writer`]) and [`to_vec`] encodes values with it. [`Decoder::with_profile`]
+//! requires valid input, which is checked in a single pass without
+//! normalising it, and [`decode`] decodes values with it. A [`Decoder`] can
+//! also check individual items with [`Decoder::check_profile`].
+//!
+//! *Requires feature* `"alloc"`.
+//!
+//! ```
+//! use minicbor::profile::{self, CoreDeterministic, DCbor, Profile};
+//! use std::collections::HashMap;
+//!
+//! let mut m = HashMap::new();
+//! m.insert(10, 2.0);
+//! m.insert(-1, 1.5);
+//!
+//! let cbor = profile::to_vec(&CoreDeterministic, &m)?;
+//! assert_eq!(b"\xa2\x0a\xf9\x40\x00\x20\xf9\x3e\x00", cbor.as_slice());
+//!
+//! let cbor = profile::to_vec(&DCbor, &m)?;
+//! assert_eq!(b"\xa2\x0a\x02\x20\xf9\x3e\x00", cbor.as_slice());
+//!
+//! // A bespoke profile which only allows tag 1.
+//! struct Timestamps;
+//!
+//! impl Profile for Timestamps {
+//!     fn tag(&self, t: u64) -> bool {
+//!         t == 1
+//!     }
+//! }
+//!
+//! assert!(profile::validate(&Timestamps, b"\xc1\x1a\x51\x4b\x67\xb0").is_ok());
+//! assert!(profile::validate(&Timestamps, b"\xc0\x60").is_err());
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-4.2.1
+//! [2]: https://datatracker.ietf.org/doc/draft-mcnally-deterministic-cbor/
+//! [3]: https://fidoalliance.org/specs/fido-v2.1-ps-20210615/fido-client-to-authenticator-protocol-v2.1-ps-20210615.html#ctap2-canonical-cbor-encoding-form
+//! [4]: https://ipld.io/specs/codecs/dag-cbor/spec/
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt;
+use crate::decode::{Decode, Decoder, Error};
+use crate::encode::{self, Encode, Encoder, Write};
+use crate::{UNSIGNED, SIGNED, BYTES, TEXT, ARRAY, MAP, TAGGED, SIMPLE};
+
+/// Max. nesting depth of items.
+const MAX_DEPTH: usize = 256;
+
+/// A set of rules CBOR items must follow.
+///
+/// Every method has a default which corresponds to the core deterministic
+/// encoding requirements of RFC 8949 (cf. [`CoreDeterministic`]).
+pub trait Profile {
+    /// Do integers, lengths, tags and simple values use the shortest form
+    /// of their argument?
+    ///
+    /// If not, arguments are kept as encoded. Lengths of indefinite-length
+    /// items, which are always made definite, use the shortest form.
+    fn shortest_arguments(&self) -> bool {
+        true
+    }
+
+    /// The order of map entries.
+    fn key_order(&self) -> KeyOrder {
+        KeyOrder::Bytewise
+    }
+
+    /// Are only text strings allowed as map keys?
+    fn text_keys(&self) -> bool {
+        false
+    }
+
+    /// How are duplicate map keys handled?
+    fn duplicate_keys(&self) -> DuplicateKeys {
+        DuplicateKeys::Reject
+    }
+
+    /// Is the given tag allowed?
+    fn tag(&self, _t: u64) -> bool {
+        true
+    }
+
+    /// Is the given simple value allowed (incl. 20 to 23, i.e. `false`,
+    /// `true`, `null` and `undefined`)?
+    fn simple(&self, _s: u8) -> bool {
+        true
+    }
+
+    /// The encoding of floats.
+    fn floats(&self) -> Floats {
+        Floats::Shortest
+    }
+
+    /// Are NaN and infinities allowed?
+    fn non_finite(&self) -> bool {
+        true
+    }
+}
+
+impl<P: Profile + ?Sized> Profile for &P {
+    fn shortest_arguments(&self) -> bool { (**self).shortest_arguments() }
+    fn key_order(&self) -> KeyOrder { (**self).key_order() }
+    fn text_keys(&self) -> bool { (**self).text_keys() }
+    fn duplicate_keys(&self) -> DuplicateKeys { (**self).duplicate_keys() }
+    fn tag(&self, t: u64) -> bool { (**self).tag(t) }
+    fn simple(&self, s: u8) -> bool { (**self).simple(s) }
+    fn floats(&self) -> Floats { (**self).floats() }
+    fn non_finite(&self) -> bool { (**self).non_finite() }
+}
+
+/// The order of map entries, based on the normalised encoding of their keys.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyOrder {
+    /// Bytewise lexicographic order (cf. [`crate::cmp`]).
+    Bytewise,
+    /// Shorter keys first, keys of equal length in bytewise lexicographic
+    /// order (the canonical order of RFC 7049).
+    LengthFirst,
+    /// Keys of lower major type first, then like `LengthFirst`.
+    MajorTypeFirst,
+    /// Map entries are kept in the order in which they are encoded.
+    Preserve
+}
+
+/// The handling of duplicate map keys.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateKeys {
+    /// Duplicate keys are an error.
+    Reject,
+    /// The first entry of a key is kept, all others are removed.
+    KeepFirst,
+    /// The last entry of a key is kept, all others are removed.
+    KeepLast
+}
+
+/// The encoding of floats.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Floats {
+    /// Floats are kept as encoded.
+    Preserve,
+    /// Floats use the shortest form which preserves their value. NaN is
+    /// always encoded as `0xf97e00`.
+    Shortest,
+    /// Floats are always encoded as `f64`.
+    Double,
+    /// Floats without a fractional part are encoded as integers, if in the
+    /// range of CBOR integers, all others like `Shortest`.
+    Reduced
+}
+
+/// The core deterministic encoding requirements of [RFC 8949, section 4.2.1][1].
+///
+/// [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-4.2.1
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CoreDeterministic;
+
+impl Profile for CoreDeterministic {}
+
+/// The [dCBOR][1] application profile.
+///
+/// In addition to the core deterministic encoding requirements, floats are
+/// reduced to integers where possible and only `false`, `true` and `null`
+/// are allowed as simple values. Unicode normalisation of text strings is
+/// not checked.
+///
+/// [1]: https://datatracker.ietf.org/doc/draft-mcnally-deterministic-cbor/
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DCbor;
+
+impl Profile for DCbor {
+    fn simple(&self, s: u8) -> bool {
+        (20 ..= 22).contains(&s)
+    }
+
+    fn floats(&self) -> Floats {
+        Floats::Reduced
+    }
+}
+
+/// The [canonical CBOR form of CTAP2][1].
+///
+/// [1]: https://fidoalliance.org/specs/fido-v2.1-ps-20210615/fido-client-to-authenticator-protocol-v2.1-ps-20210615.html#ctap2-canonical-cbor-encoding-form
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ctap2;
+
+impl Profile for Ctap2 {
+    fn key_order(&self) -> KeyOrder {
+        KeyOrder::MajorTypeFirst
+    }
+
+    fn tag(&self, _t: u64) -> bool {
+        false
+    }
+}
+
+/// [DAG-CBOR][1], the strict CBOR profile of IPLD.
+///
+/// [1]: https://ipld.io/specs/codecs/dag-cbor/spec/
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DagCbor;
+
+impl Profile for DagCbor {
+    fn key_order(&self) -> KeyOrder {
+        KeyOrder::LengthFirst
+    }
+
+    fn text_keys(&self) -> bool {
+        true
+    }
+
+    fn tag(&self, t: u64) -> bool {
+        t == 42
+    }
+
+    fn simple(&self, s: u8) -> bool {
+        (20 ..= 22).contains(&s)
+    }
+
+    fn floats(&self) -> Floats {
+        Floats::Double
+    }
+
+    fn non_finite(&self) -> bool {
+        false
+    }
+}
+
+/// Encode a value according to the given profile.
+///
+/// The value is encoded with [`Encoder::with_profile`], which fails with an
+/// [`encode::Error::Message`] if the value can not be represented.
+pub fn to_vec<P, T>(p: &P, x: T) -> Result<Vec<u8>, encode::Error<<Vec<u8> as Write>::Error>>
+where
+    P: Profile + ?Sized,
+    T: Encode
+{
+    let mut e = Encoder::with_profile(p, Vec::new());
+    e.encode(x).map_err(|e| match e {
+        encode::Error::Write(WriteError::Write(e))   => encode::Error::Write(e),
+        encode::Error::Write(WriteError::Profile(m)) => encode::Error::Message(m),
+        encode::Error::Message(m)                    => encode::Error::Message(m)
+    })?;
+    e.into_inner().into_inner().map_err(|e| match e {
+        WriteError::Write(e)   => encode::Error::Write(e),
+        WriteError::Profile(m) => encode::Error::Message(m)
+    })
+}
+
+/// Normalise the single CBOR item of the given bytes.
+///
+/// Fails if the item is malformed or can not be represented, e.g. because
+/// of duplicate map keys or disallowed tags.
+pub fn normalise<P: Profile + ?Sized>(p: &P, b: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut w = Writer::new(p, Vec::with_capacity(b.len()));
+    let n = w.feed(b, true).map_err(|e| match e {
+        WriteError::Profile(m) => Error::Message(m),
+        WriteError::Write(_)   => Error::Message("failed to write normalised item")
+    })?;
+    if !w.is_complete() {
+        return Err(Error::EndOfInput)
+    }
+    if n < b.len() {
+        return Err(Error::TrailingBytes(n))
+    }
+    Ok(w.writer)
+}
+
+/// Check that the single CBOR item of the given bytes follows the profile.
+pub fn validate<P: Profile + ?Sized>(p: &P, b: &[u8]) -> Result<(), Error> {
+    let mut d = Decoder::new(b);
+    check(p, &mut d)?;
+    if d.position() < b.len() {
+        return Err(Error::TrailingBytes(d.position()))
+    }
+    Ok(())
+}
+
+/// Decode a value according to the given profile.
+///
+/// The input must contain a single item which follows the profile (cf.
+/// [`Decoder::with_profile`]).
+pub fn decode<'b, P, T>(p: &P, b: &'b [u8]) -> Result<T, Error>
+where
+    P: Profile + ?Sized,
+    T: Decode<'b>
+{
+    let mut d = Decoder::with_profile(p, b)?;
+    let x = d.decode()?;
+    if d.position() < b.len() {
+        return Err(Error::TrailingBytes(d.position()))
+    }
+    Ok(x)
+}
+
+/// Check that the next item of the decoder follows the profile and move
+/// past it (cf. [`Decoder::check_profile`]).
+///
+/// Every head is compared with its normalised form in place, i.e. the item
+/// is not re-encoded. Nested items are tracked on an explicit stack whose
+/// size is bounded by the max. nesting depth of the decoder.
+pub(crate) fn check<'b, P: Profile + ?Sized>(p: &P, d: &mut Decoder<'b>) -> Result<(), Error> {
+    // For every array, map or tag we are currently in, the stack contains
+    // the number of items left to check. Maps count keys and values
+    // separately and collect their keys.
+    let mut stack: Vec<(u64, Option<Keys<'b>>)> = Vec::new();
+    let mut norm = Vec::new();
+
+    loop {
+        if stack.len() >= d.max_depth() {
+            return Err(Error::Message("maximum nesting depth exceeded"))
+        }
+        if let Some((n, Some(keys))) = stack.last_mut() {
+            if *n % 2 == 0 {
+                keys.start = d.position()
+            }
+        }
+        let start = d.position();
+        let (major, info, arg) = head(d)?;
+        let original = &d.input()[start .. d.position()];
+        norm.clear();
+        let nested = match (major, arg) {
+            (_, None) if major != SIMPLE => return Err(Error::Message("item is not in canonical form")),
+            (UNSIGNED, Some(n)) | (SIGNED, Some(n)) => {
+                put_arg(&mut norm, p, major, n, original);
+                0
+            }
+            (BYTES, Some(n)) | (TEXT, Some(n)) => {
+                put_len(&mut norm, p, major, n, arg, original);
+                let len = usize::try_from(n).map_err(|_| Error::Overflow(n, "u64->usize"))?;
+                let b = d.read_slice(len)?;
+                if major == TEXT {
+                    crate::decode::from_utf8(b)?;
+                }
+                0
+            }
+            (ARRAY, Some(n)) => {
+                put_len(&mut norm, p, major, n, arg, original);
+                n
+            }
+            (MAP, Some(n)) => {
+                put_len(&mut norm, p, major, n, arg, original);
+                n.saturating_mul(2)
+            }
+            (TAGGED, Some(t)) => {
+                if !p.tag(t) {
+                    return Err(Error::Message("tag not allowed"))
+                }
+                put_arg(&mut norm, p, TAGGED, t, original);
+                1
+            }
+            _ => {
+                simple(&mut norm, p, info, arg, original).map_err(Error::Message)?;
+                0
+            }
+        };
+        if norm != original {
+            return Err(Error::Message("item is not in canonical form"))
+        }
+        if nested > 0 {
+            let keys = if major == MAP { Some(Keys { start: 0, keys: Vec::new() }) } else { None };
+            stack.push((nested, keys));
+            continue
+        }
+        // We have checked a complete item. Account for it in the enclosing
+        // container and close every container which is done.
+        loop {
+            match stack.last_mut() {
+                None => return Ok(()),
+                Some((n, keys)) => {
+                    if let Some(k) = keys {
+                        if *n % 2 == 0 {
+                            let key = &d.input()[k.start .. d.position()];
+                            if p.text_keys() && key[0] & 0xe0 != TEXT {
+                                return Err(Error::Message("map keys must be text strings"))
+                            }
+                            k.keys.push(key)
+                        }
+                    }
+                    *n -= 1;
+                    if *n > 0 {
+                        break
+                    }
+                    if let Some((_, Some(k))) = stack.pop() {
+                        check_keys(p, k.keys)?
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The keys of a map, which are checked once the map is complete.
+struct Keys<'b> {
+    /// The start of the current key.
+    start: usize,
+    /// The keys checked so far.
+    keys: Vec<&'b [u8]>
+}
+
+/// Check that map keys are unique and in the order of the profile.
+fn check_keys<P: Profile + ?Sized>(p: &P, mut keys: Vec<&[u8]>) -> Result<(), Error> {
+    let order = p.key_order();
+    let unique = if order == KeyOrder::Preserve {
+        keys.sort();
+        keys.windows(2).all(|w| w[0] != w[1])
+    } else {
+        if keys.windows(2).any(|w| compare(order, w[0], w[1]) == Ordering::Greater) {
+            return Err(Error::Message("item is not in canonical form"))
+        }
+        keys.windows(2).all(|w| w[0] != w[1])
+    };
+    match (unique, p.duplicate_keys()) {
+        (true, _) => Ok(()),
+        (false, DuplicateKeys::Reject) => Err(Error::Message("duplicate map key")),
+        (false, _) => Err(Error::Message("item is not in canonical form"))
+    }
+}
+
+/// A [`Write`] impl which normalises the CBOR items written to it according
+/// to a profile and passes them on to an inner writer.
+///
+/// Every head is normalised as soon as it is complete, e.g. arguments are
+/// written in their shortest form and floats are converted as the profile
+/// requires. Only maps, whose entries need to be sorted and checked for
+/// duplicates, and items of indefinite length, whose length is known only
+/// at their end, are buffered until they are complete. Items which can not
+/// be normalised result in an error.
+///
+/// [`Encoder::with_profile`] constructs an [`Encoder`] which writes through
+/// a `Writer`.
+#[derive(Debug, Clone)]
+pub struct Writer<'p, P: ?Sized, W> {
+    profile: &'p P,
+    writer: W,
+    /// The head which is currently written.
+    head: [u8; 9],
+    head_len: usize,
+    /// The number of content bytes left of the current string.
+    content: u64,
+    /// Is the current string a text string?
+    text: bool,
+    /// An incomplete UTF-8 sequence at the end of the text written so far.
+    utf8: Vec<u8>,
+    /// The arrays, maps and indefinite-length strings which are currently written.
+    stack: Vec<Level>,
+    /// Scratch space for normalised heads and floats.
+    norm: Vec<u8>
+}
+
+/// An array, map or indefinite-length string a [`Writer`] is inside of.
+#[derive(Debug, Clone)]
+struct Level {
+    major: u8,
+    /// The original head.
+    head: Vec<u8>,
+    /// The number of items left, or `None` for indefinite lengths.
+    left: Option<u64>,
+    /// The number of items written so far.
+    count: u64,
+    /// The buffered content, unless it is written through, which is the
+    /// case for arrays of definite length.
+    buf: Option<Vec<u8>>,
+    /// The entries of a map and the key of the current entry.
+    entries: Vec<Entry>,
+    key: Option<Vec<u8>>
+}
+
+impl<'p, P: Profile + ?Sized, W: Write> Writer<'p, P, W> {
+    /// Create a new `Writer` which normalises items according to the given
+    /// profile and writes them to `w`.
+    pub fn new(p: &'p P, w: W) -> Self {
+        Writer {
+            profile: p,
+            writer: w,
+            head: [0; 9],
+            head_len: 0,
+            content: 0,
+            text: false,
+            utf8: Vec::new(),
+            stack: Vec::new(),
+            norm: Vec::new()
+        }
+    }
+
+    /// Get a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Get back the inner writer.
+    ///
+    /// Fails if the last item written is incomplete.
+    pub fn into_inner(self) -> Result<W, WriteError<W::Error>> {
+        if !self.is_complete() {
+            return Err(WriteError::Profile("incomplete item"))
+        }
+        Ok(self.writer)
+    }
+
+    /// Have all items which have been written been completed?
+    fn is_complete(&self) -> bool {
+        self.head_len == 0 && self.content == 0 && self.stack.is_empty()
+    }
+
+    /// Process the given bytes, but only up to the end of the first complete
+    /// item if `one` is set.
+    ///
+    /// Returns the number of bytes processed.
+    fn feed(&mut self, buf: &[u8], one: bool) -> Result<usize, WriteError<W::Error>> {
+        let mut i = 0;
+        while let Some(b) = buf.get(i) {
+            if self.content > 0 {
+                let k = usize::try_from(self.content).unwrap_or(usize::MAX).min(buf.len() - i);
+                let piece = &buf[i .. i + k];
+                if self.text {
+                    self.check_utf8(piece)?
+                }
+                self.emit(piece)?;
+                self.content -= k as u64;
+                i += k;
+                if self.content == 0 && self.string_done()? && one {
+                    return Ok(i)
+                }
+                continue
+            }
+            self.head[self.head_len] = *b;
+            self.head_len += 1;
+            i += 1;
+            let size = match self.head[0] & 0x1f {
+                0 ..= 23 | 31 => 1,
+                24 => 2,
+                25 => 3,
+                26 => 5,
+                27 => 9,
+                _  => return Err(WriteError::Profile("reserved additional information"))
+            };
+            if self.head_len < size {
+                continue
+            }
+            self.head_len = 0;
+            if self.on_head(size)? && one {
+                return Ok(i)
+            }
+        }
+        Ok(i)
+    }
+
+    /// Process a complete head of the given size.
+    ///
+    /// Returns true if a top-level item is complete.
+    fn on_head(&mut self, size: usize) -> Result<bool, WriteError<W::Error>> {
+        let original = self.head;
+        let original = &original[.. size];
+        let (major, info) = (original[0] & 0xe0, original[0] & 0x1f);
+        let arg = match size {
+            _ if info == 31 => None,
+            1 => Some(u64::from(info)),
+            _ => Some(be(&original[1 ..]))
+        };
+        if let Some(l) = self.stack.last() {
+            if l.major == BYTES || l.major == TEXT {
+                return match arg {
+                    Some(n) if major == l.major => {
+                        self.content = n;
+                        self.text = major == TEXT;
+                        Ok(false)
+                    }
+                    None if major == SIMPLE => self.close(),
+                    _ => Err(WriteError::Profile("invalid chunk in indefinite-length string"))
+                }
+            }
+        }
+        let p = self.profile;
+        let mut norm = core::mem::take(&mut self.norm);
+        norm.clear();
+        let result = match (major, arg) {
+            (UNSIGNED, None) | (SIGNED, None) | (TAGGED, None) => {
+                Err(WriteError::Profile("unexpected indefinite length"))
+            }
+            (SIMPLE, None) => self.close(),
+            (UNSIGNED, Some(n)) | (SIGNED, Some(n)) => {
+                put_arg(&mut norm, p, major, n, original);
+                self.emit(&norm).and_then(|()| self.done())
+            }
+            (BYTES, Some(n)) | (TEXT, Some(n)) => {
+                put_len(&mut norm, p, major, n, arg, original);
+                self.content = n;
+                self.text = major == TEXT;
+                self.emit(&norm).and_then(|()| if n == 0 { self.done() } else { Ok(false) })
+            }
+            (ARRAY, Some(n)) => {
+                put_len(&mut norm, p, major, n, arg, original);
+                self.emit(&norm).and_then(|()| {
+                    if n == 0 {
+                        self.done()
+                    } else {
+                        self.push(major, original, arg, None).map(|()| false)
+                    }
+                })
+            }
+            (MAP, Some(0)) => {
+                put_len(&mut norm, p, major, 0, arg, original);
+                self.emit(&norm).and_then(|()| self.done())
+            }
+            (MAP, Some(n)) => self.push(major, original, Some(n.saturating_mul(2)), Some(Vec::new())).map(|()| false),
+            (_, None) => self.push(major, original, None, Some(Vec::new())).map(|()| false),
+            (TAGGED, Some(t)) => {
+                if p.tag(t) {
+                    put_arg(&mut norm, p, TAGGED, t, original);
+                    self.emit(&norm).map(|()| false)
+                } else {
+                    Err(WriteError::Profile("tag not allowed"))
+                }
+            }
+            _ => match simple(&mut norm, p, info, arg, original) {
+                Ok(())  => self.emit(&norm).and_then(|()| self.done()),
+                Err(m) => Err(WriteError::Profile(m))
+            }
+        };
+        self.norm = norm;
+        result
+    }
+
+    /// Enter an array, map or indefinite-length string.
+    fn push(&mut self, major: u8, head: &[u8], left: Option<u64>, buf: Option<Vec<u8>>) -> Result<(), WriteError<W::Error>> {
+        if self.stack.len() >= MAX_DEPTH {
+            return Err(WriteError::Profile("maximum nesting depth exceeded"))
+        }
+        self.stack.push(Level {
+            major,
+            head: head.to_vec(),
+            left,
+            count: 0,
+            buf,
+            entries: Vec::new(),
+            key: None
+        });
+        Ok(())
+    }
+
+    /// Account for a complete item in the enclosing container and close
+    /// every container of definite length which is done.
+    ///
+    /// Returns true if a top-level item is complete.
+    fn done(&mut self) -> Result<bool, WriteError<W::Error>> {
+        loop {
+            let l = match self.stack.last_mut() {
+                Some(l) => l,
+                None    => return Ok(true)
+            };
+            if l.major == MAP {
+                let item = l.buf.as_mut().map(core::mem::take).unwrap_or_default();
+                match l.key.take() {
+                    None => {
+                        if self.profile.text_keys() && item[0] & 0xe0 != TEXT {
+                            return Err(WriteError::Profile("map keys must be text strings"))
+                        }
+                        l.key = Some(item)
+                    }
+                    Some(k) => l.entries.push((k, item))
+                }
+            }
+            l.count += 1;
+            match &mut l.left {
+                None    => return Ok(false),
+                Some(n) => {
+                    *n -= 1;
+                    if *n > 0 {
+                        return Ok(false)
+                    }
+                }
+            }
+            if let Some(l) = self.stack.pop() {
+                self.finish_level(l)?
+            }
+        }
+    }
+
+    /// Close the innermost item of indefinite length.
+    ///
+    /// Returns true if a top-level item is complete.
+    fn close(&mut self) -> Result<bool, WriteError<W::Error>> {
+        match self.stack.pop() {
+            Some(l) if l.left.is_none() => {
+                if l.key.is_some() {
+                    return Err(WriteError::Profile("map entry without value"))
+                }
+                self.finish_level(l)?;
+                self.done()
+            }
+            _ => Err(WriteError::Profile("unexpected break"))
+        }
+    }
+
+    /// Write the head and buffered content of a complete array, map or
+    /// string.
+    fn finish_level(&mut self, l: Level) -> Result<(), WriteError<W::Error>> {
+        let p = self.profile;
+        let mut out = Vec::new();
+        match l.major {
+            ARRAY => if let Some(b) = l.buf {
+                put_len(&mut out, p, ARRAY, l.count, l.left, &l.head);
+                out.extend_from_slice(&b)
+            }
+            MAP => {
+                let len = l.head_len();
+                let mut entries = dedup(p.duplicate_keys(), l.entries).map_err(|_| WriteError::Profile("duplicate map key"))?;
+                let order = p.key_order();
+                if order != KeyOrder::Preserve {
+                    entries.sort_by(|a, b| compare(order, &a.0, &b.0))
+                }
+                let n = entries.len() as u64;
+                put_len(&mut out, p, MAP, n, len, &l.head);
+                for (k, v) in entries {
+                    out.extend_from_slice(&k);
+                    out.extend_from_slice(&v)
+                }
+            }
+            major => {
+                let b = l.buf.unwrap_or_default();
+                put_len(&mut out, p, major, b.len() as u64, None, &l.head);
+                out.extend_from_slice(&b)
+            }
+        }
+        self.emit(&out)
+    }
+
+    /// Account for the end of a string or string chunk.
+    ///
+    /// Returns true if a top-level item is complete.
+    fn string_done(&mut self) -> Result<bool, WriteError<W::Error>> {
+        if !self.utf8.is_empty() {
+            return Err(WriteError::Profile("invalid utf-8"))
+        }
+        match self.stack.last() {
+            Some(l) if l.major == BYTES || l.major == TEXT => Ok(false),
+            _ => self.done()
+        }
+    }
+
+    /// Check that text content is valid UTF-8, with the possible exception
+    /// of an incomplete sequence at its end, which is kept until the rest of
+    /// the text has been written.
+    fn check_utf8(&mut self, b: &[u8]) -> Result<(), WriteError<W::Error>> {
+        let mut text = b;
+        let carry;
+        if !self.utf8.is_empty() {
+            let mut t = core::mem::take(&mut self.utf8);
+            t.extend_from_slice(b);
+            carry = t;
+            text = &carry
+        }
+        match core::str::from_utf8(text) {
+            Ok(_) => Ok(()),
+            Err(e) if e.error_len().is_none() => {
+                self.utf8 = text[e.valid_up_to() ..].to_vec();
+                Ok(())
+            }
+            Err(_) => Err(WriteError::Profile("invalid utf-8"))
+        }
+    }
+
+    /// Write normalised bytes to the innermost buffer or the inner writer.
+    fn emit(&mut self, b: &[u8]) -> Result<(), WriteError<W::Error>> {
+        if let Some(buf) = self.stack.iter_mut().rev().find_map(|l| l.buf.as_mut()) {
+            buf.extend_from_slice(b);
+            return Ok(())
+        }
+        self.writer.write_all(b).map_err(WriteError::Write)
+    }
+}
+
+impl Level {
+    /// The length of the original head, if definite.
+    fn head_len(&self) -> Option<u64> {
+        if self.left.is_some() {
+            Some(self.count / if self.major == MAP { 2 } else { 1 })
+        } else {
+            None
+        }
+    }
+}
+
+impl<'p, P: Profile + ?Sized, W: Write> Write for Writer<'p, P, W> {
+    type Error = WriteError<W::Error>;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.feed(buf, false).map(|_| ())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.writer.flush().map_err(WriteError::Write)
+    }
+
+    fn finish(self) -> Result<(), Self::Error> {
+        self.into_inner()?.finish().map_err(WriteError::Write)
+    }
+}
+
+/// The error of a [`Writer`].
+#[derive(Debug)]
+pub enum WriteError<E> {
+    /// Writing to the inner writer failed.
+    Write(E),
+    /// An item can not be normalised according to the profile.
+    Profile(&'static str)
+}
+
+impl<E: fmt::Display> fmt::Display for WriteError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WriteError::Write(e)   => write!(f, "write error: {}", e),
+            WriteError::Profile(m) => f.write_str(m)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + 'static> std::error::Error for WriteError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WriteError::Write(e)   => Some(e),
+            WriteError::Profile(_) => None
+        }
+    }
+}
+
+/// Write the normalised form of a simple value or float.
+fn simple<P>(out: &mut Vec<u8>, p: &P, info: u8, arg: Option<u64>, original: &[u8]) -> Result<(), &'static str>
+where
+    P: Profile + ?Sized
+{
+    match (info, arg) {
+        (0 ..= 24, Some(n)) => {
+            if info == 24 && n < 32 {
+                return Err("invalid simple value")
+            }
+            if !p.simple(n as u8) {
+                return Err("simple value not allowed")
+            }
+            put_arg(out, p, SIMPLE, n, original)
+        }
+        (25 ..= 27, Some(n)) => {
+            let x = match info {
+                25 => crate::ext::f16_to_f64(n as u16),
+                26 => f64::from(f32::from_bits(n as u32)),
+                _  => f64::from_bits(n)
+            };
+            if !p.non_finite() && !x.is_finite() {
+                return Err("non-finite floating point value")
+            }
+            match p.floats() {
+                Floats::Preserve => out.extend_from_slice(original),
+                Floats::Double   => {
+                    out.push(SIMPLE | 27);
+                    out.extend_from_slice(&x.to_bits().to_be_bytes())
+                }
+                Floats::Shortest => shortest(out, x),
+                Floats::Reduced  => reduced(out, x)
+            }
+        }
+        _ => return Err("unexpected break")
+    }
+    Ok(())
+}
+
+/// The encoded key and value of a map entry.
+type Entry = (Vec<u8>, Vec<u8>);
+
+/// Remove duplicate map entries or reject them.
+///
+/// The relative order of the remaining entries is unchanged.
+fn dedup(policy: DuplicateKeys, entries: Vec<Entry>) -> Result<Vec<Entry>, Error> {
+    let mut ix = (0 .. entries.len()).collect::<Vec<_>>();
+    ix.sort_by(|a, b| entries[*a].0.cmp(&entries[*b].0));
+    let mut keep = alloc::vec![true; entries.len()];
+    for w in ix.windows(2) {
+        if entries[w[0]].0 == entries[w[1]].0 {
+            match policy {
+                DuplicateKeys::Reject    => return Err(Error::Message("duplicate map key")),
+                DuplicateKeys::KeepFirst => keep[w[1]] = false,
+                DuplicateKeys::KeepLast  => keep[w[0]] = false
+            }
+        }
+    }
+    Ok(entries.into_iter().zip(keep).filter_map(|(e, k)| k.then_some(e)).collect())
+}
+
+/// Read the initial byte and argument of a data item.
+///
+/// Returns the major type, the additional information and the argument,
+/// which is `None` for indefinite lengths and break.
+fn head(d: &mut Decoder) -> Result<(u8, u8, Option<u64>), Error> {
+    let b = d.read_slice(1)?[0];
+    let (major, info) = (b & 0xe0, b & 0x1f);
+    let arg = match info {
+        0 ..= 23 => Some(u64::from(info)),
+        24       => Some(u64::from(d.read_slice(1)?[0])),
+        25       => Some(be(d.read_slice(2)?)),
+        26       => Some(be(d.read_slice(4)?)),
+        27       => Some(be(d.read_slice(8)?)),
+        31       => None,
+        _        => return Err(Error::Message("reserved additional information"))
+    };
+    if arg.is_none() && (major == UNSIGNED || major == SIGNED || major == TAGGED) {
+        return Err(Error::Message("unexpected indefinite length"))
+    }
+    Ok((major, info, arg))
+}
+
+fn be(b: &[u8]) -> u64 {
+    b.iter().fold(0, |n, x| n << 8 | u64::from(*x))
+}
+
+/// Write the head of an integer, tag or simple value.
+///
+/// The original head is kept if the profile does not require the shortest
+/// argument.
+fn put_arg<P: Profile + ?Sized>(out: &mut Vec<u8>, p: &P, major: u8, n: u64, original: &[u8]) {
+    if p.shortest_arguments() {
+        put_head(out, major, n)
+    } else {
+        out.extend_from_slice(original)
+    }
+}
+
+/// Write the head of a string, array or map of length `n`.
+///
+/// The original head is kept if the profile does not require the shortest
+/// argument and the original length is definite and equal to `n`.
+fn put_len<P>(out: &mut Vec<u8>, p: &P, major: u8, n: u64, len: Option<u64>, original: &[u8])
+where
+    P: Profile + ?Sized
+{
+    if !p.shortest_arguments() && len == Some(n) {
+        out.extend_from_slice(original)
+    } else {
+        put_head(out, major, n)
+    }
+}
+
+/// Write the initial byte and shortest argument of a data item.
+fn put_head(out: &mut Vec<u8>, major: u8, n: u64) {
+    match n {
+        0        ..= 0x17        => out.push(major | n as u8),
+        0x18     ..= 0xff        => out.extend_from_slice(&[major | 24, n as u8]),
+        0x100    ..= 0xffff      => {
+            out.push(major | 25);
+            out.extend_from_slice(&(n as u16).to_be_bytes())
+        }
+        0x1_0000 ..= 0xffff_ffff => {
+            out.push(major | 26);
+            out.extend_from_slice(&(n as u32).to_be_bytes())
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend_from_slice(&n.to_be_bytes())
+        }
+    }
+}
+
+fn compare(order: KeyOrder, a: &[u8], b: &[u8]) -> Ordering {
+    match order {
+        KeyOrder::Bytewise       => a.cmp(b),
+        KeyOrder::LengthFirst    => a.len().cmp(&b.len()).then_with(|| a.cmp(b)),
+        KeyOrder::MajorTypeFirst => (a[0] & 0xe0).cmp(&(b[0] & 0xe0))
+            .then_with(|| a.len().cmp(&b.len()))
+            .then_with(|| a.cmp(b)),
+        KeyOrder::Preserve => Ordering::Equal
+    }
+}
+
+/// Write a float in the shortest form which preserves its value.
+///
+/// NaN is always encoded as `0xf97e00`.
+fn shortest(out: &mut Vec<u8>, x: f64) {
+    if x.is_nan() {
+        out.extend_from_slice(&[SIMPLE | 25, 0x7e, 0])
+    } else if f64::from(x as f32) != x {
+        out.push(SIMPLE | 27);
+        out.extend_from_slice(&x.to_bits().to_be_bytes())
+    } else if let Some(h) = crate::ext::f16_bits(x as f32) {
+        out.push(SIMPLE | 25);
+        out.extend_from_slice(&h.to_be_bytes())
+    } else {
+        out.push(SIMPLE | 26);
+        out.extend_from_slice(&(x as f32).to_bits().to_be_bytes())
+    }
+}
+
+/// Write a float without fractional part as integer, if possible, or else
+/// in the shortest form which preserves its value.
+fn reduced(out: &mut Vec<u8>, x: f64) {
+    // 2^64 is exactly representable, so these are the bounds of CBOR integers.
+    const LIMIT: f64 = 18_446_744_073_709_551_616.0;
+    if x.is_finite() && x.trunc() == x {
+        if (0.0 .. LIMIT).contains(&x) {
+            return put_head(out, UNSIGNED, x as u64)
+        }
+        if (-LIMIT .. 0.0).contains(&x) {
+            return put_head(out, SIGNED, (-1.0 - x) as u64)
+        }
+    }
+    shortest(out, x)
+}
@@ -0,0 +1,432 @@
+//! Generation of [CDDL][1] schemas.
+//!
+//! Types implementing [`Schema`] describe their CBOR encoding as a CDDL
+//! type. For structs and enums the trait can be derived (*requires feature*
+//! `"derive"`) alongside `Encode` and `Decode`, using the same attributes,
+//! so that schemas used for documentation or validation never diverge from
+//! the Rust definitions. [`cddl`] produces the complete set of rules for a
+//! type:
+//!
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! use minicbor::{Encode, Decode, Schema};
+//!
+//! #[derive(Encode, Decode, Schema)]
+//! struct Point {
+//!     #[n(0)] x: f64,
+//!     #[n(1)] y: f64
+//! }
+//!
+//! #[derive(Encode, Decode, Schema)]
+//! #[cbor(map)]
+//! struct Shape {
+//!     #[n(0)] points: Vec<Point>,
+//!     #[n(2)] name: Option<String>
+//! }
+//!
+//! let expected = "\
+//! Shape = {
+//!   0 => [* Point],  ; points
+//!   ? 2 => tstr,  ; name
+//! }
+//!
+//! Point = [
+//!   x: float,
+//!   y: float,
+//! ]
+//! ";
+//!
+//! assert_eq!(expected, minicbor::schema::cddl::<Shape>());
+//! # }
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc8610.html
+
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::collections::{BinaryHeap, BTreeMap, BTreeSet, LinkedList, VecDeque};
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use crate::bytes::{ByteArray, ByteSlice, ByteVec};
+
+#[doc(hidden)]
+pub use alloc::string::String;
+
+/// Types which can describe their CBOR encoding in CDDL.
+pub trait Schema {
+    /// Get the CDDL type of `Self`.
+    ///
+    /// Rules which the type refers to are added to the generator.
+    fn schema(g: &mut Generator) -> String;
+}
+
+/// Generate the CDDL rules of a type.
+///
+/// The first rule describes `T`. If `T` has no rule of its own, a rule
+/// named `root` is introduced for it.
+pub fn cddl<T: Schema + ?Sized>() -> String {
+    let mut g = Generator::new();
+    let t = T::schema(&mut g);
+    g.finish(t)
+}
+
+/// A collection of CDDL rules.
+#[derive(Debug, Default, Clone)]
+pub struct Generator {
+    /// Rule names and definitions in order of appearance.
+    ///
+    /// A definition is `None` while it is being generated.
+    rules: Vec<(String, Option<String>)>
+}
+
+impl Generator {
+    /// Create an empty generator.
+    pub fn new() -> Self {
+        Generator::default()
+    }
+
+    /// Define a rule and get its name.
+    ///
+    /// The closure producing the definition is invoked only once per name,
+    /// so recursive types refer to their own rule instead of expanding
+    /// endlessly.
+    pub fn rule<F>(&mut self, name: &str, def: F) -> String
+    where
+        F: FnOnce(&mut Self) -> String
+    {
+        if !self.rules.iter().any(|(n, _)| n == name) {
+            let i = self.rules.len();
+            self.rules.push((name.into(), None));
+            let d = def(self);
+            self.rules[i].1 = Some(d)
+        }
+        name.into()
+    }
+
+    /// Render all rules, starting with a rule for the given type.
+    pub fn finish(self, t: String) -> String {
+        let mut s = String::new();
+        if !self.rules.iter().any(|(n, _)| *n == t) {
+            let _ = writeln!(s, "root = {}", t);
+        }
+        for (n, d) in &self.rules {
+            if !s.is_empty() {
+                s.push('\n')
+            }
+            let _ = writeln!(s, "{} = {}", n, d.as_deref().unwrap_or("any"));
+        }
+        s
+    }
+}
+
+/// A field of a struct or enum variant.
+#[derive(Debug, Clone)]
+pub struct Field {
+    index: u32,
+    name: Option<String>,
+    ty: String,
+    optional: bool
+}
+
+impl Field {
+    /// Create a field with the given index and CDDL type.
+    pub fn new(index: u32, ty: String) -> Self {
+        Field { index, name: None, ty, optional: false }
+    }
+
+    /// Set the field name.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Mark this field as optional.
+    ///
+    /// Optional fields are the ones whose type is an `Option` and the
+    /// CDDL type given to [`Field::new`] should be the one of the value
+    /// inside the `Option`.
+    pub fn optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+}
+
+/// Get the CDDL type of fields encoded as an array.
+///
+/// Gaps between field indices are filled with `null`. Optional fields are
+/// `null` if they are absent, but trailing optional fields may be omitted
+/// entirely. Fields must be sorted by index.
+pub fn array(fields: &[Field]) -> String {
+    if fields.is_empty() {
+        return "[]".into()
+    }
+    let required = fields.iter().rposition(|f| !f.optional);
+    let mut s = String::from("[\n");
+    let mut depth = 1;
+    let mut next = 0;
+    for (i, f) in fields.iter().enumerate() {
+        if required.map(|r| i > r).unwrap_or(true) {
+            let _ = writeln!(s, "{:w$}? (", "", w = 2 * depth);
+            depth += 1
+        }
+        for _ in next .. f.index {
+            let _ = writeln!(s, "{:w$}null,", "", w = 2 * depth);
+        }
+        let _ = write!(s, "{:w$}", "", w = 2 * depth);
+        if let Some(n) = &f.name {
+            let _ = write!(s, "{}: ", n);
+        }
+        s.push_str(&indent(&f.ty, depth));
+        if f.optional {
+            s.push_str(" / null")
+        }
+        s.push_str(",\n");
+        next = f.index + 1
+    }
+    while depth > 1 {
+        depth -= 1;
+        let _ = writeln!(s, "{:w$}),", "", w = 2 * depth);
+    }
+    s.push(']');
+    s
+}
+
+/// Get the CDDL type of fields encoded as a map.
+///
+/// Field indices are used as keys and optional fields are omitted if
+/// absent.
+pub fn map(fields: &[Field]) -> String {
+    if fields.is_empty() {
+        return "{}".into()
+    }
+    let mut s = String::from("{\n");
+    for f in fields {
+        let _ = write!(s, "  {}{} => {},", if f.optional { "? " } else { "" }, f.index, indent(&f.ty, 1));
+        if let Some(n) = &f.name {
+            let _ = write!(s, "  ; {}", n);
+        }
+        s.push('\n')
+    }
+    s.push('}');
+    s
+}
+
+/// Get the CDDL type of an enum variant with the given index and fields.
+///
+/// Variants are encoded as an array of the index and the fields, see
+/// [`array`] and [`map`].
+pub fn variant(index: u32, fields: String) -> String {
+    format!("[{}, {}]", index, indent(&fields, 1))
+}
+
+/// Get the CDDL type choice of the given alternatives.
+pub fn choice<I>(alternatives: I) -> String
+where
+    I: IntoIterator<Item = String>
+{
+    let mut s = String::new();
+    for (i, a) in alternatives.into_iter().enumerate() {
+        if i > 0 {
+            s.push_str("\n  / ")
+        }
+        s.push_str(&indent(&a, 1))
+    }
+    s
+}
+
+/// Indent continuation lines of a type which is nested `depth` levels deep.
+fn indent(t: &str, depth: usize) -> Cow<'_, str> {
+    if t.contains('\n') {
+        Cow::Owned(t.replace('\n', &format!("\n{:w$}", "", w = 2 * depth)))
+    } else {
+        Cow::Borrowed(t)
+    }
+}
+
+macro_rules! schema_basic {
+    ($($t:ty => $s:expr),*) => {
+        $(
+            impl Schema for $t {
+                fn schema(_: &mut Generator) -> String {
+                    $s.into()
+                }
+            }
+        )*
+    }
+}
+
+schema_basic! {
+    bool => "bool",
+    u8 => "uint",
+    u16 => "uint",
+    u32 => "uint",
+    u64 => "uint",
+    usize => "uint",
+    i8 => "int",
+    i16 => "int",
+    i32 => "int",
+    i64 => "int",
+    isize => "int",
+    u128 => "uint / biguint",
+    i128 => "integer",
+    f32 => "float",
+    f64 => "float",
+    char => "uint",
+    str => "tstr",
+    String => "tstr",
+    () => "[]",
+    ByteSlice => "bstr",
+    ByteVec => "bstr",
+    core::num::NonZeroU8 => "uint",
+    core::num::NonZeroU16 => "uint",
+    core::num::NonZeroU32 => "uint",
+    core::num::NonZeroU64 => "uint",
+    core::num::NonZeroU128 => "uint / biguint",
+    core::num::NonZeroUsize => "uint",
+    core::num::NonZeroI8 => "int",
+    core::num::NonZeroI16 => "int",
+    core::num::NonZeroI32 => "int",
+    core::num::NonZeroI64 => "int",
+    core::num::NonZeroI128 => "integer",
+    core::num::NonZeroIsize => "int"
+}
+
+#[cfg(feature = "std")]
+schema_basic! {
+    std::path::Path => "tstr",
+    std::path::PathBuf => "tstr"
+}
+
+impl<const N: usize> Schema for ByteArray<N> {
+    fn schema(_: &mut Generator) -> String {
+        format!("bstr .size {}", N)
+    }
+}
+
+impl<T: ?Sized> Schema for core::marker::PhantomData<T> {
+    fn schema(_: &mut Generator) -> String {
+        "[]".into()
+    }
+}
+
+impl<T: Schema> Schema for Option<T> {
+    fn schema(g: &mut Generator) -> String {
+        format!("{} / null", T::schema(g))
+    }
+}
+
+impl<T: Schema, E: Schema> Schema for Result<T, E> {
+    fn schema(g: &mut Generator) -> String {
+        format!("[0, {}] / [1, {}]", T::schema(g), E::schema(g))
+    }
+}
+
+macro_rules! schema_deref {
+    ($($t:ty)*) => {
+        $(
+            impl<T: Schema + ?Sized> Schema for $t {
+                fn schema(g: &mut Generator) -> String {
+                    T::schema(g)
+                }
+            }
+        )*
+    }
+}
+
+schema_deref! {
+    &T
+    &mut T
+    Box<T>
+    Rc<T>
+    core::cell::RefCell<T>
+}
+
+#[cfg(target_has_atomic = "ptr")]
+schema_deref! {
+    alloc::sync::Arc<T>
+}
+
+impl<T: Schema + ToOwned + ?Sized> Schema for Cow<'_, T> {
+    fn schema(g: &mut Generator) -> String {
+        T::schema(g)
+    }
+}
+
+macro_rules! schema_sequential {
+    ($($t:ty)*) => {
+        $(
+            impl<T: Schema> Schema for $t {
+                fn schema(g: &mut Generator) -> String {
+                    format!("[* {}]", T::schema(g))
+                }
+            }
+        )*
+    }
+}
+
+schema_sequential! {
+    [T]
+    Vec<T>
+    VecDeque<T>
+    LinkedList<T>
+    BinaryHeap<T>
+    BTreeSet<T>
+}
+
+#[cfg(feature = "std")]
+schema_sequential! {
+    std::collections::HashSet<T>
+}
+
+impl<T: Schema, const N: usize> Schema for [T; N] {
+    fn schema(g: &mut Generator) -> String {
+        format!("[{}*{} {}]", N, N, T::schema(g))
+    }
+}
+
+impl<K: Schema, V: Schema> Schema for BTreeMap<K, V> {
+    fn schema(g: &mut Generator) -> String {
+        format!("{{* {} => {}}}", K::schema(g), V::schema(g))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Schema, V: Schema> Schema for std::collections::HashMap<K, V> {
+    fn schema(g: &mut Generator) -> String {
+        format!("{{* {} => {}}}", K::schema(g), V::schema(g))
+    }
+}
+
+macro_rules! schema_tuples {
+    ($($($T:ident)+),+) => {
+        $(
+            impl<$($T: Schema),+> Schema for ($($T,)+) {
+                fn schema(g: &mut Generator) -> String {
+                    let types = [$($T::schema(g)),+];
+                    format!("[{}]", types.join(", "))
+                }
+            }
+        )+
+    }
+}
+
+schema_tuples! {
+    A,
+    A B,
+    A B C,
+    A B C D,
+    A B C D E,
+    A B C D E F,
+    A B C D E F G,
+    A B C D E F G H,
+    A B C D E F G H I,
+    A B C D E F G H I J,
+    A B C D E F G H I J K,
+    A B C D E F G H I J K L,
+    A B C D E F G H I J K L M,
+    A B C D E F G H I J K L M N,
+    A B C D E F G H I J K L M N O,
+    A B C D E F G H I J K L M N O P
+}
@@ -0,0 +1,115 @@
+//! Support for [serde]'s `Serialize` and `Deserialize` traits.
+//!
+//! This module provides a [`Serializer`] on top of an [`Encoder`] and a
+//! [`Deserializer`] on top of a [`Decoder`], so that types which implement
+//! (or derive) the serde traits can be encoded and decoded without
+//! implementing [`Encode`](crate::Encode) and [`Decode`](crate::Decode).
+//!
+//! The serde data model is mapped to CBOR as follows:
+//!
+//! - Booleans, integers, floats, strings and byte strings are mapped to
+//!   their CBOR counterparts. `char`s are encoded as strings.
+//! - `None`, `()` and unit structs are encoded as `null`, `Some(x)` and
+//!   newtype structs as the inner value.
+//! - Sequences, tuples and tuple structs are encoded as arrays, maps as
+//!   maps and structs as maps with the field names as keys.
+//! - Unit variants are encoded as the variant name, all other variants
+//!   as a map with a single entry whose key is the variant name.
+//!
+//! This is the wire format of `serde_cbor` and `ciborium`, so data can be
+//! exchanged with peers which use those crates. [`Serializer::packed_format`]
+//! selects the "packed" format of `serde_cbor`, which uses the indices of
+//! struct fields and (most) enum variants instead of their names. The
+//! [`Deserializer`] accepts both formats.
+//!
+//! *Requires feature* `"serde"`.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Point<'a> {
+//!     name: &'a str,
+//!     coord: (i32, i32)
+//! }
+//!
+//! let p = Point { name: "origin", coord: (0, 0) };
+//! let bytes = minicbor::serde::to_vec(&p)?;
+//! let q: Point = minicbor::serde::decode(&bytes)?;
+//! assert_eq!(p, q);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! # Transcoding
+//!
+//! Since [`Deserializer`] can deserialize any CBOR item without knowing
+//! its type in advance (cf. `Deserializer::deserialize_any`), it can be
+//! combined with [`serde_transcode`][1] to convert CBOR into any other
+//! self-describing format supported by serde and vice versa. Values are
+//! converted item by item, i.e. no intermediate representation of the
+//! complete document is built. CBOR tags are dropped in the process, as the
+//! serde data model can not represent them.
+//!
+//! ```
+//! use minicbor::serde::{Deserializer, Serializer};
+//!
+//! let cbor = minicbor::to_vec(&["hello", "world"])?;
+//!
+//! // CBOR to JSON
+//! let mut json = Vec::new();
+//! let mut d = Deserializer::new(&cbor);
+//! serde_transcode::transcode(&mut d, &mut serde_json::Serializer::new(&mut json))?;
+//! d.end()?;
+//! assert_eq!(br#"["hello","world"]"#, json.as_slice());
+//!
+//! // JSON to CBOR (JSON arrays and objects become indefinite-length CBOR
+//! // arrays and maps, since their lengths are not known upfront)
+//! let mut s = Serializer::new(Vec::new());
+//! serde_transcode::transcode(&mut serde_json::Deserializer::from_slice(&json), &mut s)?;
+//! let cbor = s.into_inner();
+//! assert_eq!(["hello", "world"], minicbor::decode::<[&str; 2]>(&cbor)?);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [serde]: https://serde.rs
+//! [1]: https://docs.rs/serde-transcode
+//! [`Encoder`]: crate::Encoder
+//! [`Decoder`]: crate::Decoder
+
+pub mod de;
+pub mod ser;
+
+pub use de::Deserializer;
+pub use ser::Serializer;
+
+use crate::encode::Write;
+
+/// Serialize a value to the given [`Write`] impl.
+pub fn encode<T, W>(x: &T, w: W) -> Result<(), ser::Error<W::Error>>
+where
+    T: ::serde::Serialize + ?Sized,
+    W: Write,
+    ser::Error<W::Error>: ::serde::ser::StdError
+{
+    x.serialize(&mut Serializer::new(w))
+}
+
+/// Serialize a value and return the encoded byte vector.
+pub fn to_vec<T>(x: &T) -> Result<alloc::vec::Vec<u8>, ser::Error<<alloc::vec::Vec<u8> as Write>::Error>>
+where
+    T: ::serde::Serialize + ?Sized
+{
+    let mut s = Serializer::new(alloc::vec::Vec::new());
+    x.serialize(&mut s)?;
+    Ok(s.into_inner())
+}
+
+/// Deserialize a value from the given byte slice.
+pub fn decode<'b, T>(b: &'b [u8]) -> Result<T, de::Error>
+where
+    T: ::serde::Deserialize<'b>
+{
+    T::deserialize(&mut Deserializer::new(b))
+}
@@ -0,0 +1,364 @@
+//! Deserialization of serde data types from CBOR.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use crate::data::Type;
+use crate::decode::{self, Decoder};
+use ::serde::de::{self, DeserializeSeed, Visitor};
+use ::serde::de::value::{BorrowedStrDeserializer, StringDeserializer, U32Deserializer};
+
+/// Deserialization errors.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A decoding error occured.
+    Decode(decode::Error),
+    /// A custom error produced by a `Deserialize` impl.
+    Custom(String)
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Decode(e) => write!(f, "{}", e),
+            Error::Custom(m) => f.write_str(m)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Decode(e) => Some(e),
+            Error::Custom(_) => None
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl de::StdError for Error {}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Custom(msg.to_string())
+    }
+}
+
+impl From<decode::Error> for Error {
+    fn from(e: decode::Error) -> Self {
+        Error::Decode(e)
+    }
+}
+
+/// A serde `Deserializer` which decodes values with a [`Decoder`].
+///
+/// Nested arrays and maps are subject to the max. nesting depth of the
+/// decoder (see [`Decoder::set_max_depth`]).
+#[derive(Debug)]
+pub struct Deserializer<'b> {
+    decoder: Decoder<'b>,
+    depth: usize
+}
+
+impl<'b> Deserializer<'b> {
+    /// Create a new deserializer reading from the given byte slice.
+    pub fn new(bytes: &'b [u8]) -> Self {
+        Self::from_decoder(Decoder::new(bytes))
+    }
+
+    /// Create a new deserializer from the given [`Decoder`].
+    pub fn from_decoder(decoder: Decoder<'b>) -> Self {
+        Deserializer { decoder, depth: 0 }
+    }
+
+    /// Get a mutable reference to the underlying [`Decoder`].
+    pub fn decoder_mut(&mut self) -> &mut Decoder<'b> {
+        &mut self.decoder
+    }
+
+    /// Get back the underlying [`Decoder`].
+    pub fn into_decoder(self) -> Decoder<'b> {
+        self.decoder
+    }
+
+    /// Check that the whole input has been consumed.
+    ///
+    /// If bytes remain, a [`decode::Error::TrailingBytes`] error with the
+    /// offset of the first trailing byte is returned.
+    pub fn end(&self) -> Result<(), Error> {
+        match self.decoder.datatype() {
+            Err(decode::Error::EndOfInput) => Ok(()),
+            Err(e) => Err(e.into()),
+            Ok(_)  => Err(decode::Error::TrailingBytes(self.decoder.position()).into())
+        }
+    }
+
+    /// Skip over any tags before the next data item and return its type.
+    fn untagged(&mut self) -> Result<Type, Error> {
+        loop {
+            match self.decoder.datatype()? {
+                Type::Tag => { self.decoder.tag()?; }
+                t         => return Ok(t)
+            }
+        }
+    }
+
+    /// Consume a break byte if present.
+    fn consume_break(&mut self) -> Result<bool, Error> {
+        if self.decoder.datatype()? == Type::Break {
+            self.decoder.set_position(self.decoder.position() + 1);
+            return Ok(true)
+        }
+        Ok(false)
+    }
+
+    /// Increment the nesting depth.
+    fn enter(&mut self) -> Result<(), Error> {
+        if self.depth >= self.decoder.max_depth() {
+            return Err(decode::Error::Message("maximum nesting depth exceeded").into())
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Decrement the nesting depth.
+    fn leave(&mut self) {
+        self.depth -= 1
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.untagged()? {
+            Type::Bool => visitor.visit_bool(self.decoder.bool()?),
+            Type::Null | Type::Undefined => {
+                self.decoder.skip()?;
+                visitor.visit_unit()
+            }
+            Type::U8  => visitor.visit_u8(self.decoder.u8()?),
+            Type::U16 => visitor.visit_u16(self.decoder.u16()?),
+            Type::U32 => visitor.visit_u32(self.decoder.u32()?),
+            Type::U64 => visitor.visit_u64(self.decoder.u64()?),
+            Type::I8  => visitor.visit_i8(self.decoder.i8()?),
+            Type::I16 => visitor.visit_i16(self.decoder.i16()?),
+            Type::I32 => visitor.visit_i32(self.decoder.i32()?),
+            Type::I64 => visitor.visit_i64(self.decoder.i64()?),
+            Type::F16 => visitor.visit_f32(self.decoder.f16()?),
+            Type::F32 => visitor.visit_f32(self.decoder.f32()?),
+            Type::F64 => visitor.visit_f64(self.decoder.f64()?),
+            Type::Simple => visitor.visit_u8(self.decoder.simple()?),
+            Type::Bytes  => visitor.visit_borrowed_bytes(self.decoder.bytes()?),
+            Type::BytesIndef => {
+                let mut v = Vec::new();
+                for b in self.decoder.bytes_iter()? {
+                    v.extend_from_slice(b?)
+                }
+                visitor.visit_byte_buf(v)
+            }
+            Type::String => visitor.visit_borrowed_str(self.decoder.str()?),
+            Type::StringIndef => {
+                let mut s = String::new();
+                self.decoder.push_str(&mut s)?;
+                visitor.visit_string(s)
+            }
+            Type::Array | Type::ArrayIndef => {
+                let len = self.decoder.array()?;
+                self.enter()?;
+                let mut a = Access { de: &mut *self, len };
+                let v = visitor.visit_seq(&mut a)?;
+                a.finish("array has more elements than expected")?;
+                self.leave();
+                Ok(v)
+            }
+            Type::Map | Type::MapIndef => {
+                let len = self.decoder.map()?;
+                self.enter()?;
+                let mut a = Access { de: &mut *self, len };
+                let v = visitor.visit_map(&mut a)?;
+                a.finish("map has more entries than expected")?;
+                self.leave();
+                Ok(v)
+            }
+            t @ Type::Tag | t @ Type::Break | t @ Type::Unknown(_) => {
+                Err(decode::Error::TypeMismatch(t, "unexpected type").into())
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.decoder.datatype()? {
+            Type::Null | Type::Undefined => {
+                self.decoder.skip()?;
+                visitor.visit_none()
+            }
+            _ => visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(self, _name: &'static str, _vars: &'static [&'static str], visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>
+    {
+        match self.untagged()? {
+            Type::String => {
+                let s = self.decoder.str()?;
+                visitor.visit_enum(BorrowedStrDeserializer::new(s))
+            }
+            Type::StringIndef => {
+                let mut s = String::new();
+                self.decoder.push_str(&mut s)?;
+                visitor.visit_enum(StringDeserializer::new(s))
+            }
+            Type::U8 | Type::U16 | Type::U32 => {
+                let i = self.decoder.u32()?;
+                visitor.visit_enum(U32Deserializer::new(i))
+            }
+            Type::Map | Type::MapIndef => {
+                let len = self.decoder.map()?;
+                if len != Some(1) {
+                    return Err(decode::Error::Message("expected map with a single entry for enum").into())
+                }
+                self.enter()?;
+                let v = visitor.visit_enum(Enum { de: &mut *self })?;
+                self.leave();
+                Ok(v)
+            }
+            t => Err(decode::Error::TypeMismatch(t, "expected enum").into())
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.decoder.skip()?;
+        visitor.visit_unit()
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    ::serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier
+    }
+}
+
+/// Access to the elements of an array or the entries of a map.
+struct Access<'a, 'b> {
+    de: &'a mut Deserializer<'b>,
+    len: Option<u64>
+}
+
+impl<'a, 'b> Access<'a, 'b> {
+    /// Check if another element or entry follows.
+    fn has_next(&mut self) -> Result<bool, Error> {
+        match self.len {
+            Some(0) => Ok(false),
+            Some(n) => {
+                self.len = Some(n - 1);
+                Ok(true)
+            }
+            None => if self.de.consume_break()? {
+                self.len = Some(0);
+                Ok(false)
+            } else {
+                Ok(true)
+            }
+        }
+    }
+
+    /// Ensure all elements or entries have been consumed.
+    fn finish(&mut self, msg: &'static str) -> Result<(), Error> {
+        if self.has_next()? {
+            return Err(decode::Error::Message(msg).into())
+        }
+        Ok(())
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.len.map(|n| n.min(4096) as usize)
+    }
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for Access<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+        if !self.has_next()? {
+            return Ok(None)
+        }
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Access::size_hint(self)
+    }
+}
+
+impl<'de, 'a> de::MapAccess<'de> for Access<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+        if !self.has_next()? {
+            return Ok(None)
+        }
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Access::size_hint(self)
+    }
+}
+
+/// Access to an enum variant encoded as a single entry map.
+struct Enum<'a, 'b> {
+    de: &'a mut Deserializer<'b>
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for Enum<'a, 'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let v = seed.deserialize(&mut *self.de)?;
+        Ok((v, self))
+    }
+}
+
+impl<'de, 'a> de::VariantAccess<'de> for Enum<'a, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        de::Deserialize::deserialize(&mut *self.de)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_any(&mut *self.de, visitor)
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>
+    {
+        de::Deserializer::deserialize_any(&mut *self.de, visitor)
+    }
+}
@@ -0,0 +1,457 @@
+//! Serialization of serde data types to CBOR.
+
+use alloc::string::{String, ToString};
+use core::{convert::TryFrom, fmt};
+use crate::encode::{self, Encoder, Write};
+use ::serde::ser::{self, Serialize};
+
+/// Serialization errors.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error<E> {
+    /// An encoding error occured.
+    Encode(encode::Error<E>),
+    /// A custom error produced by a `Serialize` impl.
+    Custom(String)
+}
+
+impl<E: fmt::Display> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Encode(e) => write!(f, "{}", e),
+            Error::Custom(m) => f.write_str(m)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + 'static> std::error::Error for Error<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Encode(e) => Some(e),
+            Error::Custom(_) => None
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<E: fmt::Debug + fmt::Display> ser::StdError for Error<E> {}
+
+impl<E> ser::Error for Error<E>
+where
+    Error<E>: ser::StdError
+{
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Custom(msg.to_string())
+    }
+}
+
+impl<E> From<encode::Error<E>> for Error<E> {
+    fn from(e: encode::Error<E>) -> Self {
+        Error::Encode(e)
+    }
+}
+
+/// A serde `Serializer` which encodes values with an [`Encoder`].
+#[derive(Debug)]
+pub struct Serializer<W> {
+    encoder: Encoder<W>,
+    packed: bool
+}
+
+impl<W: Write> Serializer<W> {
+    /// Create a new serializer writing to the given [`Write`] impl.
+    pub fn new(writer: W) -> Self {
+        Self::from_encoder(Encoder::new(writer))
+    }
+
+    /// Create a new serializer from the given [`Encoder`].
+    pub fn from_encoder(encoder: Encoder<W>) -> Self {
+        Serializer { encoder, packed: false }
+    }
+
+    /// Encode struct fields, unit variants and struct variants by their
+    /// index instead of their name.
+    ///
+    /// This is the "packed" format of `serde_cbor`, which is more compact
+    /// but breaks compatibility when fields or variants are reordered.
+    /// Like `serde_cbor`, newtype and tuple variants are still encoded with
+    /// the variant name as key.
+    pub fn packed_format(mut self) -> Self {
+        self.packed = true;
+        self
+    }
+
+    /// Get a mutable reference to the underlying [`Encoder`].
+    pub fn encoder_mut(&mut self) -> &mut Encoder<W> {
+        &mut self.encoder
+    }
+
+    /// Get back the [`Write`] impl.
+    pub fn into_inner(self) -> W {
+        self.encoder.into_inner()
+    }
+}
+
+impl<'a, W> ser::Serializer for &'a mut Serializer<W>
+where
+    W: Write,
+    Error<W::Error>: ser::StdError
+{
+    type Ok = ();
+    type Error = Error<W::Error>;
+
+    type SerializeSeq = Collection<'a, W>;
+    type SerializeTuple = Collection<'a, W>;
+    type SerializeTupleStruct = Collection<'a, W>;
+    type SerializeTupleVariant = Collection<'a, W>;
+    type SerializeMap = Collection<'a, W>;
+    type SerializeStruct = Collection<'a, W>;
+    type SerializeStructVariant = Collection<'a, W>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Self::Error> {
+        self.encoder.bool(v)?;
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Self::Error> {
+        self.encoder.i8(v)?;
+        Ok(())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Self::Error> {
+        self.encoder.i16(v)?;
+        Ok(())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Self::Error> {
+        self.encoder.i32(v)?;
+        Ok(())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Self::Error> {
+        self.encoder.i64(v)?;
+        Ok(())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), Self::Error> {
+        if let Ok(n) = i64::try_from(v) {
+            self.serialize_i64(n)
+        } else if let Ok(n) = u64::try_from(v) {
+            self.serialize_u64(n)
+        } else {
+            Err(encode::Error::Message("i128 value out of range").into())
+        }
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Self::Error> {
+        self.encoder.u8(v)?;
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Self::Error> {
+        self.encoder.u16(v)?;
+        Ok(())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Self::Error> {
+        self.encoder.u32(v)?;
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Self::Error> {
+        self.encoder.u64(v)?;
+        Ok(())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), Self::Error> {
+        match u64::try_from(v) {
+            Ok(n)  => self.serialize_u64(n),
+            Err(_) => Err(encode::Error::Message("u128 value out of range").into())
+        }
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Self::Error> {
+        self.encoder.f32(v)?;
+        Ok(())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Self::Error> {
+        self.encoder.f64(v)?;
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Self::Error> {
+        self.encoder.str(v.encode_utf8(&mut [0; 4]))?;
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Self::Error> {
+        self.encoder.str(v)?;
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Self::Error> {
+        self.encoder.bytes(v)?;
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Self::Error> {
+        self.encoder.null()?;
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, v: &T) -> Result<(), Self::Error> {
+        v.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Self::Error> {
+        self.encoder.null()?;
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Self::Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, idx: u32, var: &'static str) -> Result<(), Self::Error> {
+        self.key(idx, var)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, v: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized
+    {
+        v.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(self, _name: &'static str, _idx: u32, var: &'static str, v: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized
+    {
+        self.encoder.map(1)?.str(var)?;
+        v.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        if let Some(n) = len {
+            self.encoder.array(n as u64)?;
+        } else {
+            self.encoder.begin_array()?;
+        }
+        Ok(Collection { ser: self, indefinite: len.is_none(), idx: 0 })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _idx: u32, var: &'static str, len: usize) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.encoder.map(1)?.str(var)?;
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        if let Some(n) = len {
+            self.encoder.map(n as u64)?;
+        } else {
+            self.encoder.begin_map()?;
+        }
+        Ok(Collection { ser: self, indefinite: len.is_none(), idx: 0 })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, idx: u32, var: &'static str, len: usize) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.encoder.map(1)?;
+        self.key(idx, var)?;
+        self.serialize_map(Some(len))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl<W: Write> Serializer<W> {
+    /// Encode the name or, in packed format, the index of a struct field
+    /// or enum variant.
+    fn key(&mut self, idx: u32, name: &str) -> Result<(), Error<W::Error>> {
+        if self.packed {
+            self.encoder.u32(idx)?;
+        } else {
+            self.encoder.str(name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Serializer of CBOR arrays and maps.
+#[derive(Debug)]
+pub struct Collection<'a, W> {
+    ser: &'a mut Serializer<W>,
+    indefinite: bool,
+    idx: u32
+}
+
+impl<'a, W> Collection<'a, W>
+where
+    W: Write,
+    Error<W::Error>: ser::StdError
+{
+    fn element<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Error<W::Error>> {
+        v.serialize(&mut *self.ser)
+    }
+
+    fn field<T: Serialize + ?Sized>(&mut self, k: &'static str, v: &T) -> Result<(), Error<W::Error>> {
+        self.ser.key(self.idx, k)?;
+        self.idx += 1;
+        v.serialize(&mut *self.ser)
+    }
+
+    fn finish(self) -> Result<(), Error<W::Error>> {
+        if self.indefinite {
+            self.ser.encoder.end()?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, W> ser::SerializeSeq for Collection<'a, W>
+where
+    W: Write,
+    Error<W::Error>: ser::StdError
+{
+    type Ok = ();
+    type Error = Error<W::Error>;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Self::Error> {
+        self.element(v)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+impl<'a, W> ser::SerializeTuple for Collection<'a, W>
+where
+    W: Write,
+    Error<W::Error>: ser::StdError
+{
+    type Ok = ();
+    type Error = Error<W::Error>;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Self::Error> {
+        self.element(v)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+impl<'a, W> ser::SerializeTupleStruct for Collection<'a, W>
+where
+    W: Write,
+    Error<W::Error>: ser::StdError
+{
+    type Ok = ();
+    type Error = Error<W::Error>;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Self::Error> {
+        self.element(v)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+impl<'a, W> ser::SerializeTupleVariant for Collection<'a, W>
+where
+    W: Write,
+    Error<W::Error>: ser::StdError
+{
+    type Ok = ();
+    type Error = Error<W::Error>;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Self::Error> {
+        self.element(v)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+impl<'a, W> ser::SerializeMap for Collection<'a, W>
+where
+    W: Write,
+    Error<W::Error>: ser::StdError
+{
+    type Ok = ();
+    type Error = Error<W::Error>;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, k: &T) -> Result<(), Self::Error> {
+        self.element(k)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Self::Error> {
+        self.element(v)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+impl<'a, W> ser::SerializeStruct for Collection<'a, W>
+where
+    W: Write,
+    Error<W::Error>: ser::StdError
+{
+    type Ok = ();
+    type Error = Error<W::Error>;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, k: &'static str, v: &T) -> Result<(), Self::Error> {
+        self.field(k, v)
+    }
+
+    fn skip_field(&mut self, _: &'static str) -> Result<(), Self::Error> {
+        self.idx += 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+impl<'a, W> ser::SerializeStructVariant for Collection<'a, W>
+where
+    W: Write,
+    Error<W::Error>: ser::StdError
+{
+    type Ok = ();
+    type Error = Error<W::Error>;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, k: &'static str, v: &T) -> Result<(), Self::Error> {
+        self.field(k, v)
+    }
+
+    fn skip_field(&mut self, _: &'static str) -> Result<(), Self::Error> {
+        self.idx += 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
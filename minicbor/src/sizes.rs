@@ -0,0 +1,534 @@
+//! Size profiles of encoded values.
+//!
+//! Types implementing [`Describe`] provide the structure of their CBOR
+//! encoding, i.e. which array positions or map keys correspond to which
+//! fields or enum variants. For structs and enums the trait can be derived
+//! (*requires feature* `"derive"`) alongside `Encode` and `Decode`, using
+//! the same attributes. [`profile`] uses this description to attribute the
+//! bytes of an encoded value to the fields they belong to:
+//!
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! use minicbor::{Encode, Describe};
+//!
+//! #[derive(Encode, Describe)]
+//! struct Point {
+//!     #[n(0)] x: u32,
+//!     #[n(1)] y: u32
+//! }
+//!
+//! #[derive(Encode, Describe)]
+//! #[cbor(map)]
+//! struct Shape {
+//!     #[n(0)] points: Vec<Point>,
+//!     #[n(1)] name: String
+//! }
+//!
+//! let shape = Shape {
+//!     points: vec![Point { x: 1, y: 1000 }, Point { x: 2, y: 2000 }],
+//!     name: "line".into()
+//! };
+//!
+//! let cbor = minicbor::to_vec(&shape)?;
+//! let report = minicbor::sizes::profile::<Shape>(&cbor)?;
+//!
+//! assert_eq!(19, report.bytes());
+//! assert_eq!(2, report.get("points").and_then(|r| r.get("x")).unwrap().bytes());
+//! assert_eq!(6, report.get("points").and_then(|r| r.get("y")).unwrap().bytes());
+//!
+//! let expected = "\
+//! 19 bytes
+//!   0 points: 12 bytes (63.2%)
+//!     0 x: 2 bytes (10.5%)
+//!     1 y: 6 bytes (31.6%)
+//!   1 name: 6 bytes (31.6%)
+//! ";
+//!
+//! assert_eq!(expected, report.to_string());
+//! # }
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! The bytes of a field include its map key, if any. Fields of values in
+//! arrays and maps, e.g. the points above, are summed up over all elements.
+
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::collections::{BinaryHeap, BTreeMap, BTreeSet, LinkedList, VecDeque};
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use crate::bytes::{ByteArray, ByteSlice, ByteVec};
+use crate::data::Type;
+use crate::decode::{Decoder, Error};
+use crate::structural::has_next;
+
+/// Types which can describe the structure of their CBOR encoding.
+pub trait Describe {
+    /// Get the shape of the CBOR encoding of `Self`.
+    fn describe() -> Shape;
+}
+
+/// The structure of an encoded value.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Shape {
+    /// A value without known inner structure.
+    Opaque,
+    /// An array of fields, identified by their position.
+    Array(Vec<Field>),
+    /// A map of fields, identified by their unsigned integer keys.
+    Map(Vec<Field>),
+    /// An enum encoded as an array of variant index and variant value.
+    Enum(Vec<Field>),
+    /// An enum encoded as the variant index only.
+    Index(Vec<Field>),
+    /// An array of elements of the given shape.
+    Seq(fn() -> Shape),
+    /// A map with values of the given shape.
+    Dict(fn() -> Shape)
+}
+
+/// A field or enum variant.
+#[derive(Debug, Clone, Copy)]
+pub struct Field {
+    index: u64,
+    name: Option<&'static str>,
+    shape: fn() -> Shape
+}
+
+impl Field {
+    /// Create a field with the given index and shape.
+    pub fn new(index: u64, shape: fn() -> Shape) -> Self {
+        Field { index, name: None, shape }
+    }
+
+    /// Set the field name.
+    pub fn with_name(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Get the field index.
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// Get the field name, if any.
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    /// Get the shape of the field value.
+    pub fn shape(&self) -> Shape {
+        (self.shape)()
+    }
+}
+
+/// The number of bytes of a value and its fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    index: Option<u64>,
+    name: Option<&'static str>,
+    bytes: usize,
+    fields: Vec<Report>
+}
+
+impl Report {
+    fn new(index: Option<u64>, name: Option<&'static str>) -> Self {
+        Report { index, name, bytes: 0, fields: Vec::new() }
+    }
+
+    /// Get the field or variant index.
+    ///
+    /// The report of the profiled value itself has no index.
+    pub fn index(&self) -> Option<u64> {
+        self.index
+    }
+
+    /// Get the field or variant name, if any.
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    /// Get the number of bytes.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Get the reports of fields or enum variants.
+    pub fn fields(&self) -> &[Report] {
+        &self.fields
+    }
+
+    /// Lookup the report of a field or enum variant by name.
+    pub fn get(&self, name: &str) -> Option<&Report> {
+        self.fields.iter().find(|r| r.name == Some(name))
+    }
+
+    /// Lookup the report of a field or enum variant by index.
+    pub fn get_index(&self, index: u64) -> Option<&Report> {
+        self.fields.iter().find(|r| r.index == Some(index))
+    }
+
+    fn fmt_fields(&self, f: &mut fmt::Formatter, total: usize, depth: usize) -> fmt::Result {
+        for r in &self.fields {
+            write!(f, "{:w$}", "", w = 2 * depth)?;
+            if let Some(i) = r.index {
+                write!(f, "{}", i)?
+            }
+            if let Some(n) = r.name {
+                write!(f, " {}", n)?
+            }
+            let pct = if total == 0 { 0.0 } else { 100.0 * r.bytes as f64 / total as f64 };
+            writeln!(f, ": {} bytes ({:.1}%)", r.bytes, pct)?;
+            r.fmt_fields(f, total, depth + 1)?
+        }
+        Ok(())
+    }
+}
+
+/// Shows a tree of fields with their number of bytes and their share
+/// of the total number of bytes.
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} bytes", self.bytes)?;
+        self.fmt_fields(f, self.bytes, 1)
+    }
+}
+
+/// Profile the encoding of a value of type `T`.
+///
+/// The first CBOR item of the input is attributed to the fields described
+/// by `T`. Input that does not match the description is counted towards
+/// the enclosing value. An error is returned if the input is not
+/// well-formed.
+pub fn profile<T: Describe + ?Sized>(cbor: &[u8]) -> Result<Report, Error> {
+    let mut d = Decoder::new(cbor);
+    let mut r = Report::new(None, None);
+    walk(&mut d, &T::describe(), &mut r.fields, 0)?;
+    r.bytes = d.position();
+    Ok(r)
+}
+
+/// Walk the next item according to its shape and add reports of its fields.
+fn walk(d: &mut Decoder, shape: &Shape, out: &mut Vec<Report>, depth: usize) -> Result<(), Error> {
+    if depth >= d.max_depth() {
+        return Err(Error::Message("maximum nesting depth exceeded"))
+    }
+    match shape {
+        Shape::Opaque => d.skip(),
+        Shape::Array(fields) => {
+            let n = if let Some(n) = array(d)? { n } else { return d.skip() };
+            let mut i = 0;
+            while has_next(d, n, i)? {
+                let p = d.position();
+                if let Some(f) = fields.iter().find(|f| f.index == i) {
+                    field(d, f, p, out, depth)?
+                } else {
+                    d.skip()?
+                }
+                i += 1
+            }
+            Ok(())
+        }
+        Shape::Map(fields) => {
+            let n = if let Some(n) = map(d)? { n } else { return d.skip() };
+            let mut i = 0;
+            while has_next(d, n, i)? {
+                let p = d.position();
+                if !is_unsigned(d.datatype()?) {
+                    d.skip()?;
+                    d.skip()?
+                } else {
+                    let k = d.u64()?;
+                    if let Some(f) = fields.iter().find(|f| f.index == k) {
+                        field(d, f, p, out, depth)?
+                    } else {
+                        d.skip()?
+                    }
+                }
+                i += 1
+            }
+            Ok(())
+        }
+        Shape::Enum(variants) => {
+            let p = d.position();
+            let n = if let Some(n) = array(d)? { n } else { return d.skip() };
+            let mut i = 0;
+            if has_next(d, n, i)? {
+                i += 1;
+                if !is_unsigned(d.datatype()?) {
+                    d.skip()?
+                } else {
+                    let k = d.u64()?;
+                    if let Some(v) = variants.iter().find(|v| v.index == k) {
+                        let mut r = Report::new(Some(v.index), v.name);
+                        if has_next(d, n, i)? {
+                            i += 1;
+                            walk(d, &v.shape(), &mut r.fields, depth + 1)?
+                        }
+                        while has_next(d, n, i)? {
+                            d.skip()?;
+                            i += 1
+                        }
+                        r.bytes = d.position() - p;
+                        merge(out, r);
+                        return Ok(())
+                    }
+                }
+            }
+            while has_next(d, n, i)? {
+                d.skip()?;
+                i += 1
+            }
+            Ok(())
+        }
+        Shape::Index(variants) => {
+            let p = d.position();
+            if !is_unsigned(d.datatype()?) {
+                return d.skip()
+            }
+            let k = d.u64()?;
+            if let Some(v) = variants.iter().find(|v| v.index == k) {
+                let mut r = Report::new(Some(v.index), v.name);
+                r.bytes = d.position() - p;
+                merge(out, r)
+            }
+            Ok(())
+        }
+        Shape::Seq(elem) => {
+            let n = if let Some(n) = array(d)? { n } else { return d.skip() };
+            let shape = elem();
+            let mut i = 0;
+            while has_next(d, n, i)? {
+                walk(d, &shape, out, depth + 1)?;
+                i += 1
+            }
+            Ok(())
+        }
+        Shape::Dict(value) => {
+            let n = if let Some(n) = map(d)? { n } else { return d.skip() };
+            let shape = value();
+            let mut i = 0;
+            while has_next(d, n, i)? {
+                d.skip()?;
+                walk(d, &shape, out, depth + 1)?;
+                i += 1
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Walk a field value and add its report.
+///
+/// The field begins at position `p` which includes its map key, if any.
+fn field(d: &mut Decoder, f: &Field, p: usize, out: &mut Vec<Report>, depth: usize) -> Result<(), Error> {
+    let mut r = Report::new(Some(f.index), f.name);
+    walk(d, &f.shape(), &mut r.fields, depth + 1)?;
+    r.bytes = d.position() - p;
+    merge(out, r);
+    Ok(())
+}
+
+/// Add a report or, if one for the same field exists, sum both up.
+fn merge(out: &mut Vec<Report>, r: Report) {
+    if let Some(x) = out.iter_mut().find(|x| x.index == r.index && x.name == r.name) {
+        x.bytes += r.bytes;
+        for f in r.fields {
+            merge(&mut x.fields, f)
+        }
+    } else {
+        out.push(r)
+    }
+}
+
+/// Begin an array, returning `None` if the next item is not an array.
+///
+/// The inner option is `None` for arrays of indefinite length.
+fn array(d: &mut Decoder) -> Result<Option<Option<u64>>, Error> {
+    match d.datatype()? {
+        Type::Array | Type::ArrayIndef => d.array().map(Some),
+        _                              => Ok(None)
+    }
+}
+
+/// Begin a map, returning `None` if the next item is not a map.
+///
+/// The inner option is `None` for maps of indefinite length.
+fn map(d: &mut Decoder) -> Result<Option<Option<u64>>, Error> {
+    match d.datatype()? {
+        Type::Map | Type::MapIndef => d.map().map(Some),
+        _                          => Ok(None)
+    }
+}
+
+fn is_unsigned(t: Type) -> bool {
+    matches!(t, Type::U8 | Type::U16 | Type::U32 | Type::U64)
+}
+
+macro_rules! describe_opaque {
+    ($($t:ty),*) => {
+        $(
+            impl Describe for $t {
+                fn describe() -> Shape {
+                    Shape::Opaque
+                }
+            }
+        )*
+    }
+}
+
+describe_opaque! {
+    bool, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32,
+    f64, char, str, String, (), ByteSlice, ByteVec,
+    core::num::NonZeroU8, core::num::NonZeroU16, core::num::NonZeroU32,
+    core::num::NonZeroU64, core::num::NonZeroU128, core::num::NonZeroUsize,
+    core::num::NonZeroI8, core::num::NonZeroI16, core::num::NonZeroI32,
+    core::num::NonZeroI64, core::num::NonZeroI128, core::num::NonZeroIsize
+}
+
+#[cfg(feature = "std")]
+describe_opaque! {
+    std::path::Path, std::path::PathBuf
+}
+
+impl<const N: usize> Describe for ByteArray<N> {
+    fn describe() -> Shape {
+        Shape::Opaque
+    }
+}
+
+impl<T: ?Sized> Describe for core::marker::PhantomData<T> {
+    fn describe() -> Shape {
+        Shape::Opaque
+    }
+}
+
+impl<T: Describe> Describe for Option<T> {
+    fn describe() -> Shape {
+        T::describe()
+    }
+}
+
+impl<T: Describe, E: Describe> Describe for Result<T, E> {
+    fn describe() -> Shape {
+        Shape::Enum([
+            Field::new(0, T::describe).with_name("Ok"),
+            Field::new(1, E::describe).with_name("Err")
+        ].to_vec())
+    }
+}
+
+macro_rules! describe_deref {
+    ($($t:ty)*) => {
+        $(
+            impl<T: Describe + ?Sized> Describe for $t {
+                fn describe() -> Shape {
+                    T::describe()
+                }
+            }
+        )*
+    }
+}
+
+describe_deref! {
+    &T
+    &mut T
+    Box<T>
+    Rc<T>
+    core::cell::RefCell<T>
+}
+
+#[cfg(target_has_atomic = "ptr")]
+describe_deref! {
+    alloc::sync::Arc<T>
+}
+
+impl<T: Describe + ToOwned + ?Sized> Describe for Cow<'_, T> {
+    fn describe() -> Shape {
+        T::describe()
+    }
+}
+
+macro_rules! describe_sequential {
+    ($($t:ty)*) => {
+        $(
+            impl<T: Describe> Describe for $t {
+                fn describe() -> Shape {
+                    Shape::Seq(T::describe)
+                }
+            }
+        )*
+    }
+}
+
+describe_sequential! {
+    [T]
+    Vec<T>
+    VecDeque<T>
+    LinkedList<T>
+    BinaryHeap<T>
+    BTreeSet<T>
+}
+
+#[cfg(feature = "std")]
+describe_sequential! {
+    std::collections::HashSet<T>
+}
+
+impl<T: Describe, const N: usize> Describe for [T; N] {
+    fn describe() -> Shape {
+        Shape::Seq(T::describe)
+    }
+}
+
+impl<K, V: Describe> Describe for BTreeMap<K, V> {
+    fn describe() -> Shape {
+        Shape::Dict(V::describe)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, V: Describe> Describe for std::collections::HashMap<K, V> {
+    fn describe() -> Shape {
+        Shape::Dict(V::describe)
+    }
+}
+
+macro_rules! describe_tuples {
+    ($($($T:ident)+),+) => {
+        $(
+            impl<$($T: Describe),+> Describe for ($($T,)+) {
+                fn describe() -> Shape {
+                    let mut fields = [$(Field::new(0, $T::describe)),+].to_vec();
+                    for (i, f) in fields.iter_mut().enumerate() {
+                        f.index = i as u64
+                    }
+                    Shape::Array(fields)
+                }
+            }
+        )+
+    }
+}
+
+describe_tuples! {
+    A,
+    A B,
+    A B C,
+    A B C D,
+    A B C D E,
+    A B C D E F,
+    A B C D E F G,
+    A B C D E F G H,
+    A B C D E F G H I,
+    A B C D E F G H I J,
+    A B C D E F G H I J K,
+    A B C D E F G H I J K L,
+    A B C D E F G H I J K L M,
+    A B C D E F G H I J K L M N,
+    A B C D E F G H I J K L M N O,
+    A B C D E F G H I J K L M N O P
+}
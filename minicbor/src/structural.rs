@@ -0,0 +1,168 @@
+//! Structural equality of encoded CBOR items.
+
+use crate::data::Type;
+use crate::decode::{Decoder, Error};
+
+/// Check if two CBOR sequences consist of structurally equal items.
+pub(crate) fn eq(a: &[u8], b: &[u8]) -> Result<bool, Error> {
+    let mut x = Decoder::new(a);
+    let mut y = Decoder::new(b);
+    loop {
+        match (x.position() < a.len(), y.position() < b.len()) {
+            (false, false) => return Ok(true),
+            (true, true)   => if !item(&mut x, &mut y, 0)? { return Ok(false) }
+            _              => return Ok(false)
+        }
+    }
+}
+
+/// The classes of items which are equal regardless of their encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Unsigned,
+    Negative,
+    Float,
+    Simple,
+    Bytes,
+    Text,
+    Array,
+    Map,
+    Tag
+}
+
+fn class(t: Type) -> Result<Class, Error> {
+    match t {
+        Type::U8 | Type::U16 | Type::U32 | Type::U64 => Ok(Class::Unsigned),
+        Type::I8 | Type::I16 | Type::I32 | Type::I64 => Ok(Class::Negative),
+        Type::F16 | Type::F32 | Type::F64            => Ok(Class::Float),
+        Type::Bool | Type::Null | Type::Undefined | Type::Simple => Ok(Class::Simple),
+        Type::Bytes | Type::BytesIndef   => Ok(Class::Bytes),
+        Type::String | Type::StringIndef => Ok(Class::Text),
+        Type::Array | Type::ArrayIndef   => Ok(Class::Array),
+        Type::Map | Type::MapIndef       => Ok(Class::Map),
+        Type::Tag                        => Ok(Class::Tag),
+        Type::Break      => Err(Error::TypeMismatch(t, "unexpected break")),
+        Type::Unknown(_) => Err(Error::TypeMismatch(t, "unknown cbor type"))
+    }
+}
+
+/// Compare the next items of both decoders.
+fn item(x: &mut Decoder, y: &mut Decoder, depth: usize) -> Result<bool, Error> {
+    if depth >= x.max_depth() {
+        return Err(Error::Message("maximum nesting depth exceeded"))
+    }
+    let c = class(x.datatype()?)?;
+    if c != class(y.datatype()?)? {
+        return Ok(false)
+    }
+    match c {
+        Class::Unsigned => Ok(x.u64()? == y.u64()?),
+        Class::Negative => Ok(x.negative()? == y.negative()?),
+        Class::Float    => Ok(float(x)?.to_bits() == float(y)?.to_bits()),
+        Class::Simple   => Ok(simple(x)? == simple(y)?),
+        Class::Bytes    => chunks(x.bytes_iter()?, y.bytes_iter()?),
+        Class::Text     => {
+            let i = x.str_iter()?.map(|s| s.map(str::as_bytes));
+            let j = y.str_iter()?.map(|s| s.map(str::as_bytes));
+            chunks(i, j)
+        }
+        Class::Array | Class::Map => {
+            let (m, n) =
+                if c == Class::Array {
+                    (x.array()?, y.array()?)
+                } else {
+                    (x.map()?.map(|n| n.saturating_mul(2)), y.map()?.map(|n| n.saturating_mul(2)))
+                };
+            if let (Some(m), Some(n)) = (m, n) {
+                if m != n {
+                    return Ok(false)
+                }
+            }
+            let mut i = 0;
+            loop {
+                match (has_next(x, m, i)?, has_next(y, n, i)?) {
+                    (false, false) => return Ok(true),
+                    (true, true)   => if !item(x, y, depth + 1)? { return Ok(false) }
+                    _              => return Ok(false)
+                }
+                i += 1
+            }
+        }
+        Class::Tag => {
+            if x.tag()? != y.tag()? {
+                return Ok(false)
+            }
+            item(x, y, depth + 1)
+        }
+    }
+}
+
+/// Decode a float of any width.
+pub(crate) fn float(d: &mut Decoder) -> Result<f64, Error> {
+    match d.datatype()? {
+        Type::F16 => {
+            let b = d.read_slice(3)?;
+            Ok(crate::ext::f16_to_f64(u16::from(b[1]) << 8 | u16::from(b[2])))
+        }
+        Type::F32 => d.f32().map(f64::from),
+        _         => d.f64()
+    }
+}
+
+/// Decode the number of a simple value, including booleans, null and undefined.
+fn simple(d: &mut Decoder) -> Result<u8, Error> {
+    match d.datatype()? {
+        Type::Bool      => d.bool().map(|b| if b { 21 } else { 20 }),
+        Type::Null      => { d.read_slice(1)?; Ok(22) }
+        Type::Undefined => { d.read_slice(1)?; Ok(23) }
+        _               => d.simple()
+    }
+}
+
+/// Check if an array or map has more items, consuming the break byte of
+/// indefinite-length ones at the end.
+pub(crate) fn has_next(d: &mut Decoder, len: Option<u64>, i: u64) -> Result<bool, Error> {
+    match len {
+        Some(n) => Ok(i < n),
+        None    => {
+            if d.datatype()? == Type::Break {
+                d.read_slice(1)?;
+                return Ok(false)
+            }
+            Ok(true)
+        }
+    }
+}
+
+/// Compare the concatenation of two sequences of string chunks.
+fn chunks<'a, 'b, I, J>(mut i: I, mut j: J) -> Result<bool, Error>
+where
+    I: Iterator<Item = Result<&'a [u8], Error>>,
+    J: Iterator<Item = Result<&'b [u8], Error>>
+{
+    let mut a: &[u8] = &[];
+    let mut b: &[u8] = &[];
+    loop {
+        while a.is_empty() {
+            match i.next() {
+                Some(c) => a = c?,
+                None    => break
+            }
+        }
+        while b.is_empty() {
+            match j.next() {
+                Some(c) => b = c?,
+                None    => break
+            }
+        }
+        if a.is_empty() || b.is_empty() {
+            return Ok(a.is_empty() && b.is_empty())
+        }
+        let n = a.len().min(b.len());
+        if a[.. n] != b[.. n] {
+            return Ok(false)
+        }
+        a = &a[n ..];
+        b = &b[n ..]
+    }
+}
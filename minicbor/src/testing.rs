@@ -0,0 +1,133 @@
+//! Assertions for tests of `Encode` and `Decode` impls.
+//!
+//! On failure, the assertions panic with a message which shows the CBOR
+//! involved in [diagnostic notation][1] and in hex, as well as the offset
+//! of the first byte where actual and expected encoding differ:
+//!
+//! ```
+//! use minicbor::testing::{assert_encodes_to, assert_encodes_to_hex, assert_roundtrip};
+//!
+//! assert_roundtrip(vec![1u8, 2, 3]);
+//! assert_roundtrip(Some(String::from("hello")));
+//!
+//! assert_encodes_to((1u8, "a"), &[0x82, 0x01, 0x61, 0x61]);
+//! assert_encodes_to_hex(1000u16, "0x1903e8");
+//!
+//! let result = std::panic::catch_unwind(|| assert_encodes_to([1u8, 2], &[0x82, 0x01, 0x03]));
+//! assert!(result.is_err());
+//! ```
+//!
+//! A failing `assert_encodes_to` reports:
+//!
+//! ```text
+//! encoding mismatch
+//!   expected: [1, 3]
+//!             820103
+//!   actual:   [1, 2]
+//!             820102
+//!   first difference at byte 2
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-8
+
+use alloc::vec::Vec;
+use core::fmt;
+use crate::{Decode, Encode};
+
+/// Assert that a value decodes to itself after encoding.
+///
+/// Since decoding borrows from a temporary buffer, `T` must be decodable
+/// from input of any lifetime, i.e. it can not borrow from its input.
+///
+/// Returns the encoded bytes.
+///
+/// # Panics
+///
+/// If encoding or decoding fails, if not all bytes are consumed while
+/// decoding or if the decoded value is not equal to the original.
+#[track_caller]
+pub fn assert_roundtrip<T>(value: T) -> Vec<u8>
+where
+    T: Encode + PartialEq + fmt::Debug,
+    for<'a> T: Decode<'a>
+{
+    let cbor = match crate::to_vec(&value) {
+        Ok(cbor) => cbor,
+        Err(e)   => panic!("failed to encode {:?}: {}", value, e)
+    };
+    let mut d = crate::Decoder::new(&cbor);
+    let decoded = match d.decode::<T>() {
+        Ok(x)  => x,
+        Err(e) => panic!("failed to decode\n  value:    {:?}\n  cbor:     {}\n  error:    {}", value, Cbor(&cbor), e)
+    };
+    if d.position() != cbor.len() {
+        panic!("decoding left {} trailing byte(s)\n  value:    {:?}\n  cbor:     {}",
+            cbor.len() - d.position(),
+            value,
+            Cbor(&cbor))
+    }
+    if decoded != value {
+        panic!("roundtrip mismatch\n  value:    {:?}\n  decoded:  {:?}\n  cbor:     {}", value, decoded, Cbor(&cbor))
+    }
+    cbor
+}
+
+/// Assert that a value encodes to the expected bytes.
+///
+/// # Panics
+///
+/// If encoding fails or if the encoded bytes differ from the expected ones.
+#[track_caller]
+pub fn assert_encodes_to<T>(value: T, expected: &[u8])
+where
+    T: Encode + fmt::Debug
+{
+    let actual = match crate::to_vec(&value) {
+        Ok(cbor) => cbor,
+        Err(e)   => panic!("failed to encode {:?}: {}", value, e)
+    };
+    if actual != expected {
+        let pos = actual.iter()
+            .zip(expected)
+            .position(|(a, b)| a != b)
+            .unwrap_or_else(|| actual.len().min(expected.len()));
+        panic!("encoding mismatch\n  expected: {}\n  actual:   {}\n  first difference at byte {}",
+            Cbor(expected),
+            Cbor(&actual),
+            pos)
+    }
+}
+
+/// Assert that a value encodes to the bytes given as hex digits.
+///
+/// The hex digits are parsed with [`crate::from_hex`], i.e. whitespace and
+/// a leading `0x` are ignored. This allows using test vectors as they are
+/// written in RFCs, e.g. [RFC 8949, Appendix A][1].
+///
+/// # Panics
+///
+/// If the hex digits are invalid, if encoding fails or if the encoded
+/// bytes differ from the expected ones.
+///
+/// [1]: https://www.rfc-editor.org/rfc/rfc8949.html#appendix-A
+#[track_caller]
+pub fn assert_encodes_to_hex<T>(value: T, expected: &str)
+where
+    T: Encode + fmt::Debug
+{
+    match crate::from_hex(expected) {
+        Ok(bytes) => assert_encodes_to(value, &bytes),
+        Err(e)    => panic!("invalid hex {:?}: {}", expected, e)
+    }
+}
+
+/// Shows CBOR in diagnostic notation followed by hex on the next line,
+/// aligned to labels of 12 characters.
+struct Cbor<'a>(&'a [u8]);
+
+impl fmt::Display for Cbor<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", crate::display(self.0))?;
+        write!(f, "            {}", crate::display_hex(self.0))
+    }
+}
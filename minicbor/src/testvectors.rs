@@ -0,0 +1,515 @@
+//! Conformance test vectors.
+//!
+//! This module provides the examples of encoded CBOR data items from
+//! [RFC 8949, Appendix A][1] and [`check_roundtrip`] to test an arbitrary
+//! codec or transport against them.
+//!
+//! *Requires feature* `"testvectors"`.
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc8949.html#appendix-A
+
+use core::fmt;
+
+/// An example CBOR data item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestVector {
+    /// The encoded data item.
+    pub cbor: &'static [u8],
+    /// The data item in diagnostic notation.
+    pub diagnostic: &'static str,
+    /// Is `cbor` the preferred serialisation of the data item?
+    ///
+    /// If `true`, decoding and re-encoding should reproduce `cbor` exactly.
+    pub roundtrip: bool
+}
+
+/// A test vector failure, as returned by [`check_roundtrip`].
+#[derive(Debug, Clone)]
+pub enum Failure<E> {
+    /// The function under test returned an error.
+    Error(&'static TestVector, E),
+    /// The function under test did not reproduce the encoded data item.
+    Mismatch(&'static TestVector)
+}
+
+impl<E: fmt::Display> fmt::Display for Failure<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Failure::Error(v, e) => write!(f, "{} failed: {}", v.diagnostic, e),
+            Failure::Mismatch(v) => write!(f, "{} did not roundtrip", v.diagnostic)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + 'static> std::error::Error for Failure<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Failure::Error(_, e) => Some(e),
+            Failure::Mismatch(_) => None
+        }
+    }
+}
+
+/// Check a roundtrip function against the given test vectors.
+///
+/// For every test vector `f` is applied to the encoded data item. It is
+/// expected to decode and re-encode the item (or pass it through whatever
+/// transport is being tested) and return the resulting bytes. Test vectors
+/// which are marked as [`TestVector::roundtrip`] need to be reproduced
+/// exactly, for all others `f` only needs to succeed. The first failure is
+/// returned.
+///
+/// ```
+/// use minicbor::testvectors::{check_roundtrip, APPENDIX_A};
+///
+/// // The identity function trivially passes.
+/// check_roundtrip(APPENDIX_A, |b| Ok::<_, core::convert::Infallible>(b.to_vec())).unwrap();
+/// ```
+pub fn check_roundtrip<I, F, B, E>(vectors: I, mut f: F) -> Result<(), Failure<E>>
+where
+    I: IntoIterator<Item = &'static TestVector>,
+    F: FnMut(&[u8]) -> Result<B, E>,
+    B: AsRef<[u8]>
+{
+    for v in vectors {
+        match f(v.cbor) {
+            Ok(b) if v.roundtrip && b.as_ref() != v.cbor => return Err(Failure::Mismatch(v)),
+            Ok(_)  => {}
+            Err(e) => return Err(Failure::Error(v, e))
+        }
+    }
+    Ok(())
+}
+
+/// The examples of [RFC 8949, Appendix A][1].
+///
+/// [1]: https://www.rfc-editor.org/rfc/rfc8949.html#appendix-A
+pub static APPENDIX_A: &[TestVector] = &[
+    TestVector {
+        cbor: &[0x00],
+        diagnostic: "0",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x01],
+        diagnostic: "1",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x0a],
+        diagnostic: "10",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x17],
+        diagnostic: "23",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x18, 0x18],
+        diagnostic: "24",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x18, 0x19],
+        diagnostic: "25",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x18, 0x64],
+        diagnostic: "100",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x19, 0x03, 0xe8],
+        diagnostic: "1000",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x1a, 0x00, 0x0f, 0x42, 0x40],
+        diagnostic: "1000000",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x1b, 0x00, 0x00, 0x00, 0xe8, 0xd4, 0xa5, 0x10, 0x00],
+        diagnostic: "1000000000000",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+        diagnostic: "18446744073709551615",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xc2, 0x49, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        diagnostic: "18446744073709551616",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x3b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+        diagnostic: "-18446744073709551616",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xc3, 0x49, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        diagnostic: "-18446744073709551617",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x20],
+        diagnostic: "-1",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x29],
+        diagnostic: "-10",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x38, 0x63],
+        diagnostic: "-100",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x39, 0x03, 0xe7],
+        diagnostic: "-1000",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xf9, 0x00, 0x00],
+        diagnostic: "0.0",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xf9, 0x80, 0x00],
+        diagnostic: "-0.0",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xf9, 0x3c, 0x00],
+        diagnostic: "1.0",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a],
+        diagnostic: "1.1",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xf9, 0x3e, 0x00],
+        diagnostic: "1.5",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xf9, 0x7b, 0xff],
+        diagnostic: "65504.0",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xfa, 0x47, 0xc3, 0x50, 0x00],
+        diagnostic: "100000.0",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xfa, 0x7f, 0x7f, 0xff, 0xff],
+        diagnostic: "3.4028234663852886e+38",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xfb, 0x7e, 0x37, 0xe4, 0x3c, 0x88, 0x00, 0x75, 0x9c],
+        diagnostic: "1.0e+300",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xf9, 0x00, 0x01],
+        diagnostic: "5.960464477539063e-8",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xf9, 0x04, 0x00],
+        diagnostic: "0.00006103515625",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xf9, 0xc4, 0x00],
+        diagnostic: "-4.0",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xfb, 0xc0, 0x10, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66],
+        diagnostic: "-4.1",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xf9, 0x7c, 0x00],
+        diagnostic: "Infinity",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xf9, 0x7e, 0x00],
+        diagnostic: "NaN",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xf9, 0xfc, 0x00],
+        diagnostic: "-Infinity",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xfa, 0x7f, 0x80, 0x00, 0x00],
+        diagnostic: "Infinity",
+        roundtrip: false
+    },
+    TestVector {
+        cbor: &[0xfa, 0x7f, 0xc0, 0x00, 0x00],
+        diagnostic: "NaN",
+        roundtrip: false
+    },
+    TestVector {
+        cbor: &[0xfa, 0xff, 0x80, 0x00, 0x00],
+        diagnostic: "-Infinity",
+        roundtrip: false
+    },
+    TestVector {
+        cbor: &[0xfb, 0x7f, 0xf0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        diagnostic: "Infinity",
+        roundtrip: false
+    },
+    TestVector {
+        cbor: &[0xfb, 0x7f, 0xf8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        diagnostic: "NaN",
+        roundtrip: false
+    },
+    TestVector {
+        cbor: &[0xfb, 0xff, 0xf0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        diagnostic: "-Infinity",
+        roundtrip: false
+    },
+    TestVector {
+        cbor: &[0xf4],
+        diagnostic: "false",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xf5],
+        diagnostic: "true",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xf6],
+        diagnostic: "null",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xf7],
+        diagnostic: "undefined",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xf0],
+        diagnostic: "simple(16)",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xf8, 0xff],
+        diagnostic: "simple(255)",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[
+            0xc0, 0x74, 0x32, 0x30, 0x31, 0x33, 0x2d, 0x30, 0x33, 0x2d, 0x32, 0x31,
+            0x54, 0x32, 0x30, 0x3a, 0x30, 0x34, 0x3a, 0x30, 0x30, 0x5a
+        ],
+        diagnostic: "0(\"2013-03-21T20:04:00Z\")",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0],
+        diagnostic: "1(1363896240)",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xc1, 0xfb, 0x41, 0xd4, 0x52, 0xd9, 0xec, 0x20, 0x00, 0x00],
+        diagnostic: "1(1363896240.5)",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xd7, 0x44, 0x01, 0x02, 0x03, 0x04],
+        diagnostic: "23(h'01020304')",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xd8, 0x18, 0x45, 0x64, 0x49, 0x45, 0x54, 0x46],
+        diagnostic: "24(h'6449455446')",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[
+            0xd8, 0x20, 0x76, 0x68, 0x74, 0x74, 0x70, 0x3a, 0x2f, 0x2f, 0x77, 0x77,
+            0x77, 0x2e, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x2e, 0x63, 0x6f,
+            0x6d
+        ],
+        diagnostic: "32(\"http://www.example.com\")",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x40],
+        diagnostic: "h''",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x44, 0x01, 0x02, 0x03, 0x04],
+        diagnostic: "h'01020304'",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x60],
+        diagnostic: "\"\"",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x61, 0x61],
+        diagnostic: "\"a\"",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x64, 0x49, 0x45, 0x54, 0x46],
+        diagnostic: "\"IETF\"",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x62, 0x22, 0x5c],
+        diagnostic: "\"\\\"\\\\\"",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x62, 0xc3, 0xbc],
+        diagnostic: "\"\\u00fc\"",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x63, 0xe6, 0xb0, 0xb4],
+        diagnostic: "\"\\u6c34\"",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x64, 0xf0, 0x90, 0x85, 0x91],
+        diagnostic: "\"\\ud800\\udd51\"",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x80],
+        diagnostic: "[]",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x83, 0x01, 0x02, 0x03],
+        diagnostic: "[1, 2, 3]",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x83, 0x01, 0x82, 0x02, 0x03, 0x82, 0x04, 0x05],
+        diagnostic: "[1, [2, 3], [4, 5]]",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[
+            0x98, 0x19, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a,
+            0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16,
+            0x17, 0x18, 0x18, 0x18, 0x19
+        ],
+        diagnostic: "[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25]",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xa0],
+        diagnostic: "{}",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xa2, 0x01, 0x02, 0x03, 0x04],
+        diagnostic: "{1: 2, 3: 4}",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0xa2, 0x61, 0x61, 0x01, 0x61, 0x62, 0x82, 0x02, 0x03],
+        diagnostic: "{\"a\": 1, \"b\": [2, 3]}",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x82, 0x61, 0x61, 0xa1, 0x61, 0x62, 0x61, 0x63],
+        diagnostic: "[\"a\", {\"b\": \"c\"}]",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[
+            0xa5, 0x61, 0x61, 0x61, 0x41, 0x61, 0x62, 0x61, 0x42, 0x61, 0x63, 0x61,
+            0x43, 0x61, 0x64, 0x61, 0x44, 0x61, 0x65, 0x61, 0x45
+        ],
+        diagnostic: "{\"a\": \"A\", \"b\": \"B\", \"c\": \"C\", \"d\": \"D\", \"e\": \"E\"}",
+        roundtrip: true
+    },
+    TestVector {
+        cbor: &[0x5f, 0x42, 0x01, 0x02, 0x43, 0x03, 0x04, 0x05, 0xff],
+        diagnostic: "(_ h'0102', h'030405')",
+        roundtrip: false
+    },
+    TestVector {
+        cbor: &[
+            0x7f, 0x65, 0x73, 0x74, 0x72, 0x65, 0x61, 0x64, 0x6d, 0x69, 0x6e, 0x67,
+            0xff
+        ],
+        diagnostic: "(_ \"strea\", \"ming\")",
+        roundtrip: false
+    },
+    TestVector {
+        cbor: &[0x9f, 0xff],
+        diagnostic: "[_ ]",
+        roundtrip: false
+    },
+    TestVector {
+        cbor: &[0x9f, 0x01, 0x82, 0x02, 0x03, 0x9f, 0x04, 0x05, 0xff, 0xff],
+        diagnostic: "[_ 1, [2, 3], [_ 4, 5]]",
+        roundtrip: false
+    },
+    TestVector {
+        cbor: &[0x9f, 0x01, 0x82, 0x02, 0x03, 0x82, 0x04, 0x05, 0xff],
+        diagnostic: "[_ 1, [2, 3], [4, 5]]",
+        roundtrip: false
+    },
+    TestVector {
+        cbor: &[0x83, 0x01, 0x82, 0x02, 0x03, 0x9f, 0x04, 0x05, 0xff],
+        diagnostic: "[1, [2, 3], [_ 4, 5]]",
+        roundtrip: false
+    },
+    TestVector {
+        cbor: &[0x83, 0x01, 0x9f, 0x02, 0x03, 0xff, 0x82, 0x04, 0x05],
+        diagnostic: "[1, [_ 2, 3], [4, 5]]",
+        roundtrip: false
+    },
+    TestVector {
+        cbor: &[
+            0x9f, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b,
+            0x0c, 0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17,
+            0x18, 0x18, 0x18, 0x19, 0xff
+        ],
+        diagnostic: "[_ 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25]",
+        roundtrip: false
+    },
+    TestVector {
+        cbor: &[0xbf, 0x61, 0x61, 0x01, 0x61, 0x62, 0x9f, 0x02, 0x03, 0xff, 0xff],
+        diagnostic: "{_ \"a\": 1, \"b\": [_ 2, 3]}",
+        roundtrip: false
+    },
+    TestVector {
+        cbor: &[0x82, 0x61, 0x61, 0xbf, 0x61, 0x62, 0x61, 0x63, 0xff],
+        diagnostic: "[\"a\", {_ \"b\": \"c\"}]",
+        roundtrip: false
+    },
+    TestVector {
+        cbor: &[0xbf, 0x63, 0x46, 0x75, 0x6e, 0xf5, 0x63, 0x41, 0x6d, 0x74, 0x21, 0xff],
+        diagnostic: "{_ \"Fun\": true, \"Amt\": -2}",
+        roundtrip: false
+    }
+];
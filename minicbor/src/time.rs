@@ -0,0 +1,161 @@
+//! Interoperable encodings of `Duration` and `SystemTime`.
+//!
+//! The `Encode` and `Decode` impls of [`Duration`] use an array of seconds
+//! and nanoseconds which other CBOR implementations do not understand. The
+//! types and modules here use numeric representations instead:
+//!
+//! - [`Seconds`] (or `#[cbor(with = "minicbor::time::seconds")]`) encodes a
+//!   `Duration` as a number of seconds.
+//! - [`Epoch`] (or `#[cbor(with = "minicbor::time::epoch")]`) encodes a
+//!   `SystemTime` as seconds since the UNIX epoch with tag 1 (cf.
+//!   [RFC 8949, section 3.4.2][1]). *Requires feature* `"std"`.
+//!
+//! Values without fractional seconds are encoded as integers, all others
+//! as `f64` values which may lose sub-microsecond precision. Decoding
+//! accepts integers as well as floats.
+//!
+//! ```
+//! use core::time::Duration;
+//! use minicbor::time::Seconds;
+//!
+//! let cbor = minicbor::to_vec(Seconds(Duration::from_secs(90)))?;
+//! assert_eq!(b"\x18\x5a", cbor.as_slice());
+//!
+//! let cbor = minicbor::to_vec(Seconds(Duration::from_millis(1500)))?;
+//! assert_eq!(b"\xfb\x3f\xf8\x00\x00\x00\x00\x00\x00", cbor.as_slice());
+//! assert_eq!(Seconds(Duration::from_millis(1500)), minicbor::decode(&cbor)?);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-3.4.2
+
+use core::convert::TryFrom;
+use core::time::Duration;
+use crate::data::Type;
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+
+#[cfg(feature = "std")]
+use crate::data::Tag;
+
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Encode a `Duration` as a number of seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Seconds(pub Duration);
+
+/// Encode a `SystemTime` as seconds since the UNIX epoch (tag 1).
+///
+/// *Requires feature* `"std"`.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Epoch(pub SystemTime);
+
+/// Encode a positive or negative number of seconds.
+fn encode_secs<W: Write>(d: Duration, negative: bool, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+    if d.subsec_nanos() == 0 {
+        let n = i128::from(d.as_secs());
+        e.i128(if negative { -n } else { n })?.ok()
+    } else {
+        let x = d.as_secs_f64();
+        e.f64(if negative { -x } else { x })?.ok()
+    }
+}
+
+/// Decode a number of seconds into its absolute value and sign.
+fn decode_secs(d: &mut Decoder<'_>) -> Result<(Duration, bool), decode::Error> {
+    match d.datatype()? {
+        Type::F16 | Type::F32 | Type::F64 => {
+            let x = d.f64()?;
+            Duration::try_from_secs_f64(x.abs())
+                .map(|s| (s, x.is_sign_negative()))
+                .map_err(|_| decode::Error::Message("invalid number of seconds"))
+        }
+        _ => {
+            let n = d.i128()?;
+            let s = u64::try_from(n.unsigned_abs())
+                .map_err(|_| decode::Error::Message("number of seconds out of range"))?;
+            Ok((Duration::from_secs(s), n < 0))
+        }
+    }
+}
+
+impl Encode for Seconds {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        encode_secs(self.0, false, e)
+    }
+}
+
+impl<'b> Decode<'b> for Seconds {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        seconds::decode(d).map(Seconds)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Encode for Epoch {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        epoch::encode(&self.0, e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'b> Decode<'b> for Epoch {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        epoch::decode(d).map(Epoch)
+    }
+}
+
+/// Encode and decode durations as numbers of seconds.
+///
+/// For use in `#[cbor(with = "minicbor::time::seconds")]`.
+pub mod seconds {
+    use super::*;
+
+    /// Encode a duration as a number of seconds.
+    pub fn encode<W: Write>(x: &Duration, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        encode_secs(*x, false, e)
+    }
+
+    /// Decode a non-negative number of seconds.
+    pub fn decode(d: &mut Decoder<'_>) -> Result<Duration, decode::Error> {
+        match decode_secs(d)? {
+            (s, false) => Ok(s),
+            (s, true) if s.is_zero() => Ok(s),
+            (_, true)  => Err(decode::Error::Message("negative duration"))
+        }
+    }
+}
+
+/// Encode and decode system times as seconds since the UNIX epoch.
+///
+/// For use in `#[cbor(with = "minicbor::time::epoch")]`.
+///
+/// *Requires feature* `"std"`.
+#[cfg(feature = "std")]
+pub mod epoch {
+    use super::*;
+
+    /// Encode a system time as seconds since the UNIX epoch (tag 1).
+    pub fn encode<W: Write>(x: &SystemTime, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        e.tag(Tag::Timestamp)?;
+        match x.duration_since(UNIX_EPOCH) {
+            Ok(s)  => encode_secs(s, false, e),
+            Err(s) => encode_secs(s.duration(), true, e)
+        }
+    }
+
+    /// Decode a system time with tag 1.
+    pub fn decode(d: &mut Decoder<'_>) -> Result<SystemTime, decode::Error> {
+        if d.tag()? != Tag::Timestamp {
+            return Err(decode::Error::Message("expected tag 1 for date/time"))
+        }
+        let t = match decode_secs(d)? {
+            (s, false) => UNIX_EPOCH.checked_add(s),
+            (s, true)  => UNIX_EPOCH.checked_sub(s)
+        };
+        t.ok_or(decode::Error::Message("timestamp out of range"))
+    }
+}
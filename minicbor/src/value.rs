@@ -0,0 +1,896 @@
+//! A dynamic representation of CBOR items.
+//!
+//! [`Value`] can hold any well-formed CBOR item and is meant for data whose
+//! structure is not known in advance, e.g. in gateways and proxies. Its
+//! representation is kept compact, a `Value` takes 24 bytes on 64-bit
+//! targets:
+//!
+//! - Integers, floats and simple values are stored inline.
+//! - Byte and text strings of up to 22 bytes are stored inline, longer
+//!   ones are allocated.
+//! - Arrays, maps and tags, which are less common or larger anyway, are
+//!   boxed.
+//!
+//! Maps keep their entries in the original order, including duplicate keys,
+//! and tags may wrap any value, so no information is lost when decoding.
+//! Encoding a decoded value reproduces input which is already in the
+//! deterministic encoding of [RFC 8949][1] byte for byte. Other input is
+//! re-encoded with shortest integers, lengths and floats and with definite
+//! lengths only, which [`eq`](crate::eq) considers equal to the input.
+//!
+//! In addition, an [`Interner`] can be used when decoding many values with
+//! the same map keys, e.g. the field names of JSON-like documents, to let
+//! all of them share a single allocation per distinct key.
+//!
+//! The contents of a value are inspected with [`Value::view`], which
+//! returns a [`ValueRef`], or with typed getters such as [`Value::as_str`].
+//! Array elements and map values are accessed with [`Value::get`] or the
+//! `[]` operator (cf. [`Index`]).
+//!
+//! *Requires feature* `"alloc"`.
+//!
+//! ```
+//! use minicbor::value::{Value, ValueRef};
+//!
+//! let v = Value::map([
+//!     (Value::from("name"), Value::from("minicbor")),
+//!     (Value::from("tags"), Value::array([Value::from(1), Value::from(-2)]))
+//! ]);
+//!
+//! let cbor = minicbor::to_vec(&v)?;
+//! let w: Value = minicbor::decode(&cbor)?;
+//! assert_eq!(v, w);
+//!
+//! if let ValueRef::Map(entries) = w.view() {
+//!     assert_eq!(ValueRef::Text("minicbor"), entries[0].1.view());
+//! }
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [1]: https://www.rfc-editor.org/rfc/rfc8949.html#section-4.2.1
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use crate::data::{Tag, Type};
+use crate::decode::{self, Decode, Decoder};
+use crate::encode::{self, Encode, Encoder, Write};
+use crate::structural::has_next;
+
+/// Max. number of bytes of strings stored inline.
+const INLINE: usize = 22;
+
+/// A CBOR data item.
+#[derive(Clone)]
+pub struct Value(Repr);
+
+/// The internal representation of a [`Value`].
+//
+// Arrays and maps are double-boxed to keep `Value` at 24 bytes.
+#[allow(clippy::box_collection)]
+#[derive(Clone)]
+enum Repr {
+    Unsigned(u64),
+    Negative(u64),
+    Float(f64),
+    Bool(bool),
+    Null,
+    Undefined,
+    Simple(u8),
+    SmallBytes(u8, [u8; INLINE]),
+    Bytes(Box<[u8]>),
+    SmallText(u8, [u8; INLINE]),
+    Text(Box<str>),
+    #[cfg(target_has_atomic = "ptr")]
+    Shared(alloc::sync::Arc<str>),
+    Array(Box<Vec<Value>>),
+    Map(Box<Vec<(Value, Value)>>),
+    Tag(Box<(u64, Value)>)
+}
+
+/// A view of the contents of a [`Value`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueRef<'a> {
+    /// An unsigned integer.
+    Unsigned(u64),
+    /// A negative integer `n`, i.e. the value `-1 - n`.
+    Negative(u64),
+    /// A floating point number.
+    Float(f64),
+    /// A boolean.
+    Bool(bool),
+    /// The simple value `null`.
+    Null,
+    /// The simple value `undefined`.
+    Undefined,
+    /// Any other simple value.
+    Simple(u8),
+    /// A byte string.
+    Bytes(&'a [u8]),
+    /// A text string.
+    Text(&'a str),
+    /// An array.
+    Array(&'a [Value]),
+    /// A map with entries in their original order.
+    Map(&'a [(Value, Value)]),
+    /// A tagged value.
+    Tag(u64, &'a Value)
+}
+
+impl Value {
+    /// The `null` value.
+    pub fn null() -> Self {
+        Value(Repr::Null)
+    }
+
+    /// The `undefined` value.
+    pub fn undefined() -> Self {
+        Value(Repr::Undefined)
+    }
+
+    /// A simple value.
+    ///
+    /// The values 20 to 23 are mapped to `false`, `true`, `null` and
+    /// `undefined`.
+    pub fn simple(n: u8) -> Self {
+        match n {
+            20 => Value(Repr::Bool(false)),
+            21 => Value(Repr::Bool(true)),
+            22 => Value(Repr::Null),
+            23 => Value(Repr::Undefined),
+            _  => Value(Repr::Simple(n))
+        }
+    }
+
+    /// The negative integer `-1 - n`.
+    pub fn negative(n: u64) -> Self {
+        Value(Repr::Negative(n))
+    }
+
+    /// A byte string.
+    pub fn bytes(b: &[u8]) -> Self {
+        if b.len() <= INLINE {
+            let mut buf = [0; INLINE];
+            buf[.. b.len()].copy_from_slice(b);
+            Value(Repr::SmallBytes(b.len() as u8, buf))
+        } else {
+            Value(Repr::Bytes(b.into()))
+        }
+    }
+
+    /// A text string.
+    pub fn text(s: &str) -> Self {
+        if s.len() <= INLINE {
+            let mut buf = [0; INLINE];
+            buf[.. s.len()].copy_from_slice(s.as_bytes());
+            Value(Repr::SmallText(s.len() as u8, buf))
+        } else {
+            Value(Repr::Text(s.into()))
+        }
+    }
+
+    /// An array of values.
+    pub fn array<I: IntoIterator<Item = Value>>(items: I) -> Self {
+        Value(Repr::Array(Box::new(items.into_iter().collect())))
+    }
+
+    /// A map of the given entries.
+    ///
+    /// The order of entries is preserved and duplicate keys are kept.
+    pub fn map<I: IntoIterator<Item = (Value, Value)>>(entries: I) -> Self {
+        Value(Repr::Map(Box::new(entries.into_iter().collect())))
+    }
+
+    /// A value with the given tag.
+    pub fn tag(t: u64, v: Value) -> Self {
+        Value(Repr::Tag(Box::new((t, v))))
+    }
+
+    /// Get a view of the contents of this value.
+    pub fn view(&self) -> ValueRef<'_> {
+        match &self.0 {
+            Repr::Unsigned(n)       => ValueRef::Unsigned(*n),
+            Repr::Negative(n)       => ValueRef::Negative(*n),
+            Repr::Float(x)          => ValueRef::Float(*x),
+            Repr::Bool(b)           => ValueRef::Bool(*b),
+            Repr::Null              => ValueRef::Null,
+            Repr::Undefined         => ValueRef::Undefined,
+            Repr::Simple(n)         => ValueRef::Simple(*n),
+            Repr::SmallBytes(n, b)  => ValueRef::Bytes(&b[.. usize::from(*n)]),
+            Repr::Bytes(b)          => ValueRef::Bytes(b),
+            Repr::SmallText(n, b)   => {
+                // SAFETY: Inline text is only ever copied from a `str` and
+                // `n` is the length of that `str`.
+                ValueRef::Text(unsafe { core::str::from_utf8_unchecked(&b[.. usize::from(*n)]) })
+            }
+            Repr::Text(s)           => ValueRef::Text(s),
+            #[cfg(target_has_atomic = "ptr")]
+            Repr::Shared(s)         => ValueRef::Text(s),
+            Repr::Array(a)          => ValueRef::Array(a),
+            Repr::Map(m)            => ValueRef::Map(m),
+            Repr::Tag(t)            => ValueRef::Tag(t.0, &t.1)
+        }
+    }
+
+    /// Get an array element or map value.
+    ///
+    /// See [`Index`] for the supported index types. Returns `None` if this
+    /// is neither an array nor a map, or if the index or key is not found.
+    pub fn get<I: Index>(&self, i: I) -> Option<&Value> {
+        i.index_into(self)
+    }
+
+    /// Get a mutable array element or map value.
+    ///
+    /// Like [`Value::get`] but allows modification of the element.
+    pub fn get_mut<I: Index>(&mut self, i: I) -> Option<&mut Value> {
+        i.index_into_mut(self)
+    }
+
+    /// Get the value of an unsigned integer.
+    pub fn as_u64(&self) -> Option<u64> {
+        if let Repr::Unsigned(n) = self.0 { Some(n) } else { None }
+    }
+
+    /// Get the value of an integer which fits into an `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.0 {
+            Repr::Unsigned(n) => i64::try_from(n).ok(),
+            Repr::Negative(n) => i64::try_from(n).ok().map(|n| -1 - n),
+            _                 => None
+        }
+    }
+
+    /// Get the value of a float.
+    pub fn as_f64(&self) -> Option<f64> {
+        if let Repr::Float(x) = self.0 { Some(x) } else { None }
+    }
+
+    /// Get the value of a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        if let Repr::Bool(b) = self.0 { Some(b) } else { None }
+    }
+
+    /// Get the contents of a text string.
+    pub fn as_str(&self) -> Option<&str> {
+        if let ValueRef::Text(s) = self.view() { Some(s) } else { None }
+    }
+
+    /// Get the contents of a byte string.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        if let ValueRef::Bytes(b) = self.view() { Some(b) } else { None }
+    }
+
+    /// Get the elements of an array.
+    pub fn as_array(&self) -> Option<&[Value]> {
+        if let Repr::Array(a) = &self.0 { Some(a) } else { None }
+    }
+
+    /// Get the mutable elements of an array.
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value>> {
+        if let Repr::Array(a) = &mut self.0 { Some(a) } else { None }
+    }
+
+    /// Get the entries of a map.
+    pub fn as_map(&self) -> Option<&[(Value, Value)]> {
+        if let Repr::Map(m) = &self.0 { Some(m) } else { None }
+    }
+
+    /// Get the mutable entries of a map.
+    pub fn as_map_mut(&mut self) -> Option<&mut Vec<(Value, Value)>> {
+        if let Repr::Map(m) = &mut self.0 { Some(m) } else { None }
+    }
+
+    /// Is this the `null` value?
+    pub fn is_null(&self) -> bool {
+        matches!(self.0, Repr::Null)
+    }
+
+    /// Append an element to an array.
+    ///
+    /// ```
+    /// use minicbor::value::Value;
+    ///
+    /// let v = Value::array([]).with_item(1).with_item("two");
+    /// assert_eq!(Some("two"), v[1].as_str());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If this is not an array.
+    pub fn with_item<V: Into<Value>>(mut self, v: V) -> Self {
+        self.as_array_mut().expect("Value::with_item requires an array").push(v.into());
+        self
+    }
+
+    /// Insert a map entry.
+    ///
+    /// If the map already contains the key, the value of the first entry
+    /// with this key is replaced, otherwise the entry is appended.
+    ///
+    /// ```
+    /// use minicbor::value::Value;
+    ///
+    /// let v = Value::map([])
+    ///     .with_entry("name", "minicbor")
+    ///     .with_entry("tags", Value::array([]).with_item(1))
+    ///     .with_entry("name", "cbor");
+    ///
+    /// assert_eq!(Some("cbor"), v["name"].as_str());
+    /// assert_eq!(Some(1), v["tags"][0].as_u64());
+    /// assert_eq!(2, v.as_map().unwrap().len());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If this is not a map.
+    pub fn with_entry<K: Into<Value>, V: Into<Value>>(mut self, k: K, v: V) -> Self {
+        let m = self.as_map_mut().expect("Value::with_entry requires a map");
+        let k = k.into();
+        if let Some(e) = m.iter_mut().find(|e| e.0 == k) {
+            e.1 = v.into()
+        } else {
+            m.push((k, v.into()))
+        }
+        self
+    }
+}
+
+/// A `null` value for `ops::Index` results which do not exist.
+static NULL: Value = Value(Repr::Null);
+
+/// Types which can index into a [`Value`].
+///
+/// - `usize` indexes into arrays by position and into maps by unsigned
+///   integer key.
+/// - `str` indexes into maps by text key.
+/// - `Value` indexes into maps by arbitrary key.
+///
+/// If a map contains a key more than once, the first entry is used.
+///
+/// Besides [`Value::get`] and [`Value::get_mut`], values can also be
+/// indexed with the `[]` operator, which returns `null` if the element
+/// does not exist:
+///
+/// ```
+/// use minicbor::value::Value;
+///
+/// let v = Value::array([Value::map([(Value::from("name"), Value::from("x"))])]);
+///
+/// assert_eq!(Some("x"), v[0]["name"].as_str());
+/// assert!(v[1]["name"].is_null());
+/// assert_eq!(None, v.get(0).and_then(|m| m.get("id")));
+/// ```
+///
+/// This trait is sealed and can not be implemented outside of minicbor.
+pub trait Index: sealed::Sealed {}
+
+mod sealed {
+    use super::{Repr, Value, ValueRef};
+
+    pub trait Sealed {
+        /// Is this the map key `k`?
+        fn is_key(&self, k: &Value) -> bool;
+
+        /// The position of this index in an array.
+        fn position(&self) -> Option<usize> {
+            None
+        }
+
+        fn index_into<'a>(&self, v: &'a Value) -> Option<&'a Value> {
+            match &v.0 {
+                Repr::Array(a) => self.position().and_then(|i| a.get(i)),
+                Repr::Map(m)   => m.iter().find(|e| self.is_key(&e.0)).map(|e| &e.1),
+                _              => None
+            }
+        }
+
+        fn index_into_mut<'a>(&self, v: &'a mut Value) -> Option<&'a mut Value> {
+            match &mut v.0 {
+                Repr::Array(a) => self.position().and_then(move |i| a.get_mut(i)),
+                Repr::Map(m)   => m.iter_mut().find(|e| self.is_key(&e.0)).map(|e| &mut e.1),
+                _              => None
+            }
+        }
+    }
+
+    impl Sealed for usize {
+        fn is_key(&self, k: &Value) -> bool {
+            k.view() == ValueRef::Unsigned(*self as u64)
+        }
+
+        fn position(&self) -> Option<usize> {
+            Some(*self)
+        }
+    }
+
+    impl Sealed for str {
+        fn is_key(&self, k: &Value) -> bool {
+            k.view() == ValueRef::Text(self)
+        }
+    }
+
+    impl Sealed for Value {
+        fn is_key(&self, k: &Value) -> bool {
+            self == k
+        }
+    }
+
+    impl<T: Sealed + ?Sized> Sealed for &T {
+        fn is_key(&self, k: &Value) -> bool {
+            (**self).is_key(k)
+        }
+
+        fn position(&self) -> Option<usize> {
+            (**self).position()
+        }
+    }
+}
+
+impl Index for usize {}
+impl Index for str {}
+impl Index for Value {}
+impl<T: Index + ?Sized> Index for &T {}
+
+impl<I: Index> core::ops::Index<I> for Value {
+    type Output = Value;
+
+    fn index(&self, i: I) -> &Value {
+        self.get(i).unwrap_or(&NULL)
+    }
+}
+
+impl fmt::Debug for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.view().fmt(f)
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.view() == other.view()
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value(Repr::Bool(b))
+    }
+}
+
+macro_rules! from_unsigned {
+    ($($t:ty)*) => {
+        $(impl From<$t> for Value {
+            fn from(n: $t) -> Self {
+                Value(Repr::Unsigned(u64::from(n)))
+            }
+        })*
+    }
+}
+
+from_unsigned!(u8 u16 u32 u64);
+
+macro_rules! from_signed {
+    ($($t:ty)*) => {
+        $(impl From<$t> for Value {
+            fn from(n: $t) -> Self {
+                let n = i64::from(n);
+                if n >= 0 {
+                    Value(Repr::Unsigned(n as u64))
+                } else {
+                    Value(Repr::Negative((-1 - n) as u64))
+                }
+            }
+        })*
+    }
+}
+
+from_signed!(i8 i16 i32 i64);
+
+impl From<f32> for Value {
+    fn from(x: f32) -> Self {
+        Value(Repr::Float(f64::from(x)))
+    }
+}
+
+impl From<f64> for Value {
+    fn from(x: f64) -> Self {
+        Value(Repr::Float(x))
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::text(s)
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        if s.len() <= INLINE {
+            Value::text(&s)
+        } else {
+            Value(Repr::Text(s.into_boxed_str()))
+        }
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(v: Vec<Value>) -> Self {
+        Value(Repr::Array(Box::new(v)))
+    }
+}
+
+impl From<&[u8]> for Value {
+    fn from(b: &[u8]) -> Self {
+        Value::bytes(b)
+    }
+}
+
+impl<const N: usize> From<&[u8; N]> for Value {
+    fn from(b: &[u8; N]) -> Self {
+        Value::bytes(b)
+    }
+}
+
+impl Encode for Value {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), encode::Error<W::Error>> {
+        match self.view() {
+            ValueRef::Unsigned(n) => e.u64(n)?.ok(),
+            ValueRef::Negative(n) => e.type_len(crate::SIGNED, n)?.ok(),
+            ValueRef::Float(x)    => float(e, x),
+            ValueRef::Bool(b)     => e.bool(b)?.ok(),
+            ValueRef::Null        => e.null()?.ok(),
+            ValueRef::Undefined   => e.undefined()?.ok(),
+            ValueRef::Simple(n)   => e.simple(n)?.ok(),
+            ValueRef::Bytes(b)    => e.bytes(b)?.ok(),
+            ValueRef::Text(s)     => e.str(s)?.ok(),
+            ValueRef::Array(a)    => {
+                e.array(a.len() as u64)?;
+                for x in a {
+                    x.encode(e)?
+                }
+                Ok(())
+            }
+            ValueRef::Map(m) => {
+                e.map(m.len() as u64)?;
+                for (k, v) in m {
+                    k.encode(e)?;
+                    v.encode(e)?
+                }
+                Ok(())
+            }
+            ValueRef::Tag(t, v) => {
+                e.tag(Tag::Unassigned(t))?;
+                v.encode(e)
+            }
+        }
+    }
+}
+
+/// Encode a float in the shortest form which preserves its value.
+pub(crate) fn float<W: Write>(e: &mut Encoder<W>, x: f64) -> Result<(), encode::Error<W::Error>> {
+    if e.reject_non_finite() && !x.is_finite() {
+        return Err(encode::Error::Message("non-finite floating point value"))
+    }
+    if x.to_bits() == f64::NAN.to_bits() {
+        return e.put(&[crate::SIMPLE | 25, 0x7e, 0])?.ok()
+    }
+    let y = x as f32;
+    if x.is_nan() || f64::from(y) != x {
+        return e.f64(x)?.ok()
+    }
+    match crate::ext::f16_bits(y) {
+        Some(h) => e.put(&[crate::SIMPLE | 25, (h >> 8) as u8, h as u8])?.ok(),
+        None    => e.f32(y)?.ok()
+    }
+}
+
+impl<'b> Decode<'b> for Value {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+        decode_value(d, None, 0)
+    }
+}
+
+/// Shares the allocations of long text map keys when decoding values.
+///
+/// ```
+/// use minicbor::Decoder;
+/// use minicbor::value::Interner;
+/// use std::collections::BTreeMap;
+///
+/// let mut interner = Interner::new();
+/// let mut doc = BTreeMap::new();
+/// doc.insert("a rather long field name", 1);
+/// doc.insert("another rather long field name", 2);
+/// let cbor = minicbor::to_vec(&doc)?;
+///
+/// let a = interner.decode(&mut Decoder::new(&cbor))?;
+/// let b = interner.decode(&mut Decoder::new(&cbor))?;
+/// assert_eq!(a, b);
+/// assert_eq!(2, interner.len());
+///
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(target_has_atomic = "ptr")]
+#[derive(Debug, Default, Clone)]
+pub struct Interner {
+    keys: alloc::collections::BTreeSet<alloc::sync::Arc<str>>
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl Interner {
+    /// Create a new, empty interner.
+    pub fn new() -> Self {
+        Interner::default()
+    }
+
+    /// Decode a value, sharing text map keys with values previously
+    /// decoded by this interner.
+    pub fn decode(&mut self, d: &mut Decoder<'_>) -> Result<Value, decode::Error> {
+        decode_value(d, Some(self), 0)
+    }
+
+    /// Get the number of interned keys.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Are there no interned keys?
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Remove all interned keys.
+    pub fn clear(&mut self) {
+        self.keys.clear()
+    }
+
+    fn intern(&mut self, s: &str) -> Value {
+        if s.len() <= INLINE {
+            return Value::text(s)
+        }
+        if let Some(k) = self.keys.get(s) {
+            return Value(Repr::Shared(k.clone()))
+        }
+        let k: alloc::sync::Arc<str> = s.into();
+        self.keys.insert(k.clone());
+        Value(Repr::Shared(k))
+    }
+}
+
+/// Stand-in for the interner on targets without atomic pointers.
+#[cfg(not(target_has_atomic = "ptr"))]
+enum Interner {}
+
+fn decode_value(d: &mut Decoder<'_>, mut interner: Option<&mut Interner>, depth: usize) -> Result<Value, decode::Error> {
+    if depth >= d.max_depth() {
+        return Err(decode::Error::Message("maximum nesting depth exceeded"))
+    }
+    match d.datatype()? {
+        Type::U8 | Type::U16 | Type::U32 | Type::U64 => Ok(Value(Repr::Unsigned(d.u64()?))),
+        Type::I8 | Type::I16 | Type::I32 | Type::I64 => Ok(Value(Repr::Negative(d.negative()?))),
+        Type::F16 => {
+            let b = d.read_slice(3)?;
+            let x = crate::ext::f16_to_f64(u16::from(b[1]) << 8 | u16::from(b[2]));
+            if d.reject_non_finite() && !x.is_finite() {
+                return Err(decode::Error::Message("non-finite floating point value"))
+            }
+            Ok(Value(Repr::Float(x)))
+        }
+        Type::F32 => Ok(Value(Repr::Float(f64::from(d.f32()?)))),
+        Type::F64 => Ok(Value(Repr::Float(d.f64()?))),
+        Type::Bool      => Ok(Value(Repr::Bool(d.bool()?))),
+        Type::Null      => { d.read_slice(1)?; Ok(Value(Repr::Null)) }
+        Type::Undefined => { d.read_slice(1)?; Ok(Value(Repr::Undefined)) }
+        Type::Simple    => Ok(Value::simple(d.simple()?)),
+        Type::Bytes     => Ok(Value::bytes(d.bytes()?)),
+        Type::BytesIndef => {
+            let mut v = Vec::new();
+            for b in d.bytes_iter()? {
+                v.extend_from_slice(b?)
+            }
+            Ok(Value::bytes(&v))
+        }
+        Type::String      => Ok(Value::text(d.str()?)),
+        Type::StringIndef => {
+            let mut s = String::new();
+            d.push_str(&mut s)?;
+            Ok(Value::from(s))
+        }
+        Type::Array | Type::ArrayIndef => {
+            let len = d.array()?;
+            let mut v = Vec::with_capacity(len.map_or(0, |n| n.min(1024) as usize));
+            while has_next(d, len, v.len() as u64)? {
+                v.push(decode_value(d, interner.as_deref_mut(), depth + 1)?)
+            }
+            Ok(Value::from(v))
+        }
+        Type::Map | Type::MapIndef => {
+            let len = d.map()?;
+            let mut v = Vec::with_capacity(len.map_or(0, |n| n.min(1024) as usize));
+            while has_next(d, len, v.len() as u64)? {
+                let k = match interner.as_deref_mut() {
+                    #[cfg(target_has_atomic = "ptr")]
+                    Some(i) if d.datatype()? == Type::String => i.intern(d.str()?),
+                    _ => decode_value(d, interner.as_deref_mut(), depth + 1)?
+                };
+                let x = decode_value(d, interner.as_deref_mut(), depth + 1)?;
+                v.push((k, x))
+            }
+            Ok(Value(Repr::Map(Box::new(v))))
+        }
+        Type::Tag => {
+            let t = d.tag()?.numeric();
+            let x = decode_value(d, interner, depth + 1)?;
+            Ok(Value::tag(t, x))
+        }
+        t @ (Type::Break | Type::Unknown(_)) => Err(decode::Error::TypeMismatch(t, "unexpected type"))
+    }
+}
+
+/// Decode a hexadecimal string, ignoring whitespace.
+///
+/// Used by [`cbor!`](crate::cbor) and panics on invalid input.
+#[doc(hidden)]
+pub fn __hex(s: &str) -> Vec<u8> {
+    match crate::from_hex(s) {
+        Ok(bytes) => bytes,
+        Err(e)    => panic!("{}", e)
+    }
+}
+
+/// Construct a [`Value`] from a literal in a notation close to CBOR's
+/// diagnostic notation.
+///
+/// - `null` and `undefined` are the respective simple values, other simple
+///   values are written as `simple(n)`.
+/// - Arrays are written as `[a, b, c]` and maps as `{ k1 => v1, k2 => v2 }`.
+/// - A tagged item is written as the tag number followed by the item in
+///   parentheses, e.g. `32("https://example.com")`.
+/// - Byte strings are written as Rust byte string literals, e.g. `b"abc"`,
+///   or in hexadecimal as `h("DEADBEEF")`.
+/// - Any other expression, including literals and variables, is converted
+///   with [`From`], which allows interpolating values.
+///
+/// The encoded bytes can be obtained with [`to_vec`](crate::to_vec).
+///
+/// *Requires feature* `"alloc"`.
+///
+/// ```
+/// use minicbor::cbor;
+///
+/// let name = "minicbor";
+/// let v = cbor!({ 1 => "a", 2 => [true, null, h("DEADBEEF")], "name" => name, 3 => 32("https://example.com") });
+///
+/// let mut e = minicbor::Encoder::new(Vec::new());
+/// e.map(4)?
+///     .u8(1)?.str("a")?
+///     .u8(2)?.array(3)?.bool(true)?.null()?.bytes(&[0xde, 0xad, 0xbe, 0xef])?
+///     .str("name")?.str(name)?
+///     .u8(3)?.tag(minicbor::data::Tag::Uri)?.str("https://example.com")?;
+///
+/// assert_eq!(e.into_inner(), minicbor::to_vec(&v)?);
+///
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[macro_export]
+macro_rules! cbor {
+    // Arrays: accumulate the elements in brackets.
+
+    (@array [$($elems:expr,)*]) => {
+        $crate::value::Value::array([$($elems,)*])
+    };
+    (@array [$($elems:expr),*]) => {
+        $crate::value::Value::array([$($elems),*])
+    };
+    (@array [$($elems:expr,)*] null $($rest:tt)*) => {
+        $crate::cbor!(@array [$($elems,)* $crate::cbor!(null)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] undefined $($rest:tt)*) => {
+        $crate::cbor!(@array [$($elems,)* $crate::cbor!(undefined)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] simple($n:expr) $($rest:tt)*) => {
+        $crate::cbor!(@array [$($elems,)* $crate::cbor!(simple($n))] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] h($s:literal) $($rest:tt)*) => {
+        $crate::cbor!(@array [$($elems,)* $crate::cbor!(h($s))] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] [$($a:tt)*] $($rest:tt)*) => {
+        $crate::cbor!(@array [$($elems,)* $crate::cbor!([$($a)*])] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] {$($m:tt)*} $($rest:tt)*) => {
+        $crate::cbor!(@array [$($elems,)* $crate::cbor!({$($m)*})] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] $t:literal ($($x:tt)+) $($rest:tt)*) => {
+        $crate::cbor!(@array [$($elems,)* $crate::cbor!($t($($x)+))] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::cbor!(@array [$($elems,)* $crate::value::Value::from($next),] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] $last:expr) => {
+        $crate::cbor!(@array [$($elems,)* $crate::value::Value::from($last)])
+    };
+    (@array [$($elems:expr),*] , $($rest:tt)*) => {
+        $crate::cbor!(@array [$($elems,)*] $($rest)*)
+    };
+
+    // Maps: accumulate the entries in brackets and the tokens of the
+    // current key in parentheses until `=>` is found.
+
+    (@map [$($entries:expr),*] ()) => {
+        $crate::value::Value::map([$($entries),*])
+    };
+    (@map [$($entries:expr,)*] ()) => {
+        $crate::value::Value::map([$($entries,)*])
+    };
+    (@map [$($entries:expr),*] () , $($rest:tt)*) => {
+        $crate::cbor!(@map [$($entries,)*] () $($rest)*)
+    };
+    (@map [$($entries:expr,)*] ($($k:tt)+) => null $($rest:tt)*) => {
+        $crate::cbor!(@map [$($entries,)* ($crate::cbor!($($k)+), $crate::cbor!(null))] () $($rest)*)
+    };
+    (@map [$($entries:expr,)*] ($($k:tt)+) => undefined $($rest:tt)*) => {
+        $crate::cbor!(@map [$($entries,)* ($crate::cbor!($($k)+), $crate::cbor!(undefined))] () $($rest)*)
+    };
+    (@map [$($entries:expr,)*] ($($k:tt)+) => simple($n:expr) $($rest:tt)*) => {
+        $crate::cbor!(@map [$($entries,)* ($crate::cbor!($($k)+), $crate::cbor!(simple($n)))] () $($rest)*)
+    };
+    (@map [$($entries:expr,)*] ($($k:tt)+) => h($s:literal) $($rest:tt)*) => {
+        $crate::cbor!(@map [$($entries,)* ($crate::cbor!($($k)+), $crate::cbor!(h($s)))] () $($rest)*)
+    };
+    (@map [$($entries:expr,)*] ($($k:tt)+) => [$($a:tt)*] $($rest:tt)*) => {
+        $crate::cbor!(@map [$($entries,)* ($crate::cbor!($($k)+), $crate::cbor!([$($a)*]))] () $($rest)*)
+    };
+    (@map [$($entries:expr,)*] ($($k:tt)+) => {$($m:tt)*} $($rest:tt)*) => {
+        $crate::cbor!(@map [$($entries,)* ($crate::cbor!($($k)+), $crate::cbor!({$($m)*}))] () $($rest)*)
+    };
+    (@map [$($entries:expr,)*] ($($k:tt)+) => $t:literal ($($x:tt)+) $($rest:tt)*) => {
+        $crate::cbor!(@map [$($entries,)* ($crate::cbor!($($k)+), $crate::cbor!($t($($x)+)))] () $($rest)*)
+    };
+    (@map [$($entries:expr,)*] ($($k:tt)+) => $v:expr, $($rest:tt)*) => {
+        $crate::cbor!(@map [$($entries,)* ($crate::cbor!($($k)+), $crate::value::Value::from($v)),] () $($rest)*)
+    };
+    (@map [$($entries:expr,)*] ($($k:tt)+) => $v:expr) => {
+        $crate::cbor!(@map [$($entries,)* ($crate::cbor!($($k)+), $crate::value::Value::from($v))] ())
+    };
+    (@map [$($entries:expr,)*] ($($k:tt)*) $t:tt $($rest:tt)*) => {
+        $crate::cbor!(@map [$($entries,)*] ($($k)* $t) $($rest)*)
+    };
+
+    // Single items.
+
+    (null) => {
+        $crate::value::Value::null()
+    };
+    (undefined) => {
+        $crate::value::Value::undefined()
+    };
+    (simple($n:expr)) => {
+        $crate::value::Value::simple($n)
+    };
+    (h($s:literal)) => {
+        $crate::value::Value::bytes(&$crate::value::__hex($s))
+    };
+    ([]) => {
+        $crate::value::Value::array(::core::iter::empty())
+    };
+    ([$($a:tt)+]) => {
+        $crate::cbor!(@array [] $($a)+)
+    };
+    ({}) => {
+        $crate::value::Value::map(::core::iter::empty())
+    };
+    ({$($m:tt)+}) => {
+        $crate::cbor!(@map [] () $($m)+)
+    };
+    ($t:literal ($($x:tt)+)) => {
+        $crate::value::Value::tag($t, $crate::cbor!($($x)+))
+    };
+    ($x:expr) => {
+        $crate::value::Value::from($x)
+    };
+}
@@ -0,0 +1,285 @@
+//! Push-based traversal of encoded CBOR items.
+//!
+//! [`crate::walk`] decodes a CBOR sequence and invokes the callbacks of a
+//! [`Visitor`] for every item it encounters. Contrary to the pull-based
+//! [`Tokenizer`](crate::decode::Tokenizer) the walk keeps track of the
+//! nesting structure, i.e. every callback receives the depth of its item
+//! and arrays, maps, tags and indefinite-length strings can be skipped as a
+//! whole by returning [`Control::Skip`].
+//!
+//! Items of the top-level sequence have depth 0. Elements of arrays, keys
+//! and values of maps, the items of tags and the chunks of indefinite-length
+//! strings have the depth of their enclosing item plus 1.
+
+// Walking must not panic on any input, just like decoding (cf. `crate::decode`).
+#![cfg_attr(feature = "__check-panic-free", deny(
+    clippy::arithmetic_side_effects,
+    clippy::cast_possible_truncation,
+    clippy::expect_used,
+    clippy::indexing_slicing,
+    clippy::panic,
+    clippy::todo,
+    clippy::unimplemented,
+    clippy::unreachable,
+    clippy::unwrap_used
+))]
+
+use crate::data::{Tag, Type};
+use crate::decode::{Decoder, Error};
+use crate::structural::{float, has_next};
+
+/// How to continue after a [`Visitor`] callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    /// Continue with the next item.
+    Continue,
+    /// Skip over the contents of the current item.
+    ///
+    /// Only applicable to the `begin_*` callbacks and [`Visitor::on_tag`].
+    /// The items of the array, map or string or the tagged item are not
+    /// visited and neither is the corresponding `end_*` callback invoked.
+    /// For all other callbacks this is the same as [`Control::Continue`].
+    Skip,
+    /// Stop walking.
+    Stop
+}
+
+/// Callbacks invoked by [`crate::walk`].
+///
+/// Every callback receives the depth of its item (cf. [module
+/// documentation](self)). All callbacks have default implementations
+/// which return [`Control::Continue`], so only callbacks of interest need
+/// to be implemented.
+pub trait Visitor<'b> {
+    /// A boolean value.
+    fn on_bool(&mut self, _depth: usize, _val: bool) -> Control {
+        Control::Continue
+    }
+
+    /// A null value.
+    fn on_null(&mut self, _depth: usize) -> Control {
+        Control::Continue
+    }
+
+    /// An undefined value.
+    fn on_undefined(&mut self, _depth: usize) -> Control {
+        Control::Continue
+    }
+
+    /// A simple value other than booleans, null and undefined.
+    fn on_simple(&mut self, _depth: usize, _val: u8) -> Control {
+        Control::Continue
+    }
+
+    /// An unsigned or negative integer.
+    fn on_int(&mut self, _depth: usize, _val: i128) -> Control {
+        Control::Continue
+    }
+
+    /// A floating-point value of any precision.
+    fn on_float(&mut self, _depth: usize, _val: f64) -> Control {
+        Control::Continue
+    }
+
+    /// A definite-length byte string or a chunk of an indefinite-length one.
+    fn on_bytes(&mut self, _depth: usize, _val: &'b [u8]) -> Control {
+        Control::Continue
+    }
+
+    /// A definite-length text string or a chunk of an indefinite-length one.
+    fn on_str(&mut self, _depth: usize, _val: &'b str) -> Control {
+        Control::Continue
+    }
+
+    /// The start of an indefinite-length byte string.
+    ///
+    /// Its chunks are passed to [`Visitor::on_bytes`].
+    fn begin_bytes(&mut self, _depth: usize) -> Control {
+        Control::Continue
+    }
+
+    /// The end of an indefinite-length byte string.
+    fn end_bytes(&mut self, _depth: usize) -> Control {
+        Control::Continue
+    }
+
+    /// The start of an indefinite-length text string.
+    ///
+    /// Its chunks are passed to [`Visitor::on_str`].
+    fn begin_str(&mut self, _depth: usize) -> Control {
+        Control::Continue
+    }
+
+    /// The end of an indefinite-length text string.
+    fn end_str(&mut self, _depth: usize) -> Control {
+        Control::Continue
+    }
+
+    /// The start of an array of the given length (`None` if indefinite).
+    fn begin_array(&mut self, _depth: usize, _len: Option<u64>) -> Control {
+        Control::Continue
+    }
+
+    /// The end of an array.
+    fn end_array(&mut self, _depth: usize) -> Control {
+        Control::Continue
+    }
+
+    /// The start of a map with the given number of entries (`None` if
+    /// indefinite).
+    ///
+    /// Keys and values are visited alternately.
+    fn begin_map(&mut self, _depth: usize, _len: Option<u64>) -> Control {
+        Control::Continue
+    }
+
+    /// The end of a map.
+    fn end_map(&mut self, _depth: usize) -> Control {
+        Control::Continue
+    }
+
+    /// A tag, followed by the tagged item.
+    fn on_tag(&mut self, _depth: usize, _tag: Tag) -> Control {
+        Control::Continue
+    }
+}
+
+/// A visitor which ignores everything, used to skip over items.
+struct Ignore;
+
+impl Visitor<'_> for Ignore {}
+
+/// Visit all items of a CBOR sequence.
+pub(crate) fn walk<'b, V: Visitor<'b> + ?Sized>(bytes: &'b [u8], v: &mut V) -> Result<(), Error> {
+    let mut d = Decoder::new(bytes);
+    while d.position() < bytes.len() {
+        if !item(&mut d, v, 0)? {
+            break
+        }
+    }
+    Ok(())
+}
+
+/// Check that the decoder is positioned at a well-formed CBOR item and
+/// move past it.
+///
+/// Contrary to [`Decoder::skip`] this is always available and supports
+/// any nesting of definite- and indefinite-length items.
+pub(crate) fn check_item(d: &mut Decoder<'_>) -> Result<(), Error> {
+    item(d, &mut Ignore, 0).map(|_| ())
+}
+
+/// Visit the next item and everything it contains.
+///
+/// Returns `false` if the visitor stopped the walk.
+fn item<'b, V: Visitor<'b> + ?Sized>(d: &mut Decoder<'b>, v: &mut V, depth: usize) -> Result<bool, Error> {
+    if depth >= d.max_depth() {
+        return Err(Error::Message("maximum nesting depth exceeded"))
+    }
+    let inner = depth.saturating_add(1);
+    let c = match d.datatype()? {
+        Type::Array | Type::ArrayIndef => {
+            let n = d.array()?;
+            match v.begin_array(depth, n) {
+                Control::Stop     => return Ok(false),
+                Control::Skip     => return items(d, &mut Ignore, n, inner),
+                Control::Continue => if items(d, v, n, inner)? { v.end_array(depth) } else { Control::Stop }
+            }
+        }
+        Type::Map | Type::MapIndef => {
+            let n = d.map()?;
+            let c = v.begin_map(depth, n);
+            let n = n.map(|n| n.saturating_mul(2));
+            match c {
+                Control::Stop     => return Ok(false),
+                Control::Skip     => return items(d, &mut Ignore, n, inner),
+                Control::Continue => if items(d, v, n, inner)? { v.end_map(depth) } else { Control::Stop }
+            }
+        }
+        Type::Tag => {
+            let tag = d.tag()?;
+            match v.on_tag(depth, tag) {
+                Control::Stop     => return Ok(false),
+                Control::Skip     => return item(d, &mut Ignore, inner),
+                Control::Continue => return item(d, v, inner)
+            }
+        }
+        Type::BytesIndef | Type::StringIndef => chunks(d, v, depth)?,
+        _ => scalar(d, v, depth)?
+    };
+    Ok(c != Control::Stop)
+}
+
+/// Visit an item which does not contain other items.
+fn scalar<'b, V: Visitor<'b> + ?Sized>(d: &mut Decoder<'b>, v: &mut V, depth: usize) -> Result<Control, Error> {
+    let t = d.datatype()?;
+    match t {
+        Type::Bool      => Ok(v.on_bool(depth, d.bool()?)),
+        Type::Null      => { d.read_slice(1)?; Ok(v.on_null(depth)) }
+        Type::Undefined => { d.read_slice(1)?; Ok(v.on_undefined(depth)) }
+        Type::Simple    => Ok(v.on_simple(depth, d.simple()?)),
+        Type::U8 | Type::U16 | Type::U32 | Type::U64 => Ok(v.on_int(depth, i128::from(d.u64()?))),
+        Type::I8 | Type::I16 | Type::I32 | Type::I64 => Ok(v.on_int(depth, !i128::from(d.negative()?))),
+        Type::F16 | Type::F32 | Type::F64 => Ok(v.on_float(depth, float(d)?)),
+        Type::Bytes  => Ok(v.on_bytes(depth, d.bytes()?)),
+        Type::String => Ok(v.on_str(depth, d.str()?)),
+        Type::Break  => Err(Error::TypeMismatch(t, "unexpected break")),
+        _            => Err(Error::TypeMismatch(t, "unknown cbor type"))
+    }
+}
+
+/// Visit an indefinite-length byte or text string.
+fn chunks<'b, V: Visitor<'b> + ?Sized>(d: &mut Decoder<'b>, v: &mut V, depth: usize) -> Result<Control, Error> {
+    let inner = depth.saturating_add(1);
+    if d.datatype()? == Type::BytesIndef {
+        let c = v.begin_bytes(depth);
+        if c == Control::Stop {
+            return Ok(c)
+        }
+        let mut chunks = d.bytes_iter()?;
+        if c == Control::Skip {
+            for b in chunks {
+                b?;
+            }
+            return Ok(c)
+        }
+        for b in &mut chunks {
+            if v.on_bytes(inner, b?) == Control::Stop {
+                return Ok(Control::Stop)
+            }
+        }
+        Ok(v.end_bytes(depth))
+    } else {
+        let c = v.begin_str(depth);
+        if c == Control::Stop {
+            return Ok(c)
+        }
+        let mut chunks = d.str_iter()?;
+        if c == Control::Skip {
+            for s in chunks {
+                s?;
+            }
+            return Ok(c)
+        }
+        for s in &mut chunks {
+            if v.on_str(inner, s?) == Control::Stop {
+                return Ok(Control::Stop)
+            }
+        }
+        Ok(v.end_str(depth))
+    }
+}
+
+/// Visit the items of an array or map.
+///
+/// Returns `false` if the visitor stopped the walk.
+fn items<'b, V: Visitor<'b> + ?Sized>(d: &mut Decoder<'b>, v: &mut V, len: Option<u64>, depth: usize) -> Result<bool, Error> {
+    let mut i = 0u64;
+    while has_next(d, len, i)? {
+        if !item(d, v, depth)? {
+            return Ok(false)
+        }
+        i = i.saturating_add(1)
+    }
+    Ok(true)
+}
@@ -0,0 +1,328 @@
+//! Conversion between CBOR and JavaScript values of [`wasm-bindgen`][1].
+//!
+//! [`to_js`] converts a CBOR item to a [`JsValue`] as follows:
+//!
+//! - `true`, `false`, `null` and `undefined` are kept, other simple values
+//!   become numbers.
+//! - Integers become numbers if they are *safe integers*, i.e. if their
+//!   absolute value is at most 2<sup>53</sup> - 1, otherwise `BigInt`s.
+//!   Bignums (tags 2 and 3) become `BigInt`s as well.
+//! - Floats become numbers.
+//! - Byte strings become `Uint8Array`s, text strings become strings.
+//! - Arrays become `Array`s. Maps with text string keys only become plain
+//!   objects, all other maps become `Map`s.
+//! - Other tags are dropped, i.e. only their content is converted.
+//!
+//! [`from_js`] maps JavaScript values back to CBOR. Numbers which are safe
+//! integers are encoded as CBOR integers, all others as `f64` values.
+//! `BigInt`s become integers or bignums. `Map`s and other objects become
+//! maps, where the keys of objects are their own enumerable properties.
+//!
+//! Arrays, maps and tags may be nested up to [`MAX_DEPTH`] levels deep.
+//!
+//! *Requires feature* `"wasm"`.
+//!
+//! ```no_run
+//! use minicbor::wasm::{from_js, to_js};
+//!
+//! let cbor = minicbor::to_vec((u64::MAX, "x", [1.5]))?;
+//! let js = to_js(&cbor)?; // [18446744073709551615n, "x", [1.5]]
+//! assert_eq!(cbor, from_js(&js)?);
+//!
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [1]: https://docs.rs/wasm-bindgen
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt::{self, Write as _};
+use crate::data::{Tag, Type};
+use crate::decode::{self, Decoder};
+use crate::encode::{self, Encoder};
+use crate::structural::has_next;
+use js_sys::{Array, BigInt, Map, Object, Reflect, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue};
+
+/// The max. nesting depth of arrays, maps and tags.
+pub const MAX_DEPTH: usize = 256;
+
+/// The largest integer which a JavaScript number represents exactly.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Conversion errors.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// Decoding the CBOR input failed.
+    Decode(decode::Error),
+    /// Encoding the CBOR output failed.
+    Encode(&'static str),
+    /// A value has no CBOR or JavaScript representation.
+    Unsupported(&'static str)
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Decode(e)      => write!(f, "decode error: {}", e),
+            Error::Encode(m)      => write!(f, "encode error: {}", m),
+            Error::Unsupported(m) => write!(f, "unsupported: {}", m)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Decode(e) => Some(e),
+            _                => None
+        }
+    }
+}
+
+impl From<decode::Error> for Error {
+    fn from(e: decode::Error) -> Self {
+        Error::Decode(e)
+    }
+}
+
+impl<E> From<encode::Error<E>> for Error {
+    fn from(e: encode::Error<E>) -> Self {
+        match e {
+            encode::Error::Message(m) => Error::Encode(m),
+            _                         => Error::Encode("failed to write cbor")
+        }
+    }
+}
+
+impl From<Error> for JsValue {
+    fn from(e: Error) -> Self {
+        let mut s = String::new();
+        let _ = write!(s, "{}", e);
+        js_sys::Error::new(&s).into()
+    }
+}
+
+/// Convert a single CBOR item to a JavaScript value.
+///
+/// The whole input must be consumed, otherwise a
+/// [`decode::Error::TrailingBytes`] error is returned.
+pub fn to_js(cbor: &[u8]) -> Result<JsValue, Error> {
+    let mut d = Decoder::new(cbor);
+    let v = cbor_to_js(&mut d, 0)?;
+    if d.position() < cbor.len() {
+        return Err(decode::Error::TrailingBytes(d.position()).into())
+    }
+    Ok(v)
+}
+
+/// Convert a JavaScript value to CBOR.
+pub fn from_js(v: &JsValue) -> Result<Vec<u8>, Error> {
+    let mut e = Encoder::new(Vec::new());
+    js_to_cbor(v, &mut e, 0)?;
+    Ok(e.into_inner())
+}
+
+fn cbor_to_js(d: &mut Decoder, depth: usize) -> Result<JsValue, Error> {
+    if depth >= MAX_DEPTH {
+        return Err(decode::Error::Message("maximum nesting depth exceeded").into())
+    }
+    match d.datatype()? {
+        Type::Bool      => Ok(JsValue::from_bool(d.bool()?)),
+        Type::Null      => { d.skip()?; Ok(JsValue::NULL) }
+        Type::Undefined => { d.skip()?; Ok(JsValue::UNDEFINED) }
+        Type::Simple    => Ok(JsValue::from(d.simple()?)),
+        Type::U8 | Type::U16 | Type::U32 | Type::U64 => {
+            let n = d.u64()?;
+            if n <= MAX_SAFE_INTEGER {
+                Ok(JsValue::from_f64(n as f64))
+            } else {
+                Ok(BigInt::from(n).into())
+            }
+        }
+        Type::I8 | Type::I16 | Type::I32 | Type::I64 => {
+            let n = negative(d)?;
+            if -n <= i128::from(MAX_SAFE_INTEGER) {
+                Ok(JsValue::from_f64(n as f64))
+            } else {
+                Ok(BigInt::from(n).into())
+            }
+        }
+        Type::F16 => Ok(JsValue::from(d.f16()?)),
+        Type::F32 => Ok(JsValue::from(d.f32()?)),
+        Type::F64 => Ok(JsValue::from(d.f64()?)),
+        Type::Bytes | Type::BytesIndef => Ok(Uint8Array::from(bytes(d)?.as_slice()).into()),
+        Type::String | Type::StringIndef => {
+            let mut s = String::new();
+            d.push_str(&mut s)?;
+            Ok(JsValue::from(s))
+        }
+        Type::Array | Type::ArrayIndef => {
+            let len = d.array()?;
+            let a = Array::new();
+            let mut i = 0;
+            while has_next(d, len, i)? {
+                a.push(&cbor_to_js(d, depth + 1)?);
+                i += 1
+            }
+            Ok(a.into())
+        }
+        Type::Map | Type::MapIndef => {
+            let len = d.map()?;
+            let mut entries = Vec::new();
+            let mut text_keys = true;
+            let mut i = 0;
+            while has_next(d, len, i)? {
+                let k = cbor_to_js(d, depth + 1)?;
+                text_keys &= k.is_string();
+                entries.push((k, cbor_to_js(d, depth + 1)?));
+                i += 1
+            }
+            if text_keys {
+                let o = Object::new();
+                for (k, v) in entries {
+                    Reflect::set(&o, &k, &v).map_err(|_| Error::Unsupported("object property"))?;
+                }
+                Ok(o.into())
+            } else {
+                let m = Map::new();
+                for (k, v) in entries {
+                    m.set(&k, &v);
+                }
+                Ok(m.into())
+            }
+        }
+        Type::Tag => match d.tag()? {
+            t @ Tag::PosBignum | t @ Tag::NegBignum => {
+                let mut s = String::from("0x0");
+                for b in bytes(d)? {
+                    let _ = write!(s, "{:02x}", b);
+                }
+                let n = s.parse::<BigInt>().map_err(|_| Error::Unsupported("bignum"))?;
+                if t == Tag::PosBignum {
+                    Ok(n.into())
+                } else {
+                    Ok((&BigInt::from(-1) - &n).into())
+                }
+            }
+            _ => cbor_to_js(d, depth + 1)
+        }
+        t @ Type::Break | t @ Type::Unknown(_) => {
+            Err(decode::Error::TypeMismatch(t, "unexpected type").into())
+        }
+    }
+}
+
+fn js_to_cbor(v: &JsValue, e: &mut Encoder<Vec<u8>>, depth: usize) -> Result<(), Error> {
+    if depth >= MAX_DEPTH {
+        return Err(Error::Encode("maximum nesting depth exceeded"))
+    }
+    if v.is_null() {
+        e.null()?;
+    } else if v.is_undefined() {
+        e.undefined()?;
+    } else if let Some(b) = v.as_bool() {
+        e.bool(b)?;
+    } else if let Some(x) = v.as_f64() {
+        if x.fract() == 0.0 && x.abs() <= MAX_SAFE_INTEGER as f64 {
+            e.i64(x as i64)?;
+        } else {
+            e.f64(x)?;
+        }
+    } else if v.is_bigint() {
+        let n = v.clone().unchecked_into::<BigInt>();
+        if let Ok(n) = i64::try_from(n.clone()) {
+            e.i64(n)?;
+        } else if let Ok(n) = u64::try_from(n.clone()) {
+            e.u64(n)?;
+        } else {
+            bignum(n, e)?
+        }
+    } else if let Some(s) = v.as_string() {
+        e.str(&s)?;
+    } else if let Some(b) = v.dyn_ref::<Uint8Array>() {
+        e.bytes(&b.to_vec())?;
+    } else if Array::is_array(v) {
+        let a = v.unchecked_ref::<Array>();
+        e.array(u64::from(a.length()))?;
+        for i in 0 .. a.length() {
+            js_to_cbor(&a.get(i), e, depth + 1)?
+        }
+    } else if let Some(m) = v.dyn_ref::<Map>() {
+        e.map(u64::from(m.size()))?;
+        let mut r = Ok(());
+        m.for_each(&mut |v, k| {
+            if r.is_ok() {
+                r = js_to_cbor(&k, e, depth + 1).and_then(|()| js_to_cbor(&v, e, depth + 1))
+            }
+        });
+        r?
+    } else if let Some(o) = v.dyn_ref::<Object>() {
+        let entries = Object::entries(o);
+        e.map(u64::from(entries.length()))?;
+        for i in 0 .. entries.length() {
+            let kv = entries.get(i).unchecked_into::<Array>();
+            js_to_cbor(&kv.get(0), e, depth + 1)?;
+            js_to_cbor(&kv.get(1), e, depth + 1)?
+        }
+    } else {
+        return Err(Error::Unsupported("javascript value has no cbor representation"))
+    }
+    Ok(())
+}
+
+/// Encode a `BigInt` outside of the range of major types 0 and 1 as bignum.
+fn bignum(n: BigInt, e: &mut Encoder<Vec<u8>>) -> Result<(), Error> {
+    let zero = BigInt::from(0);
+    let (tag, m) = if n < zero {
+        (Tag::NegBignum, &BigInt::from(-1) - &n)
+    } else {
+        (Tag::PosBignum, n)
+    };
+    let hex: String = m.to_string(16).map_err(|_| Error::Unsupported("bignum"))?.into();
+    let hex = hex.as_bytes();
+    let mut b = Vec::with_capacity(hex.len() / 2 + 1);
+    let odd = hex.len() % 2;
+    if odd == 1 {
+        b.push(nibble(hex[0]))
+    }
+    for x in hex[odd ..].chunks(2) {
+        b.push(nibble(x[0]) << 4 | nibble(x[1]))
+    }
+    e.tag(tag)?.bytes(&b)?;
+    Ok(())
+}
+
+fn nibble(c: u8) -> u8 {
+    match c {
+        b'0' ..= b'9' => c - b'0',
+        _             => c - b'a' + 10
+    }
+}
+
+/// Collect the content of a possibly indefinite-length byte string.
+fn bytes(d: &mut Decoder) -> Result<Vec<u8>, Error> {
+    let mut b = Vec::new();
+    for x in d.bytes_iter()? {
+        b.extend_from_slice(x?)
+    }
+    Ok(b)
+}
+
+/// Decode any negative integer of major type 1.
+fn negative(d: &mut Decoder) -> Result<i128, Error> {
+    let p = d.position();
+    match d.i64() {
+        Ok(n) => Ok(n.into()),
+        Err(decode::Error::Overflow(..)) => {
+            d.set_position(p + 1);
+            let mut a = [0; 8];
+            a.copy_from_slice(d.read_slice(8)?);
+            Ok(-1 - i128::from(u64::from_be_bytes(a)))
+        }
+        Err(e) => Err(e.into())
+    }
+}